@@ -812,20 +812,30 @@ pub fn apply_only(
     existing_branch: &gix::refs::FullNameRef,
 ) -> anyhow::Result<but_workspace::branch::apply::Outcome> {
     let mut guard = ctx.exclusive_worktree_access();
-    apply_only_with_perm(ctx, existing_branch, guard.write_permission())
+    apply_only_with_perm(
+        ctx,
+        existing_branch,
+        OnWorkspaceMergeConflict::default(),
+        None,
+        guard.write_permission(),
+    )
 }
 
 /// Applies `existing_branch` to the current workspace under caller-held
 /// exclusive repository access.
 ///
-/// It applies the branch with the default workspace-apply options, updates the
-/// in-memory workspace stored in `ctx` to the returned workspace state when the
-/// state was persisted, and returns the apply outcome. This variant does not
+/// It applies the branch with the default workspace-apply options, except for
+/// `on_workspace_conflict` which decides if conflicting stacks abort the operation,
+/// and `merge_progress` which is called with each step of the workspace merge, if set.
+/// It updates the in-memory workspace stored in `ctx` to the returned workspace state
+/// when the state was persisted, and returns the apply outcome. This variant does not
 /// create an oplog entry. For lower-level implementation details, see
 /// [`but_workspace::branch::apply()`].
 pub fn apply_only_with_perm(
     ctx: &mut but_ctx::Context,
     existing_branch: &gix::refs::FullNameRef,
+    on_workspace_conflict: OnWorkspaceMergeConflict,
+    merge_progress: Option<fn(but_workspace::commit::merge::MergeProgress<'_>)>,
     perm: &mut RepoExclusive,
 ) -> anyhow::Result<but_workspace::branch::apply::Outcome> {
     let mut meta = ctx.meta()?;
@@ -839,10 +849,11 @@ pub fn apply_only_with_perm(
         //       Showing them off here while leaving defaults.
         but_workspace::branch::apply::Options {
             workspace_merge: WorkspaceMerge::default(),
-            on_workspace_conflict: OnWorkspaceMergeConflict::default(),
+            on_workspace_conflict,
             workspace_reference_naming: WorkspaceReferenceNaming::default(),
             order: None,
             new_stack_id: None,
+            merge_progress,
         },
     )?;
 
@@ -864,7 +875,13 @@ pub fn apply(
     existing_branch: &gix::refs::FullNameRef,
 ) -> anyhow::Result<but_workspace::branch::apply::Outcome> {
     let mut guard = ctx.exclusive_worktree_access();
-    apply_with_perm(ctx, existing_branch, guard.write_permission())
+    apply_with_perm(
+        ctx,
+        existing_branch,
+        OnWorkspaceMergeConflict::default(),
+        None,
+        guard.write_permission(),
+    )
 }
 
 /// Apply `existing_branch` to the workspace under caller-held exclusive
@@ -877,6 +894,8 @@ pub fn apply(
 pub fn apply_with_perm(
     ctx: &mut but_ctx::Context,
     existing_branch: &gix::refs::FullNameRef,
+    on_workspace_conflict: OnWorkspaceMergeConflict,
+    merge_progress: Option<fn(but_workspace::commit::merge::MergeProgress<'_>)>,
     perm: &mut RepoExclusive,
 ) -> anyhow::Result<but_workspace::branch::apply::Outcome> {
     // NOTE: since this is optional by nature, the same would be true if snapshotting/undo would be disabled via `ctx` app settings, for instance.
@@ -888,7 +907,13 @@ pub fn apply_with_perm(
         DryRun::No,
     );
 
-    let res = apply_only_with_perm(ctx, existing_branch, perm);
+    let res = apply_only_with_perm(
+        ctx,
+        existing_branch,
+        on_workspace_conflict,
+        merge_progress,
+        perm,
+    );
     if let Some(snapshot) = maybe_oplog_entry
         && res
             .as_ref()
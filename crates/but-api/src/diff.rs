@@ -1,10 +1,13 @@
+use bstr::{BString, ByteSlice};
 use but_api_macros::but_api;
 use but_core::{
     sync::{RepoExclusive, RepoShared},
     ui::TreeChange,
 };
 use but_ctx::Context;
-use but_hunk_assignment::{HunkAssignmentRequest, WorktreeChanges};
+use but_hunk_assignment::{
+    AssignmentRule, HunkAssignmentRequest, HunkAssignmentTarget, WorktreeChanges,
+};
 use but_hunk_dependency::ui::hunk_dependencies_for_workspace_changes_by_worktree_dir;
 use but_oplog::legacy::{OperationKind, SnapshotDetails};
 use gix::prelude::ObjectIdExt;
@@ -17,6 +20,7 @@ use but_core::diff::CommitDetails;
 /// JSON types
 // TODO: add schemars
 pub mod json {
+    use bstr::ByteSlice;
     use but_core::diff::LineStats;
     use serde::Serialize;
 
@@ -57,6 +61,107 @@ pub mod json {
             }
         }
     }
+
+    /// The JSON sibling of [super::BlobContent].
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct BlobContent {
+        /// The content of the blob as text, or `None` if it is binary or wasn't loaded.
+        pub text: Option<String>,
+        /// The size of the blob in bytes.
+        pub size: u64,
+        /// Whether the blob is binary, or `None` if it wasn't loaded.
+        pub is_binary: Option<bool>,
+        /// Whether the file is marked as executable.
+        pub is_executable: bool,
+    }
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(BlobContent);
+
+    impl From<super::BlobContent> for BlobContent {
+        fn from(value: super::BlobContent) -> Self {
+            let super::BlobContent {
+                data,
+                size,
+                is_binary,
+                is_executable,
+            } = value;
+            BlobContent {
+                text: data
+                    .filter(|_| is_binary == Some(false))
+                    .map(|data| data.to_str_lossy().into_owned()),
+                size,
+                is_binary,
+                is_executable,
+            }
+        }
+    }
+}
+
+/// The content of a file as stored in a commit, as returned by [`blob_at()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobContent {
+    /// The content of the blob, or `None` if it was larger than the size limit.
+    pub data: Option<BString>,
+    /// The size of the blob in bytes.
+    pub size: u64,
+    /// Whether the blob is binary, judged by the presence of null bytes like Git does,
+    /// or `None` if its `data` wasn't loaded.
+    pub is_binary: Option<bool>,
+    /// Whether the file is marked as executable.
+    pub is_executable: bool,
+}
+
+/// Returns the content of the file at `path` in the tree of `commit_id`, or `None` if there is
+/// no file at `path` in that commit.
+///
+/// Blobs larger than `max_size` bytes are not loaded, and only their metadata is returned.
+/// Symbolic links are returned as blobs with their target path as content.
+/// If `commit_id` isn't a commit in the repository, a
+/// [`CommitNotFound`](crate::error::Error::CommitNotFound) error is returned.
+#[but_api(json::BlobContent)]
+#[instrument(err(Debug))]
+pub fn blob_at(
+    ctx: &Context,
+    commit_id: gix::ObjectId,
+    path: BString,
+    max_size: Option<u64>,
+) -> anyhow::Result<Option<BlobContent>> {
+    let repo = ctx.repo.get()?;
+    let commit = repo
+        .try_find_object(commit_id)?
+        .and_then(|object| object.try_into_commit().ok())
+        .ok_or_else(|| crate::error::Error::CommitNotFound(commit_id).into_anyhow())?;
+    let Some(entry) = commit
+        .tree()?
+        .lookup_entry_by_path(gix::path::from_bstr(path.as_bstr()))?
+    else {
+        return Ok(None);
+    };
+    let mode = entry.mode();
+    if !mode.is_blob_or_symlink() {
+        return Ok(None);
+    }
+
+    let blob_id = entry.object_id();
+    let size = repo.find_header(blob_id)?.size();
+    let data = if max_size.is_some_and(|max_size| size > max_size) {
+        None
+    } else {
+        Some(BString::from(repo.find_blob(blob_id)?.take_data()))
+    };
+    Ok(Some(BlobContent {
+        is_binary: data.as_ref().map(|data| is_binary(data)),
+        data,
+        size,
+        is_executable: mode.is_executable(),
+    }))
+}
+
+/// Like Git, consider data binary if there is a null byte among its first 8000 bytes.
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8000)].contains(&0)
 }
 
 /// Computes the tree diff for `commit_id` against its first parent and
@@ -210,6 +315,19 @@ pub fn assign_hunk_only_with_perm(
 ) -> anyhow::Result<()> {
     let context_lines = ctx.settings.context_lines;
     let (repo, ws, mut db) = ctx.workspace_and_db_mut_with_perm(perm)?;
+    if let Some(stack_id) = assignments
+        .iter()
+        .find_map(|assignment| match assignment.target {
+            Some(HunkAssignmentTarget::Stack { stack_id })
+                if ws.find_stack_by_id(stack_id).is_none() =>
+            {
+                Some(stack_id)
+            }
+            _ => None,
+        })
+    {
+        return Err(crate::error::Error::StackNotFound(stack_id).into_anyhow());
+    }
     but_hunk_assignment::assign(
         db.hunk_assignments_mut()?,
         &repo,
@@ -265,3 +383,81 @@ pub fn assign_hunk_with_perm(
     }
     res
 }
+
+/// Returns the rules that assign new hunks to a branch by their path, in the order they were added.
+///
+/// See [`but_hunk_assignment::rules()`] for details.
+pub fn assignment_rules(ctx: &Context) -> anyhow::Result<Vec<AssignmentRule>> {
+    let db = ctx.db.get_cache()?;
+    but_hunk_assignment::rules(&db.hunk_assignments())
+}
+
+/// Persists a rule that assigns new hunks whose path matches `path_glob` to `branch_ref`.
+///
+/// This acquires exclusive worktree access from `ctx`.
+///
+/// See [`add_assignment_rule_with_perm()`] for details.
+pub fn add_assignment_rule(
+    ctx: &mut Context,
+    path_glob: String,
+    branch_ref: gix::refs::FullName,
+) -> anyhow::Result<(AssignmentRule, usize)> {
+    let mut guard = ctx.exclusive_worktree_access();
+    add_assignment_rule_with_perm(ctx, path_glob, branch_ref, guard.write_permission())
+}
+
+/// Persists a rule that assigns new hunks whose path matches `path_glob` to `branch_ref` under
+/// caller-held exclusive repository access, and assigns the currently unassigned hunks that the
+/// new rule decides for right away.
+///
+/// Returns the new rule along with the amount of hunks it assigned. Assigning them records an
+/// oplog snapshot just like [`assign_hunk_with_perm()`].
+///
+/// For lower-level implementation details, see [`but_hunk_assignment::add_rule()`].
+pub fn add_assignment_rule_with_perm(
+    ctx: &Context,
+    path_glob: String,
+    branch_ref: gix::refs::FullName,
+    perm: &mut RepoExclusive,
+) -> anyhow::Result<(AssignmentRule, usize)> {
+    let (rule, rules) = {
+        let mut db = ctx.db.get_cache_mut()?;
+        let mut rules = but_hunk_assignment::rules(&db.hunk_assignments())?;
+        let rule =
+            but_hunk_assignment::add_rule(db.hunk_assignments_mut()?, path_glob, branch_ref)?;
+        rules.push(rule.clone());
+        (rule, rules)
+    };
+
+    let assignments = changes_in_worktree_with_perm(ctx, true, perm.read_permission())?.assignments;
+    let requests: Vec<_> = assignments
+        .into_iter()
+        .filter(|assignment| assignment.branch_ref_bytes.is_none())
+        .filter(|assignment| {
+            but_hunk_assignment::rule_for(&rules, assignment.path_bytes.as_bstr())
+                .is_some_and(|deciding| deciding.id == rule.id)
+        })
+        .map(|assignment| HunkAssignmentRequest {
+            hunk_header: assignment.hunk_header,
+            path_bytes: assignment.path_bytes,
+            target: Some(HunkAssignmentTarget::Branch {
+                branch_ref_bytes: rule.branch_ref.as_bstr().to_owned(),
+            }),
+        })
+        .collect();
+    let num_assigned = requests.len();
+    if num_assigned > 0 {
+        assign_hunk_with_perm(ctx, requests, perm)?;
+    }
+    Ok((rule, num_assigned))
+}
+
+/// Deletes the rules with the given `ids` that assign new hunks to a branch by their path.
+///
+/// Hunks that were assigned by them keep their assignment.
+///
+/// For lower-level implementation details, see [`but_hunk_assignment::remove_rules()`].
+pub fn remove_assignment_rules(ctx: &Context, ids: &[uuid::Uuid]) -> anyhow::Result<()> {
+    let mut db = ctx.db.get_cache_mut()?;
+    but_hunk_assignment::remove_rules(db.hunk_assignments_mut()?, ids)
+}
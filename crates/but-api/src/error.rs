@@ -0,0 +1,121 @@
+use bstr::{BString, ByteSlice};
+use but_core::ref_metadata::StackId;
+use but_error::Code;
+
+/// Failures of API commands that callers are expected to tell apart and handle specifically.
+///
+/// Commands still return [`anyhow::Result`], so these are converted with [`Error::into_anyhow()`]
+/// which keeps them available through [`anyhow::Error::downcast_ref()`], and additionally attaches
+/// the matching [`Code`] for frontends that only look at codes.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The project has no target branch configured.
+    #[error("there is no default target")]
+    NoDefaultTarget,
+    /// The stack with the given ID isn't part of the workspace.
+    #[error("stack with ID {0} not found")]
+    StackNotFound(StackId),
+    /// The branch with the given name does not exist.
+    #[error("Branch '{0}' not found")]
+    BranchNotFound(String),
+    /// The commit with the given ID does not exist in the repository.
+    #[error("Commit {0} not found")]
+    CommitNotFound(gix::ObjectId),
+    /// The operation would have overwritten uncommitted changes in the worktree.
+    #[error("Uncommitted changes would be overwritten: {}", list_paths(paths))]
+    WorktreeConflict {
+        /// The worktree-relative paths of the changes that would be overwritten.
+        paths: Vec<BString>,
+    },
+}
+
+impl Error {
+    /// Return the error code that frontends use to identify this kind of error.
+    pub fn code(&self) -> Code {
+        match self {
+            Error::NoDefaultTarget => Code::DefaultTargetNotFound,
+            Error::StackNotFound(_) | Error::BranchNotFound(_) => Code::BranchNotFound,
+            Error::CommitNotFound(_) => Code::Validation,
+            Error::WorktreeConflict { .. } => Code::PreconditionFailed,
+        }
+    }
+
+    /// Turn this instance into an [`anyhow::Error`] that carries our [code](Self::code()) as context,
+    /// while remaining downcastable to `Self`.
+    pub fn into_anyhow(self) -> anyhow::Error {
+        let code = self.code();
+        anyhow::Error::new(self).context(code)
+    }
+}
+
+/// Turn `err` into [`Error::NoDefaultTarget`] if lower layers marked it with [`Code::DefaultTargetNotFound`],
+/// or return it unchanged.
+pub(crate) fn lift_no_default_target(err: anyhow::Error) -> anyhow::Error {
+    use but_error::AnyhowContextExt;
+    if err
+        .custom_context()
+        .is_some_and(|ctx| ctx.code == Code::DefaultTargetNotFound)
+    {
+        Error::NoDefaultTarget.into_anyhow()
+    } else {
+        err
+    }
+}
+
+fn list_paths(paths: &[BString]) -> String {
+    paths
+        .iter()
+        .map(|p| p.to_str_lossy())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use but_error::{AnyhowContextExt, Code};
+
+    use super::Error;
+
+    #[test]
+    fn into_anyhow_keeps_code_and_remains_downcastable() {
+        let stack_id = but_core::ref_metadata::StackId::generate();
+        let err = Error::StackNotFound(stack_id).into_anyhow();
+        assert_eq!(
+            err.custom_context().map(|ctx| ctx.code),
+            Some(Code::BranchNotFound)
+        );
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::StackNotFound(id)) if *id == stack_id
+        ));
+
+        let err = Error::WorktreeConflict {
+            paths: vec!["a".into(), "dir/b".into()],
+        }
+        .into_anyhow();
+        assert_eq!(
+            err.custom_context().map(|ctx| ctx.code),
+            Some(Code::PreconditionFailed)
+        );
+        assert_eq!(
+            err.downcast_ref::<Error>()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("Uncommitted changes would be overwritten: a, dir/b")
+        );
+    }
+
+    #[test]
+    fn default_target_codes_are_lifted() {
+        let err = super::lift_no_default_target(
+            anyhow::anyhow!("missing").context(Code::DefaultTargetNotFound),
+        );
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NoDefaultTarget)
+        ));
+
+        let err = super::lift_no_default_target(anyhow::anyhow!("other"));
+        assert!(err.downcast_ref::<Error>().is_none());
+    }
+}
@@ -40,6 +40,20 @@ mod hex_hash {
         }
     }
 
+    impl FromStr for HexHash {
+        type Err = gix::hash::decode::Error;
+
+        fn from_str(hex: &str) -> Result<Self, Self::Err> {
+            gix::ObjectId::from_str(hex).map(HexHash)
+        }
+    }
+
+    impl std::fmt::Display for HexHash {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
     impl<'de> Deserialize<'de> for HexHash {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
@@ -61,6 +75,81 @@ mod hex_hash {
         }
     }
 
+    /// A hexadecimal hash that may be abbreviated, like `5c69907`, as users and tools tend to pass them.
+    ///
+    /// It has to be [resolved](Self::resolve()) against a repository to learn the object id it refers to.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct HexHashPrefix(gix::hash::Prefix);
+
+    impl HexHashPrefix {
+        /// The smallest amount of hex characters we accept as prefix.
+        pub const MIN_HEX_LEN: usize = 4;
+
+        /// Return the object id in `repo` that this prefix uniquely identifies, or fail if there is
+        /// no such object or if the prefix is ambiguous.
+        ///
+        /// Full hashes are returned as is, without checking for their existence.
+        pub fn resolve(&self, repo: &gix::Repository) -> anyhow::Result<gix::ObjectId> {
+            let id = self.0.as_oid();
+            if self.0.hex_len() == id.kind().len_in_hex() {
+                return Ok(id.to_owned());
+            }
+            match repo.objects.lookup_prefix(self.0, None)? {
+                Some(Ok(id)) => Ok(id),
+                Some(Err(())) => {
+                    anyhow::bail!("Short hash '{self}' is ambiguous - provide more characters")
+                }
+                None => anyhow::bail!("No object found for short hash '{self}'"),
+            }
+        }
+    }
+
+    impl From<gix::ObjectId> for HexHashPrefix {
+        fn from(value: gix::ObjectId) -> Self {
+            HexHashPrefix(value.into())
+        }
+    }
+
+    impl FromStr for HexHashPrefix {
+        type Err = anyhow::Error;
+
+        fn from_str(hex: &str) -> Result<Self, Self::Err> {
+            let max_hex_len = gix::hash::Kind::longest().len_in_hex();
+            if !(Self::MIN_HEX_LEN..=max_hex_len).contains(&hex.len()) {
+                anyhow::bail!(
+                    "Expected a hash of {} to {max_hex_len} hex characters, got '{hex}'",
+                    Self::MIN_HEX_LEN
+                );
+            }
+            Ok(HexHashPrefix(gix::hash::Prefix::from_hex(hex)?))
+        }
+    }
+
+    impl std::fmt::Display for HexHashPrefix {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HexHashPrefix {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let hex = String::deserialize(deserializer)?;
+            hex.parse().map_err(serde::de::Error::custom)
+        }
+    }
+
+    impl Serialize for HexHashPrefix {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
     mod stringy {
         use std::str::FromStr;
 
@@ -126,10 +215,88 @@ mod hex_hash {
                 actual.unwrap(),
                 "\"5c69907b1244089142905dba380371728e2e8160\""
             );
+
+            let parsed: HexHash = hex_str.parse().expect("input is valid");
+            assert_eq!(parsed.to_string().parse::<HexHash>().ok(), Some(parsed));
+        }
+
+        #[test]
+        fn hex_hash_prefix_parsing() {
+            for invalid in ["", "abc", "xyz1", "5c69907g", "a".repeat(41).as_str()] {
+                assert!(
+                    invalid.parse::<HexHashPrefix>().is_err(),
+                    "{invalid:?} isn't a valid prefix"
+                );
+            }
+
+            for valid in [
+                "5c69",
+                "5c699",
+                "5c69907",
+                "5c69907b1244089142905dba380371728e2e8160",
+            ] {
+                let prefix: HexHashPrefix = valid.parse().expect("valid prefix");
+                assert_eq!(
+                    prefix.to_string(),
+                    valid,
+                    "odd lengths are retained as well"
+                );
+
+                let json = serde_json::to_string(&prefix).expect("serializable");
+                assert_eq!(json, format!("\"{valid}\""));
+                assert_eq!(
+                    serde_json::from_str::<HexHashPrefix>(&json).expect("round-trips"),
+                    prefix
+                );
+            }
+        }
+
+        #[test]
+        fn hex_hash_prefix_resolution() -> anyhow::Result<()> {
+            let repo = but_testsupport::read_only_in_memory_scenario("checkout-head-info")?;
+
+            let unknown = "5c69907b1244089142905dba380371728e2e8160";
+            assert_eq!(
+                unknown
+                    .parse::<HexHashPrefix>()?
+                    .resolve(&repo)?
+                    .to_string(),
+                unknown,
+                "full hashes are passed through, even if the object doesn't exist"
+            );
+            assert!(
+                unknown[..7]
+                    .parse::<HexHashPrefix>()?
+                    .resolve(&repo)
+                    .is_err(),
+                "short hashes must exist"
+            );
+
+            let mut seen = std::collections::HashMap::new();
+            let (first, second) = (0..)
+                .find_map(|idx: usize| {
+                    let id = repo.write_blob(idx.to_string()).ok()?.detach();
+                    let short = id.to_hex_with_len(HexHashPrefix::MIN_HEX_LEN).to_string();
+                    seen.insert(short, id).map(|other| (other, id))
+                })
+                .expect("eventually blobs share a prefix");
+
+            let shared_prefix = &first.to_string()[..HexHashPrefix::MIN_HEX_LEN];
+            let err = shared_prefix
+                .parse::<HexHashPrefix>()?
+                .resolve(&repo)
+                .unwrap_err();
+            assert!(err.to_string().contains("ambiguous"), "{err}");
+
+            for id in [first, second] {
+                let unique_prefix = id.to_hex_with_len(16).to_string();
+                assert_eq!(unique_prefix.parse::<HexHashPrefix>()?.resolve(&repo)?, id);
+            }
+            Ok(())
         }
     }
 }
-pub use hex_hash::{HexHash, HexHashString};
+pub use hex_hash::{HexHash, HexHashPrefix, HexHashString};
 
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(HexHashString);
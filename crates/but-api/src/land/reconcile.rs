@@ -70,7 +70,11 @@ pub(super) fn reconcile_after_land(ctx: &mut but_ctx::Context) -> anyhow::Result
 /// would be overwritten. The integration moves no refs before that point (it checks out before
 /// applying ref edits), so it is safe to treat as a deferred reconcile rather than a hard failure.
 fn is_uncommitted_changes_block(err: &anyhow::Error) -> bool {
-    err.downcast_ref::<but_error::Context>()
+    matches!(
+        err.downcast_ref::<crate::error::Error>(),
+        Some(crate::error::Error::WorktreeConflict { .. })
+    ) || err
+        .downcast_ref::<but_error::Context>()
         .is_some_and(|ctx| ctx.code == but_error::Code::PreconditionFailed)
 }
 
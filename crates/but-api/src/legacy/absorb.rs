@@ -403,7 +403,8 @@ fn ensure_target_commit(
     if let Some(stack_id) = assignment.stack_id {
         let branch_ref = assignment.branch_ref_bytes.as_ref();
 
-        let stack_details = crate::legacy::workspace::stack_details(ctx, Some(stack_id))?;
+        let stack_details =
+            crate::legacy::workspace::stack_details_with_commit_limit(ctx, Some(stack_id), 1)?;
         if let Some(branch) = find_target_branch(&stack_details, branch_ref)
             && let Some(commit) = branch.commits.first()
         {
@@ -422,7 +423,8 @@ fn ensure_target_commit(
         )?;
 
         // Fetch again to get the newly created commit
-        let stack_details = crate::legacy::workspace::stack_details(ctx, Some(stack_id))?;
+        let stack_details =
+            crate::legacy::workspace::stack_details_with_commit_limit(ctx, Some(stack_id), 1)?;
         if let Some(branch) = find_target_branch(&stack_details, branch_ref)
             && let Some(commit) = branch.commits.first()
         {
@@ -437,7 +439,8 @@ fn ensure_target_commit(
     if let Some(stack) = stacks.first()
         && let Some(stack_id) = stack.id
     {
-        let stack_details = crate::legacy::workspace::stack_details(ctx, Some(stack_id))?;
+        let stack_details =
+            crate::legacy::workspace::stack_details_with_commit_limit(ctx, Some(stack_id), 1)?;
         if let Some(branch) = stack_details.branch_details.first()
             && let Some(commit) = branch.commits.first()
         {
@@ -458,7 +461,8 @@ fn ensure_target_commit(
         )?;
 
         // Now fetch the stack details again to get the newly created commit
-        let stack_details = crate::legacy::workspace::stack_details(ctx, Some(stack_id))?;
+        let stack_details =
+            crate::legacy::workspace::stack_details_with_commit_limit(ctx, Some(stack_id), 1)?;
         if let Some(branch) = stack_details.branch_details.first()
             && let Some(commit) = branch.commits.first()
         {
@@ -299,7 +299,13 @@ pub fn review_apply(
                 )
             })?;
 
-    let out = crate::branch::apply_with_perm(ctx, remote_ref.as_ref(), guard.write_permission())?;
+    let out = crate::branch::apply_with_perm(
+        ctx,
+        remote_ref.as_ref(),
+        but_workspace::branch::OnWorkspaceMergeConflict::default(),
+        None,
+        guard.write_permission(),
+    )?;
     if out.status.persisted_mutation() {
         // The applied review is already in the forge cache (it was just fetched
         // to be applied), so its PR association is derived at projection time.
@@ -684,6 +690,35 @@ pub fn list_ci_checks_for_ref(
     )
 }
 
+/// Like [`list_ci_checks_for_ref()`], but for all `references` at once, keyed by reference.
+pub fn list_ci_checks_for_refs(
+    ctx: &Context,
+    references: &[String],
+    cache_config: Option<but_forge::CacheConfig>,
+) -> Result<std::collections::HashMap<String, Vec<but_forge::CiCheck>>> {
+    let (storage, forge_repo_info, preferred_forge_user) = {
+        let project_meta = ctx.project_meta()?;
+        let repo = ctx.repo.get()?;
+        let forge_repo_info = but_forge::derive_forge_repo_info(&remote_url(&project_meta, &repo)?);
+
+        (
+            but_forge_storage::Controller::from_path(but_path::app_data_dir()?),
+            forge_repo_info,
+            ctx.legacy_project.preferred_forge_user.clone(),
+        )
+    };
+    let db = &mut *ctx.db.get_cache_mut()?;
+
+    but_forge::ci_checks_for_refs_with_cache(
+        preferred_forge_user,
+        &forge_repo_info.context("No forge could be determined for this repository branch")?,
+        &storage,
+        references,
+        db,
+        cache_config,
+    )
+}
+
 #[but_api(napi)]
 #[instrument(err(Debug))]
 pub async fn publish_review(
@@ -1375,31 +1410,27 @@ pub async fn list_reviews_for_branch(
 #[but_api(napi)]
 #[instrument(err(Debug))]
 pub fn warm_ci_checks_cache(ctx: &Context) -> Result<()> {
-    // Get all stacks
-    let stacks = crate::legacy::workspace::stacks(ctx, None)?;
+    // Get the details of all applied stacks at once
+    let all_details = crate::legacy::workspace::all_stack_details(ctx, None)?;
 
     // Collect branch references that have CI checks cached
     let mut current_refs = std::collections::HashSet::new();
 
-    // For each stack, get details and check branches
-    for stack in stacks {
-        if let Some(stack_id) = stack.id {
-            let details = crate::legacy::workspace::stack_details(ctx, Some(stack_id))?;
-
-            // Process each branch that has a PR
-            for branch in &details.branch_details {
-                if branch.pr_number.is_some() {
-                    // Fetch CI checks with NoCache to force refresh
-                    let _ = list_ci_checks(
-                        ctx,
-                        branch.name.to_string(),
-                        Some(but_forge::CacheConfig::NoCache),
-                    );
-                    // Ignore errors for individual branches to ensure we process all branches
-
-                    // Track this reference as having CI checks
-                    current_refs.insert(branch.name.to_string());
-                }
+    // For each stack, check branches
+    for (_stack_id, details) in all_details {
+        // Process each branch that has a PR
+        for branch in &details.branch_details {
+            if branch.pr_number.is_some() {
+                // Fetch CI checks with NoCache to force refresh
+                let _ = list_ci_checks(
+                    ctx,
+                    branch.name.to_string(),
+                    Some(but_forge::CacheConfig::NoCache),
+                );
+                // Ignore errors for individual branches to ensure we process all branches
+
+                // Track this reference as having CI checks
+                current_refs.insert(branch.name.to_string());
             }
         }
     }
@@ -111,10 +111,27 @@ pub fn save_edit_and_return_to_workspace(ctx: &mut but_ctx::Context) -> Result<(
 
 #[but_api]
 #[instrument(err(Debug))]
-pub fn save_edit_and_return_to_workspace_with_output(ctx: &mut but_ctx::Context) -> Result<()> {
+pub fn save_edit_and_return_to_workspace_with_output(
+    ctx: &mut but_ctx::Context,
+) -> Result<gix::ObjectId> {
     let mut guard = ctx.exclusive_worktree_access();
-    gitbutler_edit_mode::commands::save_and_return_to_workspace(ctx, guard.write_permission())?;
-    Ok(())
+    gitbutler_edit_mode::commands::save_and_return_to_workspace(ctx, guard.write_permission())
+}
+
+#[but_api]
+#[instrument(err(Debug))]
+pub fn clear_leftover_edit_mode_state(ctx: &mut but_ctx::Context) -> Result<bool> {
+    clear_leftover_edit_mode_state_with_output(ctx).map(|leftovers| leftovers.cleaned_up)
+}
+
+/// Like [`clear_leftover_edit_mode_state()`], but also returns the stashed uncommitted changes
+/// that were kept, so callers can tell the user how to recover them.
+#[instrument(err(Debug))]
+pub fn clear_leftover_edit_mode_state_with_output(
+    ctx: &mut but_ctx::Context,
+) -> Result<gitbutler_edit_mode::LeftoverEditModeState> {
+    let mut guard = ctx.exclusive_worktree_access();
+    gitbutler_edit_mode::commands::clear_leftover_edit_mode_state(ctx, guard.write_permission())
 }
 
 #[but_api]
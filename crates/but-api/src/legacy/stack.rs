@@ -14,7 +14,7 @@ use tracing::instrument;
 pub mod create_reference {
     use serde::{Deserialize, Serialize};
 
-    use crate::json::HexHash;
+    use crate::json::HexHashPrefix;
 
     #[derive(Deserialize, Serialize, Debug)]
     #[serde(rename_all = "camelCase")]
@@ -29,7 +29,8 @@ pub mod create_reference {
     #[serde(tag = "type", content = "subject", rename_all = "camelCase")]
     pub enum Anchor {
         AtCommit {
-            commit_id: HexHash,
+            /// The commit to anchor at, which may be abbreviated as long as it's unambiguous.
+            commit_id: HexHashPrefix,
             position: but_workspace::branch::create_reference::Position,
         },
         AtSegment {
@@ -91,7 +92,7 @@ pub fn create_reference_with_perm(
                     commit_id,
                     position,
                 } => but_workspace::branch::create_reference::Anchor::AtCommit {
-                    commit_id: commit_id.into(),
+                    commit_id: commit_id.resolve(&*ctx.repo.get()?)?,
                     position,
                 },
                 create_reference::Anchor::AtSegment {
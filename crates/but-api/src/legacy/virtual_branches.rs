@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use anyhow::{Context as _, Result, anyhow, bail};
-use bstr::ByteSlice;
+use bstr::{BString, ByteSlice};
 use but_api_macros::but_api;
 use but_core::{
     DiffSpec, RefMetadata,
@@ -10,9 +10,10 @@ use but_core::{
 };
 use but_ctx::Context;
 use but_error::bail_precondition;
+use but_hunk_assignment::{HunkAssignment, HunkAssignmentRequest};
 use but_oplog::legacy::{OperationKind, SnapshotDetails, Trailer};
 use but_rebase::graph_rebase::{
-    Editor,
+    Editor, LookupStep as _,
     mutate::{InsertSide, RelativeToRef},
 };
 use but_workspace::branch::unapply::WorkspaceDisposition;
@@ -29,7 +30,10 @@ use gitbutler_reference::{Refname, normalize_branch_name as normalize_name};
 use gix::reference::Category;
 use tracing::instrument;
 
-use crate::legacy::workspace::canned_branch_name;
+use crate::{
+    error::{Error, lift_no_default_target},
+    legacy::workspace::canned_branch_name,
+};
 // Parameter structs for all functions
 
 #[but_api]
@@ -186,6 +190,7 @@ pub fn switch_back_to_workspace_with_perm(
 ) -> Result<BaseBranch> {
     let base_branch =
         gitbutler_branch_actions::base::get_base_branch_data(ctx, perm.read_permission())
+            .map_err(lift_no_default_target)
             .context("Failed to get base branch data")?;
 
     let branch_name = format!("refs/remotes/{}", base_branch.branch_name)
@@ -225,7 +230,7 @@ pub fn set_base_branch(
     push_remote: Option<String>,
 ) -> Result<BaseBranch> {
     let mut guard = ctx.exclusive_worktree_access();
-    set_base_branch_with_perm(ctx, branch, push_remote, guard.write_permission())
+    set_base_branch_with_perm(ctx, branch, push_remote, true, guard.write_permission())
 }
 
 #[instrument(skip(perm), err(Debug))]
@@ -233,16 +238,23 @@ pub fn set_base_branch(
 ///
 /// This variant is more composable than [`set_base_branch`] when the caller already holds a lock,
 /// as it reuses the provided permission token instead of obtaining exclusive access itself.
+/// If `adopt_head` is set, the branch `HEAD` points to is applied as stack if it has commits on top
+/// of the new target.
 pub fn set_base_branch_with_perm(
     ctx: &mut but_ctx::Context,
     branch: String,
     push_remote: Option<String>,
+    adopt_head: bool,
     perm: &mut RepoExclusive,
 ) -> Result<BaseBranch> {
     let branch_name = format!("refs/remotes/{branch}")
         .parse()
         .context("Invalid branch name")?;
-    let base_branch = gitbutler_branch_actions::set_base_branch(ctx, &branch_name, perm)?;
+    let base_branch = if adopt_head {
+        gitbutler_branch_actions::set_base_branch(ctx, &branch_name, perm)?
+    } else {
+        gitbutler_branch_actions::set_base_branch_without_adopting_head(ctx, &branch_name, perm)?
+    };
 
     // if they also sent a different push remote, set that too
     if let Some(push_remote) = push_remote {
@@ -309,8 +321,7 @@ fn apply_stack_order_updates(
             .iter()
             .any(|stack| stack.id == stack_id && stack.is_in_workspace())
         {
-            return Err(anyhow!("branch with ID {stack_id} not found")
-                .context(but_error::Code::BranchNotFound));
+            return Err(Error::StackNotFound(stack_id).into_anyhow());
         }
 
         if let Some(order) = update.order {
@@ -483,19 +494,51 @@ pub fn unapply_stack_with_perm(
     stack_id: StackId,
     perm: &mut RepoExclusive,
 ) -> Result<()> {
-    unapply_stack_v3_with_perm(ctx, stack_id, perm)
+    unapply_stack_v3_with_perm(ctx, stack_id, AssignedChanges::Stash, perm).map(|_| ())
+}
+
+/// What to do with the uncommitted changes assigned to a stack when it is unapplied.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AssignedChanges {
+    /// Commit them as "WIP Assignments" commit below the tip of the stack, so they travel with it.
+    #[default]
+    Stash,
+    /// Leave them in the worktree, but unassigned.
+    Keep,
+}
+
+/// What happened when unapplying a stack with [`unapply_stack_with_assigned_changes_with_perm()`].
+#[derive(Debug)]
+pub struct UnapplyStackOutcome {
+    /// The branches of the unapplied stack, tip-most branch first. They all still exist.
+    pub branches: Vec<gix::refs::FullName>,
+    /// The paths of the uncommitted changes that were assigned to the stack.
+    pub assigned_paths: Vec<BString>,
+    /// The commit the assigned changes were stashed into, if [`AssignedChanges::Stash`] was used
+    /// and there were any.
+    pub stash_commit: Option<gix::ObjectId>,
 }
 
-/// Return the worktree diffspecs currently assigned to `stack_id`.
+/// Like [`unapply_stack_with_perm()`], but let `assigned_changes` decide what happens to
+/// the uncommitted changes assigned to `stack_id`, and report what was done.
+pub fn unapply_stack_with_assigned_changes_with_perm(
+    ctx: &mut Context,
+    stack_id: StackId,
+    assigned_changes: AssignedChanges,
+    perm: &mut RepoExclusive,
+) -> Result<UnapplyStackOutcome> {
+    unapply_stack_v3_with_perm(ctx, stack_id, assigned_changes, perm)
+}
+
+/// Return the worktree hunk assignments currently owned by `stack_id`.
 ///
-/// This reconciles persisted hunk assignments with current worktree changes,
-/// keeps only assignments owned by `stack_id`, and flattens them into the
-/// diffspec list consumed by unapply implementations.
-fn assigned_diffspec_for_stack(
+/// This reconciles persisted hunk assignments with current worktree changes
+/// and keeps only assignments owned by `stack_id`.
+fn assignments_for_stack(
     ctx: &Context,
     stack_id: StackId,
     perm: &RepoShared,
-) -> Result<Vec<DiffSpec>> {
+) -> Result<Vec<HunkAssignment>> {
     let context_lines = ctx.settings.context_lines;
     let (repo, ws, mut db) = ctx.workspace_and_db_mut_with_perm(perm)?;
     let (assignments, _) = but_hunk_assignment::assignments_with_fallback(
@@ -505,14 +548,35 @@ fn assigned_diffspec_for_stack(
         Some(but_core::diff::ui::worktree_changes(&repo)?.changes),
         context_lines,
     )?;
-    let assigned_diffspec = but_workspace::flatten_diff_specs(
-        assignments
-            .into_iter()
-            .filter(|a| a.stack_id == Some(stack_id))
-            .map(|a| a.into())
-            .collect::<Vec<DiffSpec>>(),
-    );
-    Ok(assigned_diffspec)
+    Ok(assignments
+        .into_iter()
+        .filter(|a| a.stack_id == Some(stack_id))
+        .collect())
+}
+
+/// Mark all `assignments` as unassigned, so they stay in the worktree without an owner
+/// once their stack is gone.
+fn unassign(ctx: &Context, assignments: Vec<HunkAssignment>, perm: &RepoShared) -> Result<()> {
+    if assignments.is_empty() {
+        return Ok(());
+    }
+    let context_lines = ctx.settings.context_lines;
+    let (repo, ws, mut db) = ctx.workspace_and_db_mut_with_perm(perm)?;
+    let requests = assignments
+        .into_iter()
+        .map(|a| HunkAssignmentRequest {
+            hunk_header: a.hunk_header,
+            path_bytes: a.path_bytes,
+            target: None,
+        })
+        .collect();
+    but_hunk_assignment::assign(
+        db.hunk_assignments_mut()?,
+        &repo,
+        &ws,
+        requests,
+        context_lines,
+    )
 }
 
 /// Unapply a stack through the newer workspace metadata implementation.
@@ -529,7 +593,8 @@ fn assigned_diffspec_for_stack(
 /// - collect currently assigned worktree changes for `stack_id`;
 /// - identify the workspace metadata branch representing the stack;
 /// - create a best-effort unapply snapshot with all stack branch names as trailers;
-/// - commit assigned changes below the branch being unapplied so they travel with it;
+/// - commit assigned changes below the branch being unapplied so they travel with it,
+///   or unassign them if they should stay in the worktree as per `assigned_changes`;
 /// - delegate the actual workspace mutation to [`but_workspace::branch::unapply()`]
 ///   and update the cached workspace projection with the returned workspace.
 ///
@@ -542,16 +607,25 @@ fn assigned_diffspec_for_stack(
 fn unapply_stack_v3_with_perm(
     ctx: &mut Context,
     stack_id: StackId,
+    assigned_changes: AssignedChanges,
     perm: &mut RepoExclusive,
-) -> Result<()> {
+) -> Result<UnapplyStackOutcome> {
     ensure_open_workspace_mode(ctx, perm.read_permission())
         .context("Unapplying a stack requires open workspace mode")?;
 
-    let assigned_diffspec = assigned_diffspec_for_stack(ctx, stack_id, perm.read_permission())?;
+    let assignments = assignments_for_stack(ctx, stack_id, perm.read_permission())?;
     let stack_branches = stack_branch_names(ctx, stack_id, perm)?;
     let Some(branch_to_unapply) = stack_branches.first().cloned() else {
-        return Ok(());
+        return Ok(UnapplyStackOutcome {
+            branches: Vec::new(),
+            assigned_paths: Vec::new(),
+            stash_commit: None,
+        });
     };
+    let mut assigned_paths: Vec<BString> =
+        assignments.iter().map(|a| a.path_bytes.clone()).collect();
+    assigned_paths.sort();
+    assigned_paths.dedup();
 
     let trailers = stack_branches
         .iter()
@@ -559,29 +633,50 @@ fn unapply_stack_v3_with_perm(
     let details = SnapshotDetails::new(OperationKind::UnapplyBranch).with_trailers(trailers);
     let _snapshot = ctx.create_snapshot(details, perm).ok();
 
-    commit_assigned_diffspec(ctx, branch_to_unapply.as_ref(), assigned_diffspec, perm)?;
-
-    let mut meta = ctx.legacy_meta_mut(perm)?;
-    let (repo, mut ws, _) = ctx.workspace_mut_and_db_with_perm(perm)?;
-    let workspace_disposition = if ctx.settings.feature_flags.unapply_v3_pgm {
-        WorkspaceDisposition::PreventUnnecessaryWorkspaceReferencesKeepWorkspaceCommit
-    } else {
-        WorkspaceDisposition::KeepWorkspaceCommit
+    let (stash_commit, assignments_to_unassign) = match assigned_changes {
+        AssignedChanges::Stash => {
+            let assigned_diffspec = but_workspace::flatten_diff_specs(
+                assignments
+                    .into_iter()
+                    .map(Into::into)
+                    .collect::<Vec<DiffSpec>>(),
+            );
+            let stash_commit =
+                commit_assigned_diffspec(ctx, branch_to_unapply.as_ref(), assigned_diffspec, perm)?;
+            (stash_commit, Vec::new())
+        }
+        // Unassigned only once the stack is gone, so a failed unapply keeps them assigned.
+        AssignedChanges::Keep => (None, assignments),
     };
-    let outcome = but_workspace::branch::unapply(
-        branch_to_unapply.as_ref(),
-        &ws,
-        &repo,
-        &mut meta,
-        but_workspace::branch::unapply::Options {
-            workspace_disposition,
-        },
-    )?;
-    *ws = outcome.workspace.into_owned();
-    // Keeping the workspace merge commit can make legacy reconciliation infer the
-    // removed stack as applied again, so persist the explicit workspace metadata.
-    meta.write_unreconciled()?;
-    Ok(())
+
+    {
+        let mut meta = ctx.legacy_meta_mut(perm)?;
+        let (repo, mut ws, _) = ctx.workspace_mut_and_db_with_perm(perm)?;
+        let workspace_disposition = if ctx.settings.feature_flags.unapply_v3_pgm {
+            WorkspaceDisposition::PreventUnnecessaryWorkspaceReferencesKeepWorkspaceCommit
+        } else {
+            WorkspaceDisposition::KeepWorkspaceCommit
+        };
+        let outcome = but_workspace::branch::unapply(
+            branch_to_unapply.as_ref(),
+            &ws,
+            &repo,
+            &mut meta,
+            but_workspace::branch::unapply::Options {
+                workspace_disposition,
+            },
+        )?;
+        *ws = outcome.workspace.into_owned();
+        // Keeping the workspace merge commit can make legacy reconciliation infer the
+        // removed stack as applied again, so persist the explicit workspace metadata.
+        meta.write_unreconciled()?;
+    }
+    unassign(ctx, assignments_to_unassign, perm.read_permission())?;
+    Ok(UnapplyStackOutcome {
+        branches: stack_branches,
+        assigned_paths,
+        stash_commit,
+    })
 }
 
 /// Return branch names for the projected workspace stack identified by `stack_id`.
@@ -595,9 +690,7 @@ fn stack_branch_names(
 ) -> Result<Vec<gix::refs::FullName>> {
     let (_repo, ws, _) = ctx.workspace_mut_and_db_with_perm(perm)?;
     let Some(stack) = ws.stacks.iter().find(|stack| stack.id == Some(stack_id)) else {
-        return Err(
-            anyhow!("branch with ID {stack_id} not found").context(but_error::Code::BranchNotFound)
-        );
+        return Err(Error::StackNotFound(stack_id).into_anyhow());
     };
 
     if stack
@@ -629,6 +722,8 @@ fn stack_branch_names(
 /// caller-held exclusive repository permission used while mutating history and
 /// refreshing the cached workspace state.
 ///
+/// Returns the id of the new commit, if one was created.
+///
 /// # TODOs
 ///
 /// - The "WIP Assignments" commit feels like an elaborate PoC and ideally, there is
@@ -647,9 +742,9 @@ fn commit_assigned_diffspec(
     branch: &gix::refs::FullNameRef,
     assigned_diffspec: Vec<DiffSpec>,
     perm: &mut RepoExclusive,
-) -> Result<()> {
+) -> Result<Option<gix::ObjectId>> {
     if assigned_diffspec.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
 
     let mut meta = ctx.meta()?;
@@ -669,12 +764,14 @@ fn commit_assigned_diffspec(
             "Failed to commit at least one hunk"
         );
     }
-    if outcome.commit_selector.is_some() {
-        outcome.rebase.materialize()?;
-        drop((repo, ws));
-        ctx.reload_repo_and_invalidate_workspace(perm)?;
-    }
-    Ok(())
+    let Some(commit_selector) = outcome.commit_selector else {
+        return Ok(None);
+    };
+    let stash_commit = outcome.rebase.lookup_pick(commit_selector)?;
+    outcome.rebase.materialize()?;
+    drop((repo, ws));
+    ctx.reload_repo_and_invalidate_workspace(perm)?;
+    Ok(Some(stash_commit))
 }
 
 #[but_api(napi)]
@@ -750,6 +847,7 @@ pub fn fetch_from_remotes(ctx: &Context, action: Option<String>) -> Result<BaseB
 
     let guard = ctx.shared_worktree_access();
     let base_branch =
-        gitbutler_branch_actions::base::get_base_branch_data(ctx, guard.read_permission())?;
+        gitbutler_branch_actions::base::get_base_branch_data(ctx, guard.read_permission())
+            .map_err(lift_no_default_target)?;
     Ok(base_branch)
 }
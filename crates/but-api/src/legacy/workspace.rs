@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use anyhow::{Context as _, Result};
 use but_api_macros::but_api;
-use but_core::{RepositoryExt, ref_metadata::StackId};
+use but_core::{RepositoryExt, ref_metadata::StackId, sync::RepoShared};
 use but_ctx::{Context, ThreadSafeContext};
 use but_rebase::{
     RebaseOutput,
@@ -23,7 +23,7 @@ use gitbutler_oplog::{
 };
 use tracing::instrument;
 
-use crate::json::HexHash;
+use crate::{error::Error, json::HexHash};
 
 #[but_api(napi, try_from = but_workspace::ui::RefInfo)]
 #[instrument(err(Debug))]
@@ -147,16 +147,254 @@ pub fn stack_details(
             traversal,
         )
     }?;
+    apply_gerrit_mode(ctx, std::iter::once(&mut details))?;
+    Ok(details)
+}
+
+/// Return the details of all stacks that pass `filter`, along with their ids.
+///
+/// Unlike calling [`stack_details()`] for each stack returned by [`stacks()`], this
+/// computes the workspace projection only once.
+#[but_api]
+#[instrument(err(Debug))]
+#[expect(
+    deprecated,
+    reason = "calls but_workspace::legacy::all_stack_details_v3"
+)]
+pub fn all_stack_details(
+    ctx: &Context,
+    filter: Option<but_workspace::legacy::StacksFilter>,
+) -> Result<Vec<(StackId, but_workspace::ui::StackDetails)>> {
+    let traversal = ctx.graph_options(but_graph::init::Options::limited())?;
+    let mut all_details = {
+        let repo = ctx.clone_repo_for_merging_non_persisting()?;
+        let meta = ctx.meta()?;
+        but_workspace::legacy::all_stack_details_v3(
+            &repo,
+            &meta,
+            &ctx.project_meta()?,
+            traversal,
+            filter.unwrap_or_default(),
+        )
+    }?;
+    apply_gerrit_mode(ctx, all_details.iter_mut().map(|(_, details)| details))?;
+    Ok(all_details)
+}
+
+/// Like [`stack_details()`], but list at most `max_commits_per_branch` commits of each branch,
+/// starting at its tip, and tell for each branch if it has more commits than that.
+///
+/// The commits past the limit aren't listed at all, even though the workspace is still traversed entirely.
+/// Use [`branch_commits_page()`] to obtain the commits that were left out.
+#[but_api]
+#[instrument(err(Debug))]
+pub fn stack_details_limited(
+    ctx: &Context,
+    stack_id: Option<StackId>,
+    max_commits_per_branch: usize,
+) -> Result<LimitedStackDetails> {
+    // List one more commit than asked for to learn if there are more.
+    let but_workspace::ui::StackDetails {
+        derived_name,
+        push_status,
+        branch_details,
+        is_conflicted,
+    } = stack_details_with_commit_limit(ctx, stack_id, max_commits_per_branch.saturating_add(1))?;
+    Ok(LimitedStackDetails {
+        derived_name,
+        push_status,
+        branch_details: branch_details
+            .into_iter()
+            .map(|mut details| {
+                let has_more = details.commits.len() > max_commits_per_branch;
+                details.commits.truncate(max_commits_per_branch);
+                LimitedBranchDetails { details, has_more }
+            })
+            .collect(),
+        is_conflicted,
+    })
+}
+
+/// Return up to `limit` commits of the branch named `branch_name` in the stack with `stack_id`,
+/// skipping the first `cursor` commits as counted from the tip of the branch.
+///
+/// This is meant to page through the commits that [`stack_details_limited()`] left out.
+#[but_api]
+#[instrument(err(Debug))]
+pub fn branch_commits_page(
+    ctx: &Context,
+    stack_id: Option<StackId>,
+    branch_name: String,
+    cursor: usize,
+    limit: usize,
+) -> Result<BranchCommitsPage> {
+    // List one more commit than the page needs to learn if there is another page.
+    let max_commits = cursor.saturating_add(limit).saturating_add(1);
+    let details = stack_details_with_commit_limit(ctx, stack_id, max_commits)?;
+    let branch = details
+        .branch_details
+        .into_iter()
+        .find(|branch| branch.name == branch_name)
+        .ok_or_else(|| Error::BranchNotFound(branch_name).into_anyhow())?;
+    let (commits, next_cursor) = page(branch.commits, cursor, limit);
+    Ok(BranchCommitsPage {
+        commits,
+        next_cursor,
+    })
+}
+
+/// Like [`stack_details()`], but list at most `max_commits_per_branch` commits of each branch.
+#[expect(
+    deprecated,
+    reason = "calls but_workspace::legacy::stack_details_v3_limited"
+)]
+pub(crate) fn stack_details_with_commit_limit(
+    ctx: &Context,
+    stack_id: Option<StackId>,
+    max_commits_per_branch: usize,
+) -> Result<but_workspace::ui::StackDetails> {
+    let traversal = ctx.graph_options(but_graph::init::Options::limited())?;
+    let mut details = {
+        let repo = ctx.clone_repo_for_merging_non_persisting()?;
+        let meta = ctx.meta()?;
+        but_workspace::legacy::stack_details_v3_limited(
+            stack_id,
+            &repo,
+            &meta,
+            &ctx.project_meta()?,
+            traversal,
+            max_commits_per_branch,
+        )
+    }?;
+    apply_gerrit_mode(ctx, std::iter::once(&mut details))?;
+    Ok(details)
+}
+
+/// Return at most `limit` of `items` after skipping `cursor` of them, along with the cursor
+/// to the remaining items, if there are any.
+fn page<T>(items: Vec<T>, cursor: usize, limit: usize) -> (Vec<T>, Option<usize>) {
+    let total = items.len();
+    let items: Vec<_> = items.into_iter().skip(cursor).take(limit).collect();
+    let next_cursor = cursor.saturating_add(items.len());
+    (items, (next_cursor < total).then_some(next_cursor))
+}
+
+/// The details of a stack with a limited amount of commits per branch, as returned by
+/// [`stack_details_limited()`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedStackDetails {
+    /// This is the name of the top-most branch, provided by the API for convenience
+    pub derived_name: String,
+    /// The pushable status for the stack
+    pub push_status: but_workspace::ui::PushStatus,
+    /// The details about the contained branches, with their commits possibly truncated
+    pub branch_details: Vec<LimitedBranchDetails>,
+    /// Whether the stack is conflicted.
+    pub is_conflicted: bool,
+}
+
+/// The details of a branch whose commits may be truncated, as part of [`LimitedStackDetails`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedBranchDetails {
+    /// The branch details, with only the first commits from its tip.
+    #[serde(flatten)]
+    pub details: but_workspace::ui::BranchDetails,
+    /// Whether the branch has more commits than are listed in `details`.
+    pub has_more: bool,
+}
+
+/// A page of commits of a branch, as returned by [`branch_commits_page()`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchCommitsPage {
+    /// The commits of this page, from the top of the branch towards its base.
+    pub commits: Vec<but_workspace::ui::Commit>,
+    /// The cursor to pass for obtaining the next page, or `None` if this was the last page.
+    pub next_cursor: Option<usize>,
+}
+
+/// Return the id and details of the stack containing the branch `branch_name`,
+/// or `None` if no stack in the workspace contains it.
+///
+/// For details, see [`stack_details_by_branch_with_perm()`].
+#[but_api(napi)]
+#[instrument(err(Debug))]
+pub fn stack_details_by_branch(
+    ctx: &Context,
+    branch_name: String,
+) -> Result<Option<(StackId, but_workspace::ui::StackDetails)>> {
+    let guard = ctx.shared_worktree_access();
+    stack_details_by_branch_with_perm(ctx, &branch_name, guard.read_permission())
+}
+
+/// Like [`stack_details_by_branch()`], but under caller-held repository access.
+///
+/// `branch_name` may be a short name like `feature`, a full name like `refs/heads/feature`,
+/// or the name of a remote tracking branch like `origin/feature`, which maps to the local
+/// branch of the same name. The stack is looked up in the workspace projection, and its
+/// details are computed only once it was found.
+pub fn stack_details_by_branch_with_perm(
+    ctx: &Context,
+    branch_name: &str,
+    perm: &RepoShared,
+) -> Result<Option<(StackId, but_workspace::ui::StackDetails)>> {
+    let stack_id = {
+        let (repo, ws, _db) = ctx.workspace_and_db_with_perm(perm)?;
+        let Some(ref_name) = local_branch_ref_name(&repo, branch_name)? else {
+            return Ok(None);
+        };
+        ws.find_segment_and_stack_by_refname(ref_name.as_ref())
+            .and_then(|(stack, _segment)| stack.id)
+    };
+    stack_id
+        .map(|stack_id| Ok((stack_id, stack_details(ctx, Some(stack_id))?)))
+        .transpose()
+}
+
+/// Resolve `name` to the full name of a local branch, mapping remote tracking branches
+/// to the local branch of the same short name.
+fn local_branch_ref_name(
+    repo: &gix::Repository,
+    name: &str,
+) -> Result<Option<gix::refs::FullName>> {
+    let Some(reference) = repo.try_find_reference(name)? else {
+        return Ok(None);
+    };
+    let ref_name = reference.name();
+    Ok(match ref_name.category() {
+        Some(gix::refs::Category::LocalBranch) => Some(ref_name.to_owned()),
+        Some(gix::refs::Category::RemoteBranch) => {
+            but_core::extract_remote_name_and_short_name(ref_name, &repo.remote_names())
+                .map(|(_remote, short_name)| {
+                    gix::refs::FullName::try_from(format!("refs/heads/{short_name}"))
+                })
+                .transpose()?
+        }
+        _ => None,
+    })
+}
+
+/// Overlay the Gerrit review state onto all branches of `details` if Gerrit mode is enabled.
+fn apply_gerrit_mode<'a>(
+    ctx: &Context,
+    details: impl IntoIterator<Item = &'a mut but_workspace::ui::StackDetails>,
+) -> Result<()> {
     let repo = ctx.repo.get()?;
     let gerrit_mode = repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false);
+    if !gerrit_mode {
+        return Ok(());
+    }
     let db = ctx.db.get_cache()?;
-    if gerrit_mode {
-        for branch in details.branch_details.iter_mut() {
-            handle_gerrit(branch, &repo, &db)?;
-            update_push_status(branch);
-        }
+    for branch in details
+        .into_iter()
+        .flat_map(|details| details.branch_details.iter_mut())
+    {
+        handle_gerrit(branch, &repo, &db)?;
+        update_push_status(branch);
     }
-    Ok(details)
+    Ok(())
 }
 
 fn update_push_status(branch: &mut but_workspace::ui::BranchDetails) {
@@ -242,6 +480,9 @@ pub fn branch_details(
         }
         .try_into()
         .map_err(anyhow::Error::from)?;
+        if repo.try_find_reference(ref_name.as_ref())?.is_none() {
+            return Err(Error::BranchNotFound(ref_name.shorten().to_string()).into_anyhow());
+        }
         let project_meta = ctx.project_meta()?;
         but_workspace::branch_details(&repo, ref_name.as_ref(), &meta, &project_meta)
     }?;
@@ -573,7 +814,26 @@ pub mod json {
 
 #[cfg(test)]
 mod tests {
-    use super::push_needs_review_sync;
+    use super::{local_branch_ref_name, page, push_needs_review_sync};
+
+    #[test]
+    fn pages_cover_all_items_once() {
+        let items: Vec<_> = (0..5).collect();
+        assert_eq!(page(items.clone(), 0, 2), (vec![0, 1], Some(2)));
+        assert_eq!(page(items.clone(), 2, 2), (vec![2, 3], Some(4)));
+        assert_eq!(page(items.clone(), 4, 2), (vec![4], None));
+        assert_eq!(page(items.clone(), 0, 5), (items.clone(), None));
+        assert_eq!(
+            page(items.clone(), 7, 2),
+            (vec![], None),
+            "cursors past the end yield nothing"
+        );
+        assert_eq!(
+            page(items, 1, 0),
+            (vec![], Some(1)),
+            "empty pages keep the cursor"
+        );
+    }
 
     #[test]
     fn no_op_push_does_not_request_review_synchronization() {
@@ -596,4 +856,32 @@ mod tests {
         };
         assert!(push_needs_review_sync(&pushed));
     }
+
+    #[test]
+    fn branch_names_resolve_to_local_branches() -> anyhow::Result<()> {
+        let (repo, _tmp) = but_testsupport::writable_scenario("checkout-head-info");
+        let feature_id = repo.rev_parse_single("feature")?.detach();
+        repo.reference(
+            "refs/remotes/origin/feature",
+            feature_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "test remote-tracking branch",
+        )?;
+
+        let feature: gix::refs::FullName = "refs/heads/feature".try_into()?;
+        for name in [
+            "feature",
+            "refs/heads/feature",
+            "origin/feature",
+            "refs/remotes/origin/feature",
+        ] {
+            assert_eq!(
+                local_branch_ref_name(&repo, name)?.as_ref(),
+                Some(&feature),
+                "{name} maps to the local branch"
+            );
+        }
+        assert_eq!(local_branch_ref_name(&repo, "does-not-exist")?, None);
+        Ok(())
+    }
 }
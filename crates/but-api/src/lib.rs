@@ -48,6 +48,9 @@ pub mod resolve;
 /// Functions that show what changed in various Git entities, like trees, commits and the worktree.
 pub mod diff;
 
+/// Typed errors of API commands that callers may want to handle specifically.
+pub mod error;
+
 /// Types meant to be serialised to JSON, without degenerating information despite the need to be UTF-8 encodable.
 /// EXPERIMENTAL
 pub mod json;
@@ -5,7 +5,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::WorkspaceState;
+use crate::{WorkspaceState, error};
 use bstr::ByteSlice;
 use but_api_macros::but_api;
 use but_core::{
@@ -175,6 +175,83 @@ pub(crate) fn prune_missing_branch_stack_order(ctx: &but_ctx::Context) -> anyhow
     Ok(())
 }
 
+/// Remove the stacks whose references don't exist anymore from the workspace metadata, and return their names.
+///
+/// This acquires exclusive worktree access from `ctx`. See
+/// [`remove_missing_workspace_stacks_with_perm()`] for details.
+#[instrument(err(Debug))]
+pub fn remove_missing_workspace_stacks(
+    ctx: &mut but_ctx::Context,
+) -> anyhow::Result<Vec<gix::refs::FullName>> {
+    let mut guard = ctx.exclusive_worktree_access();
+    remove_missing_workspace_stacks_with_perm(ctx, guard.write_permission())
+}
+
+/// Remove the stacks whose references don't exist anymore, typically because they were deleted
+/// or renamed outside of GitButler, from the workspace metadata under caller-held exclusive
+/// repository access, and return their names.
+///
+/// The stacks of the workspace metadata are looked up in the workspace graph to learn which are missing,
+/// and [`but_workspace::cleanup_missing_stacks()`] removes them before the cached workspace is rebuilt from the
+/// remaining stacks, which may be none at all. Stacks that merely conflict are left alone.
+/// Nothing changes if the workspace has no reference or no stacks.
+pub fn remove_missing_workspace_stacks_with_perm(
+    ctx: &mut but_ctx::Context,
+    perm: &mut RepoExclusive,
+) -> anyhow::Result<Vec<gix::refs::FullName>> {
+    let mut meta = ctx.meta()?;
+    let removed = {
+        let (_repo, ws, _db) = ctx.workspace_and_db_with_perm(perm.read_permission())?;
+        let Some(ws_ref_name) = ws.ref_name() else {
+            return Ok(Vec::new());
+        };
+        let ws_md = meta.workspace(ws_ref_name)?;
+        let missing_stacks = but_workspace::WorkspaceCommit::tips_from_metadata(
+            ws_md.stacks.iter(),
+            but_workspace::commit::merge::StackFilter::All,
+            None,
+            &ws.graph,
+        )
+        .missing_stacks;
+        but_workspace::cleanup_missing_stacks(&mut meta, ws_ref_name, &missing_stacks)?
+    };
+    if !removed.is_empty() {
+        ctx.invalidate_workspace_cache()?;
+    }
+    Ok(removed)
+}
+
+/// Return the stacks that the workspace commit at `HEAD` claims to merge, as recorded in its message,
+/// or `None` if `HEAD` is unborn or isn't a workspace commit.
+///
+/// This doesn't use the workspace metadata, so it can help to rebuild it if it was lost or corrupted.
+/// See [`but_workspace::WorkspaceCommit::parsed_stacks()`] for details.
+#[instrument(err(Debug))]
+pub fn head_workspace_commit_stacks(
+    ctx: &but_ctx::Context,
+) -> anyhow::Result<Option<Vec<but_workspace::commit::Stack>>> {
+    let repo = ctx.repo.get()?;
+    let Some(head_id) = repo.head()?.id() else {
+        return Ok(None);
+    };
+    let commit = but_workspace::WorkspaceCommit::from_id(head_id)?;
+    Ok(commit.is_managed().then(|| commit.parsed_stacks()))
+}
+
+/// Find the stack of the current workspace that contains `commit_id`, or `None` if no stack contains it.
+///
+/// The location also tells which branch segment owns the commit, and if it is only on the remote tracking
+/// branch of that segment. See [`but_workspace::stack_containing_commit()`] for details.
+#[but_api(napi, json::CommitLocation)]
+#[instrument(err(Debug))]
+pub fn stack_containing_commit(
+    ctx: &but_ctx::Context,
+    commit_id: gix::ObjectId,
+) -> anyhow::Result<Option<but_workspace::CommitLocation>> {
+    let (_guard, _repo, ws, _db) = ctx.workspace_and_db()?;
+    Ok(but_workspace::stack_containing_commit(&ws, commit_id))
+}
+
 /// Return the persisted status of fetches performed through
 /// [`workspace_fetch_from_remotes()`].
 ///
@@ -327,6 +404,45 @@ pub mod json {
         }
     }
 
+    /// JSON transport type for the location of a commit in the workspace.
+    #[derive(Debug, Serialize)]
+    #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
+    #[serde(rename_all = "camelCase")]
+    pub struct CommitLocation {
+        /// The id of the stack containing the commit, or `None` if the workspace isn't managed.
+        #[cfg_attr(
+            feature = "export-schema",
+            schemars(schema_with = "but_schemars::stack_id_opt")
+        )]
+        pub stack_id: Option<but_core::ref_metadata::StackId>,
+        /// The name of the top-most branch of the containing stack, if it has one.
+        pub stack_ref_name: Option<crate::json::FullRefName>,
+        /// The name of the branch owning the commit, if it has one.
+        pub ref_name: Option<crate::json::FullRefName>,
+        /// If `true`, the commit is only on the remote tracking branch of the owning branch.
+        pub upstream_only: bool,
+    }
+
+    #[cfg(feature = "export-schema")]
+    but_schemars::register_sdk_type!(CommitLocation);
+
+    impl From<but_workspace::CommitLocation> for CommitLocation {
+        fn from(value: but_workspace::CommitLocation) -> Self {
+            let but_workspace::CommitLocation {
+                stack_id,
+                stack_ref_name,
+                ref_name,
+                upstream_only,
+            } = value;
+            Self {
+                stack_id,
+                stack_ref_name: stack_ref_name.map(Into::into),
+                ref_name: ref_name.map(Into::into),
+                upstream_only,
+            }
+        }
+    }
+
     /// JSON transport type returned by upstream integration.
     #[derive(Debug, Serialize)]
     #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
@@ -541,6 +657,9 @@ pub fn workspace_integrate_upstream_only_with_perm(
     let (workspace_state, worktree_conflicts) = {
         let (repo, mut ws, db) = ctx.workspace_mut_and_db_with_perm(perm)?;
         let project_meta = ctx.project_meta()?;
+        project_meta
+            .target_ref_or_err()
+            .map_err(error::lift_no_default_target)?;
         let review_hints = match forge_review_integration_hints(&ws, &project_meta, &db) {
             Ok(review_hints) => review_hints,
             Err(err) => {
@@ -575,7 +694,20 @@ pub fn workspace_integrate_upstream_only_with_perm(
             });
         }
 
-        let materialized = rebase.materialize()?;
+        let materialized = rebase.materialize().map_err(|err| {
+            let blocked_by_worktree = !worktree_conflicts.is_empty()
+                && err
+                    .custom_context()
+                    .is_some_and(|context| context.code == but_error::Code::PreconditionFailed);
+            if blocked_by_worktree {
+                error::Error::WorktreeConflict {
+                    paths: worktree_conflicts.iter().map(|p| (**p).clone()).collect(),
+                }
+                .into_anyhow()
+            } else {
+                err
+            }
+        })?;
         project_meta.persist(&repo)?;
 
         if let Some(ref_name) = materialized.workspace.ref_name()
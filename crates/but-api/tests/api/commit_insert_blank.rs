@@ -0,0 +1,73 @@
+use but_core::{DryRun, ref_metadata::ProjectMeta};
+use but_rebase::graph_rebase::mutate::{InsertSide, RelativeTo};
+
+use crate::support::writable_scenario;
+
+fn context_with_two_commits_on_main() -> anyhow::Result<(but_ctx::Context, tempfile::TempDir)> {
+    let (repo, tmp) = writable_scenario("main-two-commits-ahead-of-origin");
+    let target_commit_id = repo.rev_parse_single("refs/remotes/origin/main")?.detach();
+    ProjectMeta {
+        target_ref: Some("refs/remotes/origin/main".try_into()?),
+        target_commit_id: Some(target_commit_id),
+        push_remote: Some("origin".into()),
+    }
+    .persist(&repo)?;
+
+    let ctx = but_ctx::Context::from_repo_for_testing(repo)?.with_memory_app_cache();
+    Ok((ctx, tmp))
+}
+
+#[test]
+fn insert_blank_returns_new_commit_and_rewritten_descendants() -> anyhow::Result<()> {
+    let (mut ctx, _tmp) = context_with_two_commits_on_main()?;
+    let (one, two) = {
+        let repo = ctx.repo.get()?;
+        (
+            repo.rev_parse_single("main~1")?.detach(),
+            repo.rev_parse_single("main")?.detach(),
+        )
+    };
+
+    let result = but_api::commit::insert_blank::commit_insert_blank(
+        &mut ctx,
+        RelativeTo::Commit(two),
+        InsertSide::Below,
+        DryRun::No,
+    )?;
+
+    let repo = ctx.repo.get()?;
+    let new_commit = repo.find_commit(result.new_commit)?;
+    assert_eq!(
+        new_commit
+            .parent_ids()
+            .map(|id| id.detach())
+            .collect::<Vec<_>>(),
+        [one],
+        "the blank commit is inserted right below the commit it was placed relative to"
+    );
+    assert_eq!(
+        new_commit.tree_id()?.detach(),
+        repo.find_commit(one)?.tree_id()?.detach(),
+        "it doesn't change anything"
+    );
+
+    let rewritten_two = *result
+        .workspace
+        .replaced_commits
+        .get(&two)
+        .expect("the descendant of the blank commit was rewritten");
+    assert_eq!(repo.rev_parse_single("main")?.detach(), rewritten_two);
+    assert_eq!(
+        repo.find_commit(rewritten_two)?
+            .parent_ids()
+            .map(|id| id.detach())
+            .collect::<Vec<_>>(),
+        [result.new_commit],
+        "the rewritten descendant sits on top of the blank commit"
+    );
+    assert!(
+        !result.workspace.replaced_commits.contains_key(&one),
+        "commits below the insertion point are untouched"
+    );
+    Ok(())
+}
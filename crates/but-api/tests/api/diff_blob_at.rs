@@ -0,0 +1,70 @@
+use crate::support::writable_scenario;
+
+fn context_with_files() -> anyhow::Result<(but_ctx::Context, gix::ObjectId, tempfile::TempDir)> {
+    let (repo, tmp) = writable_scenario("files-of-various-kinds");
+    let commit_id = repo.rev_parse_single("main")?.detach();
+    let ctx = but_ctx::Context::from_repo_for_testing(repo)?.with_memory_app_cache();
+    Ok((ctx, commit_id, tmp))
+}
+
+#[test]
+fn blob_at_returns_content_and_metadata() -> anyhow::Result<()> {
+    let (ctx, commit_id, _tmp) = context_with_files()?;
+
+    let text = but_api::diff::blob_at(&ctx, commit_id, "dir/text.txt".into(), None)?
+        .expect("the file exists");
+    assert_eq!(text.data, Some("hello\n".into()));
+    assert_eq!(text.size, 6);
+    assert_eq!(text.is_binary, Some(false));
+    assert!(!text.is_executable);
+
+    let script = but_api::diff::blob_at(&ctx, commit_id, "script.sh".into(), None)?
+        .expect("the file exists");
+    assert!(script.is_executable);
+
+    let binary =
+        but_api::diff::blob_at(&ctx, commit_id, "data.bin".into(), None)?.expect("the file exists");
+    assert_eq!(binary.is_binary, Some(true));
+    Ok(())
+}
+
+#[test]
+fn blob_at_only_returns_metadata_for_blobs_above_the_size_limit() -> anyhow::Result<()> {
+    let (ctx, commit_id, _tmp) = context_with_files()?;
+
+    let text = but_api::diff::blob_at(&ctx, commit_id, "dir/text.txt".into(), Some(5))?
+        .expect("the file exists");
+    assert_eq!(text.data, None, "the content exceeds the limit");
+    assert_eq!(text.size, 6, "the size is known nonetheless");
+    assert_eq!(
+        text.is_binary, None,
+        "it's unknown without loading the data"
+    );
+
+    let text = but_api::diff::blob_at(&ctx, commit_id, "dir/text.txt".into(), Some(6))?
+        .expect("the file exists");
+    assert!(text.data.is_some(), "the limit is inclusive");
+    Ok(())
+}
+
+#[test]
+fn blob_at_distinguishes_missing_paths_from_missing_commits() -> anyhow::Result<()> {
+    let (ctx, commit_id, _tmp) = context_with_files()?;
+
+    for missing_path in ["missing.txt", "dir", "dir/text.txt/nested"] {
+        assert_eq!(
+            but_api::diff::blob_at(&ctx, commit_id, missing_path.into(), None)?,
+            None,
+            "{missing_path} isn't a file in the commit"
+        );
+    }
+
+    let missing_commit = gix::ObjectId::from_hex(b"5c69907b1244089142905dba380371728e2e8160")?;
+    let err = but_api::diff::blob_at(&ctx, missing_commit, "dir/text.txt".into(), None)
+        .expect_err("the commit doesn't exist");
+    assert!(matches!(
+        err.downcast_ref::<but_api::error::Error>(),
+        Some(but_api::error::Error::CommitNotFound(id)) if *id == missing_commit
+    ));
+    Ok(())
+}
@@ -15,3 +15,101 @@ fn head_info_uses_context_graph_options() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+mod limited_stack_details {
+    use but_api::legacy::workspace::{branch_commits_page, stack_details, stack_details_limited};
+    use but_core::ref_metadata::ProjectMeta;
+
+    use crate::support::writable_scenario;
+
+    /// `main` has the commits `two` and `one` on top of `origin/main`, which is the target.
+    fn context_with_two_commits_on_main() -> anyhow::Result<(but_ctx::Context, tempfile::TempDir)> {
+        let (repo, tmp) = writable_scenario("main-two-commits-ahead-of-origin");
+        let target_commit_id = repo.rev_parse_single("refs/remotes/origin/main")?.detach();
+        ProjectMeta {
+            target_ref: Some("refs/remotes/origin/main".try_into()?),
+            target_commit_id: Some(target_commit_id),
+            push_remote: Some("origin".into()),
+        }
+        .persist(&repo)?;
+        let ctx = but_ctx::Context::from_repo_for_testing(repo)?.with_memory_app_cache();
+        Ok((ctx, tmp))
+    }
+
+    fn commit_ids(commits: &[but_workspace::ui::Commit]) -> Vec<gix::ObjectId> {
+        commits.iter().map(|commit| commit.id).collect()
+    }
+
+    #[test]
+    fn commits_past_the_limit_are_left_out() -> anyhow::Result<()> {
+        let (ctx, _tmp) = context_with_two_commits_on_main()?;
+        let (one, two) = {
+            let repo = ctx.repo.get()?;
+            (
+                repo.rev_parse_single("main~1")?.detach(),
+                repo.rev_parse_single("main")?.detach(),
+            )
+        };
+
+        let all = stack_details(&ctx, None)?;
+        assert_eq!(
+            commit_ids(&all.branch_details[0].commits),
+            [two, one],
+            "without a limit, all commits are listed"
+        );
+
+        let limited = stack_details_limited(&ctx, None, 1)?;
+        assert_eq!(limited.branch_details.len(), 1);
+        let branch = &limited.branch_details[0];
+        assert_eq!(
+            commit_ids(&branch.details.commits),
+            [two],
+            "the tip comes first"
+        );
+        assert!(branch.has_more, "`one` was left out");
+
+        let limited = stack_details_limited(&ctx, None, 2)?;
+        let branch = &limited.branch_details[0];
+        assert_eq!(commit_ids(&branch.details.commits), [two, one]);
+        assert!(
+            !branch.has_more,
+            "a limit that matches the amount of commits leaves nothing out"
+        );
+        assert_eq!(branch.details.name, all.branch_details[0].name);
+        assert_eq!(limited.derived_name, all.derived_name);
+        Ok(())
+    }
+
+    #[test]
+    fn pages_continue_where_the_previous_one_ended() -> anyhow::Result<()> {
+        let (ctx, _tmp) = context_with_two_commits_on_main()?;
+        let (one, two) = {
+            let repo = ctx.repo.get()?;
+            (
+                repo.rev_parse_single("main~1")?.detach(),
+                repo.rev_parse_single("main")?.detach(),
+            )
+        };
+
+        let first = branch_commits_page(&ctx, None, "main".into(), 0, 1)?;
+        assert_eq!(commit_ids(&first.commits), [two]);
+        assert_eq!(first.next_cursor, Some(1));
+
+        let last = branch_commits_page(&ctx, None, "main".into(), 1, 1)?;
+        assert_eq!(commit_ids(&last.commits), [one]);
+        assert_eq!(
+            last.next_cursor, None,
+            "there is no page after the last one"
+        );
+
+        let past_the_end = branch_commits_page(&ctx, None, "main".into(), 2, 1)?;
+        assert!(past_the_end.commits.is_empty());
+        assert_eq!(past_the_end.next_cursor, None);
+
+        assert!(
+            branch_commits_page(&ctx, None, "unknown".into(), 0, 1).is_err(),
+            "branches that aren't in the stack can't be paged"
+        );
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ mod branch_move;
 mod branch_remove;
 mod branch_rename;
 mod commit_cherry_pick;
+mod commit_insert_blank;
+mod diff_blob_at;
 #[cfg(feature = "legacy")]
 mod forge_info;
 #[cfg(all(feature = "legacy", not(feature = "graph-workspace")))]
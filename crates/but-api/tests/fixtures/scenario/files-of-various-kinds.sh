@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+git init -b main
+echo "a single commit with a nested text file, an executable and a binary file" >.git/description
+
+git config user.name GitButler
+git config user.email gitbutler@example.com
+
+mkdir dir
+echo hello >dir/text.txt
+echo '#!/bin/sh' >script.sh
+printf '\0\001binary' >data.bin
+git add .
+git update-index --chmod=+x script.sh
+GIT_COMMITTER_DATE="1675176957 +0100" GIT_AUTHOR_DATE="1675176957 +0100" git commit -m "files"
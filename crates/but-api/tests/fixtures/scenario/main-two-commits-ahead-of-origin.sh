@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+function tick() {
+  if test -z "${tick+set}"; then
+    tick=1675176957
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick +0100"
+  GIT_AUTHOR_DATE="$tick +0100"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit_file() {
+  local name="${1:?First argument is the filename}"
+  local content="${2:?Second argument is the content}"
+  local message="${3:?Third argument is the commit message}"
+  tick
+  echo "$content" >"$name"
+  git add "$name"
+  git commit -m "$message"
+}
+
+git init -b main
+echo "main with two commits on top of origin/main" >.git/description
+
+git config user.name GitButler
+git config user.email gitbutler@example.com
+
+commit_file base.txt base "base"
+
+mkdir -p .git/refs/remotes/origin
+cp .git/refs/heads/main .git/refs/remotes/origin/main
+
+cat <<EOF >>.git/config
+[remote "origin"]
+	url = ./fake/local/path/which-is-fine-as-we-dont-fetch-or-push
+	fetch = +refs/heads/*:refs/remotes/origin/*
+EOF
+
+commit_file one.txt one "one"
+commit_file two.txt two "two"
@@ -1,20 +1,95 @@
 use std::collections::BTreeSet;
 
 use anyhow::Context;
-use bstr::{BString, ByteSlice, ByteVec};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use gix::refs;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{RepositoryExt, branch::normalize_short_name, extract_remote_name_and_short_name};
 
-/// Produce a branch named after the author configured in `repo`, or default it to a generic name
+/// The git configuration key for the template that [`canned_refname()`] expands to produce branch names.
+///
+/// The following placeholders are supported:
+///
+/// * `{name}` - the name that would be generated without a template, like `jd-branch-1`.
+/// * `{user}` - the part of the author email before the `@`.
+/// * `{date}` - the current date as `YYYY-MM-DD`.
+///
+/// For example, `users/{user}/{name}` produces names like `users/jane.doe/jd-branch-1`.
+pub const BRANCH_NAME_TEMPLATE_KEY: &str = "gitbutler.branchNameTemplate";
+
+/// Produce a branch named after the author configured in `repo`, or default it to a generic name.
+///
+/// If [`BRANCH_NAME_TEMPLATE_KEY`] is configured in `repo`, the name is produced by expanding the template instead.
 pub fn canned_refname(repo: &gix::Repository) -> anyhow::Result<gix::refs::FullName> {
-    let short_name = match repo.commit_signatures().map(|(author, _committer)| author) {
-        Ok(author) => generate_short_name_from_signature(&author)?,
-        Err(_) => "branch-1".into(),
+    let author = repo
+        .commit_signatures()
+        .map(|(author, _committer)| author)
+        .ok();
+    let name = match &author {
+        Some(author) => generate_short_name_from_signature(author)?,
+        None => "branch-1".into(),
     };
 
-    Ok(refs::Category::LocalBranch.to_full_name(short_name.as_bstr())?)
+    let config = repo.config_snapshot();
+    let Some(template) = config
+        .string(BRANCH_NAME_TEMPLATE_KEY)
+        .filter(|template| !template.trim().is_empty())
+    else {
+        return Ok(refs::Category::LocalBranch.to_full_name(name.as_bstr())?);
+    };
+    let short_name = expand_template(template.as_bstr(), name.as_bstr(), author.as_ref())?;
+    refs::Category::LocalBranch
+        .to_full_name(short_name.as_bstr())
+        .with_context(|| {
+            format!(
+                "{BRANCH_NAME_TEMPLATE_KEY} = '{template}' produced the invalid branch name '{short_name}'"
+            )
+        })
+}
+
+/// Substitute all placeholders in `template`, see [`BRANCH_NAME_TEMPLATE_KEY`] for details.
+fn expand_template(
+    template: &BStr,
+    name: &BStr,
+    author: Option<&gix::actor::Signature>,
+) -> anyhow::Result<BString> {
+    let mut out = BString::default();
+    let mut rest = template;
+    while let Some(start) = rest.find_byte(b'{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find_byte(b'}').with_context(|| {
+            format!("{BRANCH_NAME_TEMPLATE_KEY} = '{template}' has an unclosed placeholder")
+        })?;
+        let placeholder = &rest[start + 1..start + end];
+        match placeholder.as_bytes() {
+            b"name" => out.push_str(name),
+            b"user" => {
+                let author = author.with_context(|| {
+                    format!("{BRANCH_NAME_TEMPLATE_KEY} uses {{user}}, but no author is configured")
+                })?;
+                let user = author
+                    .email
+                    .split_once_str("@")
+                    .map_or(author.email.as_bstr(), |(user, _domain)| user.as_bstr());
+                let user = if user.is_empty() {
+                    author.name.as_bstr()
+                } else {
+                    user
+                };
+                out.push_str(normalize_short_name(user)?);
+            }
+            b"date" => out.push_str(
+                gix::date::Time::now_local_or_utc().format_or_unix(gix::date::time::format::SHORT),
+            ),
+            _ => anyhow::bail!(
+                "{BRANCH_NAME_TEMPLATE_KEY} = '{template}' uses the unknown placeholder '{{{placeholder}}}'"
+            ),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
 }
 
 /// Like [`canned_refname`], but produces a unique name that at the time of return form this function
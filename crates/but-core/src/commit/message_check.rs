@@ -0,0 +1,176 @@
+//! Checks for the shape of commit messages, like the length of the subject line.
+//!
+//! These only produce [problems](Problem) to show to the user, and never reject a message.
+use bstr::{BStr, ByteSlice};
+
+/// The conventions a commit message is checked against.
+///
+/// A limit of `0` disables the respective check.
+/// The default is a subject of at most 72 characters, followed by an empty line and body lines of at most 100 characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// The maximum amount of characters in the subject line.
+    pub subject_max_len: usize,
+    /// The maximum amount of characters in each line of the body.
+    pub body_max_line_len: usize,
+    /// If `true`, the subject must be followed by an empty line if there is more than a subject.
+    pub require_blank_second_line: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            subject_max_len: 72,
+            body_max_line_len: 100,
+            require_blank_second_line: true,
+        }
+    }
+}
+
+impl Rules {
+    /// Rules which don't check anything.
+    pub const DISABLED: Rules = Rules {
+        subject_max_len: 0,
+        body_max_line_len: 0,
+        require_blank_second_line: false,
+    };
+
+    /// Return `true` if no check is enabled.
+    pub fn is_disabled(&self) -> bool {
+        *self == Rules::DISABLED
+    }
+}
+
+/// A way in which a commit message doesn't follow the [`Rules`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The subject line has `len` characters, more than `max`.
+    SubjectTooLong {
+        /// The amount of characters in the subject.
+        len: usize,
+        /// The configured maximum.
+        max: usize,
+    },
+    /// The line after the subject isn't empty.
+    MissingBlankSecondLine,
+    /// The body line with the one-based `line_number` within the message has `len` characters, more than `max`.
+    BodyLineTooLong {
+        /// The one-based number of the offending line within the message.
+        line_number: usize,
+        /// The amount of characters in the line.
+        len: usize,
+        /// The configured maximum.
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::SubjectTooLong { len, max } => {
+                write!(f, "The subject has {len} characters, more than {max}")
+            }
+            Problem::MissingBlankSecondLine => {
+                write!(f, "The subject should be followed by an empty line")
+            }
+            Problem::BodyLineTooLong {
+                line_number,
+                len,
+                max,
+            } => write!(
+                f,
+                "Line {line_number} has {len} characters, more than {max}"
+            ),
+        }
+    }
+}
+
+/// Check `message` against `rules` and return all problems in order of appearance.
+///
+/// Lines starting with `#` are considered comments and are ignored, just like a trailing block of
+/// trailers such as `Signed-off-by: …`.
+/// Body lines without any whitespace, like long URLs, can't be wrapped and are ignored as well.
+pub fn check(message: &BStr, rules: Rules) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if rules.is_disabled() {
+        return problems;
+    }
+
+    let lines: Vec<(usize, &BStr)> = message
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_end().as_bstr()))
+        .filter(|(_, line)| !line.starts_with(b"#"))
+        .collect();
+    let Some(subject_idx) = lines.iter().position(|(_, line)| !line.is_empty()) else {
+        return problems;
+    };
+    let lines = &lines[subject_idx..];
+    let body_end = lines.len() - trailer_block_len(lines);
+
+    let subject_len = char_len(lines[0].1);
+    if rules.subject_max_len != 0 && subject_len > rules.subject_max_len {
+        problems.push(Problem::SubjectTooLong {
+            len: subject_len,
+            max: rules.subject_max_len,
+        });
+    }
+
+    if rules.require_blank_second_line && lines.get(1).is_some_and(|(_, line)| !line.is_empty()) {
+        problems.push(Problem::MissingBlankSecondLine);
+    }
+
+    if rules.body_max_line_len != 0 {
+        for &(line_number, line) in lines.get(1..body_end).unwrap_or_default() {
+            let len = char_len(line);
+            if len > rules.body_max_line_len && line.trim().contains_str(" ") {
+                problems.push(Problem::BodyLineTooLong {
+                    line_number,
+                    len,
+                    max: rules.body_max_line_len,
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// Return the amount of lines at the end of `lines` which form a block of trailers, i.e. the last
+/// paragraph if it's separated from the subject by an empty line and all of its lines are trailers.
+fn trailer_block_len(lines: &[(usize, &BStr)]) -> usize {
+    let content_end = lines
+        .iter()
+        .rposition(|(_, line)| !line.is_empty())
+        .map_or(0, |pos| pos + 1);
+    let Some(paragraph_start) = lines[..content_end]
+        .iter()
+        .rposition(|(_, line)| line.is_empty())
+        .map(|pos| pos + 1)
+    else {
+        return 0;
+    };
+    let paragraph = &lines[paragraph_start..content_end];
+    let is_trailer_block = paragraph.first().is_some_and(|(_, line)| is_trailer(line))
+        && paragraph
+            .iter()
+            .all(|(_, line)| is_trailer(line) || line.starts_with(b" ") || line.starts_with(b"\t"));
+    if is_trailer_block {
+        lines.len() - paragraph_start
+    } else {
+        0
+    }
+}
+
+/// A trailer looks like `Token: value`, where the token consists of alphanumerics and dashes.
+fn is_trailer(line: &BStr) -> bool {
+    line.split_once_str(":").is_some_and(|(token, _value)| {
+        !token.is_empty()
+            && token
+                .iter()
+                .all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+    })
+}
+
+fn char_len(line: &BStr) -> usize {
+    line.chars().count()
+}
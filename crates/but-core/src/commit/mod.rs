@@ -818,6 +818,7 @@ pub use conflict::{
     add_conflict_markers, is_conflicted, message_is_conflicted,
     rewrite_conflict_markers_on_message_change, strip_conflict_markers,
 };
+pub mod message_check;
 pub mod tree_expression;
 
 /// Write a GitButler conflicted tree that wraps `resolved_tree_id` together
@@ -34,9 +34,12 @@ enum Origin {
 ///
 /// It's equivalent to a `git status` which is "boiled down" into all the changes that one would have to add into `HEAD^{tree}`
 /// to get a commit with a tree equal to the current worktree.
+///
+/// Renames are tracked as configured with `diff.renames` and `diff.renameLimit`, and are on by default like in Git.
+/// Copies are never tracked.
 #[instrument(skip(repo), err(Debug))]
 pub fn worktree_changes(repo: &gix::Repository) -> anyhow::Result<WorktreeChanges> {
-    worktree_changes_inner(repo, RenameTracking::Always)
+    worktree_changes_inner(repo, RenameTracking::AsConfigured)
 }
 
 /// Just like [`worktree_changes()`], but don't do any rename tracking for performance.
@@ -46,24 +49,34 @@ pub fn worktree_changes_no_renames(repo: &gix::Repository) -> anyhow::Result<Wor
 }
 
 enum RenameTracking {
-    Always,
+    AsConfigured,
     Disabled,
 }
 
+/// Return the rename tracking configured with `diff.renames` and `diff.renameLimit` in `repo`,
+/// or `None` if renames shouldn't be tracked.
+fn configured_rewrites(repo: &gix::Repository) -> anyhow::Result<Option<gix::diff::Rewrites>> {
+    let (rewrites, _is_configured) =
+        gix::diff::new_rewrites(repo.config_snapshot().plumbing(), true)?;
+    // Copy tracking needs specific support wherever 'previous_path()' is called, so it's
+    // ignored even if `diff.renames = copies` asks for it.
+    Ok(rewrites.map(|rewrites| gix::diff::Rewrites {
+        copies: None,
+        ..rewrites
+    }))
+}
+
 fn worktree_changes_inner(
     repo: &gix::Repository,
     renames: RenameTracking,
 ) -> anyhow::Result<WorktreeChanges> {
-    let (tree_index_rewrites, worktree_rewrites) = match renames {
-        RenameTracking::Always => {
-            let rewrites = gix::diff::Rewrites::default(); /* standard Git rewrite handling for everything */
-            debug_assert!(
-                rewrites.copies.is_none(),
-                "TODO: copy tracking needs specific support wherever 'previous_path()' is called."
-            );
-            (TrackRenames::Given(rewrites), Some(rewrites))
-        }
-        RenameTracking::Disabled => (TrackRenames::Disabled, None),
+    let rewrites = match renames {
+        RenameTracking::AsConfigured => configured_rewrites(repo)?,
+        RenameTracking::Disabled => None,
+    };
+    let (tree_index_rewrites, worktree_rewrites) = match rewrites {
+        Some(rewrites) => (TrackRenames::Given(rewrites), Some(rewrites)),
+        None => (TrackRenames::Disabled, None),
     };
     let has_submodule_ignore_configuration = repo.modules()?.is_some_and(|modules| {
         modules
@@ -481,7 +481,8 @@ impl ProjectMeta {
             git_config::set_config_value(config, PROJECT_PORTED_META, "true")?;
             Ok(())
         })?;
-        if changed && let Ok(storage_path) = but_project_handle::gitbutler_storage_path(repo) {
+        if changed && let Ok(storage_path) = but_project_handle::gitbutler_shared_storage_path(repo)
+        {
             but_project_handle::write_refresh_sentinel(&storage_path.join("virtual_branches.toml"));
         }
         Ok(())
@@ -659,6 +660,10 @@ pub struct Branch {
     pub ref_info: RefInfo,
     /// Information about possibly ongoing reviews in various forges.
     pub review: Review,
+    /// A free-form description of what the branch is about, as written by the user.
+    ///
+    /// It's used as starting point for the body of reviews created for the branch.
+    pub description: Option<String>,
 }
 #[cfg(feature = "export-schema")]
 but_schemars::register_sdk_type!(Branch);
@@ -695,6 +700,9 @@ impl std::fmt::Debug for Branch {
             d.field("ref_info", &self.ref_info)
                 .field("review", &self.review);
         }
+        if let Some(description) = &self.description {
+            d.field("description", description);
+        }
         d.finish()
     }
 }
@@ -1,7 +1,7 @@
 use std::{borrow::Cow, collections::BTreeMap};
 
 use anyhow::bail;
-use bstr::ByteSlice;
+use bstr::{BStr, ByteSlice};
 use gix::{merge::tree::TreatAsUnresolved, object::tree::EntryKind, prelude::ObjectIdExt};
 
 use crate::{DiffSpec, HunkHeader, HunkRange, RepositoryExt, UnifiedPatch, apply_hunks};
@@ -37,7 +37,7 @@ pub mod create_tree {
         /// A change with multiple hunks to be applied wasn't present in the base-tree.
         /// Previously this was possible when untracked files were added with their single hunk specified, but now this shouldn't be happening anymore.
         PathNotFoundInBaseTree,
-        /// There was a change, but the path pointed to something that wasn't a file or a link.
+        /// There was a change, but the path pointed to something that wasn't a file, a link or a repository with a checked-out commit.
         /// You would see this if also in case of submodules or repositories to be added with hunks, which shouldn't be easy to do accidentally even.
         UnsupportedDirectoryEntry,
         /// The base version of a file to apply worktree changes to as present in a Git tree had an undiffable entry type.
@@ -174,6 +174,25 @@ fn into_err_spec(input: &mut PossibleChange, reason: RejectionReason) {
     };
 }
 
+/// Return the commit checked out in the submodule at `path`, or `None` if `rela_path` isn't a submodule
+/// registered in `.gitmodules` of `repo`, there is no repository, or it has no commit yet.
+fn submodule_head_id(
+    repo: &gix::Repository,
+    rela_path: &BStr,
+    path: &std::path::Path,
+) -> anyhow::Result<Option<gix::ObjectId>> {
+    let is_registered = repo.submodules()?.is_some_and(|mut submodules| {
+        submodules.any(|sm| sm.path().is_ok_and(|sm_path| sm_path.as_ref() == rela_path))
+    });
+    if !is_registered {
+        return Ok(None);
+    }
+    let Ok(submodule_repo) = gix::open_opts(path, gix::open::Options::isolated()) else {
+        return Ok(None);
+    };
+    Ok(submodule_repo.head_id().ok().map(gix::Id::detach))
+}
+
 /// A utility type to keep track of `Ok` specs to use, or `Err` specs that have been rejected.
 pub type PossibleChange = Result<DiffSpec, (RejectionReason, DiffSpec)>;
 
@@ -226,6 +245,19 @@ pub fn apply_worktree_changes<'repo>(
         };
         if change_request.hunk_headers.is_empty() {
             let rela_path = change_request.path.as_bstr();
+            if md.is_dir() {
+                // Directories can only be committed if they are registered submodules, which are committed
+                // as the commit they have checked out. Other nested repositories are rejected.
+                match submodule_head_id(repo, rela_path, &path)? {
+                    Some(id) => {
+                        base_tree_editor.upsert(rela_path, EntryKind::Commit, id)?;
+                    }
+                    None => {
+                        into_err_spec(possible_change, RejectionReason::UnsupportedDirectoryEntry)
+                    }
+                }
+                continue;
+            }
             match pipeline.worktree_file_to_object(rela_path, &index)? {
                 Some((id, kind, _fs_metadata)) => {
                     base_tree_editor.upsert(rela_path, kind, id)?;
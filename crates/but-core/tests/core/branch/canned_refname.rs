@@ -1,5 +1,7 @@
-use but_core::branch::canned_refname;
-use but_testsupport::read_only_in_memory_scenario;
+use bstr::ByteSlice;
+use but_core::branch::{BRANCH_NAME_TEMPLATE_KEY, canned_refname, unique_canned_refname};
+use but_testsupport::{read_only_in_memory_scenario, read_only_in_memory_scenario_named_with_post};
+use gix::refs::{self, transaction::PreviousValue};
 
 #[test]
 fn latin_ascii() -> anyhow::Result<()> {
@@ -90,6 +92,86 @@ fn no_author_configured() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn template_with_only_the_name_is_the_default() -> anyhow::Result<()> {
+    let mut repo = read_only_in_memory_scenario("unborn-empty")?;
+    let default_name = canned_refname(&repo)?;
+    set_template(&mut repo, "{name}")?;
+    assert_eq!(canned_refname(&repo)?, default_name);
+    Ok(())
+}
+
+#[test]
+fn template_with_user_and_name() -> anyhow::Result<()> {
+    let mut repo = read_only_in_memory_scenario("unborn-empty")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("author.email", "jane.doe@example.com")?;
+    set_template(&mut repo, "users/{user}/{name}")?;
+    assert_eq!(
+        canned_refname(&repo)?.shorten(),
+        "users/jane.doe/amo-branch-1",
+        "the user is the local part of the email"
+    );
+    Ok(())
+}
+
+#[test]
+fn template_with_date() -> anyhow::Result<()> {
+    let mut repo = read_only_in_memory_scenario("unborn-empty")?;
+    set_template(&mut repo, "{date}/{name}")?;
+    let name = canned_refname(&repo)?;
+    let (date, name) = name
+        .shorten()
+        .split_once_str("/")
+        .expect("the template has a slash");
+    assert_eq!(name.as_bstr(), "amo-branch-1");
+    assert_eq!(date.len(), "YYYY-MM-DD".len(), "{}", date.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn template_errors() -> anyhow::Result<()> {
+    let mut repo = read_only_in_memory_scenario("unborn-empty")?;
+    set_template(&mut repo, "{team}/{name}")?;
+    assert_eq!(
+        canned_refname(&repo).unwrap_err().to_string(),
+        "gitbutler.branchNameTemplate = '{team}/{name}' uses the unknown placeholder '{team}'"
+    );
+
+    set_template(&mut repo, "{name}..lock")?;
+    assert!(
+        canned_refname(&repo).is_err(),
+        "the expanded template is validated as a branch name"
+    );
+    Ok(())
+}
+
+#[test]
+fn unique_templated_name_gets_a_counter() -> anyhow::Result<()> {
+    let (mut repo, _) =
+        read_only_in_memory_scenario_named_with_post("unborn-empty", "", 1, |fixture| {
+            let repo = but_testsupport::open_repo(fixture.path())?;
+            let existing = refs::Category::LocalBranch
+                .to_full_name("team/amo-branch-1".as_bytes().as_bstr())?;
+            repo.reference(
+                existing.as_ref(),
+                repo.object_hash().null(),
+                PreviousValue::Any,
+                "test",
+            )?;
+            Ok(())
+        })?;
+    set_template(&mut repo, "team/{name}")?;
+    assert_eq!(unique_canned_refname(&repo)?.shorten(), "team/amo-branch-2");
+    Ok(())
+}
+
+fn set_template(repo: &mut gix::Repository, template: &str) -> anyhow::Result<()> {
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(BRANCH_NAME_TEMPLATE_KEY, template)?;
+    Ok(())
+}
+
 fn set_author(repo: &mut gix::Repository, name: &str) -> anyhow::Result<()> {
     let mut config = repo.config_snapshot_mut();
     config.set_raw_value("author.name", name)?;
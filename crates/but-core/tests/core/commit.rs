@@ -456,3 +456,185 @@ mod create {
         Ok(commit)
     }
 }
+
+mod message_check {
+    use but_core::commit::message_check::{Problem, Rules, check};
+
+    const RULES: Rules = Rules {
+        subject_max_len: 20,
+        body_max_line_len: 30,
+        require_blank_second_line: true,
+    };
+
+    #[test]
+    fn conforming_messages_have_no_problems() {
+        for message in [
+            "",
+            "\n\n",
+            "short subject",
+            "short subject\n",
+            "short subject\n\nbody that fits in a line\nand another one\n",
+        ] {
+            assert_eq!(
+                check(message.into(), RULES),
+                Vec::<Problem>::new(),
+                "{message:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_rules_never_find_problems() {
+        let message =
+            "a subject which is far too long\nno blank line and a body line which is too long";
+        assert_eq!(
+            check(message.into(), Rules::DISABLED),
+            Vec::<Problem>::new()
+        );
+    }
+
+    #[test]
+    fn default_rules_follow_common_conventions() {
+        let subject = "s".repeat(72);
+        let body_line = "b ".repeat(50);
+        let message = format!("{subject}\n\n{body_line}\n");
+        assert_eq!(
+            check(message.as_str().into(), Rules::default()),
+            Vec::<Problem>::new()
+        );
+
+        let message = format!("{subject}s\n{body_line}b\n");
+        assert_eq!(
+            check(message.as_str().into(), Rules::default()),
+            [
+                Problem::SubjectTooLong { len: 73, max: 72 },
+                Problem::MissingBlankSecondLine,
+                Problem::BodyLineTooLong {
+                    line_number: 2,
+                    len: 101,
+                    max: 100
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn subject_length_counts_characters() {
+        assert_eq!(
+            check("äöü äöü äöü äöü äöü ä".into(), RULES),
+            [Problem::SubjectTooLong { len: 21, max: 20 }]
+        );
+        assert_eq!(
+            check("äöü äöü äöü äöü äöü".into(), RULES),
+            Vec::<Problem>::new(),
+            "multi-byte characters count once"
+        );
+        assert_eq!(
+            check("\n\nsubject after blank lines is long".into(), RULES),
+            [Problem::SubjectTooLong { len: 33, max: 20 }],
+            "leading empty lines are skipped"
+        );
+    }
+
+    #[test]
+    fn missing_blank_second_line() {
+        assert_eq!(
+            check("subject\nbody".into(), RULES),
+            [Problem::MissingBlankSecondLine]
+        );
+        assert_eq!(
+            check(
+                "subject\nbody".into(),
+                Rules {
+                    require_blank_second_line: false,
+                    ..RULES
+                }
+            ),
+            Vec::<Problem>::new()
+        );
+    }
+
+    #[test]
+    fn long_body_lines_are_reported_with_their_line_number() {
+        assert_eq!(
+            check(
+                "subject\n\nshort\nthis line of the body is much too long\nshort again\n".into(),
+                RULES
+            ),
+            [Problem::BodyLineTooLong {
+                line_number: 4,
+                len: 38,
+                max: 30
+            }]
+        );
+    }
+
+    #[test]
+    fn unbreakable_body_lines_are_ignored() {
+        assert_eq!(
+            check(
+                "subject\n\nSee:\n  https://example.com/a/very/long/path/that/cannot/wrap\n".into(),
+                RULES
+            ),
+            Vec::<Problem>::new()
+        );
+    }
+
+    #[test]
+    fn comment_lines_are_ignored() {
+        assert_eq!(
+            check(
+                "# a comment line which is longer than any of the limits\nsubject\n\n# another comment line which is too long\nbody\n"
+                    .into(),
+                RULES
+            ),
+            Vec::<Problem>::new()
+        );
+    }
+
+    #[test]
+    fn trailer_blocks_are_ignored() {
+        let message = "subject\n\nbody\n\nSigned-off-by: Some Person With A Long Name <person@example.com>\nCo-authored-by: Another Person <another.person@example.com>\n";
+        assert_eq!(check(message.into(), RULES), Vec::<Problem>::new());
+
+        let message = "subject\n\nbody\n\nNot a trailer because of the spaces: Some Person With A Long Name\n";
+        assert_eq!(
+            check(message.into(), RULES),
+            [Problem::BodyLineTooLong {
+                line_number: 5,
+                len: 65,
+                max: 30
+            }],
+            "paragraphs which aren't all trailers are checked"
+        );
+
+        let message =
+            "subject\n\nChange-Id: I0123456789abcdef0123456789abcdef01234567 which is long\n";
+        assert_eq!(
+            check(message.into(), RULES),
+            Vec::<Problem>::new(),
+            "the last paragraph may be the trailer block even without a body"
+        );
+    }
+
+    #[test]
+    fn problems_are_displayed_for_humans() {
+        assert_eq!(
+            Problem::SubjectTooLong { len: 80, max: 72 }.to_string(),
+            "The subject has 80 characters, more than 72"
+        );
+        assert_eq!(
+            Problem::MissingBlankSecondLine.to_string(),
+            "The subject should be followed by an empty line"
+        );
+        assert_eq!(
+            Problem::BodyLineTooLong {
+                line_number: 3,
+                len: 101,
+                max: 100
+            }
+            .to_string(),
+            "Line 3 has 101 characters, more than 100"
+        );
+    }
+}
@@ -1452,6 +1452,27 @@ WorktreeChanges {
     Ok(())
 }
 
+#[test]
+fn renamed_in_worktree_without_rename_tracking() -> Result<()> {
+    let mut repo = repo("renamed-in-worktree")?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Diff::RENAMES, "false")?;
+    let actual = diff::worktree_changes(&repo)?;
+    assert_eq!(
+        actual
+            .changes
+            .iter()
+            .map(|change| (change.path.to_string(), change.previous_path().is_some()))
+            .collect::<Vec<_>>(),
+        [
+            ("new-name".to_string(), false),
+            ("to-be-renamed".to_string(), false)
+        ],
+        "`diff.renames = false` turns the rename into an addition and a deletion"
+    );
+    Ok(())
+}
+
 #[test]
 fn renamed_in_worktree_with_executable_bit() -> Result<()> {
     let repo = repo("renamed-in-worktree-with-executable-bit")?;
@@ -1,5 +1,5 @@
 use but_core::DiffSpec;
-use but_testsupport::writable_scenario;
+use but_testsupport::{CommandExt as _, git, open_repo, writable_scenario};
 use gix::object::tree::EntryKind;
 
 /// Regression test for data loss when a file is renamed and a *new directory* is committed at the
@@ -64,6 +64,71 @@ fn rename_with_new_directory_at_old_path_keeps_directory() -> anyhow::Result<()>
     Ok(())
 }
 
+#[test]
+fn registered_submodule_is_committed_as_its_checked_out_commit() -> anyhow::Result<()> {
+    let (repo, _tmp) = writable_scenario("unborn-empty");
+    let work_dir = repo.workdir().expect("non-bare repo").to_owned();
+    let submodule_head = init_nested_repo_with_commit(&work_dir.join("sub"))?;
+    std::fs::write(
+        work_dir.join(".gitmodules"),
+        "[submodule \"sub\"]\n\tpath = sub\n\turl = ./sub\n",
+    )?;
+
+    let base_tree = repo.empty_tree().id;
+    let mut changes = vec![Ok(spec(None, "sub"))];
+    let (new_tree, _base) =
+        but_core::tree::apply_worktree_changes(base_tree, &repo, &mut changes, 0)?;
+
+    assert!(
+        changes.iter().all(|c| c.is_ok()),
+        "the submodule should be accepted: {changes:?}"
+    );
+    let tree = new_tree.object()?.into_tree();
+    let sub = tree
+        .lookup_entry_by_path("sub")?
+        .expect("the submodule is part of the tree");
+    assert_eq!(sub.mode().kind(), EntryKind::Commit, "it's a gitlink");
+    assert_eq!(sub.object_id(), submodule_head);
+    Ok(())
+}
+
+#[test]
+fn unregistered_nested_repository_is_rejected() -> anyhow::Result<()> {
+    let (repo, _tmp) = writable_scenario("unborn-empty");
+    let work_dir = repo.workdir().expect("non-bare repo").to_owned();
+    init_nested_repo_with_commit(&work_dir.join("nested"))?;
+
+    let base_tree = repo.empty_tree().id;
+    let mut changes = vec![Ok(spec(None, "nested"))];
+    let (new_tree, _base) =
+        but_core::tree::apply_worktree_changes(base_tree, &repo, &mut changes, 0)?;
+
+    assert!(
+        matches!(
+            changes[0],
+            Err((
+                but_core::tree::create_tree::RejectionReason::UnsupportedDirectoryEntry,
+                _
+            ))
+        ),
+        "nested repositories that aren't submodules must not become gitlinks: {changes:?}"
+    );
+    assert_eq!(
+        new_tree.detach(),
+        base_tree,
+        "nothing was added to the tree"
+    );
+    Ok(())
+}
+
+fn init_nested_repo_with_commit(path: &std::path::Path) -> anyhow::Result<gix::ObjectId> {
+    let nested = gix::init(path)?;
+    git(&nested)
+        .args(["commit", "--allow-empty", "-m", "init"])
+        .run();
+    Ok(open_repo(path)?.head_id()?.detach())
+}
+
 fn spec(previous_path: Option<&str>, path: &str) -> DiffSpec {
     DiffSpec {
         previous_path: previous_path.map(Into::into),
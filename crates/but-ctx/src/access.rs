@@ -21,7 +21,7 @@ impl Context {
     /// Return a guard for exclusive (read+write) worktree access, blocking while waiting for
     /// someone else in the same process to release it, or for all readers to disappear.
     /// Locking is fair within this process.
-    /// When `project_data_dir` is available we also attempt to obtain a best-effort
+    /// When `shared_project_data_dir` is available we also attempt to obtain a best-effort
     /// inter-process file lock, but failures to create, open, or lock that file are logged and
     /// ignored, so the hard guarantee remains in-process exclusivity only.
     /// If this process inherits Git's commit-hook environment (`GIT_EDITOR=:` together with
@@ -32,7 +32,7 @@ impl Context {
     ///
     /// # IMPORTANT: KEEP THE GUARD ALIVE!
     pub fn exclusive_worktree_access(&mut self) -> RepoExclusiveGuard {
-        but_core::sync::exclusive_repo_access(&self.gitdir, Some(&self.project_data_dir))
+        but_core::sync::exclusive_repo_access(&self.gitdir, Some(&self.shared_project_data_dir))
     }
 
     /// Return a guard for shared (read) worktree access, and block while waiting for writers to disappear.
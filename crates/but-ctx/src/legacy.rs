@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use but_core::{RepositoryExt, sync::RepoExclusive};
 use but_settings::AppSettings;
 use tracing::instrument;
@@ -35,24 +37,41 @@ impl Context {
 
     /// Open the repository identified by `legacy_project` and `settings`, while controlling
     /// how the repository sources configuration via `repo_open_mode`.
+    pub fn new_from_legacy_project_and_settings_with_repo_open_mode(
+        legacy_project: &gitbutler_project::Project,
+        settings: AppSettings,
+        repo_open_mode: RepoOpenMode,
+    ) -> anyhow::Result<Self> {
+        Self::new_from_legacy_project_in_worktree(
+            legacy_project,
+            settings,
+            legacy_project.git_dir().to_owned(),
+            repo_open_mode,
+        )
+    }
+
+    /// Open the worktree whose private git dir is `gitdir` as part of `legacy_project`, which
+    /// allows to work in linked worktrees of the project's repository while sharing its data.
     #[allow(
         deprecated,
         reason = "Context owns the deprecated boundary cache and must initialize it."
     )]
-    pub fn new_from_legacy_project_and_settings_with_repo_open_mode(
+    pub fn new_from_legacy_project_in_worktree(
         legacy_project: &gitbutler_project::Project,
         settings: AppSettings,
+        gitdir: PathBuf,
         repo_open_mode: RepoOpenMode,
     ) -> anyhow::Result<Self> {
-        let gitdir = legacy_project.git_dir().to_owned();
         let repo = open_repo(&gitdir, repo_open_mode)?;
         let project_data_dir = repo.gitbutler_storage_path()?;
+        let shared_project_data_dir = but_project_handle::gitbutler_shared_storage_path(&repo)?;
         let app_cache_dir = but_path::app_cache_dir().ok();
         let cache_mode = CacheMode::Disk;
         Context {
             settings,
             gitdir: gitdir.clone(),
             project_data_dir: project_data_dir.clone(),
+            shared_project_data_dir: shared_project_data_dir.clone(),
             cache_mode,
             repo_open_mode,
             legacy_project: legacy_project.clone(),
@@ -152,7 +171,7 @@ impl Context {
         &self,
     ) -> anyhow::Result<but_meta::VirtualBranchesTomlMetadata> {
         but_meta::VirtualBranchesTomlMetadata::from_path(
-            self.project_data_dir().join("virtual_branches.toml"),
+            self.shared_project_data_dir().join("virtual_branches.toml"),
         )
     }
 }
@@ -97,7 +97,14 @@ pub struct Context {
     /// path outside `gitdir` gets a `<configured-path>/<project-handle>`
     /// suffix. If that key is not configured, the default is `gitdir/gitbutler`
     /// on all channels.
+    ///
+    /// In linked worktrees, this is private to the worktree, like the hunk assignments in its database.
     pub project_data_dir: PathBuf,
+    /// The directory where GitButler data is stored that all worktrees of the repository share,
+    /// like `virtual_branches.toml` and the oplog.
+    ///
+    /// It's the same as `project_data_dir` unless this is a linked worktree.
+    pub shared_project_data_dir: PathBuf,
     /// The directory to store application caches in.
     pub app_cache_dir: Option<PathBuf>,
     cache_mode: CacheMode,
@@ -147,6 +154,8 @@ pub struct ThreadSafeContext {
     pub gitdir: PathBuf,
     /// The directory where per-project GitButler data is stored.
     pub project_data_dir: PathBuf,
+    /// The directory where GitButler data shared by all worktrees of the repository is stored.
+    pub shared_project_data_dir: PathBuf,
     /// The directory to store application caches in.
     pub app_cache_dir: Option<PathBuf>,
     cache_mode: CacheMode,
@@ -169,6 +178,7 @@ impl From<ThreadSafeContext> for Context {
             settings,
             gitdir,
             project_data_dir,
+            shared_project_data_dir,
             app_cache_dir,
             cache_mode,
             repo_open_mode,
@@ -189,6 +199,7 @@ impl From<ThreadSafeContext> for Context {
             app_cache: new_ondemand_app_cache(app_cache_dir.clone(), cache_mode),
             gitdir,
             project_data_dir,
+            shared_project_data_dir,
             app_cache_dir,
             cache_mode,
             #[cfg(feature = "legacy")]
@@ -282,11 +293,13 @@ impl Context {
         let settings = app_settings(app_config_dir)?;
         let repo = open_repo(&gitdir, repo_open_mode)?;
         let project_data_dir = repo.gitbutler_storage_path()?;
+        let shared_project_data_dir = but_project_handle::gitbutler_shared_storage_path(&repo)?;
         #[cfg(not(feature = "legacy"))]
         {
             Context {
                 gitdir: gitdir.clone(),
                 project_data_dir: project_data_dir.clone(),
+                shared_project_data_dir: shared_project_data_dir.clone(),
                 settings,
                 cache_mode: CacheMode::Disk,
                 repo_open_mode,
@@ -312,6 +325,7 @@ impl Context {
                 settings,
                 gitdir: gitdir.clone(),
                 project_data_dir: project_data_dir.clone(),
+                shared_project_data_dir: shared_project_data_dir.clone(),
                 cache_mode,
                 repo_open_mode,
                 legacy_project,
@@ -420,6 +434,8 @@ impl Context {
         let app_cache_dir = but_path::app_cache_dir_for_channel(channel).ok();
         let project_data_dir =
             but_project_handle::gitbutler_storage_path_for_channel(&repo, channel)?;
+        let shared_project_data_dir =
+            but_project_handle::gitbutler_shared_storage_path_for_channel(&repo, channel)?;
         #[cfg(feature = "legacy")]
         {
             use anyhow::Context as _;
@@ -434,6 +450,7 @@ impl Context {
                 settings: app_settings(but_path::app_config_dir()?)?,
                 gitdir: gitdir.clone(),
                 project_data_dir: project_data_dir.clone(),
+                shared_project_data_dir: shared_project_data_dir.clone(),
                 cache_mode,
                 repo_open_mode,
                 legacy_project,
@@ -454,6 +471,7 @@ impl Context {
             crate::Context {
                 gitdir: gitdir.clone(),
                 project_data_dir: project_data_dir.clone(),
+                shared_project_data_dir: shared_project_data_dir.clone(),
                 settings: app_settings(but_path::app_config_dir()?)?,
                 cache_mode,
                 repo_open_mode,
@@ -491,6 +509,7 @@ impl Context {
     ) -> anyhow::Result<Context> {
         let gitdir = repo.git_dir().to_owned();
         let project_data_dir = repo.gitbutler_storage_path()?;
+        let shared_project_data_dir = but_project_handle::gitbutler_shared_storage_path(&repo)?;
         let app_cache_dir = but_path::app_cache_dir().ok();
         let repo_open_mode =
             if repo.open_options().permissions == gix::open::Permissions::isolated() {
@@ -505,6 +524,7 @@ impl Context {
             legacy_project: default_legacy_project_at_repo(&repo),
             gitdir: gitdir.clone(),
             project_data_dir: project_data_dir.clone(),
+            shared_project_data_dir: shared_project_data_dir.clone(),
             settings,
             cache_mode,
             repo_open_mode,
@@ -524,7 +544,7 @@ impl Context {
             let configured = ProjectMeta::resolve(&repo)?;
             let project_meta = if configured == ProjectMeta::default() {
                 but_meta::legacy_storage::read_legacy_project_meta(
-                    &self.project_data_dir.join("virtual_branches.toml"),
+                    &self.shared_project_data_dir.join("virtual_branches.toml"),
                 )?
                 .unwrap_or_default()
             } else {
@@ -781,8 +801,8 @@ impl Context {
         let options = self.graph_options(but_graph::init::Options::limited())?;
         let repo = self.repo.get()?;
         let meta = but_meta::BranchOrderMetadata::from_paths_read_only(
-            self.project_data_dir().join("virtual_branches.toml"),
-            self.project_data_dir(),
+            self.shared_project_data_dir().join("virtual_branches.toml"),
+            self.shared_project_data_dir(),
         )?;
         let graph = but_graph::Graph::from_head(&repo, &meta, self.project_meta()?, options)?;
         graph.into_workspace()
@@ -862,8 +882,8 @@ impl Context {
     //            `_read_only`.
     pub fn meta(&self) -> anyhow::Result<impl but_core::RefMetadata + 'static> {
         but_meta::BranchOrderMetadata::from_paths(
-            self.project_data_dir().join("virtual_branches.toml"),
-            self.project_data_dir(),
+            self.shared_project_data_dir().join("virtual_branches.toml"),
+            self.shared_project_data_dir(),
         )
     }
 
@@ -873,6 +893,7 @@ impl Context {
             settings: self.settings.clone(),
             gitdir: self.gitdir.clone(),
             project_data_dir: self.project_data_dir.clone(),
+            shared_project_data_dir: self.shared_project_data_dir.clone(),
             app_cache_dir: self.app_cache_dir.clone(),
             cache_mode: self.cache_mode,
             repo_open_mode: self.repo_open_mode,
@@ -892,6 +913,7 @@ impl Context {
             settings,
             gitdir,
             project_data_dir,
+            shared_project_data_dir,
             mut repo,
             git2_repo: _,
             db: _,
@@ -907,6 +929,7 @@ impl Context {
             settings,
             gitdir,
             project_data_dir,
+            shared_project_data_dir,
             app_cache_dir,
             cache_mode,
             repo_open_mode,
@@ -931,6 +954,11 @@ impl Context {
         self.project_data_dir.clone()
     }
 
+    /// The location where project-specific data can be stored that all worktrees of the repository share.
+    pub fn shared_project_data_dir(&self) -> PathBuf {
+        self.shared_project_data_dir.clone()
+    }
+
     /// The path to the worktree directory or the `.git` directory if there is no worktree directory.
     /// Fallible as it may need to open a repository.
     pub fn workdir_or_gitdir(&self) -> anyhow::Result<PathBuf> {
@@ -70,7 +70,7 @@ pub mod migration;
 
 #[rustfmt::skip]
 pub use table::{
-    hunk_assignments::{HunkAssignmentsHandleMut, HunkAssignmentsHandle, HunkAssignment},
+    hunk_assignments::{HunkAssignmentsHandleMut, HunkAssignmentsHandle, HunkAssignment, HunkAssignmentRule},
     branch_order::{BranchOrderHandle, BranchOrderHandleMut},
     butler_actions::ButlerAction,
     claude::{ClaudeMessage, ClaudePermissionRequest, ClaudeSession},
@@ -32,6 +32,16 @@ pub(crate) const M: &[M<'static>] = &[
         SchemaVersion::Zero,
         "ALTER TABLE `hunk_assignments` ADD COLUMN `branch_ref` BINARY;",
     ),
+    M::up(
+        20261016140000,
+        SchemaVersion::Zero,
+        "CREATE TABLE `hunk_assignment_rules`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`path_glob` TEXT NOT NULL,
+	`branch_ref` BINARY NOT NULL
+);",
+    ),
 ];
 
 /// Tests are in `but-db/tests/db/table/hunk_assignments.rs`.
@@ -45,6 +55,14 @@ pub struct HunkAssignment {
     pub branch_ref_bytes: Option<Vec<u8>>,
 }
 
+/// A rule to assign new hunks whose path matches `path_glob` to the branch `branch_ref_bytes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HunkAssignmentRule {
+    pub id: String,
+    pub path_glob: String,
+    pub branch_ref_bytes: Vec<u8>,
+}
+
 impl DbHandle {
     pub fn hunk_assignments(&self) -> HunkAssignmentsHandle<'_> {
         HunkAssignmentsHandle { conn: &self.conn }
@@ -97,6 +115,23 @@ impl HunkAssignmentsHandle<'_> {
 
         results.collect::<Result<Vec<_>, _>>()
     }
+
+    /// Lists all hunk assignment rules, in the order they were added.
+    pub fn list_rules(&self) -> rusqlite::Result<Vec<HunkAssignmentRule>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path_glob, branch_ref FROM hunk_assignment_rules ORDER BY created_at, rowid",
+        )?;
+
+        let results = stmt.query_map([], |row| {
+            Ok(HunkAssignmentRule {
+                id: row.get(0)?,
+                path_glob: row.get(1)?,
+                branch_ref_bytes: row.get(2)?,
+            })
+        })?;
+
+        results.collect::<Result<Vec<_>, _>>()
+    }
 }
 
 impl HunkAssignmentsHandleMut<'_> {
@@ -128,4 +163,24 @@ impl HunkAssignmentsHandleMut<'_> {
         self.sp.commit()?;
         Ok(())
     }
+
+    /// Adds `rule` after all existing rules.
+    pub fn insert_rule(self, rule: HunkAssignmentRule) -> rusqlite::Result<()> {
+        self.sp.execute(
+            "INSERT INTO hunk_assignment_rules (id, path_glob, branch_ref) VALUES (?1, ?2, ?3)",
+            rusqlite::params![rule.id, rule.path_glob, rule.branch_ref_bytes],
+        )?;
+        self.sp.commit()?;
+        Ok(())
+    }
+
+    /// Deletes the rules with the given `ids`, ignoring the ones that don't exist.
+    pub fn delete_rules(self, ids: &[String]) -> rusqlite::Result<()> {
+        for id in ids {
+            self.sp
+                .execute("DELETE FROM hunk_assignment_rules WHERE id = ?1", [id])?;
+        }
+        self.sp.commit()?;
+        Ok(())
+    }
 }
@@ -1,9 +1,10 @@
 use crate::{DbHandle, M, SchemaVersion, Transaction};
 
-pub(crate) const M: &[M<'static>] = &[M::up(
-    20260219130000,
-    SchemaVersion::Zero,
-    "CREATE TABLE `vb_state`(
+pub(crate) const M: &[M<'static>] = &[
+    M::up(
+        20260219130000,
+        SchemaVersion::Zero,
+        "CREATE TABLE `vb_state`(
 	`id` INTEGER PRIMARY KEY CHECK (`id` = 1),
 	`initialized` INTEGER NOT NULL DEFAULT 0,
 	`default_target_remote_name` TEXT,
@@ -60,7 +61,13 @@ CREATE INDEX `idx_vb_stacks_sort_order` ON `vb_stacks`(`sort_order`);
 CREATE INDEX `idx_vb_stacks_in_workspace` ON `vb_stacks`(`in_workspace`);
 CREATE INDEX `idx_vb_stack_heads_stack_id` ON `vb_stack_heads`(`stack_id`);
 ",
-)];
+    ),
+    M::up(
+        20261016120000,
+        SchemaVersion::Zero,
+        "ALTER TABLE `vb_stack_heads` ADD COLUMN `description` TEXT;",
+    ),
+];
 
 /// One-row state table for virtual branches metadata (`vb_state`).
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -128,6 +135,8 @@ pub struct VbStackHead {
     pub archived: bool,
     /// Optional review identifier associated with this head.
     pub review_id: Option<String>,
+    /// Optional free-form description of this head, as written by the user.
+    pub description: Option<String>,
 }
 
 /// Canonical normalized VB payload read from / written to the database.
@@ -270,7 +279,7 @@ impl VirtualBranchesHandle<'_> {
 
             let heads = {
                 let mut stmt = self.conn.prepare(
-                    "SELECT stack_id, position, name, head_sha, pr_number, archived, review_id
+                    "SELECT stack_id, position, name, head_sha, pr_number, archived, review_id, description
                      FROM vb_stack_heads
                      ORDER BY stack_id, position",
                 )?;
@@ -283,6 +292,7 @@ impl VirtualBranchesHandle<'_> {
                         pr_number: row.get(4)?,
                         archived: row.get(5)?,
                         review_id: row.get(6)?,
+                        description: row.get(7)?,
                     })
                 })?;
                 rows.collect::<Result<Vec<_>, _>>()?
@@ -404,8 +414,9 @@ impl VirtualBranchesHandleMut<'_> {
                     head_sha,
                     pr_number,
                     archived,
-                    review_id
-                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    review_id,
+                    description
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             )?;
             for head in &snapshot.heads {
                 insert_head.execute(rusqlite::params![
@@ -416,6 +427,7 @@ impl VirtualBranchesHandleMut<'_> {
                     head.pr_number,
                     head.archived,
                     head.review_id,
+                    head.description,
                 ])?;
             }
         }
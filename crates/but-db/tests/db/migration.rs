@@ -517,6 +517,14 @@ CREATE TABLE `gerrit_metadata`(
 	`updated_at` TIMESTAMP NOT NULL
 );
 
+-- table hunk_assignment_rules
+CREATE TABLE `hunk_assignment_rules`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`path_glob` TEXT NOT NULL,
+	`branch_ref` BINARY NOT NULL
+);
+
 -- table hunk_assignments
 CREATE TABLE `hunk_assignments`(
 	`hunk_header` TEXT,
@@ -549,7 +557,7 @@ CREATE TABLE `vb_stack_heads`(
 	`review_id` TEXT,
 	PRIMARY KEY(`stack_id`, `position`),
 	FOREIGN KEY(`stack_id`) REFERENCES `vb_stacks`(`id`) ON DELETE CASCADE
-);
+, `description` TEXT);
 
 -- table vb_stacks
 CREATE TABLE `vb_stacks`(
@@ -679,6 +687,8 @@ Text("20260626120100")
 Text("20260715120000")
 Text("20260715161258")
 Text("20260716175500")
+Text("20261016120000")
+Text("20261016140000")
 
 Table: hunk_assignments
 hunk_header | path | path_bytes | stack_id | id | branch_ref
@@ -717,7 +727,7 @@ Table: vb_stacks
 id | source_refname | upstream_remote_name | upstream_branch_name | sort_order | in_workspace | legacy_name | legacy_notes | legacy_ownership | legacy_allow_rebasing | legacy_post_commits | legacy_tree_sha | legacy_head_sha | legacy_created_timestamp_ms | legacy_updated_timestamp_ms
 
 Table: vb_stack_heads
-stack_id | position | name | head_sha | pr_number | archived | review_id
+stack_id | position | name | head_sha | pr_number | archived | review_id | description
 
 Table: vb_branch_targets
 stack_id | remote_name | branch_name | remote_url | sha | push_remote_name
@@ -734,6 +744,9 @@ name | archived
 Table: worktree_adoption
 id | adopted_at
 
+Table: hunk_assignment_rules
+id | created_at | path_glob | branch_ref
+
 
 "#]]
         );
@@ -1,4 +1,4 @@
-use but_db::HunkAssignment;
+use but_db::{HunkAssignment, HunkAssignmentRule};
 
 use crate::table::in_memory_db;
 
@@ -243,6 +243,55 @@ fn legacy_stack_id_fallback() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn rules_are_listed_in_insertion_order_and_survive_set_all() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let docs = rule("rule-b", "docs/**", "refs/heads/docs");
+    let ui = rule("rule-a", "crates/gui/**", "refs/heads/ui");
+    db.hunk_assignments_mut()?.insert_rule(docs.clone())?;
+    db.hunk_assignments_mut()?.insert_rule(ui.clone())?;
+    db.hunk_assignments_mut()?.set_all(vec![])?;
+
+    assert_eq!(
+        db.hunk_assignments().list_rules()?,
+        [docs.clone(), ui],
+        "the order of addition is kept regardless of the ID, and assignments don't affect rules"
+    );
+    assert!(
+        db.hunk_assignments_mut()?.insert_rule(docs).is_err(),
+        "IDs are unique"
+    );
+    Ok(())
+}
+
+#[test]
+fn delete_rules_ignores_unknown_ids() -> anyhow::Result<()> {
+    let mut db = in_memory_db();
+
+    let docs = rule("rule-a", "docs/**", "refs/heads/docs");
+    let ui = rule("rule-b", "crates/gui/**", "refs/heads/ui");
+    db.hunk_assignments_mut()?.insert_rule(docs.clone())?;
+    db.hunk_assignments_mut()?.insert_rule(ui)?;
+
+    db.hunk_assignments_mut()?
+        .delete_rules(&["rule-b".into(), "unknown".into()])?;
+    assert_eq!(db.hunk_assignments().list_rules()?, [docs]);
+
+    db.hunk_assignments_mut()?
+        .delete_rules(&["rule-a".into()])?;
+    assert!(db.hunk_assignments().list_rules()?.is_empty());
+    Ok(())
+}
+
+fn rule(id: &str, path_glob: &str, branch_ref: &str) -> HunkAssignmentRule {
+    HunkAssignmentRule {
+        id: id.to_string(),
+        path_glob: path_glob.to_string(),
+        branch_ref_bytes: branch_ref.as_bytes().to_vec(),
+    }
+}
+
 fn hunk_assignment(
     id: &str,
     hunk_header: Option<&str>,
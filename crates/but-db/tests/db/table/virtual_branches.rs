@@ -55,6 +55,7 @@ fn replace_snapshot_replaces_existing_data() -> anyhow::Result<()> {
         pr_number: None,
         archived: true,
         review_id: None,
+        description: None,
     }];
     let handle = db.virtual_branches_mut()?;
     handle.replace_snapshot(&next)?;
@@ -211,6 +212,7 @@ fn get_snapshot_returns_deterministic_ordering() -> anyhow::Result<()> {
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         },
         VbStackHead {
             stack_id: "stack-a".into(),
@@ -220,6 +222,7 @@ fn get_snapshot_returns_deterministic_ordering() -> anyhow::Result<()> {
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         },
         VbStackHead {
             stack_id: "stack-a".into(),
@@ -229,6 +232,7 @@ fn get_snapshot_returns_deterministic_ordering() -> anyhow::Result<()> {
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         },
     ];
     let handle = db.virtual_branches_mut()?;
@@ -347,6 +351,7 @@ fn sample_snapshot() -> VirtualBranchesSnapshot {
             pr_number: Some(7),
             archived: false,
             review_id: Some("rvw_1".into()),
+            description: Some("Adds the first feature".into()),
         }],
     }
 }
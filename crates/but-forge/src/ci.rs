@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::ForgeName;
@@ -40,6 +42,99 @@ pub fn ci_checks_for_ref_with_cache(
     Ok(checks)
 }
 
+/// Get the CI checks for each of `references`, keyed by reference, like [`ci_checks_for_ref_with_cache()`] does for one.
+///
+/// If any of the cached checks are outdated, they are fetched from GitHub for the heads of all
+/// open pull requests at once, so the number of requests doesn't grow with the number of references.
+/// Other forges are asked for each outdated reference.
+/// References that aren't the head of an open pull request keep their cached checks.
+pub fn ci_checks_for_refs_with_cache(
+    preferred_forge_user: Option<crate::ForgeUser>,
+    forge_repo_info: &crate::forge::ForgeRepoInfo,
+    storage: &but_forge_storage::Controller,
+    references: &[String],
+    db: &mut but_db::DbHandle,
+    cache_config: Option<crate::CacheConfig>,
+) -> anyhow::Result<HashMap<String, Vec<CiCheck>>> {
+    let mut checks_by_ref = HashMap::new();
+    for reference in references {
+        checks_by_ref.insert(
+            reference.clone(),
+            crate::db::ci_checks_from_cache(db, reference)?,
+        );
+    }
+    let now = chrono::Local::now().naive_local();
+    let is_outdated = |checks: &Vec<CiCheck>| match cache_config.clone().unwrap_or_default() {
+        crate::CacheConfig::CacheOnly => false,
+        crate::CacheConfig::CacheWithFallback { max_age_seconds } => checks
+            .first()
+            .is_none_or(|check| (now - check.last_sync_at).num_seconds() as u64 > max_age_seconds),
+        crate::CacheConfig::NoCache => true,
+    };
+    let outdated = references
+        .iter()
+        .filter(|reference| {
+            checks_by_ref
+                .get(*reference)
+                .is_some_and(|checks| is_outdated(checks))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if outdated.is_empty() {
+        return Ok(checks_by_ref);
+    }
+
+    if forge_repo_info.forge == ForgeName::GitHub {
+        let preferred_account = preferred_forge_user
+            .as_ref()
+            .and_then(|user| user.github().cloned());
+        let owner = forge_repo_info.owner.clone();
+        let repo = forge_repo_info.repo.clone();
+        let storage = storage.clone();
+        let mut runs_by_branch = std::thread::spawn(move || {
+            tokio::runtime::Runtime::new()
+                .map_err(|err| anyhow::anyhow!("Failed to create tokio runtime: {err}"))?
+                .block_on(but_github::checks::list_for_open_pulls(
+                    preferred_account.as_ref(),
+                    &owner,
+                    &repo,
+                    &storage,
+                ))
+        })
+        .join()
+        .map_err(|e| anyhow::anyhow!("Failed to join thread: {e:?}"))??;
+        for reference in references {
+            let Some(runs) = runs_by_branch.remove(reference) else {
+                continue;
+            };
+            let checks = runs
+                .into_iter()
+                .map(|run| {
+                    let mut ci_check = CiCheck::from(run);
+                    ci_check.reference = reference.clone();
+                    ci_check
+                })
+                .collect();
+            checks_by_ref.insert(
+                reference.clone(),
+                refresh_cache_with_fetched(db, reference, Some(checks)),
+            );
+        }
+    } else {
+        for reference in outdated {
+            let checks = fetch_and_refresh_cache(
+                preferred_forge_user.clone(),
+                forge_repo_info,
+                storage,
+                &reference,
+                db,
+            )?;
+            checks_by_ref.insert(reference, checks);
+        }
+    }
+    Ok(checks_by_ref)
+}
+
 /// Fetch fresh checks from the forge and refresh the cache with the result.
 ///
 /// A resolvable ref returns an authoritative list — even an empty one, which
@@ -226,6 +321,69 @@ impl CiCheck {
     }
 }
 
+/// The overall state of the CI checks of a reference, as summarized by [`CiSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CiSummaryState {
+    /// No check failed or is still running.
+    Success,
+    /// At least one check failed.
+    Failure,
+    /// No check failed, but some are still queued or running.
+    Pending,
+}
+
+/// The number of CI checks in each state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiCounts {
+    pub success: usize,
+    pub failure: usize,
+    pub pending: usize,
+    /// Checks that were skipped, cancelled, neutral or have an unknown state.
+    pub other: usize,
+}
+
+/// A compact summary of the CI checks of a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiSummary {
+    pub state: CiSummaryState,
+    pub counts: CiCounts,
+}
+
+impl CiSummary {
+    /// Summarize `checks`, or return `None` if there are none.
+    pub fn from_checks(checks: &[CiCheck]) -> Option<Self> {
+        if checks.is_empty() {
+            return None;
+        }
+        let mut counts = CiCounts::default();
+        for check in checks {
+            match &check.status {
+                CiStatus::Complete {
+                    conclusion: CiConclusion::Success,
+                    ..
+                } => counts.success += 1,
+                CiStatus::Complete {
+                    conclusion: CiConclusion::Failure | CiConclusion::TimedOut,
+                    ..
+                } => counts.failure += 1,
+                CiStatus::InProgress | CiStatus::Queued => counts.pending += 1,
+                CiStatus::Complete { .. } | CiStatus::Unknown => counts.other += 1,
+            }
+        }
+        let state = if counts.failure > 0 {
+            CiSummaryState::Failure
+        } else if counts.pending > 0 {
+            CiSummaryState::Pending
+        } else {
+            CiSummaryState::Success
+        };
+        Some(CiSummary { state, counts })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 #[cfg_attr(feature = "export-schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
@@ -478,7 +636,10 @@ impl From<but_bitbucket::BitbucketBuildStatus> for CiCheck {
 
 #[cfg(test)]
 mod tests {
-    use super::{CiCheck, CiConclusion, CiOutput, CiStatus, refresh_cache_with_fetched};
+    use super::{
+        CiCheck, CiConclusion, CiCounts, CiOutput, CiStatus, CiSummary, CiSummaryState,
+        ci_checks_for_refs_with_cache, refresh_cache_with_fetched,
+    };
 
     const REFERENCE: &str = "refs/heads/feature";
 
@@ -515,6 +676,98 @@ mod tests {
         ids
     }
 
+    fn check_with_status(id: i64, status: CiStatus) -> CiCheck {
+        CiCheck {
+            status,
+            ..cache_check(id)
+        }
+    }
+
+    fn complete(conclusion: CiConclusion) -> CiStatus {
+        CiStatus::Complete {
+            conclusion,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn summary_of_no_checks_is_none() {
+        assert_eq!(CiSummary::from_checks(&[]), None);
+    }
+
+    #[test]
+    fn summary_state_prefers_failure_over_pending_over_success() {
+        let checks = [
+            check_with_status(1, complete(CiConclusion::Success)),
+            check_with_status(2, CiStatus::Queued),
+            check_with_status(3, complete(CiConclusion::Skipped)),
+        ];
+        assert_eq!(
+            CiSummary::from_checks(&checks),
+            Some(CiSummary {
+                state: CiSummaryState::Pending,
+                counts: CiCounts {
+                    success: 1,
+                    failure: 0,
+                    pending: 1,
+                    other: 1,
+                },
+            })
+        );
+
+        let mut checks = checks.to_vec();
+        checks.push(check_with_status(4, complete(CiConclusion::TimedOut)));
+        let summary = CiSummary::from_checks(&checks).unwrap();
+        assert_eq!(summary.state, CiSummaryState::Failure);
+        assert_eq!(summary.counts.failure, 1);
+
+        let summary = CiSummary::from_checks(&[
+            check_with_status(1, complete(CiConclusion::Success)),
+            check_with_status(2, complete(CiConclusion::Neutral)),
+        ])
+        .unwrap();
+        assert_eq!(summary.state, CiSummaryState::Success);
+    }
+
+    #[test]
+    fn checks_for_refs_use_the_cache_without_asking_the_forge() {
+        let (tmp, mut db) = test_db();
+        crate::db::cache_ci_checks(&mut db, REFERENCE, &[cache_check(1)]).unwrap();
+        let storage = but_forge_storage::Controller::from_path(tmp.path());
+        let forge_repo_info = crate::forge::ForgeRepoInfo {
+            forge: crate::ForgeName::GitHub,
+            owner: "owner".into(),
+            repo: "repo".into(),
+            protocol: "https".into(),
+        };
+        let references = [REFERENCE.to_string(), "uncached".to_string()];
+
+        let checks = ci_checks_for_refs_with_cache(
+            None,
+            &forge_repo_info,
+            &storage,
+            &references,
+            &mut db,
+            Some(crate::CacheConfig::CacheOnly),
+        )
+        .unwrap();
+        assert_eq!(checks[REFERENCE].len(), 1);
+        assert!(checks["uncached"].is_empty());
+
+        let checks = ci_checks_for_refs_with_cache(
+            None,
+            &forge_repo_info,
+            &storage,
+            &references[..1],
+            &mut db,
+            Some(crate::CacheConfig::CacheWithFallback {
+                max_age_seconds: 60,
+            }),
+        )
+        .unwrap();
+        assert_eq!(checks[REFERENCE].len(), 1, "recent checks are reused");
+    }
+
     #[test]
     fn some_non_empty_result_replaces_the_cache() {
         let (_tmp, mut db) = test_db();
@@ -11,7 +11,10 @@ mod forge_info;
 mod repo;
 mod review;
 pub use association::{pr_numbers_by_head, preferred_review, review_for_head_ref, reviews_by_head};
-pub use ci::{CiCheck, CiConclusion, CiOutput, CiStatus, ci_checks_for_ref_with_cache};
+pub use ci::{
+    CiCheck, CiConclusion, CiCounts, CiOutput, CiStatus, CiSummary, CiSummaryState,
+    ci_checks_for_ref_with_cache, ci_checks_for_refs_with_cache,
+};
 pub use forge_info::{ForgeCapabilities, ForgeInfo, ForgeUnitInfo, compare_branch_url, forge_info};
 pub use repo::{RepoInfo, RepoPermissions, get_repo_info};
 pub use review::{
@@ -42,7 +45,8 @@ fn determine_forge_from_host(host: &str) -> Option<ForgeName> {
 /// Derive the forge repository information from a remote URL.
 ///
 /// If the forge type can't be determined by simply looking for keywords in the repositories URL,
-/// look through all the known accounts and try to match their custom host strings to the repository's URL host.
+/// look through all the known accounts and try to match their custom host strings to the repository's URL host,
+/// and finally try the self-hosted GitLab instance configured with the `GITLAB_HOST` environment variable.
 /// Looking at the known accounts involves retrieving data from storage, so that is a bit more expensive
 /// and that's why it's a fallback mechanism.
 pub fn derive_forge_repo_info(url: &str) -> Option<ForgeRepoInfo> {
@@ -52,12 +56,19 @@ pub fn derive_forge_repo_info(url: &str) -> Option<ForgeRepoInfo> {
 
     let provider_info: GenericProvider = git_url.provider_info().ok()?;
     // Attempt to figure out the forge by looking at the host string and
-    // falling back to matching it to the known accounts custom host URL.
-    let forge = determine_forge_from_host(host).or_else(|| {
-        // Only fetch the accounts if it can't determine the forge type from the repository's host.
-        let accounts = get_all_forge_accounts().unwrap_or_default();
-        match_host_to_accounts_custom_host(host, &accounts)
-    })?;
+    // falling back to matching it to the known accounts custom host URL,
+    // or to the GitLab host configured in the environment.
+    let forge = determine_forge_from_host(host)
+        .or_else(|| {
+            // Only fetch the accounts if it can't determine the forge type from the repository's host.
+            let accounts = get_all_forge_accounts().unwrap_or_default();
+            match_host_to_accounts_custom_host(host, &accounts)
+        })
+        .or_else(|| {
+            but_gitlab::host_from_env()
+                .filter(|gitlab_host| custom_host_matches_repository_host(host, gitlab_host))
+                .map(|_| ForgeName::GitLab)
+        })?;
 
     Some(ForgeRepoInfo {
         forge,
@@ -27,6 +27,22 @@ pub async fn list_for_ref(
     }
 }
 
+/// Fetch CI check runs for the head commits of all open PRs, keyed by the name of their head branch.
+///
+/// This takes one request per page of PRs, instead of one request per branch like [`list_for_ref()`].
+pub async fn list_for_open_pulls(
+    preferred_account: Option<&crate::GithubAccountIdentifier>,
+    owner: &str,
+    repo: &str,
+    storage: &but_forge_storage::Controller,
+) -> Result<std::collections::HashMap<String, Vec<CheckRun>>> {
+    let gh = GitHubClient::from_storage(storage, preferred_account)?;
+    gh.list_checks_for_open_pulls(owner, repo)
+        .await
+        .map_err(classify_forge_error)
+        .context("Failed to list checks for open pull requests")
+}
+
 /// A 422 on `commits/{ref}/check-runs` — GitHub couldn't resolve the ref.
 fn is_unresolvable_ref(err: &anyhow::Error) -> bool {
     err.downcast_ref::<HttpStatusError>()
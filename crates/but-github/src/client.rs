@@ -4,8 +4,8 @@ use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT}
 use serde::{Deserialize, Serialize};
 
 use crate::graphql::{
-    GQL_DISABLE_PR_AUTO_MERGE, GQL_ENABLE_PR_AUTO_MERGE, GQL_GET_PR_NODE_ID, GQL_SET_PR_DRAFT,
-    GQL_SET_PR_READY_FOR_REVIEW,
+    GQL_DISABLE_PR_AUTO_MERGE, GQL_ENABLE_PR_AUTO_MERGE, GQL_GET_PR_NODE_ID, GQL_OPEN_PR_CHECKS,
+    GQL_SET_PR_DRAFT, GQL_SET_PR_READY_FOR_REVIEW,
 };
 
 const GITHUB_API_BASE_URL: &str = "https://api.github.com";
@@ -200,6 +200,125 @@ impl GitHubClient {
         Ok(dedupe_latest_by_name(check_runs))
     }
 
+    /// Fetch the checks of the head commit of all open PRs on a repo, keyed by the name of their head branch.
+    ///
+    /// Unlike [`Self::list_checks_for_ref()`], this needs only one request per page of PRs.
+    /// Commit statuses are returned as check runs as well.
+    pub async fn list_checks_for_open_pulls(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<std::collections::HashMap<String, Vec<CheckRun>>> {
+        #[derive(Serialize)]
+        struct Variables<'a> {
+            owner: &'a str,
+            repo: &'a str,
+            after: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        struct QueryData {
+            repository: Option<Repository>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Repository {
+            pull_requests: Connection<GraphQlPullRequest>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Connection<T> {
+            #[serde(default)]
+            page_info: Option<PageInfo>,
+            nodes: Vec<Option<T>>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PageInfo {
+            has_next_page: bool,
+            end_cursor: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct GraphQlPullRequest {
+            head_ref_name: String,
+            commits: Connection<PullRequestCommit>,
+        }
+
+        #[derive(Deserialize)]
+        struct PullRequestCommit {
+            commit: Commit,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Commit {
+            oid: String,
+            status_check_rollup: Option<StatusCheckRollup>,
+        }
+
+        #[derive(Deserialize)]
+        struct StatusCheckRollup {
+            contexts: Connection<GraphQlCheckContext>,
+        }
+
+        let mut checks_by_branch = std::collections::HashMap::new();
+        let mut after = None;
+        loop {
+            let data: QueryData = self
+                .graphql_query(
+                    GQL_OPEN_PR_CHECKS,
+                    &Variables {
+                        owner,
+                        repo,
+                        after: after.take(),
+                    },
+                )
+                .await?;
+            let Some(repository) = data.repository else {
+                bail!("GitHub GraphQL could not find repository {owner}/{repo}");
+            };
+            let pulls = repository.pull_requests;
+            for pull in pulls.nodes.into_iter().flatten() {
+                let Some(commit) = pull
+                    .commits
+                    .nodes
+                    .into_iter()
+                    .flatten()
+                    .next()
+                    .map(|node| node.commit)
+                else {
+                    continue;
+                };
+                let runs = commit
+                    .status_check_rollup
+                    .map(|rollup| {
+                        rollup
+                            .contexts
+                            .nodes
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|context| context.into_check_run(&commit.oid))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                checks_by_branch.insert(pull.head_ref_name, dedupe_latest_by_name(runs));
+            }
+            match pulls.page_info {
+                Some(PageInfo {
+                    has_next_page: true,
+                    end_cursor: Some(cursor),
+                }) => after = Some(cursor),
+                _ => break,
+            }
+        }
+        Ok(checks_by_branch)
+    }
+
     /// The actual REST API call to fetch a page of the checks.
     async fn fetch_check_runs(&self, url: &str, page: usize) -> Result<reqwest::Response> {
         let response = self
@@ -1054,6 +1173,90 @@ pub struct CheckRun {
     pub details_url: Option<String>,
 }
 
+/// A check run or commit status of the `statusCheckRollup` of a commit in GraphQL.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "__typename")]
+enum GraphQlCheckContext {
+    #[serde(rename_all = "camelCase")]
+    CheckRun {
+        database_id: Option<i64>,
+        name: String,
+        status: String,
+        conclusion: Option<String>,
+        url: Option<String>,
+        details_url: Option<String>,
+        started_at: Option<String>,
+        completed_at: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatusContext {
+        context: String,
+        state: String,
+        target_url: Option<String>,
+        created_at: Option<String>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl GraphQlCheckContext {
+    /// Convert this context of the commit `head_sha` into the check run the REST API would return.
+    ///
+    /// GraphQL enums are upper case, while REST uses lower case. Commit statuses are mapped
+    /// to the check run status and conclusion that means the same.
+    fn into_check_run(self, head_sha: &str) -> Option<CheckRun> {
+        Some(match self {
+            GraphQlCheckContext::CheckRun {
+                database_id,
+                name,
+                status,
+                conclusion,
+                url,
+                details_url,
+                started_at,
+                completed_at,
+            } => CheckRun {
+                id: database_id.unwrap_or_default(),
+                name,
+                status: status.to_lowercase(),
+                conclusion: conclusion.map(|conclusion| conclusion.to_lowercase()),
+                html_url: url.clone(),
+                head_sha: Some(head_sha.to_owned()),
+                started_at,
+                completed_at,
+                url,
+                details_url,
+            },
+            GraphQlCheckContext::StatusContext {
+                context,
+                state,
+                target_url,
+                created_at,
+            } => {
+                let (status, conclusion) = match state.as_str() {
+                    "SUCCESS" => ("completed", Some("success")),
+                    "FAILURE" | "ERROR" => ("completed", Some("failure")),
+                    "PENDING" => ("in_progress", None),
+                    _ => ("queued", None),
+                };
+                CheckRun {
+                    id: 0,
+                    name: context,
+                    status: status.to_owned(),
+                    conclusion: conclusion.map(ToOwned::to_owned),
+                    html_url: target_url.clone(),
+                    head_sha: Some(head_sha.to_owned()),
+                    started_at: created_at,
+                    completed_at: None,
+                    url: target_url.clone(),
+                    details_url: target_url,
+                }
+            }
+            GraphQlCheckContext::Other => return None,
+        })
+    }
+}
+
 /// GitHub returns check runs from every suite attached to a commit —
 /// including superseded suites from force-push-cancelled runs — so an
 /// orphaned `failure` (e.g. an aggregator that detects its deps got
@@ -1342,6 +1545,67 @@ mod tests {
         assert_eq!(rebase, serde_json::json!("REBASE"));
     }
 
+    #[test]
+    fn graphql_check_contexts_convert_to_rest_check_runs() {
+        let contexts: Vec<GraphQlCheckContext> = serde_json::from_value(json!([
+            {
+                "__typename": "CheckRun",
+                "databaseId": 7,
+                "name": "test",
+                "status": "COMPLETED",
+                "conclusion": "SUCCESS",
+                "url": "https://github.com/o/r/runs/7",
+                "detailsUrl": null,
+                "startedAt": "2024-01-01T00:00:00Z",
+                "completedAt": null
+            },
+            {
+                "__typename": "StatusContext",
+                "context": "ci/legacy",
+                "state": "ERROR",
+                "targetUrl": "https://ci.example.com/1",
+                "createdAt": null
+            },
+            {
+                "__typename": "StatusContext",
+                "context": "ci/pending",
+                "state": "PENDING",
+                "targetUrl": null,
+                "createdAt": null
+            },
+            { "__typename": "SomethingNew" }
+        ]))
+        .unwrap();
+        let runs = contexts
+            .into_iter()
+            .filter_map(|context| context.into_check_run("abc"))
+            .map(|run| (run.name, run.status, run.conclusion, run.head_sha))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            runs,
+            [
+                (
+                    "test".to_string(),
+                    "completed".to_string(),
+                    Some("success".to_string()),
+                    Some("abc".to_string())
+                ),
+                (
+                    "ci/legacy".to_string(),
+                    "completed".to_string(),
+                    Some("failure".to_string()),
+                    Some("abc".to_string())
+                ),
+                (
+                    "ci/pending".to_string(),
+                    "in_progress".to_string(),
+                    None,
+                    Some("abc".to_string())
+                ),
+            ]
+        );
+    }
+
     fn check_run(name: &str, started_at: Option<&str>, conclusion: Option<&str>) -> CheckRun {
         CheckRun {
             id: 0,
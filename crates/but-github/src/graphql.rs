@@ -47,3 +47,49 @@ pub const GQL_GET_PR_NODE_ID: &str = r#"
       }
     }
     "#;
+
+pub const GQL_OPEN_PR_CHECKS: &str = r#"
+    query OpenPullRequestChecks($owner: String!, $repo: String!, $after: String) {
+      repository(owner: $owner, name: $repo) {
+        pullRequests(first: 50, after: $after, states: OPEN, orderBy: { field: UPDATED_AT, direction: DESC }) {
+          pageInfo {
+            hasNextPage
+            endCursor
+          }
+          nodes {
+            headRefName
+            commits(last: 1) {
+              nodes {
+                commit {
+                  oid
+                  statusCheckRollup {
+                    contexts(first: 100) {
+                      nodes {
+                        __typename
+                        ... on CheckRun {
+                          databaseId
+                          name
+                          status
+                          conclusion
+                          url
+                          detailsUrl
+                          startedAt
+                          completedAt
+                        }
+                        ... on StatusContext {
+                          context
+                          state
+                          targetUrl
+                          createdAt
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    "#;
@@ -8,6 +8,10 @@ use std::time::Duration;
 use crate::GitLabProjectId;
 
 const GITLAB_API_BASE_URL: &str = "https://gitlab.com/api/v4";
+/// The access token to use if no GitLab account was authenticated, as understood by `glab` as well.
+const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+/// The host of a self-hosted GitLab instance to use with [`GITLAB_TOKEN_ENV`], instead of gitlab.com.
+const GITLAB_HOST_ENV: &str = "GITLAB_HOST";
 const GITLAB_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const MAX_PIPELINE_JOB_PAGES: usize = 25;
 
@@ -65,11 +69,24 @@ impl GitLabClient {
         })
     }
 
+    /// Create a client for `preferred_account` or the first known account in `storage`.
+    ///
+    /// If there is no such account, the token in the `GITLAB_TOKEN` environment variable is used,
+    /// for the GitLab instance in `GITLAB_HOST` or gitlab.com.
     pub fn from_storage(
         storage: &but_forge_storage::Controller,
         preferred_account: Option<&crate::GitlabAccountIdentifier>,
     ) -> anyhow::Result<Self> {
-        let account_id = resolve_account(preferred_account, storage)?;
+        let account_id = match resolve_account(preferred_account, storage) {
+            Ok(account_id) => account_id,
+            Err(err) => {
+                return Self::from_token_and_host(
+                    std::env::var(GITLAB_TOKEN_ENV).ok(),
+                    host_from_env(),
+                )
+                .unwrap_or(Err(err));
+            }
+        };
         if let Some(access_token) = crate::token::get_gl_access_token(&account_id, storage)? {
             account_id.client(&access_token)
         } else {
@@ -79,6 +96,16 @@ impl GitLabClient {
         }
     }
 
+    /// Create a client authenticated with `token` for `host` or gitlab.com, or `None` if there is no token.
+    fn from_token_and_host(token: Option<String>, host: Option<String>) -> Option<Result<Self>> {
+        let token = Sensitive(token.filter(|token| !token.is_empty())?);
+        Some(match host.filter(|host| !host.is_empty()) {
+            Some(host) if host.contains("://") => Self::new_with_host_override(&token, &host),
+            Some(host) => Self::new_with_host_override(&token, &format!("https://{host}")),
+            None => Self::new(&token),
+        })
+    }
+
     pub fn new_with_host_override(access_token: &Sensitive<String>, host: &str) -> Result<Self> {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -1047,6 +1074,14 @@ fn source_project_differs_from_target(
     source_project_id != target_project_id
 }
 
+/// Return the host of the self-hosted GitLab instance to use with the `GITLAB_TOKEN` environment
+/// variable, as configured in the `GITLAB_HOST` environment variable.
+pub fn host_from_env() -> Option<String> {
+    std::env::var(GITLAB_HOST_ENV)
+        .ok()
+        .filter(|host| !host.is_empty())
+}
+
 pub(crate) fn resolve_account(
     preferred_account: Option<&crate::GitlabAccountIdentifier>,
     storage: &but_forge_storage::Controller,
@@ -1075,7 +1110,7 @@ pub(crate) fn resolve_account(
 #[cfg(test)]
 mod tests {
     use super::{
-        GitLabMergeRequest, GitLabPipelineJob, GitLabPipelineRef, MergeRequest,
+        GitLabClient, GitLabMergeRequest, GitLabPipelineJob, GitLabPipelineRef, MergeRequest,
         next_page_from_headers, normalize_pipeline_jobs, repo_owner_from_path_with_namespace,
         update_draft_state_in_title,
     };
@@ -1103,6 +1138,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn client_from_token_and_host() {
+        assert!(
+            GitLabClient::from_token_and_host(None, Some("gitlab.example.com".into())).is_none()
+        );
+        assert!(GitLabClient::from_token_and_host(Some(String::new()), None).is_none());
+
+        let client = GitLabClient::from_token_and_host(Some("token".into()), None)
+            .expect("token is set")
+            .unwrap();
+        assert_eq!(client.base_url, "https://gitlab.com/api/v4");
+
+        for host in ["gitlab.example.com", "https://gitlab.example.com/"] {
+            let client = GitLabClient::from_token_and_host(Some("token".into()), Some(host.into()))
+                .expect("token is set")
+                .unwrap();
+            assert_eq!(client.base_url, "https://gitlab.example.com/api/v4");
+        }
+    }
+
     #[test]
     fn reads_next_page_from_headers() {
         let mut headers = HeaderMap::new();
@@ -8,6 +8,7 @@ pub use client::{
     CreateMergeRequestParams, GitLabClient, GitLabLabel, GitLabPipelineJob, GitLabPipelineRef,
     GitLabProject, GitLabUser, MergeMergeRequestParams, MergeRequest, MergeRequestMergeStatus,
     SetMergeRequestAutoMergeParams, SetMergeRequestDraftStateParams, UpdateMergeRequestParams,
+    host_from_env,
 };
 pub use project::{GitLabProjectId, fetch_project};
 mod token;
@@ -396,6 +396,18 @@ impl Graph {
         segments.try_fold(first, |base, segment| self.find_merge_base(base, segment))
     }
 
+    /// Like [`Self::find_merge_base_octopus()`], but also return the commit to use as merge-base,
+    /// which is the [first reachable commit](Self::tip_skip_empty()) of the merge-base segment.
+    ///
+    /// Returns `None` if there is no merge-base, or if no commit is unambiguously reachable from it.
+    pub fn find_merge_base_octopus_tip(
+        &self,
+        segments: impl IntoIterator<Item = SegmentIndex>,
+    ) -> Option<(SegmentIndex, &Commit)> {
+        let base = self.find_merge_base_octopus(segments)?;
+        Some((base, self.tip_skip_empty(base)?))
+    }
+
     /// Like [`Self::find_merge_base_octopus()`], but works with object ids of `commits`,
     /// returning the id of the commit that is the merge-base.
     pub fn find_merge_base_octopus_by_commit_id(
@@ -1357,3 +1369,132 @@ impl std::ops::DerefMut for Graph {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Commit, CommitFlags, Graph, Segment, SegmentIndex};
+
+    /// A small deterministic pseudo-random number generator, to not depend on a crate for it.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn below(&mut self, max: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % max as u64) as usize
+        }
+    }
+
+    /// Generate a graph of `count` single-commit segments, where each segment but the first has one or two
+    /// parents among the segments generated before it. Thus, the first segment is the only root.
+    fn generated_graph(rng: &mut XorShift, count: usize) -> (Graph, Vec<SegmentIndex>) {
+        let parents: Vec<Vec<usize>> = (0..count)
+            .map(|idx| {
+                if idx == 0 {
+                    return Vec::new();
+                }
+                let mut parents = vec![rng.below(idx)];
+                let second = rng.below(idx);
+                if rng.below(3) == 0 && !parents.contains(&second) {
+                    parents.push(second);
+                }
+                parents
+            })
+            .collect();
+        // Children have higher indices than their parents, and have to have a lower generation.
+        let mut generations = vec![0; count];
+        for idx in (0..count).rev() {
+            for &parent in &parents[idx] {
+                generations[parent] = generations[parent].max(generations[idx] + 1);
+            }
+        }
+        let commit_id = |idx: usize| {
+            gix::ObjectId::from_hex(format!("{:040x}", idx + 1).as_bytes()).expect("valid hex")
+        };
+
+        let mut graph = Graph::default();
+        let segments: Vec<_> = (0..count)
+            .map(|idx| {
+                graph.insert_segment(Segment {
+                    generation: generations[idx],
+                    commits: vec![Commit {
+                        id: commit_id(idx),
+                        parent_ids: parents[idx].iter().copied().map(commit_id).collect(),
+                        refs: Vec::new(),
+                        flags: CommitFlags::empty(),
+                    }],
+                    ..Default::default()
+                })
+            })
+            .collect();
+        for (idx, parents) in parents.iter().enumerate() {
+            for (order, &parent) in parents.iter().enumerate() {
+                graph.connect_segments_with_ids(
+                    segments[idx],
+                    0,
+                    None,
+                    segments[parent],
+                    0,
+                    None,
+                    order as u32,
+                );
+            }
+        }
+        (graph, segments)
+    }
+
+    /// Return `true` if `ancestor` can be reached from `descendant`, or if both are the same.
+    fn is_ancestor_or_identity(
+        graph: &Graph,
+        ancestor: SegmentIndex,
+        descendant: SegmentIndex,
+    ) -> bool {
+        let mut queue = vec![descendant];
+        let mut seen = std::collections::BTreeSet::new();
+        while let Some(sidx) = queue.pop() {
+            if sidx == ancestor {
+                return true;
+            }
+            if seen.insert(sidx) {
+                queue.extend(
+                    graph
+                        .inner
+                        .neighbors_directed(sidx, petgraph::Direction::Outgoing),
+                );
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn octopus_merge_base_matches_pairwise_merge_bases_on_generated_graphs() {
+        let mut rng = XorShift(0x5eed_cafe_f00d_beef);
+        for _ in 0..200 {
+            let count = 2 + rng.below(14);
+            let (graph, segments) = generated_graph(&mut rng, count);
+            let tips: Vec<_> = (0..2 + rng.below(3))
+                .map(|_| segments[rng.below(count)])
+                .collect();
+
+            let pairwise = tips[1..]
+                .iter()
+                .try_fold(tips[0], |base, &tip| graph.find_merge_base(base, tip));
+            let octopus = graph.find_merge_base_octopus(tips.iter().copied());
+            assert_eq!(octopus, pairwise, "{tips:?}");
+
+            let base = octopus.expect("all segments share the first one as root");
+            for &tip in &tips {
+                assert!(
+                    is_ancestor_or_identity(&graph, base, tip),
+                    "the base {base:?} must be reachable from {tip:?}"
+                );
+            }
+            let (tip_base, commit) = graph
+                .find_merge_base_octopus_tip(tips.iter().copied())
+                .expect("each segment has a commit");
+            assert_eq!(tip_base, base);
+            assert_eq!(Some(commit.id), graph[base].tip());
+        }
+    }
+}
@@ -25,12 +25,39 @@ pub mod commit {
     const GITBUTLER_INTEGRATION_COMMIT_TITLE: &str = "GitButler Integration Commit";
     const GITBUTLER_WORKSPACE_COMMIT_TITLE: &str = "GitButler Workspace Commit";
 
-    /// Return `true` if this `commit_message` indicates a workspace commit managed by GitButler.
+    /// The titles that GitButler gave its workspace commits over time, which all mark them as managed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum WorkspaceCommitGeneration {
+        /// The commit is titled `GitButler Integration Commit`, as written by older versions which
+        /// called the workspace the *integration branch*.
+        ///
+        /// It's replaced by a commit of the current generation the next time the workspace commit is created.
+        Integration,
+        /// The commit is titled `GitButler Workspace Commit`, as written by the current version.
+        Workspace,
+    }
+
+    /// Return the generation of the workspace commit with `commit_message`, or `None` if it's not
+    /// a workspace commit managed by GitButler.
+    pub fn workspace_commit_generation_by_message(
+        commit_message: &BStr,
+    ) -> Option<WorkspaceCommitGeneration> {
+        let message = gix::objs::commit::MessageRef::from_bytes(commit_message);
+        let title = message.title.trim().as_bstr();
+        if title == GITBUTLER_WORKSPACE_COMMIT_TITLE {
+            Some(WorkspaceCommitGeneration::Workspace)
+        } else if title == GITBUTLER_INTEGRATION_COMMIT_TITLE {
+            Some(WorkspaceCommitGeneration::Integration)
+        } else {
+            None
+        }
+    }
+
+    /// Return `true` if this `commit_message` indicates a workspace commit managed by GitButler, of any
+    /// [generation](workspace_commit_generation_by_message()).
     /// If `false`, this is the tip of the stack itself which will be put underneath a *managed* workspace commit
     /// once another branch is added to the workspace.
     pub fn is_managed_workspace_by_message(commit_message: &BStr) -> bool {
-        let message = gix::objs::commit::MessageRef::from_bytes(commit_message);
-        let title = message.title.trim().as_bstr();
-        title == GITBUTLER_INTEGRATION_COMMIT_TITLE || title == GITBUTLER_WORKSPACE_COMMIT_TITLE
+        workspace_commit_generation_by_message(commit_message).is_some()
     }
 }
@@ -383,6 +383,46 @@ fn merge_base_apis_can_resolve_segments_by_first_commit_id() -> anyhow::Result<(
     Ok(())
 }
 
+#[test]
+fn octopus_merge_base_tip_is_the_first_commit_of_the_merge_base() -> anyhow::Result<()> {
+    let (repo, meta) = read_only_in_memory_scenario("four-diamond")?;
+    let graph = Graph::from_head(
+        &repo,
+        &*meta,
+        but_core::ref_metadata::ProjectMeta::default(),
+        standard_options(),
+    )?
+    .validated()?;
+
+    let merged = segment_id_by_ref_name(&graph, "refs/heads/merged")?;
+    let a = segment_id_by_ref_name(&graph, "refs/heads/A")?;
+    let b = segment_id_by_ref_name(&graph, "refs/heads/B")?;
+    let c = segment_id_by_ref_name(&graph, "refs/heads/C")?;
+    let d = segment_id_by_ref_name(&graph, "refs/heads/D")?;
+    let main = segment_id_by_ref_name(&graph, "refs/heads/main")?;
+    let main_id = repo.rev_parse_single("main")?.detach();
+    let a_id = repo.rev_parse_single("A")?.detach();
+
+    let (base, commit) = graph
+        .find_merge_base_octopus_tip([a, c, merged])
+        .context("the branches share main")?;
+    assert_eq!(base, main);
+    assert_eq!(commit.id, main_id);
+
+    let (base, commit) = graph
+        .find_merge_base_octopus_tip([b, d])
+        .context("the branches share main")?;
+    assert_eq!(base, main);
+    assert_eq!(commit.id, main_id);
+
+    let (base, commit) = graph
+        .find_merge_base_octopus_tip([merged, a])
+        .context("A is reachable from merged")?;
+    assert_eq!(base, a);
+    assert_eq!(commit.id, a_id, "the nearest merge-base is used");
+    Ok(())
+}
+
 fn segment_id_by_ref_name(graph: &Graph, name: &str) -> anyhow::Result<SegmentIndex> {
     let full_name = ref_name(name)?;
     graph
@@ -0,0 +1,111 @@
+//! Hunk assignments grouped by the file they belong to, as needed to show or commit whole files.
+
+use std::collections::BTreeMap;
+
+use bstr::BString;
+use but_core::ref_metadata::StackId;
+use serde::Serialize;
+
+use crate::HunkAssignment;
+
+/// All hunk assignments of the uncommitted file at `path`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAssignment {
+    /// The path of the file that all `assignments` belong to.
+    #[serde(with = "but_serde::bstring_lossy")]
+    pub path: BString,
+    /// The assignments of the hunks of the file, in the order they were given.
+    pub assignments: Vec<HunkAssignment>,
+}
+
+impl FileAssignment {
+    /// Return a copy of this file with only the hunks assigned to `stack_id`, or only the
+    /// unassigned hunks if it's `None`. There may be no assignments left.
+    pub fn filter_by_stack(&self, stack_id: Option<StackId>) -> FileAssignment {
+        FileAssignment {
+            path: self.path.clone(),
+            assignments: self
+                .assignments
+                .iter()
+                .filter(|assignment| assignment.stack_id == stack_id)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Group `assignments` by the path of their file, keeping the order of the hunks of each file.
+pub fn group_by_file(assignments: &[HunkAssignment]) -> BTreeMap<BString, FileAssignment> {
+    let mut by_file = BTreeMap::<BString, FileAssignment>::new();
+    for assignment in assignments {
+        by_file
+            .entry(assignment.path_bytes.clone())
+            .or_insert_with(|| FileAssignment {
+                path: assignment.path_bytes.clone(),
+                assignments: Vec::new(),
+            })
+            .assignments
+            .push(assignment.clone());
+    }
+    by_file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk_starts(file: &FileAssignment) -> Vec<u32> {
+        file.assignments
+            .iter()
+            .filter_map(|assignment| assignment.hunk_header.map(|header| header.new_start))
+            .collect()
+    }
+
+    #[test]
+    fn hunks_of_a_file_are_grouped_in_order() {
+        let assignments = [
+            HunkAssignment::new("b.rs", 20, 2, None, None),
+            HunkAssignment::new("a.rs", 1, 2, None, None),
+            HunkAssignment::new("b.rs", 1, 2, None, None),
+            HunkAssignment::new("b.rs", 40, 2, None, None),
+        ];
+        let by_file = group_by_file(&assignments);
+
+        assert_eq!(
+            by_file.keys().collect::<Vec<_>>(),
+            ["a.rs", "b.rs"],
+            "files are sorted by path"
+        );
+        assert_eq!(hunk_starts(&by_file["a.rs"]), [1]);
+        assert_eq!(
+            hunk_starts(&by_file["b.rs"]),
+            [20, 1, 40],
+            "hunks keep the order they were given in"
+        );
+        assert!(group_by_file(&[]).is_empty());
+    }
+
+    #[test]
+    fn mixed_assignments_of_a_file_can_be_filtered_by_stack() {
+        let assignments = [
+            HunkAssignment::new("a.rs", 1, 2, Some(1), None),
+            HunkAssignment::new("a.rs", 20, 2, None, None),
+            HunkAssignment::new("a.rs", 40, 2, Some(2), None),
+            HunkAssignment::new("a.rs", 60, 2, Some(1), None),
+        ];
+        let file = &group_by_file(&assignments)["a.rs"];
+        let stack_id = |n: usize| HunkAssignment::new("", 0, 0, Some(n), None).stack_id;
+
+        assert_eq!(hunk_starts(&file.filter_by_stack(stack_id(1))), [1, 60]);
+        assert_eq!(hunk_starts(&file.filter_by_stack(stack_id(2))), [40]);
+        assert_eq!(
+            hunk_starts(&file.filter_by_stack(None)),
+            [20],
+            "`None` selects the unassigned hunks"
+        );
+        let other = file.filter_by_stack(stack_id(3));
+        assert_eq!(other.path, "a.rs");
+        assert!(other.assignments.is_empty());
+    }
+}
@@ -9,7 +9,9 @@
 //!
 //! set_assignments
 
+mod file_assignment;
 mod reconcile;
+mod rules;
 mod state;
 
 use std::collections::{BTreeMap, HashMap};
@@ -19,8 +21,10 @@ use bstr::{BString, ByteSlice};
 use but_core::{DiffSpec, HunkHeader, TreeChange, UnifiedPatch, ref_metadata::StackId};
 use but_db::{HunkAssignmentsHandle, HunkAssignmentsHandleMut};
 use but_hunk_dependency::ui::HunkDependencies;
+pub use file_assignment::{FileAssignment, group_by_file};
 use gix::ObjectId;
 use reconcile::MultipleOverlapping;
+pub use rules::{AssignmentRule, add_rule, remove_rules, rule_for, rules};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use uuid::Uuid;
@@ -424,15 +428,12 @@ pub fn assign(
     }
 
     // Reconcile worktree with the persisted assignments
-    let mut persisted_assignments = state::assignments(db.to_ref())?;
-    backfill_branch_ref_from_legacy_stack_id(&mut persisted_assignments, workspace);
-    let with_worktree = reconcile::assignments(
+    let with_worktree = reconcile_with_worktree(
+        db.to_ref(),
+        workspace,
+        &worktree_changes,
         &worktree_assignments,
-        &persisted_assignments,
-        &branches_by_stack,
-        MultipleOverlapping::SetMostLines,
-        true,
-    );
+    )?;
 
     // Reconcile with the requested changes
     let request_assignments = requests_to_assignments(requests, workspace)?;
@@ -503,7 +504,12 @@ fn reconcile_worktree_changes_with_worktree(
             diff.ok().flatten(),
         ));
     }
-    let mut reconciled = reconcile_with_worktree(db.to_ref(), workspace, &worktree_assignments)?;
+    let mut reconciled = reconcile_with_worktree(
+        db.to_ref(),
+        workspace,
+        &worktree_changes,
+        &worktree_assignments,
+    )?;
 
     derive_stack_ids(&mut reconciled, workspace);
     state::set_assignments(db, reconciled.clone())?;
@@ -524,23 +530,39 @@ fn reconcile_worktree_changes_with_worktree(
 /// If a stack is no longer present in the workspace (either unapplied or deleted), any assignments to it are removed.
 ///
 /// This needs to be ran only after the worktree has changed.
-#[instrument(skip(db, workspace, worktree_assignments), err(Debug))]
+///
+/// Assignments of files that were renamed are carried over to their new path.
+///
+/// New hunks that nothing was known about are assigned according to the [rules](rules()).
+#[instrument(
+    skip(db, workspace, worktree_changes, worktree_assignments),
+    err(Debug)
+)]
 fn reconcile_with_worktree(
     db: HunkAssignmentsHandle,
     workspace: &but_graph::Workspace,
+    worktree_changes: &[TreeChange],
     worktree_assignments: &[HunkAssignment],
 ) -> Result<Vec<HunkAssignment>> {
     let branches_by_stack = workspace_branches_by_stack(workspace);
 
+    let rules = rules::rules(&db)?;
     let mut persisted_assignments = state::assignments(db)?;
     backfill_branch_ref_from_legacy_stack_id(&mut persisted_assignments, workspace);
-    let with_worktree = reconcile::assignments(
+    follow_renames(&mut persisted_assignments, worktree_changes);
+    let mut with_worktree = reconcile::assignments(
         worktree_assignments,
         &persisted_assignments,
         &branches_by_stack,
         MultipleOverlapping::SetMostLines,
         true,
     );
+    rules::apply_to_new_hunks(
+        &mut with_worktree,
+        &persisted_assignments,
+        &rules,
+        branches_by_stack.values().flatten(),
+    );
 
     Ok(with_worktree)
 }
@@ -563,6 +585,32 @@ fn backfill_branch_ref_from_legacy_stack_id(
     }
 }
 
+/// Move the `assignments` of files that were renamed in `worktree_changes` to their new path,
+/// so the hunks of a renamed file stay assigned where they were before the rename.
+///
+/// Renames whose new path already has assignments were followed before, so assignments at their
+/// previous path belong to a file that was created there since, and stay where they are.
+fn follow_renames(assignments: &mut [HunkAssignment], worktree_changes: &[TreeChange]) {
+    for change in worktree_changes {
+        let Some(previous_path) = change.previous_path() else {
+            continue;
+        };
+        if assignments
+            .iter()
+            .any(|assignment| assignment.path_bytes == change.path)
+        {
+            continue;
+        }
+        for assignment in assignments
+            .iter_mut()
+            .filter(|assignment| assignment.path_bytes.as_bstr() == previous_path)
+        {
+            assignment.path = change.path.to_str_lossy().into();
+            assignment.path_bytes = change.path.clone();
+        }
+    }
+}
+
 /// Collect the workspace branches keyed by stack for reconciliation validation.
 fn workspace_branches_by_stack(
     workspace: &but_graph::Workspace,
@@ -834,32 +882,18 @@ pub fn diff_specs_from_assignments_with_changes(
 pub fn convert_assignments_to_diff_specs(
     assignments: &[HunkAssignment],
 ) -> anyhow::Result<Vec<DiffSpec>> {
-    let mut specs_by_path: BTreeMap<BString, Vec<HunkAssignment>> = BTreeMap::new();
-
-    // Group assignments by file path
-    for assignment in assignments {
-        specs_by_path
-            .entry(assignment.path_bytes.clone())
-            .or_default()
-            .push(assignment.clone());
-    }
-
-    // Convert to DiffSpecs
-    let mut diff_specs = Vec::new();
-    for (path, hunks) in specs_by_path {
-        let mut hunk_headers = Vec::new();
-        for hunk in hunks {
-            if let Some(header) = hunk.hunk_header {
-                hunk_headers.push(header);
-            }
-        }
-
-        diff_specs.push(DiffSpec {
+    let diff_specs = group_by_file(assignments)
+        .into_values()
+        .map(|file| DiffSpec {
             previous_path: None, // TODO: Handle renames
-            path: path.clone(),
-            hunk_headers,
-        });
-    }
+            hunk_headers: file
+                .assignments
+                .iter()
+                .filter_map(|hunk| hunk.hunk_header)
+                .collect(),
+            path: file.path,
+        })
+        .collect();
 
     Ok(diff_specs)
 }
@@ -1567,6 +1601,79 @@ mod tests {
         assert_eq!(result[0].branch_ref_bytes, None);
     }
 
+    fn rename(previous_path: &str, path: &str) -> TreeChange {
+        let state = but_core::ChangeState {
+            id: gix::hash::Kind::Sha1.null(),
+            kind: gix::object::tree::EntryKind::Blob,
+        };
+        TreeChange {
+            path: path.into(),
+            status: but_core::TreeStatus::Rename {
+                previous_path: previous_path.into(),
+                previous_state: state,
+                state,
+                flags: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_follow_renames_carries_assignments_to_new_path() {
+        let mut previous_assignments = vec![
+            HunkAssignment::new("old.rs", 10, 5, Some(1), Some(1))
+                .with_branch_ref_bytes(Some("refs/heads/feature")),
+            HunkAssignment::new("other.rs", 1, 5, Some(2), Some(2)),
+        ];
+        follow_renames(&mut previous_assignments, &[rename("old.rs", "new.rs")]);
+        assert_eq!(previous_assignments[0].path, "new.rs");
+        assert_eq!(previous_assignments[0].path_bytes, "new.rs");
+        assert_eq!(previous_assignments[1].path, "other.rs");
+
+        let worktree_assignments = vec![HunkAssignment::new("new.rs", 10, 6, None, None)];
+        let branches = HashMap::from([(stack_id_seq(1), vec![branch_ref("refs/heads/feature")])]);
+        let result = reconcile::assignments(
+            &worktree_assignments,
+            &previous_assignments,
+            &branches,
+            MultipleOverlapping::SetMostLines,
+            true,
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].id,
+            Some(id_seq(1)),
+            "the renamed hunk keeps its identity"
+        );
+        assert_eq!(
+            result[0].branch_ref_bytes,
+            Some(branch_ref("refs/heads/feature")),
+            "and its branch, as if it had never been renamed"
+        );
+    }
+
+    #[test]
+    fn test_follow_renames_only_once_so_a_recreated_previous_path_keeps_its_assignments() {
+        let mut previous_assignments = vec![
+            HunkAssignment::new("old.rs", 10, 5, Some(1), Some(1))
+                .with_branch_ref_bytes(Some("refs/heads/feature")),
+        ];
+        let changes = [rename("old.rs", "new.rs")];
+        follow_renames(&mut previous_assignments, &changes);
+        assert_eq!(previous_assignments[0].path, "new.rs");
+
+        // `old.rs` was created again as a new file and assigned elsewhere.
+        previous_assignments.push(
+            HunkAssignment::new("old.rs", 0, 3, Some(2), Some(2))
+                .with_branch_ref_bytes(Some("refs/heads/other")),
+        );
+        follow_renames(&mut previous_assignments, &changes);
+        assert_eq!(previous_assignments[0].path, "new.rs");
+        assert_eq!(
+            previous_assignments[1].path, "old.rs",
+            "the rename was followed already, so the new file at the previous path stays put"
+        );
+    }
+
     #[test]
     fn test_reconcile_clears_stale_branch_ref_bytes() {
         // When a branch is deleted from the workspace but the stack remains,
@@ -0,0 +1,269 @@
+//! Rules that assign new hunks to a branch by their path.
+//!
+//! Whenever hunks appear in the worktree that weren't known before, they are assigned to the
+//! branch of the most specific rule matching their path, as long as that branch is applied.
+//! Hunks that were already known keep their assignment, so unassigning a hunk by hand sticks.
+
+use std::cmp::Reverse;
+
+use anyhow::Result;
+use bstr::{BStr, BString, ByteSlice};
+use but_db::{HunkAssignmentsHandle, HunkAssignmentsHandleMut};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::HunkAssignment;
+
+/// A rule to assign new hunks whose path matches `path_glob` to `branch_ref`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentRule {
+    /// A stable identifier for the rule.
+    pub id: Uuid,
+    /// A glob like `crates/gui/**`, where `*` doesn't match `/` but `**` does, or a plain path
+    /// which matches the file itself and everything below it if it's a directory.
+    pub path_glob: String,
+    /// The branch that matching hunks are assigned to.
+    #[serde(with = "but_serde::fullname_lossy")]
+    pub branch_ref: gix::refs::FullName,
+}
+
+impl TryFrom<but_db::HunkAssignmentRule> for AssignmentRule {
+    type Error = anyhow::Error;
+    fn try_from(value: but_db::HunkAssignmentRule) -> Result<Self, Self::Error> {
+        Ok(AssignmentRule {
+            id: Uuid::parse_str(&value.id)?,
+            path_glob: value.path_glob,
+            branch_ref: gix::refs::FullName::try_from(BString::from(value.branch_ref_bytes))
+                .map_err(|e| anyhow::anyhow!("Failed to parse branch_ref_bytes: {e}"))?,
+        })
+    }
+}
+
+impl From<AssignmentRule> for but_db::HunkAssignmentRule {
+    fn from(value: AssignmentRule) -> Self {
+        but_db::HunkAssignmentRule {
+            id: value.id.to_string(),
+            path_glob: value.path_glob,
+            branch_ref_bytes: value.branch_ref.into_inner().into(),
+        }
+    }
+}
+
+impl AssignmentRule {
+    /// Whether the file at `path` is matched by this rule.
+    pub fn matches(&self, path: &BStr) -> bool {
+        let glob = self.path_glob.trim_end_matches('/');
+        if is_glob(glob) {
+            gix::glob::wildmatch(
+                glob.into(),
+                path,
+                gix::glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            )
+        } else {
+            path == glob
+                || path
+                    .strip_prefix(glob.as_bytes())
+                    .is_some_and(|rest| rest.starts_with(b"/"))
+        }
+    }
+
+    /// How specific the rule is, with greater values being more specific: the longer the literal
+    /// start of the glob, the more specific, and then the fewer wildcards it has.
+    fn specificity(&self) -> (usize, Reverse<usize>) {
+        let glob = self.path_glob.trim_end_matches('/');
+        let literal_len = glob.find(['*', '?', '[']).unwrap_or(glob.len());
+        let wildcards = glob.matches(['*', '?', '[']).count();
+        (literal_len, Reverse(wildcards))
+    }
+}
+
+/// Whether `path_glob` contains wildcards.
+fn is_glob(path_glob: &str) -> bool {
+    path_glob.contains(['*', '?', '['])
+}
+
+/// The rule among `rules` that decides where the file at `path` goes, if any matches.
+///
+/// The most specific rule wins, and of equally specific rules the one added first.
+/// `rules` are expected in the order they were added, as returned by [`rules()`].
+pub fn rule_for<'a>(rules: &'a [AssignmentRule], path: &BStr) -> Option<&'a AssignmentRule> {
+    rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.matches(path))
+        .max_by_key(|(idx, rule)| (rule.specificity(), Reverse(*idx)))
+        .map(|(_, rule)| rule)
+}
+
+/// Return all rules in the order they were added.
+pub fn rules(db: &HunkAssignmentsHandle) -> Result<Vec<AssignmentRule>> {
+    db.list_rules()?
+        .into_iter()
+        .map(AssignmentRule::try_from)
+        .collect()
+}
+
+/// Persist a new rule that assigns hunks whose path matches `path_glob` to `branch_ref`, and
+/// return it.
+///
+/// Only hunks that appear from now on are assigned by the rule, so the caller is expected to
+/// assign the matching unassigned hunks that already exist.
+pub fn add_rule(
+    db: HunkAssignmentsHandleMut,
+    path_glob: String,
+    branch_ref: gix::refs::FullName,
+) -> Result<AssignmentRule> {
+    if path_glob.trim_end_matches('/').is_empty() {
+        anyhow::bail!("The path of a rule must not be empty");
+    }
+    let rule = AssignmentRule {
+        id: Uuid::new_v4(),
+        path_glob,
+        branch_ref,
+    };
+    db.insert_rule(rule.clone().into())?;
+    Ok(rule)
+}
+
+/// Delete the rules with the given `ids`.
+///
+/// Hunks that were assigned by them keep their assignment.
+pub fn remove_rules(db: HunkAssignmentsHandleMut, ids: &[Uuid]) -> Result<()> {
+    let ids: Vec<_> = ids.iter().map(Uuid::to_string).collect();
+    db.delete_rules(&ids)?;
+    Ok(())
+}
+
+/// Assign the unassigned hunks in `assignments` that are new, i.e. don't intersect any of the
+/// `persisted` ones, according to `rules`, as long as the branch of the rule is one of
+/// `valid_branch_refs`.
+pub(crate) fn apply_to_new_hunks<'a>(
+    assignments: &mut [HunkAssignment],
+    persisted: &[HunkAssignment],
+    rules: &[AssignmentRule],
+    valid_branch_refs: impl Iterator<Item = &'a gix::refs::FullName> + Clone,
+) {
+    if rules.is_empty() {
+        return;
+    }
+    for assignment in assignments
+        .iter_mut()
+        .filter(|assignment| assignment.branch_ref_bytes.is_none())
+    {
+        if persisted
+            .iter()
+            .any(|known| known.intersects(assignment.clone()))
+        {
+            continue;
+        }
+        if let Some(rule) = rule_for(rules, assignment.path_bytes.as_bstr())
+            && valid_branch_refs
+                .clone()
+                .any(|branch_ref| *branch_ref == rule.branch_ref)
+        {
+            assignment.branch_ref_bytes = Some(rule.branch_ref.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path_glob: &str, branch: &str) -> AssignmentRule {
+        AssignmentRule {
+            id: Uuid::nil(),
+            path_glob: path_glob.into(),
+            branch_ref: format!("refs/heads/{branch}")
+                .try_into()
+                .expect("valid ref name"),
+        }
+    }
+
+    fn branch_for<'a>(rules: &'a [AssignmentRule], path: &str) -> Option<&'a str> {
+        rule_for(rules, path.into()).map(|rule| {
+            rule.branch_ref
+                .as_bstr()
+                .to_str()
+                .expect("valid UTF-8")
+                .trim_start_matches("refs/heads/")
+        })
+    }
+
+    #[test]
+    fn plain_paths_match_the_file_and_everything_below_it() {
+        let rules = [rule("docs", "docs"), rule("README.md", "readme")];
+        assert_eq!(branch_for(&rules, "docs/guide.md"), Some("docs"));
+        assert_eq!(branch_for(&rules, "docs/deep/guide.md"), Some("docs"));
+        assert_eq!(branch_for(&rules, "docs"), Some("docs"));
+        assert_eq!(branch_for(&rules, "docsy/guide.md"), None);
+        assert_eq!(branch_for(&rules, "README.md"), Some("readme"));
+        assert_eq!(branch_for(&rules, "crates/README.md"), None);
+    }
+
+    #[test]
+    fn globs_only_cross_directories_with_double_stars() {
+        let rules = [rule("crates/*.toml", "toml"), rule("crates/gui/**", "ui")];
+        assert_eq!(branch_for(&rules, "crates/Cargo.toml"), Some("toml"));
+        assert_eq!(branch_for(&rules, "crates/but/Cargo.toml"), None);
+        assert_eq!(branch_for(&rules, "crates/gui/src/app.rs"), Some("ui"));
+    }
+
+    #[test]
+    fn the_most_specific_rule_wins_and_then_the_earliest() {
+        let rules = [
+            rule("crates/**", "all-crates"),
+            rule("crates/gui/**", "ui"),
+            rule("crates/gui/*.rs", "ui-rust"),
+            rule("crates/gui/**", "ui-later"),
+        ];
+        assert_eq!(branch_for(&rules, "crates/but/lib.rs"), Some("all-crates"));
+        assert_eq!(
+            branch_for(&rules, "crates/gui/lib.rs"),
+            Some("ui-rust"),
+            "with the same literal start, fewer wildcards are more specific"
+        );
+        assert_eq!(
+            branch_for(&rules, "crates/gui/deep/lib.rs"),
+            Some("ui"),
+            "of equally specific rules, the first one wins"
+        );
+
+        let rules = [
+            rule("crates/gui/**", "ui"),
+            rule("crates/gui/app.rs", "app"),
+        ];
+        assert_eq!(
+            branch_for(&rules, "crates/gui/app.rs"),
+            Some("app"),
+            "a plain path is more specific than a glob with the same start"
+        );
+    }
+
+    #[test]
+    fn only_new_unassigned_hunks_on_applied_branches_are_assigned() {
+        let rules = [rule("docs/**", "docs"), rule("gone/**", "unapplied")];
+        let docs_ref: gix::refs::FullName = "refs/heads/docs".try_into().unwrap();
+        let mut assignments = [
+            HunkAssignment::new("docs/new.md", 1, 2, None, None),
+            HunkAssignment::new("docs/known.md", 1, 2, None, None),
+            HunkAssignment::new("gone/new.md", 1, 2, None, None),
+            HunkAssignment::new("src/new.rs", 1, 2, None, None),
+        ];
+        let persisted = [HunkAssignment::new("docs/known.md", 1, 2, None, None)];
+
+        apply_to_new_hunks(
+            &mut assignments,
+            &persisted,
+            &rules,
+            std::iter::once(&docs_ref),
+        );
+
+        let branches: Vec<_> = assignments
+            .iter()
+            .map(|assignment| assignment.branch_ref_bytes.as_ref())
+            .collect();
+        assert_eq!(branches, [Some(&docs_ref), None, None, None]);
+    }
+}
@@ -315,6 +315,7 @@ impl Snapshot {
                     pr_number: None,
                     archived: false,
                     review_id: None,
+                    description: None,
                 });
             }
 
@@ -676,6 +677,7 @@ impl RefMetadata for VirtualBranchesTomlMetadata {
                     pull_request: branch.pr_number,
                     review_id: branch.review_id.clone(),
                 },
+                description: branch.description.clone(),
             },
         })
     }
@@ -853,6 +855,7 @@ impl RefMetadata for VirtualBranchesTomlMetadata {
                     pr_number,
                     archived,
                     review_id,
+                    description,
                     ..
                 } = stack
                     .heads
@@ -868,6 +871,7 @@ impl RefMetadata for VirtualBranchesTomlMetadata {
                 self.snapshot.changed_at = Some(Instant::now());
                 *pr_number = value.review.pull_request;
                 *review_id = value.review.review_id.clone();
+                *description = value.description.clone();
                 if let Some((stack_idx, segment_idx)) = metadata_stack_indices {
                     let meta_stack = &ws.stacks[stack_idx];
                     stack.in_workspace = meta_stack.is_in_workspace();
@@ -1332,15 +1336,19 @@ fn branch_to_stack_branch(
     Branch {
         ref_info: _, // TODO: should change parent stack if it's the top.
         review,
+        description,
     }: &Branch,
     archived: bool,
 ) -> StackBranch {
-    StackBranch::new_with_zero_head(
-        ref_name.shorten().to_string(),
-        review.pull_request,
-        review.review_id.clone(),
-        archived,
-    )
+    StackBranch {
+        description: description.clone(),
+        ..StackBranch::new_with_zero_head(
+            ref_name.shorten().to_string(),
+            review.pull_request,
+            review.review_id.clone(),
+            archived,
+        )
+    }
 }
 
 /// Deterministically compare two stacks by their `order` field, using `name` and `id` as a tiebreaker.
@@ -382,6 +382,7 @@ fn legacy_to_snapshot(
                 pr_number,
                 archived,
                 review_id,
+                description,
             } = head;
             out_heads.push(VbStackHead {
                 stack_id: stack_id.to_string(),
@@ -391,6 +392,7 @@ fn legacy_to_snapshot(
                 pr_number: pr_number.map(|value| value as i64),
                 archived: *archived,
                 review_id: review_id.clone(),
+                description: description.clone(),
             });
         }
     }
@@ -504,6 +506,7 @@ fn snapshot_to_legacy(snapshot: &VirtualBranchesSnapshot) -> anyhow::Result<Virt
             pr_number,
             archived,
             review_id,
+            description,
         } = head;
         let stack_id = StackId::from_str(stack_id)
             .with_context(|| format!("Invalid stack id '{stack_id}'"))?;
@@ -525,6 +528,7 @@ fn snapshot_to_legacy(snapshot: &VirtualBranchesSnapshot) -> anyhow::Result<Virt
                 })?,
             archived: *archived,
             review_id: review_id.clone(),
+            description: description.clone(),
         });
     }
 
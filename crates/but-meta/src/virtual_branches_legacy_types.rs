@@ -195,6 +195,9 @@ mod stack {
 
         #[serde(default)]
         pub review_id: Option<String>,
+        /// A free-form description of the branch, as provided by the user.
+        #[serde(default)]
+        pub description: Option<String>,
     }
 
     impl StackBranch {
@@ -209,6 +212,7 @@ mod stack {
                 pr_number,
                 archived,
                 review_id,
+                description: None,
                 head: gix::hash::Kind::Sha1.null(),
             }
         }
@@ -587,6 +587,7 @@ fn set_workspace_stack_only_changes_are_written_on_drop() -> anyhow::Result<()>
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         0,
         true,
@@ -599,6 +600,7 @@ fn set_workspace_stack_only_changes_are_written_on_drop() -> anyhow::Result<()>
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         1,
         true,
@@ -638,6 +640,42 @@ fn set_workspace_stack_only_changes_are_written_on_drop() -> anyhow::Result<()>
     Ok(())
 }
 
+#[test]
+fn branch_description_roundtrips() -> anyhow::Result<()> {
+    let (mut store, _tmp) = empty_vb_store_rw()?;
+    let toml_path = store.path().to_owned();
+    let branch_name: gix::refs::FullName = "refs/heads/feat".try_into()?;
+
+    let mut branch = store.branch(branch_name.as_ref())?;
+    branch.description = Some("Adds the feature\n\nWith details.".into());
+    store.set_branch(&branch)?;
+    drop(store);
+
+    assert!(
+        std::fs::read_to_string(&toml_path)?.contains("description = "),
+        "the description is mirrored into the TOML file"
+    );
+
+    let mut store = VirtualBranchesTomlMetadata::from_path(&toml_path)?;
+    let mut branch = store.branch(branch_name.as_ref())?;
+    assert_eq!(
+        branch.description.as_deref(),
+        Some("Adds the feature\n\nWith details.")
+    );
+
+    branch.description = None;
+    store.set_branch(&branch)?;
+    drop(store);
+
+    let store = VirtualBranchesTomlMetadata::from_path(&toml_path)?;
+    assert_eq!(
+        store.branch(branch_name.as_ref())?.description,
+        None,
+        "descriptions can be removed again"
+    );
+    Ok(())
+}
+
 #[test]
 fn create_workspace_and_stacks_with_branches_from_scratch() -> anyhow::Result<()> {
     let (mut store, _tmp) = empty_vb_store_rw()?;
@@ -1575,6 +1613,7 @@ fn removes_duplicate_heads_even_if_they_point_to_the_same_commit() -> anyhow::Re
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         0,
         true,
@@ -1586,6 +1625,7 @@ fn removes_duplicate_heads_even_if_they_point_to_the_same_commit() -> anyhow::Re
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         1,
         true,
@@ -1628,6 +1668,7 @@ fn garbage_collect_removes_outside_workspace_stack_at_target() -> anyhow::Result
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         0,
         true,
@@ -1639,6 +1680,7 @@ fn garbage_collect_removes_outside_workspace_stack_at_target() -> anyhow::Result
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         1,
         false,
@@ -1674,6 +1716,7 @@ fn garbage_collect_removes_outside_workspace_stack_with_missing_head() -> anyhow
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         0,
         false,
@@ -1710,6 +1753,7 @@ fn garbage_collect_removes_outside_workspace_stack_with_broken_ref() -> anyhow::
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         }],
         0,
         false,
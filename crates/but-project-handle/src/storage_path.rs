@@ -24,12 +24,52 @@ pub fn gitbutler_storage_path(repo: &gix::Repository) -> anyhow::Result<PathBuf>
 }
 
 /// Return the path where per-project GitButler data should be stored for `repo` and `channel`.
+///
+/// The data lives in the git dir of the worktree, so state like hunk assignments is kept per worktree.
+/// Use [`gitbutler_shared_storage_path_for_channel()`] for data that all worktrees share.
 pub fn gitbutler_storage_path_for_channel(
     repo: &gix::Repository,
     channel: AppChannel,
 ) -> anyhow::Result<PathBuf> {
-    let git_dir = repo.git_dir();
-    let storage_key = storage_path_config_key_for_channel(&channel);
+    storage_path_in_git_dir(repo, repo.git_dir(), &channel)
+}
+
+/// Return the path where GitButler data that all worktrees of `repo` share should be stored.
+pub fn gitbutler_shared_storage_path(repo: &gix::Repository) -> anyhow::Result<PathBuf> {
+    gitbutler_shared_storage_path_for_channel(repo, AppChannel::new())
+}
+
+/// Return the path where GitButler data that all worktrees of `repo` share should be stored for `channel`,
+/// like `virtual_branches.toml` and the oplog.
+///
+/// This is the [storage path](gitbutler_storage_path_for_channel()) of the main worktree. Linked worktrees
+/// which already have their own `virtual_branches.toml` from before the data was shared keep using their
+/// own storage path so their stacks don't get lost.
+pub fn gitbutler_shared_storage_path_for_channel(
+    repo: &gix::Repository,
+    channel: AppChannel,
+) -> anyhow::Result<PathBuf> {
+    let storage_path = storage_path_in_git_dir(repo, repo.git_dir(), &channel)?;
+    let common_dir = repo.common_dir();
+    // Linked worktrees point to their common dir with a path like `.git/worktrees/<name>/../..`.
+    let common_dir =
+        gix::path::normalize(common_dir.into(), common_dir).unwrap_or(Cow::Borrowed(common_dir));
+    if common_dir.as_ref() == repo.git_dir() || storage_path.join(SHARED_DATA_FILE_NAME).exists() {
+        return Ok(storage_path);
+    }
+    storage_path_in_git_dir(repo, &common_dir, &channel)
+}
+
+/// A file in the shared storage path whose presence in the storage path of a linked worktree indicates
+/// that the worktree still uses its own data.
+const SHARED_DATA_FILE_NAME: &str = "virtual_branches.toml";
+
+fn storage_path_in_git_dir(
+    repo: &gix::Repository,
+    git_dir: &Path,
+    channel: &AppChannel,
+) -> anyhow::Result<PathBuf> {
+    let storage_key = storage_path_config_key_for_channel(channel);
 
     match repo.config_snapshot().trusted_path(storage_key) {
         Ok(Some(path)) => resolve_configured_storage_path(git_dir, path.as_ref()),
@@ -2,8 +2,9 @@ use std::path::{Path, PathBuf};
 
 use but_path::AppChannel;
 use but_project_handle::{
-    ProjectHandle, gitbutler_storage_path, gitbutler_storage_path_for_channel,
-    storage_path_config_key, storage_path_config_key_for_app_channel,
+    ProjectHandle, gitbutler_shared_storage_path, gitbutler_storage_path,
+    gitbutler_storage_path_for_channel, storage_path_config_key,
+    storage_path_config_key_for_app_channel,
 };
 use but_testsupport::{CommandExt as _, git, gix_testtools, open_repo};
 use gix_testtools::tempfile::TempDir;
@@ -100,6 +101,75 @@ fn storage_path_default_stays_in_git_dir() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn init_repo_with_linked_worktree() -> anyhow::Result<(TempDir, gix::Repository, gix::Repository)> {
+    let (tmp, repo) = init_repo()?;
+    git(&repo)
+        .args(["commit", "--allow-empty", "-m", "initial"])
+        .run();
+    let linked_dir = tmp.path().join("linked");
+    git(&repo)
+        .args(["worktree", "add", "-b", "linked"])
+        .arg(&linked_dir)
+        .run();
+    let linked_repo = open_repo(&linked_dir)?;
+    assert_ne!(
+        linked_repo.git_dir(),
+        repo.git_dir(),
+        "the linked worktree has its own git dir"
+    );
+    Ok((tmp, repo, linked_repo))
+}
+
+#[test]
+fn storage_path_of_linked_worktree_is_private_to_the_worktree() -> anyhow::Result<()> {
+    let (_tmp, repo, linked_repo) = init_repo_with_linked_worktree()?;
+
+    assert_eq!(
+        gitbutler_storage_path(&linked_repo)?,
+        linked_repo.git_dir().join(default_storage_dir_name()),
+        "per-worktree data like assignments stays with the worktree"
+    );
+    assert_ne!(
+        gitbutler_storage_path(&linked_repo)?,
+        gitbutler_storage_path(&repo)?
+    );
+    Ok(())
+}
+
+#[test]
+fn shared_storage_path_of_linked_worktree_is_the_main_worktree_storage_path() -> anyhow::Result<()>
+{
+    let (_tmp, repo, linked_repo) = init_repo_with_linked_worktree()?;
+
+    assert_eq!(
+        gitbutler_shared_storage_path(&repo)?,
+        gitbutler_storage_path(&repo)?,
+        "in the main worktree, shared and private data live in the same place"
+    );
+    std::fs::create_dir_all(gitbutler_storage_path(&repo)?)?;
+    assert_eq!(
+        gix::path::realpath(gitbutler_shared_storage_path(&linked_repo)?)?,
+        gix::path::realpath(gitbutler_storage_path(&repo)?)?,
+        "stacks and the oplog are shared by all worktrees"
+    );
+    Ok(())
+}
+
+#[test]
+fn shared_storage_path_of_linked_worktree_with_existing_data_stays_private() -> anyhow::Result<()> {
+    let (_tmp, _repo, linked_repo) = init_repo_with_linked_worktree()?;
+    let private_storage_path = gitbutler_storage_path(&linked_repo)?;
+    std::fs::create_dir_all(&private_storage_path)?;
+    std::fs::write(private_storage_path.join("virtual_branches.toml"), "")?;
+
+    assert_eq!(
+        gitbutler_shared_storage_path(&linked_repo)?,
+        private_storage_path,
+        "worktrees that were set up on their own keep using their own data"
+    );
+    Ok(())
+}
+
 #[test]
 fn storage_path_can_be_resolved_for_an_explicit_channel() -> anyhow::Result<()> {
     let (_tmp, repo) = init_repo()?;
@@ -539,6 +539,7 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             but_post(legacy::git::git_get_global_config_cmd),
         )
         .route("/tree_change_diffs", but_post(diff::tree_change_diffs_cmd))
+        .route("/blob_at", but_post(diff::blob_at_cmd))
         .route("/get_workspace", but_post(workspace::get_workspace_cmd))
         .route(
             "/commit_details_with_line_stats",
@@ -603,6 +604,22 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             "/stack_details",
             but_post(legacy::workspace::stack_details_cmd),
         )
+        .route(
+            "/all_stack_details",
+            but_post(legacy::workspace::all_stack_details_cmd),
+        )
+        .route(
+            "/stack_details_limited",
+            but_post(legacy::workspace::stack_details_limited_cmd),
+        )
+        .route(
+            "/branch_commits_page",
+            but_post(legacy::workspace::branch_commits_page_cmd),
+        )
+        .route(
+            "/stack_details_by_branch",
+            but_post(legacy::workspace::stack_details_by_branch_cmd),
+        )
         .route(
             "/branch_details",
             but_post(legacy::workspace::branch_details_cmd),
@@ -907,6 +924,10 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
             "/workspace_fetch_status",
             but_post(workspace::workspace_fetch_status_cmd),
         )
+        .route(
+            "/stack_containing_commit",
+            but_post(workspace::stack_containing_commit_cmd),
+        )
         .route("/build_type", but_post(platform::build_type_cmd));
 
     // IRC commands — only registered when the `irc` feature is enabled.
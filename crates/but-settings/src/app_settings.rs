@@ -125,6 +125,31 @@ pub struct UiSettings {
 }
 but_schemars::register_sdk_type!(UiSettings);
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSettings {
+    /// The editor command the `but` CLI uses to edit messages, e.g. `code --wait`.
+    /// If empty, the editor is determined like Git does, i.e. by `GIT_EDITOR`, `core.editor`, `VISUAL` and `EDITOR`.
+    pub editor: String,
+    /// Whether to show a desktop notification when slow operations like `but push` or `but pull` finish.
+    pub notify_on_slow_operations: bool,
+}
+but_schemars::register_sdk_type!(CliSettings);
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMessageSettings {
+    /// Warn if the subject line has more characters than this. If `0`, the subject length isn't checked.
+    pub subject_max_length: u32,
+    /// Warn if a line of the body has more characters than this. If `0`, body lines aren't checked.
+    pub body_max_line_length: u32,
+    /// Warn if the subject isn't followed by an empty line.
+    pub require_blank_second_line: bool,
+    /// Instead of only warning, re-open the editor with the problems listed if the message was written in an editor.
+    pub strict: bool,
+}
+but_schemars::register_sdk_type!(CommitMessageSettings);
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IrcSettings {
@@ -0,0 +1,220 @@
+//! Address individual [`AppSettings`] values by dotted keys like `fetch.auto_fetch_interval_minutes`.
+//!
+//! Keys use `snake_case` segments, matching the Rust field names, even though the settings file
+//! stores them in `camelCase`.
+use anyhow::{Context as _, Result, bail};
+use serde_json::Value;
+
+use crate::AppSettings;
+
+/// Return all leaf values of `settings` along with their dotted keys, in a stable order.
+pub fn list(settings: &AppSettings) -> Result<Vec<(String, Value)>> {
+    let mut out = Vec::new();
+    collect_leaves(&serde_json::to_value(settings)?, None, &mut out);
+    Ok(out)
+}
+
+/// Return the value at the dotted `key` in `settings`.
+///
+/// Fails if `key` doesn't name a value, suggesting the closest valid key if there is one.
+pub fn get(settings: &AppSettings, key: &str) -> Result<Value> {
+    let root = serde_json::to_value(settings)?;
+    lookup(&root, key)
+        .cloned()
+        .ok_or_else(|| unknown_key_error(settings, key))
+}
+
+/// Return a copy of `settings` with the value at the dotted `key` set to `value`.
+///
+/// `value` is parsed according to the type of the value it replaces, so booleans
+/// must be `true` or `false` and numbers must be integers in the range of the field.
+/// Strings are taken verbatim, except for `null` which unsets optional values
+/// and is rejected for values that can't be unset.
+pub fn set(settings: &AppSettings, key: &str, value: &str) -> Result<AppSettings> {
+    let mut root = serde_json::to_value(settings)?;
+    let current = lookup(&root, key).ok_or_else(|| unknown_key_error(settings, key))?;
+    let new_value = match current {
+        Value::Bool(_) => Value::Bool(value.parse().with_context(|| {
+            format!("'{key}' expects a boolean ('true' or 'false'), got '{value}'")
+        })?),
+        Value::Number(_) => Value::Number(
+            value
+                .parse::<i64>()
+                .with_context(|| format!("'{key}' expects an integer, got '{value}'"))?
+                .into(),
+        ),
+        Value::String(_) | Value::Null if value == "null" => Value::Null,
+        Value::String(_) | Value::Null => Value::String(value.to_owned()),
+        Value::Array(_) | Value::Object(_) => {
+            bail!("'{key}' can't be set as only booleans, integers and strings are supported")
+        }
+    };
+    let unsets = new_value.is_null();
+    *lookup_mut(&mut root, key).expect("the key was just looked up") = new_value;
+    serde_json::from_value(root).with_context(|| {
+        if unsets {
+            format!("'{key}' can't be unset")
+        } else {
+            format!("Invalid value '{value}' for '{key}'")
+        }
+    })
+}
+
+/// Return the valid key that is closest to `key`, if one is reasonably close.
+pub fn nearest_key(settings: &AppSettings, key: &str) -> Option<String> {
+    let keys = list(settings).ok()?.into_iter().map(|(key, _)| key);
+    keys.map(|candidate| (edit_distance(key, &candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= candidate.len().max(key.len()) / 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn unknown_key_error(settings: &AppSettings, key: &str) -> anyhow::Error {
+    match nearest_key(settings, key) {
+        Some(nearest) => anyhow::anyhow!("Unknown setting '{key}' - did you mean '{nearest}'?"),
+        None => anyhow::anyhow!("Unknown setting '{key}'"),
+    }
+}
+
+fn collect_leaves(value: &Value, prefix: Option<&str>, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                let key = match prefix {
+                    Some(prefix) => format!("{prefix}.{}", camel_to_snake(name)),
+                    None => camel_to_snake(name),
+                };
+                collect_leaves(value, Some(&key), out);
+            }
+        }
+        leaf => out.push((prefix.unwrap_or_default().to_owned(), leaf.clone())),
+    }
+}
+
+/// Only leaves are addressable, so sections like `fetch` are treated as unknown keys.
+fn lookup<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+    let value = key.split('.').try_fold(root, |value, segment| {
+        value.as_object()?.get(&snake_to_camel(segment))
+    })?;
+    (!value.is_object()).then_some(value)
+}
+
+fn lookup_mut<'a>(root: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.').try_fold(root, |value, segment| {
+        value.as_object_mut()?.get_mut(&snake_to_camel(segment))
+    })
+}
+
+fn camel_to_snake(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn snake_to_camel(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            out.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn list_uses_snake_case_dotted_keys() {
+        let keys = list(&AppSettings::default()).unwrap();
+        assert!(keys.contains(&("fetch.auto_fetch_interval_minutes".to_owned(), json!(15))));
+        assert!(keys.contains(&("irc.server.host".to_owned(), json!("irc.gitbutler.com"))));
+        assert!(keys.contains(&("extra_csp.hosts".to_owned(), json!([]))));
+    }
+
+    #[test]
+    fn set_validates_against_the_field_type() {
+        let settings = AppSettings::default();
+
+        let updated = set(&settings, "fetch.auto_fetch_interval_minutes", "-1").unwrap();
+        assert_eq!(updated.fetch.auto_fetch_interval_minutes, -1);
+        let updated = set(&settings, "feature_flags.single_branch", "true").unwrap();
+        assert!(updated.feature_flags.single_branch);
+        let updated = set(&settings, "irc.connection.nickname", "butler").unwrap();
+        assert_eq!(
+            updated.irc.connection.nickname.as_deref(),
+            Some("butler"),
+            "unset optional values accept strings"
+        );
+
+        assert!(set(&settings, "feature_flags.single_branch", "yes").is_err());
+        assert!(set(&settings, "context_lines", "many").is_err());
+        assert!(
+            set(&settings, "context_lines", "-1").is_err(),
+            "the range is checked by the field type"
+        );
+        assert!(set(&settings, "extra_csp.hosts", "example.com").is_err());
+    }
+
+    #[test]
+    fn set_null_unsets_optional_values_only() {
+        let settings = set(&AppSettings::default(), "irc.connection.nickname", "butler").unwrap();
+        let updated = set(&settings, "irc.connection.nickname", "null").unwrap();
+        assert_eq!(
+            updated.irc.connection.nickname, None,
+            "`null` doesn't become a string"
+        );
+
+        let err = set(&settings, "irc.server.host", "null").unwrap_err();
+        assert_eq!(err.to_string(), "'irc.server.host' can't be unset");
+        assert!(set(&settings, "feature_flags.single_branch", "null").is_err());
+    }
+
+    #[test]
+    fn unknown_keys_suggest_the_nearest_one() {
+        let settings = AppSettings::default();
+        let err = get(&settings, "fetch.auto_fetch_interval_minute").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown setting 'fetch.auto_fetch_interval_minute' - did you mean 'fetch.auto_fetch_interval_minutes'?"
+        );
+
+        let err = set(&settings, "fetch", "1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown setting 'fetch'",
+            "sections can't be addressed"
+        );
+        assert_eq!(nearest_key(&settings, "completely.different"), None);
+    }
+}
@@ -23,6 +23,10 @@ pub struct AppSettings {
     pub reviews: app_settings::Reviews,
     /// UI settings.
     pub ui: app_settings::UiSettings,
+    /// Settings that only affect the `but` CLI.
+    pub cli: app_settings::CliSettings,
+    /// Conventions that commit messages are checked against.
+    pub commit_message: app_settings::CommitMessageSettings,
     /// The duration between application update checks in seconds. If `0`, no update checks will be performed.
     /// This setting controls background update checks for both the CLI and GUI.
     /// In the future, this will replace the legacy `ui.checkForUpdatesIntervalInSeconds` setting.
@@ -82,6 +86,7 @@ pub mod customization {
 
 pub mod app_settings;
 mod json;
+pub mod keys;
 mod legacy_settings;
 mod persistence;
 mod watch;
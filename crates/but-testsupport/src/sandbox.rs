@@ -524,6 +524,16 @@ impl Sandbox {
                 #[expect(deprecated)]
                 check_for_updates_interval_in_seconds: 0,
             },
+            cli: but_settings::app_settings::CliSettings {
+                editor: String::new(),
+                notify_on_slow_operations: false,
+            },
+            commit_message: but_settings::app_settings::CommitMessageSettings {
+                subject_max_length: 0,
+                body_max_line_length: 0,
+                require_blank_second_line: false,
+                strict: false,
+            },
             app_updates_check_interval_sec: 0,
             irc: but_settings::app_settings::IrcSettings {
                 server: but_settings::app_settings::IrcServerSettings {
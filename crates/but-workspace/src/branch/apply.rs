@@ -167,6 +167,8 @@ pub struct Options {
     pub order: Option<usize>,
     /// Create new stack id, which by default is a function that generates a new StackId.
     pub new_stack_id: Option<fn(&gix::refs::FullNameRef) -> StackId>,
+    /// If set, called with each step of the workspace merge, if one is needed.
+    pub merge_progress: Option<fn(crate::commit::merge::MergeProgress<'_>)>,
 }
 
 use anyhow::{Context as _, bail};
@@ -228,6 +230,7 @@ pub fn apply(
         workspace_reference_naming,
         order,
         new_stack_id,
+        mut merge_progress,
     }: Options,
 ) -> anyhow::Result<Outcome> {
     let ws = workspace;
@@ -519,6 +522,7 @@ pub fn apply(
             &ws,
             repo,
             head_id.object()?.peel_to_tree()?.id,
+            false,
         )?;
         let ws_commit_with_new_message = ws_commit_with_new_message.id.detach();
         let (graph, new_head_id) = if (ws_commit_with_new_message != head_id
@@ -574,6 +578,7 @@ pub fn apply(
             ws_md.stacks.iter(),
             &existing_stacks_superseded_by_branch,
         ),
+        crate::commit::merge::StackFilter::All,
         filter_superseded_anon_stacks(
             anon_stacks(&ws.stacks),
             &existing_stacks_superseded_by_branch,
@@ -581,6 +586,10 @@ pub fn apply(
         &ws.graph,
         &in_memory_repo,
         Some(branch.as_ref()),
+        crate::commit::merge::OnStackConflict::Unmerge,
+        merge_progress
+            .as_mut()
+            .map(|f| f as &mut dyn FnMut(crate::commit::merge::MergeProgress<'_>)),
     )?;
     ensure_no_missing_stacks(&merge_result)?;
     drop(existing_stacks_superseded_by_branch);
@@ -689,6 +698,7 @@ pub fn apply(
                 ws_md.stacks.iter(),
                 &existing_stacks_superseded_by_branch,
             ),
+            crate::commit::merge::StackFilter::All,
             filter_superseded_anon_stacks(
                 anon_stacks(&ws.stacks),
                 &existing_stacks_superseded_by_branch,
@@ -696,6 +706,10 @@ pub fn apply(
             &ws.graph,
             &in_memory_repo,
             Some(branch.as_ref()),
+            crate::commit::merge::OnStackConflict::Unmerge,
+            merge_progress
+                .as_mut()
+                .map(|f| f as &mut dyn FnMut(crate::commit::merge::MergeProgress<'_>)),
         )?;
         ensure_no_missing_stacks(&merge_result)?;
 
@@ -497,6 +497,9 @@ pub(crate) fn anon_stacks(
                             name: None,
                             commit_id: cid,
                             segment_idx: s.id,
+                            merge_tree: true,
+                            tree_commit_id: None,
+                            tree_id: None,
                         },
                     )
                 })
@@ -521,6 +524,92 @@ pub(crate) fn ensure_no_missing_stacks(
     }
 }
 
+/// Remove the stacks whose top branch is among `missing_stacks` from the metadata of the workspace at
+/// `workspace_ref_name` in `meta`, and return the names of the removed stacks.
+///
+/// This is for stacks whose reference was deleted or renamed outside of GitButler, so they can't be found in the graph anymore,
+/// as listed in [`ResolvedTips::missing_stacks`](crate::commit::merge::ResolvedTips::missing_stacks).
+/// Stacks are matched by the name of their top branch, but removed by their [stack id](StackId) so other stacks that
+/// merely contain a branch of the same name further down are kept. `meta` isn't written if there is nothing to remove.
+pub fn cleanup_missing_stacks(
+    meta: &mut impl but_core::RefMetadata,
+    workspace_ref_name: &gix::refs::FullNameRef,
+    missing_stacks: &[gix::refs::FullName],
+) -> anyhow::Result<Vec<gix::refs::FullName>> {
+    if missing_stacks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut ws_md = meta.workspace(workspace_ref_name)?;
+    let missing_stack_ids: Vec<StackId> = ws_md
+        .stacks(but_core::ref_metadata::StackKind::Applied)
+        .filter(|stack| {
+            stack
+                .ref_name()
+                .is_some_and(|name| missing_stacks.contains(name))
+        })
+        .map(|stack| stack.id)
+        .collect();
+    if missing_stack_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut removed = Vec::new();
+    ws_md.stacks.retain(|stack| {
+        if !missing_stack_ids.contains(&stack.id) {
+            return true;
+        }
+        removed.extend(stack.ref_name().cloned());
+        false
+    });
+    meta.set_workspace(&ws_md)?;
+    Ok(removed)
+}
+
+/// Where a commit was found in a workspace, as returned by [`stack_containing_commit()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitLocation {
+    /// The id of the stack containing the commit, or `None` if the workspace isn't managed.
+    pub stack_id: Option<StackId>,
+    /// The name of the top-most segment of the containing stack, if it has one.
+    pub stack_ref_name: Option<gix::refs::FullName>,
+    /// The name of the branch segment owning the commit, if it has one.
+    pub ref_name: Option<gix::refs::FullName>,
+    /// If `true`, the commit is only reachable from the remote tracking branch of the owning segment,
+    /// and isn't one of its local commits.
+    pub upstream_only: bool,
+}
+
+/// Find the stack in `ws` that contains `commit_id`, along with the branch segment owning it, in a single pass.
+///
+/// Local commits of a segment take precedence, and only if a commit isn't local to any segment of any stack
+/// are the commits that are only on the remote tracking branch of a segment considered.
+/// Return `None` if no stack contains `commit_id`.
+pub fn stack_containing_commit(
+    ws: &but_graph::Workspace,
+    commit_id: gix::ObjectId,
+) -> Option<CommitLocation> {
+    let mut upstream_location = None;
+    for stack in &ws.stacks {
+        for segment in &stack.segments {
+            let location = |upstream_only| CommitLocation {
+                stack_id: stack.id,
+                stack_ref_name: stack.ref_name().map(ToOwned::to_owned),
+                ref_name: segment.ref_name().map(ToOwned::to_owned),
+                upstream_only,
+            };
+            if segment.commits.iter().any(|c| c.id == commit_id) {
+                return Some(location(false));
+            }
+            if upstream_location.is_none()
+                && segment.commits_on_remote.iter().any(|c| c.id == commit_id)
+            {
+                upstream_location = Some(location(true));
+            }
+        }
+    }
+    upstream_location
+}
+
 /// Find `branch` in `repo` and reject it if it resolves to a symbolic reference.
 ///
 /// `operation` is used only for the error message so callers such as apply and unapply can share
@@ -535,8 +535,10 @@ pub(crate) mod function {
         let crate::commit::merge::ResolvedTips {
             tips,
             missing_stacks,
+            excluded_stacks: _,
         } = WorkspaceCommit::tips_from_metadata(
             ws_md.stacks.iter(),
+            crate::commit::merge::StackFilter::All,
             anon_stacks_to_preserve(ws, excluded_anonymous_tip_id),
             &ws.graph,
         );
@@ -624,7 +626,14 @@ pub(crate) mod function {
         if tips.is_empty() {
             tips.push(base_tip_after_unapply(ws, future_workspace_tips)?);
         }
-        let outcome = WorkspaceCommit::from_new_merge_with_tips(tips, &ws.graph, repo, None)?;
+        let outcome = WorkspaceCommit::from_new_merge_with_tips(
+            tips,
+            &ws.graph,
+            repo,
+            None,
+            crate::commit::merge::OnStackConflict::Unmerge,
+            None,
+        )?;
         ensure_workspace_merge_has_no_conflicts(&outcome)?;
         let workspace_commit_id = outcome.workspace_commit_id;
         Ok(WorkspaceMergeAfterUnapply {
@@ -676,6 +685,9 @@ pub(crate) mod function {
             name: None,
             commit_id,
             segment_idx,
+            merge_tree: true,
+            tree_commit_id: None,
+            tree_id: None,
         })
     }
 
@@ -70,7 +70,7 @@ pub mod squash_commits;
 pub use squash_commits::{SquashCommitsOutcome, squash_commits};
 
 /// A minimal stack for use by [WorkspaceCommit::new_from_stacks()].
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Stack {
     /// The tip of the top-most branch, i.e., the most recent commit that would become the parent of new commits of the topmost stack branch.
     pub tip: gix::ObjectId,
@@ -93,7 +93,10 @@ impl std::fmt::Debug for Stack {
 
 /// Structures related to creating a merge-commit along with the respective tree.
 pub mod merge {
+    use std::collections::HashMap;
+
     use anyhow::{Context as _, bail};
+    use bstr::{BString, ByteSlice};
     use but_core::{
         RepositoryExt,
         ref_metadata::{MaybeDebug, WorkspaceCommitRelation},
@@ -115,6 +118,23 @@ pub mod merge {
         pub commit_id: gix::ObjectId,
         /// The index to the top-most segment of the stack in the graph for use in merge-base computation.
         pub segment_idx: SegmentIndex,
+        /// If `false`, `commit_id` only becomes a parent of the workspace commit, without its tree
+        /// being merged into the workspace tree.
+        /// This is the case for stacks with [`WorkspaceCommitRelation::MergeFrom`] without a commit.
+        pub merge_tree: bool,
+        /// The commit whose tree is merged instead of the tree of `commit_id`, while `commit_id` remains the parent.
+        /// This is the case for stacks with [`WorkspaceCommitRelation::MergeFrom`] with a commit.
+        pub tree_commit_id: Option<gix::ObjectId>,
+        /// The tree of `tree_commit_id` or `commit_id`, or its auto-resolution if it's conflicted, if the caller already knows it.
+        /// If `None`, it's looked up from `tree_commit_id` or `commit_id`.
+        pub tree_id: Option<gix::ObjectId>,
+    }
+
+    impl Tip {
+        /// The commit whose tree is merged into the workspace tree.
+        pub fn tree_commit_id(&self) -> gix::ObjectId {
+            self.tree_commit_id.unwrap_or(self.commit_id)
+        }
     }
 
     /// Tips resolved from workspace metadata, with references that metadata mentioned but the graph
@@ -126,23 +146,84 @@ pub mod merge {
         /// Metadata stack tips that couldn't be found in the graph.
         /// This is usually a problem, as the Graph is expected to contain everything of interest.
         pub missing_stacks: Vec<gix::refs::FullName>,
+        /// Metadata stack tips that were part of the workspace, but were left out by the [`StackFilter`].
+        pub excluded_stacks: Vec<gix::refs::FullName>,
+    }
+
+    /// Which of the metadata stacks to merge, as passed to [WorkspaceCommit::from_new_merge_with_metadata()].
+    ///
+    /// Stacks that are left out are treated as if they were [outside](WorkspaceCommitRelation::Outside) of the
+    /// workspace, but only for this merge, so the metadata can be updated once the merge is known to work.
+    #[derive(Debug, Default, Copy, Clone)]
+    pub enum StackFilter<'a> {
+        /// Merge all stacks of the workspace.
+        #[default]
+        All,
+        /// Merge only the stacks whose top branch has one of the given names.
+        Only(&'a [gix::refs::FullName]),
+        /// Merge all stacks but the ones whose top branch has one of the given names.
+        Except(&'a [gix::refs::FullName]),
+    }
+
+    impl StackFilter<'_> {
+        /// Return `true` if the stack with the top branch `name` should be merged.
+        pub fn includes(&self, name: &gix::refs::FullNameRef) -> bool {
+            match self {
+                StackFilter::All => true,
+                StackFilter::Only(names) => names.iter().any(|n| n.as_ref() == name),
+                StackFilter::Except(names) => !names.iter().any(|n| n.as_ref() == name),
+            }
+        }
     }
 
     /// A minimal stack for to represent a stack that conflicted.
-    #[derive(Clone)]
+    #[derive(Clone, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct ConflictingStack {
         /// The tip that could not be merged in.
+        #[serde(with = "but_serde::object_id")]
         pub tip: gix::ObjectId,
         /// The name of the references to be merged, it pointed to `tip`.
+        #[serde(with = "but_serde::fullname_lossy_opt")]
         pub ref_name: Option<gix::refs::FullName>,
+        /// The paths with unresolved conflicts that made merging `tip` fail, sorted by path.
+        pub conflicting_paths: Vec<ConflictingPath>,
     }
 
     impl std::fmt::Debug for ConflictingStack {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let ConflictingStack { ref_name, tip } = self;
+            let ConflictingStack {
+                ref_name,
+                tip,
+                conflicting_paths,
+            } = self;
             f.debug_struct("ConflictingStack")
                 .field("tip", tip)
                 .field("ref_name", &MaybeDebug(ref_name))
+                .field("conflicting_paths", conflicting_paths)
+                .finish()
+        }
+    }
+
+    /// A path that couldn't be merged without conflict, as part of a [`ConflictingStack`].
+    #[derive(Clone, PartialEq, Eq, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ConflictingPath {
+        /// The path of the conflicting entry, as seen by the conflicting stack.
+        #[serde(with = "but_serde::bstring_lossy")]
+        pub path: BString,
+        /// The name of the stack that was merged before and changed `path` as well, if it could be
+        /// determined and is named.
+        #[serde(with = "but_serde::fullname_lossy_opt")]
+        pub other_stack: Option<gix::refs::FullName>,
+    }
+
+    impl std::fmt::Debug for ConflictingPath {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let ConflictingPath { path, other_stack } = self;
+            f.debug_struct("ConflictingPath")
+                .field("path", path)
+                .field("other_stack", &MaybeDebug(other_stack))
                 .finish()
         }
     }
@@ -152,13 +233,81 @@ pub mod merge {
     pub struct Outcome {
         /// The produced workspace commit, as written to the repository.
         pub workspace_commit_id: gix::ObjectId,
-        /// The names and the tips of the stacks that were successfully merged, one for each
-        /// parent of the `workspace_commit`.
+        /// The names and the tips of the stacks that were successfully merged, in the order of the parents
+        /// of the `workspace_commit`. Stacks with the same tip share a single parent.
+        /// This includes the [`unmerged_stacks`](Self::unmerged_stacks).
         pub stacks: Vec<Stack>,
         /// The stacks that were listed in the input, and whose tips couldn't be found in the graph.
         pub missing_stacks: Vec<gix::refs::FullName>,
+        /// The stacks that were listed in the input as part of the workspace, but were left out by the [`StackFilter`].
+        /// They weren't looked up in the graph, so they are never listed in [`missing_stacks`](Self::missing_stacks).
+        pub excluded_stacks: Vec<gix::refs::FullName>,
         /// All information about each stack, in order of occurrence, that could ultimately not be merged.
         pub conflicting_stacks: Vec<ConflictingStack>,
+        /// The stacks, in order of occurrence, whose tips are parents of the `workspace_commit` without
+        /// their trees being merged, so they can't conflict.
+        pub unmerged_stacks: Vec<Stack>,
+        /// The stacks, in order of occurrence, that conflicted but were kept as parents of the `workspace_commit`
+        /// anyway, with their conflicts resolved as [`OnStackConflict`] says. Always empty with [`OnStackConflict::Unmerge`].
+        pub conflicted_stacks: Vec<ConflictingStack>,
+    }
+
+    /// What to do with a stack whose tree conflicts with the trees of the stacks merged before it.
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    pub enum OnStackConflict {
+        /// Leave the stack out of the workspace commit and list it in [`Outcome::conflicting_stacks`].
+        #[default]
+        Unmerge,
+        /// Keep the stack as parent of the workspace commit and list it in [`Outcome::conflicted_stacks`],
+        /// resolving each conflict by taking the side of the stacks merged before it.
+        KeepFavoringOurs,
+        /// Like [`KeepFavoringOurs`](Self::KeepFavoringOurs), but resolve each conflict by taking the side
+        /// of the conflicting stack.
+        KeepFavoringTheirs,
+    }
+
+    /// A step of a workspace merge, as passed to the `progress` callback of
+    /// [WorkspaceCommit::from_new_merge_with_metadata()].
+    #[derive(Debug, Clone, Copy)]
+    pub enum MergeProgress<'a> {
+        /// The stacks were resolved into `count` tips to merge.
+        TipsResolved {
+            /// The amount of tips to merge.
+            count: usize,
+        },
+        /// The tree of the tip at `index` of `count` tips is about to be merged.
+        StackMergeStarted {
+            /// The name of the stack, or `None` if it's anonymous.
+            name: Option<&'a gix::refs::FullNameRef>,
+            /// The position of the tip among all tips.
+            index: usize,
+            /// The amount of tips to merge.
+            count: usize,
+        },
+        /// The tree of the tip at `index` of `count` tips was merged.
+        StackMergeFinished {
+            /// The name of the stack, or `None` if it's anonymous.
+            name: Option<&'a gix::refs::FullNameRef>,
+            /// The position of the tip among all tips.
+            index: usize,
+            /// The amount of tips to merge.
+            count: usize,
+            /// How long the merge took, which is close to nothing if it was merged before.
+            duration: std::time::Duration,
+            /// Whether the merge had unresolved conflicts.
+            conflicted: bool,
+        },
+        /// The merge starts over to find out which stacks conflict, so stacks may be merged again.
+        Retry,
+        /// All stacks are merged and the workspace commit is written.
+        WritingCommit,
+    }
+
+    impl OnStackConflict {
+        /// Return `true` if conflicting stacks are kept in the workspace commit.
+        pub fn keeps_stack(&self) -> bool {
+            !matches!(self, OnStackConflict::Unmerge)
+        }
     }
 
     impl Outcome {
@@ -178,32 +327,42 @@ pub mod merge {
         /// `stacks` are the workspace metadata stacks whose top branches should become named
         /// merge tips unless they are marked outside of the workspace.
         ///
+        /// `filter` decides which of the `stacks` that are part of the workspace are used, and the ones
+        /// it leaves out are listed in [`ResolvedTips::excluded_stacks`].
+        ///
         /// `anon_stacks` are unnamed projected tips paired with the parent slot they occupied in
         /// the workspace projection, used to preserve anonymous parents not represented in metadata.
         ///
         /// `graph` resolves metadata branch names to commit and segment ids.
         pub fn tips_from_metadata<'a>(
             stacks: impl IntoIterator<Item = &'a but_core::ref_metadata::WorkspaceStack>,
+            filter: StackFilter<'_>,
             anon_stacks: impl IntoIterator<Item = (usize, Tip)>,
             graph: &but_graph::Graph,
         ) -> ResolvedTips {
             let mut missing_stacks = Vec::new();
+            let mut excluded_stacks = Vec::new();
             let mut tips_with_metadata_slots: Vec<_> = stacks
                 .into_iter()
                 .filter_map(|s| s.branches.first().map(|b| (b, s.workspacecommit_relation)))
                 .map(|(top_segment, relation)| {
-                    match relation {
-                        WorkspaceCommitRelation::Merged => {}
-                        WorkspaceCommitRelation::MergeFrom { .. } => {
+                    let (merge_tree, tree_commit_id) = match relation {
+                        WorkspaceCommitRelation::Merged => (true, None),
+                        WorkspaceCommitRelation::MergeFrom { commit_id: None } => {
                             // These need to be part of the parents list, but shouldn't be merged.
                             // If the caller wants to retry them, they can be passed here as "Merged".
-                            todo!(
-                                "this is a placeholder for where we will have to start handling this UnmergedTree"
-                            )
+                            (false, None)
                         }
+                        WorkspaceCommitRelation::MergeFrom {
+                            commit_id: Some(commit_id),
+                        } => (true, Some(commit_id)),
                         WorkspaceCommitRelation::Outside => return None,
-                    }
+                    };
                     let stack_tip_name = top_segment.ref_name.as_ref();
+                    if !filter.includes(stack_tip_name) {
+                        excluded_stacks.push(top_segment.ref_name.to_owned());
+                        return None;
+                    }
                     match graph.segment_and_commit_by_ref_name(stack_tip_name) {
                         None => {
                             missing_stacks.push(top_segment.ref_name.to_owned());
@@ -213,6 +372,9 @@ pub mod merge {
                             name: Some(stack_tip_name.to_owned()),
                             commit_id: commit.id,
                             segment_idx: segment.id,
+                            merge_tree,
+                            tree_commit_id,
+                            tree_id: None,
                         }),
                     }
                 })
@@ -237,39 +399,69 @@ pub mod merge {
             ResolvedTips {
                 tips: tips_with_metadata_slots.into_iter().flatten().collect(),
                 missing_stacks,
+                excluded_stacks,
             }
         }
 
         /// like [`Self::from_new_merge_with_metadata`], but supports tips, which makes it possible to re-merge anything
         /// even if the tip is unnamed.
-        /// Note that [`missing_stacks`](Outcome::missing_stacks) is never set.
+        /// Note that [`missing_stacks`](Outcome::missing_stacks) and [`excluded_stacks`](Outcome::excluded_stacks) are never set.
         pub fn from_new_merge_with_tips(
             tips: impl IntoIterator<Item = Tip>,
             graph: &but_graph::Graph,
             repo: &gix::Repository,
             hero_stack: Option<&gix::refs::FullNameRef>,
+            on_conflict: OnStackConflict,
+            mut progress: Option<&mut dyn FnMut(MergeProgress<'_>)>,
         ) -> anyhow::Result<Outcome> {
             #[derive(Debug)]
             enum Instruction {
                 Merge,
+                /// Merged despite conflicts, which were resolved according to `on_conflict`.
+                MergeConflicted,
                 MergeTrial {
                     hero_sidx: SegmentIndex,
                     hero_tree_id: gix::ObjectId,
                 },
                 Skip,
                 CertainConflict,
+                /// Only a parent of the workspace commit, the tree is never merged.
+                ParentOnly,
             }
             use Instruction as I;
             impl Instruction {
                 fn should_skip(&self) -> bool {
                     match self {
-                        I::Merge | I::MergeTrial { .. } => false,
-                        I::Skip | I::CertainConflict => true,
+                        I::Merge | I::MergeConflicted | I::MergeTrial { .. } => false,
+                        I::Skip | I::CertainConflict | I::ParentOnly => true,
                     }
                 }
             }
-            let mut tips: Vec<(Instruction, Tip)> =
-                tips.into_iter().map(|t| (I::Merge, t)).collect();
+            /// The tips among `tips` whose trees were merged.
+            fn merged_tips(tips: &[(Instruction, Tip)]) -> impl Iterator<Item = &Tip> + Clone {
+                tips.iter()
+                    .filter(|(mode, _)| matches!(mode, I::Merge | I::MergeConflicted))
+                    .map(|(_, tip)| tip)
+            }
+            let mut tips: Vec<(Instruction, Tip)> = tips
+                .into_iter()
+                .map(|t| {
+                    (
+                        if t.merge_tree {
+                            I::Merge
+                        } else {
+                            I::ParentOnly
+                        },
+                        t,
+                    )
+                })
+                .collect();
+            // The paths that made the tip at the same index conflict, as last seen.
+            let mut conflicting_paths_by_tip: Vec<Vec<ConflictingPath>> =
+                vec![Vec::new(); tips.len()];
+
+            let mut merges = CachedTreeMerges::new(repo)?;
+            let tip_count = tips.len();
 
             let mut ran_merge_trials_loop_safety = false;
             #[expect(clippy::indexing_slicing)]
@@ -277,22 +469,25 @@ pub mod merge {
                 let mut prev_base_sidx = None;
                 let mut merge_tree_id = None;
                 let mut previous_tip = None;
-                let (merge_options, conflict_kind) = repo.merge_options_fail_fast()?;
-                let labels_uninteresting_as_no_conflict_allowed = repo.default_merge_labels();
                 'tips_loop: for tip_idx in 0..tips.len() {
+                    let (tips_before, tips_from_here) = tips.split_at_mut(tip_idx);
                     let (
                         mode,
                         Tip {
                             name: ref_name,
                             commit_id,
                             segment_idx: sidx,
+                            merge_tree: _,
+                            tree_commit_id,
+                            tree_id,
                         },
-                    ) = &mut tips[tip_idx];
+                    ) = &mut tips_from_here[0];
                     let sidx = *sidx;
                     if mode.should_skip() {
                         continue;
                     }
-                    let this_tree_id = peel_to_tree(commit_id.attach(repo))?;
+                    let this_tree_id =
+                        merges.tree_id(tree_commit_id.unwrap_or(*commit_id), *tree_id)?;
                     if let Some((prev_tree_id, prev_sidx)) = previous_tip {
                         let (base_tree_id, base_sidx) = {
                             // This is critical: we enforce using the lowest merge-base by using
@@ -300,44 +495,108 @@ pub mod merge {
                             // This is the same as computing the merge-base between the new
                             // (non-existing merge-commit) and the next tip.
                             let left = prev_base_sidx.unwrap_or(prev_sidx);
-                            compute_merge_base(graph, repo, left, sidx)?
+                            merges.merge_base(graph, left, sidx)?
                         };
 
-                        let mut merge = repo.merge_trees(
-                            base_tree_id,
-                            merge_tree_id.unwrap_or(prev_tree_id),
-                            this_tree_id,
-                            labels_uninteresting_as_no_conflict_allowed,
-                            merge_options.clone(),
-                        )?;
+                        let ours_tree_id = merge_tree_id.unwrap_or(prev_tree_id);
                         let is_hero = hero_stack.is_some_and(|hero| {
                             Some(hero) == ref_name.as_ref().map(|rn| rn.as_ref())
                         });
-                        if merge.has_unresolved_conflicts(conflict_kind) {
-                            if matches!(mode, I::MergeTrial { .. }) {
-                                bail!(
-                                    "BUG: Found {ref_name:?} in merge-trial, even though these shouldn't fail without the hero merged in"
-                                );
+                        let name = ref_name.as_ref().map(|rn| rn.as_ref());
+                        report(
+                            &mut progress,
+                            MergeProgress::StackMergeStarted {
+                                name,
+                                index: tip_idx,
+                                count: tip_count,
+                            },
+                        );
+                        let started = progress.is_some().then(std::time::Instant::now);
+                        let merged_tree_id =
+                            merges.merge(base_tree_id, ours_tree_id, this_tree_id)?;
+                        if let Some(started) = started {
+                            report(
+                                &mut progress,
+                                MergeProgress::StackMergeFinished {
+                                    name,
+                                    index: tip_idx,
+                                    count: tip_count,
+                                    duration: started.elapsed(),
+                                    conflicted: merged_tree_id.is_none(),
+                                },
+                            );
+                        }
+                        let merged_tree_id = match merged_tree_id {
+                            Some(merged_tree_id) => merged_tree_id,
+                            None if on_conflict.keeps_stack() => {
+                                let paths = merges.conflicting_paths(
+                                    base_tree_id,
+                                    ours_tree_id,
+                                    this_tree_id,
+                                )?;
+                                conflicting_paths_by_tip[tip_idx] = with_other_stacks(
+                                    &mut merges,
+                                    paths,
+                                    base_tree_id,
+                                    merged_tips(tips_before),
+                                )?;
+                                *mode = I::MergeConflicted;
+                                merges.merge_resolved(
+                                    base_tree_id,
+                                    ours_tree_id,
+                                    this_tree_id,
+                                    on_conflict,
+                                )?
                             }
-                            if is_hero {
-                                // We definitely want this one, so must restart the whole operation
-                                // while disallowing the most recent allowed tip.
-                                let err_msg = format!(
-                                    "BUG: if there was no allowed stack in front of {ref_name:?}, then we aren't here as no merge can be done with just one branch"
-                                );
-                                let presumed_conflicting_tip = tips[..tip_idx]
-                                    .iter_mut()
-                                    .rev()
-                                    .find(|(mode, ..)| !mode.should_skip())
-                                    .context(err_msg)?;
-                                presumed_conflicting_tip.0 = I::Skip;
-                                continue 'retry_loop;
-                            } else {
-                                // Ignore this stack, continue with the others.
-                                *mode = I::Skip;
-                                continue 'tips_loop;
+                            None => {
+                                if matches!(mode, I::MergeTrial { .. }) {
+                                    bail!(
+                                        "BUG: Found {ref_name:?} in merge-trial, even though these shouldn't fail without the hero merged in"
+                                    );
+                                }
+                                let paths = merges.conflicting_paths(
+                                    base_tree_id,
+                                    ours_tree_id,
+                                    this_tree_id,
+                                )?;
+                                if is_hero {
+                                    // We definitely want this one, so must restart the whole operation
+                                    // while disallowing the most recent allowed tip.
+                                    let err_msg = format!(
+                                        "BUG: if there was no allowed stack in front of {ref_name:?}, then we aren't here as no merge can be done with just one branch"
+                                    );
+                                    let hero_name = ref_name.clone();
+                                    let (presumed_idx, presumed_conflicting_tip) = tips_before
+                                        .iter_mut()
+                                        .enumerate()
+                                        .rev()
+                                        .find(|(_, (mode, ..))| !mode.should_skip())
+                                        .context(err_msg)?;
+                                    presumed_conflicting_tip.0 = I::Skip;
+                                    // If it's not at fault, a merge-trial will clear this later.
+                                    conflicting_paths_by_tip[presumed_idx] = paths
+                                        .into_iter()
+                                        .map(|path| ConflictingPath {
+                                            path,
+                                            other_stack: hero_name.clone(),
+                                        })
+                                        .collect();
+                                    report(&mut progress, MergeProgress::Retry);
+                                    continue 'retry_loop;
+                                } else {
+                                    // Ignore this stack, continue with the others.
+                                    *mode = I::Skip;
+                                    conflicting_paths_by_tip[tip_idx] = with_other_stacks(
+                                        &mut merges,
+                                        paths,
+                                        base_tree_id,
+                                        merged_tips(tips_before),
+                                    )?;
+                                    continue 'tips_loop;
+                                }
                             }
-                        } else if is_hero {
+                        };
+                        if is_hero {
                             // Look back and see if there is any skipped stacks. If so, we now merged the hero branch successfully,
                             //
                             // This means that skipping some worked. Now we want to try to re-enable previously disabled ones to learn if they
@@ -352,9 +611,9 @@ pub mod merge {
                             // First, mark the first X as conflict as we know it for sure.
                             let mut saw_first_certain_conflict = false;
                             let mut has_merge_trials = false;
-                            for (mode, _) in &mut tips[..tip_idx] {
+                            for (mode, _) in tips_before.iter_mut() {
                                 match mode {
-                                    I::Merge => continue,
+                                    I::Merge | I::MergeConflicted | I::ParentOnly => continue,
                                     I::MergeTrial { .. } => {
                                         bail!(
                                             "BUG: found a merge-trial, even though trial should be concluded by now"
@@ -383,6 +642,7 @@ pub mod merge {
                                     );
                                 }
                                 ran_merge_trials_loop_safety = true;
+                                report(&mut progress, MergeProgress::Retry);
                                 continue 'retry_loop;
                             }
                             // We are past possible trials and proceed as usual, with future conflicting stacks just being dropped.
@@ -393,18 +653,22 @@ pub mod merge {
                         {
                             // This stack merged cleanly, and now we have to merge the hero into that result to see if it works.
                             // This tells us if this is stack merges cleanly or causes a real conflict in conjunction with hero.
-                            let base_tree_id =
-                                compute_merge_base(graph, repo, base_sidx, hero_sidx)?.0;
-                            let merge = repo.merge_trees(
-                                base_tree_id,
-                                merge.tree.write()?,
-                                hero_tree_id,
-                                labels_uninteresting_as_no_conflict_allowed,
-                                merge_options.clone(),
-                            )?;
-                            let trial_outcome = if merge.has_unresolved_conflicts(conflict_kind) {
+                            let base_tree_id = merges.merge_base(graph, base_sidx, hero_sidx)?.0;
+                            let trial_outcome = if merges
+                                .merge(base_tree_id, merged_tree_id, hero_tree_id)?
+                                .is_none()
+                            {
+                                conflicting_paths_by_tip[tip_idx] = merges
+                                    .conflicting_paths(base_tree_id, merged_tree_id, hero_tree_id)?
+                                    .into_iter()
+                                    .map(|path| ConflictingPath {
+                                        path,
+                                        other_stack: hero_stack.map(ToOwned::to_owned),
+                                    })
+                                    .collect();
                                 I::CertainConflict
                             } else {
+                                conflicting_paths_by_tip[tip_idx].clear();
                                 I::Merge
                             };
                             *mode = trial_outcome;
@@ -415,38 +679,59 @@ pub mod merge {
                             }
                         }
                         prev_base_sidx = Some(base_sidx);
-                        merge_tree_id = merge.tree.write()?.detach().into();
+                        merge_tree_id = Some(merged_tree_id);
                     }
                     previous_tip = Some((this_tree_id, sidx));
                 }
 
-                let (stacks, conflicting_stacks) = tips.iter().fold(
-                    (Vec::new(), Vec::new()),
-                    |(mut stacks, mut conflicting_stacks),
-                     (
+                let mut stacks = Vec::new();
+                let mut conflicting_stacks = Vec::new();
+                let mut unmerged_stacks = Vec::new();
+                let mut conflicted_stacks = Vec::new();
+                for (
+                    (
                         mode,
                         Tip {
                             name: ref_name,
                             commit_id,
                             ..
                         },
-                    )| {
-                        if mode.should_skip() {
-                            conflicting_stacks.push(ConflictingStack {
+                    ),
+                    conflicting_paths,
+                ) in tips.iter().zip(&conflicting_paths_by_tip)
+                {
+                    let stack = Stack {
+                        tip: *commit_id,
+                        name: ref_name.as_ref().map(|rn| rn.shorten().to_owned()),
+                    };
+                    match mode {
+                        I::ParentOnly => {
+                            unmerged_stacks.push(stack.clone());
+                            stacks.push(stack);
+                        }
+                        I::MergeConflicted => {
+                            conflicted_stacks.push(ConflictingStack {
                                 tip: *commit_id,
                                 ref_name: ref_name.clone(),
+                                conflicting_paths: conflicting_paths.clone(),
                             });
-                        } else {
-                            stacks.push(Stack {
-                                tip: *commit_id,
-                                name: ref_name.as_ref().map(|rn| rn.shorten().to_owned()),
-                            });
+                            stacks.push(stack);
                         }
-                        (stacks, conflicting_stacks)
-                    },
-                );
+                        _ if mode.should_skip() => conflicting_stacks.push(ConflictingStack {
+                            tip: *commit_id,
+                            ref_name: ref_name.clone(),
+                            conflicting_paths: conflicting_paths.clone(),
+                        }),
+                        _ => stacks.push(stack),
+                    }
+                }
 
-                if stacks.is_empty() {
+                if stacks.len() == unmerged_stacks.len() {
+                    if !unmerged_stacks.is_empty() {
+                        bail!(
+                            "Cannot create a workspace commit only from stacks whose trees aren't merged: {unmerged_stacks:?}"
+                        )
+                    }
                     bail!(
                         "BUG: Cannot merge nothing, no tips ended up in the graph: `conflicting_stacks` = {conflicting_stacks:?}, `tips` = : {tips:?}"
                     )
@@ -463,14 +748,18 @@ pub mod merge {
                 let mut ws_commit =
                     Self::new_from_stacks(stacks.iter().cloned(), repo.object_hash());
                 ws_commit.tree = merge_tree_id;
-                Self::fixup_times(&mut ws_commit, repo);
+                Self::fixup_signatures(&mut ws_commit, repo);
 
-                let workspace_commit_id = repo.write_object(&ws_commit)?.detach();
+                report(&mut progress, MergeProgress::WritingCommit);
+                let workspace_commit_id = Self::write_maybe_signed(&mut ws_commit, repo)?.detach();
                 return Ok(Outcome {
                     workspace_commit_id,
                     stacks,
                     missing_stacks: vec![], /* this is never set here as all tips are already resolved */
+                    excluded_stacks: vec![],
                     conflicting_stacks,
+                    unmerged_stacks,
+                    conflicted_stacks,
                 });
             }
         }
@@ -479,73 +768,312 @@ pub mod merge {
         /// create a new workspace commit with their tips extracted from `graph`. Note that stacks that don't exist in `graph` aren't fatal.
         /// Also, this will create a workspace commit as it's desired, but not as it is, and the caller should assure that all branches are present.
         ///
+        /// Use `filter` to merge only some of the `stacks` without changing the metadata first, for instance to see if the
+        /// merge works before committing to it. The stacks it leaves out are listed in [`Outcome::excluded_stacks`].
+        ///
         /// Use `anon_stacks` with `(parent_index, tip)` to fill-in anonymous commits that aren't listed in metadata,
         /// as they have *no known name*. We will make sure that no commit in `anon_stacks` is a duplicate with a `stack`, and
         /// we will insert them at `parent_index` into the resulting list so they don't change their position.
         ///
+        /// Stacks whose relation is [`WorkspaceCommitRelation::MergeFrom`] without a commit become parents of the
+        /// workspace commit in their position, but their tree isn't merged, so they never conflict.
+        /// They are listed in [`Outcome::unmerged_stacks`]. If they have a commit, its tree is merged instead of the
+        /// tree of the stack tip, which still becomes the parent.
+        ///
         /// Use `hero_stack` to highlight a stack that you definitely want merged in, and would rather not merge other stacks for it.
         /// This can lead to a situation where only the hero stack is applied.
+        /// `on_conflict` decides if conflicting stacks are left out of the workspace commit, or kept with their
+        /// conflicts resolved. When they are kept, nothing is left out, so `hero_stack` has no effect.
+        /// `progress`, if set, is called with each step of the merge, which otherwise is only observable via tracing.
         /// If there is only one stack, it just uses the tree of that stack. It's an error if `stacks` is empty.
         /// `repo` is expected to be configured to be suitable for merges, and it *should* be configured to write objects into memory
         /// unless the caller knows that any result of the merge is acceptable.
         ///
-        /// ### Shortcoming: inefficient conflict behaviour
+        /// ### Parent order
+        ///
+        /// The parents of the workspace commit are the tips of the merged stacks in exactly the order in which `stacks` are listed,
+        /// with `anon_stacks` inserted at their `parent_index`. Missing, excluded and conflicting stacks are omitted without
+        /// affecting the order of the others, and a tip that is shared by multiple stacks is a parent only once.
+        /// Hence, merging the same stacks again produces the same workspace commit, as long as its author and committer are the same.
+        ///
+        /// ### Conflict behaviour
         ///
         /// In order to find out exactly which branches conflicts, we repeat the whole operations with different configuration.
-        /// One could be better and only repeat what didn't change, to avoid repeating unnecessarily.
-        /// But that shouldn't usually matter unless in the biggest repositories with tree-merge times past a 500ms or so.
+        /// Tree merges are cached by the trees involved though, so a repetition only merges again from the first tip
+        /// whose merge changed. Each tip is peeled to its tree only once, and not at all if [`Tip::tree_id`] is set,
+        /// and the merge-base of each pair of segments is only computed once as well.
         #[instrument(
             name = "re-merge workspace commit",
             level = "debug",
-            skip(stacks, anon_stacks, graph, repo),
+            skip(stacks, anon_stacks, graph, repo, progress),
             err(Debug)
         )]
         pub fn from_new_merge_with_metadata<'a>(
             stacks: impl IntoIterator<Item = &'a but_core::ref_metadata::WorkspaceStack>,
+            filter: StackFilter<'_>,
             anon_stacks: impl IntoIterator<Item = (usize, Tip)>,
             graph: &but_graph::Graph,
             repo: &gix::Repository,
             hero_stack: Option<&gix::refs::FullNameRef>,
+            on_conflict: OnStackConflict,
+            mut progress: Option<&mut dyn FnMut(MergeProgress<'_>)>,
         ) -> anyhow::Result<Outcome> {
             let ResolvedTips {
                 tips,
                 missing_stacks,
-            } = Self::tips_from_metadata(stacks, anon_stacks, graph);
-            let mut out = Self::from_new_merge_with_tips(tips, graph, repo, hero_stack)?;
+                excluded_stacks,
+            } = Self::tips_from_metadata(stacks, filter, anon_stacks, graph);
+            report(
+                &mut progress,
+                MergeProgress::TipsResolved { count: tips.len() },
+            );
+            let mut out = Self::from_new_merge_with_tips(
+                tips,
+                graph,
+                repo,
+                hero_stack,
+                on_conflict,
+                progress,
+            )?;
             out.missing_stacks = missing_stacks;
+            out.excluded_stacks = excluded_stacks;
             Ok(out)
         }
     }
 
+    /// Return the id of the commit to use as merge-base of the segments `left` and `right`, along with its segment.
     fn compute_merge_base(
         graph: &but_graph::Graph,
-        repo: &gix::Repository,
         left: SegmentIndex,
         right: SegmentIndex,
     ) -> anyhow::Result<(gix::ObjectId, SegmentIndex)> {
-        let base_sidx = graph.find_merge_base(left, right).with_context(|| {
-            format!(
-                "Couldn't find merge-base between segments {l} and {r} - they are disjoint in the commit-graph",
-                l = left.index(),
-                r = right.index()
-            )
-        })?;
-        let base_commit_id = graph
-            .tip_skip_empty(base_sidx)
+        let (base_sidx, base_commit) = graph
+            .find_merge_base_octopus_tip([left, right])
             .with_context(|| {
                 format!(
-                    "Base segment {base} between {l} and {r} didn't have  single commit reachable",
-                    base = base_sidx.index(),
+                    "Couldn't find merge-base commit between segments {l} and {r} - they are disjoint in the commit-graph, or the base has no reachable commit",
                     l = left.index(),
                     r = right.index()
                 )
-            })?
-            .id
-            .attach(repo);
-        Ok((peel_to_tree(base_commit_id)?, base_sidx))
+            })?;
+        Ok((base_commit.id, base_sidx))
+    }
+
+    /// Pass `step` to `progress`, if set.
+    fn report(progress: &mut Option<&mut dyn FnMut(MergeProgress<'_>)>, step: MergeProgress<'_>) {
+        if let Some(progress) = progress {
+            progress(step);
+        }
+    }
+
+    /// The trees of a merge, as `(base, ours, theirs)`.
+    type MergeKey = (gix::ObjectId, gix::ObjectId, gix::ObjectId);
+
+    /// The tree merges of a single workspace merge, cached by the trees involved so retries after
+    /// conflicts reuse what was merged before, and only merge from the first tip that changed.
+    /// The trees of commits and the merge-bases of segments the merges need are cached as well.
+    struct CachedTreeMerges<'repo> {
+        repo: &'repo gix::Repository,
+        options: gix::merge::tree::Options,
+        conflict_kind: gix::merge::tree::TreatAsUnresolved,
+        /// The merged tree, or `None` if the merge had unresolved conflicts.
+        merged: HashMap<MergeKey, Option<gix::ObjectId>>,
+        /// The sorted paths of all unresolved conflicts of merges that conflicted.
+        conflicting_paths: HashMap<MergeKey, Vec<BString>>,
+        /// The tree of each commit that was needed so far.
+        trees: HashMap<gix::ObjectId, gix::ObjectId>,
+        /// The tree of the merge-base and its segment for each pair of segments.
+        merge_bases: HashMap<(SegmentIndex, SegmentIndex), (gix::ObjectId, SegmentIndex)>,
+    }
+
+    #[cfg(feature = "testing")]
+    thread_local! {
+        static TREE_MERGES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        static TREE_PEELS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Return the number of commits the workspace merge peeled to their tree on the current thread so far.
+    /// This is instrumentation for tests, to assert that known trees aren't looked up again.
+    #[cfg(feature = "testing")]
+    pub fn tree_peels_on_current_thread() -> usize {
+        TREE_PEELS.get()
+    }
+
+    /// Return the number of tree merges the workspace merge performed on the current thread so far.
+    /// This is instrumentation for tests, to assert that merges aren't repeated.
+    #[cfg(feature = "testing")]
+    pub fn tree_merges_on_current_thread() -> usize {
+        TREE_MERGES.get()
+    }
+
+    impl<'repo> CachedTreeMerges<'repo> {
+        fn new(repo: &'repo gix::Repository) -> anyhow::Result<Self> {
+            let (options, conflict_kind) = repo.merge_options_fail_fast()?;
+            Ok(CachedTreeMerges {
+                repo,
+                options,
+                conflict_kind,
+                merged: HashMap::new(),
+                conflicting_paths: HashMap::new(),
+                trees: HashMap::new(),
+                merge_bases: HashMap::new(),
+            })
+        }
+
+        /// Return `known_tree_id` if set, or the tree of `commit_id`.
+        fn tree_id(
+            &mut self,
+            commit_id: gix::ObjectId,
+            known_tree_id: Option<gix::ObjectId>,
+        ) -> anyhow::Result<gix::ObjectId> {
+            if let Some(tree_id) = known_tree_id.or_else(|| self.trees.get(&commit_id).copied()) {
+                return Ok(tree_id);
+            }
+            let tree_id = peel_to_tree(commit_id.attach(self.repo))?;
+            self.trees.insert(commit_id, tree_id);
+            Ok(tree_id)
+        }
+
+        /// Return the tree of the merge-base of the segments `left` and `right`, along with its segment.
+        fn merge_base(
+            &mut self,
+            graph: &but_graph::Graph,
+            left: SegmentIndex,
+            right: SegmentIndex,
+        ) -> anyhow::Result<(gix::ObjectId, SegmentIndex)> {
+            if let Some(base) = self.merge_bases.get(&(left, right)) {
+                return Ok(*base);
+            }
+            let (base_commit_id, base_sidx) = compute_merge_base(graph, left, right)?;
+            let base = (self.tree_id(base_commit_id, None)?, base_sidx);
+            self.merge_bases.insert((left, right), base);
+            Ok(base)
+        }
+
+        /// Merge `theirs` into `ours`, and return the written tree, or `None` if there are unresolved conflicts.
+        /// The merge stops at the first conflict.
+        fn merge(
+            &mut self,
+            base: gix::ObjectId,
+            ours: gix::ObjectId,
+            theirs: gix::ObjectId,
+        ) -> anyhow::Result<Option<gix::ObjectId>> {
+            if let Some(merged) = self.merged.get(&(base, ours, theirs)) {
+                return Ok(*merged);
+            }
+            #[cfg(feature = "testing")]
+            TREE_MERGES.set(TREE_MERGES.get() + 1);
+            let mut merge = self.repo.merge_trees(
+                base,
+                ours,
+                theirs,
+                self.repo.default_merge_labels(),
+                self.options.clone(),
+            )?;
+            let merged = if merge.has_unresolved_conflicts(self.conflict_kind) {
+                None
+            } else {
+                Some(merge.tree.write()?.detach())
+            };
+            self.merged.insert((base, ours, theirs), merged);
+            Ok(merged)
+        }
+
+        /// Merge `theirs` into `ours` and resolve all conflicts by taking the side `on_conflict` favors,
+        /// to return the written tree.
+        fn merge_resolved(
+            &mut self,
+            base: gix::ObjectId,
+            ours: gix::ObjectId,
+            theirs: gix::ObjectId,
+            on_conflict: OnStackConflict,
+        ) -> anyhow::Result<gix::ObjectId> {
+            // Merges can only be forced to favor ours, so swap the sides to favor theirs.
+            let (ours, theirs) = match on_conflict {
+                OnStackConflict::KeepFavoringTheirs => (theirs, ours),
+                OnStackConflict::Unmerge | OnStackConflict::KeepFavoringOurs => (ours, theirs),
+            };
+            #[cfg(feature = "testing")]
+            TREE_MERGES.set(TREE_MERGES.get() + 1);
+            let mut merge = self.repo.merge_trees(
+                base,
+                ours,
+                theirs,
+                self.repo.default_merge_labels(),
+                self.repo.merge_options_force_ours()?,
+            )?;
+            Ok(merge.tree.write()?.detach())
+        }
+
+        /// Merge `theirs` into `ours` without stopping at the first conflict, to return the sorted paths
+        /// of all unresolved conflicts.
+        fn conflicting_paths(
+            &mut self,
+            base: gix::ObjectId,
+            ours: gix::ObjectId,
+            theirs: gix::ObjectId,
+        ) -> anyhow::Result<Vec<BString>> {
+            if let Some(paths) = self.conflicting_paths.get(&(base, ours, theirs)) {
+                return Ok(paths.clone());
+            }
+            #[cfg(feature = "testing")]
+            TREE_MERGES.set(TREE_MERGES.get() + 1);
+            let merge = self.repo.merge_trees(
+                base,
+                ours,
+                theirs,
+                self.repo.default_merge_labels(),
+                self.options.clone().with_fail_on_conflict(None),
+            )?;
+            let mut paths: Vec<_> = merge
+                .conflicts
+                .iter()
+                .filter(|c| c.is_unresolved(self.conflict_kind))
+                .map(|c| c.theirs.location().to_owned())
+                .collect();
+            paths.sort();
+            paths.dedup();
+            self.conflicting_paths
+                .insert((base, ours, theirs), paths.clone());
+            Ok(paths)
+        }
+    }
+
+    /// Pair each of `paths` with the first of `merged_tips` that changed it compared to `base_tree_id`,
+    /// as that's the stack the conflict is with.
+    fn with_other_stacks<'a>(
+        merges: &mut CachedTreeMerges<'_>,
+        paths: Vec<BString>,
+        base_tree_id: gix::ObjectId,
+        merged_tips: impl Iterator<Item = &'a Tip> + Clone,
+    ) -> anyhow::Result<Vec<ConflictingPath>> {
+        let repo = merges.repo;
+        let entry_id = |tree_id: gix::ObjectId, path: &BString| -> anyhow::Result<_> {
+            Ok(repo
+                .find_tree(tree_id)?
+                .lookup_entry_by_path(gix::path::from_bstr(path.as_bstr()))?
+                .map(|entry| entry.object_id()))
+        };
+        paths
+            .into_iter()
+            .map(|path| {
+                let base_id = entry_id(base_tree_id, &path)?;
+                let mut other_stack = None;
+                for tip in merged_tips.clone() {
+                    let tip_tree_id = merges.tree_id(tip.tree_commit_id(), tip.tree_id)?;
+                    if entry_id(tip_tree_id, &path)? != base_id {
+                        other_stack = tip.name.clone();
+                        break;
+                    }
+                }
+                Ok(ConflictingPath { path, other_stack })
+            })
+            .collect()
     }
 
     fn peel_to_tree(commit: gix::Id) -> anyhow::Result<gix::ObjectId> {
+        #[cfg(feature = "testing")]
+        TREE_PEELS.set(TREE_PEELS.get() + 1);
         let commit = but_core::Commit::from_id(commit)?;
         Ok(commit.tree_id_or_auto_resolution()?.detach())
     }
@@ -554,6 +1082,9 @@ pub mod merge {
 /// Construction
 impl<'repo> WorkspaceCommit<'repo> {
     const GITBUTLER_WORKSPACE_COMMIT_TITLE: &'static str = "GitButler Workspace Commit";
+    /// The token of the trailers that list each stack of the workspace commit as `<name> <tip>`,
+    /// or just `<tip>` if the stack has no name.
+    const STACK_TRAILER_TOKEN: &'static str = "GitButler-Stack";
 
     /// Decode the object at `commit_id` and keep its data for later query.
     pub fn from_id(commit_id: gix::Id<'repo>) -> anyhow::Result<Self> {
@@ -570,12 +1101,18 @@ impl<'repo> WorkspaceCommit<'repo> {
 
     /// A way to create a commit from `workspace` stacks, with the `tree` being used as the tree of the workspace commit.
     /// It's supposed to be the legitimate merge of the stacks contained in `workspace`.
+    /// If `verify` is `true`, the stacks are merged again in memory to assure that `tree` is exactly that, and it's an error
+    /// naming the differing paths if it's not. As this is costly, it's meant for tests and diagnostics.
     /// Note that it will be written to `repo` immediately for persistence, with its object id returned.
     pub fn from_graph_workspace_and_tree(
         workspace: &but_graph::Workspace,
         repo: &'repo gix::Repository,
         tree: gix::ObjectId,
+        verify: bool,
     ) -> anyhow::Result<Self> {
+        if verify {
+            Self::verify_merged_tree(workspace, repo, tree)?;
+        }
         let stacks: Vec<_> = workspace
             .stacks
             .iter()
@@ -598,33 +1135,146 @@ impl<'repo> WorkspaceCommit<'repo> {
         let mut ws_commit = Self::new_from_stacks(stacks, repo.object_hash());
         ws_commit.tree = tree;
 
-        Self::fixup_times(&mut ws_commit, repo);
-        let id = repo.write_object(&ws_commit)?;
+        Self::fixup_signatures(&mut ws_commit, repo);
+        let id = Self::write_maybe_signed(&mut ws_commit, repo)?;
         Ok(Self {
             id,
             inner: ws_commit,
         })
     }
 
-    /// also rewrite the author and committer time, just to be sure we respect all settings. `new_from_stacks` doesn't have a repo.
-    fn fixup_times(ws_commit: &mut gix::objs::Commit, repo: &gix::Repository) {
-        fn try_time(
+    /// Merge the tips of all stacks in `workspace` in memory, and fail if the result isn't `tree`.
+    fn verify_merged_tree(
+        workspace: &but_graph::Workspace,
+        repo: &gix::Repository,
+        tree: gix::ObjectId,
+    ) -> anyhow::Result<()> {
+        let tips = workspace
+            .stacks
+            .iter()
+            .filter_map(|s| {
+                Some(merge::Tip {
+                    name: s.ref_name().map(ToOwned::to_owned),
+                    commit_id: s.tip_skip_empty().or(s.base())?,
+                    segment_idx: s.segments.first()?.id,
+                    merge_tree: true,
+                    tree_commit_id: None,
+                    tree_id: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        let repo = repo.clone().with_object_memory();
+        let outcome = Self::from_new_merge_with_tips(
+            tips,
+            &workspace.graph,
+            &repo,
+            None,
+            merge::OnStackConflict::Unmerge,
+            None,
+        )?;
+        if outcome.has_conflicts() {
+            anyhow::bail!(
+                "The stacks of the workspace can't be merged as {} conflicted",
+                outcome
+                    .conflicting_stacks
+                    .iter()
+                    .map(|s| s
+                        .ref_name
+                        .as_ref()
+                        .map_or_else(|| s.tip.to_string(), |rn| rn.shorten().to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let merged_tree = repo
+            .find_commit(outcome.workspace_commit_id)?
+            .tree_id()?
+            .detach();
+        if merged_tree != tree {
+            let paths = but_core::diff::tree_changes(&repo, Some(merged_tree), tree)?
+                .into_iter()
+                .map(|change| change.path.to_string())
+                .collect::<Vec<_>>();
+            anyhow::bail!(
+                "The workspace tree {tree} isn't the merge of its stacks, which is {merged_tree}. Differing paths: {}",
+                paths.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Rewrite the author and committer time with the ones `repo` is configured with, as `new_from_stacks` doesn't have a repo.
+    /// Each time `repo` doesn't have is kept, which then is the one `new_from_stacks` took from the environment.
+    /// If [`WORKSPACE_COMMITTER_USE_REPO_IDENTITY`] is set, the configured names and emails are used as well.
+    fn fixup_signatures(ws_commit: &mut gix::objs::Commit, repo: &gix::Repository) {
+        fn try_signature(
             sig: Option<Result<gix::actor::SignatureRef<'_>, gix::config::time::Error>>,
-        ) -> Option<gix::date::Time> {
-            sig.transpose().ok().flatten().and_then(|s| s.time().ok())
+        ) -> Option<gix::actor::SignatureRef<'_>> {
+            sig.transpose().ok().flatten()
         }
-        if let Some(committer_time) = try_time(repo.committer()) {
-            ws_commit.committer.time = committer_time;
+        let use_repo_identity = repo
+            .config_snapshot()
+            .boolean(WORKSPACE_COMMITTER_USE_REPO_IDENTITY)
+            .unwrap_or_default();
+        for (signature, configured) in [
+            (&mut ws_commit.committer, try_signature(repo.committer())),
+            (&mut ws_commit.author, try_signature(repo.author())),
+        ] {
+            let Some(configured) = configured else {
+                continue;
+            };
+            if let Ok(time) = configured.time() {
+                signature.time = time;
+            }
+            if use_repo_identity {
+                signature.name = configured.name.into();
+                signature.email = configured.email.into();
+            }
         }
-        if let Some(author_time) = try_time(repo.committer()) {
-            ws_commit.author.time = author_time;
+    }
+
+    /// Write `ws_commit` to `repo` and return its id, after signing it like any other commit if
+    /// [`WORKSPACE_COMMIT_SIGN`] is set. If signing fails, `ws_commit` is written unsigned and a warning is logged,
+    /// unless [`WORKSPACE_COMMIT_SIGN_STRICT`] is set as well, which makes it an error.
+    fn write_maybe_signed<'a>(
+        ws_commit: &mut gix::objs::Commit,
+        repo: &'a gix::Repository,
+    ) -> anyhow::Result<gix::Id<'a>> {
+        use gix::objs::WriteTo;
+
+        let config = repo.config_snapshot();
+        if config.boolean(WORKSPACE_COMMIT_SIGN).unwrap_or_default() {
+            let mut buf = Vec::new();
+            ws_commit.write_to(&mut buf)?;
+            match but_core::commit::sign_buffer(repo, &buf) {
+                Ok(signature) => ws_commit
+                    .extra_headers
+                    .push((gix::objs::commit::SIGNATURE_FIELD_NAME.into(), signature)),
+                Err(err)
+                    if config
+                        .boolean(WORKSPACE_COMMIT_SIGN_STRICT)
+                        .unwrap_or_default() =>
+                {
+                    return Err(err.context("Failed to sign the workspace commit"));
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        ?err,
+                        "Failed to sign the workspace commit, writing it unsigned"
+                    );
+                }
+            }
         }
+        Ok(repo.write_object(&*ws_commit)?)
     }
 
     /// Create a new commit which presents itself as the merge of all the given `stacks`.
+    /// The tips of `stacks` become its parents in order, with each tip listed only once.
     ///
     /// Note that the returned commit lives entirely in memory and would still have to be written to disk.
     /// It still needs its tree set to something non-empty.
+    /// The author and committer time are taken from `GIT_AUTHOR_DATE` and `GIT_COMMITTER_DATE` respectively,
+    /// or are the current time.
     ///
     /// `object_hash` is needed to create an empty tree hash.
     pub fn new_from_stacks(
@@ -668,13 +1318,33 @@ impl<'repo> WorkspaceCommit<'repo> {
         message.push_str("For more information about what we're doing here, check out our docs:\n");
         message
             .push_str("https://docs.gitbutler.com/features/branch-management/integration-branch\n");
+        if !stacks.is_empty() {
+            message.push('\n');
+            for stack in &stacks {
+                message.push_str(Self::STACK_TRAILER_TOKEN);
+                message.push_str(": ");
+                if let Some(name) = &stack.name {
+                    message.push_str(name.to_str_lossy().as_ref());
+                    message.push(' ');
+                }
+                message.push_str(&stack.tip.to_string());
+                message.push('\n');
+            }
+        }
 
-        let author = commit_signature(commit_time("GIT_COMMITTER_DATE"));
         gix::objs::Commit {
             tree: gix::ObjectId::empty_tree(object_hash),
-            parents: stacks.iter().map(|s| s.tip).collect(),
-            committer: author.clone(),
-            author,
+            parents: stacks
+                .iter()
+                .map(|s| s.tip)
+                .fold(Vec::new(), |mut parents, tip| {
+                    if !parents.contains(&tip) {
+                        parents.push(tip);
+                    }
+                    parents
+                }),
+            committer: commit_signature(commit_time("GIT_COMMITTER_DATE")),
+            author: commit_signature(commit_time("GIT_AUTHOR_DATE")),
             encoding: Some("UTF-8".into()),
             message: message.into(),
             extra_headers: vec![],
@@ -682,6 +1352,20 @@ impl<'repo> WorkspaceCommit<'repo> {
     }
 }
 
+/// The boolean Git configuration key to make workspace commits use the configured author and committer
+/// instead of the GitButler identity. Workspace commits are recognized by their message, so this doesn't affect
+/// [`WorkspaceCommit::is_managed()`].
+pub const WORKSPACE_COMMITTER_USE_REPO_IDENTITY: &str =
+    "gitbutler.workspaceCommitterUseRepoIdentity";
+
+/// The boolean Git configuration key to sign workspace commits with the key and format configured for signing commits.
+pub const WORKSPACE_COMMIT_SIGN: &str = "gitbutler.signWorkspaceCommit";
+
+/// The boolean Git configuration key to fail writing a workspace commit if it can't be signed, instead of
+/// writing it unsigned. Only used if [`WORKSPACE_COMMIT_SIGN`] is set.
+pub const WORKSPACE_COMMIT_SIGN_STRICT: &str = "gitbutler.signWorkspaceCommitStrict";
+
+/// The GitButler identity of workspace commits, unless [`WORKSPACE_COMMITTER_USE_REPO_IDENTITY`] is set.
 fn commit_signature(time: gix::date::Time) -> gix::actor::Signature {
     gix::actor::Signature {
         name: "GitButler".into(),
@@ -707,6 +1391,73 @@ impl WorkspaceCommit<'_> {
     pub fn is_managed(&self) -> bool {
         but_graph::workspace::commit::is_managed_workspace_by_message(self.message.as_bstr())
     }
+
+    /// Return the generation of this workspace commit, as told by its title, or `None` if it's not [managed](Self::is_managed()).
+    ///
+    /// Commits of older generations are rewritten to the current one whenever the workspace commit is recreated,
+    /// as [`Self::new_from_stacks()`] only creates commits of the current generation.
+    pub fn generation(&self) -> Option<but_graph::workspace::commit::WorkspaceCommitGeneration> {
+        but_graph::workspace::commit::workspace_commit_generation_by_message(self.message.as_bstr())
+    }
+
+    /// Return the stacks that this commit claims to merge, in order, as recorded in its message by [`Self::new_from_stacks()`].
+    ///
+    /// They are taken from the [trailers](Self::stacks_from_trailers()) if there are any, or are parsed from
+    /// the list of branches in the prose of older workspace commits otherwise.
+    /// Entries that can't be parsed are skipped, so the result may be incomplete for messages that were edited by hand.
+    pub fn parsed_stacks(&self) -> Vec<Stack> {
+        self.stacks_from_trailers()
+            .unwrap_or_else(|| self.stacks_from_legacy_message())
+    }
+
+    /// Parse the ` - <name>` lines and the `   branch head: <tip>` lines that follow them,
+    /// which is all that workspace commits recorded before they had trailers.
+    fn stacks_from_legacy_message(&self) -> Vec<Stack> {
+        let mut stacks = Vec::new();
+        let mut name = None;
+        for line in self.message.lines() {
+            if let Some(stack_name) = line.strip_prefix(b" - ") {
+                name = Some(stack_name.trim().into());
+            } else if let Some(tip) = line.trim_start().strip_prefix(b"branch head:") {
+                let name = name.take();
+                let Ok(tip) = gix::ObjectId::from_hex(tip.trim()) else {
+                    continue;
+                };
+                stacks.push(Stack { tip, name });
+            }
+        }
+        stacks
+    }
+
+    /// Return the stacks listed in the `GitButler-Stack` trailers of the commit message, in order,
+    /// or `None` if there are no such trailers, as is the case for workspace commits that predate them.
+    /// Trailers whose tip isn't a valid object id are ignored.
+    pub fn stacks_from_trailers(&self) -> Option<Vec<Stack>> {
+        let mut stacks = Vec::new();
+        let mut saw_trailer = false;
+        for line in self.message.lines() {
+            let Some(value) = line
+                .strip_prefix(Self::STACK_TRAILER_TOKEN.as_bytes())
+                .and_then(|rest| rest.strip_prefix(b":"))
+            else {
+                continue;
+            };
+            saw_trailer = true;
+            let value = value.trim();
+            let (name, tip) = match value.rfind_byte(b' ') {
+                Some(pos) => (Some(value[..pos].trim()), &value[pos + 1..]),
+                None => (None, value),
+            };
+            let Ok(tip) = gix::ObjectId::from_hex(tip) else {
+                continue;
+            };
+            stacks.push(Stack {
+                tip,
+                name: name.filter(|name| !name.is_empty()).map(Into::into),
+            });
+        }
+        saw_trailer.then_some(stacks)
+    }
 }
 
 impl std::ops::Deref for WorkspaceCommit<'_> {
@@ -722,3 +1473,256 @@ impl std::ops::DerefMut for WorkspaceCommit<'_> {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use but_testsupport::gix_testtools;
+
+    use super::{
+        Stack, WORKSPACE_COMMIT_SIGN, WORKSPACE_COMMIT_SIGN_STRICT,
+        WORKSPACE_COMMITTER_USE_REPO_IDENTITY, WorkspaceCommit,
+    };
+
+    fn write_and_read(repo: &gix::Repository) -> anyhow::Result<WorkspaceCommit<'_>> {
+        let mut ws_commit = WorkspaceCommit::new_from_stacks(None::<Stack>, repo.object_hash());
+        WorkspaceCommit::fixup_signatures(&mut ws_commit, repo);
+        WorkspaceCommit::from_id(WorkspaceCommit::write_maybe_signed(&mut ws_commit, repo)?)
+    }
+
+    fn write_and_read_times(repo: &gix::Repository) -> anyhow::Result<(i64, i64)> {
+        let commit = write_and_read(repo)?;
+        Ok((commit.author.time.seconds, commit.committer.time.seconds))
+    }
+
+    fn write_and_read_identities(repo: &gix::Repository) -> anyhow::Result<[String; 2]> {
+        let commit = write_and_read(repo)?;
+        assert!(
+            commit.is_managed(),
+            "the identity doesn't matter for detection"
+        );
+        Ok([&commit.author, &commit.committer]
+            .map(|signature| format!("{} <{}>", signature.name, signature.email)))
+    }
+
+    #[test]
+    fn author_and_committer_times_are_set_independently() -> anyhow::Result<()> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init_bare(tmp.path())?;
+
+        let repo = gix::open_opts(
+            tmp.path(),
+            gix::open::Options::isolated().config_overrides([
+                gix::config::tree::gitoxide::Commit::AUTHOR_DATE
+                    .validated_assignment("2001-01-01 00:00:00 +0000".into())?,
+                gix::config::tree::gitoxide::Commit::COMMITTER_DATE
+                    .validated_assignment("2002-01-01 00:00:00 +0000".into())?,
+            ]),
+        )?;
+        assert_eq!(
+            write_and_read_times(&repo)?,
+            (978307200, 1009843200),
+            "each time is taken from its own configuration"
+        );
+
+        let repo = but_testsupport::open_repo(tmp.path())?;
+        assert_eq!(
+            write_and_read_times(&repo)?,
+            (946684800, 946771200),
+            "the times configured in the repository are used"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn workspace_commits_can_use_the_configured_identities() -> anyhow::Result<()> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init_bare(tmp.path())?;
+        let mut repo = but_testsupport::open_repo(tmp.path())?;
+        assert_eq!(
+            write_and_read_identities(&repo)?,
+            [
+                "GitButler <gitbutler@gitbutler.com>",
+                "GitButler <gitbutler@gitbutler.com>"
+            ],
+            "by default, workspace commits are made by GitButler"
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value(WORKSPACE_COMMITTER_USE_REPO_IDENTITY, "true")?;
+        assert_eq!(
+            write_and_read_identities(&repo)?,
+            [
+                "Author (Memory Override) <author@example.com>",
+                "Committer (Memory Override) <committer@example.com>"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn workspace_commits_are_written_unsigned_if_signing_fails_unless_strict() -> anyhow::Result<()>
+    {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init_bare(tmp.path())?;
+        let mut repo = but_testsupport::open_repo(tmp.path())?;
+        let is_signed = |commit: &WorkspaceCommit<'_>| {
+            commit
+                .extra_headers()
+                .find(gix::objs::commit::SIGNATURE_FIELD_NAME)
+                .is_some()
+        };
+        assert!(
+            !is_signed(&write_and_read(&repo)?),
+            "workspace commits aren't signed by default"
+        );
+
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_raw_value(WORKSPACE_COMMIT_SIGN, "true")?;
+            config.set_raw_value("gpg.format", "ssh")?;
+            config.set_raw_value("user.signingKey", "/does/not/exist.key")?;
+        }
+        let commit = write_and_read(&repo)?;
+        assert!(
+            !is_signed(&commit) && commit.is_managed(),
+            "signing failures don't prevent the workspace commit from being written"
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value(WORKSPACE_COMMIT_SIGN_STRICT, "true")?;
+        let err = write_and_read(&repo).expect_err("signing can't work without the key");
+        assert_eq!(err.to_string(), "Failed to sign the workspace commit");
+        Ok(())
+    }
+
+    #[test]
+    fn stacks_round_trip_through_trailers() -> anyhow::Result<()> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init_bare(tmp.path())?;
+        let repo = but_testsupport::open_repo(tmp.path())?.with_object_memory();
+        let tip = |hex: &str| gix::ObjectId::from_hex(hex.as_bytes());
+        let stacks = vec![
+            Stack {
+                tip: tip("d3cce74a69ee3b0e1cbea65b53908d602d6bda26")?,
+                name: Some("feature/A".into()),
+            },
+            Stack {
+                tip: tip("115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea")?,
+                name: None,
+            },
+        ];
+        fn write<'repo>(
+            repo: &'repo gix::Repository,
+            stacks: &[Stack],
+            message: Option<&str>,
+        ) -> anyhow::Result<WorkspaceCommit<'repo>> {
+            let mut ws_commit =
+                WorkspaceCommit::new_from_stacks(stacks.to_vec(), repo.object_hash());
+            if let Some(message) = message {
+                ws_commit.message = message.into();
+            }
+            WorkspaceCommit::from_id(repo.write_object(&ws_commit)?)
+        }
+
+        let commit = write(&repo, &stacks, None)?;
+        assert!(commit.is_managed());
+        assert_eq!(commit.stacks_from_trailers(), Some(stacks.clone()));
+        assert!(
+            commit.message.ends_with(
+                b"\n\nGitButler-Stack: feature/A d3cce74a69ee3b0e1cbea65b53908d602d6bda26\n\
+                  GitButler-Stack: 115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea\n"
+            ),
+            "the trailers are the last paragraph, after the prose"
+        );
+
+        let legacy_message = commit.message.to_str()?.split("\n\nGitButler-Stack").next();
+        let commit = write(&repo, &stacks, legacy_message)?;
+        assert!(
+            commit.is_managed(),
+            "commits without trailers are still workspace commits"
+        );
+        assert_eq!(commit.stacks_from_trailers(), None);
+
+        let commit = write(
+            &repo,
+            &stacks,
+            Some(
+                "GitButler Workspace Commit\n\nGitButler-Stack: A not-a-sha\nGitButler-Stack:  B  115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea \n",
+            ),
+        )?;
+        assert_eq!(
+            commit.stacks_from_trailers(),
+            Some(vec![Stack {
+                tip: tip("115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea")?,
+                name: Some("B".into()),
+            }]),
+            "invalid trailers are skipped, and whitespace is ignored"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parsed_stacks_from_trailers_or_legacy_prose() -> anyhow::Result<()> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init_bare(tmp.path())?;
+        let repo = but_testsupport::open_repo(tmp.path())?.with_object_memory();
+        let parse = |message: &str| -> anyhow::Result<Vec<String>> {
+            let mut ws_commit = WorkspaceCommit::new_from_stacks(None::<Stack>, repo.object_hash());
+            ws_commit.message = message.into();
+            let commit = WorkspaceCommit::from_id(repo.write_object(&ws_commit)?)?;
+            Ok(commit
+                .parsed_stacks()
+                .iter()
+                .map(|stack| format!("{stack:?}"))
+                .collect())
+        };
+
+        let legacy = "GitButler Workspace Commit
+
+This is a merge commit of the virtual branches in your workspace.
+
+Here are the branches that are currently applied:
+ - add-A
+   branch head: d3cce74a69ee3b0e1cbea65b53908d602d6bda26
+   branch head: 27ab782831b1145249092d54c520a15bb6425cda
+ - feature/B
+   branch head: 115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea
+For more information about what we're doing here, check out our docs:
+https://docs.gitbutler.com/features/branch-management/integration-branch
+";
+        assert_eq!(
+            parse(legacy)?,
+            [
+                r#"Stack { tip: d3cce74, name: "add-A" }"#,
+                "Stack { tip: 27ab782, name: None }",
+                r#"Stack { tip: 115e41b, name: "feature/B" }"#,
+            ]
+        );
+
+        let with_trailers =
+            format!("{legacy}\nGitButler-Stack: add-C 34c4591eac5ade7cdf094c4fc48dea798ab73bbb\n");
+        assert_eq!(
+            parse(&with_trailers)?,
+            [r#"Stack { tip: 34c4591, name: "add-C" }"#],
+            "trailers are the source of truth, the prose isn't considered"
+        );
+
+        let mangled = "GitButler Workspace Commit
+
+Here are the branches that are currently applied:
+ - add-A
+   branch head: not-a-sha
+ - add-B
+ - add-C
+   branch head:   34c4591eac5ade7cdf094c4fc48dea798ab73bbb  
+   branch head: 34c4591
+";
+        assert_eq!(
+            parse(mangled)?,
+            [r#"Stack { tip: 34c4591, name: "add-C" }"#],
+            "branch heads must be full ids, and names without a head are skipped"
+        );
+        assert!(parse("GitButler Workspace Commit")?.is_empty());
+        Ok(())
+    }
+}
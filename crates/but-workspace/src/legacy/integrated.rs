@@ -2,6 +2,8 @@
 //!
 //! This code is a fork of the [`gitbutler_branch_actions::virtual::IsCommitIntegrated`]
 
+use std::collections::HashSet;
+
 use anyhow::{Context as _, Result};
 use but_core::{RepositoryExt, commit::Headers};
 use gitbutler_commit::commit_ext::CommitExt;
@@ -146,6 +148,30 @@ impl<'repo, 'cache, 'graph> IsCommitIntegrated<'repo, 'cache, 'graph> {
     }
 }
 
+/// Return those of `tips` that are integrated into the target branch at `target_ref_name`,
+/// with the same checks that classify workspace commits as integrated.
+/// `target_base_oid` is the target commit the workspace is currently based on.
+///
+/// **IMPORTANT**: `repo` must use in-memory objects!
+pub fn integrated_tips(
+    repo: &gix::Repository,
+    target_ref_name: &gix::refs::FullNameRef,
+    target_base_oid: gix::ObjectId,
+    tips: impl IntoIterator<Item = gix::ObjectId>,
+) -> Result<HashSet<gix::ObjectId>> {
+    let cache = repo.commit_graph_if_enabled()?;
+    let mut graph = repo.revision_graph(cache.as_ref());
+    let mut check_commit =
+        IsCommitIntegrated::new_with_target(repo, target_ref_name, target_base_oid, &mut graph)?;
+    let mut integrated = HashSet::new();
+    for tip in tips {
+        if check_commit.is_integrated(tip)? {
+            integrated.insert(tip);
+        }
+    }
+    Ok(integrated)
+}
+
 pub(crate) type MergeBaseCommitGraph<'repo, 'cache> = gix::revwalk::Graph<
     'repo,
     'cache,
@@ -11,15 +11,20 @@ use serde::{Deserialize, Serialize};
 
 pub mod head;
 mod integrated;
+pub use integrated::integrated_tips;
 mod stacks;
 pub use head::{
     merge_worktree_with_workspace, remerged_workspace_commit_v2, remerged_workspace_tree_v2,
 };
 
 // TODO: _v3 versions are specifically for the UI, so import them into `ui` instead.
-#[expect(deprecated, reason = "re-exports stacks_v3 and stack_details_v3")]
+#[expect(
+    deprecated,
+    reason = "re-exports stacks_v3, stack_details_v3, stack_details_v3_limited and all_stack_details_v3"
+)]
 pub use stacks::{
-    local_and_remote_commits, stack_branches, stack_details_v3, stack_heads_info, stacks_v3,
+    all_stack_details_v3, local_and_remote_commits, stack_branches, stack_details_v3,
+    stack_details_v3_limited, stack_heads_info, stacks_v3,
 };
 
 /// Various types for the frontend.
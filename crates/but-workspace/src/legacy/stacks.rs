@@ -15,7 +15,7 @@ use gix::date::parse::TimeBuf;
 use tracing::instrument;
 
 use crate::{
-    RefInfo, branch, head_info,
+    branch, head_info,
     legacy::{
         StacksFilter,
         integrated::IsCommitIntegrated,
@@ -286,6 +286,45 @@ pub fn stacks_v3(
     Ok(stacks)
 }
 
+fn new_ref_info_options(
+    project_meta: &ProjectMeta,
+    traversal: &but_graph::init::Options,
+) -> ref_info::Options<'static> {
+    ref_info::Options {
+        project_meta: project_meta.clone(),
+        expensive_commit_info: true,
+        traversal: traversal.clone(),
+        ..Default::default()
+    }
+}
+
+/// Find the stack identified by `stack_id` in the workspace projection of any of its surviving refs,
+/// for when it isn't reachable from `HEAD` anymore.
+fn stack_from_surviving_ref(
+    stack_id: StackId,
+    repo: &gix::Repository,
+    meta: &impl RefMetadata,
+    options: ref_info::Options<'_>,
+) -> anyhow::Result<branch::Stack> {
+    let branch_names_by_stack_id = branch_names_by_stack_id(meta)?;
+    let branch_names = branch_names_by_stack_id
+        .get(&stack_id)
+        .with_context(|| format!("Couldn't find {stack_id} in workspace metadata"))?;
+    let existing_ref = branch_names
+        .iter()
+        .find_map(|ref_name| repo.find_reference(ref_name.as_ref()).ok())
+        .with_context(|| {
+            format!("Couldn't find any refs for stack {stack_id} in the repository")
+        })?;
+    ref_info(existing_ref, meta, options)?
+        .stacks
+        .into_iter()
+        .find(|stack| stack.id == Some(stack_id))
+        .with_context(|| {
+            format!("Really couldn't find {stack_id} in the current workspace projection")
+        })
+}
+
 /// Get additional information for the stack identified by `stack_id`. If `None`, it's the first available stack
 /// and we expect it to have no ID.
 // TODO: StackId shouldn't be used, instead use the ref-name or stack index as universal tip identifier.
@@ -302,27 +341,44 @@ pub fn stack_details_v3(
     project_meta: &ProjectMeta,
     traversal: but_graph::init::Options,
 ) -> anyhow::Result<ui::StackDetails> {
-    // Prefer the current `HEAD` projection if it can still see the requested stack, and only fall
-    // back to resolving from a surviving ref when that stack is no longer reachable from `HEAD`.
-    fn stack_by_id(head_info: RefInfo, stack_id: StackId) -> Option<branch::Stack> {
-        head_info
-            .stacks
-            .into_iter()
-            .find(|stack| stack.id == Some(stack_id))
-    }
-    fn new_ref_info_options(
-        project_meta: &ProjectMeta,
-        traversal: &but_graph::init::Options,
-    ) -> ref_info::Options<'static> {
-        ref_info::Options {
-            project_meta: project_meta.clone(),
-            expensive_commit_info: true,
-            traversal: traversal.clone(),
-            ..Default::default()
-        }
-    }
+    stack_details_v3_inner(stack_id, repo, meta, project_meta, traversal, None)
+}
+
+/// Like [`stack_details_v3()`], but list at most `max_commits_per_branch` commits of each branch, starting at its tip.
+///
+/// The commits that are left out aren't converted at all, which is what makes this cheaper for long branches.
+#[deprecated(
+    note = "Use head_info() and the returned RefInfo instead. Callers that already have a Context should prefer ctx.workspace_* helpers."
+)]
+#[instrument(level = "debug", skip(meta), err(Debug))]
+pub fn stack_details_v3_limited(
+    stack_id: Option<StackId>,
+    repo: &gix::Repository,
+    meta: &impl RefMetadata,
+    project_meta: &ProjectMeta,
+    traversal: but_graph::init::Options,
+    max_commits_per_branch: usize,
+) -> anyhow::Result<ui::StackDetails> {
+    stack_details_v3_inner(
+        stack_id,
+        repo,
+        meta,
+        project_meta,
+        traversal,
+        Some(max_commits_per_branch),
+    )
+}
+
+fn stack_details_v3_inner(
+    stack_id: Option<StackId>,
+    repo: &gix::Repository,
+    meta: &impl RefMetadata,
+    project_meta: &ProjectMeta,
+    traversal: but_graph::init::Options,
+    max_commits_per_branch: Option<usize>,
+) -> anyhow::Result<ui::StackDetails> {
     let mut ref_info_options = new_ref_info_options(project_meta, &traversal);
-    let mut stack = match stack_id {
+    let stack = match stack_id {
         None => {
             // assume single-branch mode.
             // Make sure the UI isn't overwhelmed, this currently happens easily on some repos where a lot of commits
@@ -347,34 +403,93 @@ pub fn stack_details_v3(
             }
         }
         Some(stack_id) => {
-            if let Some(stack) = stack_by_id(
-                head_info(repo, meta, new_ref_info_options(project_meta, &traversal))?,
-                stack_id,
-            ) {
+            // Prefer the current `HEAD` projection if it can still see the requested stack, and only fall
+            // back to resolving from a surviving ref when that stack is no longer reachable from `HEAD`.
+            if let Some(stack) = head_info(repo, meta, ref_info_options)?
+                .stacks
+                .into_iter()
+                .find(|stack| stack.id == Some(stack_id))
+            {
                 stack
             } else {
-                let branch_names_by_stack_id = branch_names_by_stack_id(meta)?;
-                let branch_names = branch_names_by_stack_id
-                    .get(&stack_id)
-                    .with_context(|| format!("Couldn't find {stack_id} in workspace metadata"))?;
-                let existing_ref = branch_names
-                    .iter()
-                    .find_map(|ref_name| repo.find_reference(ref_name.as_ref()).ok())
-                    .with_context(|| {
-                        format!("Couldn't find any refs for stack {stack_id} in the repository")
-                    })?;
-                let ref_info = ref_info(
-                    existing_ref,
+                stack_from_surviving_ref(
+                    stack_id,
+                    repo,
                     meta,
                     new_ref_info_options(project_meta, &traversal),
-                )?;
-                stack_by_id(ref_info, stack_id).with_context(|| {
-                    format!("Really couldn't find {stack_id} in the current workspace projection")
-                })?
+                )?
             }
         }
     };
+    stack_details_from_stack(repo, stack, max_commits_per_branch)
+}
 
+/// Get additional information for all stacks that pass `filter` and have a stack id.
+///
+/// This yields the same details as calling [`stack_details_v3()`] for each stack listed by [`stacks_v3()`],
+/// but computes the workspace projection only once. Only unapplied stacks still need a projection of their own.
+#[deprecated(
+    note = "Use head_info() and the returned RefInfo instead. Callers that already have a Context should prefer ctx.workspace_* helpers."
+)]
+#[instrument(level = "debug", skip(meta), err(Debug))]
+pub fn all_stack_details_v3(
+    repo: &gix::Repository,
+    meta: &impl RefMetadata,
+    project_meta: &ProjectMeta,
+    traversal: but_graph::init::Options,
+    filter: StacksFilter,
+) -> anyhow::Result<Vec<(StackId, ui::StackDetails)>> {
+    let mut applied_stacks =
+        head_info(repo, meta, new_ref_info_options(project_meta, &traversal))?.stacks;
+    // Like `stacks_v3()`, only show the checked-out stack if `HEAD` points into one.
+    if applied_stacks
+        .iter()
+        .any(|stack| stack.segments.iter().any(|segment| segment.is_entrypoint))
+    {
+        applied_stacks.retain(|stack| stack.segments.iter().any(|segment| segment.is_entrypoint));
+        applied_stacks.truncate(1);
+    }
+    let applied_ids: HashSet<_> = applied_stacks.iter().filter_map(|stack| stack.id).collect();
+
+    let mut out = Vec::new();
+    if matches!(filter, StacksFilter::All | StacksFilter::Unapplied) {
+        let unapplied_ids = default_workspace_metadata(meta)?
+            .map(|ws| {
+                ws.stacks(StackKind::AppliedAndUnapplied)
+                    .map(|stack| stack.id)
+                    .filter(|id| !applied_ids.contains(id))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for stack_id in unapplied_ids {
+            let Ok(stack) = stack_from_surviving_ref(
+                stack_id,
+                repo,
+                meta,
+                new_ref_info_options(project_meta, &traversal),
+            ) else {
+                continue;
+            };
+            out.push((stack_id, stack_details_from_stack(repo, stack, None)?));
+        }
+    }
+    if matches!(filter, StacksFilter::All | StacksFilter::InWorkspace) {
+        for stack in applied_stacks {
+            let Some(stack_id) = stack.id else {
+                continue;
+            };
+            out.push((stack_id, stack_details_from_stack(repo, stack, None)?));
+        }
+    }
+    Ok(out)
+}
+
+/// Turn `stack` into its details, with at most `max_commits_per_branch` commits for each branch if set.
+fn stack_details_from_stack(
+    repo: &gix::Repository,
+    mut stack: branch::Stack,
+    max_commits_per_branch: Option<usize>,
+) -> anyhow::Result<ui::StackDetails> {
     // This is more of a badly tested hack to quickly filter parts of a stack that aren't checked out.
     // Better to switch over to the new data-structured for proper handling of detached heads, and anonymous segments.
     if let Some(head_ref) = repo.head_ref()? {
@@ -406,7 +521,7 @@ pub fn stack_details_v3(
     let branch_details = stack
         .segments
         .iter()
-        .map(ui::BranchDetails::from_segment)
+        .map(|segment| ui::BranchDetails::from_segment(segment, max_commits_per_branch))
         .collect::<Result<Vec<_>, _>>()?;
 
     let topmost_branch = branch_details
@@ -436,6 +551,7 @@ impl ui::BranchDetails {
             is_entrypoint: _,
             base,
         }: &Segment,
+        max_commits: Option<usize>,
     ) -> anyhow::Result<Self> {
         let ref_info = ref_info
             .clone()
@@ -497,7 +613,11 @@ impl ui::BranchDetails {
                 authors.sort_by(|a, b| a.name.cmp(&b.name));
                 authors
             },
-            commits: commits_unique_from_tip.iter().map(Into::into).collect(),
+            commits: commits_unique_from_tip
+                .iter()
+                .take(max_commits.unwrap_or(usize::MAX))
+                .map(Into::into)
+                .collect(),
             is_conflicted: commits_unique_from_tip.iter().any(|c| c.has_conflicts),
             upstream_commits: commits_unique_in_remote_tracking_branch
                 .iter()
@@ -510,7 +630,7 @@ impl ui::BranchDetails {
 /// Return the branches that belong to a particular [`Stack`]
 /// The entries are ordered from newest to oldest.
 pub fn stack_branches(stack_id: StackId, ctx: &Context) -> anyhow::Result<Vec<ui::Branch>> {
-    let state = state_handle(&ctx.project_data_dir());
+    let state = state_handle(&ctx.shared_project_data_dir());
     let repo = ctx.repo.get()?;
     let remote = ctx.project_meta()?.push_remote_name(&repo)?;
 
@@ -555,7 +675,7 @@ pub fn stack_branch_local_and_remote_commits(
     ctx: &Context,
     repo: &gix::Repository,
 ) -> anyhow::Result<Vec<ui::Commit>> {
-    let state = state_handle(&ctx.project_data_dir());
+    let state = state_handle(&ctx.shared_project_data_dir());
     let stack = state.get_stack(stack_id)?;
 
     let branches = stack.branches();
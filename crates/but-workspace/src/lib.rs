@@ -44,6 +44,7 @@ pub use tree_manipulation::discard_worktree_changes::discard_workspace_changes;
 /// 🚧utilities for applying and unapplying branches 🚧.
 /// Ignore the name of this module; it's just a place to put code by now.
 pub mod branch;
+pub use branch::{CommitLocation, cleanup_missing_stacks, stack_containing_commit};
 
 mod changeset;
 mod divergence;
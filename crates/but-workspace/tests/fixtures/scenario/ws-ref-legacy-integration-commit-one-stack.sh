@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+source "${BASH_SOURCE[0]%/*}/shared.sh"
+
+### General Description
+
+# A ws-ref points to a workspace commit titled like older GitButler versions did, with one stack inside.
+# Another branch can be applied.
+git init
+commit M
+setup_target_to_match_main
+
+git checkout -b C
+  commit C
+git checkout main
+
+git checkout -b A
+  commit A
+git checkout -b gitbutler/workspace
+  commit "GitButler Integration Commit"
@@ -13,18 +13,21 @@ use but_core::{
 use but_graph::{
     Graph,
     init::{Options, Overlay, Tip},
-    workspace::WorkspaceKind,
+    workspace::{WorkspaceKind, commit::WorkspaceCommitGeneration},
 };
 use but_testsupport::{
     CommandExt, InMemoryRefMetadata, git, graph_workspace, graph_workspace_determinisitcally,
     id_at, id_by_rev, sanitize_uuids_and_timestamps, visualize_commit_graph_all,
     visualize_disk_tree_with_hashes_skip_dot_git,
 };
-use but_workspace::branch::{
-    OnWorkspaceMergeConflict,
-    apply::{OutcomeStatus, WorkspaceMerge, WorkspaceReferenceNaming},
-    create_reference::{Anchor, Position::Above},
-    unapply::WorkspaceDisposition,
+use but_workspace::{
+    WorkspaceCommit,
+    branch::{
+        OnWorkspaceMergeConflict,
+        apply::{OutcomeStatus, WorkspaceMerge, WorkspaceReferenceNaming},
+        create_reference::{Anchor, Position::Above},
+        unapply::WorkspaceDisposition,
+    },
 };
 use gix::refs::{Category, transaction::PreviousValue};
 use snapbox::prelude::*;
@@ -3310,6 +3313,47 @@ fn apply_repairs_stale_outside_metadata_for_reachable_branch() -> anyhow::Result
     Ok(())
 }
 
+#[test]
+fn apply_rewrites_legacy_workspace_commit_to_current_generation() -> anyhow::Result<()> {
+    let (_tmp, graph, repo, mut meta, _description) =
+        named_writable_scenario_with_description_and_graph(
+            "ws-ref-legacy-integration-commit-one-stack",
+            |meta| {
+                add_stack_with_segments(meta, 1, "A", StackState::InWorkspace, &[]);
+            },
+        )?;
+    let head_generation =
+        || -> anyhow::Result<_> { Ok(WorkspaceCommit::from_id(repo.head_id()?)?.generation()) };
+    assert_eq!(
+        head_generation()?,
+        Some(WorkspaceCommitGeneration::Integration)
+    );
+    assert!(
+        graph.managed_entrypoint_commit(&repo)?.is_some(),
+        "legacy workspace commits are recognized as such, and are not considered part of a stack"
+    );
+
+    let ws = graph.into_workspace()?;
+    let out =
+        but_workspace::branch::apply(r("refs/heads/C"), ws, &repo, &mut meta, apply_options())?;
+    assert_eq!(out.status, OutcomeStatus::Applied);
+    assert_eq!(
+        head_generation()?,
+        Some(WorkspaceCommitGeneration::Workspace),
+        "the workspace commit is recreated in the current format"
+    );
+    let head = repo.head_commit()?;
+    assert_eq!(
+        head.parent_ids().map(|id| id.detach()).collect::<Vec<_>>(),
+        [
+            id_by_rev(&repo, "A").detach(),
+            id_by_rev(&repo, "C").detach()
+        ],
+        "the legacy workspace commit isn't kept as parent"
+    );
+    Ok(())
+}
+
 #[test]
 fn apply_multiple_segments_of_stack_in_order_merge_if_needed() -> anyhow::Result<()> {
     let (_tmp, graph, repo, mut meta, _description) =
@@ -6095,6 +6139,7 @@ fn apply_options() -> but_workspace::branch::apply::Options {
         workspace_reference_naming: WorkspaceReferenceNaming::Default,
         order: None,
         new_stack_id: Some(stack_id_for_name),
+        merge_progress: None,
     }
 }
 
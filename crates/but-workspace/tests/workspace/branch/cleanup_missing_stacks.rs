@@ -0,0 +1,183 @@
+use but_core::{
+    RefMetadata,
+    ref_metadata::{StackId, StackKind},
+};
+use but_graph::init::Options;
+use but_meta::VirtualBranchesTomlMetadata;
+use but_testsupport::{CommandExt, git};
+use but_workspace::{
+    WorkspaceCommit,
+    commit::merge::{OnStackConflict, Outcome, StackFilter},
+};
+
+use crate::{
+    ref_info::with_workspace_commit::utils::{
+        StackState, add_stack_with_segments, named_writable_scenario,
+    },
+    utils::r,
+};
+
+#[test]
+fn stacks_of_deleted_refs_are_removed() -> anyhow::Result<()> {
+    let (_tmp, repo, mut meta) = named_writable_scenario("various-heads-for-merge-conflict")?;
+    add_stacks(&mut meta);
+    git(&repo).args(["branch", "-D", "clean-A"]).run();
+
+    let outcome = merge_workspace(&repo, &meta)?;
+    assert_eq!(short_names(&outcome.missing_stacks), ["clean-A"]);
+    assert_eq!(
+        outcome.conflicting_stacks.len(),
+        1,
+        "conflict-C2 conflicts with conflict-C1, but exists"
+    );
+
+    let removed =
+        but_workspace::cleanup_missing_stacks(&mut meta, r(WS_REF), &outcome.missing_stacks)?;
+    assert_eq!(short_names(&removed), ["clean-A"]);
+    assert_eq!(
+        stacks_in_workspace(&meta)?,
+        [
+            (
+                "conflict-C1".to_owned(),
+                StackId::from_number_for_testing(2)
+            ),
+            ("clean-B".to_owned(), StackId::from_number_for_testing(3)),
+            (
+                "conflict-C2".to_owned(),
+                StackId::from_number_for_testing(4)
+            ),
+        ],
+        "the conflicting stack is kept, and so are the ids of all other stacks"
+    );
+
+    let removed =
+        but_workspace::cleanup_missing_stacks(&mut meta, r(WS_REF), &outcome.missing_stacks)?;
+    assert!(removed.is_empty(), "there is nothing left to remove");
+    Ok(())
+}
+
+#[test]
+fn stacks_of_renamed_refs_are_removed() -> anyhow::Result<()> {
+    let (_tmp, repo, mut meta) = named_writable_scenario("various-heads-for-merge-conflict")?;
+    add_stacks(&mut meta);
+    git(&repo)
+        .args(["branch", "-m", "clean-B", "clean-B-renamed"])
+        .run();
+
+    let outcome = merge_workspace(&repo, &meta)?;
+    assert_eq!(short_names(&outcome.missing_stacks), ["clean-B"]);
+
+    let removed =
+        but_workspace::cleanup_missing_stacks(&mut meta, r(WS_REF), &outcome.missing_stacks)?;
+    assert_eq!(short_names(&removed), ["clean-B"]);
+    assert_eq!(
+        stacks_in_workspace(&meta)?,
+        [
+            ("clean-A".to_owned(), StackId::from_number_for_testing(1)),
+            (
+                "conflict-C1".to_owned(),
+                StackId::from_number_for_testing(2)
+            ),
+            (
+                "conflict-C2".to_owned(),
+                StackId::from_number_for_testing(4)
+            ),
+        ],
+        "the renamed branch isn't added, it's up to the user to apply it again"
+    );
+    Ok(())
+}
+
+#[test]
+fn all_stacks_can_be_missing() -> anyhow::Result<()> {
+    let (_tmp, repo, mut meta) = named_writable_scenario("various-heads-for-merge-conflict")?;
+    add_stacks(&mut meta);
+    git(&repo).args(["checkout", "gitbutler/workspace"]).run();
+    git(&repo)
+        .args([
+            "branch",
+            "-D",
+            "clean-A",
+            "conflict-C1",
+            "clean-B",
+            "conflict-C2",
+        ])
+        .run();
+
+    let graph = but_graph::Graph::from_head(
+        &repo,
+        &meta,
+        but_core::ref_metadata::ProjectMeta::default(),
+        Options::limited(),
+    )?;
+    let ws_md = meta.workspace(r(WS_REF))?;
+    let tips = WorkspaceCommit::tips_from_metadata(&ws_md.stacks, StackFilter::All, None, &graph);
+    assert!(tips.tips.is_empty(), "there is nothing left to merge");
+    assert_eq!(
+        short_names(&tips.missing_stacks),
+        ["clean-A", "conflict-C1", "clean-B", "conflict-C2"]
+    );
+
+    let removed =
+        but_workspace::cleanup_missing_stacks(&mut meta, r(WS_REF), &tips.missing_stacks)?;
+    assert_eq!(
+        short_names(&removed),
+        ["clean-A", "conflict-C1", "clean-B", "conflict-C2"]
+    );
+    assert!(
+        stacks_in_workspace(&meta)?.is_empty(),
+        "the workspace is empty now, which is fine"
+    );
+    Ok(())
+}
+
+const WS_REF: &str = "refs/heads/gitbutler/workspace";
+
+fn add_stacks(meta: &mut VirtualBranchesTomlMetadata) {
+    for (idx, name) in ["clean-A", "conflict-C1", "clean-B", "conflict-C2"]
+        .into_iter()
+        .enumerate()
+    {
+        add_stack_with_segments(meta, idx as u128 + 1, name, StackState::InWorkspace, &[]);
+    }
+}
+
+fn merge_workspace(
+    repo: &gix::Repository,
+    meta: &VirtualBranchesTomlMetadata,
+) -> anyhow::Result<Outcome> {
+    let graph = but_graph::Graph::from_head(
+        repo,
+        meta,
+        but_core::ref_metadata::ProjectMeta::default(),
+        Options::limited(),
+    )?;
+    let ws_md = meta.workspace(r(WS_REF))?;
+    WorkspaceCommit::from_new_merge_with_metadata(
+        &ws_md.stacks,
+        StackFilter::All,
+        None,
+        &graph,
+        &repo.clone().with_object_memory(),
+        None,
+        OnStackConflict::Unmerge,
+        None,
+    )
+}
+
+fn stacks_in_workspace(
+    meta: &VirtualBranchesTomlMetadata,
+) -> anyhow::Result<Vec<(String, StackId)>> {
+    Ok(meta
+        .workspace(r(WS_REF))?
+        .stacks(StackKind::Applied)
+        .filter_map(|stack| Some((stack.name()?.shorten().to_string(), stack.id)))
+        .collect())
+}
+
+fn short_names(names: &[gix::refs::FullName]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| name.shorten().to_string())
+        .collect()
+}
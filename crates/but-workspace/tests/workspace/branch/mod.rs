@@ -1,7 +1,9 @@
 /// Various journeys with apply and unapply operations.
 mod apply_unapply;
+mod cleanup_missing_stacks;
 mod create_reference;
 mod integrate_branch_upstream;
 mod move_branch;
 mod remove_reference;
+mod stack_containing_commit;
 mod tear_off_branch;
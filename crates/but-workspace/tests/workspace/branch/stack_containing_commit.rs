@@ -0,0 +1,57 @@
+use but_core::ref_metadata::StackId;
+use but_graph::init::Options;
+use but_workspace::CommitLocation;
+
+use crate::ref_info::with_workspace_commit::utils::{
+    StackState, add_stack, project_meta, read_only_in_memory_scenario,
+};
+
+#[test]
+fn local_commits_and_upstream_only_commits_are_found() -> anyhow::Result<()> {
+    let (repo, mut meta) = read_only_in_memory_scenario("remote-advanced-ff")?;
+    add_stack(&mut meta, 1, "A", StackState::InWorkspace);
+    let project_meta = project_meta(&repo)?;
+    let graph = but_graph::Graph::from_head(
+        &repo,
+        &*meta,
+        project_meta.clone(),
+        Options {
+            extra_target_commit_id: project_meta.target_commit_id,
+            ..Options::limited()
+        },
+    )?;
+    let ws = graph.into_workspace()?;
+
+    let a_ref = Some("refs/heads/A".try_into()?);
+    let local_id = repo.rev_parse_single("A")?.detach();
+    assert_eq!(
+        but_workspace::stack_containing_commit(&ws, local_id),
+        Some(CommitLocation {
+            stack_id: Some(StackId::from_number_for_testing(1)),
+            stack_ref_name: a_ref.clone(),
+            ref_name: a_ref.clone(),
+            upstream_only: false,
+        }),
+        "the tip of A is a local commit"
+    );
+
+    let remote_id = repo.rev_parse_single("origin/A")?.detach();
+    assert_eq!(
+        but_workspace::stack_containing_commit(&ws, remote_id),
+        Some(CommitLocation {
+            stack_id: Some(StackId::from_number_for_testing(1)),
+            stack_ref_name: a_ref.clone(),
+            ref_name: a_ref,
+            upstream_only: true,
+        }),
+        "origin/A is one commit ahead and only reachable from the remote tracking branch"
+    );
+
+    let ws_commit_id = repo.head_id()?.detach();
+    assert_eq!(
+        but_workspace::stack_containing_commit(&ws, ws_commit_id),
+        None,
+        "the workspace commit isn't part of any stack"
+    );
+    Ok(())
+}
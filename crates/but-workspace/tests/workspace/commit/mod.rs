@@ -9,12 +9,62 @@ mod reword;
 mod squash_commits;
 mod uncommit_changes;
 
+mod from_graph_workspace_and_tree {
+    use but_workspace::WorkspaceCommit;
+
+    use crate::ref_info::with_workspace_commit::utils::{
+        StackState, add_stack_with_segments, named_writable_scenario_with_description_and_graph,
+    };
+
+    #[test]
+    fn verification_rejects_trees_that_are_not_the_merge_of_all_stacks() -> anyhow::Result<()> {
+        let (_tmp, graph, repo, _meta, _description) =
+            named_writable_scenario_with_description_and_graph(
+                "ws-ref-ws-commit-two-file-stacks",
+                |meta| {
+                    add_stack_with_segments(meta, 1, "A", StackState::InWorkspace, &[]);
+                    add_stack_with_segments(meta, 2, "B", StackState::InWorkspace, &[]);
+                },
+            )?;
+        let repo = repo.with_object_memory();
+        let ws = graph.into_workspace()?;
+
+        let head_tree = repo.head_commit()?.tree_id()?.detach();
+        let commit = WorkspaceCommit::from_graph_workspace_and_tree(&ws, &repo, head_tree, true)?;
+        assert!(
+            commit.is_managed(),
+            "the tree of HEAD is the legitimate merge"
+        );
+
+        let tree_of_a = repo.rev_parse_single("A^{tree}")?.detach();
+        let err = WorkspaceCommit::from_graph_workspace_and_tree(&ws, &repo, tree_of_a, true)
+            .expect_err("the content of B is missing");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "The workspace tree {tree_of_a} isn't the merge of its stacks, which is {head_tree}. Differing paths: B"
+            )
+        );
+        assert!(
+            WorkspaceCommit::from_graph_workspace_and_tree(&ws, &repo, tree_of_a, false).is_ok(),
+            "without verification, the tree is trusted"
+        );
+        Ok(())
+    }
+}
+
 mod from_new_merge_with_metadata {
     use bstr::ByteSlice;
-    use but_core::ref_metadata::WorkspaceCommitRelation::Outside;
+    use but_core::ref_metadata::WorkspaceCommitRelation::{MergeFrom, Outside};
     use but_graph::init::{Options, Overlay};
     use but_testsupport::{visualize_commit_graph_all, visualize_tree};
-    use but_workspace::{WorkspaceCommit, commit::merge::Tip};
+    use but_workspace::{
+        WorkspaceCommit,
+        commit::merge::{
+            MergeProgress, OnStackConflict, StackFilter, Tip, tree_merges_on_current_thread,
+            tree_peels_on_current_thread,
+        },
+    };
     use gix::{prelude::ObjectIdExt, refs::Target};
     use snapbox::prelude::*;
 
@@ -51,10 +101,13 @@ mod from_new_merge_with_metadata {
         )?;
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             None,
+            OnStackConflict::Unmerge,
+            None,
         )?;
         let commit = out.workspace_commit_id.attach(&repo).object()?;
         // This commit is never signed.
@@ -83,6 +136,8 @@ Here are the branches that are currently applied:
 For more information about what we're doing here, check out our docs:
 https://docs.gitbutler.com/features/branch-management/integration-branch
 
+GitButler-Stack: add-A d3cce74a69ee3b0e1cbea65b53908d602d6bda26
+
 "#]]
         );
         snapbox::assert_data_eq!(
@@ -94,7 +149,10 @@ Outcome {
         Stack { tip: d3cce74, name: "add-A" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -119,10 +177,13 @@ f53c910
         )?;
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             Some("refs/heads/has-no-effect-outside-conflicts".try_into()?),
+            OnStackConflict::Unmerge,
+            None,
         )?;
         // It retains order.
         snapbox::assert_data_eq!(
@@ -137,7 +198,10 @@ Outcome {
         Stack { tip: 115e41b, name: "add-B" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -178,6 +242,11 @@ Here are the branches that are currently applied:
 For more information about what we're doing here, check out our docs:
 https://docs.gitbutler.com/features/branch-management/integration-branch
 
+GitButler-Stack: add-D 27ab782831b1145249092d54c520a15bb6425cda
+GitButler-Stack: add-A d3cce74a69ee3b0e1cbea65b53908d602d6bda26
+GitButler-Stack: add-C 34c4591eac5ade7cdf094c4fc48dea798ab73bbb
+GitButler-Stack: add-B 115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea
+
 "#]]
         );
         // Order isn't visible in the merged tree.
@@ -230,6 +299,9 @@ https://docs.gitbutler.com/features/branch-management/integration-branch
             name: None,
             commit_id: commit.id,
             segment_idx: segment.id,
+            merge_tree: true,
+            tree_commit_id: None,
+            tree_id: None,
         };
 
         let mut stacks = to_stacks(["add-A", "add-D", "add-B"]);
@@ -240,10 +312,13 @@ https://docs.gitbutler.com/features/branch-management/integration-branch
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &stacks,
+            StackFilter::All,
             [(2, anon_c_tip)],
             &graph,
             &repo,
             None,
+            OnStackConflict::Unmerge,
+            None,
         )?;
 
         // anonymous stacks preserve order after filtered named parents
@@ -258,11 +333,312 @@ Outcome {
         Stack { tip: 115e41b, name: "add-B" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
+    conflicting_stacks: [],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
+}
+
+"#]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parents_follow_stack_order_without_duplicates() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-clean-merge", "")?;
+        let stack_names = ["add-C", "add-A", "add-D", "add-A", "add-B"];
+        add_stacks(&mut meta, stack_names);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+        let mut stacks = to_stacks(stack_names);
+        stacks
+            .get_mut(2)
+            .expect("add-D is in the middle")
+            .workspacecommit_relation = Outside;
+        let merge = |stacks: &[but_core::ref_metadata::WorkspaceStack]| {
+            WorkspaceCommit::from_new_merge_with_metadata(
+                stacks,
+                StackFilter::All,
+                None,
+                &graph,
+                &repo,
+                None,
+                OnStackConflict::Unmerge,
+                None,
+            )
+        };
+        let parents = |commit_id: gix::ObjectId| -> anyhow::Result<Vec<String>> {
+            Ok(repo
+                .find_commit(commit_id)?
+                .parent_ids()
+                .map(|id| id.to_hex_with_len(7).to_string())
+                .collect())
+        };
+
+        let out = merge(&stacks)?;
+        assert_eq!(
+            parents(out.workspace_commit_id)?,
+            ["34c4591", "d3cce74", "115e41b"],
+            "parents are in metadata order, without the stack outside the workspace and without duplicates"
+        );
+        assert_eq!(
+            out.stacks.len(),
+            4,
+            "both stacks of the shared tip are still listed"
+        );
+        for _ in 0..3 {
+            assert_eq!(
+                merge(&stacks)?.workspace_commit_id,
+                out.workspace_commit_id,
+                "merging the same stacks again yields the same commit"
+            );
+        }
+
+        stacks.reverse();
+        let reversed = merge(&stacks)?;
+        assert_eq!(
+            parents(reversed.workspace_commit_id)?,
+            ["115e41b", "d3cce74", "34c4591"],
+            "only the metadata order decides the parent order"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unmerged_tree_stacks_are_parents_without_their_tree() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-clean-merge", "")?;
+        add_stacks(&mut meta, ["add-A", "add-B", "add-C", "add-D"]);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+
+        let mut stacks = to_stacks(["add-A", "add-B", "add-C", "add-D"]);
+        stacks
+            .get_mut(1)
+            .expect("add-B is the unmerged stack")
+            .workspacecommit_relation = MergeFrom { commit_id: None };
+        stacks
+            .get_mut(3)
+            .expect("add-D is outside of the workspace")
+            .workspacecommit_relation = Outside;
+
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &stacks,
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            None,
+            OnStackConflict::Unmerge,
+            None,
+        )?;
+        snapbox::assert_data_eq!(
+            out.to_debug(),
+            snapbox::str![[r#"
+Outcome {
+    workspace_commit_id: Sha1([..]),
+    stacks: [
+        Stack { tip: d3cce74, name: "add-A" },
+        Stack { tip: 115e41b, name: "add-B" },
+        Stack { tip: 34c4591, name: "add-C" },
+    ],
+    missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [],
+    unmerged_stacks: [
+        Stack { tip: 115e41b, name: "add-B" },
+    ],
+    conflicted_stacks: [],
 }
 
 "#]]
         );
+
+        let commit = out
+            .workspace_commit_id
+            .attach(&repo)
+            .object()?
+            .into_commit();
+        assert_eq!(
+            commit
+                .parent_ids()
+                .map(|id| id.to_hex_with_len(7).to_string())
+                .collect::<Vec<_>>(),
+            ["d3cce74", "115e41b", "34c4591"],
+            "the unmerged stack keeps its position among the parents"
+        );
+        let files = commit
+            .tree()?
+            .iter()
+            .map(|entry| entry.map(|entry| entry.filename().to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            files,
+            ["A", "C"],
+            "only the trees of merged stacks are in the workspace tree"
+        );
+
+        let mut stacks = to_stacks(["add-B"]);
+        stacks
+            .get_mut(0)
+            .expect("add-B is the only stack")
+            .workspacecommit_relation = MergeFrom { commit_id: None };
+        let err = WorkspaceCommit::from_new_merge_with_metadata(
+            &stacks,
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            None,
+            OnStackConflict::Unmerge,
+            None,
+        )
+        .expect_err("a workspace tree needs at least one merged stack");
+        assert!(
+            err.to_string().starts_with(
+                "Cannot create a workspace commit only from stacks whose trees aren't merged"
+            ),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_from_commit_merges_its_tree_with_the_stack_tip_as_parent() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-clean-merge", "")?;
+        add_stacks(&mut meta, ["add-A", "add-B", "add-C"]);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+
+        let base_of_b = repo.rev_parse_single("add-B~1")?.detach();
+        let mut stacks = to_stacks(["add-A", "add-B", "add-C"]);
+        stacks
+            .get_mut(1)
+            .expect("add-B shows an earlier commit")
+            .workspacecommit_relation = MergeFrom {
+            commit_id: Some(base_of_b),
+        };
+
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &stacks,
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            None,
+            OnStackConflict::Unmerge,
+            None,
+        )?;
+        assert!(
+            out.unmerged_stacks.is_empty() && out.conflicting_stacks.is_empty(),
+            "the tree of the given commit is merged"
+        );
+
+        let commit = out
+            .workspace_commit_id
+            .attach(&repo)
+            .object()?
+            .into_commit();
+        assert_eq!(
+            commit
+                .parent_ids()
+                .map(|id| id.to_hex_with_len(7).to_string())
+                .collect::<Vec<_>>(),
+            ["d3cce74", "115e41b", "34c4591"],
+            "the tip of add-B is still the parent"
+        );
+        let files = commit
+            .tree()?
+            .iter()
+            .map(|entry| entry.map(|entry| entry.filename().to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            files,
+            ["A", "C"],
+            "add-B is seen at its first parent, which doesn't have the file B yet"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn filtered_stacks_are_excluded_without_being_looked_up() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-clean-merge", "")?;
+        add_stacks(&mut meta, ["add-A", "add-B", "add-C", "add-D"]);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+        let stacks = to_stacks(["add-A", "add-B", "add-C", "add-D", "gone"]);
+        let except: [gix::refs::FullName; 2] = [
+            "refs/heads/add-B".try_into()?,
+            "refs/heads/gone".try_into()?,
+        ];
+        let only: [gix::refs::FullName; 2] = [
+            "refs/heads/add-C".try_into()?,
+            "refs/heads/gone".try_into()?,
+        ];
+        let merge = |filter| {
+            WorkspaceCommit::from_new_merge_with_metadata(
+                &stacks,
+                filter,
+                None,
+                &graph,
+                &repo,
+                None,
+                OnStackConflict::Unmerge,
+                None,
+            )
+        };
+        let short_names = |names: &[gix::refs::FullName]| {
+            names
+                .iter()
+                .map(|name| name.shorten().to_string())
+                .collect::<Vec<_>>()
+        };
+        let stack_names = |stacks: &[but_workspace::commit::Stack]| {
+            stacks
+                .iter()
+                .filter_map(|s| s.name.as_ref().map(|name| name.shorten().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        let out = merge(StackFilter::Except(&except))?;
+        assert_eq!(stack_names(&out.stacks), ["add-A", "add-C", "add-D"]);
+        assert_eq!(short_names(&out.excluded_stacks), ["add-B", "gone"]);
+        assert!(
+            out.missing_stacks.is_empty(),
+            "excluded stacks aren't looked up, so they can't be missing"
+        );
+
+        let out = merge(StackFilter::Only(&only))?;
+        assert_eq!(stack_names(&out.stacks), ["add-C"]);
+        assert_eq!(
+            short_names(&out.excluded_stacks),
+            ["add-A", "add-B", "add-D"]
+        );
+        assert_eq!(
+            short_names(&out.missing_stacks),
+            ["gone"],
+            "included stacks are still reported if they don't exist"
+        );
         Ok(())
     }
 
@@ -309,10 +685,13 @@ Outcome {
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             Some("refs/heads/conflict-hero".try_into()?),
+            OnStackConflict::Unmerge,
+            None,
         )?;
         snapbox::assert_data_eq!(
             out.to_debug(),
@@ -327,20 +706,39 @@ Outcome {
         Stack { tip: d3cce74, name: "clean-A" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [
         ConflictingStack {
             tip: Sha1(bf09eaee36b845f0ee6af0b4e19731498b6a017b),
             ref_name: FullName(
                 "refs/heads/conflict-F1",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "F1",
+                    other_stack: FullName(
+                        "refs/heads/conflict-hero",
+                    ),
+                },
+            ],
         },
         ConflictingStack {
             tip: Sha1(f2ce66d01ec4227683e16ad679def2ee6aa0d282),
             ref_name: FullName(
                 "refs/heads/conflict-F2",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "F2",
+                    other_stack: FullName(
+                        "refs/heads/conflict-hero",
+                    ),
+                },
+            ],
         },
     ],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -363,10 +761,13 @@ Outcome {
         // Just for show, see what happens if there is no hero.
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             None,
+            OnStackConflict::Unmerge,
+            None,
         )?;
         snapbox::assert_data_eq!(
             out.to_debug(),
@@ -382,14 +783,31 @@ Outcome {
         Stack { tip: d3cce74, name: "clean-A" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [
         ConflictingStack {
             tip: Sha1(4bbb93c2e76f7ae0fe61183ac3774943284ba9af),
             ref_name: FullName(
                 "refs/heads/conflict-hero",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "F1",
+                    other_stack: FullName(
+                        "refs/heads/conflict-F1",
+                    ),
+                },
+                ConflictingPath {
+                    path: "F2",
+                    other_stack: FullName(
+                        "refs/heads/conflict-F2",
+                    ),
+                },
+            ],
         },
     ],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -397,6 +815,128 @@ Outcome {
         Ok(())
     }
 
+    #[test]
+    fn conflict_retries_reuse_previous_tree_merges() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-multi-line-merge-conflict", "")?;
+        let stacks = [
+            "clean-A",
+            "conflict-F1",
+            "clean-B",
+            "conflict-F2",
+            "clean-C",
+            "conflict-hero",
+            "clean-A",
+        ];
+        add_stacks(&mut meta, stacks);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+
+        let merges_before = tree_merges_on_current_thread();
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &to_stacks(stacks),
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            Some("refs/heads/conflict-hero".try_into()?),
+            OnStackConflict::Unmerge,
+            None,
+        )?;
+        let names = |stacks: &[but_workspace::commit::Stack]| {
+            stacks
+                .iter()
+                .map(|s| s.name.clone().unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&out.stacks),
+            ["clean-A", "clean-B", "clean-C", "conflict-hero", "clean-A"],
+            "the outcome is the same as without caching, see `with_multi_line_conflict_journey`"
+        );
+        assert_eq!(
+            tree_merges_on_current_thread() - merges_before,
+            21,
+            "Without caching, each of the four retries that disable a stack in front of the hero \
+             would merge all stacks before it again, and the hero would be merged once more after \
+             the last successful merge-trial, for 28 merges in total"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn known_tip_trees_are_not_peeled_again() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-multi-line-merge-conflict", "")?;
+        let stacks = [
+            "clean-A",
+            "conflict-F1",
+            "clean-B",
+            "conflict-F2",
+            "clean-C",
+            "conflict-hero",
+            "clean-A",
+        ];
+        add_stacks(&mut meta, stacks);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+        let stacks = to_stacks(stacks);
+        let tips =
+            WorkspaceCommit::tips_from_metadata(&stacks, StackFilter::All, None, &graph).tips;
+        let merge = |tips: Vec<Tip>| {
+            WorkspaceCommit::from_new_merge_with_tips(
+                tips,
+                &graph,
+                &repo,
+                Some("refs/heads/conflict-hero".try_into()?),
+                OnStackConflict::Unmerge,
+                None,
+            )
+        };
+
+        let peels_before = tree_peels_on_current_thread();
+        let expected = merge(tips.clone())?;
+        assert_eq!(
+            tree_peels_on_current_thread() - peels_before,
+            7,
+            "each of the 6 distinct tips is peeled once across all retries, along with their merge-base"
+        );
+
+        let tips_with_trees = tips
+            .into_iter()
+            .map(|tip| -> anyhow::Result<_> {
+                let tree_id = but_core::Commit::from_id(tip.commit_id.attach(&repo))?
+                    .tree_id_or_auto_resolution()?
+                    .detach();
+                Ok(Tip {
+                    tree_id: Some(tree_id),
+                    ..tip
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let peels_before = tree_peels_on_current_thread();
+        let out = merge(tips_with_trees)?;
+        assert_eq!(
+            tree_peels_on_current_thread() - peels_before,
+            1,
+            "only the merge-base is peeled as the trees of all tips are known"
+        );
+        assert_eq!(
+            format!("{:?}", (&out.stacks, &out.conflicting_stacks)),
+            format!("{:?}", (&expected.stacks, &expected.conflicting_stacks)),
+            "known trees don't change the outcome"
+        );
+        Ok(())
+    }
+
     #[test]
     fn with_conflict_commits() -> anyhow::Result<()> {
         let (_tmp, mut graph, repo, mut meta, _description) =
@@ -449,10 +989,13 @@ Outcome {
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             None,
+            OnStackConflict::Unmerge,
+            None,
         )?;
         snapbox::assert_data_eq!(
             out.to_debug(),
@@ -464,7 +1007,10 @@ Outcome {
         Stack { tip: 8ab1c4d, name: "unrelated" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -522,10 +1068,13 @@ Outcome {
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             None,
+            OnStackConflict::Unmerge,
+            None,
         )?;
         snapbox::assert_data_eq!(
             out.to_debug(),
@@ -538,14 +1087,25 @@ Outcome {
         Stack { tip: 115e41b, name: "clean-B" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [
         ConflictingStack {
             tip: Sha1(f8392d239500de94b23f42c8ab5508dae1b3b657),
             ref_name: FullName(
                 "refs/heads/conflict-C2",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "C",
+                    other_stack: FullName(
+                        "refs/heads/conflict-C1",
+                    ),
+                },
+            ],
         },
     ],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -583,6 +1143,10 @@ Here are the branches that are currently applied:
 For more information about what we're doing here, check out our docs:
 https://docs.gitbutler.com/features/branch-management/integration-branch
 
+GitButler-Stack: clean-A d3cce74a69ee3b0e1cbea65b53908d602d6bda26
+GitButler-Stack: conflict-C1 6777bd8aff28a87a07739e2f309d3699d93685f9
+GitButler-Stack: clean-B 115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea
+
 "#]]
         );
         snapbox::assert_data_eq!(
@@ -599,10 +1163,13 @@ fc2bf71
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(stacks),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             Some("refs/heads/conflict-C2".try_into()?),
+            OnStackConflict::Unmerge,
+            None,
         )?;
         // TODO: make clean-B show up!
         snapbox::assert_data_eq!(
@@ -616,14 +1183,25 @@ Outcome {
         Stack { tip: f8392d2, name: "conflict-C2" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [
         ConflictingStack {
             tip: Sha1(6777bd8aff28a87a07739e2f309d3699d93685f9),
             ref_name: FullName(
                 "refs/heads/conflict-C1",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "C",
+                    other_stack: FullName(
+                        "refs/heads/conflict-C2",
+                    ),
+                },
+            ],
         },
     ],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -660,6 +1238,10 @@ Here are the branches that are currently applied:
 For more information about what we're doing here, check out our docs:
 https://docs.gitbutler.com/features/branch-management/integration-branch
 
+GitButler-Stack: clean-A d3cce74a69ee3b0e1cbea65b53908d602d6bda26
+GitButler-Stack: clean-B 115e41b0ffb7fcb56f91a9fb64cf4a7b786c1bea
+GitButler-Stack: conflict-C2 f8392d239500de94b23f42c8ab5508dae1b3b657
+
 "#]]
         );
         snapbox::assert_data_eq!(
@@ -676,10 +1258,13 @@ https://docs.gitbutler.com/features/branch-management/integration-branch
 
         let out = WorkspaceCommit::from_new_merge_with_metadata(
             &to_stacks(["conflict-C2", "conflict-C2", "conflict-C1", "clean-A"]),
+            StackFilter::All,
             None,
             &graph,
             &repo,
             Some("refs/heads/conflict-C1".try_into()?),
+            OnStackConflict::Unmerge,
+            None,
         )?;
         snapbox::assert_data_eq!(
             out.to_debug(),
@@ -691,20 +1276,39 @@ Outcome {
         Stack { tip: d3cce74, name: "clean-A" },
     ],
     missing_stacks: [],
+    excluded_stacks: [],
     conflicting_stacks: [
         ConflictingStack {
             tip: Sha1(f8392d239500de94b23f42c8ab5508dae1b3b657),
             ref_name: FullName(
                 "refs/heads/conflict-C2",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "C",
+                    other_stack: FullName(
+                        "refs/heads/conflict-C1",
+                    ),
+                },
+            ],
         },
         ConflictingStack {
             tip: Sha1(f8392d239500de94b23f42c8ab5508dae1b3b657),
             ref_name: FullName(
                 "refs/heads/conflict-C2",
             ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "C",
+                    other_stack: FullName(
+                        "refs/heads/conflict-C1",
+                    ),
+                },
+            ],
         },
     ],
+    unmerged_stacks: [],
+    conflicted_stacks: [],
 }
 
 "#]]
@@ -725,6 +1329,171 @@ Outcome {
         Ok(())
     }
 
+    #[test]
+    fn conflicting_stacks_can_be_kept_with_resolved_conflicts() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-merge-conflict", "")?;
+        let stacks = ["clean-A", "conflict-C1", "clean-B", "conflict-C2"];
+        add_stacks(&mut meta, stacks);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &to_stacks(stacks),
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            None,
+            OnStackConflict::KeepFavoringOurs,
+            None,
+        )?;
+        snapbox::assert_data_eq!(
+            out.to_debug(),
+            snapbox::str![[r#"
+Outcome {
+    workspace_commit_id: Sha1([..]),
+    stacks: [
+        Stack { tip: d3cce74, name: "clean-A" },
+        Stack { tip: 6777bd8, name: "conflict-C1" },
+        Stack { tip: 115e41b, name: "clean-B" },
+        Stack { tip: f8392d2, name: "conflict-C2" },
+    ],
+    missing_stacks: [],
+    excluded_stacks: [],
+    conflicting_stacks: [],
+    unmerged_stacks: [],
+    conflicted_stacks: [
+        ConflictingStack {
+            tip: Sha1(f8392d239500de94b23f42c8ab5508dae1b3b657),
+            ref_name: FullName(
+                "refs/heads/conflict-C2",
+            ),
+            conflicting_paths: [
+                ConflictingPath {
+                    path: "C",
+                    other_stack: FullName(
+                        "refs/heads/conflict-C1",
+                    ),
+                },
+            ],
+        },
+    ],
+}
+
+"#]]
+        );
+        let commit = out.workspace_commit_id.attach(&repo).object()?;
+        snapbox::assert_data_eq!(
+            visualize_tree(commit.peel_to_tree()?.id()).to_string(),
+            snapbox::str![[r#"
+fc2bf71
+├── A:100644:f70f10e "A\n"
+├── B:100644:223b783 "B\n"
+└── C:100644:e2cf5e7 "C1\n"
+
+"#]]
+            .raw()
+        );
+
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &to_stacks(stacks),
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            Some("refs/heads/conflict-C1".try_into()?),
+            OnStackConflict::KeepFavoringTheirs,
+            None,
+        )?;
+        assert_eq!(
+            out.stacks.len(),
+            4,
+            "nothing is left out, so the hero doesn't matter"
+        );
+        assert!(out.conflicting_stacks.is_empty());
+        assert_eq!(
+            out.conflicted_stacks
+                .iter()
+                .map(|s| s.ref_name.as_ref().map(|rn| rn.shorten().to_string()))
+                .collect::<Vec<_>>(),
+            [Some("conflict-C2".to_owned())]
+        );
+        let commit = out.workspace_commit_id.attach(&repo).object()?;
+        snapbox::assert_data_eq!(
+            visualize_tree(commit.peel_to_tree()?.id()).to_string(),
+            snapbox::str![[r#"
+39ba522
+├── A:100644:f70f10e "A\n"
+├── B:100644:223b783 "B\n"
+└── C:100644:c4b2d41 "C2\n"
+
+"#]]
+            .raw()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn progress_reports_each_step() -> anyhow::Result<()> {
+        let (repo, mut meta) =
+            named_read_only_in_memory_scenario("various-heads-for-merge-conflict", "")?;
+        let stacks = ["clean-A", "conflict-C1", "clean-B", "conflict-C2"];
+        add_stacks(&mut meta, stacks);
+        let graph = but_graph::Graph::from_head(
+            &repo,
+            &*meta,
+            but_core::ref_metadata::ProjectMeta::default(),
+            Options::limited(),
+        )?;
+
+        let mut steps = Vec::new();
+        let mut record = |step: MergeProgress<'_>| {
+            steps.push(match step {
+                MergeProgress::TipsResolved { count } => format!("resolved {count}"),
+                MergeProgress::StackMergeStarted { name, index, count } => format!(
+                    "start {index}/{count} {}",
+                    name.map_or_else(|| "anonymous".into(), |name| name.shorten().to_string())
+                ),
+                MergeProgress::StackMergeFinished {
+                    index, conflicted, ..
+                } => format!("finish {index} conflicted={conflicted}"),
+                MergeProgress::Retry => "retry".into(),
+                MergeProgress::WritingCommit => "write".into(),
+            })
+        };
+        let out = WorkspaceCommit::from_new_merge_with_metadata(
+            &to_stacks(stacks),
+            StackFilter::All,
+            None,
+            &graph,
+            &repo,
+            None,
+            OnStackConflict::Unmerge,
+            Some(&mut record),
+        )?;
+        assert_eq!(out.conflicting_stacks.len(), 1);
+        assert_eq!(
+            steps,
+            [
+                "resolved 4",
+                "start 1/4 conflict-C1",
+                "finish 1 conflicted=false",
+                "start 2/4 clean-B",
+                "finish 2 conflicted=false",
+                "start 3/4 conflict-C2",
+                "finish 3 conflicted=true",
+                "write",
+            ],
+            "the first tip is the start of the merge, so it isn't merged itself"
+        );
+        Ok(())
+    }
+
     mod utils {
         use but_core::ref_metadata::{
             StackId, WorkspaceCommitRelation::Merged, WorkspaceStack, WorkspaceStackBranch,
@@ -1,6 +1,6 @@
 #![expect(
     deprecated,
-    reason = "covers calls to but_workspace::legacy::stacks_v3 and but_workspace::legacy::stack_details_v3"
+    reason = "covers calls to but_workspace::legacy::stacks_v3, but_workspace::legacy::stack_details_v3 and but_workspace::legacy::all_stack_details_v3"
 )]
 
 use snapbox::prelude::*;
@@ -72,6 +72,23 @@ pub fn stack_details_v3(
     )
 }
 
+#[deprecated(
+    note = "Use head_info() and the returned RefInfo instead. Callers that already have a Context should prefer ctx.workspace_* helpers."
+)]
+pub fn all_stack_details_v3(
+    repo: &gix::Repository,
+    meta: &but_meta::VirtualBranchesTomlMetadata,
+    filter: StacksFilter,
+) -> anyhow::Result<Vec<(StackId, but_workspace::ui::StackDetails)>> {
+    but_workspace::legacy::all_stack_details_v3(
+        repo,
+        meta,
+        &project_meta(repo)?,
+        but_graph::init::Options::limited(),
+        filter,
+    )
+}
+
 fn first_commit(info: &but_workspace::RefInfo) -> &but_workspace::ref_info::LocalCommit {
     &info.stacks[0].segments[0].commits[0]
 }
@@ -305,8 +305,10 @@ mod stack_details {
     use but_testsupport::{graph_workspace, invoke_bash, visualize_commit_graph_all};
     use snapbox::prelude::*;
 
+    use but_workspace::legacy::StacksFilter;
+
     use crate::ref_info::{
-        head_info, stack_details_v3,
+        all_stack_details_v3, head_info, stack_details_v3, stacks_v3,
         utils::standard_options,
         with_workspace_commit::{
             read_only_in_memory_scenario,
@@ -553,6 +555,27 @@ StackDetails {
         Ok(())
     }
 
+    #[test]
+    fn all_stack_details_match_details_of_each_stack() -> anyhow::Result<()> {
+        let (repo, mut meta) = read_only_in_memory_scenario("multiple-stacks-with-shared-segment")?;
+        add_stack(&mut meta, 1, "B-on-A", StackState::InWorkspace);
+        add_stack(&mut meta, 2, "C-on-A", StackState::InWorkspace);
+
+        let expected = stacks_v3(&repo, &meta, StacksFilter::InWorkspace, None)?
+            .into_iter()
+            .filter_map(|stack| stack.id)
+            .map(|stack_id| {
+                stack_details_v3(Some(stack_id), &repo, &meta).map(|details| (stack_id, details))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(expected.len(), 2, "both stacks are in the workspace");
+
+        // The batch call computes the same details as the per-stack loop.
+        let actual = all_stack_details_v3(&repo, &meta, StacksFilter::InWorkspace)?;
+        snapbox::assert_data_eq!(actual.to_debug(), expected.to_debug());
+        Ok(())
+    }
+
     #[test]
     fn multi_segment_stack_uses_advanced_tip_ref_to_find_full_stack() -> anyhow::Result<()> {
         let (_tmp, repo, mut meta) = named_writable_scenario("ws-ref-ws-commit-one-stack")?;
@@ -1,3 +1,4 @@
+use but_core::ref_metadata::StackId;
 use nonempty::NonEmpty;
 use serde::Serialize;
 
@@ -5,7 +6,11 @@ use crate::{
     CliError, CliId, CliResult, IdMap,
     args::atoms::BranchArg,
     bad_input,
-    id::{CommitId, CommittedFileId, UncommittedHunkOrFile},
+    id::{
+        CommitId, CommittedFileId, UncommittedHunkOrFile,
+        parser::{list_matches, prompt_for_disambiguation},
+    },
+    utils::OutputChannel,
 };
 
 /// An argument atom for cli ids that can match multiple things like branches, commits, files, etc.
@@ -56,6 +61,25 @@ impl CliIdArg {
         }
     }
 
+    /// Like [`Self::resolve_in_workspace()`], but if the argument matches several items and `out`
+    /// allows prompting, the user picks one of them instead of getting an error.
+    pub fn resolve_in_workspace_with_prompt(
+        &self,
+        repo: &gix::Repository,
+        id_map: &IdMap,
+        purpose: Purpose,
+        priority: Option<Priority>,
+        out: &mut OutputChannel,
+    ) -> CliResult<ResolvedCliIdArg> {
+        if let Some(id) = self.try_resolve_with_prompt(repo, id_map, purpose, priority, out)? {
+            Ok(id)
+        } else {
+            Err(bad_input(format!("Could not find {purpose}: '{self}'"))
+                .hint(Self::TARGET_MISSING_HINT)
+                .into())
+        }
+    }
+
     /// Try and resolve the argument to something that might exist in the workspace.
     ///
     /// Returns `Ok(None)` if it doesn't exist in the workspace.
@@ -66,24 +90,20 @@ impl CliIdArg {
         purpose: Purpose,
         priority: Option<Priority>,
     ) -> CliResult<Option<ResolvedCliIdArg>> {
-        let Some(id) = try_resolve_cli_id(self, repo, id_map, purpose, priority)? else {
-            return Ok(None);
-        };
-        Ok(Some(match id {
-            CliId::Branch(branch) => ResolvedCliIdArg::Branch(BranchArg(branch.name)),
-            CliId::Commit(CommitId {
-                commit_id,
-                change_id,
-                ..
-            }) => ResolvedCliIdArg::Commit(commit_id, change_id),
-            CliId::UncommittedHunkOrFile(uncommitted) => {
-                ResolvedCliIdArg::UncommittedHunkOrFile(Box::new(uncommitted))
-            }
-            CliId::PathPrefix { .. } => ResolvedCliIdArg::PathPrefix,
-            CliId::CommittedFile(file) => ResolvedCliIdArg::CommittedFile(file),
-            CliId::Uncommitted { .. } => ResolvedCliIdArg::Uncommitted,
-            CliId::Stack { .. } => ResolvedCliIdArg::Stack,
-        }))
+        Ok(try_resolve_cli_id(self, repo, id_map, purpose, priority, None)?.map(Into::into))
+    }
+
+    /// Like [`Self::try_resolve()`], but if the argument matches several items and `out` allows
+    /// prompting, the user picks one of them instead of getting an error.
+    pub fn try_resolve_with_prompt(
+        &self,
+        repo: &gix::Repository,
+        id_map: &IdMap,
+        purpose: Purpose,
+        priority: Option<Priority>,
+        out: &mut OutputChannel,
+    ) -> CliResult<Option<ResolvedCliIdArg>> {
+        Ok(try_resolve_cli_id(self, repo, id_map, purpose, priority, Some(out))?.map(Into::into))
     }
 
     /// Resolve the argument to a commit that exists in the workspace.
@@ -109,8 +129,14 @@ impl CliIdArg {
         repo: &gix::Repository,
         id_map: &IdMap,
     ) -> CliResult<Option<gix::ObjectId>> {
-        let Some(id) =
-            try_resolve_cli_id(self, repo, id_map, Purpose::Commit, Some(Priority::Commit))?
+        let Some(id) = try_resolve_cli_id(
+            self,
+            repo,
+            id_map,
+            Purpose::Commit,
+            Some(Priority::Commit),
+            None,
+        )?
         else {
             return Ok(None);
         };
@@ -158,8 +184,14 @@ impl CliIdArg {
         repo: &gix::Repository,
         id_map: &IdMap,
     ) -> CliResult<Option<BranchArg>> {
-        let Some(id) =
-            try_resolve_cli_id(self, repo, id_map, Purpose::Branch, Some(Priority::Branch))?
+        let Some(id) = try_resolve_cli_id(
+            self,
+            repo,
+            id_map,
+            Purpose::Branch,
+            Some(Priority::Branch),
+            None,
+        )?
         else {
             return Ok(None);
         };
@@ -181,6 +213,7 @@ impl CliIdArg {
             id_map,
             Purpose::Uncommitted,
             Some(Priority::Uncommitted),
+            None,
         )?
         else {
             return Ok(None);
@@ -272,65 +305,41 @@ fn try_resolve_cli_id(
     id_map: &IdMap,
     purpose: Purpose,
     priority: Option<Priority>,
+    out: Option<&mut OutputChannel>,
 ) -> CliResult<Option<CliId>> {
-    let mut target_ids = arg.parse(repo, id_map)?.into_iter().peekable();
-    let Some(target) = target_ids.next() else {
-        return Ok(None);
-    };
-
-    if target_ids.peek().is_none() {
-        return Ok(Some(target));
+    let mut matches = arg.parse(repo, id_map)?;
+    if matches.len() <= 1 {
+        return Ok(matches.pop());
     }
 
     if let Some(priority) = priority {
-        let mut commits = Vec::new();
-        let mut branches = Vec::new();
-        let mut uncommitted = Vec::new();
-        for id in std::iter::once(target).chain(target_ids) {
-            match id {
-                CliId::Branch(..) => branches.push(id),
-                CliId::Commit { .. } => commits.push(id),
-                CliId::UncommittedHunkOrFile(..) => uncommitted.push(id),
-                CliId::PathPrefix { .. }
-                | CliId::CommittedFile { .. }
-                | CliId::Uncommitted { .. }
-                | CliId::Stack { .. } => {}
-            }
-        }
-
-        match priority {
-            Priority::Branch => {
-                if branches.len() == 1 {
-                    return Ok(Some(branches.pop().unwrap()));
-                }
-            }
-            Priority::Commit => {
-                if commits.len() == 1 {
-                    return Ok(Some(commits.pop().unwrap()));
-                }
-            }
-            Priority::Uncommitted => {
-                if uncommitted.len() == 1 {
-                    return Ok(Some(uncommitted.pop().unwrap()));
-                }
-            }
-            Priority::BranchAndCommit => match (branches.len(), commits.len()) {
-                (1, 0) => {
-                    return Ok(Some(branches.pop().unwrap()));
-                }
-                (0, 1) => {
-                    return Ok(Some(commits.pop().unwrap()));
-                }
-                _ => {}
-            },
+        let is_prioritized = |id: &CliId| match priority {
+            Priority::Branch => matches!(id, CliId::Branch(..)),
+            Priority::Commit => matches!(id, CliId::Commit { .. }),
+            Priority::Uncommitted => matches!(id, CliId::UncommittedHunkOrFile(..)),
+            Priority::BranchAndCommit => matches!(id, CliId::Branch(..) | CliId::Commit { .. }),
+        };
+        let mut prioritized = matches.iter().filter(|id| is_prioritized(id));
+        if let (Some(id), None) = (prioritized.next(), prioritized.next()) {
+            return Ok(Some(id.clone()));
         }
     }
 
-    Err(bad_input(format!(
-        "Ambiguous {purpose} '{arg}', matches multiple items"
-    ))
-    .hint("Use a longer ID to disambiguate")
-    .into())
+    match out {
+        Some(out) if out.can_prompt() => Ok(Some(prompt_for_disambiguation(
+            &arg.0,
+            matches,
+            &format!("the {purpose}"),
+            repo,
+            out,
+        )?)),
+        _ => Err(bad_input(format!(
+            "Ambiguous {purpose} '{arg}', matches multiple items:\n{}",
+            list_matches(&matches, repo)
+        ))
+        .hint("Use a longer ID to disambiguate")
+        .into()),
+    }
 }
 
 /// The "purpose" of the resolution. Used in error messages.
@@ -371,11 +380,31 @@ pub enum ResolvedCliIdArg {
     Branch(BranchArg),
     UncommittedHunkOrFile(Box<UncommittedHunkOrFile>),
     CommittedFile(CommittedFileId),
+    Stack(StackId),
     // These have no data because we don't have any commands that use them. So just add data if you
     // have a use case
     PathPrefix,
     Uncommitted,
-    Stack,
+}
+
+impl From<CliId> for ResolvedCliIdArg {
+    fn from(id: CliId) -> Self {
+        match id {
+            CliId::Branch(branch) => ResolvedCliIdArg::Branch(BranchArg(branch.name)),
+            CliId::Commit(CommitId {
+                commit_id,
+                change_id,
+                ..
+            }) => ResolvedCliIdArg::Commit(commit_id, change_id),
+            CliId::UncommittedHunkOrFile(uncommitted) => {
+                ResolvedCliIdArg::UncommittedHunkOrFile(Box::new(uncommitted))
+            }
+            CliId::PathPrefix { .. } => ResolvedCliIdArg::PathPrefix,
+            CliId::CommittedFile(file) => ResolvedCliIdArg::CommittedFile(file),
+            CliId::Uncommitted { .. } => ResolvedCliIdArg::Uncommitted,
+            CliId::Stack { stack_id, .. } => ResolvedCliIdArg::Stack(stack_id),
+        }
+    }
 }
 
 impl ResolvedCliIdArg {
@@ -400,7 +429,7 @@ impl ResolvedCliIdArg {
             ResolvedCliIdArg::Branch { .. } => "a branch",
             ResolvedCliIdArg::Commit { .. } => "a commit",
             ResolvedCliIdArg::Uncommitted => "uncommitted changes",
-            ResolvedCliIdArg::Stack => "a stack",
+            ResolvedCliIdArg::Stack(_) => "a stack",
         }
     }
 
@@ -417,7 +446,7 @@ impl ResolvedCliIdArg {
             ResolvedCliIdArg::CommittedFile(file) => ResolvedCliIdArgRef::CommittedFile(file),
             ResolvedCliIdArg::PathPrefix => ResolvedCliIdArgRef::PathPrefix,
             ResolvedCliIdArg::Uncommitted => ResolvedCliIdArgRef::Uncommitted,
-            ResolvedCliIdArg::Stack => ResolvedCliIdArgRef::Stack,
+            ResolvedCliIdArg::Stack(_) => ResolvedCliIdArgRef::Stack,
         }
     }
 }
@@ -431,7 +460,7 @@ impl std::fmt::Display for ResolvedCliIdArg {
             ResolvedCliIdArg::PathPrefix => f.write_str("path"),
             ResolvedCliIdArg::CommittedFile { .. } => f.write_str("committed file"),
             ResolvedCliIdArg::Uncommitted => f.write_str("uncommitted changes"),
-            ResolvedCliIdArg::Stack => f.write_str("stack"),
+            ResolvedCliIdArg::Stack(_) => f.write_str("stack"),
         }
     }
 }
@@ -1,5 +1,8 @@
 #[cfg(feature = "legacy")]
-use crate::args::atoms::{BranchArg, CliIdArg};
+use crate::args::{
+    HyperlinkMode,
+    atoms::{BranchArg, CliIdArg},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Default)]
 pub enum IntegrationStrategy {
@@ -32,6 +35,10 @@ pub enum Subcommands {
     /// which can be either a commit ID or an existing branch name to create
     /// the new branch from. This allows you to create stacked branches.
     ///
+    /// To split a branch in two, use `--at` with a commit of an applied stack.
+    /// The new branch will point at that commit, so it owns it and all commits
+    /// below it, while the commits above remain with the original branch.
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     New {
@@ -40,20 +47,53 @@ pub enum Subcommands {
         /// Anchor point - either a commit ID or branch name to create the new branch from
         #[clap(long, short = 'a')]
         anchor: Option<CliIdArg>,
+        /// Commit in an applied stack the new branch should point at, splitting the stack there
+        #[clap(long, conflicts_with = "anchor")]
+        at: Option<CliIdArg>,
     },
 
-    /// Deletes a branch from the workspace
+    /// Deletes a branch
+    ///
+    /// If the branch is applied, this removes it and all its commits from the
+    /// workspace. Otherwise its local reference is deleted.
     ///
-    /// This will remove the branch and all its commits from the workspace.
-    /// If the branch has unpushed commits, you will be prompted for confirmation
-    /// unless the `--force` flag is used.
+    /// Branches with commits that aren't pushed are refused unless the `--force`
+    /// flag is used. Use `--remote` to also delete the branch on its remote.
     ///
     #[cfg(feature = "legacy")]
     #[clap(short_flag = 'd')]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Delete {
-        /// Name of the branch to delete
+        /// Name or CLI ID of the branch to delete
         branch_name: CliIdArg,
+        /// Also delete the branch on its remote
+        #[clap(long, short = 'r')]
+        remote: bool,
+        /// Delete the branch even if it has unpushed commits
+        #[clap(long, short = 'f')]
+        force: bool,
+    },
+
+    /// Unapply a branch and the stack it is in from the workspace
+    ///
+    /// The branch remains, so it can be applied again later with `but branch apply`.
+    ///
+    /// Uncommitted changes assigned to the stack are committed onto its branch
+    /// as a "WIP Assignments" commit by default, so they come back when the
+    /// branch is applied again. Use `--keep-changes` to leave them in the
+    /// worktree as unassigned changes instead.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Unapply {
+        /// Name or CLI ID of the branch or stack to unapply
+        identifier: String,
+        /// Keep uncommitted changes assigned to the stack in the worktree, unassigned
+        #[clap(long, conflicts_with = "stash_changes")]
+        keep_changes: bool,
+        /// Commit uncommitted changes assigned to the stack onto its branch as WIP commit (default)
+        #[clap(long)]
+        stash_changes: bool,
     },
 
     /// List the branches in the repository
@@ -61,14 +101,25 @@ pub enum Subcommands {
     /// By default, shows the active branch and the 20 most recently updated branches.
     ///
     /// You can use the `--all` flag to show all branches, `--local` to show only
-    /// local branches, or `--remote` to show only remote branches.
+    /// local branches, or `--remote` to show only remote branches. Use `--limit`
+    /// to show more or fewer unapplied branches, applied ones are always shown.
+    ///
+    /// Long output is shown in the pager configured for git with `GIT_PAGER`,
+    /// `core.pager` or `PAGER`, unless `--no-pager` is given or the output isn't a terminal.
     ///
     /// You can also filter branch names by specifying a substring, such as
-    /// `but branch list feature` to show only branches with "feature" in the name.
+    /// `but branch list feature` to show only branches with "feature" in the name,
+    /// or a glob pattern like `but branch list 'feat/*'`. Use `--author` to only
+    /// show branches whose tip commit was authored by a matching name or email.
+    /// Filters ignore case unless `--case-sensitive` is given, and applied branches
+    /// are always shown, with those that don't match dimmed.
     ///
     /// If you want to check for review status, you can add `--review` to fetch
     /// and display pull request or merge request information for each branch.
-    /// This will make the command slower as it needs to query the forge.
+    /// Reviews fetched within the last 5 minutes are reused, which can be
+    /// configured in seconds with the `but.reviews.cacheSeconds` git config.
+    /// Use `--refresh` to query the forge regardless. Open reviews are followed
+    /// by ✓, ✗ or ● if the CI checks of the branch pass, fail or are pending.
     ///
     /// By default, the command checks if each branch merges cleanly into
     /// the *upstream base target branch* (not your workspace).
@@ -78,11 +129,25 @@ pub enum Subcommands {
     /// of the base branch. You can disable this with `--no-ahead` to
     /// make the command faster.
     ///
+    /// Branches with conflicted commits are marked `[conflicted]`, and those that
+    /// were already integrated into the target branch are marked `[integrated]`.
+    /// Checking unapplied branches for integration can be skipped with `--fast`.
+    ///
+    /// Use `--verbose` to also see the tip commit of each branch and how long
+    /// ago it was made, to find stale branches. Long branch names are then
+    /// shortened in the middle to fit the terminal, but remain complete in JSON.
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     List {
-        /// Filter branches by name (case-insensitive substring match)
+        /// Filter branches by name, as substring or glob pattern
         filter: Option<String>,
+        /// Filter unapplied branches by the name or email of their tip commit author, as substring or glob pattern
+        #[clap(long)]
+        author: Option<String>,
+        /// Match the name and author filters case-sensitively
+        #[clap(long)]
+        case_sensitive: bool,
         /// Show only local branches
         #[clap(long, short = 'l', conflicts_with = "remote")]
         local: bool,
@@ -98,12 +163,30 @@ pub enum Subcommands {
         /// Fetch and display review information (PRs, MRs, etc.)
         #[clap(long)]
         review: bool,
+        /// Fetch review information from the forge even if it was cached recently, implies `--review`
+        #[clap(long)]
+        refresh: bool,
         /// Don't check if each branch merges cleanly into upstream
         #[clap(long)]
         no_check: bool,
         /// Include branches with no commits on them (hidden by default)
         #[clap(long)]
         empty: bool,
+        /// Show the tip commit, its age and author for each branch
+        #[clap(long, short = 'v')]
+        verbose: bool,
+        /// Show at most this many unapplied branches, or all of them with 0 [default: 20]
+        #[clap(long, conflicts_with = "all")]
+        limit: Option<usize>,
+        /// Don't check if unapplied branches are integrated into the target (faster)
+        #[clap(long)]
+        fast: bool,
+        /// Don't count the uncommitted files assigned to applied stacks (faster)
+        #[clap(long)]
+        no_worktree: bool,
+        /// Make review numbers clickable links in terminals that support it
+        #[clap(long, value_enum, default_value_t)]
+        hyperlinks: HyperlinkMode,
     },
 
     /// Show commits ahead of base for a specific branch
@@ -134,6 +217,35 @@ pub enum Subcommands {
         check: bool,
     },
 
+    /// Set the description of a branch
+    ///
+    /// The description says what the branch is about and is used as starting
+    /// point for the body of reviews created for it. It's stored with the
+    /// branch metadata, and also shown and editable in the desktop app.
+    ///
+    /// If no text is given, the editor is opened to edit the current description.
+    /// An empty description removes it.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    SetDescription {
+        /// CLI ID or name of the branch to describe
+        branch: CliIdArg,
+        /// The new description, opens the editor if omitted
+        text: Option<String>,
+    },
+
+    /// Show the description of a branch
+    ///
+    /// Use `but branch set-description` to set it.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Describe {
+        /// CLI ID or name of the branch whose description to show
+        branch: CliIdArg,
+    },
+
     /// Deprecated: use `but move` instead
     #[clap(hide = true)]
     Move {
@@ -141,19 +253,27 @@ pub enum Subcommands {
         _args: Vec<String>,
     },
 
-    /// Apply a branch to the workspace (non-legacy path)
+    /// Apply a branch to the workspace
     ///
     /// If you want to apply an unapplied branch to your workspace so you
     /// can work on it, you can run `but branch apply <branch-name>`.
     ///
     /// This will apply the changes in that branch into your working directory
-    /// as a parallel applied branch.
+    /// as a parallel applied branch. Local branches are preferred, otherwise a
+    /// remote-tracking branch of the same name is used and a local branch is
+    /// created for it.
+    ///
+    /// If the branch conflicts with stacks already in the workspace, nothing
+    /// changes unless `--force-apply` is given, which applies the branch and
+    /// unapplies the conflicting stacks instead.
     ///
-    #[cfg(not(feature = "legacy"))]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Apply {
         /// Name of the branch to apply
         branch_name: String,
+        /// Apply the branch even if it conflicts, unapplying the conflicting stacks
+        #[clap(long)]
+        force_apply: bool,
     },
 
     /// Update your local branch with the content of its remote counterpart.
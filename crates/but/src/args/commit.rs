@@ -15,6 +15,10 @@ pub struct Platform {
     /// If no branch name is given, a new branch with a generated name will be created.
     #[clap(short = 'c', long = "create")]
     pub create: bool,
+    /// Stack the branch created with `--create` on top of this branch or commit,
+    /// instead of creating it as a new independent stack.
+    #[clap(long, requires = "create")]
+    pub anchor: Option<CliIdArg>,
     /// Insert the commit before this commit or branch.
     #[clap(long, conflicts_with = "after")]
     pub before: Option<CliIdArg>,
@@ -27,7 +31,11 @@ pub struct Platform {
     /// Bypass pre-commit hooks
     #[clap(short = 'n', long = "no-hooks", alias = "no-verify")]
     pub no_hooks: bool,
-    /// Generate commit message using AI with optional user summary.
+    /// Commit files larger than `but.commit.largeFileThreshold` (50 MiB by default) without asking.
+    #[clap(long = "allow-large-files")]
+    pub allow_large_files: bool,
+    /// Generate commit message with the configured Claude Code executable, with optional user summary,
+    /// and open it in the editor for review.
     /// Use --ai by itself or --ai="your instructions" (equals sign required for value)
     #[clap(
         short = 'i',
@@ -38,7 +46,11 @@ pub struct Platform {
         require_equals = true
     )]
     pub ai: Option<Option<String>>,
+    /// Commit with the message generated by `--ai` right away, without opening the editor.
+    #[clap(long = "no-edit", requires = "ai")]
+    pub no_edit: bool,
     /// Uncommitted file or hunk CLI IDs to include in the commit.
+    /// Paths, directories and globs like 'src/auth/**' select all changes they match.
     /// Can be specified multiple times or as comma-separated values.
     /// If not specified, all uncommitted changes (or changes staged to the target branch) are committed.
     #[clap(long = "changes", short = 'p', value_delimiter = ',')]
@@ -192,6 +192,60 @@ pub enum Subcommands {
         status: Option<FeatureStatus>,
     },
 
+    /// List all application settings along with where their values come from.
+    ///
+    /// Values are either built-in defaults, or customized in the settings file.
+    /// Keys are shown in the dotted form accepted by `but config get` and `but config set`.
+    ///
+    /// ## Examples
+    ///
+    /// List all settings:
+    ///
+    /// ```text
+    /// but config list
+    /// ```
+    List,
+
+    /// Show the value of a single application setting.
+    ///
+    /// ## Examples
+    ///
+    /// Show the auto-fetch interval:
+    ///
+    /// ```text
+    /// but config get fetch.auto_fetch_interval_minutes
+    /// ```
+    Get {
+        /// The dotted key of the setting, as shown by `but config list`.
+        key: String,
+    },
+
+    /// Change the value of a single application setting.
+    ///
+    /// The value must match the type of the setting, i.e. `true` or `false` for booleans
+    /// and whole numbers for integers.
+    ///
+    /// ## Examples
+    ///
+    /// Disable auto-fetching:
+    ///
+    /// ```text
+    /// but config set fetch.auto_fetch_interval_minutes -1
+    /// ```
+    ///
+    /// Enable a feature flag:
+    ///
+    /// ```text
+    /// but config set feature_flags.single_branch true
+    /// ```
+    Set {
+        /// The dotted key of the setting, as shown by `but config list`.
+        key: String,
+        /// The new value of the setting.
+        #[clap(allow_hyphen_values = true)]
+        value: String,
+    },
+
     /// View and configure UI preferences.
     ///
     /// Without arguments, displays current UI settings.
@@ -12,6 +12,9 @@ pub mod pr {
         /// Create a new review for a branch.
         /// If no branch is specified, you will be prompted to select one.
         /// If there is only one branch without a review, you will be asked to confirm.
+        /// The branch is pushed first, and reviews of stacked branches target their parent branch.
+        /// If the branch already has a review, its URL is shown instead.
+        #[clap(visible_alias = "create")]
         New {
             /// The branch to create a review for.
             #[clap(value_name = "BRANCH")]
@@ -22,6 +25,12 @@ pub mod pr {
             /// Read review title and description from file. The first line is the title, the rest is the description.
             #[clap(short = 'F', long = "file", value_name = "FILE", conflicts_with_all = &["message", "default"])]
             file: Option<std::path::PathBuf>,
+            /// The review title, defaults to the subject of the top commit of the branch.
+            #[clap(long, conflicts_with_all = &["message", "file"])]
+            title: Option<String>,
+            /// Read the review description from file, defaults to the branch description.
+            #[clap(long, value_name = "FILE", conflicts_with_all = &["message", "file"])]
+            body_file: Option<std::path::PathBuf>,
             /// Force push even if it's not fast-forward (defaults to true).
             #[clap(long, short = 'f', default_value_t = true)]
             with_force: bool,
@@ -32,7 +41,8 @@ pub mod pr {
             #[clap(long = "no-hooks", visible_alias = "no-verify")]
             no_hooks: bool,
             /// Use the default content for the review title and description, skipping any prompts.
-            /// If the branch contains only a single commit, the commit message will be used.
+            /// The title is the subject of the top commit, and the description is the branch description.
+            /// Without one, the message of a single commit is used if the `reviews.autoFillPrDescriptionFromCommit` setting is enabled.
             #[clap(long, short = 't', default_value_t = false)]
             default: bool,
             /// Whether to create reviews as a draft.
@@ -81,6 +91,17 @@ pub mod pr {
             #[clap(value_name = "SELECTOR")]
             selector: Option<String>,
         },
+        /// Open the review of a branch in the browser.
+        /// If no branch is specified and exactly one applied branch has a review, that one is opened.
+        /// Otherwise, the branches with reviews are listed.
+        Open {
+            /// The name or CLI ID of the branch whose review to open.
+            #[clap(value_name = "BRANCH")]
+            branch: Option<String>,
+            /// Only print the URL of the review instead of opening it.
+            #[clap(long)]
+            print: bool,
+        },
         /// Configure the template to use for review descriptions.
         /// This will list all available templates found in the repository and allow you to select one.
         Template {
@@ -10,6 +10,11 @@ pub enum CommandName {
     Tui,
     Stf,
     Rub,
+    Assign,
+    Unassign,
+    RuleList,
+    RuleAdd,
+    RuleRemove,
     Uncommit,
     Amend,
     Squash,
@@ -19,6 +24,7 @@ pub enum CommandName {
     Diff,
     Diff2,
     Edit,
+    EditCommit,
     Show,
     Commit,
     CommitEmpty,
@@ -39,6 +45,10 @@ pub enum CommandName {
     BranchDelete,
     BranchList,
     BranchShow,
+    StackList,
+    StackMerge,
+    BranchSetDescription,
+    BranchDescribe,
     BranchUnapply,
     BranchApply,
     BranchUpdate,
@@ -51,6 +61,7 @@ pub enum CommandName {
     ForgeForget,
     PrNew,
     PrTemplate,
+    PrOpen,
     DisableAutoMerge,
     EnableAutoMerge,
     SetReviewReady,
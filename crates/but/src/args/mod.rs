@@ -34,10 +34,39 @@ pub struct Args {
     #[clap(long, hide = true)]
     pub log_file: Option<PathBuf>,
     /// Run as if but was started in PATH instead of the current working directory.
-    #[clap(short = 'C', long, default_value = ".", value_name = "PATH")]
+    ///
+    /// If given multiple times, each PATH is interpreted relative to the previous one,
+    /// just like `git -C`.
+    #[clap(
+        short = 'C',
+        long = "current-dir",
+        visible_alias = "directory",
+        value_name = "PATH",
+        action = clap::ArgAction::Append
+    )]
+    pub directories: Vec<PathBuf>,
+    /// The directory to run in, with all `directories` applied in order.
+    #[clap(skip = PathBuf::from("."))]
     pub current_dir: PathBuf,
     #[clap(flatten)]
     pub format: OutputFormatArg,
+    /// When to color the output.
+    ///
+    /// With `auto`, output is colored if it goes to a terminal, unless `NO_COLOR` is set.
+    /// `CLICOLOR_FORCE` enables colors even if it doesn't.
+    #[clap(long, value_enum, default_value_t, global = true, value_name = "WHEN")]
+    pub color: ColorMode,
+    /// Don't pass long output through the pager.
+    #[clap(long, global = true)]
+    pub no_pager: bool,
+    /// Show a desktop notification when slow operations like `but push` or `but pull` finish.
+    ///
+    /// By default, this happens if `cli.notify_on_slow_operations` is enabled.
+    #[clap(long, global = true, overrides_with = "no_notify")]
+    pub notify: bool,
+    /// Don't show desktop notifications when slow operations finish.
+    #[clap(long, global = true, overrides_with = "notify")]
+    pub no_notify: bool,
     /// Whether mutation commands should append workspace status.
     #[clap(skip)]
     pub status_after: bool,
@@ -66,6 +95,11 @@ pub struct OutputFormatArg {
         global = true
     )]
     pub format: OutputFormat,
+    /// Output JSON, a shorthand for `--format json`.
+    ///
+    /// Errors are written to stdout as `{"error": {"message": …, "kind": …}}` as well.
+    #[clap(long, global = true)]
+    pub json: bool,
 }
 
 /// How we should format anything written to [`std::io::stdout()`].
@@ -112,6 +146,30 @@ impl OutputFormat {
     }
 }
 
+/// When to color the output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum ColorMode {
+    /// Use colors if the output is a terminal, following `NO_COLOR` and `CLICOLOR_FORCE`.
+    #[default]
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+/// When to make references like review numbers clickable with terminal hyperlinks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum HyperlinkMode {
+    /// Use hyperlinks if the output is a terminal known to support them.
+    #[default]
+    Auto,
+    /// Always use hyperlinks.
+    Always,
+    /// Never use hyperlinks.
+    Never,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::Subcommand)]
 pub enum HelpTopic {
     /// Smart IDs to reference commits, branches and more in `but`.
@@ -129,6 +187,9 @@ pub enum HelpTopic {
     ///     - The entire change ID
     ///     - Any prefix of the commit ID or change ID that is unique in the current context. `but status`
     ///       highlights the shortest possible prefix.
+    ///     - `HEAD` or `@` for the tip of the applied stack, if there is only one
+    ///     - `<branch>~N`, `<branch>~` or `<branch>^` for the N-th or first parent of a branch tip
+    ///       within its stack, also after `HEAD` or `@`, like `HEAD~2`
     /// * **Branch:**
     ///     - The entire branch name
     ///     - An exact short ID for the branch name, as shown by `but status`
@@ -143,6 +204,11 @@ pub enum HelpTopic {
     /// data is written to files, commits are made or rearranged and branches are created or
     /// deleted.
     ///
+    /// Uncommitted file IDs are derived from the path and hunk IDs from the content of the hunk,
+    /// so they stay the same while other changes come and go. If a file or hunk ID shown by
+    /// `but status` or `but diff` is used after its change is gone, for example because the hunk
+    /// was edited, it is refused as stale instead of resolving to another change.
+    ///
     /// Some CLI IDs are more stable than others. For example, a commit's change ID is stable even
     /// when commits are made and moved around, but the minimum prefix may increase as other IDs are
     /// introduced.
@@ -199,13 +265,88 @@ pub enum Subcommands {
     /// but status -f
     /// ```
     ///
+    /// Only the stack of a branch
+    ///
+    /// ```text
+    /// but status my-feature
+    /// ```
+    ///
+    /// When a branch or stack is given, by name or CLI ID, only its stack is shown,
+    /// in JSON as well. Add `--show-unassigned` to also see the unassigned changes.
+    ///
+    /// With `--verbose`, the hunks of uncommitted files are listed below them, with
+    /// their ID, header and first changed line. At most 10 hunks are shown per file,
+    /// which can be configured with the `but.status.maxHunksPerFile` git config,
+    /// where 0 shows all of them. Hunks that depend on a commit are marked like
+    /// `(locked to 1a2b3c4 on my-branch)`, as they can only be committed to its stack.
+    ///
+    /// Each uncommitted file is followed by the number of lines it adds and
+    /// removes, like `+12 -3`, or `bin` for binary files, and each section of
+    /// uncommitted changes by their total. Counting can be skipped with `--no-stat`.
+    ///
+    /// Directories with 20 or more untracked files, and no other uncommitted
+    /// changes, are shown as a single line like `node_modules/ (87 files)`.
+    /// `--collapse-untracked` does so from two files on, and `--no-collapse` shows
+    /// every file. The directory, with its trailing slash, can be used as ID to
+    /// select all of its files at once, as in `but rub node_modules/ my-branch`.
+    /// With `--group-by-dir`, uncommitted files are listed below their top-level
+    /// directory along with the number of files in it.
+    ///
+    /// With `--watch`, the status stays on screen and is shown again in place
+    /// whenever files in the worktree, the index or the workspace change, also
+    /// when that's done by the desktop app. If filesystem notifications aren't
+    /// available, changes are checked for every `--interval` seconds instead.
+    /// Press Ctrl-C to stop.
+    ///
+    /// ## Porcelain format
+    ///
+    /// With `--porcelain`, only the uncommitted changes are printed, one per line
+    /// and without colors, in a format that stays stable for scripts:
+    ///
+    /// ```text
+    /// XY <target> <path>
+    /// ```
+    ///
+    /// `XY` is `??` for untracked, `A ` for added, ` M` for modified, ` D` for
+    /// deleted, `R ` for renamed and `UU` for conflicted files. `<target>` is the
+    /// branch the change is assigned to, or `-` if it's unassigned, padded with
+    /// spaces to the width of the longest target. Renames are shown as
+    /// `<old> -> <new>`, and paths with special or non-UTF-8 characters are
+    /// quoted like Git does.
+    ///
+    /// With `-z`, which implies `--porcelain`, entries end with NUL instead of a
+    /// newline and paths are never quoted. Renames are then written as `<new>`,
+    /// NUL and `<old>`.
+    ///
+    /// ## IDs only
+    ///
+    /// With `--ids-only`, only the IDs of the uncommitted files and their hunks
+    /// are printed, without colors and separated by tabs:
+    ///
+    /// ```text
+    /// <id>\t<path>
+    /// <id>\t<path>\t<hunk-header>
+    /// ```
+    ///
+    /// This is handy for picking a change interactively, like
+    /// `but diff "$(but status --ids-only | fzf | cut -f1)"`.
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Status {
+        /// Only show the stack of this branch, given by name or CLI ID, or of this stack CLI ID.
+        #[clap(value_name = "BRANCH_OR_STACK", conflicts_with_all = ["branch", "porcelain", "nul_terminated"])]
+        stack: Option<CliIdArg>,
+        /// Only show the stack of this branch, like the positional argument.
+        #[clap(long = "branch", value_name = "NAME", conflicts_with_all = ["porcelain", "nul_terminated"])]
+        branch: Option<CliIdArg>,
+        /// When only showing one stack, also show the unassigned changes.
+        #[clap(long = "show-unassigned", default_value_t = false)]
+        show_unassigned: bool,
         /// Determines whether the committed files should be shown as well.
         #[clap(short = 'f', alias = "files", default_value_t = false)]
         show_files: bool,
-        /// Show verbose output with commit author and timestamp.
+        /// Show verbose output with commit author and timestamp, and the hunks of uncommitted files.
         #[clap(short = 'v', long = "verbose", default_value_t = false)]
         verbose: bool,
         /// Forces a sync of pull requests from the forge before showing status.
@@ -217,6 +358,40 @@ pub enum Subcommands {
         /// Disable hints about available commands at the end of output.
         #[clap(long = "no-hint", default_value_t = false)]
         no_hint: bool,
+        /// Don't count the added and removed lines of uncommitted files, which is faster for large changes.
+        #[clap(long = "no-stat", default_value_t = false)]
+        no_stat: bool,
+        /// Show untracked files as a single line per directory, like `dir/ (87 files)`, if there are at least two of them.
+        #[clap(
+            long = "collapse-untracked",
+            default_value_t = false,
+            conflicts_with = "no_collapse"
+        )]
+        collapse_untracked: bool,
+        /// Show each untracked file on its own line, even in directories with many of them.
+        #[clap(long = "no-collapse", default_value_t = false)]
+        no_collapse: bool,
+        /// Group uncommitted files by their top-level directory, with the number of files in each.
+        #[clap(long = "group-by-dir", default_value_t = false)]
+        group_by_dir: bool,
+        /// Keep showing the status and update it whenever something changes, until Ctrl-C is pressed.
+        #[clap(long = "watch", default_value_t = false, conflicts_with_all = ["json", "porcelain", "nul_terminated"])]
+        watch: bool,
+        /// With `--watch`, how often to check for changes if filesystem notifications aren't available.
+        #[clap(long = "interval", value_name = "SECONDS", default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..), requires = "watch")]
+        interval: u64,
+        /// Print only the uncommitted changes in a stable, line-oriented format for scripts.
+        #[clap(long = "porcelain", default_value_t = false, conflicts_with = "json")]
+        porcelain: bool,
+        /// List the hunks of each uncommitted file with their IDs, like `-v` does.
+        #[clap(long = "ids", default_value_t = false)]
+        ids: bool,
+        /// Print only the IDs of uncommitted files and hunks, one per line, for piping into tools like `fzf`.
+        #[clap(long = "ids-only", default_value_t = false, conflicts_with_all = ["stack", "branch", "watch", "json", "porcelain", "nul_terminated"])]
+        ids_only: bool,
+        /// Terminate porcelain entries with NUL instead of newline and don't quote paths, implies `--porcelain`.
+        #[clap(short = 'z', default_value_t = false, conflicts_with = "json")]
+        nul_terminated: bool,
         // Hidden no-op compatibility flag because some agents have a habit of running
         // `but status --short`.
         #[clap(long = "short", default_value_t = false, hide = true)]
@@ -279,6 +454,12 @@ pub enum Subcommands {
     /// but show my-feature-branch --verbose
     /// ```
     ///
+    /// Show the content of a file as of a commit, or at the tip of a branch:
+    ///
+    /// ```text
+    /// but show a1b2c3d --path src/main.rs
+    /// ```
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Show {
@@ -287,6 +468,9 @@ pub enum Subcommands {
         /// Show full commit messages and files changed for each commit
         #[clap(short = 'v', long = "verbose")]
         verbose: bool,
+        /// Show the content of the file at this path instead
+        #[clap(long = "path", value_name = "PATH", conflicts_with = "verbose")]
+        path: Option<String>,
     },
 
     /// Commit changes to a stack.
@@ -343,6 +527,17 @@ pub enum Subcommands {
         cli_id: CliIdArg,
     },
 
+    /// Print candidates for completing the last of `words`, one per line.
+    ///
+    /// This is called by the scripts of `but completions` for values only known at runtime,
+    /// like branch names, and prints nothing if there is nothing to offer.
+    #[clap(hide = true, name = "__complete")]
+    _Complete {
+        /// The command line after `but`, ending with the possibly empty word to complete.
+        #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
     /// Commands for managing branches.
     ///
     /// This includes creating, deleting, listing, and showing details about branches.
@@ -354,6 +549,18 @@ pub enum Subcommands {
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Branch(branch::Platform),
 
+    /// Commands for inspecting stacks.
+    ///
+    /// A stack is one or more branches on top of each other, which are applied
+    /// to the workspace together. Commands like `but commit` or `but unapply`
+    /// operate on stacks, which can be referred to by their CLI ID.
+    ///
+    /// By default without a subcommand, it will list the applied stacks.
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Stack(stack::Platform),
+
     /// Land a branch directly onto the target branch.
     ///
     /// Lands the branch onto the configured target (for example `origin/master`) without going
@@ -434,6 +641,9 @@ pub enum Subcommands {
     /// Alternatively, resolve with AI in one step: `but resolve <commit-id> --ai`,
     /// or `but resolve --ai` to resolve all conflicted commits, oldest first.
     ///
+    /// To use the merge tool configured with `merge.tool` instead of editing the
+    /// files by hand, pass `--tool`: `but resolve <commit-id> --tool`.
+    ///
     /// When in resolution mode, `but status` will also show that you're resolving conflicts.
     ///
     #[cfg(feature = "legacy")]
@@ -451,6 +661,12 @@ pub enum Subcommands {
         /// Undo the result with `but undo`.
         #[clap(long)]
         ai: bool,
+        /// Launch the merge tool configured in `merge.tool` for each conflicted file.
+        ///
+        /// The tool is invoked with `mergetool.<tool>.cmd`, like `git mergetool` does.
+        /// With a commit ID this enters resolution mode first.
+        #[clap(long, conflicts_with = "ai")]
+        tool: bool,
     },
 
     /// Unapply a branch from the workspace.
@@ -470,6 +686,11 @@ pub enum Subcommands {
     /// If a branch name (or an identifier pointing to a branch) is provided,
     /// the entire stack containing that branch will be unapplied.
     ///
+    /// Uncommitted changes assigned to the stack are committed onto its branch
+    /// as a "WIP Assignments" commit by default, so they come back when the
+    /// branch is applied again. Use `--keep-changes` to leave them in the
+    /// worktree as unassigned changes instead.
+    ///
     /// ## Examples
     ///
     /// Unapply by branch name:
@@ -484,11 +705,23 @@ pub enum Subcommands {
     /// but unapply bu
     /// ```
     ///
+    /// Unapply, but keep the uncommitted changes of the branch in the worktree:
+    ///
+    /// ```text
+    /// but unapply my-feature-branch --keep-changes
+    /// ```
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Unapply {
         /// CLI ID or name of the branch/stack to unapply
         identifier: String,
+        /// Keep uncommitted changes assigned to the stack in the worktree, unassigned
+        #[clap(long, conflicts_with = "stash_changes")]
+        keep_changes: bool,
+        /// Commit uncommitted changes assigned to the stack onto its branch as WIP commit (default)
+        #[clap(long)]
+        stash_changes: bool,
     },
 
     /// Apply a branch to the workspace.
@@ -507,11 +740,20 @@ pub enum Subcommands {
     /// but apply my-feature-branch
     /// ```
     ///
+    /// Apply a branch that conflicts with the workspace, unapplying the stacks it conflicts with:
+    ///
+    /// ```text
+    /// but apply my-feature-branch --force-apply
+    /// ```
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Apply {
         /// Name of the branch to apply
         branch_name: String,
+        /// Apply the branch even if it conflicts, unapplying the conflicting stacks
+        #[clap(long)]
+        force_apply: bool,
     },
 
     /// Push changes in a branch to remote.
@@ -623,6 +865,126 @@ pub enum Subcommands {
         target: String,
     },
 
+    /// Assign uncommitted files or hunks to a branch.
+    ///
+    /// Changes assigned to a branch are what `but absorb <branch>` amends into
+    /// it, and what stays with its stack when it's unapplied. Each file or hunk
+    /// is given by its ID as shown in `but status`, and the branch by name or
+    /// ID. Several files or hunks can be assigned at once.
+    ///
+    /// Instead of an ID, a file can also be given by its path, and a directory
+    /// or a glob like 'src/auth/**' selects all changed files it matches. Globs
+    /// match against the uncommitted changes, not the files on disk, with `*`
+    /// staying within a directory and `**` crossing them.
+    ///
+    /// With `--from <branch>`, only the changes that are currently assigned to
+    /// that branch are assigned, or all of them if no files or hunks are given.
+    ///
+    /// Changes that are already assigned to the branch are left alone. Hunks that
+    /// depend on a commit in another stack can't be committed to the branch on
+    /// their own, so they are only assigned with `--force`.
+    ///
+    /// ## Examples
+    ///
+    /// Assign a file and a hunk to a branch:
+    ///
+    /// ```text
+    /// but assign nk ab:c3 my-feature
+    /// ```
+    ///
+    /// Assign all changes under a directory:
+    ///
+    /// ```text
+    /// but assign 'src/auth/**' auth-branch
+    /// ```
+    ///
+    /// Move everything assigned to one branch to another:
+    ///
+    /// ```text
+    /// but assign --from my-feature other-feature
+    /// ```
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Assign {
+        /// The IDs of the uncommitted files or hunks to assign, as shown in `but status`, or paths, directories or globs
+        #[clap(value_name = "FILE_OR_HUNK", required_unless_present = "from")]
+        sources: Vec<String>,
+        /// The branch or stack to assign the changes to
+        #[clap(value_name = "BRANCH")]
+        branch: String,
+        /// Only assign changes that are currently assigned to this branch or stack
+        #[clap(long = "from", value_name = "BRANCH")]
+        from: Option<String>,
+        /// Also assign hunks that depend on commits in other stacks
+        #[clap(long = "force", default_value_t = false)]
+        force: bool,
+    },
+
+    /// Return assigned files or hunks to the unassigned changes.
+    ///
+    /// Each file or hunk is given by its ID as shown in `but status`, or by path,
+    /// directory or glob like with `but assign`. With
+    /// `--all --branch <branch>`, everything assigned to that branch's stack is
+    /// unassigned, which is useful before unapplying it.
+    ///
+    /// The unassigned changes are listed by file. Files or hunks that can't be
+    /// found or aren't assigned are skipped and listed as well, and the command
+    /// only fails if nothing was unassigned.
+    ///
+    /// ## Examples
+    ///
+    /// Unassign a file and a hunk:
+    ///
+    /// ```text
+    /// but unassign nk ab:c3
+    /// ```
+    ///
+    /// Unassign everything assigned to a branch:
+    ///
+    /// ```text
+    /// but unassign --all --branch my-feature
+    /// ```
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Unassign {
+        /// The IDs of the uncommitted files or hunks to unassign, as shown in `but status`, or paths, directories or globs
+        #[clap(value_name = "FILE_OR_HUNK", required_unless_present = "all")]
+        sources: Vec<String>,
+        /// Unassign everything assigned to the branch given with `--branch`
+        #[clap(long = "all", default_value_t = false, requires = "branch")]
+        all: bool,
+        /// The branch or stack to unassign everything from, with `--all`
+        #[clap(long = "branch", value_name = "BRANCH", requires = "all")]
+        branch: Option<String>,
+    },
+
+    /// Commands for rules that assign new changes to a branch by their path.
+    ///
+    /// Rules are applied whenever new uncommitted changes appear, both here and
+    /// in the app. See `but rule add --help` for how paths are matched.
+    ///
+    /// By default, lists the rules (same as `but rule list`).
+    ///
+    /// ## Examples
+    ///
+    /// Assign all new changes below `docs/` to the `docs` branch:
+    ///
+    /// ```text
+    /// but rule add --path 'docs/' --branch docs
+    /// ```
+    ///
+    /// Remove the rules whose branch was deleted:
+    ///
+    /// ```text
+    /// but rule remove --prune
+    /// ```
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Rule(rule::Platform),
+
     /// Amends changes into the appropriate commits where they belong.
     ///
     /// The semantic for finding "the appropriate commit" is as follows:
@@ -691,6 +1053,56 @@ pub enum Subcommands {
         no_diff: bool,
     },
 
+    /// Check out a commit into the worktree to modify it directly.
+    ///
+    /// This enters edit mode: your uncommitted changes are saved away, and the
+    /// worktree is replaced with the state of the given commit. Edit files as
+    /// needed to fix the commit, for instance one deep inside a stack.
+    ///
+    /// While in edit mode, `but status` shows which commit is being edited and
+    /// commands that would change the workspace are refused.
+    ///
+    /// Use `--continue` to amend the worktree into the commit, rebase everything
+    /// on top of it and restore your uncommitted changes, or `--abort` to drop the
+    /// edits and restore everything as it was. Both are safe to re-run after an
+    /// interrupted attempt.
+    ///
+    /// Conflicted commits are handled by `but resolve` instead.
+    ///
+    /// ## Examples
+    ///
+    /// Edit a commit by its CLI ID:
+    ///
+    /// ```text
+    /// but edit c5
+    /// ```
+    ///
+    /// Apply the edits and return to the workspace:
+    ///
+    /// ```text
+    /// but edit --continue
+    /// ```
+    ///
+    /// Discard the edits and return to the workspace:
+    ///
+    /// ```text
+    /// but edit --abort
+    /// ```
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Edit {
+        /// Commit ID to check out for editing
+        #[clap(required_unless_present_any = ["continue", "abort"])]
+        commit: Option<String>,
+        /// Amend the worktree into the edited commit and return to the workspace
+        #[clap(long, conflicts_with_all = ["commit", "abort"])]
+        r#continue: bool,
+        /// Discard the edits and return to the workspace
+        #[clap(long, conflicts_with = "commit")]
+        abort: bool,
+    },
+
     /// Uncommit changes from a commit or file-in-commit to the unstaged area.
     ///
     /// Use `--discard` to remove the selected committed changes entirely instead.
@@ -863,13 +1275,18 @@ pub enum Subcommands {
     /// This command will:
     /// - Add the repository to the global GitButler project registry
     /// - Switch to the gitbutler/workspace branch (if not already on it)
-    /// - Set up a default target branch (the remote's HEAD)
+    /// - Set up a default target branch (the remote's HEAD, or the one given with `--target`)
+    /// - Apply the checked-out branch to the workspace if it has commits on top of the target
     /// - Add a gb-local remote if no push remote exists
     ///
     /// If you have an existing Git repository and want to start using GitButler
     /// with it, you can run this command to set up the necessary configuration
     /// and data structures.
     ///
+    /// Without `--target`, the remote's HEAD is used, falling back to well-known branch
+    /// names like `main` unless `--no-guess` is given. You are asked to choose if more than
+    /// one of these branches exists and a terminal is available.
+    ///
     /// ## Examples
     ///
     /// Initialize a new git repository and set up GitButler:
@@ -878,6 +1295,12 @@ pub enum Subcommands {
     /// but setup --init
     /// ```
     ///
+    /// Base all branches on `origin/develop`, fetching it first:
+    ///
+    /// ```text
+    /// but setup --target origin/develop --fetch
+    /// ```
+    ///
     #[cfg(feature = "legacy")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Setup {
@@ -888,6 +1311,24 @@ pub enum Subcommands {
         #[clap(long)]
         #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
         init: bool,
+        /// The remote-tracking branch to use as target, like `origin/develop`.
+        #[clap(long, value_name = "REMOTE/BRANCH")]
+        target: Option<String>,
+        /// Fetch the remote of `--target` before validating it.
+        #[clap(long, requires = "target")]
+        fetch: bool,
+        /// If the remote has no HEAD, try its `main`, `master`, `trunk` and `develop` branches and
+        /// the upstream of the current branch as target. This is the default.
+        #[clap(long, conflicts_with = "no_guess")]
+        guess: bool,
+        /// Only use the remote's HEAD as target, and fail if there is none.
+        #[clap(long = "no-guess", conflicts_with = "guess")]
+        no_guess: bool,
+        /// Don't apply the checked-out branch to the workspace, even if it has commits on top of the target.
+        ///
+        /// Its commits stay out of the workspace, but uncommitted changes are still kept as unassigned changes.
+        #[clap(long = "no-adopt")]
+        no_adopt: bool,
     },
 
     /// Exit GitButler mode and return to normal Git workflow.
@@ -922,6 +1363,47 @@ pub enum Subcommands {
         checkout_to: Option<String>,
     },
 
+    /// Stop managing the repository with GitButler.
+    ///
+    /// This command:
+    /// - Leaves the workspace like `but teardown`, checking out the only applied
+    ///   branch, or the local branch of the target if none is applied
+    ///     - Use `--checkout <branch>` to choose the branch, which is required
+    ///       if more than one stack is applied
+    /// - Keeps uncommitted changes in the worktree
+    /// - Deletes the `gitbutler/workspace` branch
+    /// - Removes the project from GitButler
+    ///
+    /// The GitButler data of the repository is kept unless `--purge` is given.
+    ///
+    /// ## Examples
+    ///
+    /// Stop using GitButler, keeping its data:
+    ///
+    /// ```text
+    /// but deinit
+    /// ```
+    ///
+    /// Stop using GitButler on `my-feature` and delete all of its data:
+    ///
+    /// ```text
+    /// but deinit --checkout my-feature --purge
+    /// ```
+    ///
+    #[cfg(feature = "legacy")]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Deinit {
+        /// The local branch to check out when leaving the workspace.
+        #[clap(long, short = 'c', value_name = "LOCAL_BRANCH")]
+        checkout: Option<String>,
+        /// Keep the GitButler data of the repository. This is the default.
+        #[clap(long, conflicts_with = "purge")]
+        keep_data: bool,
+        /// Delete the GitButler data of the repository.
+        #[clap(long, conflicts_with = "keep_data")]
+        purge: bool,
+    },
+
     /// Open the GitButler GUI for the current project.
     ///
     /// Running `but gui` will launch the GitButler graphical user interface
@@ -1285,12 +1767,12 @@ pub enum Subcommands {
     /// Edit a file:
     ///
     /// ```text
-    /// but edit README.md
+    /// but _edit-file README.md
     /// ```
     ///
-    #[clap(hide = true)]
+    #[clap(hide = true, name = "_edit-file")]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
-    Edit {
+    _EditFile {
         /// Path to the file to edit (created if it doesn't exist)
         file: String,
     },
@@ -1353,6 +1835,28 @@ pub enum Subcommands {
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
     Actions(actions::Platform),
 
+    /// AI: Commit what Claude Code sessions changed once they end.
+    ///
+    /// `but claude install-hooks` configures Claude Code in this repository to run
+    /// `but claude post-session` when a session ends, which commits the files the
+    /// session edited to its own branch.
+    ///
+    /// This only happens if `claude.executable` is set and
+    /// `claude.auto_commit_after_completion` is enabled, see `but config`.
+    ///
+    /// ## Examples
+    ///
+    /// Let Claude Code sessions in this repository commit their changes:
+    ///
+    /// ```text
+    /// but claude install-hooks
+    /// but config set claude.auto_commit_after_completion true
+    /// ```
+    #[cfg(feature = "legacy")]
+    #[clap(hide = true)]
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Claude(claude::Platform),
+
     /// INTERNAL: If metrics are permitted, this subcommand handles posthog event creation.
     #[clap(hide = true)]
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
@@ -1376,8 +1880,12 @@ pub enum Subcommands {
     ///
     /// # fish, put in config.fish
     /// but completions fish | source
+    ///
+    /// # PowerShell, put in $PROFILE
+    /// but completions powershell | Out-String | Invoke-Expression
     /// ```
     #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    #[clap(alias = "completion")]
     Completions {
         /// The shell to generate completions for, or the one extracted from the `SHELL` environment variable.
         #[clap(value_enum)]
@@ -1441,6 +1949,8 @@ pub mod move2;
 pub mod skill;
 #[cfg(feature = "legacy")]
 pub mod squash2;
+#[cfg(feature = "legacy")]
+pub mod stack;
 pub mod update;
 
 pub mod actions {
@@ -1469,6 +1979,27 @@ pub mod actions {
     }
 }
 
+pub mod claude {
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Commit the files the Claude Code session that just ended edited to the branch of the session.
+        ///
+        /// Meant to be run by a Claude Code `SessionEnd` hook, which passes details about the session on stdin.
+        /// Does nothing unless `claude.executable` is set and `claude.auto_commit_after_completion` is enabled.
+        PostSession,
+        /// Configure Claude Code to run `but claude post-session` when a session ends.
+        ///
+        /// The hook is added to `.claude/settings.json` in the worktree, keeping all other settings.
+        InstallHooks,
+    }
+}
+
 pub mod forge;
 pub mod metrics;
 #[cfg(feature = "legacy")]
@@ -1476,6 +2007,8 @@ pub mod oplog;
 pub mod push;
 #[cfg(feature = "legacy")]
 pub mod resolve;
+#[cfg(feature = "legacy")]
+pub mod rule;
 
 pub mod worktree {
     #[derive(Debug, clap::Parser)]
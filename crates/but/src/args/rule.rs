@@ -0,0 +1,80 @@
+//! Command-line argument definitions for the `but rule` command.
+
+#[derive(Debug, clap::Parser)]
+pub struct Platform {
+    #[clap(subcommand)]
+    pub cmd: Option<Subcommands>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// List the rules (default).
+    ///
+    /// Shows the ID of each rule, the path glob it matches and the branch it
+    /// assigns to, and flags rules whose branch doesn't exist anymore.
+    ///
+    List,
+
+    /// Add a rule that assigns new changes to a branch by their path.
+    ///
+    /// Whenever new uncommitted changes appear whose path matches `--path`,
+    /// they are assigned to `--branch` as long as it's applied. The path is a
+    /// glob where `*` doesn't match `/` but `**` does, or a plain path which
+    /// also matches everything below it if it's a directory.
+    ///
+    /// Unassigned changes that match the rule are assigned right away. Changes
+    /// that were already known keep their assignment, so unassigning a change
+    /// by hand sticks.
+    ///
+    /// If several rules match a path, the most specific one wins: the one with
+    /// the longer path before its first wildcard, then the one with fewer
+    /// wildcards, and then the one that was added first.
+    ///
+    /// Files that were assigned by a rule are marked in `but status`.
+    ///
+    /// ## Examples
+    ///
+    /// ```text
+    /// but rule add --path 'docs/' --branch docs
+    /// but rule add --path 'crates/gui/**' --branch ui
+    /// ```
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Add {
+        /// The glob or path that the paths of changes have to match
+        #[clap(long = "path", value_name = "GLOB")]
+        path: String,
+        /// The applied branch to assign matching changes to
+        #[clap(long = "branch", value_name = "BRANCH")]
+        branch: String,
+    },
+
+    /// Remove rules.
+    ///
+    /// Changes that were assigned by a removed rule stay assigned.
+    ///
+    /// ## Examples
+    ///
+    /// ```text
+    /// but rule remove 1a2b3c4
+    /// but rule remove --prune
+    /// but rule remove --all
+    /// ```
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Remove {
+        /// The IDs of the rules to remove, as shown in `but rule list`
+        #[clap(
+            value_name = "ID",
+            required_unless_present_any = ["all", "prune"],
+            conflicts_with_all = ["all", "prune"]
+        )]
+        ids: Vec<String>,
+        /// Remove all rules
+        #[clap(long = "all", default_value_t = false, conflicts_with = "prune")]
+        all: bool,
+        /// Remove the rules whose branch doesn't exist anymore
+        #[clap(long = "prune", default_value_t = false)]
+        prune: bool,
+    },
+}
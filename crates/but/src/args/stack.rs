@@ -0,0 +1,82 @@
+/// Which of the two stacks given to `but stack merge` ends up at the bottom of the merged stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Default)]
+pub enum MergeOrder {
+    /// The branches of the first stack stay at the bottom, with those of the second on top.
+    #[default]
+    AFirst,
+    /// The branches of the second stack stay at the bottom, with those of the first on top.
+    BFirst,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Platform {
+    #[clap(subcommand)]
+    pub cmd: Option<Subcommands>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Subcommands {
+    /// List the stacks of the workspace.
+    ///
+    /// Each stack is shown with its stack ID and CLI ID, followed by its
+    /// branches from top to bottom along with the commits they point to.
+    /// It also shows how many commits a stack has, and how many
+    /// uncommitted files are assigned to it.
+    ///
+    /// ## Examples
+    ///
+    /// List the applied stacks:
+    ///
+    /// ```text
+    /// but stack list
+    /// ```
+    ///
+    /// Also list stacks that aren't applied:
+    ///
+    /// ```text
+    /// but stack list --all
+    /// ```
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    List {
+        /// Also list stacks that are known but not applied to the workspace
+        #[clap(long, short = 'a')]
+        all: bool,
+    },
+    /// Merge two stacks into one, with the branches of one stacked on top of the other.
+    ///
+    /// The branches of the second stack are rebased on top of the first stack,
+    /// keeping the boundaries between them, and changes assigned to the second
+    /// stack are assigned to the merged stack. Use `--order b-first` to stack
+    /// the first stack on top of the second instead.
+    ///
+    /// If rebasing any commit would conflict, nothing is changed and the
+    /// conflicting commits and files are listed instead.
+    ///
+    /// ## Examples
+    ///
+    /// Stack the branches of the stack `feature-b` is in on top of `feature-a`:
+    ///
+    /// ```text
+    /// but stack merge feature-a feature-b
+    /// ```
+    ///
+    /// Stack `feature-a` on top of `feature-b` instead:
+    ///
+    /// ```text
+    /// but stack merge feature-a feature-b --order b-first
+    /// ```
+    ///
+    #[cfg_attr(feature = "raw-clap-docs", clap(verbatim_doc_comment))]
+    Merge {
+        /// A branch or the CLI ID of the first stack
+        #[clap(value_name = "STACK_A")]
+        a: String,
+        /// A branch or the CLI ID of the second stack
+        #[clap(value_name = "STACK_B")]
+        b: String,
+        /// Which stack ends up at the bottom of the merged stack
+        #[clap(long, value_enum, default_value_t)]
+        order: MergeOrder,
+    },
+}
@@ -180,6 +180,25 @@ mod agent_setup {
     }
 }
 
+mod notify {
+    use clap::Parser;
+
+    use crate::args::Args;
+
+    #[test]
+    fn the_last_notify_flag_wins_anywhere_on_the_command_line() {
+        let args = Args::try_parse_from(["but", "config", "--notify"]).expect("parse args");
+        assert!(args.notify && !args.no_notify);
+
+        let args =
+            Args::try_parse_from(["but", "--notify", "config", "--no-notify"]).expect("parse args");
+        assert!(!args.notify && args.no_notify);
+
+        let args = Args::try_parse_from(["but", "config"]).expect("parse args");
+        assert!(!args.notify && !args.no_notify, "the settings decide");
+    }
+}
+
 mod branch_update {
     use clap::Parser;
 
@@ -377,24 +396,265 @@ fn status_short_is_hidden_noop_compatibility_flag() {
 
     match cmd {
         Subcommands::Status {
+            stack,
+            branch,
+            show_unassigned,
             show_files,
             verbose,
             refresh_prs,
             upstream,
             no_hint,
+            no_stat,
+            collapse_untracked,
+            no_collapse,
+            group_by_dir,
+            watch,
+            interval,
+            json,
+            porcelain,
+            ids,
+            ids_only,
+            nul_terminated,
             short,
         } => {
             assert!(short, "compatibility-only flag should parse");
+            assert!(stack.is_none());
+            assert!(branch.is_none());
+            assert!(!show_unassigned);
             assert!(!show_files);
             assert!(!verbose);
             assert!(!refresh_prs);
             assert!(!upstream);
             assert!(!no_hint);
+            assert!(!no_stat);
+            assert!(!collapse_untracked);
+            assert!(!no_collapse);
+            assert!(!group_by_dir);
+            assert!(!watch);
+            assert_eq!(interval, 2);
+            assert!(!json);
+            assert!(!porcelain);
+            assert!(!ids);
+            assert!(!ids_only);
+            assert!(!nul_terminated);
         }
         _ => panic!("unexpected command shape"),
     }
 }
 
+#[cfg(feature = "legacy")]
+#[test]
+fn status_watch_interval_requires_watch_and_json_conflicts() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "status", "--watch", "--interval", "5"])
+        .expect("parse status --watch --interval");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Status {
+            watch: true,
+            interval: 5,
+            ..
+        })
+    ));
+
+    for invalid in [
+        &["but", "status", "--interval", "5"][..],
+        &["but", "status", "--watch", "--interval", "0"],
+        &["but", "status", "--watch", "--json"],
+        &["but", "status", "--watch", "--porcelain"],
+    ] {
+        assert!(
+            Args::try_parse_from(invalid).is_err(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn status_ids_only_conflicts_with_other_output_modes() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "status", "--ids-only"]).expect("parse --ids-only");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Status {
+            ids_only: true,
+            ids: false,
+            ..
+        })
+    ));
+
+    for invalid in [
+        &["but", "status", "--ids-only", "--json"][..],
+        &["but", "status", "--ids-only", "--porcelain"],
+        &["but", "status", "--ids-only", "--watch"],
+        &["but", "status", "--ids-only", "A"],
+    ] {
+        assert!(
+            Args::try_parse_from(invalid).is_err(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn assign_takes_several_sources_then_the_branch() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "assign", "nk", "ab:c3", "A"]).expect("parse assign");
+    match args.cmd {
+        Some(Subcommands::Assign {
+            sources,
+            branch,
+            from,
+            force,
+        }) => {
+            assert_eq!(sources, ["nk", "ab:c3"]);
+            assert_eq!(branch, "A");
+            assert!(from.is_none());
+            assert!(!force);
+        }
+        _ => panic!("unexpected command shape"),
+    }
+
+    let args =
+        Args::try_parse_from(["but", "assign", "--from", "A", "B"]).expect("parse assign --from");
+    match args.cmd {
+        Some(Subcommands::Assign {
+            sources,
+            branch,
+            from,
+            ..
+        }) => {
+            assert!(sources.is_empty());
+            assert_eq!(branch, "B");
+            assert_eq!(from.as_deref(), Some("A"));
+        }
+        _ => panic!("unexpected command shape"),
+    }
+
+    assert!(
+        Args::try_parse_from(["but", "assign", "A"]).is_err(),
+        "changes to assign are needed unless --from is given"
+    );
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn unassign_all_needs_a_branch() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "unassign", "--all", "--branch", "A"])
+        .expect("parse unassign --all --branch");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Unassign { all: true, branch: Some(ref branch), ref sources })
+            if branch == "A" && sources.is_empty()
+    ));
+
+    for invalid in [
+        &["but", "unassign"][..],
+        &["but", "unassign", "--all"],
+        &["but", "unassign", "nk", "--branch", "A"],
+    ] {
+        assert!(
+            Args::try_parse_from(invalid).is_err(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn rule_add_needs_a_path_and_a_branch() {
+    use clap::Parser;
+
+    use crate::args::rule;
+
+    let args = Args::try_parse_from(["but", "rule", "add", "--path", "docs/**", "--branch", "A"])
+        .expect("parse rule add");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Rule(rule::Platform {
+            cmd: Some(rule::Subcommands::Add { ref path, ref branch })
+        })) if path == "docs/**" && branch == "A"
+    ));
+
+    for invalid in [
+        &["but", "rule", "add", "--path", "docs/**"][..],
+        &["but", "rule", "add", "--branch", "A"],
+    ] {
+        assert!(
+            Args::try_parse_from(invalid).is_err(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn rule_remove_takes_ids_or_all_or_prune() {
+    use clap::Parser;
+
+    use crate::args::rule;
+
+    let args = Args::try_parse_from(["but", "rule", "remove", "1a2b3c4", "5d6e7f8"])
+        .expect("parse rule remove with IDs");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Rule(rule::Platform {
+            cmd: Some(rule::Subcommands::Remove { ref ids, all: false, prune: false })
+        })) if ids == &["1a2b3c4", "5d6e7f8"]
+    ));
+    for valid in [
+        &["but", "rule", "remove", "--all"][..],
+        &["but", "rule", "remove", "--prune"],
+    ] {
+        assert!(
+            Args::try_parse_from(valid).is_ok(),
+            "{valid:?} should be accepted"
+        );
+    }
+
+    for invalid in [
+        &["but", "rule", "remove"][..],
+        &["but", "rule", "remove", "--all", "--prune"],
+        &["but", "rule", "remove", "1a2b3c4", "--all"],
+        &["but", "rule", "remove", "1a2b3c4", "--prune"],
+    ] {
+        assert!(
+            Args::try_parse_from(invalid).is_err(),
+            "{invalid:?} should be rejected"
+        );
+    }
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn status_collapse_untracked_conflicts_with_no_collapse() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "status", "--collapse-untracked", "--group-by-dir"])
+        .expect("parse status --collapse-untracked --group-by-dir");
+    assert!(matches!(
+        args.cmd,
+        Some(Subcommands::Status {
+            collapse_untracked: true,
+            no_collapse: false,
+            group_by_dir: true,
+            ..
+        })
+    ));
+
+    assert!(
+        Args::try_parse_from(["but", "status", "--collapse-untracked", "--no-collapse"]).is_err(),
+        "collapsing and not collapsing can't be combined"
+    );
+}
+
 #[cfg(feature = "legacy")]
 #[test]
 fn status_short_is_not_shown_in_help() {
@@ -453,6 +713,20 @@ fn output_format_parses_agent() {
     assert!(matches!(args.format.format, OutputFormat::Agent));
 }
 
+#[test]
+fn color_defaults_to_auto_and_is_global() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["but", "status"]).expect("parse args");
+    assert_eq!(args.color, ColorMode::Auto);
+
+    let args = Args::try_parse_from(["but", "status", "--color=never"]).expect("parse args");
+    assert_eq!(args.color, ColorMode::Never);
+
+    let args = Args::try_parse_from(["but", "--color", "always", "status"]).expect("parse args");
+    assert_eq!(args.color, ColorMode::Always);
+}
+
 mod config_target {
     use clap::Parser;
 
@@ -1039,4 +1313,46 @@ mod pr {
             }
         }
     }
+
+    #[test]
+    fn create_is_an_alias_of_new_with_title_and_body_file() {
+        let args = crate::args::Args::try_parse_from([
+            "but",
+            "pr",
+            "create",
+            "topic",
+            "--title",
+            "The title",
+            "--body-file",
+            "body.md",
+        ])
+        .expect("parse args");
+        let cmd = args.cmd.expect("subcommand");
+        match cmd {
+            crate::args::Subcommands::Pr(crate::args::forge::pr::Platform {
+                cmd:
+                    Some(crate::args::forge::pr::Subcommands::New {
+                        branch,
+                        title,
+                        body_file,
+                        ..
+                    }),
+                ..
+            }) => {
+                assert_eq!(branch.as_deref(), Some("topic"));
+                assert_eq!(title.as_deref(), Some("The title"));
+                assert_eq!(body_file, Some("body.md".into()));
+            }
+            _ => panic!("unexpected command shape"),
+        }
+    }
+
+    #[test]
+    fn title_conflicts_with_message() {
+        let err = crate::args::Args::try_parse_from([
+            "but", "pr", "create", "topic", "--title", "t", "-m", "message",
+        ])
+        .expect_err("conflicting arguments");
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
 }
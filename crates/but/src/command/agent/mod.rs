@@ -744,8 +744,14 @@ fn run_but_setup(current_dir: &Path, out: &mut OutputChannel) -> Result<()> {
     let repo = gix::discover(current_dir).context("No git repository found for `but setup`.")?;
     let mut ctx = but_ctx::Context::from_repo_with_settings(repo, crate::app_settings()?.clone())?;
     let mut guard = ctx.exclusive_worktree_access();
-    crate::command::legacy::setup::repo(&mut ctx, current_dir, out, guard.write_permission())
-        .context("Failed to set up GitButler project.")
+    crate::command::legacy::setup::repo(
+        &mut ctx,
+        current_dir,
+        crate::command::legacy::setup::TargetOptions::default(),
+        out,
+        guard.write_permission(),
+    )
+    .context("Failed to set up GitButler project.")
 }
 
 #[cfg(not(feature = "legacy"))]
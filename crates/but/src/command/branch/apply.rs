@@ -1,11 +1,26 @@
 use but_ctx::Context;
-use but_workspace::branch::apply::OutcomeStatus;
+use but_workspace::{
+    branch::{OnWorkspaceMergeConflict, apply::OutcomeStatus},
+    commit::merge::MergeProgress,
+};
 use gix::reference::Category;
 
-use crate::utils::OutputChannel;
+use crate::{
+    CliError, CliResult, bad_input,
+    theme::{self, Paint},
+    utils::{OutputChannel, ProgressChannel},
+};
 
 /// Apply a branch to the workspace, and return the full ref name to it.
-pub fn apply(mut ctx: Context, branch_name: &str, out: &mut OutputChannel) -> anyhow::Result<()> {
+///
+/// If `force_apply` is set, the branch is applied even if it conflicts with stacks in the workspace,
+/// which are then left out of the workspace merge.
+pub fn apply(
+    mut ctx: Context,
+    branch_name: &str,
+    force_apply: bool,
+    out: &mut OutputChannel,
+) -> CliResult<()> {
     let mut guard = ctx.exclusive_worktree_access();
     let reference = {
         let repo = ctx.repo.get()?;
@@ -14,14 +29,22 @@ pub fn apply(mut ctx: Context, branch_name: &str, out: &mut OutputChannel) -> an
     let outcome = but_api::branch::apply_with_perm(
         &mut ctx,
         reference.name.as_ref(),
+        if force_apply {
+            OnWorkspaceMergeConflict::MaterializeAndReportConflictingStacks
+        } else {
+            OnWorkspaceMergeConflict::AbortAndReportConflictingStacks
+        },
+        out.for_human()
+            .is_some()
+            .then_some(write_merge_progress as fn(MergeProgress<'_>)),
         guard.write_permission(),
     )?;
 
-    let error_message = apply_error_message(reference.name.as_ref(), &outcome);
+    let error = apply_error(reference.name.as_ref(), &outcome);
 
     if let Some(out) = out.for_human() {
-        if let Some(message) = error_message.as_deref() {
-            anyhow::bail!("{message}");
+        if let Some(err) = error {
+            return Err(err);
         }
         write_human_apply_outcome(out, reference.name.as_ref(), &outcome)?;
     } else if let Some(out) = out.for_shell() {
@@ -31,17 +54,43 @@ pub fn apply(mut ctx: Context, branch_name: &str, out: &mut OutputChannel) -> an
     if let Some(out) = out.for_json() {
         out.write_value(but_api::branch::json::ApplyOutcome::from(outcome))?;
     }
-    if let Some(message) = error_message.as_deref() {
-        anyhow::bail!("{message}");
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
     }
-    Ok(())
 }
 
-fn apply_error_message(
+/// Show which stack the workspace merge is at on a single line of stderr, if it's a terminal.
+/// The line is cleared once the workspace commit is written.
+fn write_merge_progress(step: MergeProgress<'_>) {
+    use std::io::Write;
+
+    let t = theme::get();
+    let mut progress = ProgressChannel::new(true);
+    let line = match step {
+        MergeProgress::StackMergeStarted { name, index, count } => format!(
+            "Merging stack {}/{count}: {}",
+            index + 1,
+            name.map_or_else(|| "(anonymous)".into(), |name| name.shorten().to_string())
+        ),
+        MergeProgress::Retry => "Looking for the conflicting stacks".into(),
+        MergeProgress::WritingCommit => {
+            write!(progress, "\r\x1b[2K").ok();
+            return;
+        }
+        MergeProgress::TipsResolved { .. } | MergeProgress::StackMergeFinished { .. } => return,
+    };
+    write!(progress, "\r\x1b[2K{}", t.progress.paint(line)).ok();
+    progress.flush().ok();
+}
+
+fn apply_error(
     requested_branch: &gix::refs::FullNameRef,
     outcome: &but_workspace::branch::apply::Outcome,
-) -> Option<String> {
-    if !outcome.conflicting_stacks.is_empty() {
+) -> Option<CliError> {
+    if outcome.status.persisted_mutation() {
+        None
+    } else if !outcome.conflicting_stacks.is_empty() {
         let short_name = requested_branch.shorten();
         let conflicting_stack_names = outcome
             .conflicting_stacks
@@ -49,14 +98,21 @@ fn apply_error_message(
             .map(|stack| stack.ref_name.shorten().to_string())
             .collect::<Vec<_>>()
             .join(", ");
-        Some(format!(
-            "'{short_name}' conflicts with existing stack in the workspace: {conflicting_stack_names}"
-        ))
+        Some(
+            bad_input(format!(
+                "'{short_name}' conflicts with existing stack in the workspace: {conflicting_stack_names}"
+            ))
+            .hint("Use `--force-apply` to apply it anyway, which unapplies the conflicting stacks")
+            .into(),
+        )
     } else if matches!(outcome.status, OutcomeStatus::ConflictAborted) {
         let short_name = requested_branch.shorten();
-        Some(format!(
-            "'{short_name}' could not be applied because conflicts prevented persistence"
-        ))
+        Some(
+            anyhow::anyhow!(
+                "'{short_name}' could not be applied because conflicts prevented persistence"
+            )
+            .into(),
+        )
     } else {
         None
     }
@@ -81,6 +137,12 @@ fn write_shell_apply_outcome(
     for stack in &outcome.conflicting_stacks {
         writeln!(out, "conflicting_stack={}", stack.ref_name)?;
     }
+    if outcome.status.persisted_mutation() {
+        for (stack_id, ref_name) in applied_stacks(requested_branch, outcome) {
+            writeln!(out, "applied_stack_id={stack_id}")?;
+            writeln!(out, "applied_stack={ref_name}")?;
+        }
+    }
     Ok(())
 }
 
@@ -115,6 +177,33 @@ fn write_human_apply_outcome(
                     write_applied_branch(name.as_ref())?;
                 }
             }
+            for (stack_id, ref_name) in applied_stacks(requested_branch, outcome) {
+                writeln!(out, "New stack {stack_id} for '{}'", ref_name.shorten())?;
+            }
+            if !outcome.conflicting_stacks.is_empty() {
+                writeln!(
+                    out,
+                    "Unapplied conflicting stacks: {}",
+                    outcome
+                        .conflicting_stacks
+                        .iter()
+                        .map(|stack| stack.ref_name.shorten().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?;
+            }
+            let workspace_stacks = outcome
+                .workspace
+                .stacks
+                .iter()
+                .filter_map(|stack| stack.ref_name())
+                .map(|name| name.shorten().to_string())
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                "Workspace now contains: {}",
+                workspace_stacks.join(", ")
+            )?;
         }
         OutcomeStatus::ConflictAborted => {
             unreachable!("conflict-aborted applies are rejected before formatting");
@@ -122,3 +211,28 @@ fn write_human_apply_outcome(
     }
     Ok(())
 }
+
+/// Return the id and tip name of each workspace stack that contains a branch that was just applied,
+/// or the `requested_branch` itself.
+fn applied_stacks<'a>(
+    requested_branch: &gix::refs::FullNameRef,
+    outcome: &'a but_workspace::branch::apply::Outcome,
+) -> Vec<(but_core::ref_metadata::StackId, &'a gix::refs::FullNameRef)> {
+    outcome
+        .workspace
+        .stacks
+        .iter()
+        .filter(|stack| {
+            stack.segments.iter().any(|segment| {
+                segment.ref_name().is_some_and(|name| {
+                    name == requested_branch
+                        || outcome
+                            .applied_branches
+                            .iter()
+                            .any(|applied| applied.as_ref() == name)
+                })
+            })
+        })
+        .filter_map(|stack| Some((stack.id?, stack.ref_name()?)))
+        .collect()
+}
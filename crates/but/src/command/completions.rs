@@ -1,10 +1,16 @@
-use std::io;
+use std::{
+    io,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context as _, Result};
+use bstr::ByteSlice as _;
+use but_core::RepositoryExt as _;
 use clap::CommandFactory;
 use clap_complete::Shell;
 
-use crate::args::Args;
+use crate::{IdMap, args::Args};
 
 /// Generate shell completions for the specified shell
 pub fn generate_completions(shell: Option<Shell>) -> Result<()> {
@@ -14,7 +20,240 @@ pub fn generate_completions(shell: Option<Shell>) -> Result<()> {
     let mut cmd = Args::command();
     let bin_name = cmd.get_name().to_string();
 
-    clap_complete::generate(shell, &mut cmd, bin_name, &mut io::stdout());
+    let mut stdout = io::stdout();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut stdout);
+    match dynamic_completions_script(shell) {
+        Some(script) => stdout.write_all(script.as_bytes())?,
+        None => writeln!(
+            io::stderr(),
+            "Hint: {shell} only completes commands and options. Use bash, zsh or fish to also complete values like branch names."
+        )?,
+    }
 
     Ok(())
 }
+
+/// Shell code to append to the static completions of `shell` so that values only known at runtime,
+/// like branch names, are obtained from `but __complete`, or `None` if `shell` isn't supported.
+fn dynamic_completions_script(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(
+            r#"
+_but_dynamic() {
+    local candidates
+    candidates=$(but __complete "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null)
+    if [[ -n "$candidates" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($candidates)
+        return 0
+    fi
+    _but "$@"
+}
+complete -F _but_dynamic -o bashdefault -o default but
+"#,
+        ),
+        Shell::Zsh => Some(
+            r#"
+_but_dynamic() {
+    local -a candidates
+    candidates=("${(@f)$(but __complete "${words[@]:1:$((CURRENT - 1))}" 2>/dev/null)}")
+    if [[ -n "${candidates[1]}" ]]; then
+        compadd -a candidates
+    else
+        _but "$@"
+    fi
+}
+compdef _but_dynamic but
+"#,
+        ),
+        Shell::Fish => Some(
+            r#"
+complete -c but -a '(but __complete (commandline -opc)[2..] (commandline -ct) 2>/dev/null)'
+"#,
+        ),
+        _ => None,
+    }
+}
+
+/// The kinds of values `but __complete` can offer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Candidates {
+    /// The names of the branches applied to the workspace.
+    Branches,
+    /// The CLI IDs of the stacks in the workspace.
+    Stacks,
+    /// The paths of the uncommitted changes in the worktree.
+    ChangedPaths,
+}
+
+/// Global options that are followed by a value.
+const GLOBAL_OPTIONS_WITH_VALUE: &[&str] =
+    &["-C", "--current-dir", "--directory", "--format", "--color"];
+
+/// Print the candidates for completing the last of `words`, the command line after `but`, one
+/// per line. Nothing is printed if there is nothing to offer, or if the directory that `words`
+/// would run in isn't in a repository managed by GitButler.
+pub(crate) fn complete(current_dir: &Path, words: &[String]) {
+    let Some((current, preceding)) = words.split_last() else {
+        return;
+    };
+    let Some(kinds) = candidates_for(preceding) else {
+        return;
+    };
+    let Ok(candidates) = candidates(&directory_of(current_dir, preceding), kinds) else {
+        return;
+    };
+    let mut stdout = io::stdout().lock();
+    for candidate in candidates
+        .iter()
+        .filter(|candidate| candidate.starts_with(current.as_str()))
+    {
+        if writeln!(stdout, "{candidate}").is_err() {
+            return;
+        }
+    }
+}
+
+/// Determine what to offer for the word following `preceding`, the words after `but`.
+fn candidates_for(preceding: &[String]) -> Option<&'static [Candidates]> {
+    let previous = preceding.last().map(String::as_str);
+    match previous {
+        Some("-b" | "--branch" | "--from") => return Some(&[Candidates::Branches]),
+        Some("--stack") => return Some(&[Candidates::Stacks]),
+        Some(option) if option.starts_with('-') => return None,
+        _ => {}
+    }
+
+    let mut positionals = Vec::new();
+    let mut words = preceding.iter().map(String::as_str);
+    while let Some(word) = words.next() {
+        if GLOBAL_OPTIONS_WITH_VALUE.contains(&word) {
+            words.next();
+        } else if !word.starts_with('-') {
+            positionals.push(word);
+        }
+    }
+    match positionals.as_slice() {
+        ["status"] => Some(&[Candidates::Branches, Candidates::Stacks]),
+        ["unapply" | "push"] | ["branch", "show" | "delete"] => Some(&[Candidates::Branches]),
+        ["diff" | "discard" | "rub"] => Some(&[Candidates::ChangedPaths]),
+        ["assign", ..] => Some(&[Candidates::ChangedPaths, Candidates::Branches]),
+        _ => None,
+    }
+}
+
+/// Return the directory the command line `preceding` would run in, with all `-C` options among
+/// them applied to `current_dir` in order, like `but` itself does.
+fn directory_of(current_dir: &Path, preceding: &[String]) -> PathBuf {
+    let mut dir = current_dir.to_owned();
+    let mut words = preceding.iter().map(String::as_str);
+    while let Some(word) = words.next() {
+        if matches!(word, "-C" | "--current-dir" | "--directory")
+            && let Some(path) = words.next()
+        {
+            dir = dir.join(path);
+        }
+    }
+    dir
+}
+
+/// Look up the values of all `kinds` in the repository at `current_dir`, without accessing the
+/// network.
+/// Repositories that GitButler doesn't manage yet are left untouched.
+fn candidates(current_dir: &Path, kinds: &[Candidates]) -> Result<Vec<String>> {
+    if !gix::discover(current_dir)?
+        .gitbutler_storage_path()?
+        .exists()
+    {
+        return Ok(Vec::new());
+    }
+    let ctx = but_ctx::Context::discover(current_dir)?;
+    let mut out = Vec::new();
+    for kind in kinds {
+        match kind {
+            Candidates::Branches => {
+                let (_guard, _repo, ws, _db) = ctx.workspace_and_db()?;
+                out.extend(
+                    ws.stacks
+                        .iter()
+                        .flat_map(|stack| &stack.segments)
+                        .filter_map(|segment| segment.ref_name())
+                        .map(|ref_name| ref_name.shorten().to_str_lossy().into_owned()),
+                );
+            }
+            Candidates::Stacks => {
+                let id_map = IdMap::legacy_new_from_context(&ctx, None)?;
+                out.extend(
+                    id_map
+                        .stacks()
+                        .iter()
+                        .filter_map(|stack| stack.id)
+                        .filter_map(|stack_id| id_map.resolve_stack(stack_id))
+                        .map(|cli_id| cli_id.to_short_string()),
+                );
+            }
+            Candidates::ChangedPaths => {
+                let repo = ctx.repo.get()?;
+                out.extend(
+                    but_core::diff::worktree_changes_no_renames(&repo)?
+                        .changes
+                        .into_iter()
+                        .map(|change| change.path.to_str_lossy().into_owned()),
+                );
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Candidates, candidates_for, directory_of};
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(ToOwned::to_owned).collect()
+    }
+
+    #[test]
+    fn values_of_options_and_positionals_are_recognized() {
+        assert_eq!(
+            candidates_for(&words("commit -b")),
+            Some(&[Candidates::Branches][..])
+        );
+        assert_eq!(
+            candidates_for(&words("-C ../repo diff")),
+            Some(&[Candidates::ChangedPaths][..])
+        );
+        assert_eq!(
+            candidates_for(&words("--format json branch show")),
+            Some(&[Candidates::Branches][..])
+        );
+        assert_eq!(
+            candidates_for(&words("status")),
+            Some(&[Candidates::Branches, Candidates::Stacks][..])
+        );
+        assert_eq!(candidates_for(&words("commit -m")), None);
+        assert_eq!(candidates_for(&words("")), None, "subcommands are static");
+    }
+
+    #[test]
+    fn directory_options_are_applied_in_order() {
+        let cwd = Path::new("cwd");
+        assert_eq!(directory_of(cwd, &words("diff")), cwd);
+        assert_eq!(
+            directory_of(cwd, &words("-C repo diff")),
+            Path::new("cwd/repo")
+        );
+        assert_eq!(
+            directory_of(cwd, &words("-C a --directory b --current-dir c status")),
+            Path::new("cwd/a/b/c")
+        );
+        assert_eq!(
+            directory_of(cwd, &words("-C /abs diff")),
+            Path::new("/abs"),
+            "absolute paths replace the current directory"
+        );
+    }
+}
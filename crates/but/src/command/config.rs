@@ -17,7 +17,7 @@ use but_llm::{
 };
 use but_secret::{Sensitive, secret};
 use but_settings::{
-    AppSettingsWithDiskSync,
+    AppSettings, AppSettingsWithDiskSync,
     api::{FeatureFlagsUpdate, TelemetryUpdate},
 };
 use cfg_if::cfg_if;
@@ -26,10 +26,12 @@ use serde::Serialize;
 
 use super::git_config::edit_git_config;
 use crate::{
+    CliError, CliResult,
     args::config::{
         AiKeyOption, AiSubcommand, FeatureFlag, FeatureStatus, ForgeSubcommand, MetricsStatus,
         Subcommands, UiSubcommand, UserSubcommand,
     },
+    bad_input,
     theme::{self, Paint},
     tui,
     utils::{ConfirmOrEmpty, InputOutputChannel, OutputChannel},
@@ -100,6 +102,9 @@ pub async fn exec(
         Some(Subcommands::Forge { cmd }) => forge_config(out, cmd).await,
         Some(Subcommands::Metrics { status }) => metrics_config(out, status).await,
         Some(Subcommands::Feature { flag, status }) => feature_config(out, flag, status),
+        Some(cmd @ (Subcommands::List | Subcommands::Get { .. } | Subcommands::Set { .. })) => {
+            settings_config(out, &cmd)
+        }
         Some(Subcommands::Ai { local, global, cmd }) => {
             ai_config_with_repo(ctx, out, cmd, local, global)
         }
@@ -488,6 +493,140 @@ fn write_feature_flag_value(
     Ok(())
 }
 
+/// Where the effective value of an application setting comes from.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SettingSource {
+    Default,
+    User,
+}
+
+impl SettingSource {
+    fn of(key: &str, value: &serde_json::Value, defaults: &AppSettings) -> Self {
+        if but_settings::keys::get(defaults, key).is_ok_and(|default| &default == value) {
+            SettingSource::Default
+        } else {
+            SettingSource::User
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            SettingSource::Default => "default",
+            SettingSource::User => "user",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SettingInfo {
+    key: String,
+    value: serde_json::Value,
+    source: SettingSource,
+}
+
+/// Handle `list`, `get` and `set` for application settings without requiring a repository context.
+pub(crate) fn settings_config(out: &mut OutputChannel, cmd: &Subcommands) -> CliResult<()> {
+    let t = theme::get();
+    let app_settings_sync = load_app_settings_sync()?;
+    let settings = app_settings_sync.get()?.clone();
+    let defaults = AppSettings::default();
+    let bad_key_or_value = |err: anyhow::Error| {
+        CliError::from(
+            bad_input(format!("{err:#}")).hint("Run `but config list` to see all settings"),
+        )
+    };
+
+    match cmd {
+        Subcommands::List => {
+            let infos: Vec<_> = but_settings::keys::list(&settings)?
+                .into_iter()
+                .map(|(key, value)| {
+                    let source = SettingSource::of(&key, &value, &defaults);
+                    let value = redact_secret_setting(&key, value);
+                    SettingInfo { key, value, source }
+                })
+                .collect();
+            if let Some(out) = out.for_human() {
+                for info in &infos {
+                    writeln!(
+                        out,
+                        "{} = {} {}",
+                        t.config_key.paint(&info.key),
+                        t.config_value.paint(setting_value_for_display(&info.value)),
+                        t.hint.paint(format!("({})", info.source.as_str()))
+                    )?;
+                }
+            } else if let Some(out) = out.for_json() {
+                out.write_value(infos)?;
+            }
+        }
+        Subcommands::Get { key } => {
+            let value = but_settings::keys::get(&settings, key).map_err(bad_key_or_value)?;
+            let source = SettingSource::of(key, &value, &defaults);
+            let value = redact_secret_setting(key, value);
+            if let Some(out) = out.for_human() {
+                writeln!(
+                    out,
+                    "{} {}",
+                    t.config_value.paint(setting_value_for_display(&value)),
+                    t.hint.paint(format!("({})", source.as_str()))
+                )?;
+            } else if let Some(out) = out.for_shell() {
+                writeln!(out, "{}", setting_value_for_display(&value))?;
+            } else if let Some(out) = out.for_json() {
+                out.write_value(SettingInfo {
+                    key: key.clone(),
+                    value,
+                    source,
+                })?;
+            }
+        }
+        Subcommands::Set { key, value } => {
+            let updated =
+                but_settings::keys::set(&settings, key, value).map_err(bad_key_or_value)?;
+            updated.save(app_settings_sync.config_path(), None)?;
+            let value = but_settings::keys::get(&updated, key)?;
+            let source = SettingSource::of(key, &value, &defaults);
+            let value = redact_secret_setting(key, value);
+            if let Some(out) = out.for_human() {
+                writeln!(
+                    out,
+                    "{} Set {} to {}",
+                    t.sym().success,
+                    t.config_key.paint(key),
+                    t.config_value.paint(setting_value_for_display(&value))
+                )?;
+            } else if let Some(out) = out.for_json() {
+                out.write_value(SettingInfo {
+                    key: key.clone(),
+                    value,
+                    source,
+                })?;
+            }
+        }
+        _ => unreachable!("BUG: only settings subcommands are passed here"),
+    }
+    Ok(())
+}
+
+/// Passwords are stored in plain text, so never print them.
+fn redact_secret_setting(key: &str, value: serde_json::Value) -> serde_json::Value {
+    if key.ends_with("password") && !value.is_null() {
+        serde_json::Value::String("<redacted>".into())
+    } else {
+        value
+    }
+}
+
+fn setting_value_for_display(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        serde_json::Value::Null => "<unset>".into(),
+        other => other.to_string(),
+    }
+}
+
 pub(crate) fn load_app_settings_sync() -> Result<AppSettingsWithDiskSync> {
     let config_dir = but_path::app_config_dir()?;
     std::fs::create_dir_all(&config_dir)?;
@@ -59,12 +59,10 @@ fn print_topic(out: &mut OutputChannel, topic: HelpTopic) -> std::fmt::Result {
     // `Command::print_long_help()` and that forces use of `std::io::Stdout`, side-stepping our
     // OutputChannel implementation.
     //
-    // A full implementation here would entail using `anstream::AutoStream` along with
-    // `Command::get_color()` and map that to `anstream::ColorChoice`. But just checking if the
-    // output is a terminal is generally sufficient as all modern terminals support ANSI escape
-    // codes, so we'll stay with this simple solution for now.
+    // Instead, the rendered help is colored according to the same decision as all other output,
+    // which also takes `--color` into account.
     let long_help = topic_command.render_long_help();
-    if out.is_terminal() {
+    if theme::should_colorize() {
         writeln!(out, "{}", long_help.ansi())
     } else {
         writeln!(out, "{long_help}")
@@ -128,6 +126,8 @@ fn print_grouped_with_truncation(
                 SubcommandDiscriminant::_Commit2 => Group::BranchingAndCommitting,
                 SubcommandDiscriminant::Branch => Group::BranchingAndCommitting,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Stack => Group::BranchingAndCommitting,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Discard => Group::BranchingAndCommitting,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Unapply => Group::BranchingAndCommitting,
@@ -153,6 +153,12 @@ fn print_grouped_with_truncation(
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Rub => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Assign => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Unassign => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Rule => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Absorb => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Reword => Group::EditingCommits,
@@ -164,6 +170,8 @@ fn print_grouped_with_truncation(
                 SubcommandDiscriminant::Squash => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::_Squash2 => Group::EditingCommits,
+                #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Edit => Group::EditingCommits,
                 SubcommandDiscriminant::Move => Group::EditingCommits,
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::_Move2 => Group::EditingCommits,
@@ -189,11 +197,14 @@ fn print_grouped_with_truncation(
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Teardown => Group::OtherCommands,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Deinit => Group::OtherCommands,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Tui => Group::OtherCommands,
 
-                SubcommandDiscriminant::Edit => continue,
+                SubcommandDiscriminant::_EditFile => continue,
                 SubcommandDiscriminant::_Open => continue,
                 SubcommandDiscriminant::_Expand => continue,
+                SubcommandDiscriminant::_Complete => continue,
                 SubcommandDiscriminant::Metrics => continue,
                 SubcommandDiscriminant::Onboarding => continue,
                 SubcommandDiscriminant::External => continue,
@@ -207,6 +218,8 @@ fn print_grouped_with_truncation(
                 #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Actions => continue,
                 #[cfg(feature = "legacy")]
+                SubcommandDiscriminant::Claude => continue,
+                #[cfg(feature = "legacy")]
                 SubcommandDiscriminant::Fetch => continue,
                 SubcommandDiscriminant::AgentLog => continue,
             };
@@ -329,6 +342,10 @@ fn print_grouped_with_truncation(
             "      --format <FORMAT>",
             "   Explicitly control how output should be formatted [possible values: human, agent, shell, json, none]",
         ),
+        (
+            "      --json",
+            "              Output JSON, a shorthand for `--format json`",
+        ),
         ("  -h, --help", "              Print help"),
     ];
 
@@ -340,8 +357,13 @@ fn print_grouped_with_truncation(
 
     writeln!(out)?;
     writeln!(out, "{}:", t.important.paint("Environment variables"))?;
+    let env_width = envs::ALL_ENVS
+        .iter()
+        .map(|(env, _)| env.len())
+        .max()
+        .unwrap_or_default();
     for (env, desc) in envs::ALL_ENVS {
-        let env = format!("  {env}");
+        let env = format!("  {env:<env_width$}");
         let available_width = terminal_width.saturating_sub(env.len() + 2);
         let truncated_desc = truncate_text(desc, available_width);
         writeln!(out, "{env}  {truncated_desc}")?;
@@ -382,6 +404,7 @@ Inspection:
 Branching and Committing:
   commit       Commit changes to a stack
   branch       Commands for managing branches
+  stack        Commands for inspecting stacks
   discard      Discard uncommitted changes from the worktree
   resolve      Resolve conflicts in a commit
   unapply      Unapply a branch from the workspace
@@ -393,6 +416,7 @@ Editing Commits:
   rub          Combines two entities together to perform an operation like ame…
   absorb       Amends changes into the appropriate commits where they belong
   reword       Edit the commit message of the specified commit
+  edit         Check out a commit into the worktree to modify it directly
   uncommit     Uncommit changes from a commit or file-in-commit to the unstage…
   amend        Amend one or more file changes into a specific commit and rebas…
   squash       Squash commits together
@@ -412,6 +436,7 @@ Server Interactions:
 Other Commands:
   setup        Sets up a GitButler project from a git repository in the curren…
   teardown     Exit GitButler mode and return to normal Git workflow
+  deinit       Stop managing the repository with GitButler
   gui          Open the GitButler GUI for the current project
   tui          Open a live terminal workspace for branches, commits, changes, …
   update       Manage GitButler CLI and app updates
@@ -433,11 +458,13 @@ To use the GitButler CLI with coding agents (Claude Code hooks, Cursor hooks, MC
 Options:
   -C, --current-dir <PATH>  Run as if but was started in PATH instead of the cu…
       --format <FORMAT>     Explicitly control how output should be formatted […
+      --json                Output JSON, a shorthand for `--format json`
   -h, --help                Print help
 
 Environment variables:
-  BUT_PAGER  Sets the pager for large outputs. [default: less]
-  BUT_THEME  Sets the theme for but. Options: dark, light. [default: dark]
+  BUT_PAGER    Sets the pager for large outputs. [default: less]
+  BUT_THEME    Sets the theme for but. Options: dark, light. [default: dark]
+  BUT_OFFLINE  Set to 1 to not access the network, using cached forge data.
 
 "#]]
         );
@@ -509,11 +536,13 @@ To use the GitButler CLI with coding agents (Claude Code hooks, Cursor hooks, MC
 Options:
   -C, --current-dir <PATH>  Run as if but was started in PATH instead of the cu…
       --format <FORMAT>     Explicitly control how output should be formatted […
+      --json                Output JSON, a shorthand for `--format json`
   -h, --help                Print help
 
 Environment variables:
-  BUT_PAGER  Sets the pager for large outputs. [default: less]
-  BUT_THEME  Sets the theme for but. Options: dark, light. [default: dark]
+  BUT_PAGER    Sets the pager for large outputs. [default: less]
+  BUT_THEME    Sets the theme for but. Options: dark, light. [default: dark]
+  BUT_OFFLINE  Set to 1 to not access the network, using cached forge data.
 
 "#]]
         );
@@ -1,12 +1,20 @@
 //! AI-powered commit message generation.
 //!
-//! This module provides functionality to generate commit messages using AI (LLM) based on
-//! the unified diff of changes and an optional user-provided summary.
+//! This module provides functionality to generate commit messages using AI, with the configured
+//! Claude Code executable based on the unified diff of changes, or with an LLM when combining
+//! the messages of squashed commits, each guided by an optional user-provided summary.
 
-use std::fmt::Write as _;
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    io::{Read, Write as _},
+    process::{Child, Command, Output, Stdio},
+    time::{Duration, Instant},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use but_llm::{ChatMessage, LLMProvider};
+use but_settings::app_settings::Claude;
 use schemars::JsonSchema;
 
 use crate::{
@@ -14,52 +22,53 @@ use crate::{
     utils::OutputChannel,
 };
 
-/// Generate a commit message using AI based on the unified diff and optional user summary.
+/// The most bytes of the diff that are passed to Claude Code, so large changes don't exceed
+/// what the model can take in.
+const MAX_CLAUDE_DIFF_BYTES: usize = 64 * 1024;
+/// The model Claude Code is asked to use, unless it should use the one it's configured with.
+const CLAUDE_COMMIT_MESSAGE_MODEL: &str = "haiku";
+
+/// Generate a commit message for `diff` using the Claude Code executable configured in `settings`,
+/// guided by `user_summary` if given. If `timeout` is set, Claude Code is stopped if it takes longer.
 ///
-/// This function uses an LLM (Large Language Model) to analyze the provided diff and generate
-/// a well-formatted commit message that follows best practices. The generated message will
-/// include a short summary line and a longer explanation of the changes.
-///
-/// # Arguments
-///
-/// * `out` - Output channel for displaying progress messages to the user.
-/// * `diff` - A unified diff string showing the changes to be committed. Should be in standard
-///   unified diff format with file headers and hunks.
-/// * `user_summary` - An optional user-provided summary that gives context about the changes.
-///   If provided, the AI will use this to generate a more accurate and contextual commit message.
-///   If `None`, the AI will generate the message based solely on the diff.
+/// Only the first 64 KiB of `diff` are passed on. Unless
+/// `settings.use_configured_model` is set, Claude Code is told which model to use.
 ///
 /// # Returns
 ///
-/// Returns a formatted commit message string on success, or an error if:
-/// - The OpenAI LLM provider cannot be initialized (e.g., missing API credentials)
-/// - The AI request fails
-/// - The AI response cannot be parsed as a valid commit message
-pub fn generate_commit_message(
+/// Returns the commit message Claude Code printed, or an error if:
+/// - No executable is configured, in which case nothing is passed anywhere
+/// - The executable can't be run, fails or doesn't finish within `timeout`
+/// - It doesn't print a commit message
+pub fn generate_commit_message_with_claude(
     out: &mut OutputChannel,
+    settings: &Claude,
     diff: &str,
-    user_summary: Option<String>,
+    user_summary: Option<&str>,
+    timeout: Option<Duration>,
 ) -> Result<String> {
-    let mut progress = out.progress_channel();
+    let executable = settings.executable.trim();
+    if executable.is_empty() {
+        bail!(
+            "No Claude Code executable is configured, set one with `but config set claude.executable <path>`"
+        );
+    }
 
+    let mut progress = out.progress_channel();
     writeln!(
         progress,
         "{}",
         theme::get().progress.paint("Generating commit message...")
     )?;
-    let llm = LLMProvider::default_openai()
-        .ok_or_else(|| anyhow::anyhow!("Failed to initialize default OpenAI LLM provider"))?;
-    let system_message =
-        "You are a version control assistant that helps with Git branch committing.".to_string();
+
     let summary = user_summary.unwrap_or_default();
-    let user_message = format!(
-        r#"Extract the git commit data from the user summary if provided and the diff output.
-Return the commit message. Determine from this user summary and diff output what the git commit data should be.
+    let diff = truncate_diff(diff, MAX_CLAUDE_DIFF_BYTES);
+    let prompt = format!(
+        r#"Write the git commit message for the changes in the unified diff below.
+Reply with the commit message only, without any explanation or formatting around it.
 
 {DEFAULT_COMMIT_MESSAGE_INSTRUCTIONS}
 
-Here is the data:
-
 User summary (optional): {summary}
 
 unified diff:
@@ -69,12 +78,107 @@ unified diff:
 "#
     );
 
-    let chat_messages = vec![ChatMessage::User(user_message)];
-    let response = llm
-        .structured_output::<StructuredOutput>(&system_message, chat_messages, "gpt-5-mini")?
-        .context("Failed to generate structured content for commit message")?;
+    let mut cmd = Command::new(executable);
+    cmd.arg("--print");
+    if !settings.use_configured_model {
+        cmd.args(["--model", CLAUDE_COMMIT_MESSAGE_MODEL]);
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run Claude Code at '{executable}'"))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("stdin was configured to be piped")?;
+    // Write from another thread so the prompt can't block on Claude Code filling its output.
+    let writer = std::thread::spawn(move || stdin.write_all(prompt.as_bytes()));
+    let output = wait_with_output(child, timeout)?;
+    // Claude Code may exit without reading all of the prompt, which its exit status tells about.
+    writer.join().ok();
+    if !output.status.success() {
+        bail!(
+            "Claude Code failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
 
-    Ok(response.commit_message)
+    let message = strip_code_fence(String::from_utf8_lossy(&output.stdout).trim()).to_owned();
+    if message.is_empty() {
+        bail!("Claude Code didn't produce a commit message");
+    }
+    Ok(message)
+}
+
+/// Wait for `child` to finish and collect its output, but kill it if it doesn't finish within `timeout`.
+fn wait_with_output(mut child: Child, timeout: Option<Duration>) -> Result<Output> {
+    let Some(timeout) = timeout else {
+        return child
+            .wait_with_output()
+            .context("Failed to wait for Claude Code");
+    };
+    // Read from other threads so Claude Code can't block on filling its output while we wait.
+    let read_to_end = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_end(&mut buf).ok();
+            }
+            buf
+        })
+    };
+    let stdout = read_to_end(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = read_to_end(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let started_at = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for Claude Code")? {
+            break status;
+        }
+        if started_at.elapsed() >= timeout {
+            child.kill().ok();
+            child.wait().ok();
+            bail!(
+                "Claude Code didn't finish within {} seconds",
+                timeout.as_secs()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Cut `diff` after at most `max_bytes`, at the end of a line if possible, and say so.
+fn truncate_diff(diff: &str, max_bytes: usize) -> Cow<'_, str> {
+    if diff.len() <= max_bytes {
+        return Cow::Borrowed(diff);
+    }
+    let mut end = max_bytes;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = diff[..end].rfind('\n').map_or(end, |newline| newline + 1);
+    Cow::Owned(format!(
+        "{}[The rest of the diff, {} bytes, is left out]\n",
+        &diff[..end],
+        diff.len() - end
+    ))
+}
+
+/// Remove the code fence `message` may be wrapped in despite being asked not to.
+fn strip_code_fence(message: &str) -> &str {
+    message
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .and_then(|inner| inner.split_once('\n'))
+        .map_or(message, |(_language, inner)| inner.trim())
 }
 
 /// Generate an updated commit message when squashing multiple commits together.
@@ -179,3 +283,44 @@ Implement `readlink()` by reading NTFS reparse points via the
 `read_reparse_point()` function that was introduced earlier to determine
 the length of symlink targets. Works for symlinks and directory
 junctions."#;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        process::{Command, Stdio},
+        time::{Duration, Instant},
+    };
+
+    use super::wait_with_output;
+
+    #[cfg(unix)]
+    #[test]
+    fn processes_taking_too_long_are_stopped() -> anyhow::Result<()> {
+        let child = Command::new("sh")
+            .args(["-c", "sleep 10"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let started_at = Instant::now();
+        let err = wait_with_output(child, Some(Duration::from_millis(100)))
+            .expect_err("the process is killed");
+        assert!(err.to_string().contains("didn't finish"));
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_of_processes_finishing_in_time_is_collected() -> anyhow::Result<()> {
+        let child = Command::new("sh")
+            .args(["-c", "echo message; echo problem >&2"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let output = wait_with_output(child, Some(Duration::from_secs(10)))?;
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"message\n");
+        assert_eq!(output.stderr, b"problem\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,635 @@
+//! Implementation of the `but assign` and `but unassign` commands.
+//!
+//! Assigns uncommitted files and hunks to a stack, or returns them to the unassigned changes. The
+//! stack a change is assigned to is where `but absorb` puts it, and it stays with the stack when
+//! that's unapplied.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{Result, bail};
+use bstr::{BStr, BString, ByteSlice};
+use but_core::{HunkHeader, ref_metadata::StackId};
+use but_ctx::Context;
+use but_hunk_assignment::{HunkAssignment, HunkAssignmentRequest, HunkAssignmentTarget};
+use but_hunk_dependency::ui::{HunkDependencies, HunkLockTarget};
+use serde::Serialize;
+
+use crate::{
+    CliId, IdMap,
+    id::{
+        WorktreeHunk,
+        parser::{parse_uncommitted_sources_with_disambiguation, prompt_for_disambiguation},
+    },
+    theme::{self, Paint},
+    utils::{OutputChannel, rejection::hunks_overlap, shorten_object_id},
+};
+
+/// The stack that changes are assigned to or taken from.
+pub(crate) struct Lane {
+    pub stack_id: StackId,
+    /// The name of the branch, or of the topmost branch of the stack, to show to the user.
+    pub name: String,
+}
+
+/// The number of uncommitted files assigned to each stack, and of those assigned to none.
+///
+/// A file counts for each stack it has hunks assigned to.
+#[derive(Debug, Default)]
+pub(crate) struct AssignedFiles {
+    by_stack: HashMap<StackId, usize>,
+    pub unassigned: usize,
+}
+
+impl AssignedFiles {
+    /// Count the files of `assignments` by the stack they are assigned to.
+    pub(crate) fn from_assignments(assignments: &[HunkAssignment]) -> Self {
+        let mut paths = HashMap::<_, HashSet<_>>::new();
+        for assignment in assignments {
+            paths
+                .entry(assignment.stack_id)
+                .or_default()
+                .insert(assignment.path_bytes.as_bstr());
+        }
+        AssignedFiles {
+            unassigned: paths.remove(&None).map_or(0, |paths| paths.len()),
+            by_stack: paths
+                .into_iter()
+                .filter_map(|(stack_id, paths)| Some((stack_id?, paths.len())))
+                .collect(),
+        }
+    }
+
+    /// Count the uncommitted files by the stack they are assigned to, which needs a diff of the
+    /// worktree.
+    pub(crate) fn count_in_worktree(ctx: &Context) -> Result<Self> {
+        let guard = ctx.shared_worktree_access();
+        let assignments =
+            but_api::diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?
+                .assignments;
+        Ok(Self::from_assignments(&assignments))
+    }
+
+    /// The number of files assigned to the stack with `stack_id`.
+    pub(crate) fn of(&self, stack_id: Option<StackId>) -> usize {
+        stack_id
+            .and_then(|stack_id| self.by_stack.get(&stack_id).copied())
+            .unwrap_or(0)
+    }
+}
+
+/// The changes of one file, as reported after assigning.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileOutcome {
+    cli_id: String,
+    path: String,
+    /// The number of hunks of this file that are assigned to the stack.
+    hunks: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AssignOutcome {
+    branch: String,
+    /// The files with the hunks that were assigned by this invocation.
+    assigned: Vec<FileOutcome>,
+    /// The files with the hunks that already were assigned to the stack.
+    already_assigned: Vec<FileOutcome>,
+}
+
+/// A file whose hunks were unassigned from one stack.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnassignedFile {
+    cli_id: String,
+    path: String,
+    /// The branch the hunks were assigned to.
+    branch: String,
+    hunks: usize,
+}
+
+/// A source given to `but unassign` that didn't unassign anything, and why.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Skipped {
+    target: String,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnassignOutcome {
+    unassigned: Vec<UnassignedFile>,
+    skipped: Vec<Skipped>,
+}
+
+/// Handle `but assign <source>... <branch>`.
+///
+/// Assigns the uncommitted files and hunks of `sources` to the stack of `branch`, or with `from`,
+/// only those of them that are assigned to its stack, or all of them if there are no `sources`.
+/// Hunks that are locked to a commit in another stack are only assigned with `force`.
+pub(crate) fn handle(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    sources: &[String],
+    branch: &str,
+    from: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let t = theme::get();
+    let mut guard = ctx.exclusive_worktree_access();
+    let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
+    let target = resolve_lane(ctx, &id_map, branch, out)?;
+    let from = from
+        .map(|from| resolve_lane(ctx, &id_map, from, out))
+        .transpose()?;
+
+    let worktree_changes =
+        but_api::diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?;
+    let assignments = &worktree_changes.assignments;
+
+    let mut hunks = Vec::<WorktreeHunk>::new();
+    for source in sources {
+        for hunk in selected_hunks(ctx, &id_map, source, out)? {
+            if !hunks.contains(&hunk) {
+                hunks.push(hunk);
+            }
+        }
+    }
+    if let Some(from) = &from {
+        if sources.is_empty() {
+            hunks = assignments
+                .iter()
+                .filter(|assignment| assignment.stack_id == Some(from.stack_id))
+                .cloned()
+                .map(WorktreeHunk::from)
+                .collect();
+        } else {
+            hunks.retain(|hunk| assigned_stack(assignments, hunk) == Some(from.stack_id));
+        }
+        if hunks.is_empty() {
+            if let Some(out) = out.for_human() {
+                writeln!(
+                    out,
+                    "{}",
+                    t.hint.paint(format!(
+                        "Nothing to assign, no such changes are assigned to '{}'.",
+                        from.name
+                    ))
+                )?;
+            }
+            if let Some(out) = out.for_json() {
+                out.write_value(AssignOutcome {
+                    branch: target.name,
+                    assigned: Vec::new(),
+                    already_assigned: Vec::new(),
+                })?;
+            }
+            return Ok(());
+        }
+    }
+
+    let (already_assigned, to_assign): (Vec<_>, Vec<_>) = hunks
+        .into_iter()
+        .partition(|hunk| assigned_stack(assignments, hunk) == Some(target.stack_id));
+
+    let locked: Vec<_> = to_assign
+        .iter()
+        .filter_map(|hunk| {
+            let commits = locking_commits(
+                worktree_changes.dependencies.as_ref(),
+                hunk,
+                target.stack_id,
+            );
+            (!commits.is_empty()).then_some((hunk, commits))
+        })
+        .collect();
+    if !locked.is_empty() {
+        let repo = ctx.repo.get()?;
+        let mut details = String::new();
+        for (hunk, commits) in &locked {
+            let commits = commits
+                .iter()
+                .map(|commit| shorten_object_id(&repo, *commit))
+                .collect::<Vec<_>>()
+                .join(", ");
+            details.push_str(&format!(
+                "\n  {}{} depends on {commits}",
+                hunk.path_bytes.as_bstr(),
+                hunk.hunk_header
+                    .map(|header| format!(" {}", format_header(&header)))
+                    .unwrap_or_default()
+            ));
+        }
+        if !force {
+            bail!(
+                "Refusing to assign changes that are locked to commits in other stacks, as they can't be committed to '{}' on their own:{details}\nUse --force to assign them anyway.",
+                target.name
+            );
+        }
+        if let Some(out) = out.for_human() {
+            writeln!(
+                out,
+                "{}",
+                t.attention.paint(format!(
+                    "Warning: assigning changes that are locked to commits in other stacks:{details}"
+                ))
+            )?;
+        }
+    }
+
+    if !to_assign.is_empty() {
+        let requests = to_assign
+            .iter()
+            .map(|hunk| HunkAssignmentRequest {
+                hunk_header: hunk.hunk_header,
+                path_bytes: hunk.path_bytes.clone(),
+                target: Some(HunkAssignmentTarget::Stack {
+                    stack_id: target.stack_id,
+                }),
+            })
+            .collect();
+        but_api::diff::assign_hunk_with_perm(ctx, requests, guard.write_permission())?;
+    }
+
+    // Report what the stack holds now, as read back after assigning.
+    let assignments =
+        but_api::diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?
+            .assignments;
+    let assigned = files_on_stack(&id_map, &assignments, &to_assign, target.stack_id);
+    let already_assigned =
+        files_on_stack(&id_map, &assignments, &already_assigned, target.stack_id);
+
+    if let Some(out) = out.for_human() {
+        if assigned.is_empty() {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(format!(
+                    "Nothing to assign, the changes are already assigned to '{}'.",
+                    target.name
+                ))
+            )?;
+        } else {
+            writeln!(
+                out,
+                "Assigned to {}:",
+                t.local_branch.paint(target.name.as_str())
+            )?;
+            for file in &assigned {
+                writeln!(
+                    out,
+                    "  {} {} {}",
+                    t.cli_id.paint(file.cli_id.as_str()),
+                    file.path,
+                    t.hint.paint(format!(
+                        "({} {})",
+                        file.hunks,
+                        if file.hunks == 1 { "hunk" } else { "hunks" }
+                    ))
+                )?;
+            }
+            for file in &already_assigned {
+                writeln!(
+                    out,
+                    "  {} {} {}",
+                    t.cli_id.paint(file.cli_id.as_str()),
+                    file.path,
+                    t.hint.paint("(already assigned)")
+                )?;
+            }
+        }
+    }
+    if let Some(out) = out.for_json() {
+        out.write_value(AssignOutcome {
+            branch: target.name,
+            assigned,
+            already_assigned,
+        })?;
+    }
+    Ok(())
+}
+
+/// Handle `but unassign <source>...` and `but unassign --all --branch <branch>`.
+///
+/// Returns the assigned hunks of `sources`, or all hunks assigned to the stack of `all_of`, to the
+/// unassigned changes. Sources that can't be resolved or are already unassigned are skipped and
+/// reported, and it's only an error if nothing was unassigned.
+pub(crate) fn handle_unassign(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    sources: &[String],
+    all_of: Option<&str>,
+) -> Result<()> {
+    let t = theme::get();
+    let mut guard = ctx.exclusive_worktree_access();
+    let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
+    let assignments =
+        but_api::diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?
+            .assignments;
+
+    let mut hunks = Vec::<HunkAssignment>::new();
+    let mut skipped = Vec::new();
+    if let Some(branch) = all_of {
+        let lane = resolve_lane(ctx, &id_map, branch, out)?;
+        hunks.extend(
+            assignments
+                .iter()
+                .filter(|assignment| assignment.stack_id == Some(lane.stack_id))
+                .cloned(),
+        );
+        if hunks.is_empty() {
+            skipped.push(Skipped {
+                target: branch.to_owned(),
+                reason: format!("nothing is assigned to '{}'", lane.name),
+            });
+        }
+    }
+    for source in sources {
+        let selected = match selected_hunks(ctx, &id_map, source, out) {
+            Ok(selected) => selected,
+            Err(err) => {
+                skipped.push(Skipped {
+                    target: source.clone(),
+                    reason: err.to_string(),
+                });
+                continue;
+            }
+        };
+        let assigned: Vec<_> = selected
+            .iter()
+            .filter_map(|hunk| assignment_of(&assignments, hunk))
+            .filter(|assignment| assignment.stack_id.is_some())
+            .collect();
+        if assigned.is_empty() {
+            skipped.push(Skipped {
+                target: source.clone(),
+                reason: "already unassigned".into(),
+            });
+            continue;
+        }
+        for assignment in assigned {
+            if !hunks.contains(assignment) {
+                hunks.push(assignment.clone());
+            }
+        }
+    }
+
+    if hunks.is_empty() {
+        let reasons: String = skipped
+            .iter()
+            .map(|skipped| format!("\n  {}: {}", skipped.target, skipped.reason))
+            .collect();
+        bail!("Nothing was unassigned:{reasons}");
+    }
+    let requests = hunks
+        .iter()
+        .map(|hunk| HunkAssignmentRequest {
+            hunk_header: hunk.hunk_header,
+            path_bytes: hunk.path_bytes.clone(),
+            target: None,
+        })
+        .collect();
+    but_api::diff::assign_hunk_with_perm(ctx, requests, guard.write_permission())?;
+
+    let mut unassigned = Vec::<UnassignedFile>::new();
+    for hunk in &hunks {
+        let branch = hunk
+            .stack_id
+            .and_then(|stack_id| stack_name(&id_map, stack_id))
+            .unwrap_or_default();
+        let path = hunk.path_bytes.to_str_lossy();
+        match unassigned
+            .iter_mut()
+            .find(|file| file.path == path && file.branch == branch)
+        {
+            Some(file) => file.hunks += 1,
+            None => unassigned.push(UnassignedFile {
+                cli_id: file_cli_id(&id_map, hunk.path_bytes.as_bstr()),
+                path: path.into_owned(),
+                branch,
+                hunks: 1,
+            }),
+        }
+    }
+    unassigned.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.branch.cmp(&b.branch)));
+
+    if let Some(out) = out.for_human() {
+        writeln!(out, "Unassigned:")?;
+        for file in &unassigned {
+            writeln!(
+                out,
+                "  {} {} {}",
+                t.cli_id.paint(file.cli_id.as_str()),
+                file.path,
+                t.hint.paint(format!(
+                    "({} {} from {})",
+                    file.hunks,
+                    if file.hunks == 1 { "hunk" } else { "hunks" },
+                    file.branch
+                ))
+            )?;
+        }
+        if !skipped.is_empty() {
+            writeln!(out, "{}", t.attention.paint("Skipped:"))?;
+            for skipped in &skipped {
+                writeln!(out, "  {}: {}", skipped.target, skipped.reason)?;
+            }
+        }
+    }
+    if let Some(out) = out.for_json() {
+        out.write_value(UnassignOutcome {
+            unassigned,
+            skipped,
+        })?;
+    }
+    Ok(())
+}
+
+/// Resolve `entity` to an applied branch or a stack, prompting to pick one if it's ambiguous.
+pub(crate) fn resolve_lane(
+    ctx: &mut Context,
+    id_map: &IdMap,
+    entity: &str,
+    out: &mut OutputChannel,
+) -> Result<Lane> {
+    let all = id_map.parse_using_context(entity, ctx)?;
+    let mut lanes: Vec<CliId> = all
+        .iter()
+        .filter(|id| matches!(id, CliId::Branch(..) | CliId::Stack { .. }))
+        .cloned()
+        .collect();
+    let id = match lanes.len() {
+        0 => match all.first() {
+            Some(other) => bail!(
+                "'{entity}' is {} but must be a branch or stack",
+                other.kind_for_humans()
+            ),
+            None => bail!(
+                "Could not find the branch or stack '{entity}'. Run `but status` to see the applied branches."
+            ),
+        },
+        1 => lanes.remove(0),
+        _ => {
+            let repo = ctx.repo.get()?;
+            prompt_for_disambiguation(entity, lanes, "the branch", &repo, out)?
+        }
+    };
+    match id {
+        CliId::Branch(branch) => {
+            let Some(stack_id) = branch.stack_id else {
+                bail!("Branch '{}' isn't in a stack", branch.name);
+            };
+            Ok(Lane {
+                stack_id,
+                name: branch.name,
+            })
+        }
+        CliId::Stack { id, stack_id } => Ok(Lane {
+            stack_id,
+            name: stack_name(id_map, stack_id).unwrap_or(id),
+        }),
+        _ => unreachable!("only branches and stacks are kept"),
+    }
+}
+
+/// The uncommitted hunks that `source` selects, which may be several IDs separated by commas.
+fn selected_hunks(
+    ctx: &mut Context,
+    id_map: &IdMap,
+    source: &str,
+    out: &mut OutputChannel,
+) -> Result<Vec<WorktreeHunk>> {
+    let mut hunks = Vec::new();
+    for id in parse_uncommitted_sources_with_disambiguation(ctx, id_map, source, out)? {
+        match id {
+            CliId::UncommittedHunkOrFile(uncommitted) => hunks.extend(uncommitted.hunk_assignments),
+            CliId::PathPrefix {
+                id,
+                hunk_assignments,
+            } => {
+                let matched: Vec<_> = hunk_assignments.into_iter().map(|(_, hunk)| hunk).collect();
+                write_path_matches(out, &id, &matched)?;
+                hunks.extend(matched);
+            }
+            other => bail!(
+                "'{source}' is {} but must be an uncommitted file or hunk",
+                other.kind_for_humans()
+            ),
+        }
+    }
+    Ok(hunks)
+}
+
+/// Tell the user how many and which files the path or glob `pattern` matched with `hunks`.
+pub(crate) fn write_path_matches(
+    out: &mut OutputChannel,
+    pattern: &str,
+    hunks: &[WorktreeHunk],
+) -> Result<()> {
+    let Some(out) = out.for_human() else {
+        return Ok(());
+    };
+    let paths: BTreeSet<&BStr> = hunks.iter().map(|hunk| hunk.path_bytes.as_bstr()).collect();
+    writeln!(
+        out,
+        "'{pattern}' matches {} {}:",
+        paths.len(),
+        if paths.len() == 1 { "file" } else { "files" }
+    )?;
+    for path in paths {
+        writeln!(out, "  {path}")?;
+    }
+    Ok(())
+}
+
+/// The name of the topmost branch of the stack with `stack_id`.
+fn stack_name(id_map: &IdMap, stack_id: StackId) -> Option<String> {
+    id_map
+        .stacks()
+        .iter()
+        .find(|stack| stack.id == Some(stack_id))
+        .and_then(|stack| stack.segments.first())
+        .and_then(|segment| segment.branch_name())
+        .map(|name| name.to_string())
+}
+
+/// The current assignment of `hunk`.
+fn assignment_of<'a>(
+    assignments: &'a [HunkAssignment],
+    hunk: &WorktreeHunk,
+) -> Option<&'a HunkAssignment> {
+    assignments.iter().find(|assignment| {
+        assignment.path_bytes == hunk.path_bytes && assignment.hunk_header == hunk.hunk_header
+    })
+}
+
+/// The stack `hunk` is currently assigned to, if any.
+fn assigned_stack(assignments: &[HunkAssignment], hunk: &WorktreeHunk) -> Option<StackId> {
+    assignment_of(assignments, hunk).and_then(|assignment| assignment.stack_id)
+}
+
+/// The commits in stacks other than `stack_id` that `hunk` depends on.
+fn locking_commits(
+    dependencies: Option<&HunkDependencies>,
+    hunk: &WorktreeHunk,
+    stack_id: StackId,
+) -> Vec<gix::ObjectId> {
+    let (Some(dependencies), Some(header)) = (dependencies, hunk.hunk_header) else {
+        return Vec::new();
+    };
+    let mut commits = Vec::new();
+    for (path, dependency_hunk, locks) in &dependencies.diffs {
+        if path.as_bytes().as_bstr() != hunk.path_bytes.as_bstr()
+            || !hunks_overlap(&header, &HunkHeader::from(dependency_hunk))
+        {
+            continue;
+        }
+        for lock in locks {
+            if matches!(lock.target, HunkLockTarget::Stack(id) if id != stack_id)
+                && !commits.contains(&lock.commit_id)
+            {
+                commits.push(lock.commit_id);
+            }
+        }
+    }
+    commits
+}
+
+/// The files of `hunks` along with how many of their hunks are assigned to `stack_id`, by path.
+fn files_on_stack(
+    id_map: &IdMap,
+    assignments: &[HunkAssignment],
+    hunks: &[WorktreeHunk],
+    stack_id: StackId,
+) -> Vec<FileOutcome> {
+    let by_file = but_hunk_assignment::group_by_file(assignments);
+    let paths: BTreeSet<&BString> = hunks.iter().map(|hunk| &hunk.path_bytes).collect();
+    paths
+        .into_iter()
+        .map(|path| FileOutcome {
+            cli_id: file_cli_id(id_map, path.as_bstr()),
+            path: path.to_str_lossy().into_owned(),
+            hunks: by_file.get(path).map_or(0, |file| {
+                file.filter_by_stack(Some(stack_id)).assignments.len()
+            }),
+        })
+        .collect()
+}
+
+/// The CLI ID of the uncommitted file at `path`.
+fn file_cli_id(id_map: &IdMap, path: &BStr) -> String {
+    id_map
+        .uncommitted_files
+        .values()
+        .find(|file| file.path() == path)
+        .map(|file| file.short_id.clone())
+        .unwrap_or_default()
+}
+
+fn format_header(header: &HunkHeader) -> String {
+    format!(
+        "@@ -{},{} +{},{} @@",
+        header.old_start, header.old_lines, header.new_start, header.new_lines
+    )
+}
@@ -0,0 +1,144 @@
+use anyhow::bail;
+use but_core::RefMetadata as _;
+use but_ctx::Context;
+
+use super::json;
+use crate::{
+    CliResult, IdMap,
+    args::atoms::CliIdArg,
+    theme::{self, Paint},
+    tui,
+    utils::OutputChannel,
+};
+
+/// Set the description of the branch identified by `branch_arg` to `text`, or to what the user
+/// enters in the editor if `text` is `None`.
+///
+/// The description is stored in the branch metadata, an empty description removes it.
+pub fn set_description(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    branch_arg: CliIdArg,
+    text: Option<String>,
+) -> CliResult<()> {
+    let t = theme::get();
+    let ref_name = {
+        let guard = ctx.shared_worktree_access();
+        resolve_branch(ctx, &branch_arg, guard.read_permission())?
+    };
+    // Don't hold the lock while the user is in the editor.
+    let text = match text {
+        Some(text) => text,
+        None => get_description_from_editor(
+            ref_name.shorten().to_string().as_str(),
+            ctx.meta()?
+                .branch_opt(ref_name.as_ref())?
+                .and_then(|branch| branch.description.clone())
+                .as_deref(),
+        )?,
+    };
+    let description = Some(text.trim().to_owned()).filter(|text| !text.is_empty());
+
+    {
+        let _guard = ctx.exclusive_worktree_access();
+        let mut meta = ctx.meta()?;
+        let mut branch = meta.branch(ref_name.as_ref())?;
+        branch.description = description.clone();
+        meta.set_branch(&branch)?;
+    }
+
+    let branch_name = ref_name.shorten().to_string();
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{} {} description of branch {}",
+            t.sym().success,
+            if description.is_some() {
+                "Set"
+            } else {
+                "Removed"
+            },
+            t.local_branch.paint(branch_name.as_str()),
+        )?;
+    } else if let Some(out) = out.for_shell() {
+        writeln!(out, "{branch_name}")?;
+    } else if let Some(out) = out.for_json() {
+        out.write_value(json::BranchDescriptionOutput {
+            branch: branch_name,
+            description,
+        })?;
+    }
+    Ok(())
+}
+
+/// Print the description of the branch identified by `branch_arg`.
+pub fn describe(ctx: &mut Context, out: &mut OutputChannel, branch_arg: CliIdArg) -> CliResult<()> {
+    let t = theme::get();
+    let (branch_name, description) = {
+        let guard = ctx.shared_worktree_access();
+        let ref_name = resolve_branch(ctx, &branch_arg, guard.read_permission())?;
+        let description = ctx
+            .meta()?
+            .branch_opt(ref_name.as_ref())?
+            .and_then(|branch| branch.description.clone());
+        (ref_name.shorten().to_string(), description)
+    };
+
+    if let Some(out) = out.for_human() {
+        match &description {
+            Some(description) => writeln!(out, "{description}")?,
+            None => writeln!(
+                out,
+                "Branch {} has no description. {}",
+                t.local_branch.paint(branch_name.as_str()),
+                t.hint.paint(format!(
+                    "Set one with `but branch set-description {branch_name}`"
+                ))
+            )?,
+        }
+    } else if let Some(out) = out.for_shell() {
+        if let Some(description) = &description {
+            writeln!(out, "{description}")?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(json::BranchDescriptionOutput {
+            branch: branch_name,
+            description,
+        })?;
+    }
+    Ok(())
+}
+
+/// Resolve `branch_arg` to an existing local branch, by name or CLI ID.
+fn resolve_branch(
+    ctx: &Context,
+    branch_arg: &CliIdArg,
+    perm: &but_core::sync::RepoShared,
+) -> CliResult<gix::refs::FullName> {
+    let id_map = IdMap::new_from_context(ctx, None, perm)?;
+    let repo = ctx.repo.get()?;
+    branch_arg.resolve_existing_local_branch(&repo, &id_map)
+}
+
+/// Let the user edit `current_description` of `branch_name` in the editor and return the result.
+fn get_description_from_editor(
+    branch_name: &str,
+    current_description: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut template = String::new();
+    if let Some(current_description) = current_description {
+        template.push_str(current_description);
+        template.push('\n');
+    }
+    template.push_str(&format!(
+        "\n# Please enter the description of branch '{branch_name}'. Lines starting\n"
+    ));
+    template.push_str("# with '#' will be ignored, and an empty description removes it.\n");
+    template.push_str("#\n");
+
+    let description = tui::get_text::from_editor_no_comments("branch_description", &template)?;
+    let Ok(description) = String::from_utf8(description.into()) else {
+        bail!("The branch description must be valid UTF-8");
+    };
+    Ok(description)
+}
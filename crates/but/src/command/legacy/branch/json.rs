@@ -7,14 +7,41 @@ pub struct BranchNewOutput {
     pub branch: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub anchor: Option<CliIdArg>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<CliIdArg>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDeleteOutput {
+    /// Full names of all deleted references, the local branch first.
+    pub deleted_refs: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BranchDescriptionOutput {
+    pub branch: String,
+    /// The description of the branch, or `None` if it has none.
+    pub description: Option<String>,
+}
+
+/// The version of the [`BranchListOutput`] schema.
+///
+/// Increment it whenever fields are removed, renamed or change their meaning.
+/// Adding fields doesn't require a new version.
+pub const BRANCH_LIST_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchListOutput {
+    /// Always [`BRANCH_LIST_SCHEMA_VERSION`], so consumers can detect changes.
+    pub schema_version: u32,
     pub applied_stacks: Vec<StackOutput>,
     pub branches: Vec<BranchOutput>,
     pub has_more_branches: bool,
+    /// The number of uncommitted files not assigned to any stack, unless counting was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unassigned_files: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
@@ -22,13 +49,21 @@ pub struct BranchListOutput {
 pub struct StackOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    /// Whether the stack is applied to the workspace.
+    pub applied: bool,
+    /// The branches of the stack, from top to bottom.
     pub heads: Vec<BranchHeadOutput>,
+    /// The number of uncommitted files assigned to the stack, unless counting was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_files: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchHeadOutput {
     pub name: String,
+    /// The hex-encoded commit the branch points to.
+    pub tip: String,
     pub reviews: Vec<ReviewOutput>,
     /// Last commit timestamp in milliseconds since epoch
     pub last_commit_at: u128,
@@ -38,14 +73,23 @@ pub struct BranchHeadOutput {
     /// Whether the branch merges cleanly into upstream
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merges_cleanly: Option<bool>,
+    /// Whether the branch contains conflicted commits.
+    pub conflicted: bool,
+    /// Whether the branch was integrated into the target branch.
+    pub integrated: bool,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchOutput {
     pub name: String,
+    /// The hex-encoded commit the branch points to.
+    pub head: String,
     pub reviews: Vec<ReviewOutput>,
+    /// Whether a local branch exists. If not, the branch exists only on the `remotes`.
     pub has_local: bool,
+    /// The names of the remotes the branch exists on.
+    pub remotes: Vec<String>,
     /// Last commit timestamp in milliseconds since epoch
     pub last_commit_at: u128,
     /// Number of commits ahead of the base branch
@@ -54,6 +98,20 @@ pub struct BranchOutput {
     /// Whether the branch merges cleanly into upstream
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merges_cleanly: Option<bool>,
+    /// Whether the branch was integrated into the target branch, unless the check was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrated: Option<bool>,
+    /// The unapplied stack the branch is listed in, along with other branches of it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<UnappliedStackOutput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnappliedStackOutput {
+    pub id: String,
+    /// The names of the listed branches of the stack, from top to bottom.
+    pub branches: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -68,4 +126,43 @@ pub struct AuthorOutput {
 pub struct ReviewOutput {
     pub number: u64,
     pub url: String,
+    pub state: ReviewState,
+    /// The state of the CI checks of the branch, if the review is open and has any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ci: Option<but_forge::CiSummary>,
+}
+
+/// The state of a review on the forge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewState {
+    Open,
+    Draft,
+    Merged,
+    Closed,
+}
+
+impl ReviewState {
+    /// Return the state of `review`.
+    pub fn of(review: &but_forge::ForgeReview) -> Self {
+        if review.is_merged() {
+            ReviewState::Merged
+        } else if !review.is_open() {
+            ReviewState::Closed
+        } else if review.draft {
+            ReviewState::Draft
+        } else {
+            ReviewState::Open
+        }
+    }
+
+    /// The name of the state, as used in JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewState::Open => "open",
+            ReviewState::Draft => "draft",
+            ReviewState::Merged => "merged",
+            ReviewState::Closed => "closed",
+        }
+    }
 }
@@ -1,15 +1,135 @@
 use std::collections::HashMap;
 
+use but_core::RefMetadata as _;
 use but_ctx::Context;
 use gitbutler_branch_actions::BranchListingFilter;
 
 use crate::{
-    command::legacy::workspace_target,
+    args::HyperlinkMode,
+    command::legacy::{assign::AssignedFiles, workspace_target},
     legacy::workspace::HeadInfoStack,
     theme::{self, Paint},
     utils::OutputChannel,
 };
 
+/// Restricts the branches shown by `but branch list`.
+///
+/// Patterns match as substring, or as glob if they contain `*`, `?` or `[`.
+#[derive(Debug, Default)]
+pub struct BranchFilter {
+    /// The pattern the branch name has to match.
+    pub name: Option<String>,
+    /// The pattern the name or email of the author of the branch tip has to match.
+    pub author: Option<String>,
+    /// Match patterns case-sensitively instead of ignoring case.
+    pub case_sensitive: bool,
+}
+
+impl BranchFilter {
+    /// Return `true` if the branch `name` with a tip authored by `author_name` and `author_email`
+    /// passes all patterns.
+    fn matches(
+        &self,
+        name: &str,
+        author_name: Option<String>,
+        author_email: Option<String>,
+    ) -> bool {
+        self.name
+            .as_deref()
+            .is_none_or(|pattern| self.matches_pattern(pattern, name))
+            && self.author.as_deref().is_none_or(|pattern| {
+                [author_name, author_email]
+                    .into_iter()
+                    .flatten()
+                    .any(|value| self.matches_pattern(pattern, &value))
+            })
+    }
+
+    fn matches_pattern(&self, pattern: &str, value: &str) -> bool {
+        if pattern.contains(['*', '?', '[']) {
+            let mode = if self.case_sensitive {
+                gix::glob::wildmatch::Mode::empty()
+            } else {
+                gix::glob::wildmatch::Mode::IGNORE_CASE
+            };
+            gix::glob::wildmatch(pattern.into(), value.into(), mode)
+        } else if self.case_sensitive {
+            value.contains(pattern)
+        } else {
+            value.to_lowercase().contains(&pattern.to_lowercase())
+        }
+    }
+}
+
+/// The amount of non-applied branches shown if no limit is given.
+const DEFAULT_LIMIT: usize = 20;
+
+/// The branches left out after the limit was reached.
+#[derive(Debug, Clone, Copy)]
+enum MoreBranches {
+    Exactly(usize),
+    /// Not all remaining branches were checked for commits ahead of the target, so some may not count.
+    AtMost(usize),
+}
+
+/// The branches of a stack that isn't applied to the workspace, as shown in the listing.
+#[derive(Debug, Clone)]
+struct UnappliedStack {
+    id: but_core::ref_metadata::StackId,
+    /// The names of the shown branches of the stack, from top to bottom.
+    branches: Vec<String>,
+}
+
+/// Where a listed branch is drawn within its unapplied stack.
+#[derive(Debug, Clone)]
+struct StackPlacement {
+    stack: std::rc::Rc<UnappliedStack>,
+    /// The index into the branches of `stack`.
+    position: usize,
+}
+
+/// Where to get the reviews shown for each branch from.
+#[derive(Debug, Clone, Copy)]
+pub enum ReviewSource {
+    /// Use the cached reviews if they are recent enough, or else query the forge.
+    Cache,
+    /// Always query the forge.
+    Forge,
+}
+
+/// The git config key for the number of seconds cached reviews are considered recent.
+const REVIEW_CACHE_SECONDS_KEY: &str = "but.reviews.cacheSeconds";
+const DEFAULT_REVIEW_CACHE_SECONDS: u64 = 5 * 60;
+
+impl ReviewSource {
+    pub(crate) fn cache_config(self, ctx: &Context) -> anyhow::Result<but_forge::CacheConfig> {
+        if crate::utils::envs::is_offline() {
+            return Ok(but_forge::CacheConfig::CacheOnly);
+        }
+        Ok(match self {
+            ReviewSource::Cache => but_forge::CacheConfig::CacheWithFallback {
+                max_age_seconds: ctx
+                    .repo
+                    .get()?
+                    .config_snapshot()
+                    .integer(REVIEW_CACHE_SECONDS_KEY)
+                    .and_then(|seconds| u64::try_from(seconds).ok())
+                    .unwrap_or(DEFAULT_REVIEW_CACHE_SECONDS),
+            },
+            ReviewSource::Forge => but_forge::CacheConfig::NoCache,
+        })
+    }
+}
+
+/// The reviews of each branch, and the CI summary of each branch with an open review.
+type ReviewsAndCi = (
+    HashMap<String, Vec<but_forge::ForgeReview>>,
+    HashMap<String, but_forge::CiSummary>,
+);
+
+/// A query for [`ReviewsAndCi`], which is run on its own thread while branches are listed.
+type ReviewQuery = Box<dyn FnOnce(&Context) -> anyhow::Result<ReviewsAndCi> + Send>;
+
 #[expect(clippy::too_many_arguments)]
 pub fn list(
     ctx: &mut but_ctx::Context,
@@ -17,11 +137,69 @@ pub fn list(
     remote: bool,
     all: bool,
     ahead: bool,
-    review: bool,
-    filter: Option<String>,
+    reviews: Option<ReviewSource>,
+    filter: &BranchFilter,
+    out: &mut OutputChannel,
+    check_merge: bool,
+    show_empty: bool,
+    verbose: bool,
+    limit: Option<usize>,
+    fast: bool,
+    count_assigned_files: bool,
+    hyperlinks: HyperlinkMode,
+) -> Result<(), anyhow::Error> {
+    let review_query = reviews
+        .map(|source| -> anyhow::Result<ReviewQuery> {
+            let cache_config = source.cache_config(ctx)?;
+            Ok(Box::new(move |ctx: &Context| {
+                crate::command::legacy::forge::review::get_review_map(
+                    ctx,
+                    Some(cache_config.clone()),
+                )
+                .map(|review_map| {
+                    let ci_map = ci_summaries(ctx, &review_map, cache_config);
+                    (review_map, ci_map)
+                })
+            }))
+        })
+        .transpose()?;
+    list_with_review_query(
+        ctx,
+        local,
+        remote,
+        all,
+        ahead,
+        review_query,
+        filter,
+        out,
+        check_merge,
+        show_empty,
+        verbose,
+        limit,
+        fast,
+        count_assigned_files,
+        hyperlinks,
+    )
+}
+
+/// Like [`list()`], but with reviews obtained by `review_query`, if set.
+#[expect(clippy::too_many_arguments)]
+fn list_with_review_query(
+    ctx: &mut but_ctx::Context,
+    local: bool,
+    remote: bool,
+    all: bool,
+    ahead: bool,
+    review_query: Option<ReviewQuery>,
+    filter: &BranchFilter,
     out: &mut OutputChannel,
     check_merge: bool,
     show_empty: bool,
+    verbose: bool,
+    limit: Option<usize>,
+    fast: bool,
+    count_assigned_files: bool,
+    hyperlinks: HyperlinkMode,
 ) -> Result<(), anyhow::Error> {
     let listing_filter = if local {
         Some(BranchListingFilter {
@@ -32,16 +210,24 @@ pub fn list(
         None
     };
 
-    let branch_review_map = if review {
-        crate::command::legacy::forge::review::get_review_map(
-            ctx,
-            Some(but_forge::CacheConfig::CacheOnly),
-        )?
-    } else {
-        HashMap::new()
+    // Query the forge while listing branches locally, as it's independent and may be slow.
+    let review_map_rx = match review_query {
+        Some(query) => {
+            let ctx = ctx.to_sync();
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let ctx = ctx.into_thread_local();
+                tx.send(query(&ctx)).ok();
+            });
+            Some(rx)
+        }
+        None => None,
     };
 
     let mut applied_stacks = crate::legacy::workspace::applied_stacks(ctx)?;
+    let assigned_files = count_assigned_files
+        .then(|| AssignedFiles::count_in_worktree(ctx))
+        .transpose()?;
 
     // Resolve the target once for all target-based filtering and calculations we may perform.
     let target_oid: Option<gix::ObjectId> = if !show_empty || ahead || check_merge {
@@ -79,23 +265,6 @@ pub fn list(
         applied_stacks.retain(|stack| !stack.branches.is_empty());
     }
 
-    // Apply name filter to applied stacks if provided
-    if let Some(ref filter_str) = filter {
-        let filter_lower = filter_str.to_lowercase();
-        applied_stacks.retain(|stack| {
-            stack
-                .branches
-                .iter()
-                .any(|branch| branch.name.to_lowercase().contains(&filter_lower))
-        });
-        // Also filter the heads within each stack
-        for stack in &mut applied_stacks {
-            stack
-                .branches
-                .retain(|branch| branch.name.to_lowercase().contains(&filter_lower));
-        }
-    }
-
     let mut branches = but_api::legacy::virtual_branches::list_branches(ctx, listing_filter)?;
 
     // Filter out branches that are part of applied stacks
@@ -115,17 +284,14 @@ pub fn list(
         branches.retain(|branch| !branch.has_local);
     }
 
-    // Apply name filter if provided
-    if let Some(ref filter_str) = filter {
-        let filter_lower = filter_str.to_lowercase();
-        branches.retain(|branch| {
-            branch
-                .name
-                .to_string()
-                .to_lowercase()
-                .contains(&filter_lower)
-        });
-    }
+    // Apply name and author filters, which never hide applied stacks.
+    branches.retain(|branch| {
+        filter.matches(
+            &branch.name.to_string(),
+            branch.last_commiter.name.as_ref().map(ToString::to_string),
+            branch.last_commiter.email.as_ref().map(ToString::to_string),
+        )
+    });
 
     // Filter out dependabot branches unless --all is specified
     if !all {
@@ -150,11 +316,15 @@ pub fn list(
             .then_with(|| a.name.cmp(&b.name))
     });
 
-    let max_branches = if all { usize::MAX / 2 } else { 20 };
-    // Take one extra branch than we want to show to check if there's more to show
-    let num_branches_to_take = max_branches + 1;
+    let max_branches = match limit {
+        _ if all => usize::MAX,
+        Some(0) => usize::MAX,
+        Some(limit) => limit,
+        None => DEFAULT_LIMIT,
+    };
 
-    let branches_to_show: Vec<_> = if let Some(target_oid) = target_oid_for_filter {
+    let mut candidates = branches.into_iter();
+    let (branches_to_show, more_branches) = if let Some(target_oid) = target_oid_for_filter {
         let repo = ctx.repo.get()?;
         let cache = repo.commit_graph_if_enabled()?;
         let mut graph = repo.revision_graph(cache.as_ref());
@@ -166,26 +336,38 @@ pub fn list(
         // This computation is _very heavy_ and can take minutes in repositories with thousands of
         // branches (e.g. the VS Code repository), so we perform it lazily. It's therefore important
         // that it happens after sorting.
-        branches
-            .into_iter()
-            .filter(|branch| {
-                if branch.head == target_oid {
-                    return false;
-                }
-                // If the merge-base equals the branch head, the branch is already fully contained
-                // in the target and has no commits ahead to show.
-                repo.merge_base_with_graph(branch.head, target_oid, &mut graph)
-                    .map(|merge_base| merge_base.detach() != branch.head)
-                    .unwrap_or(true)
-            })
-            .take(num_branches_to_take)
-            .collect()
+        let mut has_commits_ahead = |branch: &gitbutler_branch_actions::BranchListing| {
+            if branch.head == target_oid {
+                return false;
+            }
+            // If the merge-base equals the branch head, the branch is already fully contained
+            // in the target and has no commits ahead to show.
+            repo.merge_base_with_graph(branch.head, target_oid, &mut graph)
+                .map(|merge_base| merge_base.detach() != branch.head)
+                .unwrap_or(true)
+        };
+        let branches_to_show: Vec<_> = candidates
+            .by_ref()
+            .filter(|branch| has_commits_ahead(branch))
+            .take(max_branches)
+            .collect();
+        // Only look for one more branch to show, the others remain unchecked.
+        let unchecked: Vec<_> = candidates.collect();
+        let more_branches = unchecked
+            .iter()
+            .any(|branch| has_commits_ahead(branch))
+            .then_some(MoreBranches::AtMost(unchecked.len()));
+        (branches_to_show, more_branches)
     } else {
-        branches.into_iter().take(num_branches_to_take).collect()
+        let branches_to_show: Vec<_> = candidates.by_ref().take(max_branches).collect();
+        let more_branches = Some(candidates.len())
+            .filter(|count| *count > 0)
+            .map(MoreBranches::Exactly);
+        (branches_to_show, more_branches)
     };
-
-    let has_more_branches = branches_to_show.len() > max_branches;
-    let branches_to_show: Vec<_> = branches_to_show.into_iter().take(max_branches).collect();
+    let has_more_branches = more_branches.is_some();
+    let (branches_to_show, placements) =
+        group_by_unapplied_stack(branches_to_show, &unapplied_stacks(ctx)?);
 
     // Calculate commits ahead if requested
     let commits_ahead_map: Option<HashMap<String, usize>> = if ahead {
@@ -210,29 +392,72 @@ pub fn list(
         None
     };
 
+    // Check if branches are integrated into the target, only for those that are shown.
+    let integration_map: Option<HashMap<String, bool>> = if fast {
+        None
+    } else {
+        integrated_branches(ctx, &branches_to_show)
+    };
+
+    let (branch_review_map, ci_map) = match review_map_rx {
+        Some(rx) => recv_with_spinner(rx, &mut out.progress_channel(), "Fetching reviews")??,
+        None => (HashMap::new(), HashMap::new()),
+    };
+
     let allow_truncation = out.format().allows_truncation();
+    let hyperlinks = match hyperlinks {
+        HyperlinkMode::Always => true,
+        HyperlinkMode::Never => false,
+        HyperlinkMode::Auto => {
+            out.format().allows_human_ui()
+                && out.is_terminal()
+                && crate::tui::text::terminal_supports_hyperlinks()
+        }
+    };
     if let Some(out) = out.for_json() {
         output_json(
             &applied_stacks,
             &branches_to_show,
+            &placements,
             has_more_branches,
             &branch_review_map,
+            &ci_map,
             commits_ahead_map.as_ref(),
             merge_status_map.as_ref(),
+            integration_map.as_ref(),
+            assigned_files.as_ref(),
             ctx,
             out,
         )?;
     } else if let Some(out) = out.for_human() {
+        let t = theme::get();
+        let review_labels = review_labels(&branch_review_map, &ci_map, hyperlinks);
+        let descriptions = if verbose {
+            let applied_names = applied_stacks
+                .iter()
+                .flat_map(|stack| stack.branches.iter().map(|branch| branch.name.clone()));
+            let unapplied_names = branches_to_show
+                .iter()
+                .filter(|branch| branch.has_local)
+                .map(|branch| branch.name.to_string());
+            branch_descriptions(ctx, applied_names.chain(unapplied_names))?
+        } else {
+            HashMap::new()
+        };
         // Print applied branches section with header
         if !applied_stacks.is_empty() {
             writeln!(out, "Applied branches")?;
             print_applied_branches_table(
                 &applied_stacks,
-                &branch_review_map,
+                filter,
+                &review_labels,
+                &descriptions,
                 ctx,
                 commits_ahead_map.as_ref(),
                 merge_status_map.as_ref(),
+                assigned_files.as_ref(),
                 allow_truncation,
+                verbose,
                 out,
             )?;
         }
@@ -245,19 +470,51 @@ pub fn list(
             writeln!(out, "Unapplied Branches")?;
             print_branches_table(
                 &branches_to_show,
-                &branch_review_map,
+                &placements,
+                &review_labels,
+                &descriptions,
                 commits_ahead_map.as_ref(),
                 merge_status_map.as_ref(),
+                integration_map.as_ref(),
                 allow_truncation,
+                verbose,
+                out,
+            )?;
+        }
+
+        if let Some(unassigned) = assigned_files
+            .as_ref()
+            .map(|files| files.unassigned)
+            .filter(|count| *count > 0)
+        {
+            writeln!(
+                out,
+                "\n{}",
+                t.hint.paint(format!(
+                    "{unassigned} unassigned changed file{}",
+                    if unassigned == 1 { "" } else { "s" }
+                ))
+            )?;
+        }
+
+        if let Some(synced_at) = reviews_synced_at(&branch_review_map) {
+            writeln!(
                 out,
+                "\n{}",
+                t.hint.paint(format!("(reviews as of {synced_at})"))
             )?;
         }
 
-        if has_more_branches {
+        if let Some(more_branches) = more_branches {
+            let count = match more_branches {
+                MoreBranches::Exactly(count) => count.to_string(),
+                MoreBranches::AtMost(count) => format!("up to {count}"),
+            };
             writeln!(
                 out,
-                "\n... result truncated to {} matching branches (use --all to show all that match filters)",
-                branches_to_show.len()
+                "\n{}",
+                t.hint
+                    .paint(format!("… and {count} more (use --limit 0 for all)"))
             )?;
         }
     }
@@ -268,10 +525,14 @@ pub fn list(
 fn output_json(
     applied_stacks: &[HeadInfoStack],
     branches: &[gitbutler_branch_actions::BranchListing],
+    placements: &HashMap<String, StackPlacement>,
     has_more_branches: bool,
     branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    ci_map: &HashMap<String, but_forge::CiSummary>,
     commits_ahead_map: Option<&HashMap<String, usize>>,
     merge_status_map: Option<&HashMap<String, bool>>,
+    integration_map: Option<&HashMap<String, bool>>,
+    assigned_files: Option<&AssignedFiles>,
     ctx: &Context,
     out: &mut OutputChannel,
 ) -> Result<(), anyhow::Error> {
@@ -287,7 +548,7 @@ fn output_json(
                 .branches
                 .iter()
                 .map(|branch| {
-                    let reviews = get_reviews_json(&branch.name, branch_review_map);
+                    let reviews = get_reviews_json(&branch.name, branch_review_map, ci_map);
                     let commits_ahead =
                         commits_ahead_map.and_then(|map| map.get(&branch.name).copied());
                     let merges_cleanly =
@@ -303,18 +564,23 @@ fn output_json(
 
                     BranchHeadOutput {
                         name: branch.name.clone(),
+                        tip: branch.tip.to_string(),
                         reviews,
                         last_commit_at,
                         commits_ahead,
                         last_author,
                         merges_cleanly,
+                        conflicted: is_conflicted(branch),
+                        integrated: is_integrated(branch),
                     }
                 })
                 .collect();
 
             StackOutput {
                 id: stack.id.map(|id| id.to_string()),
+                applied: true,
                 heads,
+                assigned_files: assigned_files.map(|files| files.of(stack.id)),
             }
         })
         .collect();
@@ -322,15 +588,23 @@ fn output_json(
     let branches_output: Vec<BranchOutput> = branches
         .iter()
         .map(|branch| {
-            let reviews = get_reviews_json(&branch.name.to_string(), branch_review_map);
+            let reviews = get_reviews_json(&branch.name.to_string(), branch_review_map, ci_map);
             let commits_ahead =
                 commits_ahead_map.and_then(|map| map.get(&branch.name.to_string()).copied());
             let merges_cleanly =
                 merge_status_map.and_then(|map| map.get(&branch.name.to_string()).copied());
+            let integrated =
+                integration_map.and_then(|map| map.get(&branch.name.to_string()).copied());
             BranchOutput {
                 name: branch.name.to_string(),
+                head: branch.head.to_string(),
                 reviews,
                 has_local: branch.has_local,
+                remotes: branch
+                    .remotes
+                    .iter()
+                    .map(|remote| remote.as_bstr().to_string())
+                    .collect(),
                 last_commit_at: branch.updated_at,
                 commits_ahead,
                 last_author: AuthorOutput {
@@ -338,20 +612,120 @@ fn output_json(
                     email: branch.last_commiter.email.as_ref().map(|e| e.to_string()),
                 },
                 merges_cleanly,
+                integrated,
+                stack: placements.get(&branch.name.to_string()).map(|placement| {
+                    UnappliedStackOutput {
+                        id: placement.stack.id.to_string(),
+                        branches: placement.stack.branches.clone(),
+                    }
+                }),
             }
         })
         .collect();
 
     let output = BranchListOutput {
+        schema_version: BRANCH_LIST_SCHEMA_VERSION,
         applied_stacks: applied_stacks_output,
         branches: branches_output,
         has_more_branches,
+        unassigned_files: assigned_files.map(|files| files.unassigned),
     };
 
     out.write_value(output)?;
     Ok(())
 }
 
+/// Read the stacks with more than one branch that are known to the workspace but not applied to it.
+fn unapplied_stacks(ctx: &Context) -> anyhow::Result<Vec<UnappliedStack>> {
+    use but_core::RefMetadata as _;
+
+    let meta = ctx.meta()?;
+    let workspace_ref: &gix::refs::FullNameRef = but_core::WORKSPACE_REF_NAME.try_into()?;
+    let workspace = meta.workspace(workspace_ref)?;
+    Ok(workspace
+        .stacks
+        .iter()
+        .filter(|stack| !stack.is_in_workspace())
+        .map(|stack| UnappliedStack {
+            id: stack.id,
+            branches: stack
+                .branches
+                .iter()
+                .filter(|branch| !branch.archived)
+                .map(|branch| branch.ref_name.shorten().to_string())
+                .collect(),
+        })
+        .filter(|stack| stack.branches.len() > 1)
+        .collect())
+}
+
+/// Reorder `branches` so the shown members of each of the unapplied `stacks` follow each other
+/// from top to bottom, at the position of the first member in `branches`.
+///
+/// Return the reordered branches along with the placement of each grouped branch by name.
+/// Stacks with less than two shown members aren't grouped.
+fn group_by_unapplied_stack(
+    branches: Vec<gitbutler_branch_actions::BranchListing>,
+    stacks: &[UnappliedStack],
+) -> (
+    Vec<gitbutler_branch_actions::BranchListing>,
+    HashMap<String, StackPlacement>,
+) {
+    let shown_names: std::collections::HashSet<String> = branches
+        .iter()
+        .map(|branch| branch.name.to_string())
+        .collect();
+    let mut placements = HashMap::new();
+    for stack in stacks {
+        let shown = UnappliedStack {
+            id: stack.id,
+            branches: stack
+                .branches
+                .iter()
+                .filter(|name| shown_names.contains(*name))
+                .cloned()
+                .collect(),
+        };
+        if shown.branches.len() < 2 {
+            continue;
+        }
+        let shown = std::rc::Rc::new(shown);
+        for (position, name) in shown.branches.iter().enumerate() {
+            placements.insert(
+                name.clone(),
+                StackPlacement {
+                    stack: shown.clone(),
+                    position,
+                },
+            );
+        }
+    }
+    if placements.is_empty() {
+        return (branches, placements);
+    }
+
+    let names: Vec<String> = branches
+        .iter()
+        .map(|branch| branch.name.to_string())
+        .collect();
+    let mut by_name: HashMap<String, gitbutler_branch_actions::BranchListing> =
+        names.iter().cloned().zip(branches).collect();
+    let mut grouped = Vec::with_capacity(names.len());
+    for name in &names {
+        match placements.get(name) {
+            Some(placement) => grouped.extend(
+                placement
+                    .stack
+                    .branches
+                    .iter()
+                    .filter_map(|name| by_name.remove(name)),
+            ),
+            None => grouped.extend(by_name.remove(name)),
+        }
+    }
+    (grouped, placements)
+}
+
 /// Read display metadata for an applied stack head commit.
 fn applied_head_commit_info(
     repo: &gix::Repository,
@@ -372,9 +746,198 @@ fn applied_head_commit_info(
         .unwrap_or_default()
 }
 
+/// Return `true` if the applied `branch` contains conflicted commits.
+fn is_conflicted(branch: &crate::legacy::workspace::HeadInfoBranch) -> bool {
+    branch.commits.iter().any(|commit| commit.has_conflicts)
+}
+
+/// Return `true` if the applied `branch` was integrated into the target, as the desktop sees it.
+fn is_integrated(branch: &crate::legacy::workspace::HeadInfoBranch) -> bool {
+    branch.push_status == but_workspace::ui::PushStatus::Integrated
+}
+
+/// Return whether each of the unapplied `branches` is integrated into the target, keyed by name,
+/// with the same checks the workspace uses for its commits.
+///
+/// Returns `None` if that can't be determined, for instance because the target branch is missing.
+fn integrated_branches(
+    ctx: &Context,
+    branches: &[gitbutler_branch_actions::BranchListing],
+) -> Option<HashMap<String, bool>> {
+    let check = || -> anyhow::Result<Option<HashMap<String, bool>>> {
+        let project_meta = ctx.project_meta()?;
+        let (Some(target_ref), Some(target_commit_id)) =
+            (project_meta.target_ref, project_meta.target_commit_id)
+        else {
+            return Ok(None);
+        };
+        let repo = ctx.clone_repo_for_merging_non_persisting()?;
+        let integrated = but_workspace::legacy::integrated_tips(
+            &repo,
+            target_ref.as_ref(),
+            target_commit_id,
+            branches.iter().map(|branch| branch.head),
+        )?;
+        Ok(Some(
+            branches
+                .iter()
+                .map(|branch| (branch.name.to_string(), integrated.contains(&branch.head)))
+                .collect(),
+        ))
+    };
+    check()
+        .inspect_err(|err| tracing::warn!(?err, "Could not check if branches are integrated"))
+        .ok()
+        .flatten()
+}
+
+/// Wait for a value from `rx` while showing a spinner with `message` on `progress`, which is cleared
+/// once the value was received.
+fn recv_with_spinner<T>(
+    rx: std::sync::mpsc::Receiver<T>,
+    progress: &mut dyn std::fmt::Write,
+    message: &str,
+) -> anyhow::Result<T> {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    const FRAME_DURATION: std::time::Duration = std::time::Duration::from_millis(80);
+
+    let t = theme::get();
+    let mut frames = FRAMES.iter().cycle();
+    let mut spinning = false;
+    loop {
+        match rx.recv_timeout(FRAME_DURATION) {
+            Ok(value) => {
+                if spinning {
+                    write!(progress, "\r\x1b[2K")?;
+                }
+                return Ok(value);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                spinning = true;
+                let frame = frames.next().expect("frames cycle forever");
+                write!(progress, "\r{frame} {}", t.progress.paint(message))?;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("{message} stopped unexpectedly")
+            }
+        }
+    }
+}
+
+/// Summarize the CI checks of each branch in `branch_review_map` with an open review.
+///
+/// CI is optional information, so failures to get it just leave it out.
+fn ci_summaries(
+    ctx: &Context,
+    branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    cache_config: but_forge::CacheConfig,
+) -> HashMap<String, but_forge::CiSummary> {
+    let references = branch_review_map
+        .iter()
+        .filter(|(_, reviews)| reviews.iter().any(|review| review.is_open()))
+        .map(|(branch_name, _)| branch_name.clone())
+        .collect::<Vec<_>>();
+    if references.is_empty() {
+        return HashMap::new();
+    }
+    but_api::legacy::forge::list_ci_checks_for_refs(ctx, &references, Some(cache_config))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(reference, checks)| {
+            but_forge::CiSummary::from_checks(&checks).map(|summary| (reference, summary))
+        })
+        .collect()
+}
+
+/// Return the reviews of each branch in `branch_review_map` as label like " (#12, #34)" to put
+/// after the branch name, with the review numbers linking to the reviews if `hyperlinks` is `true`.
+///
+/// Review numbers are colored by the state of the review, which is spelled out like `#12(merged)`
+/// for all but open reviews if there are no colors. Open reviews are followed by the state
+/// of the CI checks of the branch in `ci_map`.
+fn review_labels(
+    branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    ci_map: &HashMap<String, but_forge::CiSummary>,
+    hyperlinks: bool,
+) -> HashMap<String, String> {
+    use super::json::ReviewState;
+
+    let t = theme::get();
+    let colorize = theme::should_colorize();
+    branch_review_map
+        .iter()
+        .map(|(branch_name, reviews)| {
+            let review_numbers = reviews
+                .iter()
+                .map(|r| {
+                    let state = ReviewState::of(r);
+                    let mut number = format!("{}{}", r.unit_symbol, r.number);
+                    if !colorize && state != ReviewState::Open {
+                        number = format!("{number}({})", state.as_str());
+                    }
+                    let number = if hyperlinks {
+                        crate::tui::text::hyperlink(&number, &r.html_url)
+                    } else {
+                        number
+                    };
+                    let style = match state {
+                        ReviewState::Open => t.pr_open,
+                        ReviewState::Draft => t.pr_draft,
+                        ReviewState::Merged => t.pr_merged,
+                        ReviewState::Closed => t.pr_closed,
+                    };
+                    let ci = r
+                        .is_open()
+                        .then(|| ci_map.get(branch_name))
+                        .flatten()
+                        .map(|ci| format!(" {}", ci_marker(ci)))
+                        .unwrap_or_default();
+                    format!("{}{ci}", style.paint(number))
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            (
+                branch_name.clone(),
+                t.info.paint(format!(" ({review_numbers})")).to_string(),
+            )
+        })
+        .collect()
+}
+
+/// A compact marker for the state of the CI checks summarized in `ci`.
+fn ci_marker(ci: &but_forge::CiSummary) -> String {
+    let sym = theme::get().sym();
+    match ci.state {
+        but_forge::CiSummaryState::Success => sym.success.to_string(),
+        but_forge::CiSummaryState::Failure => sym.error.to_string(),
+        but_forge::CiSummaryState::Pending => sym.dot.attention().to_string(),
+    }
+}
+
+/// Return how long ago the oldest of the reviews in `branch_review_map` was fetched from the forge,
+/// like "4m ago", or `None` if there are no reviews.
+fn reviews_synced_at(
+    branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+) -> Option<String> {
+    let synced_at = branch_review_map
+        .values()
+        .flatten()
+        .map(|review| review.last_sync_at)
+        .min()?
+        .and_local_timezone(chrono::Local)
+        .earliest()?;
+    Some(crate::utils::time::format_relative_time(
+        std::time::SystemTime::now(),
+        synced_at.timestamp(),
+    ))
+}
+
 fn get_reviews_json(
     branch_name: &str,
     branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    ci_map: &HashMap<String, but_forge::CiSummary>,
 ) -> Vec<super::json::ReviewOutput> {
     if let Some(reviews) = branch_review_map.get(branch_name) {
         reviews
@@ -382,6 +945,11 @@ fn get_reviews_json(
             .map(|r| super::json::ReviewOutput {
                 number: r.number as u64,
                 url: r.html_url.clone(),
+                state: super::json::ReviewState::of(r),
+                ci: r
+                    .is_open()
+                    .then(|| ci_map.get(branch_name).copied())
+                    .flatten(),
             })
             .collect()
     } else {
@@ -519,6 +1087,178 @@ fn calculate_commits_ahead(
     Ok(result)
 }
 
+/// The display columns the verbose table needs besides the branch name, including separators
+/// and the tree prefix and merge status in front of the name.
+const VERBOSE_RESERVED_WIDTH: usize = 55;
+/// Branch names are never truncated to fewer display columns than this.
+const MIN_BRANCH_NAME_WIDTH: usize = 16;
+/// Branch descriptions are truncated to this many display columns in the verbose table.
+const MAX_DESCRIPTION_WIDTH: usize = 40;
+
+/// Return the first line of the description of each of the local branches with `branch_names`,
+/// keyed by branch name, for those that have one.
+fn branch_descriptions(
+    ctx: &Context,
+    branch_names: impl IntoIterator<Item = String>,
+) -> anyhow::Result<HashMap<String, String>> {
+    let meta = ctx.meta()?;
+    let mut descriptions = HashMap::new();
+    for name in branch_names {
+        let Ok(ref_name) = gix::refs::Category::LocalBranch.to_full_name(name.as_str()) else {
+            continue;
+        };
+        let Some(description) = meta
+            .branch_opt(ref_name.as_ref())?
+            .and_then(|branch| branch.description.clone())
+        else {
+            continue;
+        };
+        if let Some(first_line) = description
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+        {
+            descriptions.insert(name, first_line.to_owned());
+        }
+    }
+    Ok(descriptions)
+}
+
+/// Return the markers to show after a branch name if it is `conflicted` or `integrated`.
+fn markers(conflicted: bool, integrated: bool) -> String {
+    let t = theme::get();
+    let mut markers = String::new();
+    if conflicted {
+        markers.push_str(&format!(" {}", t.error.paint("[conflicted]")));
+    }
+    if integrated {
+        markers.push_str(&format!(" {}", t.attention.paint("[integrated]")));
+    }
+    markers
+}
+
+/// Return the dimmed description of the branch `name` to show after it, if it has one.
+fn description_suffix(
+    descriptions: &HashMap<String, String>,
+    name: &str,
+    truncate: bool,
+) -> String {
+    let t = theme::get();
+    descriptions
+        .get(name)
+        .map(|description| {
+            let description = if truncate {
+                crate::tui::text::truncate_text(description.as_str(), MAX_DESCRIPTION_WIDTH)
+            } else {
+                description.as_str().into()
+            };
+            format!(" {}", t.hint.paint(description))
+        })
+        .unwrap_or_default()
+}
+
+/// Build the column headers of a branch table, adding the tip commit column if `verbose`.
+fn table_headers(verbose: bool) -> Vec<crate::tui::table::Cell> {
+    use crate::tui::table::Cell;
+
+    // BRANCH is marked no_truncate so the name is always fully visible, or shortened by us.
+    // AUTHOR is flexible and will shrink first when space is tight.
+    let mut headers = vec![
+        Cell::new("TYPE").with_width(7),
+        Cell::new("BRANCH").no_truncate(),
+        Cell::new("AHEAD").with_width(6),
+        Cell::new("DATE").with_width(10),
+        Cell::new("AUTHOR"),
+    ];
+    if verbose {
+        headers.insert(2, Cell::new("COMMIT").with_width(7));
+        // Verbose dates like "11 months ago" don't fit into the compact width.
+        headers[4] = Cell::new("DATE").no_truncate();
+    }
+    headers
+}
+
+/// Build a table row matching [`table_headers()`], which is verbose if `tip` is set.
+fn table_row(
+    type_str: String,
+    branch_str: String,
+    tip: Option<gix::ObjectId>,
+    ahead_str: String,
+    date_str: String,
+    author_str: String,
+) -> Vec<crate::tui::table::Cell> {
+    use crate::tui::table::Cell;
+
+    let t = theme::get();
+    let mut row = vec![
+        Cell::new(type_str),
+        Cell::new(branch_str),
+        Cell::new(ahead_str),
+        Cell::new(t.hint.paint(date_str).to_string()),
+        Cell::new(t.hint.paint(author_str).to_string()),
+    ];
+    if let Some(tip) = tip {
+        row.insert(
+            2,
+            Cell::new(t.commit_id.paint(tip.to_hex_with_len(7)).to_string()),
+        );
+    }
+    row
+}
+
+/// The tree drawn in front of the branch at `position` of a stack with `len` branches,
+/// from top to bottom.
+fn tree_prefix(position: usize, len: usize) -> &'static str {
+    if len == 1 {
+        "*"
+    } else if position == 0 {
+        "*-"
+    } else if position + 1 == len {
+        "└─"
+    } else {
+        "├─"
+    }
+}
+
+/// Shorten `name` in the middle to fit the terminal next to the verbose columns if `truncate`
+/// is set, so prefixes and suffixes like ticket numbers remain visible.
+fn display_branch_name(name: &str, truncate: bool) -> std::borrow::Cow<'_, str> {
+    if !truncate {
+        return name.into();
+    }
+    let max_width = crate::tui::text::terminal_width()
+        .saturating_sub(VERBOSE_RESERVED_WIDTH)
+        .max(MIN_BRANCH_NAME_WIDTH);
+    crate::tui::text::truncate_text_middle(name, max_width)
+}
+
+/// Format `timestamp_ms` relative to now, compact or `verbose` like "3 weeks ago".
+fn format_date(timestamp_ms: u128, verbose: bool) -> String {
+    if verbose {
+        crate::utils::time::format_relative_time_verbose(std::time::SystemTime::now(), timestamp_ms)
+    } else {
+        format_date_for_display(timestamp_ms)
+    }
+}
+
+/// Return the commit time and author of the tip of the applied `branch`.
+///
+/// The tip is usually the first of the commits already known from the stack details,
+/// so it only has to be looked up if the branch has no commits of its own.
+fn applied_tip_info(
+    repo: &gix::Repository,
+    branch: &crate::legacy::workspace::HeadInfoBranch,
+) -> (u128, Option<String>, Option<String>) {
+    match branch.commits.first() {
+        Some(commit) if commit.id == branch.tip => (
+            commit.committed_at.max(0) as u128,
+            Some(commit.author.name.clone()),
+            Some(commit.author.email.clone()),
+        ),
+        _ => applied_head_commit_info(repo, branch.tip),
+    }
+}
+
 fn format_date_for_display(timestamp_ms: u128) -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -549,16 +1289,21 @@ fn format_date_for_display(timestamp_ms: u128) -> String {
     }
 }
 
+#[expect(clippy::too_many_arguments)]
 fn print_applied_branches_table(
     applied_stacks: &[HeadInfoStack],
-    branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    filter: &BranchFilter,
+    review_labels: &HashMap<String, String>,
+    descriptions: &HashMap<String, String>,
     ctx: &Context,
     commits_ahead_map: Option<&HashMap<String, usize>>,
     merge_status_map: Option<&HashMap<String, bool>>,
+    assigned_files: Option<&AssignedFiles>,
     allow_truncation: bool,
+    verbose: bool,
     out: &mut dyn std::fmt::Write,
 ) -> Result<(), anyhow::Error> {
-    use crate::tui::{Table, table::Cell};
+    use crate::tui::Table;
 
     let t = theme::get();
 
@@ -569,33 +1314,19 @@ fn print_applied_branches_table(
     // Open repo to get commit information
     let repo = &*ctx.repo.get()?;
 
-    // Define column headers with fixed widths
-    // BRANCH is marked no_truncate so the name is always fully visible.
-    // AUTHOR is flexible and will shrink first when space is tight.
-    let headers = vec![
-        Cell::new("TYPE").with_width(7),
-        Cell::new("BRANCH").no_truncate(),
-        Cell::new("AHEAD").with_width(6),
-        Cell::new("DATE").with_width(10),
-        Cell::new("AUTHOR"),
-    ];
-
-    let mut table = Table::new(headers).with_truncation(allow_truncation);
+    let mut table = Table::new(table_headers(verbose)).with_truncation(allow_truncation);
 
     for stack in applied_stacks {
-        let first_branch = stack.branches.first();
-        let last_branch = stack.branches.last();
-        for branch in &stack.branches {
-            let is_single_branch = stack.branches.len() == 1;
-
+        for (position, branch) in stack.branches.iter().enumerate() {
             // Get commit information
-            let (timestamp_ms, author_name, author_email) =
-                applied_head_commit_info(repo, branch.tip);
+            let (timestamp_ms, author_name, author_email) = applied_tip_info(repo, branch);
+            // Applied branches are always shown, but those not matching the filter are dimmed.
+            let is_match = filter.matches(&branch.name, author_name.clone(), author_email.clone());
             let author_str = author_name
                 .or(author_email)
                 .unwrap_or_else(|| "Unknown".to_string());
             let date_str = if timestamp_ms > 0 {
-                format_date_for_display(timestamp_ms)
+                format_date(timestamp_ms, verbose)
             } else {
                 "unknown".to_string()
             };
@@ -620,64 +1351,48 @@ fn print_applied_branches_table(
                 String::new()
             };
 
-            let painted_branch_name = t.local_branch.paint(&branch.name);
-
-            // Branch name with tree prefix and merge status
-            let branch_with_prefix = if is_single_branch {
-                format!(
-                    "{}{}{}",
-                    merge_status_str,
-                    t.hint.paint("*"),
-                    painted_branch_name
-                )
-            } else if let (Some(first), Some(last)) = (first_branch, last_branch) {
-                if branch.name == first.name {
-                    format!(
-                        "{}{}{}",
-                        merge_status_str,
-                        t.hint.paint("*-"),
-                        painted_branch_name
-                    )
-                } else if branch.name == last.name {
-                    format!(
-                        "{}{}{}",
-                        merge_status_str,
-                        t.hint.paint("└─"),
-                        painted_branch_name
-                    )
-                } else {
-                    format!(
-                        "{}{}{}",
-                        merge_status_str,
-                        t.hint.paint("├─"),
-                        painted_branch_name
-                    )
-                }
+            let display_name = display_branch_name(&branch.name, verbose && allow_truncation);
+            let painted_branch_name = if is_match {
+                t.local_branch.paint(display_name)
             } else {
-                format!("{merge_status_str}{painted_branch_name}")
+                t.hint.paint(display_name)
             };
 
-            // Get PR/review info
-            let reviews_str = if let Some(reviews) = branch_review_map.get(&branch.name) {
-                let review_numbers = reviews
-                    .iter()
-                    .map(|r| format!("{}{}", r.unit_symbol, r.number))
-                    .collect::<Vec<String>>()
-                    .join(", ");
-                t.info.paint(format!(" ({review_numbers})"))
-            } else {
-                t.default.paint("")
-            };
+            // Branch name with tree prefix and merge status
+            let branch_with_prefix = format!(
+                "{}{}{}",
+                merge_status_str,
+                t.hint.paint(tree_prefix(position, stack.branches.len())),
+                painted_branch_name
+            );
 
-            let branch_str = format!("{branch_with_prefix}{reviews_str}");
+            // Get PR/review info
+            let reviews_str = review_labels
+                .get(&branch.name)
+                .map(String::as_str)
+                .unwrap_or_default();
 
-            table.add_row(vec![
-                Cell::new(type_str),
-                Cell::new(branch_str),
-                Cell::new(ahead_str.to_string()),
-                Cell::new(t.hint.paint(date_str).to_string()),
-                Cell::new(t.hint.paint(author_str).to_string()),
-            ]);
+            let markers_str = markers(is_conflicted(branch), is_integrated(branch));
+            // The changes belong to the stack as a whole, so they are marked at its top branch.
+            let assigned_str = match assigned_files.map(|files| files.of(stack.id)) {
+                Some(count) if count > 0 && position == 0 => {
+                    format!(" {}", t.hint.paint(format!("●{count}")))
+                }
+                _ => String::new(),
+            };
+            let description_str = description_suffix(descriptions, &branch.name, allow_truncation);
+            let branch_str = format!(
+                "{branch_with_prefix}{reviews_str}{markers_str}{assigned_str}{description_str}"
+            );
+
+            table.add_row(table_row(
+                type_str,
+                branch_str,
+                verbose.then_some(branch.tip),
+                ahead_str.to_string(),
+                date_str,
+                author_str,
+            ));
         }
     }
 
@@ -685,33 +1400,27 @@ fn print_applied_branches_table(
     Ok(())
 }
 
+#[expect(clippy::too_many_arguments)]
 fn print_branches_table(
     branches: &[gitbutler_branch_actions::BranchListing],
-    branch_review_map: &HashMap<String, Vec<but_forge::ForgeReview>>,
+    placements: &HashMap<String, StackPlacement>,
+    review_labels: &HashMap<String, String>,
+    descriptions: &HashMap<String, String>,
     commits_ahead_map: Option<&HashMap<String, usize>>,
     merge_status_map: Option<&HashMap<String, bool>>,
+    integration_map: Option<&HashMap<String, bool>>,
     allow_truncation: bool,
+    verbose: bool,
     out: &mut dyn std::fmt::Write,
 ) -> Result<(), anyhow::Error> {
-    use crate::tui::{Table, table::Cell};
+    use crate::tui::Table;
     let t = theme::get();
 
     if branches.is_empty() {
         return Ok(());
     }
 
-    // Define column headers with fixed widths
-    // BRANCH is marked no_truncate so the name is always fully visible.
-    // AUTHOR is flexible and will shrink first when space is tight.
-    let headers = vec![
-        Cell::new("TYPE").with_width(7),
-        Cell::new("BRANCH").no_truncate(),
-        Cell::new("AHEAD").with_width(6),
-        Cell::new("DATE").with_width(10),
-        Cell::new("AUTHOR"),
-    ];
-
-    let mut table = Table::new(headers).with_truncation(allow_truncation);
+    let mut table = Table::new(table_headers(verbose)).with_truncation(allow_truncation);
 
     for branch in branches {
         // Ahead column
@@ -732,7 +1441,7 @@ fn print_branches_table(
         };
 
         // Date column
-        let date_str = format_date_for_display(branch.updated_at);
+        let date_str = format_date(branch.updated_at, verbose);
 
         // Author column
         let author_str = branch
@@ -744,39 +1453,198 @@ fn print_branches_table(
             .unwrap_or_else(|| "Unknown".to_string());
 
         // Branch name with PR info and merge status
-        let reviews_str = if let Some(reviews) = branch_review_map.get(&branch.name.to_string()) {
-            let review_numbers = reviews
-                .iter()
-                .map(|r| format!("{}{}", r.unit_symbol, r.number))
-                .collect::<Vec<String>>()
-                .join(", ");
-            t.info.paint(format!(" ({review_numbers})"))
-        } else {
-            t.default.paint("")
-        };
+        let reviews_str = review_labels
+            .get(&branch.name.to_string())
+            .map(String::as_str)
+            .unwrap_or_default();
 
-        let (type_str, branch_name) = if branch.has_local {
-            (
-                t.default.paint("local"),
-                t.local_branch.paint(branch.name.to_string()),
-            )
+        let display_name =
+            display_branch_name(&branch.name.to_string(), verbose && allow_truncation).into_owned();
+        let type_str = if branch.has_local {
+            t.default.paint("local")
         } else {
-            (
-                t.hint.paint("remote"),
-                t.remote_branch.paint(branch.name.to_string()),
-            )
+            t.hint.paint("remote")
         };
-        let branch_str = format!("{merge_status_str}{branch_name}{reviews_str}");
-
-        table.add_row(vec![
-            Cell::new(type_str.to_string()),
-            Cell::new(branch_str),
-            Cell::new(ahead_str),
-            Cell::new(t.hint.paint(date_str).to_string()),
-            Cell::new(t.hint.paint(author_str).to_string()),
-        ]);
+        let branch_name = match placements.get(&branch.name.to_string()) {
+            // Members of unapplied stacks are drawn like applied stacks, but without their color.
+            Some(placement) => format!(
+                "{}{}{}",
+                t.hint.paint(tree_prefix(
+                    placement.position,
+                    placement.stack.branches.len()
+                )),
+                t.default.paint(display_name),
+                if placement.position == 0 {
+                    t.hint.paint(" (unapplied)").to_string()
+                } else {
+                    String::new()
+                }
+            ),
+            None if branch.has_local => t.local_branch.paint(display_name).to_string(),
+            None => t.remote_branch.paint(display_name).to_string(),
+        };
+        let is_integrated = integration_map
+            .and_then(|map| map.get(&branch.name.to_string()))
+            .copied()
+            .unwrap_or_default();
+        let markers_str = markers(false, is_integrated);
+        let description_str =
+            description_suffix(descriptions, &branch.name.to_string(), allow_truncation);
+        let branch_str =
+            format!("{merge_status_str}{branch_name}{reviews_str}{markers_str}{description_str}");
+
+        table.add_row(table_row(
+            type_str.to_string(),
+            branch_str,
+            verbose.then_some(branch.head),
+            ahead_str,
+            date_str,
+            author_str,
+        ));
     }
 
     table.render(out)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use but_testsupport::Sandbox;
+
+    use super::{BranchFilter, ReviewQuery, list_with_review_query, recv_with_spinner};
+    use crate::{
+        args::{HyperlinkMode, OutputFormat},
+        utils::OutputChannel,
+    };
+
+    /// Return the JSON output of listing the branches in `ctx` with reviews from `review_query`.
+    fn list_json(
+        ctx: &mut but_ctx::Context,
+        review_query: Option<ReviewQuery>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut out = OutputChannel::new(OutputFormat::Json);
+        out.start_json_buffering();
+        list_with_review_query(
+            ctx,
+            false,
+            false,
+            false,
+            false,
+            review_query,
+            &BranchFilter::default(),
+            &mut out,
+            false,
+            false,
+            false,
+            None,
+            true,
+            false,
+            HyperlinkMode::Never,
+        )?;
+        Ok(out.take_json_buffer().expect("buffering was started"))
+    }
+
+    fn forge_review(source_branch: &str) -> but_forge::ForgeReview {
+        but_forge::ForgeReview {
+            html_url: "https://example.com/review/1".to_string(),
+            number: 1,
+            title: "Review".to_string(),
+            body: None,
+            author: None,
+            labels: vec![],
+            draft: false,
+            source_branch: source_branch.to_string(),
+            target_branch: "main".to_string(),
+            sha: "abc123".to_string(),
+            integration_commit_shas: vec![],
+            created_at: None,
+            modified_at: None,
+            merged_at: None,
+            closed_at: None,
+            repository_ssh_url: None,
+            repository_https_url: None,
+            repo_owner: None,
+            head_repo_is_fork: false,
+            reviewers: vec![],
+            unit_symbol: "#".to_string(),
+            last_sync_at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    #[test]
+    fn local_listing_is_unaffected_by_a_slow_review_query() -> anyhow::Result<()> {
+        let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+        env.setup_metadata(&["A"]);
+        let mut ctx = env.context();
+        let without_reviews = list_json(&mut ctx, None)?;
+
+        let slow_query: ReviewQuery = Box::new(|_ctx| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            let review_map = HashMap::from([("A".to_string(), vec![forge_review("A")])]);
+            Ok((review_map, HashMap::new()))
+        });
+        let mut with_reviews = list_json(&mut ctx, Some(slow_query))?;
+
+        let reviews = &mut with_reviews["appliedStacks"][0]["heads"][0]["reviews"];
+        assert_eq!(
+            *reviews,
+            serde_json::json!([{"number": 1, "url": "https://example.com/review/1", "state": "open"}]),
+            "the reviews are merged into the listing once the query is done"
+        );
+        *reviews = serde_json::json!([]);
+        assert_eq!(
+            with_reviews, without_reviews,
+            "everything else is the same as without reviews"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn spinner_is_shown_while_waiting_and_cleared_afterwards() -> anyhow::Result<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            tx.send(42).ok();
+        });
+
+        let mut progress = String::new();
+        assert_eq!(
+            recv_with_spinner(rx, &mut progress, "Fetching reviews")?,
+            42
+        );
+        assert!(
+            progress.starts_with("\r⠋ Fetching reviews"),
+            "the spinner is shown while waiting: {progress:?}"
+        );
+        assert!(
+            progress.ends_with("\r\x1b[2K"),
+            "and cleared once done: {progress:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn no_spinner_if_value_is_ready() -> anyhow::Result<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send("ready")?;
+
+        let mut progress = String::new();
+        assert_eq!(
+            recv_with_spinner(rx, &mut progress, "Fetching reviews")?,
+            "ready"
+        );
+        assert_eq!(progress, "", "nothing to clear either");
+        Ok(())
+    }
+
+    #[test]
+    fn error_if_sender_is_gone() {
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        drop(tx);
+
+        let err = recv_with_spinner(rx, &mut String::new(), "Fetching reviews").unwrap_err();
+        assert_eq!(err.to_string(), "Fetching reviews stopped unexpectedly");
+    }
+}
@@ -1,55 +1,242 @@
 use anyhow::Context as _;
-use but_api::json::HexHash;
+use bstr::ByteSlice as _;
 use but_core::DryRun;
+use but_workspace::ref_info::LocalCommitRelation;
 use gitbutler_oplog::entry::{OperationKind, SnapshotDetails};
 
 use crate::{
     CliResult, IdMap,
-    args::atoms::{BranchArg, BranchOrCommit, CliIdArg, Purpose, ResolvedCliIdArg},
+    args::atoms::{BranchArg, BranchOrCommit, CliIdArg, Priority, Purpose, ResolvedCliIdArg},
+    bad_input,
+    legacy::workspace::HeadInfoStack,
     theme::{self, Paint},
     utils::OutputChannel,
 };
 
+mod describe;
+pub use describe::{describe, set_description};
 mod json;
 mod list;
+pub use list::{BranchFilter, ReviewSource};
 mod show;
 
+/// A branch to delete, either as part of the workspace or as a plain local reference.
+enum BranchToDelete {
+    Workspace(BranchArg),
+    Local(gix::refs::FullName),
+}
+
 pub fn delete(
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
     branch_arg: CliIdArg,
+    remote: bool,
+    force: bool,
 ) -> CliResult<()> {
     let t = theme::get();
 
-    let branch_arg = {
+    let branch = {
         let guard = ctx.exclusive_worktree_access();
         let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
         let repo = ctx.repo.get()?;
-        branch_arg.resolve_branch_in_workspace(&repo, &id_map)?
+        match branch_arg.try_resolve_branch(&repo, &id_map)? {
+            Some(branch) => BranchToDelete::Workspace(branch),
+            None => {
+                BranchToDelete::Local(branch_arg.resolve_existing_local_branch(&repo, &id_map)?)
+            }
+        }
     };
 
-    let head_info = but_api::legacy::workspace::head_info(ctx)?;
-    let segment = branch_arg.resolve_segment(&head_info)?;
+    let (ref_name, remote_tracking_ref_name) = match branch {
+        BranchToDelete::Workspace(branch) => {
+            let head_info = but_api::legacy::workspace::head_info(ctx)?;
+            let segment = branch.resolve_segment(&head_info)?;
+            let ref_name = segment
+                .ref_info
+                .as_ref()
+                .context("segment missing ref_info")?
+                .ref_name
+                .clone();
 
-    let ref_name = &segment
-        .ref_info
-        .context("segment missing ref_info")?
-        .ref_name;
+            let unpushed = segment
+                .commits
+                .iter()
+                .filter(|commit| match commit.relation {
+                    LocalCommitRelation::LocalOnly => true,
+                    LocalCommitRelation::LocalAndRemote(remote_id) => remote_id != commit.inner.id,
+                    LocalCommitRelation::Integrated(_) => false,
+                })
+                .count();
+            ensure_no_unpushed_commits(ref_name.as_ref(), unpushed, force)?;
+            ensure_remote_branch_if_requested(
+                ref_name.as_ref(),
+                segment.remote_tracking_ref_name.as_ref(),
+                remote,
+            )?;
+            // Delete on the remote first, so a failure there leaves the local branch in place.
+            let remote_tracking_ref_name = segment.remote_tracking_ref_name.filter(|_| remote);
+            if let Some(remote_tracking_ref_name) = &remote_tracking_ref_name {
+                delete_remote_branch(ctx, remote_tracking_ref_name.as_ref())?;
+            }
+
+            let mut meta = ctx.meta()?;
+            let snapshot_details = SnapshotDetails::new(OperationKind::DeleteBranch);
+            but_transaction::with_transaction(
+                ctx,
+                &mut meta,
+                snapshot_details,
+                DryRun::No,
+                |mut tx| {
+                    tx.remove_reference(ref_name.as_ref())?;
+                    if !segment.commits.is_empty() {
+                        tx.discard_commits(segment.commits.iter().map(|commit| commit.id))?;
+                    }
+                    Ok(())
+                },
+            )?;
+            (ref_name, remote_tracking_ref_name)
+        }
+        BranchToDelete::Local(ref_name) => {
+            let repo = ctx.repo.get()?;
+            let reference = repo.find_reference(ref_name.as_ref())?;
+            let tip = reference.id().detach();
+            let remote_tips = repo
+                .references()?
+                .remote_branches()?
+                .filter_map(Result::ok)
+                .filter_map(|mut remote_ref| remote_ref.peel_to_id().ok())
+                .map(|id| id.detach())
+                .collect::<Vec<_>>();
+            let unpushed = repo.rev_walk([tip]).with_hidden(remote_tips).all()?.count();
+            ensure_no_unpushed_commits(ref_name.as_ref(), unpushed, force)?;
 
-    let mut meta = ctx.meta()?;
-    let snapshot_details = SnapshotDetails::new(OperationKind::DeleteBranch);
-    but_transaction::with_transaction(ctx, &mut meta, snapshot_details, DryRun::No, |mut tx| {
-        tx.remove_reference(ref_name.as_ref())?;
-        if !segment.commits.is_empty() {
-            tx.discard_commits(segment.commits.iter().map(|commit| commit.id))?;
+            let remote_tracking_ref_name = repo
+                .branch_remote_tracking_ref_name(ref_name.as_ref(), gix::remote::Direction::Push)
+                .transpose()?
+                .map(|name| name.into_owned());
+            ensure_remote_branch_if_requested(
+                ref_name.as_ref(),
+                remote_tracking_ref_name.as_ref(),
+                remote,
+            )?;
+            let safe_delete = but_core::branch::SafeDelete::new(&repo)?;
+            if let Some(worktree_dirs) = safe_delete.worktree_dirs_with_ref(&reference) {
+                return Err(bad_input(format!(
+                    "Branch '{}' is checked out in {}",
+                    ref_name.shorten(),
+                    worktree_dirs
+                        .iter()
+                        .map(|dir| format!("'{}'", dir.display()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .into());
+            }
+            // Delete on the remote first, so a failure there leaves the local branch in place.
+            let remote_tracking_ref_name = remote_tracking_ref_name.filter(|_| remote);
+            if let Some(remote_tracking_ref_name) = &remote_tracking_ref_name {
+                delete_remote_branch(ctx, remote_tracking_ref_name.as_ref())?;
+            }
+            let outcome = safe_delete.delete_reference(&reference)?;
+            debug_assert!(
+                outcome.was_deleted(),
+                "checked out branches were rejected above"
+            );
+            (ref_name, remote_tracking_ref_name)
         }
-        Ok(())
-    })?;
+    };
+
+    let mut deleted_refs = vec![ref_name.clone()];
+    deleted_refs.extend(remote_tracking_ref_name);
 
     if let Some(out) = out.for_human() {
-        writeln!(out, "Deleted branch {}", t.local_branch.paint(&branch_arg))?;
+        writeln!(
+            out,
+            "Deleted branch {}",
+            t.local_branch.paint(ref_name.shorten().to_string())
+        )?;
+        for deleted_ref in &deleted_refs[1..] {
+            writeln!(
+                out,
+                "Deleted remote branch {}",
+                t.remote_branch.paint(deleted_ref.shorten().to_string())
+            )?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(json::BranchDeleteOutput {
+            deleted_refs: deleted_refs
+                .iter()
+                .map(|deleted_ref| deleted_ref.to_string())
+                .collect(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Refuse to delete `ref_name` if it has `unpushed` commits, unless `force` is set.
+fn ensure_no_unpushed_commits(
+    ref_name: &gix::refs::FullNameRef,
+    unpushed: usize,
+    force: bool,
+) -> CliResult<()> {
+    if unpushed == 0 || force {
+        return Ok(());
+    }
+    Err(bad_input(format!(
+        "Branch '{}' has {unpushed} unpushed commit{}",
+        ref_name.shorten(),
+        if unpushed == 1 { "" } else { "s" }
+    ))
+    .hint("use `--force` to delete it anyway")
+    .into())
+}
+
+/// With `remote` set, refuse to delete `ref_name` if there is no remote branch to delete along with it.
+fn ensure_remote_branch_if_requested(
+    ref_name: &gix::refs::FullNameRef,
+    remote_tracking_ref_name: Option<&gix::refs::FullName>,
+    remote: bool,
+) -> CliResult<()> {
+    if !remote || remote_tracking_ref_name.is_some() {
+        return Ok(());
     }
+    Err(bad_input(format!(
+        "Branch '{}' has no remote branch to delete",
+        ref_name.shorten()
+    ))
+    .hint("run without `--remote` to delete only the local branch")
+    .into())
+}
 
+/// Delete the branch that `remote_tracking_ref_name` tracks on its remote, along with the
+/// remote tracking reference itself.
+fn delete_remote_branch(
+    ctx: &but_ctx::Context,
+    remote_tracking_ref_name: &gix::refs::FullNameRef,
+) -> anyhow::Result<()> {
+    let repo = ctx.repo.get()?;
+    let (remote_name, short_name) = but_core::extract_remote_name_and_short_name(
+        remote_tracking_ref_name,
+        &repo.remote_names(),
+    )
+    .with_context(|| format!("Failed to determine the remote of '{remote_tracking_ref_name}'"))?;
+    let workdir = repo.workdir().context("Repository has no workdir")?;
+
+    let output = std::process::Command::new(gix::path::env::exe_invocation())
+        .arg("-C")
+        .arg(workdir)
+        .args(["push", "--quiet", "--delete", &remote_name])
+        .arg(short_name.to_str_lossy().as_ref())
+        .output()
+        .context("Failed to execute git push")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to delete remote branch '{}': {}",
+            remote_tracking_ref_name.shorten(),
+            output.stderr.to_str_lossy().trim()
+        );
+    }
     Ok(())
 }
 
@@ -58,6 +245,7 @@ pub fn new(
     out: &mut OutputChannel,
     branch_name_arg: Option<BranchArg>,
     anchor_arg: Option<CliIdArg>,
+    at_arg: Option<CliIdArg>,
 ) -> CliResult<()> {
     let mut guard = ctx.exclusive_worktree_access();
 
@@ -75,10 +263,13 @@ pub fn new(
 
     let resolved_anchor = {
         let repo = ctx.repo.get()?;
-        anchor_arg
-            .clone()
-            .map(|anchor| anchor.resolve_in_workspace(&repo, &id_map, Purpose::Anchor, None))
-            .transpose()?
+        match at_arg.as_ref() {
+            Some(at) => Some(resolve_commit_in_applied_stack(&repo, &id_map, at)?),
+            None => anchor_arg
+                .as_ref()
+                .map(|anchor| resolve_anchor(&repo, &id_map, anchor))
+                .transpose()?,
+        }
     };
 
     if resolved_anchor.is_none()
@@ -103,28 +294,13 @@ pub fn new(
             },
             guard.write_permission(),
         )?;
-        write_new_branch_output(out, &branch_name, None, anchor_arg)?;
+        write_new_branch_output(out, &branch_name, None, None, anchor_arg, None)?;
         return Ok(());
     }
 
     let anchor = resolved_anchor
         .clone()
-        .map(|anchor| -> CliResult<_> {
-            match anchor.into_branch_or_commit()? {
-                BranchOrCommit::Commit(commit) => {
-                    Ok(but_api::legacy::stack::create_reference::Anchor::AtCommit {
-                        commit_id: HexHash(commit),
-                        position: but_workspace::branch::create_reference::Position::Above,
-                    })
-                }
-                BranchOrCommit::Branch(BranchArg(name)) => Ok(
-                    but_api::legacy::stack::create_reference::Anchor::AtSegment {
-                        short_name: name.clone(),
-                        position: but_workspace::branch::create_reference::Position::Above,
-                    },
-                ),
-            }
-        })
+        .map(create_reference_anchor)
         .transpose()?;
 
     but_api::legacy::stack::create_reference_with_perm(
@@ -136,8 +312,150 @@ pub fn new(
         guard.write_permission(),
     )?;
 
-    write_new_branch_output(out, &branch_name, resolved_anchor.as_ref(), anchor_arg)?;
+    let stack = if resolved_anchor.is_some() {
+        crate::legacy::workspace::applied_stacks(ctx)?
+            .into_iter()
+            .find(|stack| stack.contains_branch(&branch_name))
+    } else {
+        None
+    };
+    write_new_branch_output(
+        out,
+        &branch_name,
+        resolved_anchor.as_ref(),
+        stack.as_ref(),
+        anchor_arg,
+        at_arg,
+    )?;
+
+    Ok(())
+}
+
+/// Resolve `at` to a commit of an applied stack, or explain where the commit was found instead.
+fn resolve_commit_in_applied_stack(
+    repo: &gix::Repository,
+    id_map: &IdMap,
+    at: &CliIdArg,
+) -> CliResult<ResolvedCliIdArg> {
+    match at.try_resolve(repo, id_map, Purpose::Commit, Some(Priority::Commit))? {
+        Some(commit @ ResolvedCliIdArg::Commit(..)) => return Ok(commit),
+        Some(other) => {
+            return Err(bad_input(format!(
+                "Expected a commit, got {}",
+                other.kind_for_humans()
+            ))
+            .into());
+        }
+        None => {}
+    }
+
+    let Some(commit_id) = repo
+        .rev_parse_single(at.0.as_str())
+        .ok()
+        .and_then(|id| id.object().ok()?.peel_to_commit().ok())
+        .map(|commit| commit.id)
+    else {
+        return Err(bad_input(format!("Could not find commit: '{at}'"))
+            .hint(CliIdArg::TARGET_MISSING_HINT)
+            .into());
+    };
+
+    let mut containing_branches = Vec::new();
+    for reference in repo.references()?.local_branches()?.filter_map(Result::ok) {
+        let Some(tip) = reference.try_id() else {
+            continue;
+        };
+        if repo
+            .merge_base(commit_id, tip.detach())
+            .is_ok_and(|base| base.detach() == commit_id)
+        {
+            containing_branches.push(reference.name().shorten().to_string());
+        }
+    }
+    let short_id = commit_id.to_hex_with_len(7);
+    match containing_branches.first() {
+        Some(first) => Err(bad_input(format!(
+            "Commit {short_id} isn't part of an applied stack, it was found in {}",
+            containing_branches
+                .iter()
+                .map(|name| format!("'{name}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .hint(format!("apply the branch first with `but apply {first}`"))
+        .into()),
+        None => Err(bad_input(format!(
+            "Commit {short_id} isn't part of an applied stack or any local branch"
+        ))
+        .into()),
+    }
+}
+
+/// Resolve `anchor` to a branch or commit in the workspace to stack a new branch on,
+/// explaining why branches that aren't applied can't be used.
+pub(crate) fn resolve_anchor(
+    repo: &gix::Repository,
+    id_map: &IdMap,
+    anchor: &CliIdArg,
+) -> CliResult<ResolvedCliIdArg> {
+    if let Some(resolved) = anchor.try_resolve(repo, id_map, Purpose::Anchor, None)? {
+        return Ok(resolved);
+    }
+    if repo
+        .try_find_reference(format!("refs/heads/{anchor}").as_str())
+        .ok()
+        .flatten()
+        .is_some()
+    {
+        return Err(bad_input(format!(
+            "Cannot stack on branch '{anchor}' as it isn't applied to the workspace"
+        ))
+        .hint(format!("apply it first with `but apply {anchor}`"))
+        .into());
+    }
+    anchor.resolve_in_workspace(repo, id_map, Purpose::Anchor, None)
+}
+
+/// Turn a resolved anchor into the position a new reference is created at, right above it.
+pub(crate) fn create_reference_anchor(
+    anchor: ResolvedCliIdArg,
+) -> CliResult<but_api::legacy::stack::create_reference::Anchor> {
+    Ok(match anchor.into_branch_or_commit()? {
+        BranchOrCommit::Commit(commit) => {
+            but_api::legacy::stack::create_reference::Anchor::AtCommit {
+                commit_id: commit.into(),
+                position: but_workspace::branch::create_reference::Position::Above,
+            }
+        }
+        BranchOrCommit::Branch(BranchArg(name)) => {
+            but_api::legacy::stack::create_reference::Anchor::AtSegment {
+                short_name: name,
+                position: but_workspace::branch::create_reference::Position::Above,
+            }
+        }
+    })
+}
 
+/// Write the branches of `stack` from top to bottom, highlighting `new_branch`.
+pub(crate) fn write_stack_structure(
+    out: &mut dyn crate::utils::WriteWithUtils,
+    stack: &HeadInfoStack,
+    new_branch: &str,
+) -> std::fmt::Result {
+    let t = theme::get();
+    writeln!(out, "Stack (top to bottom):")?;
+    for name in stack.branch_names() {
+        if name == new_branch {
+            writeln!(
+                out,
+                "  {} {}",
+                t.local_branch.paint(name),
+                t.hint.paint("(new)")
+            )?;
+        } else {
+            writeln!(out, "  {name}")?;
+        }
+    }
     Ok(())
 }
 
@@ -145,18 +463,24 @@ fn write_new_branch_output(
     out: &mut OutputChannel,
     branch_name: &str,
     resolved_anchor: Option<&ResolvedCliIdArg>,
+    stack: Option<&HeadInfoStack>,
     anchor_arg: Option<CliIdArg>,
+    at_arg: Option<CliIdArg>,
 ) -> CliResult<()> {
     let t = theme::get();
     if let Some(out) = out.for_human() {
         if let Some(resolved_anchor) = resolved_anchor {
             writeln!(
                 out,
-                "{} Created branch {} stacked on {}",
+                "{} Created branch {} {} {}",
                 t.sym().success,
                 t.local_branch.paint(branch_name),
+                if at_arg.is_some() { "at" } else { "stacked on" },
                 t.hint.paint(format!("{resolved_anchor}")),
             )?;
+            if let Some(stack) = stack {
+                write_stack_structure(out, stack, branch_name)?;
+            }
         } else {
             writeln!(
                 out,
@@ -171,6 +495,7 @@ fn write_new_branch_output(
         let value = json::BranchNewOutput {
             branch: branch_name.to_owned(),
             anchor: anchor_arg,
+            at: at_arg,
         };
         out.write_value(value)?;
     }
@@ -194,21 +519,46 @@ pub fn show_branches(
 pub fn list_branches(
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
-    filter: Option<String>,
+    filter: BranchFilter,
     local: bool,
     remote: bool,
     all: bool,
     no_ahead: bool,
     review: bool,
+    refresh: bool,
     no_check: bool,
     empty: bool,
+    verbose: bool,
+    limit: Option<usize>,
+    fast: bool,
+    no_worktree: bool,
+    hyperlinks: crate::args::HyperlinkMode,
 ) -> Result<(), anyhow::Error> {
     let ahead = !no_ahead;
     // Invert the flag
     let check = !no_check;
     // Invert the flag
+    let reviews = if refresh {
+        Some(list::ReviewSource::Forge)
+    } else {
+        review.then_some(list::ReviewSource::Cache)
+    };
     list::list(
-        ctx, local, remote, all, ahead, review, filter, out, check, empty,
+        ctx,
+        local,
+        remote,
+        all,
+        ahead,
+        reviews,
+        &filter,
+        out,
+        check,
+        empty,
+        verbose,
+        limit,
+        fast,
+        !no_worktree,
+        hyperlinks,
     )?;
     Ok(())
 }
@@ -218,6 +568,21 @@ pub fn handle_no_subcommand(
     out: &mut OutputChannel,
 ) -> Result<(), anyhow::Error> {
     list_branches(
-        ctx, out, None, false, false, false, false, false, false, false,
+        ctx,
+        out,
+        BranchFilter::default(),
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        None,
+        false,
+        false,
+        crate::args::HyperlinkMode::default(),
     )
 }
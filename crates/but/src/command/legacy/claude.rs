@@ -0,0 +1,381 @@
+//! Claude Code hooks that commit what a session changed to a branch of its own once it ends.
+//!
+//! `but claude install-hooks` registers `but claude post-session` as `SessionEnd` hook, which Claude
+//! Code runs when a session ends. The branch of each session is remembered in the GitButler data of
+//! the worktree, so the changes of a resumed session end up on the same branch.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    io::{IsTerminal as _, Read as _},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use bstr::BString;
+use but_ctx::Context;
+use but_settings::app_settings::Claude;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::{ShowDiffInEditor, ai, commit};
+use crate::{
+    CliResult,
+    args::atoms::CliIdArg,
+    theme::{self, Paint},
+    utils::{OutputChannel, notifications},
+};
+
+/// The command Claude Code runs when a session ends.
+const POST_SESSION_COMMAND: &str = "but claude post-session";
+/// The hook event `POST_SESSION_COMMAND` runs on.
+const POST_SESSION_HOOK_EVENT: &str = "SessionEnd";
+/// The hook event `POST_SESSION_COMMAND` used to run on, after each response.
+const OUTDATED_POST_SESSION_HOOK_EVENT: &str = "Stop";
+/// The most bytes of prompts of a session that are passed on to summarize it.
+const MAX_SESSION_PROMPTS_BYTES: usize = 4 * 1024;
+/// How long Claude Code may take to write the commit message before a default message is used,
+/// so the session doesn't hang while it ends.
+const COMMIT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(60);
+/// The file in the GitButler data of the worktree that maps Claude Code sessions to their branch.
+const SESSION_BRANCHES_FILE_NAME: &str = "claude-session-branches.json";
+
+/// Return `true` if changes of Claude Code sessions should be committed once they finish.
+pub(crate) fn auto_commit_enabled(settings: &Claude) -> bool {
+    !settings.executable.trim().is_empty() && settings.auto_commit_after_completion
+}
+
+/// What Claude Code passes to hooks on stdin, of which only what's needed is kept.
+#[derive(Debug, Default, Deserialize)]
+struct HookInput {
+    session_id: Option<String>,
+    transcript_path: Option<PathBuf>,
+}
+
+impl HookInput {
+    /// Read the input of the hook from stdin, or assume there is none if it's a terminal or can't
+    /// be understood.
+    fn from_stdin() -> Self {
+        let mut stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            return Self::default();
+        }
+        let mut input = String::new();
+        if stdin.read_to_string(&mut input).is_err() {
+            return Self::default();
+        }
+        serde_json::from_str(&input).unwrap_or_default()
+    }
+}
+
+/// Commit the uncommitted changes to the files the Claude Code session that just ended edited to
+/// the branch of the session, with a message generated by Claude Code, and show a desktop
+/// notification about it if `notify` is set.
+///
+/// Files changed by other means than the file editing tools of Claude Code, like commands it ran,
+/// aren't committed.
+/// The branch is created with a canned name the first time a session commits.
+pub(crate) fn post_session(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    notify: bool,
+) -> CliResult<()> {
+    let input = HookInput::from_stdin();
+    let Some(transcript) = input.transcript_path.as_deref().and_then(read_transcript) else {
+        return Ok(());
+    };
+    let workdir = ctx
+        .repo
+        .get()?
+        .workdir()
+        .context("Claude Code sessions can only be committed in repositories with a worktree")?
+        .to_owned();
+    let paths = session_files(&transcript, &workdir);
+    let Some((diff, file_ids)) = commit::uncommitted_unified_diff_of_paths(ctx, &paths)? else {
+        return Ok(());
+    };
+
+    let known_branch = input
+        .session_id
+        .as_ref()
+        .and_then(|session_id| read_session_branches(ctx).remove(session_id))
+        .filter(|branch| {
+            ctx.repo
+                .get()
+                .is_ok_and(|repo| matches!(repo.try_find_reference(branch.as_str()), Ok(Some(_))))
+        });
+    let branch = match known_branch {
+        Some(branch) => branch,
+        None => but_api::legacy::workspace::canned_branch_name(ctx)?,
+    };
+
+    let prompts = session_prompts(&transcript);
+    let message = match ai::generate_commit_message_with_claude(
+        out,
+        &ctx.settings.claude,
+        &diff,
+        prompts.as_deref(),
+        Some(COMMIT_MESSAGE_TIMEOUT),
+    ) {
+        Ok(message) => message,
+        Err(err) => {
+            let mut progress = out.progress_channel();
+            writeln!(
+                progress,
+                "{} Couldn't generate a commit message: {err:#}",
+                theme::get().attention.paint("Warning:")
+            )?;
+            match &input.session_id {
+                Some(session_id) => format!("Changes of Claude Code session {session_id}"),
+                None => "Changes of a Claude Code session".to_owned(),
+            }
+        }
+    };
+
+    commit::commit(
+        ctx,
+        out,
+        Some(&message),
+        Some(CliIdArg(branch.clone())),
+        None,
+        None,
+        &file_ids,
+        false,
+        // Creates the branch only if it isn't in the workspace yet.
+        true,
+        None,
+        false,
+        false,
+        None,
+        false,
+        ShowDiffInEditor::Unspecified,
+    )?;
+
+    if let Some(session_id) = &input.session_id {
+        remember_session_branch(ctx, session_id, &branch)?;
+    }
+    if notify {
+        let commit_id = ctx
+            .repo
+            .get()?
+            .find_reference(branch.as_str())?
+            .peel_to_id()?
+            .shorten_or_id();
+        notifications::show(
+            "Claude Code session committed",
+            &format!("Committed {commit_id} to {branch}"),
+        );
+    }
+    Ok(())
+}
+
+/// Configure Claude Code to run `but claude post-session` when a session ends, by adding the hook
+/// to `.claude/settings.json` in the worktree unless it's already there.
+pub(crate) fn install_hooks(ctx: &Context, out: &mut OutputChannel) -> CliResult<()> {
+    let repo = ctx.repo.get()?;
+    let workdir = repo
+        .workdir()
+        .context("Claude Code hooks can only be installed in repositories with a worktree")?;
+    let path = workdir.join(".claude").join("settings.json");
+
+    let mut settings = match std::fs::read(&path) {
+        Ok(contents) => serde_json::from_slice(&contents)
+            .with_context(|| format!("Couldn't parse '{}'", path.display()))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => json!({}),
+        Err(err) => {
+            return Err(anyhow::Error::from(err)
+                .context(format!("Couldn't read '{}'", path.display()))
+                .into());
+        }
+    };
+    let changed = add_post_session_hook(&mut settings)
+        .with_context(|| format!("Unexpected structure of '{}'", path.display()))?;
+    if changed {
+        std::fs::create_dir_all(path.parent().expect("settings are in a directory"))?;
+        let mut contents = serde_json::to_string_pretty(&settings)?;
+        contents.push('\n');
+        std::fs::write(&path, contents)?;
+    }
+
+    if let Some(out) = out.for_human() {
+        let t = theme::get();
+        if changed {
+            writeln!(
+                out,
+                "{} Claude Code will run `{POST_SESSION_COMMAND}` when a session ends, as configured in {}",
+                t.sym().success,
+                path.display()
+            )?;
+        } else {
+            writeln!(
+                out,
+                "Claude Code already runs `{POST_SESSION_COMMAND}` when a session ends, as configured in {}",
+                path.display()
+            )?;
+        }
+        if !auto_commit_enabled(&ctx.settings.claude) {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(
+                    "Sessions are only committed with `claude.executable` set and `but config set claude.auto_commit_after_completion true`"
+                )
+            )?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(json!({
+            "path": path,
+            "changed": changed,
+        }))?;
+    }
+    Ok(())
+}
+
+/// Add the `SessionEnd` hook running `but claude post-session` to the Claude Code `settings`, and
+/// return `true` if it wasn't there yet.
+/// The hook is removed from `Stop` hooks, which it was added to before.
+fn add_post_session_hook(settings: &mut Value) -> anyhow::Result<bool> {
+    let hooks = settings
+        .as_object_mut()
+        .context("settings aren't an object")?
+        .entry("hooks")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .context("'hooks' isn't an object")?;
+    let runs_post_session = |matcher: &Value| {
+        matcher["hooks"].as_array().is_some_and(|hooks| {
+            hooks
+                .iter()
+                .any(|hook| hook["command"].as_str() == Some(POST_SESSION_COMMAND))
+        })
+    };
+
+    let mut changed = false;
+    if let Some(outdated_hooks) = hooks
+        .get_mut(OUTDATED_POST_SESSION_HOOK_EVENT)
+        .and_then(Value::as_array_mut)
+    {
+        let len_before = outdated_hooks.len();
+        outdated_hooks.retain(|matcher| !runs_post_session(matcher));
+        changed = outdated_hooks.len() != len_before;
+        if outdated_hooks.is_empty() {
+            hooks.remove(OUTDATED_POST_SESSION_HOOK_EVENT);
+        }
+    }
+
+    let session_end_hooks = hooks
+        .entry(POST_SESSION_HOOK_EVENT)
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .with_context(|| format!("'hooks.{POST_SESSION_HOOK_EVENT}' isn't an array"))?;
+    if !session_end_hooks.iter().any(runs_post_session) {
+        session_end_hooks.push(json!({
+            "hooks": [{ "type": "command", "command": POST_SESSION_COMMAND }]
+        }));
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// Read the branches of Claude Code sessions by session ID, or assume there are none if they
+/// can't be read.
+fn read_session_branches(ctx: &Context) -> BTreeMap<String, String> {
+    std::fs::read(ctx.project_data_dir().join(SESSION_BRANCHES_FILE_NAME))
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Remember that `branch` is the branch of the Claude Code session with `session_id`, and forget
+/// the branches of sessions that don't exist anymore.
+fn remember_session_branch(ctx: &Context, session_id: &str, branch: &str) -> anyhow::Result<()> {
+    let mut branches = read_session_branches(ctx);
+    branches.insert(session_id.to_owned(), branch.to_owned());
+    let repo = ctx.repo.get()?;
+    branches.retain(|_, branch| matches!(repo.try_find_reference(branch.as_str()), Ok(Some(_))));
+
+    let data_dir = ctx.project_data_dir();
+    std::fs::create_dir_all(&data_dir)?;
+    std::fs::write(
+        data_dir.join(SESSION_BRANCHES_FILE_NAME),
+        serde_json::to_vec_pretty(&branches)?,
+    )?;
+    Ok(())
+}
+
+/// Read the entries of the Claude Code transcript at `path`, skipping lines that aren't JSON.
+fn read_transcript(path: &Path) -> Option<Vec<Value>> {
+    let transcript = std::fs::read_to_string(path).ok()?;
+    Some(
+        transcript
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    )
+}
+
+/// Return the paths relative to `workdir` of the files that the file editing tools of Claude Code
+/// were used on in `transcript`.
+fn session_files(transcript: &[Value], workdir: &Path) -> BTreeSet<BString> {
+    let canonical_workdir = gix::path::realpath(workdir).ok();
+    transcript
+        .iter()
+        .filter(|entry| entry["type"] == "assistant")
+        .filter_map(|entry| entry["message"]["content"].as_array())
+        .flatten()
+        .filter(|part| part["type"] == "tool_use")
+        .filter_map(|part| {
+            let input = &part["input"];
+            input["file_path"]
+                .as_str()
+                .or_else(|| input["notebook_path"].as_str())
+        })
+        .filter_map(|path| {
+            let path = Path::new(path);
+            let relative_path = if path.is_relative() {
+                path
+            } else {
+                path.strip_prefix(workdir)
+                    .ok()
+                    .or_else(|| path.strip_prefix(canonical_workdir.as_deref()?).ok())?
+            };
+            let relative_path =
+                gix::path::to_unix_separators_on_windows(gix::path::into_bstr(relative_path));
+            Some(relative_path.into_owned())
+        })
+        .collect()
+}
+
+/// Extract what the user asked for in the Claude Code session with `transcript`, keeping the
+/// latest prompts if there are too many.
+fn session_prompts(transcript: &[Value]) -> Option<String> {
+    let prompts: Vec<String> = transcript
+        .iter()
+        .filter(|entry| entry["type"] == "user")
+        .filter_map(|entry| match &entry["message"]["content"] {
+            Value::String(text) => Some(text.trim().to_owned()),
+            Value::Array(parts) => {
+                let text = parts
+                    .iter()
+                    .filter(|part| part["type"] == "text")
+                    .filter_map(|part| part["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Some(text.trim().to_owned())
+            }
+            _ => None,
+        })
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    let mut summary = String::new();
+    for prompt in prompts.iter().rev() {
+        if !summary.is_empty() && summary.len() + prompt.len() > MAX_SESSION_PROMPTS_BYTES {
+            break;
+        }
+        summary.insert_str(0, &format!("{prompt}\n"));
+    }
+    let summary = summary.trim_end();
+    (!summary.is_empty()).then(|| summary.to_owned())
+}
@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, fmt::Write as _};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+};
 
 use anyhow::{Context, Result, bail};
 use bstr::{BString, ByteSlice};
@@ -19,17 +22,80 @@ use crate::{
     args::atoms::{BranchArg, BranchOrCommit, CliIdArg, Priority, Purpose, ResolvedCliIdArg},
     bad_input,
     command::legacy::{
-        commit_message_prep::normalize_commit_message,
+        commit_message_prep::{
+            commit_message_problems, normalize_commit_message, revise_commit_message_in_editor,
+            warn_about_commit_message_problems,
+        },
         status::assignment::{CLIHunkAssignment, FileAssignment},
     },
+    id::WorktreeHunk,
     legacy::workspace::{HeadInfoBranch, HeadInfoStack},
     theme::{self, Paint},
     tui,
-    utils::{InputOutputChannel, OutputChannel, diff_specs, get_change_id_for_commit, rejection},
+    utils::{
+        Confirm, ConfirmDefault, InputOutputChannel, OutputChannel,
+        binary_files::{describe_binary_change, format_size},
+        diff_specs, get_change_id_for_commit,
+        large_files::{LARGE_FILE_THRESHOLD_KEY, large_file_threshold, large_files, uses_lfs},
+        quoted_path::{display_path, quote_path_enabled},
+        rejection,
+        submodules::{SubmoduleState, changed_submodule_states, is_submodule_change},
+    },
 };
 
 type TargetStack = (StackId, HeadInfoStack);
 
+/// Where to stack the branch created with `--create`, as given by `--anchor`.
+struct BranchAnchor {
+    resolved: ResolvedCliIdArg,
+    anchor: but_api::legacy::stack::create_reference::Anchor,
+}
+
+impl BranchAnchor {
+    /// Return `true` if `branch` of `stack` is where the anchor would have put it, i.e. right
+    /// above or below the anchor branch or commit, as given by its position.
+    fn is_anchor_of(&self, stack: &HeadInfoStack, branch: &str) -> bool {
+        use but_api::legacy::stack::create_reference::Anchor;
+        use but_workspace::branch::create_reference::Position;
+
+        let Some(index) = stack.branches.iter().position(|b| b.name == branch) else {
+            return false;
+        };
+        // Branches are ordered from top to bottom.
+        let below = stack.branches.get(index + 1);
+        let above = index
+            .checked_sub(1)
+            .and_then(|index| stack.branches.get(index));
+        match &self.anchor {
+            Anchor::AtSegment {
+                short_name,
+                position,
+            }
+            | Anchor::AtReference {
+                short_name,
+                position,
+            } => {
+                let neighbour = match position {
+                    Position::Above => below,
+                    Position::Below => above,
+                };
+                neighbour.is_some_and(|neighbour| neighbour.name == *short_name)
+            }
+            Anchor::AtCommit { position, .. } => {
+                let ResolvedCliIdArg::Commit(commit_id, _) = &self.resolved else {
+                    return false;
+                };
+                match position {
+                    Position::Above => stack.branches[index].base_commit == *commit_id,
+                    Position::Below => above
+                        .and_then(|above| above.commits.last())
+                        .is_some_and(|bottom_commit| bottom_commit.id == *commit_id),
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn insert_blank_commit(
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
@@ -45,20 +111,20 @@ pub(crate) fn insert_blank_commit(
         let repo = ctx.repo.get()?;
         if let Some(t) = before {
             (
-                t.resolve_in_workspace(&repo, &id_map, Purpose::Target, None)?
+                t.resolve_in_workspace_with_prompt(&repo, &id_map, Purpose::Target, None, out)?
                     .into_branch_or_commit()?,
                 InsertSide::Below,
             )
         } else if let Some(t) = after {
             (
-                t.resolve_in_workspace(&repo, &id_map, Purpose::Target, None)?
+                t.resolve_in_workspace_with_prompt(&repo, &id_map, Purpose::Target, None, out)?
                     .into_branch_or_commit()?,
                 InsertSide::Above,
             )
         } else if let Some(t) = target {
             // Default to --before behavior when using positional argument
             (
-                t.resolve_in_workspace(&repo, &id_map, Purpose::Target, None)?
+                t.resolve_in_workspace_with_prompt(&repo, &id_map, Purpose::Target, None, out)?
                     .into_branch_or_commit()?,
                 InsertSide::Below,
             )
@@ -135,7 +201,7 @@ pub(crate) fn insert_blank_commit(
     };
 
     let mut meta = ctx.meta()?;
-    let (new_commit, _workspace) = but_transaction::with_transaction_with_perm(
+    let (new_commit, workspace) = but_transaction::with_transaction_with_perm(
         ctx,
         &mut meta,
         guard.write_permission(),
@@ -156,8 +222,14 @@ pub(crate) fn insert_blank_commit(
     if let Some(out) = out.for_human() {
         writeln!(out, "{success_message}")?;
     } else if let Some(json_out) = out.for_json() {
+        let rewritten_commits: serde_json::Map<_, _> = workspace
+            .replaced_commits
+            .iter()
+            .map(|(old, new)| (old.to_string(), new.to_string().into()))
+            .collect();
         let commit_data = serde_json::json!({
             "commit_id": new_commit.to_string(),
+            "rewritten_commits": rewritten_commits,
         });
         json_out.write_value(commit_data)?;
     }
@@ -172,50 +244,84 @@ fn generate_unified_diff(
 ) -> anyhow::Result<String> {
     let mut diff_output = String::new();
     let repo = ctx.repo.get()?;
+    let quote_path = quote_path_enabled(&repo);
+    let context_lines = ctx.settings.context_lines;
 
     for fa in files_to_commit {
         // Find the corresponding TreeChange for this file
         if let Some(change) = changes.iter().find(|c| c.path_bytes == fa.path) {
             // Convert to but_core::TreeChange and get unified patch
             let core_change: but_core::TreeChange = change.clone().into();
-
-            // Propagate errors from unified_patch, only skip when it returns Ok(None)
-            match core_change.unified_patch(&repo, ctx.settings.context_lines)? {
-                Some(patch) => {
-                    // Add file header
-                    writeln!(
+            let (previous_path, previous_state, state, flags) = match &core_change.status {
+                but_core::TreeStatus::Modification {
+                    previous_state,
+                    state,
+                    flags: Some(flags),
+                } => (None, previous_state, state, flags),
+                but_core::TreeStatus::Rename {
+                    previous_path,
+                    previous_state,
+                    state,
+                    flags: Some(flags),
+                } => (Some(previous_path), previous_state, state, flags),
+                _ => {
+                    write_file_diff(
                         &mut diff_output,
-                        "diff --git a/{} b/{}",
-                        fa.path.to_str_lossy(),
-                        fa.path.to_str_lossy()
+                        &repo,
+                        &core_change,
+                        &[],
+                        context_lines,
+                        quote_path,
                     )?;
-
-                    // Add patch content based on type
-                    match patch {
-                        but_core::UnifiedPatch::Binary => {
-                            writeln!(&mut diff_output, "Binary files differ")?;
-                        }
-                        but_core::UnifiedPatch::TooLarge { size_in_bytes } => {
-                            writeln!(&mut diff_output, "File too large ({size_in_bytes} bytes)")?;
-                        }
-                        but_core::UnifiedPatch::Patch { hunks, .. } => {
-                            for hunk in hunks {
-                                // Add hunk header
-                                writeln!(
-                                    &mut diff_output,
-                                    "@@ -{},{} +{},{} @@",
-                                    hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
-                                )?;
-                                // Add hunk content (already includes +/- prefixes)
-                                diff_output.push_str(hunk.diff.to_str_lossy().as_ref());
-                            }
-                        }
-                    }
-                }
-                None => {
-                    // Ok(None) means the file can't produce a diff (e.g., submodules, type changes)
-                    // This is expected and we skip the file silently
+                    continue;
                 }
+            };
+            if flags.is_typechange() {
+                // Like Git, show a type change as deletion of the old entry and addition of the new one.
+                let deletion = but_core::TreeChange {
+                    path: previous_path.unwrap_or(&core_change.path).clone(),
+                    status: but_core::TreeStatus::Deletion {
+                        previous_state: *previous_state,
+                    },
+                };
+                let addition = but_core::TreeChange {
+                    path: core_change.path.clone(),
+                    status: but_core::TreeStatus::Addition {
+                        state: *state,
+                        is_untracked: false,
+                    },
+                };
+                write_file_diff(
+                    &mut diff_output,
+                    &repo,
+                    &deletion,
+                    &[format!(
+                        "deleted file mode {:o}",
+                        previous_state.kind as u16
+                    )],
+                    context_lines,
+                    quote_path,
+                )?;
+                write_file_diff(
+                    &mut diff_output,
+                    &repo,
+                    &addition,
+                    &[format!("new file mode {:o}", state.kind as u16)],
+                    context_lines,
+                    quote_path,
+                )?;
+            } else {
+                write_file_diff(
+                    &mut diff_output,
+                    &repo,
+                    &core_change,
+                    &[
+                        format!("old mode {:o}", previous_state.kind as u16),
+                        format!("new mode {:o}", state.kind as u16),
+                    ],
+                    context_lines,
+                    quote_path,
+                )?;
             }
         }
     }
@@ -223,6 +329,100 @@ fn generate_unified_diff(
     Ok(diff_output)
 }
 
+/// Write the diff of `change` to `diff_output`, with `extended_headers` like `new file mode` lines
+/// after the `diff --git` line. Nothing is written if there is neither a patch nor extended headers.
+fn write_file_diff(
+    diff_output: &mut String,
+    repo: &gix::Repository,
+    change: &but_core::TreeChange,
+    extended_headers: &[String],
+    context_lines: u32,
+    quote_path: bool,
+) -> anyhow::Result<()> {
+    // Propagate errors from unified_patch, only skip when it returns Ok(None)
+    let patch = change.unified_patch(repo, context_lines)?;
+    if patch.is_none() && extended_headers.is_empty() {
+        return Ok(());
+    }
+    // Add file header, with paths quoted like Git does
+    let prefixed_path = |prefix: &str| {
+        let mut path = BString::from(prefix);
+        path.extend_from_slice(&change.path);
+        display_path(path.as_bstr(), quote_path)
+    };
+    writeln!(
+        diff_output,
+        "diff --git {} {}",
+        prefixed_path("a/"),
+        prefixed_path("b/")
+    )?;
+    for header in extended_headers {
+        writeln!(diff_output, "{header}")?;
+    }
+    // Add patch content based on type
+    match &patch {
+        Some(but_core::UnifiedPatch::Binary) => {
+            let description = describe_binary_change(repo, change, patch.as_ref())?;
+            writeln!(
+                diff_output,
+                "{}",
+                description.as_deref().unwrap_or("Binary files differ")
+            )?;
+        }
+        Some(but_core::UnifiedPatch::TooLarge { size_in_bytes }) => {
+            writeln!(diff_output, "File too large ({size_in_bytes} bytes)")?;
+        }
+        Some(but_core::UnifiedPatch::Patch { hunks, .. }) => {
+            for hunk in hunks {
+                // Add hunk header
+                writeln!(
+                    diff_output,
+                    "@@ -{},{} +{},{} @@",
+                    hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+                )?;
+                // Add hunk content (already includes +/- prefixes)
+                diff_output.push_str(hunk.diff.to_str_lossy().as_ref());
+            }
+        }
+        None => {
+            // Ok(None) means the file can't produce a diff (e.g., submodules)
+            // This is expected and we only show the extended headers
+        }
+    }
+    Ok(())
+}
+
+/// Generate a unified diff string of the uncommitted files at `paths`, along with the IDs to pass
+/// to [`commit()`] to commit them, or `None` if none of them has changes.
+pub(crate) fn uncommitted_unified_diff_of_paths(
+    ctx: &mut but_ctx::Context,
+    paths: &BTreeSet<BString>,
+) -> anyhow::Result<Option<(String, Vec<String>)>> {
+    let (files, file_ids, changes) = {
+        let guard = ctx.shared_worktree_access();
+        let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
+        let changes = diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?
+            .worktree_changes
+            .changes;
+        let files: Vec<_> = FileAssignment::get_assignments_by_file(&id_map)
+            .into_values()
+            .filter(|fa| paths.contains(&fa.path))
+            .collect();
+        let file_ids: Vec<_> = id_map
+            .uncommitted_files
+            .values()
+            .filter(|file| paths.contains(file.path()))
+            .map(|file| file.short_id.clone())
+            .collect();
+        (files, file_ids, changes)
+    };
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let diff = generate_unified_diff(ctx, &files, &changes)?;
+    Ok(Some((diff, file_ids)))
+}
+
 /// If `id` looks like `<file>:<line-range>` (e.g. `qt:16-40`), returns a message
 /// explaining that a hunk ID is expected, not a line range. `None` otherwise, so
 /// real hunk IDs like `qt:9` are left alone.
@@ -245,6 +445,7 @@ fn line_range_hunk_hint(id: &str) -> Option<String> {
 fn resolve_file_ids(
     id_map: &IdMap,
     ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
     file_ids: &[String],
 ) -> anyhow::Result<Vec<FileAssignment>> {
     let mut resolved_files: BTreeMap<BString, FileAssignment> = BTreeMap::new();
@@ -269,9 +470,15 @@ fn resolve_file_ids(
         };
 
         if cli_ids.is_empty() {
-            errors.push(line_range_hunk_hint(file_id).unwrap_or_else(|| {
-                format!("'{file_id}' not found. Run 'but status' to see available file IDs.")
-            }));
+            errors.push(
+                line_range_hunk_hint(file_id)
+                    .or_else(|| crate::id::parser::unmatched_path_pattern(id_map, file_id))
+                    .unwrap_or_else(|| {
+                        format!(
+                            "'{file_id}' not found. Run 'but status' to see available file IDs."
+                        )
+                    }),
+            );
             continue;
         }
 
@@ -293,30 +500,22 @@ fn resolve_file_ids(
                 {
                     continue;
                 }
-
-                // Convert UncommittedHunkOrFile to FileAssignment and merge with existing entry if present
-                let path = uncommitted.hunk_assignments.first().path_bytes.clone();
-                let new_assignments: Vec<CLIHunkAssignment> = uncommitted
-                    .hunk_assignments
+                merge_hunks(
+                    &mut resolved_files,
+                    file_id,
+                    uncommitted.hunk_assignments.iter(),
+                );
+            }
+            CliId::PathPrefix {
+                id,
+                hunk_assignments,
+            } => {
+                let hunks: Vec<_> = hunk_assignments
                     .iter()
-                    .map(|ha| CLIHunkAssignment {
-                        inner: ha.clone(),
-                        cli_id: file_id.to_owned(),
-                    })
+                    .map(|(_, hunk)| hunk.clone())
                     .collect();
-
-                // Merge with existing entry for same path, or insert new
-                if let Some(existing) = resolved_files.get_mut(&path) {
-                    existing.assignments.extend(new_assignments);
-                } else {
-                    resolved_files.insert(
-                        path.clone(),
-                        FileAssignment {
-                            path,
-                            assignments: new_assignments,
-                        },
-                    );
-                }
+                super::assign::write_path_matches(out, id, &hunks)?;
+                merge_hunks(&mut resolved_files, file_id, hunks.iter());
             }
             other => {
                 errors.push(format!(
@@ -335,8 +534,32 @@ fn resolve_file_ids(
     Ok(resolved_files.into_values().collect())
 }
 
+/// Add `hunks`, selected by `cli_id`, to the files in `resolved_files`, merging them with the
+/// hunks already there for the same path.
+fn merge_hunks<'a>(
+    resolved_files: &mut BTreeMap<BString, FileAssignment>,
+    cli_id: &str,
+    hunks: impl Iterator<Item = &'a WorktreeHunk>,
+) {
+    for hunk in hunks {
+        let assignment = CLIHunkAssignment {
+            inner: hunk.clone(),
+            cli_id: cli_id.to_owned(),
+        };
+        resolved_files
+            .entry(hunk.path_bytes.clone())
+            .or_insert_with(|| FileAssignment {
+                path: hunk.path_bytes.clone(),
+                assignments: Vec::new(),
+            })
+            .assignments
+            .push(assignment);
+    }
+}
+
 fn branch_hint_from_arg(
     ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
     id_map: &IdMap,
     branch_arg: Option<CliIdArg>,
     perm: &RepoShared,
@@ -344,7 +567,7 @@ fn branch_hint_from_arg(
     if let Some(branch_arg) = branch_arg {
         let repo = ctx.repo.get()?;
         if let Some(branch) = branch_arg
-            .try_resolve(&repo, id_map, Purpose::Branch, Some(Priority::Branch))?
+            .try_resolve_with_prompt(&repo, id_map, Purpose::Branch, Some(Priority::Branch), out)?
             .and_then(|id| {
                 if let ResolvedCliIdArg::Branch(BranchArg(branch)) = id {
                     Some(branch)
@@ -353,6 +576,15 @@ fn branch_hint_from_arg(
                 }
             })
         {
+            if branch == branch_arg.0
+                && !id_map.parse_ref_name(&branch)?.is_empty()
+                && let Some(out) = out.for_human()
+            {
+                writeln!(
+                    out,
+                    "Using the local branch '{branch}' rather than the branch that tracks the remote branch '{branch}'"
+                )?;
+            }
             Ok(Some(branch))
         } else {
             let (repo, ws, _db) = ctx.workspace_and_db_with_perm(perm)?;
@@ -439,6 +671,7 @@ fn ensure_position_target_matches_branch(
 
 fn resolve_insert_position(
     ctx: &mut but_ctx::Context,
+    out: &mut OutputChannel,
     id_map: &IdMap,
     target_branch: &crate::legacy::workspace::HeadInfoBranch,
     before: Option<CliIdArg>,
@@ -448,7 +681,7 @@ fn resolve_insert_position(
         let target = {
             let repo = ctx.repo.get()?;
             target
-                .resolve_in_workspace(&repo, id_map, Purpose::Target, None)?
+                .resolve_in_workspace_with_prompt(&repo, id_map, Purpose::Target, None, out)?
                 .into_branch_or_commit()?
         };
         ensure_position_target_matches_branch(&target, target_branch, "--before")?;
@@ -463,7 +696,7 @@ fn resolve_insert_position(
         let target = {
             let repo = ctx.repo.get()?;
             target
-                .resolve_in_workspace(&repo, id_map, Purpose::Target, None)?
+                .resolve_in_workspace_with_prompt(&repo, id_map, Purpose::Target, None, out)?
                 .into_branch_or_commit()?
         };
         ensure_position_target_matches_branch(&target, target_branch, "--after")?;
@@ -493,8 +726,11 @@ pub(crate) fn commit(
     file_ids: &[String],
     all: bool,
     create_branch: bool,
+    anchor: Option<CliIdArg>,
     no_hooks: bool,
+    allow_large_files: bool,
     generate_message: Option<Option<String>>,
+    no_edit: bool,
     show_diff_in_editor: ShowDiffInEditor,
 ) -> CliResult<()> {
     let mut guard = ctx.exclusive_worktree_access();
@@ -505,7 +741,26 @@ pub(crate) fn commit(
     }
 
     let is_positioned_commit = before.is_some() || after.is_some();
-    let branch_hint = branch_hint_from_arg(ctx, &id_map, branch_arg, guard.read_permission())?;
+    let mut branch_hint =
+        branch_hint_from_arg(ctx, out, &id_map, branch_arg, guard.read_permission())?;
+
+    let anchor = match anchor {
+        Some(anchor) => {
+            let resolved = {
+                let repo = ctx.repo.get()?;
+                super::branch::resolve_anchor(&repo, &id_map, &anchor)?
+            };
+            // The new branch must be known by name to commit onto it, as it may end up in the middle of the stack.
+            if branch_hint.is_none() {
+                branch_hint = Some(but_api::legacy::workspace::canned_branch_name(ctx)?);
+            }
+            Some(BranchAnchor {
+                anchor: super::branch::create_reference_anchor(resolved.clone())?,
+                resolved,
+            })
+        }
+        None => None,
+    };
 
     let t = theme::get();
 
@@ -526,18 +781,19 @@ pub(crate) fn commit(
         .into());
     }
 
-    let (_target_stack_id, target_stack) = select_stack(
+    let (target_stack_id, target_stack) = select_stack(
         &id_map,
         ctx,
         &stacks,
         branch_hint.as_deref(),
         create_branch,
+        anchor,
         out,
         guard.write_permission(),
     )?;
     let target_branch = select_target_branch(&target_stack, branch_hint.as_deref(), &id_map, ctx)?;
     let (relative_to, insert_side) =
-        resolve_insert_position(ctx, &id_map, target_branch, before, after)?;
+        resolve_insert_position(ctx, out, &id_map, target_branch, before, after)?;
 
     // Get changes and assignments using but-api
     let worktree_changes = diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?;
@@ -546,7 +802,7 @@ pub(crate) fn commit(
     // Get files to commit - either specific files by ID or all eligible files
     let files_to_commit = if !file_ids.is_empty() {
         // User specified specific file IDs - resolve them
-        resolve_file_ids(&id_map, ctx, file_ids)?
+        resolve_file_ids(&id_map, ctx, out, file_ids)?
     } else {
         // Default behavior: uncommitted files + files assigned to target stack
         let assignments_by_file: BTreeMap<BString, FileAssignment> =
@@ -569,6 +825,20 @@ pub(crate) fn commit(
         builder.into_diff_specs()
     };
 
+    // Refuse up-front to commit hunks that depend on commits in other stacks, as they can't land
+    // on the target branch on their own.
+    let locked = {
+        let (repo, ws, _db) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+        rejection::locked_to_other_stacks(&repo, &ws, &diff_specs, target_stack_id)
+    };
+    if !locked.is_empty() {
+        return Err(rejection::locked_changes_error(&locked, &target_branch.name).into());
+    }
+
+    if !allow_large_files {
+        confirm_large_files(ctx, out, &files_to_commit, &changes)?;
+    }
+
     // Run pre-commit hook unless --no-hooks was specified
     // This runs BEFORE getting the commit message so the user doesn't waste time writing a message
     // for a commit that will fail the hook
@@ -591,17 +861,68 @@ pub(crate) fn commit(
     // Get commit message
     let commit_message = if let Some(user_summary) = generate_message {
         let diff = generate_unified_diff(ctx, &files_to_commit, &changes)?;
-        super::ai::generate_commit_message(out, &diff, user_summary)?
+        let generated = super::ai::generate_commit_message_with_claude(
+            out,
+            &ctx.settings.claude,
+            &diff,
+            user_summary.as_deref(),
+            None,
+        );
+        // Without an interactive editor, the generated message is used as is.
+        let can_edit = out.for_human_ui().is_some();
+        match generated {
+            Ok(message) if no_edit || !can_edit => message,
+            Ok(message) => {
+                let message = get_commit_message_from_editor(
+                    ctx,
+                    &files_to_commit,
+                    &changes,
+                    show_diff_in_editor,
+                    Some(&message),
+                )?;
+                revise_commit_message_in_editor(&ctx.settings.commit_message, message)?
+            }
+            Err(err) if can_edit => {
+                if let Some(out) = out.for_human_ui() {
+                    writeln!(
+                        out,
+                        "{} Couldn't generate a commit message: {err:#}",
+                        t.attention.paint("Warning:")
+                    )?;
+                }
+                let message = get_commit_message_from_editor(
+                    ctx,
+                    &files_to_commit,
+                    &changes,
+                    show_diff_in_editor,
+                    None,
+                )?;
+                revise_commit_message_in_editor(&ctx.settings.commit_message, message)?
+            }
+            Err(err) => return Err(err.context("Couldn't generate a commit message").into()),
+        }
     } else if let Some(msg) = message {
         msg.to_string()
     } else {
         // The pre-check in lib.rs guarantees a message for formats without an interactive editor.
-        get_commit_message_from_editor(ctx, &files_to_commit, &changes, show_diff_in_editor)?
+        let message = get_commit_message_from_editor(
+            ctx,
+            &files_to_commit,
+            &changes,
+            show_diff_in_editor,
+            None,
+        )?;
+        revise_commit_message_in_editor(&ctx.settings.commit_message, message)?
     };
 
     if commit_message.trim().is_empty() {
         return Err(anyhow::anyhow!("Aborting commit due to empty commit message.").into());
     }
+    warn_about_commit_message_problems(
+        out,
+        &commit_message_problems(&ctx.settings.commit_message, &commit_message),
+    )?;
+    warn_about_dirty_submodules(ctx, out, &files_to_commit, &changes)?;
 
     // Run commit-msg hook unless --no-hooks was specified
     // This hook can validate and optionally modify the commit message
@@ -744,33 +1065,153 @@ pub(crate) fn commit(
     Ok(())
 }
 
-fn create_independent_branch(
+/// Ask before committing files larger than the configured threshold, or fail if there is no
+/// terminal to ask on, as large files are hard to remove once pushed.
+fn confirm_large_files(
+    ctx: &but_ctx::Context,
+    out: &mut OutputChannel,
+    files_to_commit: &[FileAssignment],
+    changes: &[TreeChange],
+) -> CliResult<()> {
+    let repo = ctx.repo.get()?;
+    let Some(threshold) = large_file_threshold(&repo) else {
+        return Ok(());
+    };
+    let changes: Vec<but_core::TreeChange> = changes
+        .iter()
+        .filter(|change| {
+            files_to_commit
+                .iter()
+                .any(|fa| fa.path == change.path_bytes)
+        })
+        .map(|change| change.clone().into())
+        .collect();
+    let large = large_files(&repo, &changes, threshold)?;
+    if large.is_empty() {
+        return Ok(());
+    }
+
+    let quote_path = quote_path_enabled(&repo);
+    let mut message = format!("These files are larger than {}:", format_size(threshold));
+    for file in &large {
+        message.push_str(&format!(
+            "\n  {} ({})",
+            display_path(file.path.as_bstr(), quote_path),
+            format_size(file.size)
+        ));
+    }
+    let lfs_hint = if uses_lfs(&repo) {
+        "This repository uses Git LFS, consider tracking them with `git lfs track`. "
+    } else {
+        ""
+    };
+
+    let Some(mut inout) = out.prepare_for_terminal_input() else {
+        return Err(bad_input(message)
+            .hint(format!(
+                "{lfs_hint}Pass --allow-large-files to commit them anyway, or change the size limit with `git config {LARGE_FILE_THRESHOLD_KEY} <bytes>`"
+            ))
+            .into());
+    };
+    let t = theme::get();
+    writeln!(inout, "{} {message}", t.attention.paint("Warning:"))?;
+    if !lfs_hint.is_empty() {
+        writeln!(inout, "{}", t.hint.paint(lfs_hint.trim_end()))?;
+    }
+    if inout.confirm("Commit anyway?", ConfirmDefault::No)? == Confirm::No {
+        return Err(anyhow::anyhow!("Commit cancelled").into());
+    }
+    Ok(())
+}
+
+/// Warn that the uncommitted changes inside of the submodules in `files_to_commit` aren't part of
+/// the commit, as only the commit a submodule points to is recorded.
+fn warn_about_dirty_submodules(
+    ctx: &but_ctx::Context,
+    out: &mut OutputChannel,
+    files_to_commit: &[FileAssignment],
+    changes: &[TreeChange],
+) -> Result<()> {
+    let Some(out) = out.for_human() else {
+        return Ok(());
+    };
+    let repo = ctx.repo.get()?;
+    let submodule_paths = changes
+        .iter()
+        .filter(|change| {
+            is_submodule_change(&change.status)
+                && files_to_commit
+                    .iter()
+                    .any(|fa| fa.path == change.path_bytes)
+        })
+        .map(|change| change.path_bytes.as_bstr());
+    let quote_path = quote_path_enabled(&repo);
+    let t = theme::get();
+    for (path, state) in changed_submodule_states(&repo, submodule_paths) {
+        if state != SubmoduleState::Dirty {
+            continue;
+        }
+        let displayed_path = display_path(path.as_bstr(), quote_path);
+        writeln!(
+            out,
+            "{} Only the new commit of submodule '{displayed_path}' is recorded, not the uncommitted changes inside of it",
+            t.attention.paint("Warning:")
+        )?;
+    }
+    Ok(())
+}
+
+/// Create `branch_name` as new independent stack, or stacked on `anchor` if given.
+fn create_target_branch(
     branch_name: &str,
+    anchor: Option<BranchAnchor>,
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
     perm: &mut RepoExclusive,
 ) -> anyhow::Result<TargetStack> {
-    // Create a new independent stack with the given branch name
+    let t = theme::get();
+    let (resolved_anchor, anchor) = match anchor {
+        Some(BranchAnchor { resolved, anchor }) => (Some(resolved), Some(anchor)),
+        None => (None, None),
+    };
     let (new_stack_id_opt, _new_ref) = but_api::legacy::stack::create_reference_with_perm(
         ctx,
         but_api::legacy::stack::create_reference::Request {
             new_name: branch_name.to_string(),
-            anchor: None,
+            anchor,
         },
         perm,
     )?;
 
-    if let Some(new_stack_id) = new_stack_id_opt {
+    let Some(resolved_anchor) = resolved_anchor else {
+        let Some(new_stack_id) = new_stack_id_opt else {
+            bail!("Failed to create new branch '{branch_name}'");
+        };
         if let Some(out) = out.for_human() {
             writeln!(out, "Created new independent branch '{branch_name}'")?;
         }
-        Ok((
+        return Ok((
             new_stack_id,
             crate::legacy::workspace::applied_stack(ctx, Some(new_stack_id))?,
-        ))
-    } else {
+        ));
+    };
+
+    let Some((stack_id, stack)) = crate::legacy::workspace::applied_stacks(ctx)?
+        .into_iter()
+        .find(|stack| stack.contains_branch(branch_name))
+        .and_then(|stack| stack.id.map(|id| (id, stack)))
+    else {
         bail!("Failed to create new branch '{branch_name}'");
+    };
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "Created new branch '{branch_name}' stacked on {}",
+            t.hint.paint(resolved_anchor.to_string())
+        )?;
+        super::branch::write_stack_structure(out, &stack, branch_name)?;
     }
+    Ok((stack_id, stack))
 }
 
 fn select_stack(
@@ -779,6 +1220,7 @@ fn select_stack(
     stacks: &[TargetStack],
     branch_hint: Option<&str>,
     create_branch: bool,
+    anchor: Option<BranchAnchor>,
     out: &mut OutputChannel,
     perm: &mut RepoExclusive,
 ) -> anyhow::Result<TargetStack> {
@@ -788,19 +1230,29 @@ fn select_stack(
             Some(hint) => String::from(hint),
             None => but_api::legacy::workspace::canned_branch_name(ctx)?,
         };
-        return create_independent_branch(&branch_name, ctx, out, perm);
+        return create_target_branch(&branch_name, anchor, ctx, out, perm);
     }
 
     match branch_hint {
         Some(hint) => {
             // Try to find stack by branch hint
-            if let Some(stack) = find_stack_by_hint(id_map, stacks, hint) {
+            if let Some((stack, branch_name)) =
+                find_stack_by_hint(ctx, id_map, stacks, hint, perm.read_permission())
+            {
+                if let Some(anchor) = anchor.as_ref()
+                    && !anchor.is_anchor_of(&stack.1, &branch_name)
+                {
+                    bail!(
+                        "Branch '{branch_name}' already exists, but isn't stacked right on anchor '{}'",
+                        anchor.resolved
+                    );
+                }
                 return Ok(stack);
             }
 
             // Branch not found - create if flag is set, otherwise error
             if create_branch {
-                create_independent_branch(hint, ctx, out, perm)
+                create_target_branch(hint, anchor, ctx, out, perm)
             } else {
                 bail!("Branch '{hint}' not found")
             }
@@ -808,7 +1260,7 @@ fn select_stack(
         None if create_branch => {
             // Create with canned name
             let branch_name = but_api::legacy::workspace::canned_branch_name(ctx)?;
-            create_independent_branch(&branch_name, ctx, out, perm)
+            create_target_branch(&branch_name, anchor, ctx, out, perm)
         }
         None if stacks.len() == 1 => {
             // Only one stack - use it
@@ -825,29 +1277,33 @@ fn select_stack(
     }
 }
 
-fn find_stack_by_hint(id_map: &IdMap, stacks: &[TargetStack], hint: &str) -> Option<TargetStack> {
-    // Try exact branch name match
-    for (stack_id, stack) in stacks {
-        if stack.contains_branch(hint) {
-            return Some((*stack_id, stack.clone()));
-        }
-    }
-
-    // Try CLI ID parsing
-    let cli_ids = id_map
-        .parse(hint, Box::new(move |_, _| Ok(Vec::new())))
-        .ok()?;
-    for cli_id in cli_ids {
-        if let CliId::Branch(branch) = cli_id {
-            for (stack_id, stack) in stacks {
-                if stack.contains_branch(&branch.name) {
-                    return Some((*stack_id, stack.clone()));
-                }
-            }
-        }
-    }
-
-    None
+/// Find the stack containing the branch `hint` refers to by name or CLI ID, and return it along
+/// with the name of that branch.
+fn find_stack_by_hint(
+    ctx: &but_ctx::Context,
+    id_map: &IdMap,
+    stacks: &[TargetStack],
+    hint: &str,
+    perm: &RepoShared,
+) -> Option<(TargetStack, String)> {
+    let stack_id_of = |branch_name: &str| {
+        but_api::legacy::workspace::stack_details_by_branch_with_perm(ctx, branch_name, perm)
+            .ok()
+            .flatten()
+            .map(|(stack_id, _details)| (stack_id, branch_name.to_owned()))
+    };
+    let (stack_id, branch_name) = stack_id_of(hint).or_else(|| {
+        id_map
+            .parse(hint, Box::new(move |_, _| Ok(Vec::new())))
+            .ok()?
+            .into_iter()
+            .find_map(|cli_id| match cli_id {
+                CliId::Branch(branch) => stack_id_of(&branch.name),
+                _ => None,
+            })
+    })?;
+    let stack = stacks.iter().find(|(id, _)| *id == stack_id).cloned()?;
+    Some((stack, branch_name))
 }
 
 fn prompt_for_stack_selection(
@@ -872,22 +1328,32 @@ fn prompt_for_stack_selection(
         .ok_or_else(|| anyhow::anyhow!("Selection aborted"))
 }
 
+/// Let the user write the commit message in their editor, starting out with `initial_message`
+/// if given.
 fn get_commit_message_from_editor(
     ctx: &mut but_ctx::Context,
     files_to_commit: &[FileAssignment],
     changes: &[TreeChange],
     show_diff_in_editor: ShowDiffInEditor,
+    initial_message: Option<&str>,
 ) -> anyhow::Result<String> {
     // Generate commit message template
-    let mut template = String::new();
+    let mut template = initial_message
+        .map(|message| format!("{}\n", message.trim_end()))
+        .unwrap_or_default();
     template.push_str("\n# Please enter the commit message for your changes. Lines starting\n");
     template.push_str("# with '#' will be ignored, and an empty message aborts the commit.\n");
     template.push_str("#\n");
     template.push_str("# Changes to be committed:\n");
 
+    let quote_path = quote_path_enabled(&*ctx.repo.get()?);
     for fa in files_to_commit {
         let status_char = get_status_char(&fa.path, changes);
-        template.push_str(&format!("#\t{}  {}\n", status_char, fa.path.to_str_lossy()));
+        template.push_str(&format!(
+            "#\t{}  {}\n",
+            status_char,
+            display_path(fa.path.as_bstr(), quote_path)
+        ));
     }
     template.push_str("#\n");
 
@@ -921,17 +1387,24 @@ fn get_commit_message_from_editor(
 }
 
 fn get_status_char(path: &BString, changes: &[TreeChange]) -> &'static str {
-    for change in changes {
-        if change.path_bytes == *path {
-            return match change.status {
-                but_core::ui::TreeStatus::Addition { .. } => "new file:",
-                but_core::ui::TreeStatus::Modification { .. } => "modified:",
-                but_core::ui::TreeStatus::Deletion { .. } => "deleted:",
-                but_core::ui::TreeStatus::Rename { .. } => "renamed:",
-            };
-        }
+    use but_core::{ModeFlags, ui::TreeStatus};
+    let Some(change) = changes.iter().find(|change| change.path_bytes == *path) else {
+        return "modified:"; // fallback
+    };
+    match change.status {
+        TreeStatus::Modification {
+            flags: Some(flags), ..
+        } if ModeFlags::from(flags).is_typechange() => "typechange:",
+        TreeStatus::Modification { flags: Some(_), .. } => "mode change:",
+        TreeStatus::Rename {
+            flags: Some(flags), ..
+        } if ModeFlags::from(flags).is_typechange() => "renamed, typechange:",
+        TreeStatus::Rename { flags: Some(_), .. } => "renamed, mode change:",
+        TreeStatus::Addition { .. } => "new file:",
+        TreeStatus::Modification { .. } => "modified:",
+        TreeStatus::Deletion { .. } => "deleted:",
+        TreeStatus::Rename { .. } => "renamed:",
     }
-    "modified:" // fallback
 }
 
 #[cfg(test)]
@@ -1,3 +1,12 @@
+use anyhow::Result;
+use but_core::commit::message_check::{self, Problem};
+
+use crate::{
+    theme::{self, Paint},
+    tui,
+    utils::OutputChannel,
+};
+
 /// Returns the canonical commit message representation used for comparisons and storage.
 ///
 /// This currently trims leading and trailing whitespace.
@@ -18,6 +27,67 @@ pub(crate) fn commit_message_has_multiple_lines(message: &str) -> bool {
     message.trim_end().split_once('\n').is_some()
 }
 
+/// Returns all ways in which `message` doesn't follow the conventions configured in `settings`.
+pub(crate) fn commit_message_problems(
+    settings: &but_settings::app_settings::CommitMessageSettings,
+    message: &str,
+) -> Vec<Problem> {
+    message_check::check(
+        message.into(),
+        message_check::Rules {
+            subject_max_len: settings.subject_max_length as usize,
+            body_max_line_len: settings.body_max_line_length as usize,
+            require_blank_second_line: settings.require_blank_second_line,
+        },
+    )
+}
+
+/// If `strict` checks are configured in `settings`, re-open the editor with the problems of `message`
+/// listed as comments, until there are no problems or the message is left unchanged.
+///
+/// Returns the final message.
+pub(crate) fn revise_commit_message_in_editor(
+    settings: &but_settings::app_settings::CommitMessageSettings,
+    mut message: String,
+) -> Result<String> {
+    if !settings.strict {
+        return Ok(message);
+    }
+    loop {
+        let problems = commit_message_problems(settings, &message);
+        if problems.is_empty() {
+            return Ok(message);
+        }
+        let mut template = message.trim_end().to_owned();
+        template
+            .push_str("\n\n# The commit message doesn't follow the configured conventions:\n#\n");
+        for problem in &problems {
+            template.push_str(&format!("#\t{problem}\n"));
+        }
+        template.push_str("#\n# Save it unchanged to use it anyway.\n");
+        let revised = tui::get_text::from_editor_no_comments("commit_msg", &template)?.to_string();
+        if !should_update_commit_message(&message, &revised) {
+            return Ok(message);
+        }
+        message = revised;
+    }
+}
+
+/// Tell humans about all `problems` with a commit message.
+pub(crate) fn warn_about_commit_message_problems(
+    out: &mut OutputChannel,
+    problems: &[Problem],
+) -> Result<()> {
+    let Some(out) = out.for_human() else {
+        return Ok(());
+    };
+    let t = theme::get();
+    for problem in problems {
+        writeln!(out, "{} {problem}", t.attention.paint("Warning:"))?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,165 @@
+use bstr::ByteSlice as _;
+use but_core::{RepositoryExt as _, WORKSPACE_REF_NAME, extract_remote_name_and_short_name};
+use but_ctx::Context;
+use serde::Serialize;
+
+use crate::{
+    CliResult, bad_input,
+    command::legacy::teardown,
+    theme::{self, Paint},
+    utils::OutputChannel,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeinitResult {
+    /// The snapshot taken before leaving the workspace, if we were in it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_id: Option<String>,
+    /// The branch that was checked out when leaving the workspace, if we were in it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checked_out_branch: Option<String>,
+    /// Whether the GitButler data of the repository was deleted.
+    purged: bool,
+}
+
+/// Stop managing the repository of `ctx` with GitButler by leaving the workspace for `checkout`
+/// or the only applied branch, deleting `gitbutler/workspace` and removing the project.
+/// If `purge` is set, the GitButler data in the repository is deleted as well.
+pub(crate) fn deinit(
+    ctx: &mut Context,
+    checkout: Option<String>,
+    purge: bool,
+    out: &mut OutputChannel,
+) -> CliResult<()> {
+    let t = theme::get();
+
+    let on_workspace = {
+        let repo = ctx.repo.get()?;
+        let head = repo.head()?;
+        head.referent_name()
+            .is_some_and(|name| name.shorten().starts_with(b"gitbutler/"))
+    };
+    let left_workspace = if on_workspace {
+        let checkout = match checkout {
+            Some(checkout) => Some(checkout),
+            None => default_checkout_branch(ctx)?,
+        };
+        Some(teardown::leave_workspace(ctx, checkout, out)?)
+    } else {
+        None
+    };
+
+    let repo = ctx.repo.get()?.clone();
+    if let Some(workspace_ref) = repo.try_find_reference(WORKSPACE_REF_NAME)? {
+        workspace_ref.delete()?;
+        if let Some(out) = out.for_human() {
+            writeln!(
+                out,
+                "{}",
+                t.success.paint(format!("✓ Deleted {WORKSPACE_REF_NAME}"))
+            )?;
+        }
+    }
+
+    let data_dir = repo.gitbutler_storage_path()?;
+    let project_id = ctx.legacy_project.id.clone();
+    if purge {
+        gitbutler_project::delete(project_id)?;
+        // The project may never have been registered, which leaves its data in place.
+        if data_dir.exists() {
+            std::fs::remove_dir_all(&data_dir)?;
+        }
+    } else {
+        gitbutler_project::unregister(project_id)?;
+    }
+
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{}",
+            t.success.paint("✓ Removed the project from GitButler")
+        )?;
+        if purge {
+            writeln!(
+                out,
+                "{}",
+                t.success.paint(format!(
+                    "✓ Deleted GitButler data in {}",
+                    data_dir.display()
+                ))
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{}",
+                t.hint
+                    .paint(format!("Kept GitButler data in {}", data_dir.display()))
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{}",
+            t.success
+                .paint("✓ GitButler no longer manages this repository")
+        )?;
+        writeln!(out, "{}", t.info.paint("To use GitButler again, run:"))?;
+        writeln!(out, "  {}", t.command_suggestion.paint("but setup"))?;
+    }
+
+    if let Some(out) = out.for_json() {
+        out.write_value(&DeinitResult {
+            snapshot_id: left_workspace
+                .as_ref()
+                .map(|result| result.snapshot_id.clone()),
+            checked_out_branch: left_workspace.map(|result| result.checked_out_branch),
+            purged: purge,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Return the branch to check out if none was given, or `None` to let the teardown pick the only
+/// applied branch.
+/// With no applied stack this is the local branch of the target, and with more than one
+/// it's ambiguous.
+fn default_checkout_branch(ctx: &Context) -> CliResult<Option<String>> {
+    let Ok(stacks) = crate::legacy::workspace::applied_stacks(ctx) else {
+        // Let the teardown attempt to repair the workspace.
+        return Ok(None);
+    };
+    match stacks.len() {
+        0 => {}
+        1 => return Ok(None),
+        num_stacks => {
+            return Err(bad_input(format!(
+                "{num_stacks} stacks are applied, so it's unclear which branch to check out"
+            ))
+            .hint("Pass `--checkout <branch>` to choose the branch to check out")
+            .into());
+        }
+    }
+
+    let repo = ctx.repo.get()?;
+    let target_branch = ctx
+        .project_meta()?
+        .target_ref
+        .and_then(|target_ref| {
+            extract_remote_name_and_short_name(target_ref.as_ref(), &repo.remote_names())
+        })
+        .and_then(|(_remote_name, short_name)| short_name.to_str().ok().map(str::to_owned))
+        .filter(|short_name| {
+            repo.try_find_reference(format!("refs/heads/{short_name}").as_str())
+                .is_ok_and(|local_branch| local_branch.is_some())
+        });
+    match target_branch {
+        Some(target_branch) => Ok(Some(target_branch)),
+        None => Err(
+            bad_input("No branch is applied and the target has no local branch")
+                .hint("Pass `--checkout <branch>` to choose the branch to check out")
+                .into(),
+        ),
+    }
+}
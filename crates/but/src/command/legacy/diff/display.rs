@@ -1,9 +1,11 @@
+use bstr::ByteSlice as _;
 use but_core::{UnifiedPatch, ui, unified_diff::DiffHunk};
 use colored::ColoredString;
 
 use crate::command::legacy::status::status_letter_ui;
 use crate::id::WorktreeHunk;
 use crate::theme::Paint as _;
+use crate::utils::{binary_files::sanitize_for_terminal, quoted_path::display_path};
 
 fn path_with_color_ui(status: &ui::TreeStatus, path: String) -> ColoredString {
     let t = crate::theme::get();
@@ -28,31 +30,44 @@ pub(crate) trait DiffDisplay {
     ///
     /// This method generates a nicely formatted diff with colored output.
     /// If `cli_id` is provided, it will be displayed first in the output.
-    fn print_diff(&self, short_id: Option<&str>) -> String;
+    /// Paths are quoted like Git does, including bytes outside of ASCII if `quote_path` is set.
+    fn print_diff(&self, short_id: Option<&str>, quote_path: bool) -> String;
 }
 
 #[derive(Debug)]
 pub(crate) struct TreeChangeWithPatch {
     change: ui::TreeChange,
     patch: Option<UnifiedPatch>,
+    /// Shown instead of the diff of binary files, like `binary file changed (1 KiB -> 2 KiB)`.
+    binary_description: Option<String>,
 }
 
 impl TreeChangeWithPatch {
     pub fn new(change: ui::TreeChange, patch: Option<UnifiedPatch>) -> Self {
-        Self { change, patch }
+        Self {
+            change,
+            patch,
+            binary_description: None,
+        }
+    }
+
+    /// Show `description` instead of a generic note if the patch is binary.
+    pub fn with_binary_description(mut self, description: Option<String>) -> Self {
+        self.binary_description = description;
+        self
     }
 }
 
 impl DiffDisplay for TreeChangeWithPatch {
-    fn print_diff(&self, _cli_id: Option<&str>) -> String {
+    fn print_diff(&self, _cli_id: Option<&str>, quote_path: bool) -> String {
         let t = crate::theme::get();
         // Note: CLI IDs are per-hunk, so we don't display them for TreeChangeWithPatch
         // which shows file-level diffs with potentially multiple hunks.
         let mut output = String::new();
 
         let status = status_letter_ui(&self.change.status);
-        let path = path_with_color_ui(&self.change.status, self.change.path_bytes.to_string());
-        let path_str = self.change.path_bytes.to_string();
+        let path_str = display_path(self.change.path_bytes.as_bstr(), quote_path);
+        let path = path_with_color_ui(&self.change.status, path_str.clone());
 
         // Calculate the width needed for the box (status + space + filename)
         // We use the raw path length for width calculation since ANSI codes don't count
@@ -69,7 +84,11 @@ impl DiffDisplay for TreeChangeWithPatch {
         };
 
         if let Some(patch) = &self.patch {
-            output.push_str(&format_patch(patch, render_header));
+            output.push_str(&format_patch(
+                patch,
+                self.binary_description.as_deref(),
+                render_header,
+            ));
         } else {
             render_header(&mut output);
         }
@@ -81,7 +100,12 @@ impl DiffDisplay for TreeChangeWithPatch {
 ///
 /// This is a helper function for consistent patch formatting.
 /// The `render_header` function is called before each hunk to render the file header.
-fn format_patch(patch: &UnifiedPatch, render_header: impl Fn(&mut String)) -> String {
+/// Binary patches are shown as `binary_description` if available.
+fn format_patch(
+    patch: &UnifiedPatch,
+    binary_description: Option<&str>,
+    render_header: impl Fn(&mut String),
+) -> String {
     let t = crate::theme::get();
     let mut output = String::new();
     match patch {
@@ -89,7 +113,8 @@ fn format_patch(patch: &UnifiedPatch, render_header: impl Fn(&mut String)) -> St
             render_header(&mut output);
             output.push_str(&format!(
                 "   {}\n",
-                t.hint.paint("Binary file - no diff available")
+                t.hint
+                    .paint(binary_description.unwrap_or("Binary file - no diff available"))
             ));
         }
         UnifiedPatch::TooLarge { size_in_bytes } => {
@@ -125,7 +150,6 @@ fn format_patch(patch: &UnifiedPatch, render_header: impl Fn(&mut String)) -> St
 }
 
 fn fmt_hunk(hunk: &DiffHunk) -> String {
-    use bstr::ByteSlice;
     let t = crate::theme::get();
 
     let mut output = String::new();
@@ -160,6 +184,7 @@ fn fmt_hunk(hunk: &DiffHunk) -> String {
         };
 
         let content_str = content.to_str_lossy();
+        let content_str = sanitize_for_terminal(&content_str);
 
         match prefix {
             '+' => {
@@ -195,12 +220,13 @@ fn fmt_hunk(hunk: &DiffHunk) -> String {
 }
 
 impl DiffDisplay for WorktreeHunk {
-    fn print_diff(&self, short_id: Option<&str>) -> String {
+    fn print_diff(&self, short_id: Option<&str>, quote_path: bool) -> String {
         let t = crate::theme::get();
         let mut output = String::new();
+        let path = display_path(self.path_bytes.as_bstr(), quote_path);
 
         // Calculate the width needed for the box (id + space + filename)
-        let content_width = short_id.as_ref().map_or(0, |s| s.len() + 1) + self.path.len();
+        let content_width = short_id.as_ref().map_or(0, |s| s.len() + 1) + path.len();
 
         // Render box-style header:
         // ─────────╮
@@ -211,10 +237,10 @@ impl DiffDisplay for WorktreeHunk {
             output.push_str(&format!(
                 "{} {}│\n",
                 t.cli_id.paint(id),
-                t.important.paint(&self.path)
+                t.important.paint(&path)
             ));
         } else {
-            output.push_str(&format!("{}│\n", t.important.paint(&self.path)));
+            output.push_str(&format!("{}│\n", t.important.paint(&path)));
         }
         output.push_str(&format!("{}╯\n", t.hint.paint("─".repeat(content_width))));
 
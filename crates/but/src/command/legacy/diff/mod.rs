@@ -5,7 +5,7 @@ use crate::{
     CliId, IdMap,
     command::legacy::diff::show::Filter,
     id::{CommitId, CommittedFileId},
-    utils::OutputChannel,
+    utils::{OutputChannel, quoted_path::quote_path_enabled},
 };
 
 mod display;
@@ -64,6 +64,7 @@ pub fn handle(
 ) -> anyhow::Result<()> {
     let wt_changes = but_api::diff::changes_in_worktree(ctx, true)?;
     let id_map = IdMap::legacy_new_from_context(ctx, Some(wt_changes.assignments.clone()))?;
+    let quote_path = quote_path_enabled(&*ctx.repo.get()?);
 
     if let Some(entity) = target_str {
         let id = id_map
@@ -71,24 +72,32 @@ pub fn handle(
             .first() // TODO: handle ambiguity
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("No ID found for entity"))?;
+        id_map.remember_displayed_ids(ctx);
 
         match id {
             CliId::UncommittedHunkOrFile(id) => {
-                show::worktree(id_map, out, Some(Filter::Uncommitted(id)))
+                show::worktree(id_map, out, Some(Filter::Uncommitted(id)), quote_path)
             }
             CliId::PathPrefix {
                 hunk_assignments, ..
-            } => show::hunk_assignments(&hunk_assignments, out),
-            CliId::Uncommitted { .. } => show::worktree(id_map, out, Some(Filter::UncommittedArea)),
+            } => show::hunk_assignments(&hunk_assignments, out, quote_path),
+            CliId::Uncommitted { .. } => {
+                show::worktree(id_map, out, Some(Filter::UncommittedArea), quote_path)
+            }
             CliId::CommittedFile(CommittedFileId {
                 commit_id, path, ..
-            }) => show::commit(ctx, out, commit_id, Some(path)),
-            CliId::Branch(branch) => show::branch(ctx, out, branch.name),
-            CliId::Commit(CommitId { commit_id: id, .. }) => show::commit(ctx, out, id, None),
-            CliId::Stack { .. } => show::worktree(id_map, out, Some(Filter::UncommittedArea)),
+            }) => show::commit(ctx, out, commit_id, Some(path), quote_path),
+            CliId::Branch(branch) => show::branch(ctx, out, branch.name, quote_path),
+            CliId::Commit(CommitId { commit_id: id, .. }) => {
+                show::commit(ctx, out, id, None, quote_path)
+            }
+            CliId::Stack { .. } => {
+                show::worktree(id_map, out, Some(Filter::UncommittedArea), quote_path)
+            }
         }
     } else {
-        show::worktree(id_map, out, None)
+        id_map.remember_displayed_ids(ctx);
+        show::worktree(id_map, out, None, quote_path)
     }
 }
 
@@ -106,9 +115,15 @@ struct JsonChange {
     #[serde(skip_serializing_if = "Option::is_none")]
     id: Option<String>,
     path: String,
+    /// The raw bytes of `path`, only present if it isn't valid UTF-8 and `path` is lossy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path_bytes: Option<Vec<u8>>,
     status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     old_path: Option<String>,
+    /// The raw bytes of `old_path`, only present if it isn't valid UTF-8 and `old_path` is lossy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path_bytes: Option<Vec<u8>>,
     diff: JsonDiff,
 }
 
@@ -1,4 +1,4 @@
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice as _};
 use but_api::diff::ComputeLineStats;
 use but_core::{UnifiedPatch, unified_diff::DiffHunk};
 use but_ctx::Context;
@@ -10,7 +10,7 @@ use super::{
 use crate::{
     IdMap,
     id::{UncommittedHunkOrFile, WorktreeHunk},
-    utils::OutputChannel,
+    utils::{OutputChannel, binary_files::describe_binary_change},
 };
 
 #[expect(clippy::large_enum_variant)]
@@ -19,10 +19,13 @@ pub(crate) enum Filter {
     Uncommitted(UncommittedHunkOrFile),
 }
 
+/// Show the uncommitted hunks in `id_map` that pass `filter`, with paths quoted like Git does,
+/// including bytes outside of ASCII if `quote_path` is set.
 pub(crate) fn worktree(
     id_map: IdMap,
     out: &mut OutputChannel,
     filter: Option<Filter>,
+    quote_path: bool,
 ) -> anyhow::Result<()> {
     let short_id_assignment_pairs: Vec<(&str, &WorktreeHunk)> = id_map
         .uncommitted_hunks
@@ -43,24 +46,34 @@ pub(crate) fn worktree(
         })
         .map(|(short_id, uncommitted_hunk)| (short_id.as_str(), &uncommitted_hunk.hunk_assignment))
         .collect();
-    print_short_id_assignment_pairs(short_id_assignment_pairs, out)
+    print_short_id_assignment_pairs(short_id_assignment_pairs, out, quote_path)
 }
 
 pub(crate) fn hunk_assignments<'a>(
     hunk_assignments: impl IntoIterator<Item = &'a (String, WorktreeHunk)>,
     out: &mut OutputChannel,
+    quote_path: bool,
 ) -> anyhow::Result<()> {
     let short_id_assignment_pairs: Vec<(&str, &WorktreeHunk)> = hunk_assignments
         .into_iter()
         .map(|(short_id, hunk_assignment)| (short_id.as_str(), hunk_assignment))
         .collect();
-    print_short_id_assignment_pairs(short_id_assignment_pairs, out)
+    print_short_id_assignment_pairs(short_id_assignment_pairs, out, quote_path)
 }
 
 fn print_short_id_assignment_pairs<'a>(
     mut short_id_assignment_pairs: Vec<(&'a str, &'a WorktreeHunk)>,
     out: &mut OutputChannel,
+    quote_path: bool,
 ) -> anyhow::Result<()> {
+    // Changes without a textual diff, like binary files, can only be selected as a whole.
+    for (short_id, assignment) in &mut short_id_assignment_pairs {
+        if assignment.hunk_header.is_none()
+            && let Some((file_id, _hunk_id)) = short_id.split_once(':')
+        {
+            *short_id = file_id;
+        }
+    }
     short_id_assignment_pairs.sort_by(|(_, a_assignment), (_, b_assignment)| {
         a_assignment
             .path_bytes
@@ -85,7 +98,7 @@ fn print_short_id_assignment_pairs<'a>(
         json_out.write_value(output)?;
     } else if let Some(out) = out.for_human_or_shell() {
         for (short_id, assignment) in short_id_assignment_pairs {
-            write!(out, "{}", assignment.print_diff(Some(short_id)))?;
+            write!(out, "{}", assignment.print_diff(Some(short_id), quote_path))?;
         }
     }
     Ok(())
@@ -96,6 +109,7 @@ pub(crate) fn commit(
     out: &mut OutputChannel,
     id: gix::ObjectId,
     path: Option<BString>,
+    quote_path: bool,
 ) -> anyhow::Result<()> {
     let result = but_api::diff::commit_details(ctx, id, ComputeLineStats::No)?;
 
@@ -115,13 +129,18 @@ pub(crate) fn commit(
         let output = JsonDiffOutput { changes };
         json_out.write_value(output)?;
     } else if let Some(out) = out.for_human_or_shell() {
+        let repo = ctx.repo.get()?;
         for change in result.diff_with_first_parent {
             if path.as_ref().is_none_or(|p| p == &change.path) {
                 let patch = but_api::diff::tree_change_diffs(ctx, change.clone().into())
                     .ok()
                     .flatten();
-                let diff = TreeChangeWithPatch::new(change.into(), patch);
-                write!(out, "{}", diff.print_diff(None))?;
+                let description = describe_binary_change(&repo, &change, patch.as_ref())
+                    .ok()
+                    .flatten();
+                let diff = TreeChangeWithPatch::new(change.into(), patch)
+                    .with_binary_description(description);
+                write!(out, "{}", diff.print_diff(None, quote_path))?;
             }
         }
     }
@@ -132,6 +151,7 @@ pub(crate) fn branch(
     ctx: &Context,
     out: &mut OutputChannel,
     short_name: String,
+    quote_path: bool,
 ) -> anyhow::Result<()> {
     let result = but_api::branch::branch_diff(ctx, short_name)?;
 
@@ -150,13 +170,17 @@ pub(crate) fn branch(
         let output = JsonDiffOutput { changes };
         json_out.write_value(output)?;
     } else if let Some(out) = out.for_human_or_shell() {
+        let repo = ctx.repo.get()?;
         for change in result.changes {
             let patch = but_api::diff::tree_change_diffs(ctx, change.clone())
                 .ok()
                 .flatten();
 
-            let diff = TreeChangeWithPatch::new(change, patch);
-            write!(out, "{}", diff.print_diff(None))?;
+            let description = describe_binary_change(&repo, &change.clone().into(), patch.as_ref())
+                .ok()
+                .flatten();
+            let diff = TreeChangeWithPatch::new(change, patch).with_binary_description(description);
+            write!(out, "{}", diff.print_diff(None, quote_path))?;
         }
     }
     Ok(())
@@ -188,8 +212,10 @@ fn hunk_assignment_to_json(id: Option<&str>, assignment: &WorktreeHunk) -> JsonC
     JsonChange {
         id: id.map(str::to_string),
         path: assignment.path.clone(),
+        path_bytes: lossy_path_bytes(assignment.path_bytes.as_bstr()),
         status: "modified".to_owned(),
         old_path: None,
+        old_path_bytes: None,
         diff,
     }
 }
@@ -201,11 +227,19 @@ fn tree_change_to_json(
 ) -> JsonChange {
     use but_core::ui::TreeStatus;
 
-    let (status, old_path) = match &change.status {
-        TreeStatus::Addition { .. } => ("added", None),
-        TreeStatus::Deletion { .. } => ("deleted", None),
-        TreeStatus::Modification { .. } => ("modified", None),
-        TreeStatus::Rename { previous_path, .. } => ("renamed", Some(previous_path.to_string())),
+    let (status, old_path, old_path_bytes) = match &change.status {
+        TreeStatus::Addition { .. } => ("added", None, None),
+        TreeStatus::Deletion { .. } => ("deleted", None, None),
+        TreeStatus::Modification { .. } => ("modified", None, None),
+        TreeStatus::Rename {
+            previous_path,
+            previous_path_bytes,
+            ..
+        } => (
+            "renamed",
+            Some(previous_path.to_string()),
+            lossy_path_bytes(previous_path_bytes.as_bstr()),
+        ),
     };
 
     let diff = match patch {
@@ -228,15 +262,20 @@ fn tree_change_to_json(
     JsonChange {
         id: id.map(str::to_string),
         path: change.path_bytes.to_string(),
+        path_bytes: lossy_path_bytes(change.path_bytes.as_bstr()),
         status: status.to_owned(),
         old_path,
+        old_path_bytes,
         diff,
     }
 }
 
-fn hunk_to_json_hunk(hunk: &DiffHunk) -> JsonHunk {
-    use bstr::ByteSlice;
+/// The bytes of `path` if it isn't valid UTF-8, so it can't be represented as string without loss.
+fn lossy_path_bytes(path: &BStr) -> Option<Vec<u8>> {
+    path.to_str().is_err().then(|| path.to_vec())
+}
 
+fn hunk_to_json_hunk(hunk: &DiffHunk) -> JsonHunk {
     JsonHunk {
         old_start: hunk.old_start,
         old_lines: hunk.old_lines,
@@ -150,7 +150,7 @@ impl DiffLineWriter for DiffWriter<'_> {
                     let rendered = line_style.patch(span.style).paint(&span.content);
                     write!(self.out, "{rendered}")?;
                 }
-                if line_style.bg.is_some() && colored::control::SHOULD_COLORIZE.should_colorize() {
+                if line_style.bg.is_some() && crate::theme::should_colorize() {
                     write!(self.out, "{}", line_style.paint(CLEAR_TO_END_OF_LINE))?;
                 }
                 writeln!(self.out)?;
@@ -192,6 +192,7 @@ pub fn diff<'a>(
     let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
 
     let op = resolve(ctx, &id_map, args)?;
+    id_map.remember_displayed_ids(ctx);
     Ok(run(ctx, op)?)
 }
 
@@ -227,7 +228,7 @@ fn resolve(ctx: &Context, id_map: &IdMap, args: Platform) -> CliResult<DiffOpera
             path,
             id,
         }),
-        ResolvedCliIdArg::Stack => {
+        ResolvedCliIdArg::Stack(_) => {
             Err(bad_input("viewing diffs for stack assignments is not supported").into())
         }
         ResolvedCliIdArg::PathPrefix => {
@@ -0,0 +1,321 @@
+//! Implementation of the `but edit` command, which checks out a commit into the worktree
+//! so it can be modified directly.
+
+use std::fmt::Write;
+
+use anyhow::{Context as _, Result, bail};
+use bstr::ByteSlice;
+use but_api::legacy::modes::{
+    abort_edit_and_return_to_workspace, clear_leftover_edit_mode_state_with_output,
+    edit_changes_from_initial, enter_edit_mode, operating_mode,
+    save_edit_and_return_to_workspace_with_output,
+};
+use but_ctx::Context;
+use gitbutler_commit::commit_ext::CommitExt;
+use gitbutler_edit_mode::UNCOMMITTED_CHANGES_REF;
+use gitbutler_operating_modes::{EDIT_BRANCH_REF, EditModeMetadata, OperatingMode};
+
+use crate::{
+    CliResult,
+    args::Subcommands,
+    bad_input,
+    command::legacy::{resolve, rub::undo::stack_id_by_commit_id},
+    theme::{self, Paint},
+    utils::{OutputChannel, get_change_id_for_commit},
+};
+
+/// Enter edit mode for the commit identified by `commit_id_str`.
+///
+/// Uncommitted changes are saved away by edit mode itself and restored when it is left.
+pub(crate) fn handle(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    commit_id_str: &str,
+) -> Result<()> {
+    let t = theme::get();
+    if let OperatingMode::Edit(metadata) = operating_mode(ctx)?.operating_mode {
+        bail!(
+            "Already editing commit {}. Finish that edit before starting another one.",
+            commit_ref(ctx, metadata.commit_oid)?
+        );
+    }
+
+    let (commit_id, commit_ref) = resolve::parse_commit_id(ctx, commit_id_str)?;
+    if ctx.repo.get()?.find_commit(commit_id)?.is_conflicted() {
+        bail!(
+            "Commit {commit_ref} is conflicted. Run `but resolve {commit_id_str}` to resolve it instead."
+        );
+    }
+    let stack_id = {
+        let guard = ctx.shared_worktree_access();
+        stack_id_by_commit_id(ctx, guard.read_permission(), commit_id)?
+    };
+
+    enter_edit_mode(ctx, commit_id, stack_id).context("Failed to enter edit mode")?;
+
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{} {}",
+            t.important.paint("Editing commit"),
+            commit_ref
+        )?;
+        writeln!(
+            out,
+            "Your uncommitted changes were saved and will be restored when you leave edit mode."
+        )?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "Modify the files in the worktree, then run {} to amend them into the commit,",
+            t.command_suggestion.paint("but edit --continue")
+        )?;
+        writeln!(
+            out,
+            "or {} to discard them.",
+            t.command_suggestion.paint("but edit --abort")
+        )?;
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "commit_id": commit_id.to_string(),
+            "stack_id": stack_id.to_string(),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Amend the worktree into the commit being edited, rebase everything on top of it and
+/// return to the workspace with the previously uncommitted changes restored.
+///
+/// If edit mode isn't active, leftovers of an interrupted previous attempt are cleaned up instead.
+pub(crate) fn continue_edit(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
+    let t = theme::get();
+    let OperatingMode::Edit(metadata) = operating_mode(ctx)?.operating_mode else {
+        return nothing_to_do(ctx, out);
+    };
+
+    let new_commit_id = save_edit_and_return_to_workspace_with_output(ctx)
+        .context("Failed to save edits and return to workspace")?;
+
+    if let Some(out) = out.for_human() {
+        let old_ref = commit_ref(ctx, metadata.commit_oid)?;
+        if new_commit_id == metadata.commit_oid {
+            writeln!(
+                out,
+                "{} {old_ref} is unchanged as no files were modified.",
+                t.attention.paint("Commit")
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{} {old_ref} → {}",
+                t.success.paint("Updated commit"),
+                commit_ref(ctx, new_commit_id)?
+            )?;
+        }
+        writeln!(out, "Workspace restored with your uncommitted changes.")?;
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "commit_id": metadata.commit_oid.to_string(),
+            "new_commit_id": new_commit_id.to_string(),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Discard all edits to the commit being edited and restore the workspace as it was.
+///
+/// If edit mode isn't active, leftovers of an interrupted previous attempt are cleaned up instead.
+pub(crate) fn abort_edit(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
+    let t = theme::get();
+    let OperatingMode::Edit(metadata) = operating_mode(ctx)?.operating_mode else {
+        return nothing_to_do(ctx, out);
+    };
+
+    abort_edit_and_return_to_workspace(ctx, true)
+        .context("Failed to abort edit and return to workspace")?;
+
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{} {}, it is unchanged.",
+            t.attention.paint("Stopped editing commit"),
+            commit_ref(ctx, metadata.commit_oid)?
+        )?;
+        writeln!(out, "Workspace restored with your uncommitted changes.")?;
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "commit_id": metadata.commit_oid.to_string(),
+            "new_commit_id": metadata.commit_oid.to_string(),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Report that no commit is being edited, after removing what an interrupted attempt to
+/// leave edit mode may have left behind.
+///
+/// Uncommitted changes that were stashed when entering edit mode are kept, and the user is told
+/// how to restore them.
+fn nothing_to_do(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
+    let t = theme::get();
+    let leftovers = clear_leftover_edit_mode_state_with_output(ctx)?;
+    if let Some(out) = out.for_human() {
+        if leftovers.cleaned_up {
+            writeln!(
+                out,
+                "Removed leftover state of an interrupted edit. {}",
+                t.hint.paint("Nothing else to do.")
+            )?;
+        } else {
+            writeln!(out, "No commit is being edited, nothing to do.")?;
+        }
+        if leftovers.stashed_changes.is_some() {
+            writeln!(
+                out,
+                "{}",
+                t.attention.paint(format!(
+                    "Uncommitted changes from before the edit are still stashed in {UNCOMMITTED_CHANGES_REF}."
+                ))
+            )?;
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(format!(
+                    "Restore them with `git restore --source={UNCOMMITTED_CHANGES_REF} --worktree -- .`, then remove the stash with `git update-ref -d {UNCOMMITTED_CHANGES_REF}`."
+                ))
+            )?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "commit_id": null,
+            "new_commit_id": null,
+            "cleaned_up_leftover_state": leftovers.cleaned_up,
+            "stashed_changes": leftovers.stashed_changes.map(|id| id.to_string()),
+        }))?;
+    }
+    Ok(())
+}
+
+/// Show which commit is being edited and which files were changed so far.
+///
+/// Used by `but status` while in edit mode on a commit that isn't conflicted.
+pub(crate) fn show_status(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    metadata: &EditModeMetadata,
+) -> Result<()> {
+    let t = theme::get();
+    let changes = edit_changes_from_initial(ctx).context("Failed to get edited files")?;
+
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{} {}",
+            t.important.paint("You are currently editing commit"),
+            commit_ref(ctx, metadata.commit_oid)?
+        )?;
+        writeln!(
+            out,
+            " - modify files in the worktree\n - finalize with {}\n - OR cancel with {}\n",
+            t.success.paint("but edit --continue"),
+            t.error.paint("but edit --abort")
+        )?;
+        if changes.is_empty() {
+            writeln!(out, "{}", t.hint.paint("No files changed yet."))?;
+        } else {
+            writeln!(out, "{}:", t.attention.paint("Changed files"))?;
+            for change in &changes {
+                writeln!(out, "  {}", change.path.to_str_lossy())?;
+            }
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "edit_mode": {
+                "commit_id": metadata.commit_oid.to_string(),
+                "stack_id": metadata.stack_id.to_string(),
+                "changed_files": changes
+                    .iter()
+                    .map(|change| change.path.to_str_lossy().to_string())
+                    .collect::<Vec<_>>(),
+            }
+        }))?;
+    }
+    Ok(())
+}
+
+/// Return `true` if `cmd` changes the workspace and thus can't run while a commit is edited.
+///
+/// Inspection commands, and the commands used to leave edit mode, remain available.
+pub(crate) fn is_refused_in_edit_mode(cmd: &Subcommands) -> bool {
+    use crate::args::{branch, oplog, rule};
+    match cmd {
+        Subcommands::Commit(_)
+        | Subcommands::_Commit2(_)
+        | Subcommands::_Squash2(_)
+        | Subcommands::_Move2(_)
+        | Subcommands::Land { .. }
+        | Subcommands::Discard { .. }
+        | Subcommands::Unapply { .. }
+        | Subcommands::Apply { .. }
+        | Subcommands::Push(_)
+        | Subcommands::Pull { .. }
+        | Subcommands::Rub { .. }
+        | Subcommands::Assign { .. }
+        | Subcommands::Unassign { .. }
+        | Subcommands::Absorb { .. }
+        | Subcommands::Reword { .. }
+        | Subcommands::Uncommit { .. }
+        | Subcommands::Amend { .. }
+        | Subcommands::Squash { .. }
+        | Subcommands::Move { .. }
+        | Subcommands::Undo
+        | Subcommands::Redo
+        | Subcommands::Teardown { .. }
+        | Subcommands::Clean { .. }
+        | Subcommands::Pick { .. } => true,
+        Subcommands::Edit { commit, .. } => commit.is_some(),
+        Subcommands::Branch(branch::Platform { cmd }) => matches!(
+            cmd,
+            Some(
+                branch::Subcommands::New { .. }
+                    | branch::Subcommands::Delete { .. }
+                    | branch::Subcommands::Apply { .. }
+                    | branch::Subcommands::Unapply { .. }
+                    | branch::Subcommands::Update { .. }
+            )
+        ),
+        Subcommands::Rule(rule::Platform { cmd }) => matches!(
+            cmd,
+            Some(rule::Subcommands::Add { .. } | rule::Subcommands::Remove { .. })
+        ),
+        Subcommands::Oplog(oplog::Platform { cmd }) => matches!(
+            cmd,
+            Some(oplog::Subcommands::Snapshot { .. } | oplog::Subcommands::Restore { .. })
+        ),
+        _ => false,
+    }
+}
+
+/// Fail with a hint on how to leave edit mode if the repository at `current_dir` is in edit mode.
+///
+/// Repositories that can't be discovered pass, so the command itself can report that.
+pub(crate) fn ensure_not_in_edit_mode(current_dir: &std::path::Path) -> CliResult<()> {
+    let in_edit_mode = gix::discover(current_dir)
+        .ok()
+        .and_then(|repo| repo.head_name().ok().flatten())
+        .is_some_and(|head_name| head_name.as_bstr() == EDIT_BRANCH_REF);
+    if in_edit_mode {
+        return Err(
+            bad_input("This command can't run while a commit is being edited.")
+                .hint("run `but edit --continue` to apply your edits, or `but edit --abort` to discard them")
+                .into(),
+        );
+    }
+    Ok(())
+}
+
+fn commit_ref(ctx: &Context, commit_id: gix::ObjectId) -> Result<String> {
+    let repo = ctx.repo.get()?;
+    Ok(theme::Commit(commit_id, Some(get_change_id_for_commit(&repo, commit_id)?)).to_string())
+}
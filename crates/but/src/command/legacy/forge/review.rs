@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use bstr::{BStr, ByteSlice};
-use but_core::ref_metadata::StackId;
+use but_core::{RefMetadata as _, ref_metadata::StackId};
 use but_ctx::Context;
 use but_workspace::ui::Commit;
 use gitbutler_project::Project;
@@ -230,6 +230,135 @@ pub async fn set_draftiness(
     Ok(())
 }
 
+/// Open the review of `branch` in the browser, or just print its URL if `print` is `true`.
+///
+/// Without `branch`, the review of the only applied branch with a review is opened, or the
+/// applied branches with reviews are listed if there are several.
+pub fn open_review(
+    ctx: &mut Context,
+    branch: Option<String>,
+    print: bool,
+    out: &mut OutputChannel,
+) -> anyhow::Result<()> {
+    let t = theme::get();
+    let cache_config = crate::command::legacy::branch::ReviewSource::Cache.cache_config(ctx)?;
+    let review_map = get_review_map(ctx, Some(cache_config))?;
+
+    let (branch_name, review) = match branch {
+        Some(branch) => {
+            let branch_name = match get_branch_names(&ctx.legacy_project, &branch) {
+                Ok(names) => names.into_iter().next().unwrap_or(branch),
+                // Unapplied branches may still have a review.
+                Err(_) if review_map.contains_key(&branch) => branch,
+                Err(err) => return Err(err),
+            };
+            let Some(review) = review_map
+                .get(&branch_name)
+                .and_then(|reviews| reviews.first())
+            else {
+                anyhow::bail!(
+                    "Branch '{branch_name}' has no review yet.\nRun 'but pr create {branch_name}' to create one."
+                );
+            };
+            (branch_name, review.clone())
+        }
+        None => {
+            let applied_stacks = crate::legacy::workspace::applied_stacks(ctx)?;
+            let mut candidates = applied_stacks
+                .iter()
+                .flat_map(|stack| &stack.branches)
+                .filter_map(|branch| {
+                    let review = review_map.get(&branch.name)?.first()?;
+                    Some((branch.name.clone(), review.clone()))
+                })
+                .collect::<Vec<_>>();
+            match candidates.len() {
+                0 => anyhow::bail!(
+                    "No branch in the workspace has a review yet.\nRun 'but pr create <branch>' to create one."
+                ),
+                1 => candidates.remove(0),
+                _ => {
+                    if let Some(out) = out.for_json() {
+                        out.write_value(
+                            candidates
+                                .iter()
+                                .map(|(branch, review)| OpenedReview::new(branch, review))
+                                .collect::<Vec<_>>(),
+                        )?;
+                    } else if let Some(out) = out.for_shell() {
+                        for (_, review) in &candidates {
+                            writeln!(out, "{}", review.html_url)?;
+                        }
+                    } else if let Some(out) = out.for_human() {
+                        writeln!(out, "Multiple branches have reviews:")?;
+                        for (branch, review) in &candidates {
+                            writeln!(
+                                out,
+                                "  {} {}{} {}",
+                                t.local_branch.paint(branch),
+                                t.pr_number.paint(&review.unit_symbol),
+                                t.pr_number.paint(review.number.to_string()),
+                                t.link.paint(&review.html_url)
+                            )?;
+                        }
+                        writeln!(
+                            out,
+                            "{}",
+                            t.hint
+                                .paint("Run 'but pr open <branch>' to open one of them.")
+                        )?;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    if !print {
+        but_api::open::open_url(review.html_url.clone())?;
+    }
+
+    if let Some(out) = out.for_json() {
+        out.write_value(OpenedReview::new(&branch_name, &review))?;
+    } else if let Some(out) = out.for_shell() {
+        writeln!(out, "{}", review.html_url)?;
+    } else if let Some(out) = out.for_human() {
+        if print {
+            writeln!(out, "{}", review.html_url)?;
+        } else {
+            writeln!(
+                out,
+                "{} Opened {}{} of {} {}",
+                t.sym().success,
+                t.pr_number.paint(&review.unit_symbol),
+                t.pr_number.paint(review.number.to_string()),
+                t.local_branch.paint(&branch_name),
+                t.link.paint(&review.html_url)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// A review as shown by [`open_review()`] in JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenedReview {
+    branch: String,
+    number: i64,
+    url: String,
+}
+
+impl OpenedReview {
+    fn new(branch: &str, review: &but_forge::ForgeReview) -> Self {
+        OpenedReview {
+            branch: branch.to_owned(),
+            number: review.number,
+            url: review.html_url.clone(),
+        }
+    }
+}
+
 /// Set the review template for the given project.
 pub fn set_review_template(
     ctx: &mut Context,
@@ -297,6 +426,7 @@ pub async fn create_review(
     default: bool,
     draft: bool,
     message: Option<ForgeReviewMessage>,
+    overrides: ReviewMessageOverrides,
     out: &mut OutputChannel,
 ) -> anyhow::Result<()> {
     // Fail fast if no forge user is authenticated, before pushing or prompting.
@@ -351,6 +481,7 @@ pub async fn create_review(
         default,
         draft,
         message.as_ref(),
+        &overrides,
         out,
         maybe_branch_names,
     )
@@ -467,6 +598,7 @@ pub async fn handle_multiple_branches_in_workspace(
     default_message: bool,
     draft: bool,
     message: Option<&ForgeReviewMessage>,
+    overrides: &ReviewMessageOverrides,
     out: &mut OutputChannel,
     selected_branches: Option<Vec<String>>,
 ) -> anyhow::Result<()> {
@@ -512,6 +644,7 @@ pub async fn handle_multiple_branches_in_workspace(
             default_message,
             draft,
             message,
+            overrides,
             out,
         )
         .await?;
@@ -523,10 +656,11 @@ pub async fn handle_multiple_branches_in_workspace(
         overall_outcome.review_sync.extend(outcome.review_sync);
     }
 
+    let hyperlinks = out.is_terminal() && crate::tui::text::terminal_supports_hyperlinks();
     if let Some(out) = out.for_json() {
         out.write_value(overall_outcome)?;
     } else if let Some(out) = out.for_human() {
-        display_review_publication_summary(overall_outcome, out)?;
+        display_review_publication_summary(overall_outcome, hyperlinks, out)?;
     }
 
     Ok(())
@@ -611,6 +745,7 @@ async fn publish_reviews_for_branch_and_dependents(
     default_message: bool,
     draft: bool,
     message: Option<&ForgeReviewMessage>,
+    overrides: &ReviewMessageOverrides,
     out: &mut OutputChannel,
 ) -> Result<PublishReviewsOutcome, anyhow::Error> {
     let t = theme::get();
@@ -685,6 +820,7 @@ async fn publish_reviews_for_branch_and_dependents(
             message_plan.default_message,
             draft,
             message_plan.message,
+            (branch.name == branch_name).then_some(overrides),
         )
         .await?;
         match published_review {
@@ -719,15 +855,17 @@ async fn publish_reviews_for_branch_and_dependents(
 }
 
 /// Display a summary of published and already existing reviews for humans
+/// URLs are clickable links if `hyperlinks` is `true`.
 fn display_review_publication_summary(
     outcome: PublishReviewsOutcome,
+    hyperlinks: bool,
     out: &mut dyn std::fmt::Write,
 ) -> std::fmt::Result {
     // Show newly published PRs
     if !outcome.published.is_empty() {
         writeln!(out)?;
         for review in &outcome.published {
-            print_new_pr_info(review, out)?;
+            print_new_pr_info(review, hyperlinks, out)?;
         }
     }
 
@@ -735,7 +873,7 @@ fn display_review_publication_summary(
     if !outcome.already_existing.is_empty() {
         writeln!(out)?;
         for review in &outcome.already_existing {
-            print_existing_pr_info(review, out)?;
+            print_existing_pr_info(review, hyperlinks, out)?;
         }
     }
 
@@ -755,6 +893,7 @@ fn display_review_publication_summary(
 /// Print information about a newly created PR
 fn print_new_pr_info(
     review: &but_forge::ForgeReview,
+    hyperlinks: bool,
     out: &mut dyn std::fmt::Write,
 ) -> std::fmt::Result {
     let t = theme::get();
@@ -777,7 +916,7 @@ fn print_new_pr_info(
         out,
         "  {} {}",
         t.hint.paint("URL:"),
-        t.link.paint(&review.html_url)
+        review_url(review, hyperlinks)
     )?;
     if review.draft {
         writeln!(out, "  {}", t.hint.paint("Draft only"))?;
@@ -789,6 +928,7 @@ fn print_new_pr_info(
 /// Print information about an existing PR
 fn print_existing_pr_info(
     review: &but_forge::ForgeReview,
+    hyperlinks: bool,
     out: &mut dyn std::fmt::Write,
 ) -> std::fmt::Result {
     let t = theme::get();
@@ -804,12 +944,22 @@ fn print_existing_pr_info(
         out,
         "  {} {}",
         t.hint.paint("URL:"),
-        t.link.paint(&review.html_url)
+        review_url(review, hyperlinks)
     )?;
 
     Ok(())
 }
 
+/// The URL of `review` for display, as clickable link if `hyperlinks` is `true`.
+fn review_url(review: &but_forge::ForgeReview, hyperlinks: bool) -> String {
+    let url = theme::get().link.paint(&review.html_url).to_string();
+    if hyperlinks {
+        crate::tui::text::hyperlink(&url, &review.html_url)
+    } else {
+        url
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PublishReviewsOutcome {
@@ -850,6 +1000,20 @@ pub struct ForgeReviewMessage {
     pub body: String,
 }
 
+/// Parts of the review message given on the command line, the others use their default.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewMessageOverrides {
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+impl ReviewMessageOverrides {
+    /// Return `true` if no part of the message is overridden.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.body.is_none()
+    }
+}
+
 pub fn parse_review_message(content: &str) -> anyhow::Result<ForgeReviewMessage> {
     let mut lines = content.lines();
     let title = lines.next().unwrap_or("").trim().to_string();
@@ -880,6 +1044,7 @@ async fn publish_review_for_branch(
     default_message: bool,
     draft: bool,
     message: Option<&ForgeReviewMessage>,
+    overrides: Option<&ReviewMessageOverrides>,
 ) -> anyhow::Result<PublishReviewResult> {
     // Check if a review already exists for the branch.
     // If it does, skip publishing a new review.
@@ -897,19 +1062,35 @@ async fn publish_review_for_branch(
         return Ok(PublishReviewResult::AlreadyExists(vec![review]));
     }
 
-    let commit = default_commit(ctx, stack_id, branch_name)?;
+    let description = branch_description(ctx, branch_name)?;
     let (title, body) = if let Some(message) = message {
         (message.title.clone(), message.body.clone())
     } else if default_message {
-        let title = extract_commit_title(commit.as_ref())
-            .map(|t| t.to_string())
-            .unwrap_or(branch_name.to_string());
-        let body = extract_commit_description(commit.as_ref())
-            .map(|b| b.join("\n"))
-            .unwrap_or_default();
-        (title, body)
+        let commits = branch_commits(ctx, stack_id, branch_name)?;
+        let commit_messages = commits
+            .iter()
+            .map(|commit| commit.message.as_bstr())
+            .collect::<Vec<_>>();
+        let (title, body) = default_title_and_body(
+            branch_name,
+            &commit_messages,
+            description.as_deref(),
+            ctx.settings.reviews.auto_fill_pr_description_from_commit,
+        );
+        let overrides = overrides.cloned().unwrap_or_default();
+        (
+            overrides.title.unwrap_or(title),
+            overrides.body.unwrap_or(body),
+        )
     } else {
-        get_pr_title_and_body_from_editor(ctx, stack_id, commit.as_ref(), branch_name)?
+        let commit = default_commit(ctx, stack_id, branch_name)?;
+        get_pr_title_and_body_from_editor(
+            ctx,
+            stack_id,
+            commit.as_ref(),
+            description.as_deref(),
+            branch_name,
+        )?
     };
 
     // Publish a new review for the branch
@@ -930,6 +1111,41 @@ async fn publish_review_for_branch(
     .map(|review| PublishReviewResult::Published(Box::new(review)))
 }
 
+/// The default title and body of the review for `branch_name` with `commit_messages`, top-most first.
+///
+/// The title is the subject of the top-most commit, and the body is the branch `description`.
+/// Without description, the body of a single commit is used if `auto_fill_from_commit` is `true`.
+fn default_title_and_body(
+    branch_name: &str,
+    commit_messages: &[&BStr],
+    description: Option<&str>,
+    auto_fill_from_commit: bool,
+) -> (String, String) {
+    let title = extract_commit_title(commit_messages.first().copied())
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or(branch_name)
+        .to_string();
+    let body = match description {
+        Some(description) => description.to_string(),
+        None if auto_fill_from_commit && commit_messages.len() == 1 => {
+            extract_commit_description(commit_messages.first().copied())
+                .map(|b| b.join("\n"))
+                .unwrap_or_default()
+        }
+        None => String::new(),
+    };
+    (title, body)
+}
+
+/// Get the description of the local branch named `branch_name`, if it has one.
+fn branch_description(ctx: &Context, branch_name: &str) -> anyhow::Result<Option<String>> {
+    let ref_name = gix::refs::Category::LocalBranch.to_full_name(branch_name)?;
+    Ok(ctx
+        .meta()?
+        .branch_opt(ref_name.as_ref())?
+        .and_then(|branch| branch.description.clone()))
+}
+
 /// Get the default commit for the branch, if it has exactly one commit.
 fn default_commit(
     ctx: &Context,
@@ -972,13 +1188,14 @@ fn get_pr_title_and_body_from_editor(
     ctx: &Context,
     stack_id: Option<StackId>,
     commit: Option<&Commit>,
+    branch_description: Option<&str>,
     branch_name: &str,
 ) -> anyhow::Result<(String, String)> {
     let mut template = String::new();
 
     // Use the first line of the commit message as the default title if available
-    let commit_title =
-        extract_commit_title(commit).map(|s| s.replace(HTML_COMMENT_START_MARKER, "<\\!--"));
+    let commit_title = extract_commit_title(commit.map(|c| c.message.as_bstr()))
+        .map(|s| s.replace(HTML_COMMENT_START_MARKER, "<\\!--"));
     if let Some(commit_title) = commit_title {
         template.push_str(&commit_title);
     } else {
@@ -989,9 +1206,12 @@ fn get_pr_title_and_body_from_editor(
     // Add a blank line between title and description
     template.push('\n');
 
-    // Use commit description as template if available
-    let commit_description = extract_commit_description(commit);
-    if let Some(commit_description) = commit_description {
+    // Use the branch or commit description as template if available
+    let commit_description = extract_commit_description(commit.map(|c| c.message.as_bstr()));
+    if let Some(branch_description) = branch_description {
+        template.push_str(branch_description);
+        template.push('\n');
+    } else if let Some(commit_description) = commit_description {
         for line in commit_description {
             template.push_str(line);
             template.push('\n');
@@ -1081,10 +1301,9 @@ HTML comments are stripped before submit.
 }
 
 /// Extract the commit description (body) from the commit message, skipping the first line (title).
-fn extract_commit_description(commit: Option<&Commit>) -> Option<Vec<&str>> {
-    commit.and_then(|c| {
-        let desc_lines: Vec<&str> = c
-            .message
+fn extract_commit_description(message: Option<&BStr>) -> Option<Vec<&str>> {
+    message.and_then(|message| {
+        let desc_lines: Vec<&str> = message
             .lines()
             .skip(1)
             .skip_while(|l| l.trim().is_empty())
@@ -1099,8 +1318,8 @@ fn extract_commit_description(commit: Option<&Commit>) -> Option<Vec<&str>> {
 }
 
 /// Extract the commit title from the commit message (first line).
-fn extract_commit_title(commit: Option<&Commit>) -> Option<&str> {
-    commit.and_then(|c| c.message.lines().next().and_then(|l| l.to_str().ok()))
+fn extract_commit_title(message: Option<&BStr>) -> Option<&str> {
+    message.and_then(|message| message.lines().next().and_then(|l| l.to_str().ok()))
 }
 
 /// Get a mapping from branch names to their associated reviews.
@@ -1396,6 +1615,50 @@ mod tests {
         assert!(plan.message.is_none());
     }
 
+    #[test]
+    fn default_title_and_body_uses_top_commit_subject_and_branch_description() {
+        let messages = ["top\n\ntop body".into(), "bottom".into()];
+        assert_eq!(
+            default_title_and_body("feat", &messages, Some("Adds feat"), true),
+            ("top".to_string(), "Adds feat".to_string())
+        );
+        assert_eq!(
+            default_title_and_body("feat", &messages, None, true),
+            ("top".to_string(), String::new()),
+            "commit bodies are only used for single-commit branches"
+        );
+    }
+
+    #[test]
+    fn default_title_and_body_fills_body_from_single_commit_if_enabled() {
+        let messages = ["title\n\nfirst\nsecond".into()];
+        assert_eq!(
+            default_title_and_body("feat", &messages, None, true),
+            ("title".to_string(), "first\nsecond".to_string())
+        );
+        assert_eq!(
+            default_title_and_body("feat", &messages, None, false),
+            ("title".to_string(), String::new())
+        );
+        assert_eq!(
+            default_title_and_body("feat", &messages, Some("described"), true),
+            ("title".to_string(), "described".to_string()),
+            "the branch description takes precedence"
+        );
+    }
+
+    #[test]
+    fn default_title_and_body_falls_back_to_branch_name() {
+        assert_eq!(
+            default_title_and_body("feat", &[], None, true),
+            ("feat".to_string(), String::new())
+        );
+        assert_eq!(
+            default_title_and_body("feat", &["\n\nbody".into()], None, false),
+            ("feat".to_string(), String::new())
+        );
+    }
+
     #[test]
     fn parse_pr_message_title_only() {
         let msg = parse_review_message("My PR Title").unwrap();
@@ -7,15 +7,19 @@ use bstr::ByteSlice;
 pub mod absorb;
 pub mod actions;
 pub mod ai;
+pub mod assign;
 pub mod branch;
+pub mod claude;
 pub mod clean;
 pub mod commit;
 pub mod commit2;
 pub mod commit_message_prep;
+pub mod deinit;
 pub mod diff;
 #[cfg(feature = "legacy")]
 pub mod diff2;
 pub mod discard;
+pub mod edit;
 pub mod forge;
 pub mod land;
 pub mod mcp;
@@ -29,9 +33,11 @@ pub mod resolve;
 pub mod reword;
 pub mod reword2;
 pub mod rub;
+pub mod rule;
 pub mod setup;
 pub mod show;
 pub mod squash2;
+pub mod stack;
 pub mod status;
 pub mod teardown;
 pub mod unapply;
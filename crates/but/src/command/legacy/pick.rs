@@ -17,7 +17,7 @@ use gix::{revision::walk::Sorting, traverse::commit::simple::CommitTimeOrder};
 use crate::{
     CliId, IdMap,
     command::legacy::workspace_target,
-    id::CommitId,
+    id::{CommitId, parser::list_matches},
     utils::{OutputChannel, WriteWithUtils, shorten_hex_object_id, shorten_object_id},
 };
 
@@ -184,10 +184,18 @@ fn resolve_source_commits(
     // Try using IdMap for CLI IDs
     let cli_ids = id_map.parse_using_context(source, ctx)?;
 
-    for cli_id in &cli_ids {
-        if let CliId::Commit(CommitId { commit_id, .. }) = cli_id {
-            return Ok(vec![*commit_id]);
-        }
+    let commits: Vec<_> = cli_ids
+        .iter()
+        .filter(|cli_id| matches!(cli_id, CliId::Commit(_)))
+        .cloned()
+        .collect();
+    match commits.as_slice() {
+        [] => {}
+        [CliId::Commit(CommitId { commit_id, .. })] => return Ok(vec![*commit_id]),
+        _ => bail!(
+            "Source '{source}' is ambiguous, matches multiple commits:\n{}",
+            list_matches(&commits, &*ctx.repo.get()?)
+        ),
     }
 
     // Fall back to git revision (handles full SHA, short SHA, refs)
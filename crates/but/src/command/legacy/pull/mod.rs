@@ -10,11 +10,16 @@ use json::{BaseBranchInfo, BranchStatusInfo, PullCheckOutput, UpstreamCommit, Up
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    command::legacy::upstream::{
-        self, BranchStatus as PullBranchStatus, BranchStatusInfo as PullBranchStatusInfo,
+    command::legacy::{
+        upstream::{
+            self, BranchStatus as PullBranchStatus, BranchStatusInfo as PullBranchStatusInfo,
+        },
+        workspace_target,
     },
     theme::{self, Paint},
-    utils::{OutputChannel, shorten_hex_object_id},
+    utils::{
+        OutputChannel, notifications::SlowOperation, shorten_hex_object_id, shorten_object_id,
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,15 +78,39 @@ struct PullSummary {
     branches_unchanged: usize,
 }
 
+/// Update the workspace with upstream changes, or only fetch and report them if `check_only` is
+/// set. If `notify` is set, a desktop notification tells about the outcome if it was slow.
 pub async fn handle(
     ctx: &mut Context,
     out: &mut OutputChannel,
     check_only: bool,
+    notify: bool,
 ) -> anyhow::Result<()> {
-    if check_only {
+    let operation = SlowOperation::start(if check_only { "Fetch" } else { "Pull" }, notify);
+    let result = if check_only {
         handle_check(ctx, out).await
     } else {
         handle_pull(ctx, out).await
+    };
+    operation.finish(|| target_subject(ctx), &result);
+    result
+}
+
+/// Describe the target branch along with the commit it points to for notifications.
+fn target_subject(ctx: &Context) -> String {
+    let guard = ctx.shared_worktree_access();
+    let Ok(target) =
+        workspace_target::ResolvedTarget::resolve_with_perm(ctx, guard.read_permission())
+    else {
+        return "Target branch".to_owned();
+    };
+    let commit = match ctx.repo.get() {
+        Ok(repo) => shorten_object_id(&repo, target.oid()),
+        Err(_) => target.oid().to_hex_with_len(7).to_string(),
+    };
+    match target.display_name() {
+        Some(name) => format!("{name} at {commit}"),
+        None => format!("Target at {commit}"),
     }
 }
 
@@ -9,9 +9,11 @@ use serde::Serialize;
 use crate::{
     CliId, IdMap,
     args::{push, push::Command},
-    command::legacy::workspace_target,
+    command::legacy::{setup, workspace_target},
     theme::{self, Paint},
-    utils::{OutputChannel, shorten_hex_object_id, shorten_object_id},
+    utils::{
+        OutputChannel, notifications::SlowOperation, shorten_hex_object_id, shorten_object_id,
+    },
 };
 
 /// Represents the result of branch selection when no branch is specified
@@ -44,10 +46,13 @@ struct FailedBranch {
     error: String,
 }
 
+/// Push the branches selected by `args`, and if `notify` is set, show a desktop notification
+/// about the outcome if pushing was slow.
 pub async fn handle(
     args: push::Command,
     ctx: &mut Context,
     out: &mut OutputChannel,
+    notify: bool,
 ) -> anyhow::Result<()> {
     // Check gerrit mode early
     let gerrit_mode = {
@@ -55,6 +60,8 @@ pub async fn handle(
         repo.git_settings()?.gitbutler_gerrit_mode.unwrap_or(false)
     };
 
+    ensure_push_remote_is_configured(ctx)?;
+
     // If dry-run, show what would be pushed
     if args.dry_run {
         return handle_dry_run(ctx, &args.branch_id, out);
@@ -75,21 +82,72 @@ pub async fn handle(
     };
 
     // Handle branch selection
-    match branch_selection {
-        BranchSelection::All => {
-            push_all_branches(ctx, &args, gerrit_mode, out).await?;
-        }
-        BranchSelection::Single(branch_name) => {
-            push_single_branch(ctx, &branch_name, &args, gerrit_mode, out).await?;
-        }
+    let operation = SlowOperation::start("Push", notify);
+    let (result, branch_names) = match branch_selection {
+        BranchSelection::All => (
+            push_all_branches(ctx, &args, gerrit_mode, out)
+                .await
+                .map(|_| ()),
+            Vec::new(),
+        ),
+        BranchSelection::Single(branch_name) => (
+            push_single_branch(ctx, &branch_name, &args, gerrit_mode, out).await,
+            vec![branch_name],
+        ),
         BranchSelection::Multiple(branch_names) => {
-            for branch_name in branch_names {
-                push_single_branch(ctx, &branch_name, &args, gerrit_mode, out).await?;
+            let mut result = Ok(());
+            for branch_name in &branch_names {
+                result = push_single_branch(ctx, branch_name, &args, gerrit_mode, out).await;
+                if result.is_err() {
+                    break;
+                }
             }
+            (result, branch_names)
         }
         BranchSelection::None => return Ok(()),
+    };
+    operation.finish(|| pushed_branches_subject(ctx, &branch_names), &result);
+    result
+}
+
+/// Describe the pushed `branch_names` along with the commits they point to for notifications,
+/// or as all branches if there are none.
+fn pushed_branches_subject(ctx: &Context, branch_names: &[String]) -> String {
+    if branch_names.is_empty() {
+        return "All branches".to_owned();
     }
+    let Ok(repo) = ctx.repo.get() else {
+        return branch_names.join(", ");
+    };
+    branch_names
+        .iter()
+        .map(|name| {
+            match repo
+                .try_find_reference(name.as_str())
+                .ok()
+                .flatten()
+                .and_then(|mut reference| reference.peel_to_id().ok())
+            {
+                Some(id) => format!("{name} at {}", shorten_object_id(&repo, id.detach())),
+                None => name.clone(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
+/// Fail with a helpful error if the target was set up without a remote, as pushing to the
+/// repository itself (the `gb-local` remote) would achieve nothing.
+fn ensure_push_remote_is_configured(ctx: &Context) -> anyhow::Result<()> {
+    let target = {
+        let guard = ctx.shared_worktree_access();
+        workspace_target::ResolvedTarget::resolve_with_perm(ctx, guard.read_permission())?
+    };
+    if target.push_remote_name() == Some(setup::LOCAL_REMOTE_NAME) {
+        anyhow::bail!(
+            "This project has no remote to push to - configure a remote first with `git remote add origin <url>` and `but config target origin/<branch>`."
+        );
+    }
     Ok(())
 }
 
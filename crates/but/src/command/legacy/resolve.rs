@@ -28,6 +28,7 @@ pub(crate) fn handle(
     cmd: Option<Subcommands>,
     commit_id: Option<String>,
     ai: bool,
+    tool: bool,
 ) -> Result<()> {
     if ai {
         if cmd.is_some() {
@@ -35,6 +36,16 @@ pub(crate) fn handle(
         }
         return resolve_with_ai(ctx, out, commit_id.as_deref());
     }
+    if tool {
+        if cmd.is_some() {
+            bail!("--tool cannot be combined with a resolve subcommand");
+        }
+        if let Some(commit_id_str) = commit_id {
+            enter_resolution(ctx, out, &commit_id_str)?;
+        }
+        run_merge_tool(ctx, out)?;
+        return show_status(ctx, out);
+    }
     match cmd {
         Some(Subcommands::Status) => show_status(ctx, out),
         Some(Subcommands::Finish) => finish_resolution(ctx, out),
@@ -60,7 +71,10 @@ pub(crate) fn handle(
 
 /// Resolve a user-provided commit identifier (CLI ID or partial SHA) to an
 /// object id, along with its display ref rendered from the same map.
-fn parse_commit_id(ctx: &mut Context, commit_id_str: &str) -> Result<(gix::ObjectId, String)> {
+pub(crate) fn parse_commit_id(
+    ctx: &mut Context,
+    commit_id_str: &str,
+) -> Result<(gix::ObjectId, String)> {
     // Create an IdMap to resolve commit IDs (supports both CLI IDs and partial SHAs)
     let id_map = IdMap::legacy_new_from_context(ctx, None)?;
 
@@ -473,6 +487,120 @@ fn finish_resolution(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
     Ok(())
 }
 
+/// Launch the configured `merge.tool` for each conflicted file that still contains
+/// conflict markers, the way `git mergetool` would with `mergetool.<tool>.cmd`.
+///
+/// The tool sees the new base as `LOCAL`, the edited commit as `REMOTE`, and
+/// writes its result to `MERGED`, the file in the worktree.
+fn run_merge_tool(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
+    use gix::prelude::ObjectIdExt as _;
+
+    let t = theme::get();
+    let OperatingMode::Edit(metadata) = operating_mode(ctx)?.operating_mode else {
+        bail!(
+            "Not in conflict resolution mode. Run `but resolve <commit-id> --tool` to start resolving a commit."
+        );
+    };
+    let paths = files_with_conflict_markers(ctx)?;
+
+    let repo = ctx.repo.get()?;
+    let config = repo.config_snapshot();
+    let tool = config
+        .string("merge.tool")
+        .map(|tool| tool.to_str_lossy().into_owned())
+        .context("No merge tool configured. Set one with `git config merge.tool <tool>`.")?;
+    let tool_cmd = config
+        .string(format!("mergetool.{tool}.cmd").as_str())
+        .map(|cmd| cmd.to_str_lossy().into_owned())
+        .with_context(|| {
+            format!(
+                "Merge tool '{tool}' has no command. Set one with `git config mergetool.{tool}.cmd <command>`."
+            )
+        })?;
+    let trust_exit_code = config
+        .boolean(format!("mergetool.{tool}.trustExitCode").as_str())
+        .unwrap_or(false);
+    drop(config);
+
+    let workdir = repo.workdir().context("No workdir")?.to_owned();
+    let (base, ours, theirs) = but_core::Commit::from_id(metadata.commit_oid.attach(&repo))?
+        .conflicted_tree_ids()?
+        .context("The commit being edited is not conflicted")?;
+    let stages_dir = tempfile::tempdir().context("Failed to create directory for merge tool")?;
+
+    let mut progress = out.progress_channel();
+    let mut results = Vec::new();
+    for path in paths {
+        let file_name = std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        let mut stage_files = Vec::new();
+        for (stage, tree_id) in [("BASE", base), ("LOCAL", ours), ("REMOTE", theirs)] {
+            let stage_file = stages_dir.path().join(format!("{stage}_{file_name}"));
+            let data = match tree_id.object()?.into_tree().lookup_entry_by_path(&path)? {
+                Some(entry) => entry.object()?.detach().data,
+                None => Vec::new(),
+            };
+            std::fs::write(&stage_file, data)
+                .with_context(|| format!("Failed to write {stage} version of {path}"))?;
+            stage_files.push((stage, stage_file));
+        }
+
+        writeln!(
+            progress,
+            "Launching {} for {}",
+            t.important.paint(&tool),
+            t.attention.paint(sanitize_terminal_text(&path))
+        )?;
+        let mut cmd: std::process::Command = gix::command::prepare(tool_cmd.as_str())
+            .stdin(std::process::Stdio::inherit())
+            .stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .with_shell()
+            .into();
+        cmd.current_dir(&workdir).env("MERGED", &path);
+        for (stage, stage_file) in &stage_files {
+            cmd.env(stage, stage_file);
+        }
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to launch merge tool '{tool}'"))?;
+
+        let has_markers = std::fs::read_to_string(workdir.join(&path))
+            .map(|content| has_conflict_markers(&content))
+            .unwrap_or(false);
+        let resolved = !has_markers && (status.success() || !trust_exit_code);
+        results.push((path, resolved));
+    }
+
+    if let Some(human_out) = out.for_human() {
+        for (path, resolved) in &results {
+            let path = sanitize_terminal_text(path);
+            if *resolved {
+                writeln!(human_out, "{} {}", t.sym().success, t.success.paint(path))?;
+            } else {
+                writeln!(
+                    human_out,
+                    "{} {} is still unresolved",
+                    t.sym().error,
+                    t.attention.paint(path)
+                )?;
+            }
+        }
+        writeln!(human_out)?;
+    } else if let Some(json_out) = out.for_json() {
+        json_out.write_value(serde_json::json!({
+            "merge_tool": tool,
+            "files": results
+                .iter()
+                .map(|(path, resolved)| serde_json::json!({ "path": path, "resolved": resolved }))
+                .collect::<Vec<_>>(),
+        }))?;
+    }
+    Ok(())
+}
+
 /// Paths of initially-conflicted files that still contain conflict markers in
 /// the edit-mode worktree.
 fn files_with_conflict_markers(ctx: &mut Context) -> Result<Vec<String>> {
@@ -726,7 +854,7 @@ fn resolve_one_with_ai(
 /// Strip control characters (keeping newlines and tabs) from text that will
 /// be written to the terminal but originates outside this program - model
 /// output or repository file content.
-fn sanitize_terminal_text(text: &str) -> String {
+pub(crate) fn sanitize_terminal_text(text: &str) -> String {
     text.chars()
         .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
         .collect()
@@ -738,6 +866,10 @@ pub(crate) struct ConflictedCommit {
     pub(crate) commit_oid: gix::ObjectId,
     pub(crate) commit_short_id: String,
     pub(crate) commit_message: String,
+    /// Paths of the conflicted files as stored in the repository, sorted.
+    ///
+    /// Use [`sanitize_terminal_text()`] before showing them in a terminal.
+    pub(crate) conflicted_files: Vec<String>,
 }
 
 /// Check for new conflicts introduced during rebase and report them
@@ -805,6 +937,7 @@ fn check_for_new_conflicts_after_rebase(
                         "commit_id": c.commit_oid.to_string(),
                         "commit_short_id": c.commit_short_id,
                         "commit_message": c.commit_message,
+                        "conflicted_files": c.conflicted_files,
                     })
                 })
                 .collect();
@@ -879,6 +1012,7 @@ pub(crate) fn find_conflicted_commits(
                             .map(|change_id| change_id.padded_short_id())
                             .unwrap_or_else(|| shorten_object_id(&repo, oid)),
                         commit_message: message,
+                        conflicted_files: conflicted_files(&repo, oid),
                     };
 
                     conflicts_by_branch
@@ -893,6 +1027,28 @@ pub(crate) fn find_conflicted_commits(
     Ok(conflicts_by_branch)
 }
 
+/// Paths of the files recorded as conflicted in the conflicted commit `commit_id`.
+///
+/// Best-effort: a malformed conflicted commit yields no paths rather than failing the listing.
+pub(crate) fn conflicted_files(repo: &gix::Repository, commit_id: gix::ObjectId) -> Vec<String> {
+    use gix::prelude::ObjectIdExt as _;
+
+    let Ok(Some(entries)) = but_core::Commit::from_id(commit_id.attach(repo))
+        .and_then(|commit| commit.conflict_entries())
+    else {
+        return Vec::new();
+    };
+    entries
+        .ancestor_entries
+        .iter()
+        .chain(&entries.our_entries)
+        .chain(&entries.their_entries)
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
 /// Check for conflicted commits and prompt user to resolve them
 fn check_and_prompt_for_conflicts(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
     let t = theme::get();
@@ -915,6 +1071,7 @@ fn check_and_prompt_for_conflicts(ctx: &mut Context, out: &mut OutputChannel) ->
                         "commit_id": c.commit_oid.to_string(),
                         "commit_short_id": c.commit_short_id,
                         "commit_message": c.commit_message,
+                        "conflicted_files": c.conflicted_files,
                     })
                 })
                 .collect();
@@ -955,6 +1112,13 @@ fn check_and_prompt_for_conflicts(ctx: &mut Context, out: &mut OutputChannel) ->
                     t.hint.paint(&commit.commit_short_id),
                     commit.commit_message
                 )?;
+                for path in &commit.conflicted_files {
+                    writeln!(
+                        human_out,
+                        "      {}",
+                        t.attention.paint(sanitize_terminal_text(path))
+                    )?;
+                }
             }
             writeln!(human_out)?;
         }
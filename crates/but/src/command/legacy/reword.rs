@@ -6,7 +6,10 @@ use but_ctx::Context;
 
 use super::{
     ShowDiffInEditor,
-    commit_message_prep::{normalize_commit_message, should_update_commit_message},
+    commit_message_prep::{
+        commit_message_problems, normalize_commit_message, revise_commit_message_in_editor,
+        should_update_commit_message, warn_about_commit_message_problems,
+    },
     estimate_diff_blob_size,
 };
 use crate::{
@@ -205,9 +208,21 @@ fn edit_commit_message_by_id_and_reword_commit(
             &current_message,
             show_diff_in_editor,
         )?
+        .map(|new_message| {
+            revise_commit_message_in_editor(&ctx.settings.commit_message, new_message)
+                .map(|revised| normalize_commit_message(&revised).to_owned())
+        })
+        .transpose()?
     }
     .filter(|new_message| should_update_commit_message(&current_message, new_message));
 
+    if let Some(new_message) = &new_message {
+        warn_about_commit_message_problems(
+            out,
+            &commit_message_problems(&ctx.settings.commit_message, new_message),
+        )?;
+    }
+
     if let Some(new_message) = new_message {
         let change_id = {
             let repo = ctx.repo.get()?;
@@ -32,7 +32,7 @@ use crate::{
 
 mod amend;
 pub(crate) mod squash;
-mod undo;
+pub(crate) mod undo;
 
 /// A description of a set of hunks.
 type Description = String;
@@ -693,7 +693,9 @@ fn ids(
     }
 
     // Still ambiguous even after filtering by validity - prompt the user
-    let selected_target = prompt_for_disambiguation(target, valid_targets, "the target", out)?;
+    let repo = ctx.repo.get()?;
+    let selected_target =
+        prompt_for_disambiguation(target, valid_targets, "the target", &repo, out)?;
     Ok((sources, selected_target))
 }
 
@@ -741,7 +743,8 @@ fn resolve_single_id(
     }
 
     // Multiple matches - use disambiguation
-    prompt_for_disambiguation(entity_str, matches, context, out)
+    let repo = ctx.repo.get()?;
+    prompt_for_disambiguation(entity_str, matches, context, &repo, out)
 }
 
 /// Handler for `but uncommit <source>` - runs `but rub <source> zz`
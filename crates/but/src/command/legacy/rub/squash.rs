@@ -1,6 +1,10 @@
 use anyhow::{Context as _, bail};
 use bstr::BString;
-use but_core::{DryRun, ref_metadata::StackId, sync::RepoExclusive};
+use but_core::{
+    DryRun,
+    ref_metadata::StackId,
+    sync::{RepoExclusive, RepoShared},
+};
 use but_ctx::Context;
 use but_workspace::commit::squash_commits::MessageCombinationStrategy;
 use gitbutler_oplog::{
@@ -76,7 +80,7 @@ pub(crate) fn handle(
 
         // No exact match found - try parsing as a range or list if it contains special characters
         if entity_str.contains("..") {
-            let sources = parse_commit_range(ctx, &id_map, entity_str)?;
+            let sources = parse_commit_range(ctx, &id_map, entity_str, guard.read_permission())?;
             if sources.len() < 2 {
                 bail!("Need at least 2 commits to squash");
             }
@@ -439,6 +443,7 @@ fn parse_commit_range(
     ctx: &mut Context,
     id_map: &IdMap,
     range_str: &str,
+    perm: &RepoShared,
 ) -> anyhow::Result<Vec<CliId>> {
     let parts: Vec<&str> = range_str.split("..").collect();
     if parts.len() != 2 {
@@ -479,8 +484,8 @@ fn parse_commit_range(
     };
 
     // Verify both commits are on the same stack FIRST
-    let start_stack = stack_id_by_commit_id(ctx, *start_commit_oid)?;
-    let end_stack = stack_id_by_commit_id(ctx, *end_commit_oid)?;
+    let start_stack = stack_id_by_commit_id(ctx, perm, *start_commit_oid)?;
+    let end_stack = stack_id_by_commit_id(ctx, perm, *end_commit_oid)?;
     if start_stack != end_stack {
         bail!(
             "Range endpoints must be on the same stack. '{start_str}' and '{end_str}' are on different stacks."
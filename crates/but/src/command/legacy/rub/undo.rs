@@ -1,18 +1,16 @@
-use but_core::ref_metadata::StackId;
+use anyhow::Context as _;
+use but_core::{ref_metadata::StackId, sync::RepoShared};
 use but_ctx::Context;
 
-pub(crate) fn stack_id_by_commit_id(ctx: &Context, oid: gix::ObjectId) -> anyhow::Result<StackId> {
-    for stack in crate::legacy::workspace::applied_stacks_with_expensive_commit_info(ctx)? {
-        let Some(id) = stack.id else {
-            continue;
-        };
-        if stack
-            .branches
-            .iter()
-            .any(|branch| branch.commits.iter().any(|commit| commit.id == oid))
-        {
-            return Ok(id);
-        }
-    }
-    anyhow::bail!("No stack found for commit {oid}")
+/// Return the id of the stack that has `oid` as one of its local commits.
+pub(crate) fn stack_id_by_commit_id(
+    ctx: &Context,
+    perm: &RepoShared,
+    oid: gix::ObjectId,
+) -> anyhow::Result<StackId> {
+    let (_repo, ws, _db) = ctx.workspace_and_db_with_perm(perm)?;
+    but_workspace::stack_containing_commit(&ws, oid)
+        .filter(|location| !location.upstream_only)
+        .and_then(|location| location.stack_id)
+        .with_context(|| format!("No stack found for commit {oid}"))
 }
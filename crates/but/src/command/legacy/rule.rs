@@ -0,0 +1,258 @@
+//! Implementation of the `but rule` command.
+//!
+//! Rules assign new uncommitted changes to a branch by their path. They are applied by the
+//! assignment engine whenever new changes appear, see [`but_hunk_assignment::rule_for()`].
+
+use anyhow::{Result, bail};
+use but_ctx::Context;
+use but_hunk_assignment::AssignmentRule;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    IdMap,
+    command::legacy::assign::resolve_lane,
+    theme::{self, Paint},
+    utils::OutputChannel,
+};
+
+/// A rule as listed to the user.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleEntry {
+    id: Uuid,
+    short_id: String,
+    /// The glob or path that the paths of changes have to match.
+    path: String,
+    /// The short name of the branch that matching changes are assigned to.
+    branch: String,
+    /// Whether the branch still exists. Rules of branches that don't are never applied.
+    branch_exists: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveOutcome<'a> {
+    removed: Vec<&'a RuleEntry>,
+}
+
+/// The rules to remove with `but rule remove`.
+pub(crate) enum Selection {
+    /// The rules with the given full or abbreviated IDs.
+    Ids(Vec<String>),
+    /// All rules.
+    All,
+    /// The rules whose branch doesn't exist anymore.
+    Pruned,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddOutcome {
+    id: Uuid,
+    short_id: String,
+    path: String,
+    branch: String,
+    /// The number of unassigned hunks the rule assigned right away.
+    assigned_hunks: usize,
+}
+
+/// Handle `but rule list`, showing all rules in the order they were added.
+pub(crate) fn handle_list(ctx: &mut Context, out: &mut OutputChannel) -> Result<()> {
+    let t = theme::get();
+    let entries = rule_entries(ctx, but_api::diff::assignment_rules(ctx)?)?;
+
+    if let Some(out) = out.for_human() {
+        if entries.is_empty() {
+            writeln!(
+                out,
+                "No rules. Add one with `but rule add --path <glob> --branch <branch>`."
+            )?;
+        }
+        let path_width = entries
+            .iter()
+            .map(|entry| entry.path.chars().count())
+            .max()
+            .unwrap_or_default();
+        for entry in &entries {
+            writeln!(
+                out,
+                "{} {:path_width$} → {}{}",
+                t.cli_id.paint(entry.short_id.as_str()),
+                entry.path,
+                t.local_branch.paint(entry.branch.as_str()),
+                if entry.branch_exists {
+                    String::new()
+                } else {
+                    t.attention.paint(" (branch doesn't exist)").to_string()
+                }
+            )?;
+        }
+    }
+    if let Some(out) = out.for_json() {
+        out.write_value(entries)?;
+    }
+    Ok(())
+}
+
+/// Handle `but rule add --path <glob> --branch <branch>`.
+///
+/// Persists a rule that assigns new changes matching `path` to the applied `branch`, and assigns
+/// the unassigned changes it decides for right away.
+pub(crate) fn handle_add(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    path: &str,
+    branch: &str,
+) -> Result<()> {
+    let t = theme::get();
+    let mut guard = ctx.exclusive_worktree_access();
+    let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
+    let lane = resolve_lane(ctx, &id_map, branch, out)?;
+    let branch_ref = gix::refs::Category::LocalBranch.to_full_name(lane.name.as_str())?;
+    let (rule, assigned_hunks) = but_api::diff::add_assignment_rule_with_perm(
+        ctx,
+        path.to_owned(),
+        branch_ref,
+        guard.write_permission(),
+    )?;
+
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "Added rule: {} → {}",
+            rule.path_glob,
+            t.local_branch.paint(lane.name.as_str())
+        )?;
+        if assigned_hunks == 0 {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint("No unassigned changes match it yet.")
+            )?;
+        } else {
+            writeln!(
+                out,
+                "Assigned {assigned_hunks} unassigned {} that {} it.",
+                if assigned_hunks == 1 { "hunk" } else { "hunks" },
+                if assigned_hunks == 1 {
+                    "matches"
+                } else {
+                    "match"
+                },
+            )?;
+        }
+    }
+    if let Some(out) = out.for_json() {
+        out.write_value(AddOutcome {
+            id: rule.id,
+            short_id: short_id(rule.id),
+            path: rule.path_glob,
+            branch: lane.name,
+            assigned_hunks,
+        })?;
+    }
+    Ok(())
+}
+
+/// Handle `but rule remove`, removing the rules of `selection`.
+///
+/// Changes that were assigned by the removed rules keep their assignment.
+pub(crate) fn handle_remove(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    selection: Selection,
+) -> Result<()> {
+    let t = theme::get();
+    let entries = rule_entries(ctx, but_api::diff::assignment_rules(ctx)?)?;
+    let mut removed = Vec::<&RuleEntry>::new();
+    match &selection {
+        Selection::Ids(ids) => {
+            for id in ids {
+                let entry = find_entry(&entries, id)?;
+                if !removed.iter().any(|removed| removed.id == entry.id) {
+                    removed.push(entry);
+                }
+            }
+        }
+        Selection::All => removed.extend(&entries),
+        Selection::Pruned => removed.extend(entries.iter().filter(|entry| !entry.branch_exists)),
+    }
+
+    if !removed.is_empty() {
+        let ids: Vec<_> = removed.iter().map(|entry| entry.id).collect();
+        but_api::diff::remove_assignment_rules(ctx, &ids)?;
+    }
+
+    if let Some(out) = out.for_human() {
+        if removed.is_empty() {
+            let reason = match selection {
+                Selection::Pruned if !entries.is_empty() => {
+                    "All rules point to branches that exist."
+                }
+                _ => "There are no rules.",
+            };
+            writeln!(out, "{reason}")?;
+        } else {
+            writeln!(out, "Removed:")?;
+            for entry in &removed {
+                writeln!(
+                    out,
+                    "  {} {} → {}",
+                    t.cli_id.paint(entry.short_id.as_str()),
+                    entry.path,
+                    t.local_branch.paint(entry.branch.as_str()),
+                )?;
+            }
+            writeln!(
+                out,
+                "{}",
+                t.hint
+                    .paint("Changes that were assigned by these rules stay assigned.")
+            )?;
+        }
+    }
+    if let Some(out) = out.for_json() {
+        out.write_value(RemoveOutcome { removed })?;
+    }
+    Ok(())
+}
+
+/// The entry among `entries` whose ID starts with `id`, which has to be unambiguous.
+fn find_entry<'a>(entries: &'a [RuleEntry], id: &str) -> Result<&'a RuleEntry> {
+    let id = id.to_ascii_lowercase();
+    let mut matches = entries
+        .iter()
+        .filter(|entry| !id.is_empty() && entry.id.to_string().starts_with(&id));
+    match (matches.next(), matches.next()) {
+        (Some(entry), None) => Ok(entry),
+        (Some(_), Some(_)) => {
+            bail!("The rule ID '{id}' is ambiguous, use more of its characters.")
+        }
+        (None, _) => {
+            bail!("Could not find the rule '{id}'. Run `but rule list` to see the rules.")
+        }
+    }
+}
+
+/// Turn `rules` into entries to show, with whether their branch exists looked up in the repository.
+fn rule_entries(ctx: &Context, rules: Vec<AssignmentRule>) -> Result<Vec<RuleEntry>> {
+    let repo = ctx.repo.get()?;
+    rules
+        .into_iter()
+        .map(|rule| {
+            Ok(RuleEntry {
+                id: rule.id,
+                short_id: short_id(rule.id),
+                path: rule.path_glob,
+                branch: rule.branch_ref.shorten().to_string(),
+                branch_exists: repo.try_find_reference(rule.branch_ref.as_ref())?.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// The abbreviated `id` of a rule as shown to the user.
+fn short_id(id: Uuid) -> String {
+    id.to_string()[..7].to_owned()
+}
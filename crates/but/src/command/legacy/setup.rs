@@ -1,10 +1,16 @@
 use std::path::{self, Path};
 
+use anyhow::Context as _;
+use bstr::ByteSlice as _;
+
 use but_core::{
+    extract_remote_name_and_short_name,
     git_config::edit_repo_config,
     sync::{RepoExclusive, RepoShared},
 };
 use but_ctx::Context;
+use gitbutler_git::GitContextExt as _;
+use nonempty::NonEmpty;
 use serde::Serialize;
 
 use crate::{
@@ -41,6 +47,9 @@ struct TargetInfo {
     remote_name: String,
     /// Whether the target was newly set or already existed
     newly_set: bool,
+    /// The previously checked-out branch that was applied to the workspace along with its commits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    adopted_branch: Option<String>,
 }
 
 /// Display a colorful splash screen with GitButler branding and helpful commands
@@ -171,10 +180,24 @@ pub fn find_or_initialize_repo(
     }
 }
 
-// setup a gitbutler project for the repository at `repo_path`
+/// How to set the target if a project doesn't have one yet.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct TargetOptions<'a> {
+    /// The remote-tracking branch to use as target, like `origin/develop`, instead of detecting it.
+    pub branch: Option<&'a str>,
+    /// Fetch the remote of `branch` before validating it.
+    pub fetch: bool,
+    /// Only use the remote's HEAD when detecting the target, instead of also trying well-known branch names.
+    pub no_guess: bool,
+    /// Don't apply the checked-out branch as stack if it has commits on top of the target.
+    pub no_adopt: bool,
+}
+
+// setup a gitbutler project for the repository at `repo_path`, with `target_options` used if it doesn't have a target yet
 pub(crate) fn repo(
     ctx: &mut Context,
     repo_path: &Path,
+    target_options: TargetOptions<'_>,
     out: &mut OutputChannel,
     perm: &mut RepoExclusive,
 ) -> anyhow::Result<()> {
@@ -281,6 +304,14 @@ pub(crate) fn repo(
 
     // Check if target branch is set
     let target = but_api::legacy::virtual_branches::get_base_branch_data(ctx, perm)?;
+    if let (Some(existing), Some(target_branch)) = (&target, target_options.branch)
+        && existing.branch_name != target_branch
+    {
+        anyhow::bail!(
+            "The project already targets '{}' - run `but config target {target_branch}` to change it.",
+            existing.branch_name
+        );
+    }
 
     // If new or already exists but target is not set, set the target to be the remote's HEAD
     if (matches!(outcome, gitbutler_project::AddProjectOutcome::Added(_))
@@ -300,70 +331,52 @@ pub(crate) fn repo(
             )?;
         }
 
-        let mut repo = ctx.repo.get_mut()?;
-        let remote_name = match repo.remote_default_name(gix::remote::Direction::Push) {
-            Some(name) => {
-                if let Some(out) = out.for_human() {
-                    writeln!(
-                        out,
-                        "  {}",
-                        t.success
-                            .paint(format!("✓ Using existing push remote: {name}"))
-                    )?;
-                }
-                name.to_string()
-            }
+        let (remote_name, name) = match target_options.branch {
+            Some(target_branch) => explicit_target(ctx, target_branch, target_options.fetch)?,
             None => {
-                let remote_name = setup_local_remote(&repo, out)?;
-                repo.reload()?;
-                remote_name
-            }
-        };
-
-        // Try to find the remote HEAD, or fall back to detecting main/master
-        let name = if let Ok(mut head_ref) =
-            repo.find_reference(&format!("refs/remotes/{remote_name}/HEAD"))
-        {
-            head_ref.peel_to_commit().ok(); // Need this in order to "open" HEAD
-            head_ref.name().shorten().to_string()
-        } else {
-            // No HEAD reference, try to find main or master
-            let fallback_branch = ["main", "master"].into_iter().find(|branch| {
-                repo.find_reference(&format!("refs/remotes/{remote_name}/{branch}"))
-                    .is_ok()
-            });
-            match fallback_branch {
-                Some(branch) => {
-                    if let Some(out) = out.for_human() {
-                        writeln!(
-                            out,
-                            "  {}",
-                            t.attention.paint(format!(
-                                "✓ No remote HEAD found, using {remote_name}/{branch}"
-                            ))
-                        )?;
+                let mut repo = ctx.repo.get_mut()?;
+                let remote_name = match repo.remote_default_name(gix::remote::Direction::Push) {
+                    Some(name) => {
+                        if let Some(out) = out.for_human() {
+                            writeln!(
+                                out,
+                                "  {}",
+                                t.success
+                                    .paint(format!("✓ Using existing push remote: {name}"))
+                            )?;
+                        }
+                        name.to_string()
                     }
-                    format!("{remote_name}/{branch}")
-                }
-                None => {
-                    anyhow::bail!("No HEAD reference found for remote {remote_name}");
-                }
+                    None => {
+                        let remote_name = setup_local_remote(&repo, out)?;
+                        repo.reload()?;
+                        remote_name
+                    }
+                };
+                let name = default_target(&repo, &remote_name, !target_options.no_guess, out)?;
+                (remote_name, name)
             }
         };
 
-        drop(repo);
         but_api::legacy::virtual_branches::set_base_branch_with_perm(
             ctx,
             name.clone(),
             Some(remote_name.clone()),
+            !target_options.no_adopt,
             perm,
         )?;
+        let adopted_branch = if target_options.no_adopt {
+            None
+        } else {
+            adopted_head_branch(ctx, &pre_head_name, perm)?
+        };
 
         // Track target info for JSON output
         target_info = Some(TargetInfo {
             branch_name: name.clone(),
             remote_name: remote_name.clone(),
             newly_set: true,
+            adopted_branch: adopted_branch.clone(),
         });
 
         if let Some(out) = out.for_human() {
@@ -372,6 +385,14 @@ pub(crate) fn repo(
                 "  {}",
                 t.success.paint(format!("✓ Set default target to: {name}"))
             )?;
+            if let Some(branch) = adopted_branch {
+                writeln!(
+                    out,
+                    "  {}",
+                    t.success
+                        .paint(format!("✓ Applied branch {branch} to the workspace"))
+                )?;
+            }
             writeln!(out)?;
             writeln!(
                 out,
@@ -389,6 +410,7 @@ pub(crate) fn repo(
                 branch_name: target.branch_name.clone(),
                 remote_name: target.remote_name.clone(),
                 newly_set: false,
+                adopted_branch: None,
             });
         }
 
@@ -481,6 +503,161 @@ More info: https://docs.gitbutler.com/workspace-branch
     Ok(())
 }
 
+/// Return `head_name` if it's the name of a local branch that is now a stack in the workspace, as
+/// it was applied when setting the target.
+fn adopted_head_branch(
+    ctx: &Context,
+    head_name: &str,
+    perm: &RepoExclusive,
+) -> anyhow::Result<Option<String>> {
+    if head_name.is_empty() || head_name.starts_with("gitbutler/") {
+        return Ok(None);
+    }
+    let (_, ws, _) = ctx.workspace_and_db_with_perm(perm.read_permission())?;
+    let head_ref = format!("refs/heads/{head_name}");
+    let is_applied = ws.stacks.iter().any(|stack| {
+        stack
+            .ref_name()
+            .is_some_and(|ref_name| ref_name.as_bstr() == head_ref.as_str())
+    });
+    Ok(is_applied.then(|| head_name.to_owned()))
+}
+
+/// Validate `target_branch` like `origin/develop`, after fetching its remote if `fetch` is set,
+/// and return its remote name along with the target branch.
+fn explicit_target(
+    ctx: &Context,
+    target_branch: &str,
+    fetch: bool,
+) -> anyhow::Result<(String, String)> {
+    let target_ref: gix::refs::FullName = format!("refs/remotes/{target_branch}")
+        .try_into()
+        .with_context(|| format!("'{target_branch}' is not a valid branch name"))?;
+    let remote_name = {
+        let repo = ctx.repo.get()?;
+        extract_remote_name_and_short_name(target_ref.as_ref(), &repo.remote_names())
+            .map(|(remote_name, _short_name)| remote_name)
+            .filter(|remote_name| repo.find_remote(remote_name.as_str()).is_ok())
+            .with_context(|| {
+                format!(
+                    "'{target_branch}' doesn't start with the name of a remote - pass the target as <remote>/<branch>, like `origin/main`."
+                )
+            })?
+    };
+    if fetch {
+        ctx.fetch(&remote_name, Some("setup".into()))?;
+    }
+    if ctx
+        .repo
+        .get()?
+        .try_find_reference(target_ref.as_ref())?
+        .is_none()
+    {
+        if fetch {
+            anyhow::bail!(
+                "The remote-tracking branch '{target_branch}' doesn't exist - run `git branch -r` to see the available branches."
+            );
+        }
+        anyhow::bail!(
+            "The remote-tracking branch '{target_branch}' doesn't exist - pass `--fetch` to fetch the remote first."
+        );
+    }
+    Ok((remote_name, target_branch.to_owned()))
+}
+
+/// Return the target branch like `origin/main` to use for `remote_name`, which is the remote's
+/// HEAD. If there is none and we may `guess`, it's the first of the well-known base branches
+/// of the remote, or the branch the current branch tracks on it.
+///
+/// If more than one commonly used base branch exists and the user can be asked, they get to choose.
+fn default_target(
+    repo: &gix::Repository,
+    remote_name: &str,
+    guess: bool,
+    out: &mut OutputChannel,
+) -> anyhow::Result<String> {
+    const WELL_KNOWN_BRANCHES: [&str; 4] = ["main", "master", "trunk", "develop"];
+    let t = theme::get();
+    let remote_head = repo
+        .find_reference(&format!("refs/remotes/{remote_name}/HEAD"))
+        .ok()
+        .map(|mut head_ref| {
+            head_ref.peel_to_commit().ok(); // Need this in order to "open" HEAD
+            head_ref.name().shorten().to_string()
+        });
+    let mut candidates: Vec<String> = remote_head.iter().cloned().collect();
+    if guess {
+        let head_upstream = repo
+            .head_ref()
+            .ok()
+            .flatten()
+            .and_then(|head_ref| {
+                head_ref
+                    .remote_tracking_ref_name(gix::remote::Direction::Fetch)?
+                    .ok()
+                    .map(|name| name.into_owned())
+            })
+            .and_then(|name| {
+                name.as_bstr()
+                    .strip_prefix(format!("refs/remotes/{remote_name}/").as_bytes())
+                    .map(|branch| branch.to_str_lossy().into_owned())
+            });
+        for branch in WELL_KNOWN_BRANCHES
+            .into_iter()
+            .map(str::to_owned)
+            .chain(head_upstream)
+        {
+            let candidate = format!("{remote_name}/{branch}");
+            if !candidates.contains(&candidate)
+                && repo
+                    .find_reference(&format!("refs/remotes/{candidate}"))
+                    .is_ok()
+            {
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    let Some(candidates) = NonEmpty::from_vec(candidates) else {
+        if !guess {
+            anyhow::bail!(
+                "No HEAD reference found for remote {remote_name} - looked for refs/remotes/{remote_name}/HEAD. Pass `--target <remote>/<branch>` to choose the target."
+            );
+        }
+        let well_known = WELL_KNOWN_BRANCHES
+            .map(|branch| format!("{remote_name}/{branch}"))
+            .join(", ");
+        anyhow::bail!(
+            "No HEAD reference found for remote {remote_name} - looked for refs/remotes/{remote_name}/HEAD, {well_known} and the upstream of the current branch. Pass `--target <remote>/<branch>` to choose the target."
+        );
+    };
+    if candidates.len() > 1
+        && let Some(mut input) = out.prepare_for_terminal_input()
+    {
+        let items = candidates.clone().map(|name| (name.clone(), name));
+        return input
+            .prompt_select(
+                "Select the target branch your branches are based on",
+                &items,
+            )?
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No target branch selected"));
+    }
+
+    let name = candidates.head;
+    if remote_head.is_none()
+        && let Some(out) = out.for_human()
+    {
+        writeln!(
+            out,
+            "  {}",
+            t.attention
+                .paint(format!("✓ No remote HEAD found, using {name}"))
+        )?;
+    }
+    Ok(name)
+}
+
 /// Checks if a GitButler project is set up for the repository at `repo_path`.
 /// If so, returns true
 /// Otherwise, returns an error with a message indicating what is not setup.
@@ -530,6 +707,9 @@ pub fn check_project_setup(ctx: &Context, perm: &RepoShared) -> anyhow::Result<b
     Ok(true)
 }
 
+/// The name of the remote that points at the repository itself, used as target when there is no other remote.
+pub(crate) const LOCAL_REMOTE_NAME: &str = "gb-local";
+
 /// Creates a 'gb-local' remote pointing to this repository and creates tracking refs for the default branch.
 fn setup_local_remote(repo: &gix::Repository, out: &mut OutputChannel) -> anyhow::Result<String> {
     let t = theme::get();
@@ -549,7 +729,8 @@ fn setup_local_remote(repo: &gix::Repository, out: &mut OutputChannel) -> anyhow
     }
 
     edit_repo_config(repo, gix::config::Source::Local, |config| {
-        let mut section = config.section_mut_or_create_new("remote", Some("gb-local".into()))?;
+        let mut section =
+            config.section_mut_or_create_new("remote", Some(LOCAL_REMOTE_NAME.into()))?;
         section.push("url", repo_url)?;
         Ok(())
     })?;
@@ -605,7 +786,7 @@ fn setup_local_remote(repo: &gix::Repository, out: &mut OutputChannel) -> anyhow
         )?;
     }
 
-    Ok("gb-local".to_string())
+    Ok(LOCAL_REMOTE_NAME.to_string())
 }
 
 /// Sets up a new git repository and creates an initial empty commit.
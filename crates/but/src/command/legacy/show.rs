@@ -6,10 +6,13 @@ use but_ctx::access::RepoShared;
 
 use crate::{
     CLI_DATE, CliId, IdMap,
-    id::CommitId,
+    id::{CommitId, parser::list_matches},
     theme::{self, Paint},
     utils::{
-        OutputChannel, get_change_id_for_commit, shorten_object_id, time::format_relative_time,
+        OutputChannel, get_change_id_for_commit,
+        quoted_path::{display_path, quote_path_enabled},
+        shorten_object_id,
+        time::format_relative_time,
     },
 };
 
@@ -18,6 +21,7 @@ pub(crate) fn show_commit(
     out: &mut OutputChannel,
     commit_id_str: &str,
     verbose: bool,
+    path: Option<&str>,
 ) -> Result<()> {
     let t = theme::get();
     let id_map = IdMap::legacy_new_from_context(ctx, None)?;
@@ -29,7 +33,9 @@ pub(crate) fn show_commit(
             || b.name.to_string().to_lowercase() == commit_id_str.to_lowercase()
     });
 
-    if let Some(branch) = branch_match {
+    if path.is_none()
+        && let Some(branch) = branch_match
+    {
         // This is a branch, display branch name and list of commits
         return show_branch(ctx, out, &branch.name.to_string(), verbose, &id_map);
     }
@@ -40,8 +46,9 @@ pub(crate) fn show_commit(
     for stack in &stacks {
         for branch in &stack.branches {
             let head_name = branch.name.clone();
-            if head_name == commit_id_str
-                || head_name.to_lowercase() == commit_id_str.to_lowercase()
+            if path.is_none()
+                && (head_name == commit_id_str
+                    || head_name.to_lowercase() == commit_id_str.to_lowercase())
             {
                 // Found the branch in a stack
                 return show_branch(ctx, out, &head_name, verbose, &id_map);
@@ -65,13 +72,17 @@ pub(crate) fn show_commit(
         commit.id
     } else if cli_ids.len() > 1 {
         bail!(
-            "Commit ID '{}' is ambiguous. Found {} matches",
-            commit_id_str,
-            cli_ids.len()
+            "Commit ID '{commit_id_str}' is ambiguous, matches multiple items:\n{}",
+            list_matches(&cli_ids, &*ctx.repo.get()?)
         );
     } else {
         match &cli_ids[0] {
             CliId::Commit(CommitId { commit_id, .. }) => *commit_id,
+            CliId::Branch(branch) if path.is_some() => ctx
+                .repo
+                .get()?
+                .rev_parse_single(branch.name.as_str())?
+                .detach(),
             CliId::Branch(branch) => {
                 // This is a branch identified by CLI ID, show the branch
                 return show_branch(ctx, out, &branch.name, verbose, &id_map);
@@ -85,6 +96,10 @@ pub(crate) fn show_commit(
         }
     };
 
+    if let Some(path) = path {
+        return show_file_at_commit(ctx, out, commit_id, path);
+    }
+
     // Get commit and file details
     let repo = ctx.repo.get()?;
     let raw_commit = repo.find_commit(commit_id)?;
@@ -169,6 +184,7 @@ pub(crate) fn show_commit(
         let changes = tree_changes.into_tree_changes();
         if !changes.is_empty() {
             writeln!(out, "{}", t.important.paint("Files changed:"))?;
+            let quote_path = quote_path_enabled(&repo);
             for change in &changes {
                 let (status_char, status_color) = match &change.status {
                     but_core::TreeStatus::Addition { .. } => ("A", t.addition),
@@ -177,11 +193,27 @@ pub(crate) fn show_commit(
                     but_core::TreeStatus::Rename { .. } => ("R", t.renaming),
                 };
 
+                let mode_change = match &change.status {
+                    but_core::TreeStatus::Modification {
+                        flags: Some(flags), ..
+                    }
+                    | but_core::TreeStatus::Rename {
+                        flags: Some(flags), ..
+                    } => {
+                        let label = if flags.is_typechange() {
+                            " (typechange)"
+                        } else {
+                            " (mode change)"
+                        };
+                        t.hint.paint(label).to_string()
+                    }
+                    _ => String::new(),
+                };
                 writeln!(
                     out,
-                    "  {} {}",
+                    "  {} {}{mode_change}",
                     status_color.paint(status_char),
-                    change.path.to_str_lossy()
+                    display_path(change.path.as_bstr(), quote_path)
                 )?;
             }
         }
@@ -233,6 +265,41 @@ pub(crate) fn show_commit(
     Ok(())
 }
 
+/// Print the content of the file at `path` as stored in `commit_id`.
+fn show_file_at_commit(
+    ctx: &Context,
+    out: &mut OutputChannel,
+    commit_id: gix::ObjectId,
+    path: &str,
+) -> Result<()> {
+    let Some(blob) = but_api::diff::blob_at(ctx, commit_id, path.into(), None)? else {
+        bail!(
+            "Path '{path}' does not exist in commit {}",
+            shorten_object_id(&*ctx.repo.get()?, commit_id)
+        );
+    };
+
+    if let Some(out) = out.for_human() {
+        match (&blob.data, blob.is_binary) {
+            (Some(data), Some(false)) => write!(out, "{}", data.to_str_lossy())?,
+            _ => writeln!(out, "Binary file '{path}' ({} bytes)", blob.size)?,
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(serde_json::json!({
+            "commit": commit_id.to_string(),
+            "path": path,
+            "size": blob.size,
+            "isBinary": blob.is_binary,
+            "isExecutable": blob.is_executable,
+            "content": blob
+                .data
+                .filter(|_| blob.is_binary == Some(false))
+                .map(|data| data.to_str_lossy().into_owned()),
+        }))?;
+    }
+    Ok(())
+}
+
 fn show_branch(
     ctx: &mut Context,
     out: &mut OutputChannel,
@@ -0,0 +1,329 @@
+//! Implementation of the `but stack` commands.
+
+use std::{collections::HashSet, fmt::Write as _};
+
+use anyhow::{Context as _, bail};
+use but_api::diff;
+use but_core::{DryRun, ref_metadata::StackId};
+use but_ctx::Context;
+use but_hunk_assignment::{HunkAssignment, HunkAssignmentRequest, HunkAssignmentTarget};
+use but_workspace::legacy::StacksFilter;
+use gitbutler_oplog::{
+    OplogExt,
+    entry::{OperationKind, SnapshotDetails},
+};
+use gix::refs::FullName;
+use serde::Serialize;
+
+use super::{
+    assign::{AssignedFiles, resolve_lane},
+    resolve::{find_conflicted_commits, sanitize_terminal_text},
+};
+use crate::{
+    IdMap,
+    args::stack::MergeOrder,
+    theme::{self, Paint},
+    utils::{OutputChannel, shorten_object_id},
+};
+
+/// JSON output for a stack listed by `but stack list`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stack {
+    /// The ID of the stack as stored in the workspace metadata
+    id: String,
+    /// The short ID to refer to the stack with on the command line, only available if it's applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cli_id: Option<String>,
+    /// Whether the stack is applied to the workspace
+    applied: bool,
+    /// The branches of the stack, from top to bottom
+    branches: Vec<Branch>,
+    /// The number of commits in all branches of the stack
+    commits: usize,
+    /// The number of uncommitted files assigned to the stack
+    assigned_files: usize,
+}
+
+/// JSON output for a branch of a stack listed by `but stack list`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Branch {
+    /// The short name of the branch
+    name: String,
+    /// The commit the branch points to
+    #[serde(serialize_with = "but_serde::object_id::serialize")]
+    tip: gix::ObjectId,
+    /// The number of commits of the branch which aren't in any branch below it
+    commits: usize,
+}
+
+/// List the stacks applied to the workspace, along with those that aren't applied if `all` is set.
+///
+/// Applied stacks are listed first.
+pub fn list(ctx: &mut Context, out: &mut OutputChannel, all: bool) -> anyhow::Result<()> {
+    let guard = ctx.shared_worktree_access();
+    let worktree_changes = diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?;
+    let assigned_files = AssignedFiles::from_assignments(&worktree_changes.assignments);
+    let id_map = IdMap::new_from_context(
+        ctx,
+        Some(worktree_changes.assignments),
+        guard.read_permission(),
+    )?;
+    drop(guard);
+
+    let filter = if all {
+        StacksFilter::All
+    } else {
+        StacksFilter::InWorkspace
+    };
+    let repo = ctx.repo.get()?.clone().for_commit_shortening();
+    let mut stacks: Vec<Stack> = but_api::legacy::workspace::all_stack_details(ctx, Some(filter))?
+        .into_iter()
+        .map(|(stack_id, details)| {
+            let cli_id = id_map
+                .resolve_stack(stack_id)
+                .map(|id| id.to_short_string());
+            let branches: Vec<Branch> = details
+                .branch_details
+                .iter()
+                .map(|branch| Branch {
+                    name: branch.name.to_string(),
+                    tip: branch.tip,
+                    commits: branch.commits.len(),
+                })
+                .collect();
+            Stack {
+                id: stack_id.to_string(),
+                applied: cli_id.is_some(),
+                cli_id,
+                commits: branches.iter().map(|branch| branch.commits).sum(),
+                assigned_files: assigned_files.of(Some(stack_id)),
+                branches,
+            }
+        })
+        .collect();
+    stacks.sort_by_key(|stack| !stack.applied);
+
+    if let Some(out) = out.for_human() {
+        let t = theme::get();
+        if stacks.is_empty() {
+            writeln!(out, "{}", t.hint.paint("No stacks in the workspace"))?;
+        }
+        for stack in &stacks {
+            let cli_id = match &stack.cli_id {
+                Some(cli_id) => t.cli_id.paint(cli_id.as_str()).to_string(),
+                None => t.hint.paint("unapplied").to_string(),
+            };
+            writeln!(
+                out,
+                "{cli_id} {} ({} commit{}, {} assigned file{})",
+                t.hint.paint(stack.id.as_str()),
+                stack.commits,
+                if stack.commits == 1 { "" } else { "s" },
+                stack.assigned_files,
+                if stack.assigned_files == 1 { "" } else { "s" },
+            )?;
+            for branch in &stack.branches {
+                writeln!(
+                    out,
+                    "  {} {} ({} commit{})",
+                    t.local_branch.paint(branch.name.as_str()),
+                    t.commit_id.paint(shorten_object_id(&repo, branch.tip)),
+                    branch.commits,
+                    if branch.commits == 1 { "" } else { "s" },
+                )?;
+            }
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(stacks)?;
+    }
+    Ok(())
+}
+
+/// JSON output of `but stack merge`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MergeOutcome {
+    /// The ID of the merged stack
+    id: String,
+    /// The short names of the branches of the merged stack, from top to bottom
+    branches: Vec<String>,
+    /// The number of uncommitted hunks that were assigned to the merged stack
+    reassigned_hunks: usize,
+}
+
+/// Merge the stacks of `a` and `b` into one, with the branches of `b` rebased on top of those of
+/// `a`, or the other way around as determined by `order`.
+///
+/// The hunks assigned to the stack that is put on top are assigned to the merged stack. If any
+/// commit conflicts after rebasing, the workspace is restored and the conflicts are returned as
+/// error, so neither stack changes.
+pub fn merge(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    a: &str,
+    b: &str,
+    order: MergeOrder,
+) -> anyhow::Result<()> {
+    let mut guard = ctx.exclusive_worktree_access();
+    let id_map = IdMap::new_from_context(ctx, None, guard.read_permission())?;
+    let a = resolve_lane(ctx, &id_map, a, out)?;
+    let b = resolve_lane(ctx, &id_map, b, out)?;
+    if a.stack_id == b.stack_id {
+        bail!(
+            "'{}' and '{}' are already in the same stack",
+            a.name,
+            b.name
+        );
+    }
+    let (bottom, top) = match order {
+        MergeOrder::AFirst => (a, b),
+        MergeOrder::BFirst => (b, a),
+    };
+
+    // Only the branches are needed, not their commits.
+    let branches_of = |stack_id: StackId| -> anyhow::Result<Vec<FullName>> {
+        let details = but_api::legacy::workspace::stack_details_limited(ctx, Some(stack_id), 0)
+            .with_context(|| format!("Couldn't find the stack {stack_id} in the workspace"))?;
+        Ok(details
+            .branch_details
+            .into_iter()
+            .map(|branch| branch.details.reference)
+            .collect())
+    };
+    let bottom_top_branch = branches_of(bottom.stack_id)?
+        .into_iter()
+        .next()
+        .context("BUG: stacks always have a branch")?;
+    let top_branches = branches_of(top.stack_id)?;
+
+    let top_assignments: Vec<HunkAssignment> =
+        diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?
+            .assignments
+            .into_iter()
+            .filter(|assignment| assignment.stack_id == Some(top.stack_id))
+            .collect();
+    let conflicts_before: HashSet<gix::ObjectId> = find_conflicted_commits(ctx)?
+        .into_values()
+        .flatten()
+        .map(|commit| commit.commit_oid)
+        .collect();
+
+    // Moving the branches one by one rewrites history several times, so restore the snapshot if
+    // any of the moves fails or conflicts to leave both stacks as they were.
+    let snapshot = ctx.create_snapshot(
+        SnapshotDetails::new(OperationKind::MoveBranch),
+        guard.write_permission(),
+    )?;
+    let merge_result = (|| -> anyhow::Result<Vec<String>> {
+        // Bottom-most first, so each branch lands on top of the one that was below it.
+        let mut onto = bottom_top_branch.clone();
+        for branch in top_branches.iter().rev() {
+            but_api::branch::move_branch_with_perm(
+                ctx,
+                branch.as_ref(),
+                onto.as_ref(),
+                DryRun::No,
+                guard.write_permission(),
+            )?;
+            onto = branch.clone();
+        }
+
+        let mut conflicts = Vec::new();
+        for (branch, commits) in find_conflicted_commits(ctx)? {
+            for commit in commits {
+                if conflicts_before.contains(&commit.commit_oid) {
+                    continue;
+                }
+                let mut conflict = format!(
+                    "  {} {} (on {branch})",
+                    commit.commit_short_id, commit.commit_message
+                );
+                for file in &commit.conflicted_files {
+                    write!(conflict, "\n    {}", sanitize_terminal_text(file))?;
+                }
+                conflicts.push(conflict);
+            }
+        }
+        Ok(conflicts)
+    })();
+    let failure = match merge_result {
+        Ok(conflicts) if conflicts.is_empty() => None,
+        Ok(conflicts) => Some(anyhow::anyhow!(
+            "Merging the stacks would cause conflicts, so both were left untouched:\n{}",
+            conflicts.join("\n")
+        )),
+        Err(err) => Some(err),
+    };
+    if let Some(err) = failure {
+        ctx.restore_snapshot(
+            snapshot,
+            gitbutler_oplog::RestoreKind::ExplicitRestoreFromSnapshot,
+            guard.write_permission(),
+        )
+        .with_context(|| format!("Failed to restore snapshot {snapshot} after failing to merge"))?;
+        return Err(err);
+    }
+
+    let (merged_stack_id, merged) = but_api::legacy::workspace::all_stack_details(ctx, None)?
+        .into_iter()
+        .find(|(_, details)| {
+            details
+                .branch_details
+                .iter()
+                .any(|branch| branch.reference == bottom_top_branch)
+        })
+        .context("Couldn't find the merged stack in the workspace")?;
+    let reassigned_hunks = top_assignments.len();
+    if !top_assignments.is_empty() {
+        let requests = top_assignments
+            .into_iter()
+            .map(|assignment| HunkAssignmentRequest {
+                hunk_header: assignment.hunk_header,
+                path_bytes: assignment.path_bytes,
+                target: Some(HunkAssignmentTarget::Stack {
+                    stack_id: merged_stack_id,
+                }),
+            })
+            .collect();
+        but_api::diff::assign_hunk_with_perm(ctx, requests, guard.write_permission())?;
+    }
+
+    let branches: Vec<String> = merged
+        .branch_details
+        .iter()
+        .map(|branch| branch.name.to_string())
+        .collect();
+    if let Some(out) = out.for_human() {
+        let t = theme::get();
+        writeln!(
+            out,
+            "{} Stacked {} on top of {}:",
+            t.sym().success,
+            t.local_branch.paint(top.name.as_str()),
+            t.local_branch.paint(bottom.name.as_str()),
+        )?;
+        for branch in &branches {
+            writeln!(out, "  {}", t.local_branch.paint(branch.as_str()))?;
+        }
+        if reassigned_hunks > 0 {
+            writeln!(
+                out,
+                "{}",
+                t.hint.paint(format!(
+                    "Assigned {reassigned_hunks} hunk{} of {} to the merged stack",
+                    if reassigned_hunks == 1 { "" } else { "s" },
+                    top.name
+                ))
+            )?;
+        }
+    } else if let Some(out) = out.for_json() {
+        out.write_value(MergeOutcome {
+            id: merged_stack_id.to_string(),
+            branches,
+            reassigned_hunks,
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! The number of added and removed lines of uncommitted files, as shown by `but status`.
+
+use std::ops::AddAssign;
+
+use bstr::{BStr, ByteSlice};
+use but_core::ui;
+use ratatui::text::Span;
+use serde::Serialize;
+
+use super::assignment::FileAssignment;
+
+/// The number of lines added to and removed from a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiffStat {
+    /// The number of added lines
+    pub insertions: usize,
+    /// The number of removed lines
+    pub deletions: usize,
+    /// Whether the file is binary or too large to be diffed, in which case its lines aren't counted
+    pub binary: bool,
+}
+
+impl DiffStat {
+    /// Count the added and removed lines of all hunks of `file`.
+    ///
+    /// Files without any textual hunk, like binary files, renames without changes or empty files,
+    /// are diffed once more to tell them apart.
+    pub fn of_file(
+        repo: &gix::Repository,
+        file: &FileAssignment,
+        worktree_changes: &[ui::TreeChange],
+    ) -> anyhow::Result<Self> {
+        let diffs: Vec<_> = file
+            .assignments
+            .iter()
+            .filter_map(|assignment| assignment.inner.diff.as_ref())
+            .collect();
+        if !diffs.is_empty() {
+            let mut stat = DiffStat::default();
+            for diff in diffs {
+                stat += DiffStat::of_hunk(diff.as_bstr());
+            }
+            return Ok(stat);
+        }
+
+        let Some(change) = worktree_changes
+            .iter()
+            .find(|change| change.path_bytes == file.path)
+        else {
+            return Ok(DiffStat::default());
+        };
+        let change: but_core::TreeChange = change.clone().into();
+        Ok(match change.unified_patch(repo, 0)? {
+            Some(but_core::UnifiedPatch::Binary | but_core::UnifiedPatch::TooLarge { .. }) => {
+                DiffStat {
+                    binary: true,
+                    ..Default::default()
+                }
+            }
+            Some(but_core::UnifiedPatch::Patch {
+                lines_added,
+                lines_removed,
+                ..
+            }) => DiffStat {
+                insertions: usize::try_from(lines_added).unwrap_or(usize::MAX),
+                deletions: usize::try_from(lines_removed).unwrap_or(usize::MAX),
+                binary: false,
+            },
+            None => DiffStat::default(),
+        })
+    }
+
+    /// Count the added and removed lines in the unified `diff` of a single hunk.
+    fn of_hunk(diff: &BStr) -> Self {
+        let mut stat = DiffStat::default();
+        for line in diff.lines() {
+            match line.first() {
+                Some(b'+') => stat.insertions += 1,
+                Some(b'-') => stat.deletions += 1,
+                _ => {}
+            }
+        }
+        stat
+    }
+
+    /// The text shown for this stat, like `+12 -3`, or `bin` for binary files.
+    fn text(&self) -> String {
+        if self.binary {
+            "bin".into()
+        } else {
+            format!("+{} -{}", self.insertions, self.deletions)
+        }
+    }
+
+    /// The width of [`Self::text()`] in columns.
+    pub fn width(&self) -> usize {
+        self.text().len()
+    }
+
+    /// The spans to show this stat right-aligned in a column that is `width` columns wide.
+    pub fn spans(&self, width: usize) -> Vec<Span<'static>> {
+        let t = crate::theme::get();
+        let padding = Span::raw(" ".repeat(width.saturating_sub(self.width())));
+        if self.binary {
+            return Vec::from([padding, Span::styled("bin", t.hint)]);
+        }
+        Vec::from([
+            padding,
+            Span::styled(format!("+{}", self.insertions), t.addition),
+            Span::raw(" "),
+            Span::styled(format!("-{}", self.deletions), t.deletion),
+        ])
+    }
+}
+
+impl AddAssign for DiffStat {
+    fn add_assign(&mut self, rhs: Self) {
+        self.insertions = self.insertions.saturating_add(rhs.insertions);
+        self.deletions = self.deletions.saturating_add(rhs.deletions);
+        self.binary |= rhs.binary;
+    }
+}
+
+/// The sum of the [`DiffStat`] of a number of files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DiffStatTotal {
+    /// The number of files that were summed up.
+    pub files: usize,
+    /// The added and removed lines of all files.
+    pub lines: DiffStat,
+}
+
+impl DiffStatTotal {
+    /// Add the `stat` of one more file.
+    pub fn add(&mut self, stat: DiffStat) {
+        self.files += 1;
+        self.lines += stat;
+    }
+
+    /// The spans to show the total, like `(2 files, +12 -3)`.
+    pub fn spans(&self) -> Vec<Span<'static>> {
+        let t = crate::theme::get();
+        let files = if self.files == 1 { "file" } else { "files" };
+        Vec::from([
+            Span::styled(format!("({} {files}, ", self.files), t.hint),
+            Span::styled(format!("+{}", self.lines.insertions), t.addition),
+            Span::raw(" "),
+            Span::styled(format!("-{}", self.lines.deletions), t.deletion),
+            Span::styled(")", t.hint),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::ByteSlice;
+
+    use super::{DiffStat, DiffStatTotal};
+
+    #[test]
+    fn hunk_lines_are_counted_without_header_and_context() {
+        let diff = b"@@ -1,3 +1,3 @@\n context\n-removed\n+added\n+added too\n\\ No newline at end of file\n";
+        assert_eq!(
+            DiffStat::of_hunk(diff.as_bstr()),
+            DiffStat {
+                insertions: 2,
+                deletions: 1,
+                binary: false
+            }
+        );
+    }
+
+    #[test]
+    fn text_is_right_aligned_to_width() {
+        let stat = DiffStat {
+            insertions: 1,
+            deletions: 0,
+            binary: false,
+        };
+        let text = |spans: Vec<ratatui::text::Span<'_>>| {
+            spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        };
+        assert_eq!(text(stat.spans(7)), "  +1 -0");
+        let binary = DiffStat {
+            binary: true,
+            ..Default::default()
+        };
+        assert_eq!(text(binary.spans(7)), "    bin");
+
+        let mut total = DiffStatTotal::default();
+        total.add(stat);
+        assert_eq!(text(total.spans()), "(1 file, +1 -0)");
+        total.add(binary);
+        assert_eq!(text(total.spans()), "(2 files, +1 -0)");
+    }
+}
@@ -0,0 +1,248 @@
+//! Collapsing untracked directories and grouping uncommitted files by directory, as shown by
+//! `but status`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use super::assignment::FileAssignment;
+
+/// The number of untracked files below a directory from which on they are shown as a single line
+/// by default.
+pub(crate) const DEFAULT_COLLAPSE_THRESHOLD: usize = 20;
+
+/// One line of uncommitted files in `but status`.
+#[derive(Debug)]
+pub(crate) enum FileEntry<'a> {
+    /// A single file.
+    File(&'a FileAssignment),
+    /// All untracked files below `dir`, which ends with a slash, shown as a single line.
+    UntrackedDir {
+        dir: BString,
+        files: Vec<&'a FileAssignment>,
+    },
+}
+
+impl FileEntry<'_> {
+    /// The path of the file, or of the directory with a trailing slash.
+    pub fn path(&self) -> &BStr {
+        match self {
+            FileEntry::File(fa) => fa.path.as_bstr(),
+            FileEntry::UntrackedDir { dir, .. } => dir.as_bstr(),
+        }
+    }
+
+    /// The files shown in this line.
+    pub fn files(&self) -> Vec<&FileAssignment> {
+        match self {
+            FileEntry::File(fa) => Vec::from([*fa]),
+            FileEntry::UntrackedDir { files, .. } => files.clone(),
+        }
+    }
+
+    /// The top-level directory this entry is in, with a trailing slash, or `None` for files at
+    /// the root of the worktree.
+    pub fn top_level_dir(&self) -> Option<&BStr> {
+        parent_dirs(self.path()).next()
+    }
+}
+
+/// Turn `files` into the lines to show them with, in the same order.
+///
+/// Untracked files are collapsed into the outermost directory below which all uncommitted files
+/// are untracked and among `files`, so that the directory selects exactly these files, if there
+/// are at least `min_files` of them. `uncommitted_paths` are the paths of all uncommitted files.
+/// Nothing is collapsed if `min_files` is `None`.
+pub(crate) fn collapse_untracked<'a>(
+    files: &'a [FileAssignment],
+    is_untracked: impl Fn(&BStr) -> bool,
+    uncommitted_paths: &[&BStr],
+    min_files: Option<usize>,
+) -> Vec<FileEntry<'a>> {
+    let Some(min_files) = min_files else {
+        return files.iter().map(FileEntry::File).collect();
+    };
+    let untracked: BTreeSet<&BStr> = files
+        .iter()
+        .map(|fa| fa.path.as_bstr())
+        .filter(|path| is_untracked(path))
+        .collect();
+    let only_untracked_below = |dir: &BStr| {
+        uncommitted_paths
+            .iter()
+            .filter(|path| path.starts_with(dir))
+            .all(|path| untracked.contains(path))
+    };
+
+    let dirs: Vec<Option<BString>> = files
+        .iter()
+        .map(|fa| {
+            let path = fa.path.as_bstr();
+            if !untracked.contains(path) {
+                return None;
+            }
+            parent_dirs(path)
+                .find(|dir| only_untracked_below(dir))
+                .map(ToOwned::to_owned)
+        })
+        .collect();
+    let mut files_by_dir = BTreeMap::<&BString, Vec<&FileAssignment>>::new();
+    for (fa, dir) in files.iter().zip(&dirs) {
+        if let Some(dir) = dir {
+            files_by_dir.entry(dir).or_default().push(fa);
+        }
+    }
+    // The files of directories that are collapsed, until the directory is shown.
+    let mut collapsed: BTreeMap<&BString, Option<Vec<&FileAssignment>>> = files_by_dir
+        .into_iter()
+        .filter(|(_, files)| files.len() >= min_files)
+        .map(|(dir, files)| (dir, Some(files)))
+        .collect();
+
+    let mut entries = Vec::new();
+    for (fa, dir) in files.iter().zip(&dirs) {
+        match dir
+            .as_ref()
+            .and_then(|dir| Some((dir, collapsed.get_mut(dir)?)))
+        {
+            Some((dir, files)) => {
+                // The directory is shown where its first file would have been.
+                if let Some(files) = files.take() {
+                    entries.push(FileEntry::UntrackedDir {
+                        dir: dir.clone(),
+                        files,
+                    });
+                }
+            }
+            None => entries.push(FileEntry::File(fa)),
+        }
+    }
+    entries
+}
+
+/// Sort `entries` by their top-level directory, keeping their order otherwise, and split them
+/// into one group per directory, with the files at the root of the worktree coming first.
+pub(crate) fn group_by_top_level_dir<'a, 'b>(
+    entries: &'b mut [FileEntry<'a>],
+) -> Vec<(Option<BString>, &'b [FileEntry<'a>])> {
+    entries.sort_by(|a, b| a.top_level_dir().cmp(&b.top_level_dir()));
+    entries
+        .chunk_by(|a, b| a.top_level_dir() == b.top_level_dir())
+        .map(|group| (group[0].top_level_dir().map(ToOwned::to_owned), group))
+        .collect()
+}
+
+/// The directories `path` is in, each with a trailing slash, from the outermost to the innermost.
+fn parent_dirs(path: &BStr) -> impl Iterator<Item = &BStr> {
+    path.iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'/')
+        .map(move |(end, _)| path[..=end].as_bstr())
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::{BStr, ByteSlice};
+
+    use super::{FileEntry, collapse_untracked, group_by_top_level_dir};
+    use crate::command::legacy::status::assignment::FileAssignment;
+
+    fn files(paths: &[&str]) -> Vec<FileAssignment> {
+        paths
+            .iter()
+            .map(|path| FileAssignment {
+                path: (*path).into(),
+                assignments: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn lines(entries: &[FileEntry<'_>]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                FileEntry::File(fa) => fa.path.to_string(),
+                FileEntry::UntrackedDir { dir, files } => format!("{dir} ({})", files.len()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn untracked_files_collapse_into_the_outermost_directory_with_only_untracked_files() {
+        let files = files(&[
+            "README.md",
+            "build/gen/a.rs",
+            "build/gen/b.rs",
+            "build/out/c.o",
+            "src/lib.rs",
+            "src/new/x.rs",
+            "src/new/y.rs",
+            "src/z.rs",
+        ]);
+        let is_untracked = |path: &BStr| path != "README.md" && path != "src/lib.rs";
+        let paths: Vec<_> = files.iter().map(|fa| fa.path.as_bstr()).collect();
+
+        let entries = collapse_untracked(&files, is_untracked, &paths, Some(2));
+        assert_eq!(
+            lines(&entries),
+            [
+                "README.md",
+                "build/ (3)",
+                "src/lib.rs",
+                "src/new/ (2)",
+                "src/z.rs"
+            ],
+            "`src/` has a tracked file, and `src/z.rs` is on its own"
+        );
+
+        let entries = collapse_untracked(&files, is_untracked, &paths, Some(3));
+        assert_eq!(
+            lines(&entries),
+            [
+                "README.md",
+                "build/ (3)",
+                "src/lib.rs",
+                "src/new/x.rs",
+                "src/new/y.rs",
+                "src/z.rs"
+            ],
+            "directories with fewer files than the threshold stay expanded"
+        );
+
+        let entries = collapse_untracked(&files, is_untracked, &paths, None);
+        assert_eq!(entries.len(), files.len(), "nothing is collapsed");
+    }
+
+    #[test]
+    fn directories_with_uncommitted_files_elsewhere_are_not_collapsed() {
+        let files = files(&["gen/a.rs", "gen/b.rs"]);
+        let mut paths: Vec<_> = files.iter().map(|fa| fa.path.as_bstr()).collect();
+        paths.push("gen/c.rs".as_bytes().as_bstr());
+
+        let entries = collapse_untracked(&files, |_| true, &paths, Some(2));
+        assert_eq!(lines(&entries), ["gen/a.rs", "gen/b.rs"]);
+    }
+
+    #[test]
+    fn entries_are_grouped_by_top_level_directory_with_root_files_first() {
+        let files = files(&["a/x.rs", "b.txt", "a/y/z.rs", "c/d.rs"]);
+        let paths: Vec<_> = files.iter().map(|fa| fa.path.as_bstr()).collect();
+        let mut entries = collapse_untracked(&files, |_| false, &paths, Some(2));
+
+        let groups: Vec<_> = group_by_top_level_dir(&mut entries)
+            .into_iter()
+            .map(|(dir, entries)| (dir.map(|dir| dir.to_string()), lines(entries)))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (None, Vec::from(["b.txt".to_string()])),
+                (
+                    Some("a/".to_string()),
+                    Vec::from(["a/x.rs".to_string(), "a/y/z.rs".to_string()])
+                ),
+                (Some("c/".to_string()), Vec::from(["c/d.rs".to_string()])),
+            ]
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! The `but status --ids-only` output, the IDs of the uncommitted files and hunks for piping into
+//! tools like `fzf`.
+//!
+//! Each uncommitted file is one line of `<id>\t<path>`, followed by one line of
+//! `<id>\t<path>\t<hunk-header>` for each of its hunks. Paths with special or non-UTF-8
+//! characters, including tabs, are quoted like Git does.
+
+use bstr::BString;
+use but_ctx::Context;
+
+use crate::{
+    id::{IdMap, UncommittedFile},
+    utils::{
+        OutputChannel,
+        quoted_path::{quote_path, quote_path_enabled},
+    },
+};
+
+/// Write the IDs of the uncommitted files and hunks of the worktree to `out`.
+pub(crate) fn worktree(ctx: &mut Context, out: &mut OutputChannel) -> anyhow::Result<()> {
+    let id_map = {
+        let guard = ctx.shared_worktree_access();
+        IdMap::new_from_context(ctx, None, guard.read_permission())?
+    };
+    let quote_non_ascii = quote_path_enabled(&*ctx.repo.get()?);
+    let mut files: Vec<_> = id_map.uncommitted_files.values().collect();
+    files.sort_by(|a, b| a.path().cmp(b.path()));
+    for file in files {
+        out.write_bytes(&format_file(file, quote_non_ascii))?;
+    }
+    id_map.remember_displayed_ids(ctx);
+    Ok(())
+}
+
+fn format_file(file: &UncommittedFile, quote_non_ascii: bool) -> BString {
+    let path = quote_path(file.path(), quote_non_ascii);
+    let mut lines = BString::from(format!("{}\t", file.short_id));
+    lines.extend_from_slice(&path);
+    lines.push(b'\n');
+    for (hunk_id, hunk) in file.hunk_ids_and_assignments() {
+        lines.extend_from_slice(format!("{hunk_id}\t").as_bytes());
+        lines.extend_from_slice(&path);
+        if let Some(header) = hunk.hunk_header {
+            lines.extend_from_slice(
+                format!(
+                    "\t@@ -{},{} +{},{} @@",
+                    header.old_start, header.old_lines, header.new_start, header.new_lines
+                )
+                .as_bytes(),
+            );
+        }
+        lines.push(b'\n');
+    }
+    lines
+}
@@ -9,9 +9,10 @@
 //! Non-goals:
 //! - Completeness: The output structures do not include all the data that the internal but-api has.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::Context as _;
+use bstr::BString;
 use but_graph::SegmentIndex;
 use but_workspace::ref_info::LocalCommit;
 use chrono::{DateTime, Utc};
@@ -22,18 +23,29 @@ use crate::{
     id::{RemoteCommitWithId, SegmentWithId, WorkspaceCommitWithId},
 };
 
-use super::StatusContext;
+use super::{StatusContext, diffstat::DiffStat};
+
+/// The version of the [`WorkspaceStatus`] schema.
+///
+/// Increment it whenever fields are removed, renamed or change their meaning.
+/// Adding fields doesn't require a new version.
+pub(crate) const STATUS_SCHEMA_VERSION: u32 = 1;
 
 /// JSON output for the `but status` command
 /// This represents the status of the GitButler "workspace".
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceStatus {
+    /// Always [`STATUS_SCHEMA_VERSION`], so consumers can detect changes.
+    schema_version: u32,
     /// Represents uncommitted changes that are not assigned to any stack
-    uncommitted_changes: Vec<FileChange>,
+    uncommitted_changes: Vec<UncommittedChange>,
     /// Uncommitted files with unresolved merge conflicts in the index; not committable until resolved.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     conflicted_files: Vec<String>,
+    /// Commits of the applied stacks that are conflicted and need to be resolved with `but resolve`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    conflicts: Vec<Conflict>,
     /// The stacks that are applied in the current workspace
     stacks: Vec<Stack>,
     /// The most recent common merge base between all applied stacks and the target upstream branch
@@ -59,15 +71,18 @@ pub(crate) struct UpstreamState {
 
 impl WorkspaceStatus {
     pub fn new(
-        uncommitted_changes: Vec<FileChange>,
+        uncommitted_changes: Vec<UncommittedChange>,
         conflicted_files: Vec<String>,
+        conflicts: Vec<Conflict>,
         stacks: Vec<Stack>,
         merge_base: Commit,
         upstream_state: UpstreamState,
     ) -> Self {
         Self {
+            schema_version: STATUS_SCHEMA_VERSION,
             uncommitted_changes,
             conflicted_files,
+            conflicts,
             stacks,
             merge_base,
             upstream_state,
@@ -75,6 +90,34 @@ impl WorkspaceStatus {
     }
 }
 
+/// A conflicted commit in one of the applied stacks
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Conflict {
+    /// The name of the branch the commit is on
+    branch: String,
+    /// The CLI ID of the commit, to be passed to `but resolve`
+    cli_id: String,
+    /// The full ID of the commit
+    commit_id: String,
+    /// The first line of the commit message
+    subject: String,
+    /// The number of files with conflicts in the commit
+    conflicted_file_count: usize,
+}
+
+impl From<super::ConflictedCommit> for Conflict {
+    fn from(commit: super::ConflictedCommit) -> Self {
+        Conflict {
+            branch: commit.branch,
+            cli_id: commit.cli_id,
+            commit_id: commit.commit_id.to_string(),
+            subject: commit.subject,
+            conflicted_file_count: commit.conflicted_files,
+        }
+    }
+}
+
 /// Represents a stack of branches applied in the current workspace
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -82,13 +125,17 @@ pub(crate) struct Stack {
     /// A unique ID specific to the current state of the workspace, to be used by other CLI operations (e.g `rub`)
     cli_id: String,
     /// Represents uncommitted changes assigned to this stack
-    assigned_changes: Vec<FileChange>,
+    assigned_changes: Vec<UncommittedChange>,
     /// The branches that are part of this stack, newest first
     branches: Vec<Branch>,
 }
 
 impl Stack {
-    pub fn new(cli_id: String, assigned_changes: Vec<FileChange>, branches: Vec<Branch>) -> Self {
+    pub fn new(
+        cli_id: String,
+        assigned_changes: Vec<UncommittedChange>,
+        branches: Vec<Branch>,
+    ) -> Self {
         Self {
             cli_id,
             assigned_changes,
@@ -230,8 +277,41 @@ pub(crate) struct FileChange {
     cli_id: String,
     /// The file path, UTF-8 encoded (note - this can be lossy for some Operating Systems)
     file_path: String,
+    /// The raw bytes of the file path, only present if it isn't valid UTF-8 and `file_path` is lossy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_path_bytes: Option<Vec<u8>>,
     /// The type of change that happened to the file
     change_type: ChangeType,
+    /// The path the file had before it was renamed, only present for renames
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_file_path: Option<String>,
+    /// The raw bytes of the path before the rename, only present if it isn't valid UTF-8 and
+    /// `previous_file_path` is lossy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_file_path_bytes: Option<Vec<u8>>,
+}
+
+/// A change to a file in the worktree that isn't committed yet
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UncommittedChange {
+    #[serde(flatten)]
+    change: FileChange,
+    /// The number of added and removed lines, unless `--no-stat` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stat: Option<DiffStat>,
+    /// The hunks of the change, in the order they appear in the file
+    hunks: Vec<Hunk>,
+}
+
+/// A hunk of an uncommitted change
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Hunk {
+    /// The ID of just this hunk, like `<file-cli-id>:<hunk-id>`, to be used by other CLI operations (e.g `commit --changes`)
+    cli_id: String,
+    /// The lines of the file the hunk covers, or `None` if the change has no textual diff, like for binary files
+    hunk_header: Option<but_core::HunkHeader>,
 }
 
 /// The type of change that happened to a file
@@ -369,10 +449,29 @@ impl Branch {
 
 impl FileChange {
     pub fn from_tree_change(cli_id: String, tree_change: but_core::ui::TreeChange) -> Self {
+        let file_path_bytes = std::str::from_utf8(&tree_change.path_bytes)
+            .is_err()
+            .then(|| tree_change.path_bytes.to_vec());
+        let (previous_file_path, previous_file_path_bytes) = match &tree_change.status {
+            but_core::ui::TreeStatus::Rename {
+                previous_path,
+                previous_path_bytes,
+                ..
+            } => (
+                Some(previous_path.to_string()),
+                std::str::from_utf8(previous_path_bytes)
+                    .is_err()
+                    .then(|| previous_path_bytes.to_vec()),
+            ),
+            _ => (None, None),
+        };
         FileChange {
             cli_id,
             file_path: tree_change.path.to_string(),
+            file_path_bytes,
             change_type: tree_change.status.into(),
+            previous_file_path,
+            previous_file_path_bytes,
         }
     }
 }
@@ -510,17 +609,36 @@ pub(crate) fn gix_time_to_rfc3339(time: &gix::date::Time) -> String {
         .unwrap_or_default()
 }
 
-/// Convert file assignments to JSON FileChange objects
+/// Convert file assignments to JSON UncommittedChange objects
 fn convert_file_assignments(
     assignments: &[super::assignment::FileAssignment],
     worktree_changes: &[but_core::ui::TreeChange],
-) -> Vec<FileChange> {
+    id_map: &crate::IdMap,
+    diff_stats: Option<&BTreeMap<BString, DiffStat>>,
+) -> Vec<UncommittedChange> {
     assignments
         .iter()
         .filter_map(|fa| {
             let cli_id = fa.assignments[0].cli_id.to_string();
             let change = worktree_changes.iter().find(|c| c.path_bytes == fa.path)?;
-            Some(FileChange::from_tree_change(cli_id, change.clone()))
+            let hunks = id_map
+                .uncommitted_files
+                .values()
+                .find(|file| *file.path() == fa.path)
+                .map(|file| {
+                    file.hunk_ids_and_assignments()
+                        .map(|(cli_id, hunk)| Hunk {
+                            cli_id,
+                            hunk_header: hunk.hunk_header,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(UncommittedChange {
+                change: FileChange::from_tree_change(cli_id, change.clone()),
+                stat: diff_stats.map(|stats| stats.get(&fa.path).copied().unwrap_or_default()),
+                hunks,
+            })
         })
         .collect()
 }
@@ -585,8 +703,12 @@ pub(super) fn build_workspace_status_json(
 
     for (stack_id, (stack_with_id, assignments)) in &status_ctx.stack_details {
         if stack_id.is_none() {
-            json_uncommitted_changes =
-                convert_file_assignments(assignments, &status_ctx.worktree_changes);
+            json_uncommitted_changes = convert_file_assignments(
+                assignments,
+                &status_ctx.worktree_changes,
+                &status_ctx.id_map,
+                status_ctx.diff_stats.as_ref(),
+            );
         } else if let (Some(stack_id), Some(stack_with_id)) = (stack_id, stack_with_id) {
             let stack_cli_id = status_ctx
                 .id_map
@@ -594,8 +716,12 @@ pub(super) fn build_workspace_status_json(
                 .map(|id| id.to_short_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
-            let json_assigned_changes =
-                convert_file_assignments(assignments, &status_ctx.worktree_changes);
+            let json_assigned_changes = convert_file_assignments(
+                assignments,
+                &status_ctx.worktree_changes,
+                &status_ctx.id_map,
+                status_ctx.diff_stats.as_ref(),
+            );
 
             let json_branches = stack_with_id
                 .segments
@@ -717,6 +843,10 @@ pub(super) fn build_workspace_status_json(
     Ok(WorkspaceStatus::new(
         json_uncommitted_changes,
         status_ctx.conflicted_paths.clone(),
+        super::conflicted_commits(repo, status_ctx)
+            .into_iter()
+            .map(Conflict::from)
+            .collect(),
         json_stacks,
         merge_base_commit,
         upstream_state_json,
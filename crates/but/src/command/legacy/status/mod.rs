@@ -5,7 +5,7 @@ use assignment::FileAssignment;
 use bstr::{BStr, BString, ByteSlice};
 use but_api::diff::ComputeLineStats;
 use but_core::{
-    ChangeId, IgnoredWorktreeTreeChangeStatus, RepositoryExt, TreeStatus,
+    ChangeId, HunkHeader, IgnoredWorktreeTreeChangeStatus, RepositoryExt, TreeStatus,
     ref_metadata::StackId,
     sync::{RepoExclusive, RepoExclusiveGuard},
     ui,
@@ -13,18 +13,25 @@ use but_core::{
 use but_ctx::Context;
 use but_forge::ForgeReview;
 use but_graph::SegmentIndex;
+use but_hunk_dependency::ui::{HunkDependencies, HunkLockTarget};
 use but_workspace::{
     ref_info::{Commit, LocalCommit, LocalCommitRelation, Segment},
     ui::PushStatus,
 };
+use diffstat::{DiffStat, DiffStatTotal};
+use directories::FileEntry;
 use gitbutler_operating_modes::OperatingMode;
 use gix::date::time::CustomFormat;
 use ratatui::{style::Modifier, text::Span};
 use serde::Serialize;
 
 use crate::{
-    CLI_DATE, CliId, IdMap,
-    args::OutputFormat,
+    CLI_DATE, CliId, CliResult, IdMap,
+    args::{
+        OutputFormat,
+        atoms::{CliIdArg, Priority, Purpose, ResolvedCliIdArg},
+    },
+    bad_input,
     command::legacy::{
         forge::review,
         status::output::{
@@ -40,13 +47,22 @@ use crate::{
     },
     tui::text::truncate_text,
     utils::{
-        InputOutputChannel, OutputChannel, WriteWithUtils, shorten_hex_object_id,
-        shorten_object_id, time::format_relative_time_verbose,
+        InputOutputChannel, OutputChannel, WriteWithUtils,
+        binary_files::{describe_binary_change, sanitize_for_terminal},
+        quoted_path::{display_path, quote_path_enabled},
+        rejection, shorten_hex_object_id, shorten_object_id,
+        submodules::{SubmoduleState, changed_submodule_states, is_submodule_change},
+        time::format_relative_time_verbose,
     },
 };
 
 pub(crate) mod assignment;
+pub(crate) mod diffstat;
+pub(crate) mod directories;
+pub(crate) mod ids_only;
 pub(crate) mod json;
+pub(crate) mod porcelain;
+pub(crate) mod watch;
 
 mod output;
 mod render_oneshot;
@@ -54,6 +70,12 @@ mod tui;
 
 const DATE_ONLY: CustomFormat = CustomFormat::new("%Y-%m-%d");
 
+/// The git config key for the number of hunks shown per uncommitted file with `--verbose`.
+const MAX_HUNKS_PER_FILE_KEY: &str = "but.status.maxHunksPerFile";
+const DEFAULT_MAX_HUNKS_PER_FILE: usize = 10;
+/// The number of columns of a changed line shown next to the header of a hunk.
+const HUNK_SNIPPET_WIDTH: usize = 40;
+
 #[derive(Debug, Copy, Clone)]
 pub struct StatusFlags {
     pub show_files: FilesStatusFlag,
@@ -61,6 +83,14 @@ pub struct StatusFlags {
     pub refresh_prs: bool,
     pub show_upstream: bool,
     pub hint: bool,
+    /// Show the number of added and removed lines of each uncommitted file.
+    pub diff_stat: bool,
+    /// Which directories of untracked files to show as a single line.
+    pub collapse_untracked: CollapseUntracked,
+    /// Group uncommitted files by their top-level directory.
+    pub group_by_dir: bool,
+    /// List the hunks of each uncommitted file with their IDs, as `verbose` does.
+    pub ids: bool,
 }
 
 impl StatusFlags {
@@ -71,6 +101,10 @@ impl StatusFlags {
             refresh_prs: false,
             show_upstream: false,
             hint: false,
+            diff_stat: false,
+            collapse_untracked: CollapseUntracked::Never,
+            group_by_dir: false,
+            ids: false,
         }
     }
 
@@ -81,10 +115,46 @@ impl StatusFlags {
             refresh_prs: false,
             show_upstream: false,
             hint: false,
+            diff_stat: false,
+            collapse_untracked: CollapseUntracked::Never,
+            group_by_dir: false,
+            ids: false,
+        }
+    }
+}
+
+/// Which directories of untracked files are shown as a single line, like `node_modules/ (87 files)`.
+#[derive(Debug, Copy, Clone)]
+pub enum CollapseUntracked {
+    /// Show each untracked file on its own line.
+    Never,
+    /// Collapse directories with many untracked files.
+    PastThreshold,
+    /// Collapse all directories with more than one untracked file.
+    Always,
+}
+
+impl CollapseUntracked {
+    /// The number of untracked files a directory needs to have to be collapsed, or `None` if
+    /// no directory is collapsed.
+    pub fn min_files(self) -> Option<usize> {
+        match self {
+            CollapseUntracked::Never => None,
+            CollapseUntracked::PastThreshold => Some(directories::DEFAULT_COLLAPSE_THRESHOLD),
+            CollapseUntracked::Always => Some(2),
         }
     }
 }
 
+/// Restricts the status to the stack of a single branch.
+#[derive(Debug, Clone)]
+pub struct StackFilter {
+    /// The name or CLI ID of a branch, or the CLI ID of a stack.
+    pub target: CliIdArg,
+    /// Also show the changes that aren't assigned to any stack.
+    pub show_unassigned: bool,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum FilesStatusFlag {
     /// Don't show files for any commits.
@@ -193,6 +263,18 @@ struct StatusContext<'a> {
     worktree_changes: Vec<ui::TreeChange>,
     /// Uncommitted files with unresolved merge conflicts in the index; not committable until resolved.
     conflicted_paths: Vec<String>,
+    /// The added and removed lines of each uncommitted file by path, if [`StatusFlags::diff_stat`] is set.
+    diff_stats: Option<BTreeMap<BString, DiffStat>>,
+    /// The short name of the branch that uncommitted files were assigned to by a rule, by path.
+    auto_assigned: BTreeMap<BString, String>,
+    /// Whether paths with bytes outside of ASCII are quoted, as configured with `core.quotePath`.
+    quote_path: bool,
+    /// Whether the submodules that point to new commits have uncommitted changes inside of them, by path.
+    submodule_states: BTreeMap<BString, SubmoduleState>,
+    /// The uncommitted hunks that are locked to commits, if hunks are shown.
+    hunk_locks: Vec<HunkLock>,
+    /// A description of each uncommitted binary file by path, if hunks are shown.
+    binary_changes: BTreeMap<BString, String>,
     common_merge_base_data: CommonMergeBase,
     target_tip_id: gix::ObjectId,
     upstream_state: Option<UpstreamState>,
@@ -213,9 +295,57 @@ struct StatusContext<'a> {
     mode: &'a gitbutler_operating_modes::OperatingMode,
 }
 
-fn show_edit_mode_status(ctx: &mut Context, out: &mut OutputChannel) -> anyhow::Result<()> {
-    // Delegate to the resolve status logic to show actual conflict details
-    crate::command::legacy::resolve::show_resolve_status(ctx, out)
+/// Describe each binary file among `changes` by path, skipping files with hunks in `assignments`
+/// as only files without textual diff can be binary.
+fn binary_changes(
+    repo: &gix::Repository,
+    changes: &[ui::TreeChange],
+    assignments: &[but_hunk_assignment::HunkAssignment],
+) -> anyhow::Result<BTreeMap<BString, String>> {
+    let mut out = BTreeMap::new();
+    for change in changes {
+        let has_hunks = assignments.iter().any(|assignment| {
+            assignment.path_bytes == change.path_bytes && assignment.hunk_header.is_some()
+        });
+        if has_hunks {
+            continue;
+        }
+        let change: but_core::TreeChange = change.clone().into();
+        let patch = change.unified_patch(repo, 0)?;
+        if let Some(description) = describe_binary_change(repo, &change, patch.as_ref())? {
+            out.insert(change.path, description);
+        }
+    }
+    Ok(out)
+}
+
+/// An uncommitted hunk that depends on commits in the workspace, so it can only be committed to
+/// their stack.
+struct HunkLock {
+    path: BString,
+    hunk: HunkHeader,
+    /// The abbreviated commits the hunk is locked to, along with the name of their branch if known.
+    commits: Vec<(String, Option<String>)>,
+}
+
+fn show_edit_mode_status(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    metadata: &gitbutler_operating_modes::EditModeMetadata,
+) -> anyhow::Result<()> {
+    use gitbutler_commit::commit_ext::CommitExt as _;
+
+    let is_conflicted = ctx
+        .repo
+        .get()?
+        .find_commit(metadata.commit_oid)?
+        .is_conflicted();
+    if is_conflicted {
+        // Delegate to the resolve status logic to show actual conflict details
+        crate::command::legacy::resolve::show_resolve_status(ctx, out)
+    } else {
+        crate::command::legacy::edit::show_status(ctx, out, metadata)
+    }
 }
 
 pub(crate) fn worktree(
@@ -223,15 +353,16 @@ pub(crate) fn worktree(
     out: &mut OutputChannel,
     flags: StatusFlags,
     render_mode: StatusRenderMode,
-) -> anyhow::Result<()> {
+    filter: Option<&StackFilter>,
+) -> CliResult<()> {
     // Check if we're in edit mode first, before doing any expensive operations
     let mode = but_api::legacy::modes::operating_mode(ctx)?.operating_mode;
-    if let gitbutler_operating_modes::OperatingMode::Edit(_metadata) = mode {
-        // In edit mode, show the conflict resolution status
-        return show_edit_mode_status(ctx, out);
+    if let gitbutler_operating_modes::OperatingMode::Edit(metadata) = &mode {
+        // In edit mode, show the edit or conflict resolution status
+        return Ok(show_edit_mode_status(ctx, out, metadata)?);
     }
 
-    let status_ctx = {
+    let mut status_ctx = {
         let mut guard = ctx.exclusive_worktree_access();
         let format = out.format();
         build_status_context(
@@ -249,6 +380,11 @@ pub(crate) fn worktree(
         // Re-acquire repo for use after the async call
         let repo = ctx.repo.get()?;
 
+        if let Some(filter) = filter {
+            apply_stack_filter(&mut status_ctx, &repo, filter)?;
+        }
+        status_ctx.id_map.remember_displayed_ids(ctx);
+
         if let Some(out) = out.for_json() {
             let workspace_status = json::build_workspace_status_json(&status_ctx, &repo)?;
             out.write_value(workspace_status)?;
@@ -300,6 +436,62 @@ pub(crate) fn worktree(
     Ok(())
 }
 
+/// Keep only the stack selected by `filter` in `status_ctx`, along with the unassigned changes
+/// if they were asked for. Changes that aren't shown are also removed, so that the hints and
+/// counts derived from them describe what's displayed.
+fn apply_stack_filter(
+    status_ctx: &mut StatusContext<'_>,
+    repo: &gix::Repository,
+    filter: &StackFilter,
+) -> CliResult<()> {
+    let stack_id = match filter.target.resolve_in_workspace(
+        repo,
+        &status_ctx.id_map,
+        Purpose::Branch,
+        Some(Priority::Branch),
+    )? {
+        ResolvedCliIdArg::Stack(stack_id) => Some(stack_id),
+        ResolvedCliIdArg::Branch(branch) => status_ctx
+            .stack_details
+            .iter()
+            .find(|(_, (stack_with_id, _))| {
+                stack_with_id.as_ref().is_some_and(|stack| {
+                    stack.segments.iter().any(|segment| {
+                        segment
+                            .branch_name()
+                            .is_some_and(|name| name == branch.0.as_str())
+                    })
+                })
+            })
+            .and_then(|(stack_id, _)| *stack_id),
+        other => {
+            return Err(bad_input(format!(
+                "Cannot show the status of {}, expected a branch or a stack",
+                other.kind_for_humans()
+            ))
+            .into());
+        }
+    };
+    let Some(stack_id) = stack_id else {
+        return Err(bad_input(format!(
+            "Could not find the stack of branch '{}'",
+            filter.target
+        ))
+        .hint(CliIdArg::TARGET_MISSING_HINT)
+        .into());
+    };
+
+    status_ctx.stack_details.retain(|(id, _)| match id {
+        Some(id) => *id == stack_id,
+        None => filter.show_unassigned,
+    });
+    if !filter.show_unassigned {
+        status_ctx.worktree_changes.clear();
+        status_ctx.conflicted_paths.clear();
+    }
+    Ok(())
+}
+
 pub(crate) fn tui_with_options(
     ctx: &mut Context,
     mut guard: RepoExclusiveGuard,
@@ -422,6 +614,41 @@ fn build_status_context<'a>(
         .collect();
     conflicted_paths.sort();
 
+    let hunk_locks = match worktree_changes.dependencies.as_ref() {
+        Some(dependencies) if flags.verbose || flags.ids => {
+            let (repo, ws, _db) = ctx.workspace_and_db_with_perm(perm.read_permission())?;
+            hunk_locks(&repo, &ws, dependencies)
+        }
+        _ => Vec::new(),
+    };
+
+    let binary_changes = if flags.verbose || flags.ids {
+        let repo = ctx.repo.get()?;
+        binary_changes(
+            &repo,
+            &worktree_changes.worktree_changes.changes,
+            &worktree_changes.assignments,
+        )?
+    } else {
+        BTreeMap::new()
+    };
+
+    let rules = but_api::diff::assignment_rules(ctx)?;
+    let auto_assigned: BTreeMap<BString, String> = worktree_changes
+        .assignments
+        .iter()
+        .filter_map(|assignment| {
+            let branch_ref = assignment.branch_ref_bytes.as_ref()?;
+            let rule = but_hunk_assignment::rule_for(&rules, assignment.path_bytes.as_bstr())?;
+            (rule.branch_ref == *branch_ref).then(|| {
+                (
+                    assignment.path_bytes.clone(),
+                    branch_ref.shorten().to_string(),
+                )
+            })
+        })
+        .collect();
+
     let id_map = IdMap::new(
         stacks,
         worktree_changes.assignments.clone(),
@@ -436,6 +663,21 @@ fn build_status_context<'a>(
         FileAssignment::get_assignments_by_file(&id_map);
     let mut stack_details: Vec<StackEntry> = Vec::new();
 
+    let diff_stats = if flags.diff_stat {
+        let repo = ctx.repo.get()?;
+        Some(
+            assignments_by_file
+                .values()
+                .map(|file| {
+                    DiffStat::of_file(&repo, file, &worktree_changes.worktree_changes.changes)
+                        .map(|stat| (file.path.clone(), stat))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        )
+    } else {
+        None
+    };
+
     let uncommitted = assignments_by_file.values().cloned().collect();
     stack_details.push((None, (None, uncommitted)));
 
@@ -555,10 +797,29 @@ fn build_status_context<'a>(
     let is_paged = out.is_paged();
     let should_truncate_for_terminal = truncation_policy(format, render_mode, is_paged);
 
+    let submodule_states = changed_submodule_states(
+        &*ctx.repo.get()?,
+        worktree_changes
+            .worktree_changes
+            .changes
+            .iter()
+            .filter(|change| {
+                is_submodule_change(&change.status)
+                    && !matches!(change.status, ui::TreeStatus::Deletion { .. })
+            })
+            .map(|change| change.path_bytes.as_bstr()),
+    );
+
     Ok(StatusContext {
         stack_details,
         worktree_changes: worktree_changes.worktree_changes.changes,
         conflicted_paths,
+        diff_stats,
+        auto_assigned,
+        quote_path: quote_path_enabled(&*ctx.repo.get()?),
+        submodule_states,
+        hunk_locks,
+        binary_changes,
         common_merge_base_data,
         target_tip_id,
         upstream_state,
@@ -593,6 +854,7 @@ fn build_status_output(
     output: &mut StatusOutput<'_>,
 ) -> anyhow::Result<()> {
     print_update_notice(ctx, status_ctx, output)?;
+    print_conflicts(&ctx.repo.get()?, status_ctx, output)?;
     let has_merged_upstream_branch = print_worktree_status(ctx, status_ctx, output)?;
     print_upstream_state(ctx, status_ctx, output)?;
     print_common_merge_base_summary(status_ctx, output)?;
@@ -634,6 +896,134 @@ fn print_update_notice(
     Ok(())
 }
 
+/// A commit of an applied stack that is in a conflicted state and needs to be resolved.
+struct ConflictedCommit {
+    /// The name of the branch the commit is on.
+    branch: String,
+    /// The CLI ID of the commit, as shown in the stack listing.
+    cli_id: String,
+    commit_id: gix::ObjectId,
+    /// The first line of the commit message.
+    subject: String,
+    /// The number of files with conflicts in the commit.
+    conflicted_files: usize,
+}
+
+/// Collect the conflicted commits of the stacks in `status_ctx`, in the order they are listed.
+fn conflicted_commits(
+    repo: &gix::Repository,
+    status_ctx: &StatusContext<'_>,
+) -> Vec<ConflictedCommit> {
+    let segments = status_ctx
+        .stack_details
+        .iter()
+        .filter_map(|(_, (stack_with_id, _))| stack_with_id.as_ref())
+        .flat_map(|stack_with_id| &stack_with_id.segments);
+    let mut conflicted = Vec::new();
+    for segment in segments {
+        for commit in &segment.workspace_commits {
+            let Some(local_commit) = status_ctx
+                .local_commits_by_id
+                .get(&commit.commit_id())
+                .filter(|local_commit| local_commit.inner.has_conflicts)
+            else {
+                continue;
+            };
+            conflicted.push(ConflictedCommit {
+                branch: segment.branch_name().unwrap_or_default().to_string(),
+                cli_id: commit
+                    .change_id
+                    .as_ref()
+                    .map(|change_id| change_id.padded_short_id())
+                    .unwrap_or_else(|| commit.short_id.clone()),
+                commit_id: commit.commit_id(),
+                subject: local_commit
+                    .inner
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_str_lossy()
+                    .into_owned(),
+                conflicted_files: super::resolve::conflicted_files(repo, commit.commit_id()).len(),
+            });
+        }
+    }
+    conflicted
+}
+
+/// Print the conflicted commits at the top of the status, so they are noticed before anything
+/// else. Nothing is printed if there are none.
+fn print_conflicts(
+    repo: &gix::Repository,
+    status_ctx: &StatusContext<'_>,
+    output: &mut StatusOutput<'_>,
+) -> anyhow::Result<()> {
+    let conflicts = conflicted_commits(repo, status_ctx);
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    let t = crate::theme::get();
+    output.warning(Vec::from([Span::styled(
+        "⚠ Conflicted commits:",
+        t.attention,
+    )]))?;
+    for conflict in conflicts {
+        let files = if conflict.conflicted_files == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        output.warning(Vec::from([
+            Span::raw("  "),
+            Span::styled(conflict.branch, t.local_branch),
+            Span::raw(" "),
+            Span::styled(conflict.cli_id, t.cli_id),
+            Span::raw(" "),
+            Span::raw(conflict.subject),
+            Span::styled(
+                format!(" ({} conflicted {files})", conflict.conflicted_files),
+                t.attention,
+            ),
+        ]))?;
+    }
+    output.hint(Vec::from([Span::styled(
+        "Hint: run `but resolve <commit>` to resolve a conflicted commit",
+        t.hint,
+    )]))?;
+    output.connector(Vec::from([Span::raw("")]))?;
+    Ok(())
+}
+
+/// Print the total of the diff stats of all uncommitted files, if they are spread over more
+/// than one section, as the total of a single section is already shown in its header.
+fn print_diff_stat_total(
+    status_ctx: &StatusContext<'_>,
+    output: &mut StatusOutput<'_>,
+) -> anyhow::Result<()> {
+    let section_totals: Vec<_> = status_ctx
+        .stack_details
+        .iter()
+        .filter_map(|(_, (_, assignments))| diff_stat_total(status_ctx, assignments))
+        .collect();
+    if section_totals.len() < 2 {
+        return Ok(());
+    }
+    let mut total = DiffStatTotal::default();
+    for section_total in section_totals {
+        total.files += section_total.files;
+        total.lines += section_total.lines;
+    }
+    let mut line = Vec::from([
+        Span::raw("┊ "),
+        Span::styled("uncommitted in total ", crate::theme::get().hint),
+    ]);
+    line.extend(total.spans());
+    output.connector(line)?;
+    output.between_stacks(Vec::from([Span::raw("┊")]))?;
+    Ok(())
+}
+
 /// Print a note on how to deal with the uncommitted files marked `{conflicted}` in the
 /// listing above.
 fn print_conflicted_files_warning(
@@ -1058,6 +1448,7 @@ fn print_worktree_status(
         has_merged_upstream_branch |=
             print_group(ctx, status_ctx, stack_with_id, assignments, i == 0, output)?;
     }
+    print_diff_stat_total(status_ctx, output)?;
 
     Ok(has_merged_upstream_branch)
 }
@@ -1148,55 +1539,174 @@ fn print_assignments(
                     .into_iter()
                     .flatten(),
             )
+            .chain(diff_stat_total_spans(status_ctx, assignments))
             .collect(),
             staged_changes_cli_id,
         )?;
     }
 
-    let max_id_width = assignments
+    let uncommitted_paths: Vec<&BStr> = status_ctx
+        .id_map
+        .uncommitted_files
+        .values()
+        .map(|file| file.path())
+        .collect();
+    let mut entries = directories::collapse_untracked(
+        assignments,
+        |path| {
+            matches!(
+                status_from_changes(&status_ctx.worktree_changes, path.to_owned()),
+                Some(ui::TreeStatus::Addition {
+                    is_untracked: true,
+                    ..
+                })
+            )
+        },
+        &uncommitted_paths,
+        status_ctx.flags.collapse_untracked.min_files(),
+    );
+    let groups = if status_ctx.flags.group_by_dir {
+        directories::group_by_top_level_dir(&mut entries)
+    } else {
+        Vec::from([(None, entries.as_slice())])
+    };
+    // Files in a directory group are indented below its header.
+    let group_indent = |dir: &Option<BString>| if dir.is_some() { 2 } else { 0 };
+
+    let max_id_width = groups
         .iter()
-        .map(|fa| fa.assignments[0].cli_id.len())
+        .flat_map(|(_, entries)| entries.iter())
+        .filter_map(|entry| match entry {
+            FileEntry::File(fa) => Some(fa.assignments[0].cli_id.len()),
+            FileEntry::UntrackedDir { .. } => None,
+        })
         .max()
         .unwrap_or(0);
-
-    for fa in assignments {
-        let state = status_from_changes(&status_ctx.worktree_changes, fa.path.clone());
-        let path = match &state {
-            Some(state) => path_with_color_ui(state, fa.path.to_string()),
-            None => Span::raw(fa.path.to_string()),
+    let diff_stat = |entry: &FileEntry<'_>| {
+        status_ctx.diff_stats.as_ref().map(|stats| {
+            let mut stat = DiffStat::default();
+            for fa in entry.files() {
+                stat += stats.get(&fa.path).copied().unwrap_or_default();
+            }
+            stat
+        })
+    };
+    let max_stat_width = groups
+        .iter()
+        .flat_map(|(_, entries)| entries.iter())
+        .filter_map(diff_stat)
+        .map(|stat| stat.width())
+        .max()
+        .unwrap_or(0);
+    let max_path_width = groups
+        .iter()
+        .flat_map(|(dir, entries)| {
+            entries.iter().map(move |entry| {
+                group_indent(dir)
+                    + entry_path_spans(status_ctx, entry)
+                        .iter()
+                        .map(Span::width)
+                        .sum::<usize>()
+            })
+        })
+        .max()
+        .unwrap_or(0);
+    let max_hunks = repo
+        .config_snapshot()
+        .integer(MAX_HUNKS_PER_FILE_KEY)
+        .and_then(|max| usize::try_from(max).ok())
+        .unwrap_or(DEFAULT_MAX_HUNKS_PER_FILE);
+
+    for (dir, entries) in &groups {
+        let mut connector = if unstaged {
+            Vec::from([Span::raw("┊"), Span::raw(" "), Span::raw("  ")])
+        } else {
+            Vec::from([Span::raw("┊  │ ")])
         };
+        if let Some(name) = dir {
+            let files: usize = entries.iter().map(|entry| entry.files().len()).sum();
+            let header = Vec::from([
+                Span::styled(name.to_string(), t.info),
+                Span::styled(
+                    format!(" ({files} {})", if files == 1 { "file" } else { "files" }),
+                    t.hint,
+                ),
+            ]);
+            output.directory_group(connector.clone(), header)?;
+            connector.push(Span::raw(" ".repeat(group_indent(dir))));
+        }
 
-        let status = state.as_ref().map(status_letter_ui).unwrap_or_default();
-
-        let first_assignment = &fa.assignments[0];
-        let cli_id = &first_assignment.cli_id;
-        let id_padding = " ".repeat(max_id_width.saturating_sub(cli_id.len()) + 1);
+        for entry in entries.iter() {
+            let path = entry_path_spans(status_ctx, entry);
+            let stat = diff_stat(entry)
+                .map(|diff_stat| {
+                    let path_width: usize = path.iter().map(Span::width).sum();
+                    let padding = max_path_width - group_indent(dir) - path_width + 2;
+                    let mut stat = Vec::from([Span::raw(" ".repeat(padding))]);
+                    stat.extend(diff_stat.spans(max_stat_width));
+                    stat
+                })
+                .unwrap_or_default();
 
-        let file_cli_id = lookup_cli_id_for_short_id(
-            &status_ctx.id_map,
-            repo,
-            cli_id,
-            |id| matches!(id, CliId::UncommittedHunkOrFile(uncommitted) if uncommitted.is_entire_file),
-            "uncommitted file",
-        )?;
+            let (id, cli_id, status) = match entry {
+                FileEntry::File(fa) => {
+                    let cli_id = &fa.assignments[0].cli_id;
+                    let id_padding = " ".repeat(max_id_width.saturating_sub(cli_id.len()) + 1);
+                    let file_cli_id = lookup_cli_id_for_short_id(
+                        &status_ctx.id_map,
+                        repo,
+                        cli_id,
+                        |id| matches!(id, CliId::UncommittedHunkOrFile(uncommitted) if uncommitted.is_entire_file),
+                        "uncommitted file",
+                    )?;
+                    (
+                        Vec::from([
+                            Span::styled(cli_id.to_string(), t.cli_id),
+                            Span::raw(id_padding),
+                        ]),
+                        file_cli_id,
+                        status_from_changes(&status_ctx.worktree_changes, fa.path.clone()),
+                    )
+                }
+                // The directory itself is the ID, as shown in place of the path.
+                FileEntry::UntrackedDir { dir, files } => (
+                    Vec::from([Span::raw(" ".repeat(max_id_width + 1))]),
+                    lookup_cli_id_for_short_id(
+                        &status_ctx.id_map,
+                        repo,
+                        &dir.to_string(),
+                        |id| matches!(id, CliId::PathPrefix { .. }),
+                        "path prefix",
+                    )?,
+                    status_from_changes(&status_ctx.worktree_changes, files[0].path.clone()),
+                ),
+            };
+            let status = status.as_ref().map(status_letter_ui).unwrap_or_default();
 
-        let file_line = FileLineContent {
-            id: Vec::from([
-                Span::styled(cli_id.to_string(), t.cli_id),
-                Span::raw(id_padding),
-            ]),
-            status: Vec::from([Span::raw(status.to_string()), Span::raw(" ")]),
-            path: Vec::from([path]),
-        };
+            let file_line = FileLineContent {
+                id,
+                status: Vec::from([Span::raw(status.to_string()), Span::raw(" ")]),
+                path,
+                stat,
+            };
 
-        if unstaged {
-            output.uncommitted_file(
-                Vec::from([Span::raw("┊"), Span::raw(" "), Span::raw("  ")]),
-                file_line,
-                file_cli_id,
-            )?;
-        } else {
-            output.staged_file(Vec::from([Span::raw("┊  │ ")]), file_line, file_cli_id)?;
+            if unstaged {
+                output.uncommitted_file(connector.clone(), file_line, cli_id)?;
+            } else {
+                output.staged_file(connector.clone(), file_line, cli_id)?;
+            }
+            if (status_ctx.flags.verbose || status_ctx.flags.ids)
+                && let FileEntry::File(fa) = entry
+            {
+                print_uncommitted_hunks(
+                    status_ctx,
+                    fa.path.as_ref(),
+                    connector.clone(),
+                    max_id_width + 1,
+                    max_hunks,
+                    output,
+                )?;
+            }
         }
     }
 
@@ -1207,6 +1717,244 @@ fn print_assignments(
     Ok(())
 }
 
+/// The path of `entry` as shown in its line, which is the directory and its number of files for
+/// collapsed untracked directories.
+fn entry_path_spans(status_ctx: &StatusContext<'_>, entry: &FileEntry<'_>) -> Vec<Span<'static>> {
+    let t = crate::theme::get();
+    match entry {
+        FileEntry::File(fa) => file_path_spans(status_ctx, fa),
+        FileEntry::UntrackedDir { dir, files } => Vec::from([
+            Span::styled(dir.to_string(), t.cli_id),
+            Span::styled(format!(" ({} files)", files.len()), t.hint),
+        ]),
+    }
+}
+
+/// The path of the uncommitted file `fa` as shown in its line, like `old -> new` for renames, and
+/// the branch a rule assigned it to.
+fn file_path_spans(status_ctx: &StatusContext<'_>, fa: &FileAssignment) -> Vec<Span<'static>> {
+    let t = crate::theme::get();
+    let state = status_from_changes(&status_ctx.worktree_changes, fa.path.clone());
+    let mut path = Vec::new();
+    if let Some(ui::TreeStatus::Rename {
+        previous_path_bytes,
+        ..
+    }) = &state
+    {
+        path.push(Span::styled(
+            display_path(previous_path_bytes.as_bstr(), status_ctx.quote_path),
+            t.renaming,
+        ));
+        path.push(Span::raw(" -> "));
+    }
+    let displayed_path = display_path(fa.path.as_bstr(), status_ctx.quote_path);
+    let is_submodule = state.as_ref().is_some_and(is_submodule_change);
+    if is_submodule {
+        path.push(Span::styled("submodule: ", t.hint));
+    } else if let Some(label) = state.as_ref().and_then(mode_change_label) {
+        path.push(Span::styled(format!("{label}: "), t.hint));
+    }
+    path.push(match &state {
+        Some(state) => path_with_color_ui(state, displayed_path),
+        None => Span::raw(displayed_path),
+    });
+    if is_submodule {
+        let note = match status_ctx.submodule_states.get(&fa.path) {
+            Some(SubmoduleState::Dirty) => " (new commits, dirty)",
+            Some(SubmoduleState::Unknown) => " (new commits, state unknown)",
+            Some(SubmoduleState::Clean) | None => " (new commits)",
+        };
+        path.push(Span::styled(note, t.hint));
+    }
+    if let Some(branch) = status_ctx.auto_assigned.get(&fa.path) {
+        path.push(Span::styled(format!(" (auto: {branch})"), t.hint));
+    }
+    path
+}
+
+/// The total of the diff stats of the uncommitted files in `assignments`, if there are any
+/// and diff stats are shown.
+fn diff_stat_total(
+    status_ctx: &StatusContext<'_>,
+    assignments: &[FileAssignment],
+) -> Option<DiffStatTotal> {
+    let stats = status_ctx.diff_stats.as_ref()?;
+    if assignments.is_empty() {
+        return None;
+    }
+    let mut total = DiffStatTotal::default();
+    for fa in assignments {
+        total.add(stats.get(&fa.path).copied().unwrap_or_default());
+    }
+    Some(total)
+}
+
+/// The total of [`diff_stat_total()`] as suffix of a section header, or nothing.
+fn diff_stat_total_spans(
+    status_ctx: &StatusContext<'_>,
+    assignments: &[FileAssignment],
+) -> Vec<Span<'static>> {
+    diff_stat_total(status_ctx, assignments)
+        .map(|total| {
+            let mut spans = Vec::from([Span::raw(" ")]);
+            spans.extend(total.spans());
+            spans
+        })
+        .unwrap_or_default()
+}
+
+/// Print the hunks of the uncommitted file at `path` below it, indented by `indent` after
+/// `connector`, with at most `max_hunks` of them unless it's 0.
+fn print_uncommitted_hunks(
+    status_ctx: &StatusContext<'_>,
+    path: &BStr,
+    mut connector: Vec<Span<'static>>,
+    indent: usize,
+    max_hunks: usize,
+    output: &mut StatusOutput<'_>,
+) -> anyhow::Result<()> {
+    let t = crate::theme::get();
+    let Some(file) = status_ctx
+        .id_map
+        .uncommitted_files
+        .values()
+        .find(|file| file.path() == path)
+    else {
+        return Ok(());
+    };
+    connector.push(Span::raw(" ".repeat(indent)));
+
+    let hunks: Vec<_> = file.hunk_ids_and_assignments().collect();
+    let shown = if max_hunks == 0 {
+        hunks.len()
+    } else {
+        hunks.len().min(max_hunks)
+    };
+    let max_id_width = hunks[..shown]
+        .iter()
+        .map(|(hunk_id, _)| hunk_id.len())
+        .max()
+        .unwrap_or(0);
+    for (hunk_id, hunk) in &hunks[..shown] {
+        let Some(header) = hunk.hunk_header else {
+            // Without a textual diff, there is no hunk to select, only the file as a whole.
+            let description = status_ctx
+                .binary_changes
+                .get(path)
+                .map_or("(no detailed diff available)", String::as_str);
+            output.uncommitted_hunk(
+                connector.clone(),
+                Vec::from([Span::styled(description.to_owned(), t.hint)]),
+            )?;
+            continue;
+        };
+        let mut line = Vec::from([
+            Span::styled(hunk_id.clone(), t.cli_id),
+            Span::raw(" ".repeat(max_id_width - hunk_id.len() + 1)),
+            Span::styled(
+                format!(
+                    "@@ -{},{} +{},{} @@",
+                    header.old_start, header.old_lines, header.new_start, header.new_lines
+                ),
+                t.hint,
+            ),
+        ]);
+        if let Some(snippet) = hunk
+            .diff
+            .as_ref()
+            .and_then(|diff| first_changed_line(diff.as_ref()))
+        {
+            line.extend([Span::raw(" "), snippet]);
+        }
+        if let Some(locked_to) =
+            locked_to_label(&status_ctx.hunk_locks, hunk.path_bytes.as_bstr(), &header)
+        {
+            line.push(Span::styled(
+                format!(" (locked to {locked_to})"),
+                t.attention,
+            ));
+        }
+        output.uncommitted_hunk(connector.clone(), line)?;
+    }
+    if shown < hunks.len() {
+        output.uncommitted_hunk(
+            connector,
+            Vec::from([Span::styled(
+                format!("… {} more", hunks.len() - shown),
+                t.hint,
+            )]),
+        )?;
+    }
+    Ok(())
+}
+
+/// The locks of all uncommitted hunks in `dependencies`, with their commits resolved in `ws`.
+fn hunk_locks(
+    repo: &gix::Repository,
+    ws: &but_graph::Workspace,
+    dependencies: &HunkDependencies,
+) -> Vec<HunkLock> {
+    dependencies
+        .diffs
+        .iter()
+        .filter(|(_path, _hunk, locks)| !locks.is_empty())
+        .map(|(path, hunk, locks)| HunkLock {
+            path: path.as_str().into(),
+            hunk: HunkHeader::from(hunk),
+            commits: locks
+                .iter()
+                .map(|lock| {
+                    let stack_id = match lock.target {
+                        HunkLockTarget::Stack(stack_id) => Some(stack_id),
+                        HunkLockTarget::Unidentified => None,
+                    };
+                    (
+                        shorten_object_id(repo, lock.commit_id),
+                        rejection::branch_of_commit(ws, lock.commit_id, stack_id),
+                    )
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The commits, and their branches, that the uncommitted hunk `header` of the file at `path` is
+/// locked to according to `hunk_locks`, like `1a2b3c4 on A`, or `None` if it isn't locked.
+fn locked_to_label(hunk_locks: &[HunkLock], path: &BStr, header: &HunkHeader) -> Option<String> {
+    let mut commits = Vec::<String>::new();
+    for lock in hunk_locks
+        .iter()
+        .filter(|lock| lock.path == path && rejection::hunks_overlap(header, &lock.hunk))
+    {
+        for (commit, branch) in &lock.commits {
+            let label = match branch {
+                Some(branch) => format!("{commit} on {branch}"),
+                None => commit.clone(),
+            };
+            if !commits.contains(&label) {
+                commits.push(label);
+            }
+        }
+    }
+    (!commits.is_empty()).then(|| commits.join(", "))
+}
+
+/// The first added or removed line of the hunk `diff`, trimmed and shortened, to recognize the hunk by.
+fn first_changed_line(diff: &BStr) -> Option<Span<'static>> {
+    let t = crate::theme::get();
+    diff.lines().find_map(|line| {
+        let style = match line.first()? {
+            b'+' => t.addition,
+            b'-' => t.deletion,
+            _ => return None,
+        };
+        let text = line[1..].to_str_lossy();
+        let text = sanitize_for_terminal(text.trim());
+        (!text.is_empty())
+            .then(|| Span::styled(truncate_text(text, HUNK_SNIPPET_WIDTH).into_owned(), style))
+    })
+}
+
 fn print_group(
     ctx: &Context,
     status_ctx: &StatusContext<'_>,
@@ -1365,11 +2113,16 @@ fn print_group(
 
             first = false;
 
-            let has_remote_commits_to_print = segment.remote_commits.iter().any(|commit| {
-                status_ctx
-                    .remote_commits_by_id
-                    .contains_key(&commit.commit_id())
-            });
+            let incoming_commits = segment
+                .remote_commits
+                .iter()
+                .filter(|commit| {
+                    status_ctx
+                        .remote_commits_by_id
+                        .contains_key(&commit.commit_id())
+                })
+                .count();
+            let has_remote_commits_to_print = incoming_commits > 0;
 
             if has_remote_commits_to_print {
                 let tracking_branch = segment
@@ -1378,14 +2131,26 @@ fn print_group(
                     .as_ref()
                     .and_then(|rtb| rtb.as_bstr().strip_prefix(b"refs/remotes/"))
                     .unwrap_or(b"unknown");
+                let commits = if incoming_commits == 1 {
+                    "commit"
+                } else {
+                    "commits"
+                };
+                let mut line = Vec::from([Span::styled(
+                    format!(
+                        "↓ {incoming_commits} incoming {commits} on {}",
+                        BStr::new(tracking_branch)
+                    ),
+                    t.attention,
+                )]);
+                if let Some(branch) = segment.branch_name() {
+                    line.push(Span::styled(
+                        format!(" (run `but branch update {branch}`)"),
+                        t.hint,
+                    ));
+                }
                 output.connector(Vec::from([Span::raw("┊┊")]))?;
-                output.upstream_changes(
-                    Vec::from([Span::raw("┊╭┄┄ ")]),
-                    Vec::from([Span::styled(
-                        format!("(upstream: on {})", BStr::new(tracking_branch)),
-                        t.attention,
-                    )]),
-                )?;
+                output.upstream_changes(Vec::from([Span::raw("┊╭┄┄ ")]), line)?;
             }
             for commit in &segment.remote_commits {
                 let Some(inner) = status_ctx.remote_commits_by_id.get(&commit.commit_id()) else {
@@ -1452,7 +2217,7 @@ fn print_group(
             suffix: if assignments.is_empty() && status_ctx.conflicted_paths.is_empty() {
                 Vec::from([Span::raw(" "), Span::styled("(no changes)", t.hint)])
             } else {
-                Vec::new()
+                diff_stat_total_spans(status_ctx, assignments)
             },
         };
         output.unstaged_changes(Vec::from([Span::raw("╭┄ ")]), line, cli_id.clone())?;
@@ -1517,6 +2282,24 @@ pub fn status_letter_ui(status: &ui::TreeStatus) -> char {
     }
 }
 
+/// Describe how the type or executable bit of the file changed in `status`, or `None` if neither did.
+pub(crate) fn mode_change_label(status: &ui::TreeStatus) -> Option<&'static str> {
+    let (ui::TreeStatus::Modification {
+        flags: Some(flags), ..
+    }
+    | ui::TreeStatus::Rename {
+        flags: Some(flags), ..
+    }) = status
+    else {
+        return None;
+    };
+    Some(if but_core::ModeFlags::from(*flags).is_typechange() {
+        "typechange"
+    } else {
+        "mode change"
+    })
+}
+
 pub fn path_with_color_ui(status: &ui::TreeStatus, path: String) -> Span<'static> {
     let t = crate::theme::get();
     match status {
@@ -1698,7 +2481,7 @@ fn print_commit(
                     });
 
                     let display_id = displayed_file_id(padded_file_id_prefix.as_deref(), short_id);
-                    let (status, path) = tree_change_display_cli(inner);
+                    let (status, path) = tree_change_display_cli(inner, status_ctx.quote_path);
                     output.file(
                         Vec::from([Span::raw("┊"), Span::raw("│"), Span::raw("     ")]),
                         FileLineContent {
@@ -1710,6 +2493,7 @@ fn print_commit(
                             ]),
                             status: Vec::from([status]),
                             path: Vec::from([path]),
+                            stat: Vec::new(),
                         },
                         file_cli_id,
                     )?;
@@ -1717,13 +2501,14 @@ fn print_commit(
             }
             CommitChanges::Remote(tree_changes) => {
                 for change in tree_changes {
-                    let (status, path) = tree_change_display_cli(change);
+                    let (status, path) = tree_change_display_cli(change, status_ctx.quote_path);
                     output.file(
                         Vec::from([Span::raw("┊│     ")]),
                         FileLineContent {
                             id: Vec::new(),
                             status: Vec::from([status]),
                             path: Vec::from([path]),
+                            stat: Vec::new(),
                         },
                         commit_cli_id.clone(),
                     )?;
@@ -1751,23 +2536,20 @@ fn displayed_file_id(padded_prefix: Option<&str>, short_id: &str) -> String {
     }
 }
 
-fn tree_change_display_cli(change: &but_core::TreeChange) -> (Span<'static>, Span<'static>) {
-    let path = path_with_color(&change.status, change.path.to_string());
+/// The status letter and the path of `change`, with the path quoted like Git does, including
+/// bytes outside of ASCII if `quote_path` is set.
+fn tree_change_display_cli(
+    change: &but_core::TreeChange,
+    quote_path: bool,
+) -> (Span<'static>, Span<'static>) {
+    let path = path_with_color(
+        &change.status,
+        display_path(change.path.as_bstr(), quote_path),
+    );
     let status_letter = status_letter(&change.status);
     (Span::raw(format!("{status_letter} ")), path)
 }
 
-impl CliDisplay for but_core::TreeChange {
-    fn display_cli(
-        &self,
-        _verbose: bool,
-        _should_truncate_for_terminal: bool,
-    ) -> impl IntoIterator<Item = Span<'static>> {
-        let (status, path) = tree_change_display_cli(self);
-        [status, path]
-    }
-}
-
 fn display_cli_commit_details(
     repo: &gix::Repository,
     short_id: ShortId,
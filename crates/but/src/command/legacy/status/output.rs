@@ -129,6 +129,30 @@ impl StatusOutput<'_> {
         )
     }
 
+    pub(super) fn uncommitted_hunk(
+        &mut self,
+        connector: Vec<Span<'static>>,
+        line: Vec<Span<'static>>,
+    ) -> anyhow::Result<()> {
+        self.push_line(
+            Some(connector),
+            StatusOutputContent::Plain(line),
+            StatusOutputLineData::UncommittedHunk,
+        )
+    }
+
+    pub(super) fn directory_group(
+        &mut self,
+        connector: Vec<Span<'static>>,
+        line: Vec<Span<'static>>,
+    ) -> anyhow::Result<()> {
+        self.push_line(
+            Some(connector),
+            StatusOutputContent::Plain(line),
+            StatusOutputLineData::DirectoryGroup,
+        )
+    }
+
     pub(super) fn branch(
         &mut self,
         connector: Vec<Span<'static>>,
@@ -292,7 +316,7 @@ pub(super) struct BranchLineContent {
     pub(super) suffix: Vec<Span<'static>>,
 }
 
-/// Consdering the example "ae:sv A a/b/c.rs" see the field docs for what exactly they
+/// Consdering the example "ae:sv A a/b/c.rs  +12 -3" see the field docs for what exactly they
 /// correspond to.
 #[derive(Debug, Default, Clone)]
 pub(super) struct FileLineContent {
@@ -302,6 +326,8 @@ pub(super) struct FileLineContent {
     pub(super) status: Vec<Span<'static>>,
     /// "a/b/c.rs" in the example
     pub(super) path: Vec<Span<'static>>,
+    /// "  +12 -3" in the example, if the diff stat of the file is shown
+    pub(super) stat: Vec<Span<'static>>,
 }
 
 /// Considering the example "zz [uncommitted] (no changes)" see the field docs for what exactly
@@ -371,6 +397,8 @@ impl StatusOutputLine {
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
             | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::EmptyCommitMessage => false,
         }
@@ -413,6 +441,10 @@ pub(super) enum StatusOutputLineData {
     Warning,
     Hint,
     NoAssignmentsUnstaged,
+    /// A hunk of an uncommitted file, only shown in verbose mode.
+    UncommittedHunk,
+    /// The directory above the uncommitted files in it, only shown with `--group-by-dir`.
+    DirectoryGroup,
 }
 
 impl StatusOutputLineData {
@@ -434,7 +466,10 @@ impl StatusOutputLineData {
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => None,
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup
+            | StatusOutputLineData::DirectoryGroup => None,
         }
     }
 }
@@ -0,0 +1,220 @@
+//! The `but status --porcelain` output, a stable line-oriented format for scripts.
+//!
+//! Each uncommitted change is one line of `XY <target> <path>`, similar to `git status --porcelain`:
+//!
+//! * `XY` is a two-letter status code: `??` for untracked files, `A ` for added files,
+//!   ` M` for modified files, ` D` for deleted files, `R ` for renamed files and
+//!   `UU` for files with unresolved merge conflicts.
+//! * `<target>` is the branch the change is assigned to, or `-` if it's unassigned. It's padded
+//!   with spaces to the width of the longest target, so paths start in the same column.
+//!   A file with hunks assigned to several branches shows the branch of its first hunk.
+//! * `<path>` is the path of the file, `<old> -> <new>` for renames. Paths with
+//!   special or non-UTF-8 characters are quoted like Git does, honoring `core.quotePath`.
+//!
+//! With NUL termination, lines end with a NUL byte instead of a newline, paths are never quoted,
+//! and renames are written as `<new>` followed by a NUL byte and `<old>`.
+
+use std::collections::HashMap;
+
+use bstr::{BString, ByteSlice};
+use but_core::{IgnoredWorktreeTreeChangeStatus, ui};
+use but_ctx::Context;
+
+use crate::utils::{
+    OutputChannel,
+    quoted_path::{quote_path, quote_path_enabled},
+};
+
+/// The target column of changes that aren't assigned to a branch.
+const UNASSIGNED: &str = "-";
+
+/// A single line of the porcelain output.
+#[derive(Debug)]
+struct Entry {
+    code: &'static str,
+    /// The short name of the branch the change is assigned to.
+    target: Option<String>,
+    path: BString,
+    /// The path before a rename.
+    previous_path: Option<BString>,
+}
+
+/// Write the uncommitted changes of the worktree in porcelain format to `out`,
+/// terminating each entry with a NUL byte instead of a newline if `nul_terminated` is set.
+pub(crate) fn worktree(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    nul_terminated: bool,
+) -> anyhow::Result<()> {
+    let quote_non_ascii = quote_path_enabled(&*ctx.repo.get()?);
+    let (changes, assignments) = {
+        let guard = ctx.shared_worktree_access();
+        let changes =
+            but_api::diff::changes_in_worktree_with_perm(ctx, true, guard.read_permission())?;
+        (changes.worktree_changes, changes.assignments)
+    };
+    let mut targets = HashMap::new();
+    for assignment in &assignments {
+        targets
+            .entry(assignment.path_bytes.as_bstr())
+            .or_insert_with(|| {
+                assignment
+                    .branch_ref_bytes
+                    .as_ref()
+                    .map(|branch_ref| branch_ref.shorten().to_string())
+            });
+    }
+    let mut entries: Vec<_> = changes
+        .changes
+        .iter()
+        .map(|change| {
+            let target = targets.get(change.path_bytes.as_bstr()).cloned().flatten();
+            entry_from_change(change, target)
+        })
+        .chain(
+            changes
+                .ignored_changes
+                .iter()
+                .filter(|change| matches!(change.status, IgnoredWorktreeTreeChangeStatus::Conflict))
+                .map(|change| Entry {
+                    code: "UU",
+                    target: None,
+                    path: change.path.clone(),
+                    previous_path: None,
+                }),
+        )
+        .collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let target_width = entries
+        .iter()
+        .filter_map(|entry| entry.target.as_deref())
+        .map(|target| target.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(UNASSIGNED.len());
+    for entry in &entries {
+        out.write_bytes(&format_entry(
+            entry,
+            target_width,
+            nul_terminated,
+            quote_non_ascii,
+        ))?;
+    }
+    Ok(())
+}
+
+fn entry_from_change(change: &ui::TreeChange, target: Option<String>) -> Entry {
+    let (code, previous_path) = match &change.status {
+        ui::TreeStatus::Addition { is_untracked, .. } => {
+            (if *is_untracked { "??" } else { "A " }, None)
+        }
+        ui::TreeStatus::Deletion { .. } => (" D", None),
+        ui::TreeStatus::Modification { .. } => (" M", None),
+        ui::TreeStatus::Rename {
+            previous_path_bytes,
+            ..
+        } => ("R ", Some(previous_path_bytes.clone())),
+    };
+    Entry {
+        code,
+        target,
+        path: change.path_bytes.clone(),
+        previous_path,
+    }
+}
+
+/// Pad the target of `entry` to `target_width` characters, and quote paths in entries that aren't
+/// `nul_terminated` like Git does, including bytes outside of ASCII if `quote_non_ascii` is set.
+fn format_entry(
+    entry: &Entry,
+    target_width: usize,
+    nul_terminated: bool,
+    quote_non_ascii: bool,
+) -> BString {
+    let target = entry.target.as_deref().unwrap_or(UNASSIGNED);
+    let mut line = BString::from(format!("{} {target:<target_width$} ", entry.code));
+    if nul_terminated {
+        line.extend_from_slice(&entry.path);
+        line.push(b'\0');
+        if let Some(previous_path) = &entry.previous_path {
+            line.extend_from_slice(previous_path);
+            line.push(b'\0');
+        }
+    } else {
+        if let Some(previous_path) = &entry.previous_path {
+            line.extend_from_slice(&quote_path(previous_path.as_bstr(), quote_non_ascii));
+            line.extend_from_slice(b" -> ");
+        }
+        line.extend_from_slice(&quote_path(entry.path.as_bstr(), quote_non_ascii));
+        line.push(b'\n');
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::{BString, ByteSlice};
+
+    use super::{Entry, format_entry};
+
+    #[test]
+    fn renames_are_old_to_new_or_new_then_old_with_nul() {
+        let entry = Entry {
+            code: "R ",
+            target: None,
+            path: BString::from("new.txt"),
+            previous_path: Some(BString::from("old.txt")),
+        };
+        assert_eq!(
+            format_entry(&entry, 1, false, true),
+            "R  - old.txt -> new.txt\n"
+        );
+        assert_eq!(
+            format_entry(&entry, 1, true, true),
+            "R  - new.txt\0old.txt\0"
+        );
+    }
+
+    #[test]
+    fn non_ascii_paths_are_quoted_unless_disabled() {
+        let entry = Entry {
+            code: "??",
+            target: None,
+            path: BString::from(&b"caf\xe9.txt"[..]),
+            previous_path: None,
+        };
+        assert_eq!(
+            format_entry(&entry, 1, false, true),
+            "?? - \"caf\\351.txt\"\n"
+        );
+        assert_eq!(
+            format_entry(&entry, 1, false, false),
+            b"?? - caf\xe9.txt\n".as_bstr()
+        );
+    }
+
+    #[test]
+    fn targets_are_padded_to_the_same_width() {
+        let assigned = Entry {
+            code: " M",
+            target: Some("feature".into()),
+            path: BString::from("a.txt"),
+            previous_path: None,
+        };
+        let unassigned = Entry {
+            code: " M",
+            target: None,
+            path: BString::from("b.txt"),
+            previous_path: None,
+        };
+        assert_eq!(
+            format_entry(&assigned, 7, false, true),
+            " M feature a.txt\n"
+        );
+        assert_eq!(
+            format_entry(&unassigned, 7, false, true),
+            " M -       b.txt\n"
+        );
+    }
+}
@@ -60,10 +60,12 @@ pub(super) fn render_oneshot(
             mut id,
             mut status,
             mut path,
+            mut stat,
         }) => {
             spans.append(&mut id);
             spans.append(&mut status);
             spans.append(&mut path);
+            spans.append(&mut stat);
         }
         StatusOutputContent::Uncommitted(UncommittedLineContent {
             mut id,
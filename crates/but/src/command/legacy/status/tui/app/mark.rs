@@ -832,7 +832,9 @@ fn handle_mark_uncommitted(
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => None,
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => None,
         })
         .collect::<Vec<_>>();
 
@@ -918,7 +918,9 @@ impl App {
                                     | StatusOutputLineData::UpstreamChanges
                                     | StatusOutputLineData::Warning
                                     | StatusOutputLineData::Hint
-                                    | StatusOutputLineData::NoAssignmentsUnstaged => None,
+                                    | StatusOutputLineData::NoAssignmentsUnstaged
+                                    | StatusOutputLineData::UncommittedHunk
+                                    | StatusOutputLineData::DirectoryGroup => None,
                                 })
                                 .flat_map(|assignments| assignments.iter())
                                 .map(|assignment| assignment.path_bytes.as_ref());
@@ -1207,7 +1209,9 @@ impl App {
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => {}
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => {}
         }
 
         Ok(())
@@ -1246,7 +1250,9 @@ impl App {
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => return Ok(()),
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => return Ok(()),
         };
 
         messages.push(Message::Reload(
@@ -209,7 +209,9 @@ impl App {
                 | StatusOutputLineData::UpstreamChanges
                 | StatusOutputLineData::Warning
                 | StatusOutputLineData::Hint
-                | StatusOutputLineData::NoAssignmentsUnstaged => return,
+                | StatusOutputLineData::NoAssignmentsUnstaged
+                | StatusOutputLineData::UncommittedHunk
+                | StatusOutputLineData::DirectoryGroup => return,
             }
         };
 
@@ -289,7 +291,9 @@ impl App {
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => {
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => {
                 return Ok(());
             }
         };
@@ -182,7 +182,9 @@ fn line_uses_top_stack_for_stack_mode(line: &StatusOutputLine) -> bool {
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => false,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => false,
     }
 }
 
@@ -221,7 +223,9 @@ fn stack_id_for_line(
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => None,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => None,
     }
 }
 
@@ -266,7 +270,9 @@ fn stack_id_for_cli_id(cli_id: &CliId, status_lines: &[StatusOutputLine]) -> Opt
                 | StatusOutputLineData::UpstreamChanges
                 | StatusOutputLineData::Warning
                 | StatusOutputLineData::Hint
-                | StatusOutputLineData::NoAssignmentsUnstaged => None,
+                | StatusOutputLineData::NoAssignmentsUnstaged
+                | StatusOutputLineData::UncommittedHunk
+                | StatusOutputLineData::DirectoryGroup => None,
             })
         }
         CliId::Branch(branch) => branch.stack_id,
@@ -429,7 +429,9 @@ impl Cursor {
                 | StatusOutputLineData::UpstreamChanges
                 | StatusOutputLineData::Warning
                 | StatusOutputLineData::Hint
-                | StatusOutputLineData::NoAssignmentsUnstaged => None,
+                | StatusOutputLineData::NoAssignmentsUnstaged
+                | StatusOutputLineData::UncommittedHunk
+                | StatusOutputLineData::DirectoryGroup => None,
             })
     }
 
@@ -796,7 +798,9 @@ fn is_discard_commit_boundary(line: &StatusOutputLine) -> bool {
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => false,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => false,
     }
 }
 
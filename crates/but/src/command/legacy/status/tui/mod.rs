@@ -1,9 +1,6 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
-use std::{
-    sync::mpsc::{Receiver, Sender},
-    time::Duration,
-};
+use std::{sync::mpsc::Receiver, time::Duration};
 
 use anyhow::Context as _;
 use bstr::BString;
@@ -129,8 +126,10 @@ pub fn render_tui(
             mode,
         )?
     } else {
-        let _watcher_handle =
-            start_watcher(ctx, watcher_tx).context("failed to start filesystem watcher")?;
+        let _watcher_handle = start_watcher(ctx, move |change| {
+            _ = watcher_tx.send(Message::WatcherEvent(change));
+        })
+        .context("failed to start filesystem watcher")?;
 
         let mut terminal_guard = CrosstermTerminalGuard::alt_screen(true)?;
         let mut event_polling = CrosstermEventPolling::default();
@@ -492,9 +491,11 @@ fn mouse_is_over_details(app: &App, terminal_area: Rect, column: u16, row: u16)
         .is_some_and(|area| area.contains(Position { x: column, y: row }))
 }
 
-fn start_watcher(
+/// Watch the worktree and repository of `ctx` in the background, and call `on_change` with
+/// each change. Drop the returned handle to stop watching.
+pub(super) fn start_watcher(
     ctx: &mut Context,
-    tx: Sender<Message>,
+    on_change: impl Fn(gitbutler_watcher::Change) + Send + Sync + 'static,
 ) -> anyhow::Result<gitbutler_watcher::WatcherHandle> {
     let app_settings = app_settings_sync()?;
     let watch_mode = gitbutler_watcher::WatchMode::from_env_or_settings(
@@ -503,7 +504,7 @@ fn start_watcher(
     );
 
     let handler = gitbutler_watcher::Handler::new(move |change| {
-        _ = tx.send(Message::WatcherEvent(change));
+        on_change(change);
         Ok(())
     });
 
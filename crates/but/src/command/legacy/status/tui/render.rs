@@ -427,7 +427,9 @@ fn row_stack_ids(lines: &[StatusOutputLine]) -> Vec<Option<StackId>> {
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => {
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => {
                 current_stack_id = None;
                 None
             }
@@ -722,7 +724,12 @@ fn render_status_list_item(
                 line.extend(decoration_end);
                 line.extend(suffix);
             }
-            StatusOutputContent::File(FileLineContent { id, status, path }) => {
+            StatusOutputContent::File(FileLineContent {
+                id,
+                status,
+                path,
+                stat,
+            }) => {
                 if line_has_copied_highlight {
                     line.extend(id);
                 } else if let Mode::Jump(jump_mode) = &*app.mode {
@@ -740,6 +747,7 @@ fn render_status_list_item(
                 } else {
                     line.extend(path);
                 }
+                line.extend(stat);
             }
             StatusOutputContent::Uncommitted(UncommittedLineContent {
                 id,
@@ -1219,7 +1227,9 @@ pub fn commit_operation_display(
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => None,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => None,
     }
 }
 
@@ -1252,7 +1262,9 @@ pub fn move_operation_display(
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => None,
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => None,
         },
         MoveSource::Marks(marks) => match data {
             StatusOutputLineData::Commit { .. } => match insert_side {
@@ -1282,7 +1294,9 @@ pub fn move_operation_display(
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => None,
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => None,
         },
         MoveSource::Branch(..) => match data {
             StatusOutputLineData::Branch { .. } => Some("stack branch"),
@@ -1301,7 +1315,9 @@ pub fn move_operation_display(
             | StatusOutputLineData::UpstreamChanges
             | StatusOutputLineData::Warning
             | StatusOutputLineData::Hint
-            | StatusOutputLineData::NoAssignmentsUnstaged => None,
+            | StatusOutputLineData::NoAssignmentsUnstaged
+            | StatusOutputLineData::UncommittedHunk
+            | StatusOutputLineData::DirectoryGroup => None,
         },
     }
 }
@@ -1327,7 +1343,9 @@ pub fn reorder_operation_display(
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => None,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => None,
     }
 }
 
@@ -1362,7 +1380,9 @@ pub fn stack_operation_display(
         | StatusOutputLineData::UpstreamChanges
         | StatusOutputLineData::Warning
         | StatusOutputLineData::Hint
-        | StatusOutputLineData::NoAssignmentsUnstaged => None,
+        | StatusOutputLineData::NoAssignmentsUnstaged
+        | StatusOutputLineData::UncommittedHunk
+        | StatusOutputLineData::DirectoryGroup => None,
     }
 }
 
@@ -0,0 +1,241 @@
+//! `but status --watch`, which shows the status again whenever the worktree or workspace changes.
+
+use std::{
+    fmt::Write as _,
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    time::Duration,
+};
+
+use but_ctx::Context;
+use crossterm::{
+    Command as _,
+    cursor::MoveTo,
+    terminal::{Clear, ClearType},
+};
+use gitbutler_operating_modes::OperatingMode;
+
+use super::{
+    StackFilter, StatusFlags, StatusRenderMode, apply_stack_filter, build_status_context,
+    build_status_output, output::StatusOutput,
+};
+use crate::{
+    CliResult,
+    args::OutputFormat,
+    bad_input,
+    theme::Paint as _,
+    tui::text::truncate_text,
+    utils::{InputOutputChannel, OutputChannel, WriteWithUtils},
+};
+
+/// How long to wait for more changes after one was noticed, so a burst of writes leads to a
+/// single redraw.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Something that happened while waiting for the next redraw.
+enum WatchEvent {
+    /// The worktree, the index or the workspace changed.
+    Changed,
+    /// Ctrl-C was pressed.
+    Interrupted,
+}
+
+/// Show the status like `but status` does, and show it again in place each time the worktree,
+/// the index or the workspace changes, until Ctrl-C is pressed.
+///
+/// If filesystem notifications can't be set up, the status is recomputed every `poll_interval`
+/// instead. The screen is only repainted if the output actually changed.
+pub(crate) fn watch(
+    ctx: &mut Context,
+    out: &mut OutputChannel,
+    mut flags: StatusFlags,
+    filter: Option<&StackFilter>,
+    poll_interval: Duration,
+) -> CliResult<()> {
+    if !out.format().allows_human_ui() {
+        return Err(bad_input("`but status --watch` only works with human-readable output").into());
+    }
+    let mut progress = out.progress_channel();
+    let Some(mut inout) = out.prepare_for_terminal_input() else {
+        return Err(bad_input("`but status --watch` needs to run in a terminal").into());
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let interrupt_tx = tx.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            _ = interrupt_tx.send(WatchEvent::Interrupted);
+        }
+    });
+    let watcher = super::tui::start_watcher(ctx, move |_change| {
+        _ = tx.send(WatchEvent::Changed);
+    });
+    let poll_interval = match &watcher {
+        Ok(_) => None,
+        Err(err) => {
+            writeln!(
+                progress,
+                "Could not watch the worktree ({err:#}), checking for changes every {}s instead",
+                poll_interval.as_secs()
+            )?;
+            Some(poll_interval)
+        }
+    };
+
+    let mut previous = None;
+    loop {
+        let frame = match render_frame(ctx, &mut inout, flags, filter) {
+            Ok(frame) => frame,
+            // Errors that occur right away, like an unknown branch, end the watch as they won't go away.
+            Err(err) if previous.is_none() => return Err(err),
+            Err(err) => format!("{}\n", crate::theme::get().error.paint(format!("{err}"))),
+        };
+        if let Some(repaint) = repaint(previous.as_deref(), &frame) {
+            inout.write_str(&repaint)?;
+        }
+        previous = Some(frame);
+        // Pull requests are refreshed once, not on every change.
+        flags.refresh_prs = false;
+
+        match wait_for_change(&rx, poll_interval) {
+            WatchEvent::Changed => {}
+            WatchEvent::Interrupted => break,
+        }
+    }
+    drop(watcher);
+    Ok(())
+}
+
+/// Compute the status as it would be printed by `but status`, followed by a note on how to stop.
+fn render_frame(
+    ctx: &mut Context,
+    out: &mut InputOutputChannel<'_>,
+    flags: StatusFlags,
+    filter: Option<&StackFilter>,
+) -> CliResult<String> {
+    let t = crate::theme::get();
+    let mode = but_api::legacy::modes::operating_mode(ctx)?.operating_mode;
+    let mut frame = Frame::default();
+    if let OperatingMode::Edit(_) = &mode {
+        frame.text.push_str(&format!(
+            "{}\n",
+            t.attention
+                .paint("A commit is being edited, run `but status` to see what changed so far")
+        ));
+    } else {
+        let mut status_ctx = {
+            let mut guard = ctx.exclusive_worktree_access();
+            build_status_context(
+                ctx,
+                guard.write_permission(),
+                out,
+                OutputFormat::Human,
+                &mode,
+                flags,
+                StatusRenderMode::Oneshot,
+            )?
+        };
+        if let Some(filter) = filter {
+            let repo = ctx.repo.get()?;
+            apply_stack_filter(&mut status_ctx, &repo, filter)?;
+        }
+        status_ctx.id_map.remember_displayed_ids(ctx);
+        let mut output = StatusOutput::Immediate { out: &mut frame };
+        build_status_output(ctx, &status_ctx, &mut output)?;
+    }
+    frame.text.push_str(&format!(
+        "\n{}\n",
+        t.hint.paint("Watching for changes, press Ctrl-C to stop")
+    ));
+    Ok(frame.text)
+}
+
+/// Block until the next change, which is either reported by the watcher or, if `poll_interval`
+/// is set, assumed after each interval. Changes that follow each other quickly are merged.
+fn wait_for_change(rx: &Receiver<WatchEvent>, poll_interval: Option<Duration>) -> WatchEvent {
+    let first = match poll_interval {
+        Some(interval) => match rx.recv_timeout(interval) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => return WatchEvent::Changed,
+            Err(RecvTimeoutError::Disconnected) => return WatchEvent::Interrupted,
+        },
+        None => match rx.recv() {
+            Ok(event) => event,
+            Err(_) => return WatchEvent::Interrupted,
+        },
+    };
+    if let WatchEvent::Interrupted = first {
+        return first;
+    }
+    loop {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(WatchEvent::Changed) => {}
+            Ok(WatchEvent::Interrupted) => return WatchEvent::Interrupted,
+            Err(_) => return WatchEvent::Changed,
+        }
+    }
+}
+
+/// Return what to write to the terminal to replace the `previous` frame with `next`, or `None`
+/// if nothing changed.
+///
+/// Instead of clearing the screen first, each line is overwritten and only its remainder is
+/// cleared, which avoids flicker.
+fn repaint(previous: Option<&str>, next: &str) -> Option<String> {
+    if previous == Some(next) {
+        return None;
+    }
+    let mut out = String::new();
+    if previous.is_none() {
+        _ = Clear(ClearType::All).write_ansi(&mut out);
+    }
+    _ = MoveTo(0, 0).write_ansi(&mut out);
+    for line in next.lines() {
+        out.push_str(line);
+        _ = Clear(ClearType::UntilNewLine).write_ansi(&mut out);
+        out.push('\n');
+    }
+    _ = Clear(ClearType::FromCursorDown).write_ansi(&mut out);
+    Some(out)
+}
+
+/// The output of a single status, collected to be compared with the one shown before.
+#[derive(Default)]
+struct Frame {
+    text: String,
+}
+
+impl std::fmt::Write for Frame {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.text.push_str(s);
+        Ok(())
+    }
+}
+
+impl WriteWithUtils for Frame {
+    fn truncate_if_unpaged(&self, text: &str, max_width: usize) -> String {
+        truncate_text(text, max_width).into_owned()
+    }
+
+    fn is_paged(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::repaint;
+
+    #[test]
+    fn repaint_only_when_changed_and_without_clearing_the_screen_again() {
+        let first = repaint(None, "a\nb\n").expect("the first frame is always shown");
+        assert_eq!(first, "\u{1b}[2J\u{1b}[1;1Ha\u{1b}[K\nb\u{1b}[K\n\u{1b}[J");
+
+        assert_eq!(repaint(Some("a\nb\n"), "a\nb\n"), None);
+
+        let second = repaint(Some("a\nb\n"), "a\n").expect("changed frames are shown");
+        assert_eq!(
+            second, "\u{1b}[1;1Ha\u{1b}[K\n\u{1b}[J",
+            "lines are overwritten in place and what's left of the previous frame is cleared"
+        );
+    }
+}
@@ -12,9 +12,9 @@ use crate::{
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct TeardownResult {
-    snapshot_id: String,
-    checked_out_branch: String,
+pub(crate) struct TeardownResult {
+    pub(crate) snapshot_id: String,
+    pub(crate) checked_out_branch: String,
 }
 
 pub(crate) fn teardown(
@@ -23,6 +23,46 @@ pub(crate) fn teardown(
     out: &mut OutputChannel,
 ) -> CliResult<()> {
     let t = theme::get();
+    let result = leave_workspace(ctx, checkout_to, out)?;
+
+    // Final success message
+    if let Some(out) = out.for_human() {
+        writeln!(
+            out,
+            "{}",
+            t.success.paint("✓ Successfully exited GitButler mode!")
+        )?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{}",
+            t.hint.paint(format!(
+                "You are now on branch: {}",
+                result.checked_out_branch
+            ))
+        )?;
+        writeln!(out)?;
+        writeln!(out, "{}", t.info.paint("To return to GitButler mode, run:"))?;
+        writeln!(out, "  {}", t.command_suggestion.paint("but setup"))?;
+        writeln!(out)?;
+    }
+
+    // Output JSON if requested
+    if let Some(out) = out.for_json() {
+        out.write_value(&result)?;
+    }
+
+    Ok(())
+}
+
+/// Snapshot the workspace, uninstall the managed hooks and check out `checkout_to`, or the first
+/// branch of the first applied stack.
+pub(crate) fn leave_workspace(
+    ctx: &mut Context,
+    checkout_to: Option<String>,
+    out: &mut OutputChannel,
+) -> CliResult<TeardownResult> {
+    let t = theme::get();
 
     // Check that we're on gitbutler/workspace
     let head_name = {
@@ -226,35 +266,10 @@ pub(crate) fn teardown(
         writeln!(out)?;
     }
 
-    // Final success message
-    if let Some(out) = out.for_human() {
-        writeln!(
-            out,
-            "{}",
-            t.success.paint("✓ Successfully exited GitButler mode!")
-        )?;
-        writeln!(out)?;
-        writeln!(
-            out,
-            "{}",
-            t.hint
-                .paint(format!("You are now on branch: {target_branch_name}"))
-        )?;
-        writeln!(out)?;
-        writeln!(out, "{}", t.info.paint("To return to GitButler mode, run:"))?;
-        writeln!(out, "  {}", t.command_suggestion.paint("but setup"))?;
-        writeln!(out)?;
-    }
-
-    // Output JSON if requested
-    if let Some(out) = out.for_json() {
-        out.write_value(&TeardownResult {
-            snapshot_id,
-            checked_out_branch: target_branch_name,
-        })?;
-    }
-
-    Ok(())
+    Ok(TeardownResult {
+        snapshot_id,
+        checked_out_branch: target_branch_name,
+    })
 }
 
 // a call to get stacks failed, which could be because someone committed on top
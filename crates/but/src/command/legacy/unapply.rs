@@ -1,9 +1,16 @@
 //! Implementation of the `but unapply` command.
 
 use anyhow::{Context as _, bail};
+use bstr::ByteSlice;
+use but_api::{error::Error, legacy::virtual_branches::AssignedChanges};
 use but_core::ref_metadata::StackId;
 
-use crate::{CliId, IdMap, legacy::workspace::HeadInfoStack, utils::OutputChannel};
+use crate::{
+    CliId, IdMap,
+    legacy::workspace::HeadInfoStack,
+    theme::{self, Paint},
+    utils::OutputChannel,
+};
 
 /// Handle the unapply command.
 ///
@@ -13,10 +20,12 @@ use crate::{CliId, IdMap, legacy::workspace::HeadInfoStack, utils::OutputChannel
 /// - A branch name
 ///
 /// If a branch is specified, the entire stack containing that branch will be unapplied.
+/// `assigned_changes` decides what happens to the uncommitted changes assigned to the stack.
 pub fn handle(
     ctx: &mut but_ctx::Context,
     out: &mut OutputChannel,
     identifier: &str,
+    assigned_changes: AssignedChanges,
 ) -> anyhow::Result<()> {
     let mut guard = ctx.exclusive_worktree_access();
     // Fetch stacks once at the start
@@ -28,7 +37,7 @@ pub fn handle(
     // Try to find the stack to unapply
     let (stack_id, branches) = if parsed_ids.is_empty() {
         // No CLI ID match, try to find by branch name directly
-        find_stack_by_branch_name(&stacks, identifier)?
+        find_stack_by_branch_name(&*ctx.repo.get()?, &stacks, identifier)?
     } else if parsed_ids.len() == 1 {
         match &parsed_ids[0] {
             CliId::Stack { stack_id, .. } => {
@@ -65,7 +74,23 @@ pub fn handle(
         );
     };
 
-    unapply_stack(ctx, stack_id, &branches, out, guard.write_permission())
+    unapply_stack(
+        ctx,
+        stack_id,
+        &branches,
+        assigned_changes,
+        out,
+        guard.write_permission(),
+    )
+}
+
+/// Map the `--keep-changes` flag to what should happen to the changes assigned to the unapplied stack.
+pub fn assigned_changes(keep_changes: bool) -> AssignedChanges {
+    if keep_changes {
+        AssignedChanges::Keep
+    } else {
+        AssignedChanges::Stash
+    }
 }
 
 /// Get branches for a stack by ID, validating the stack exists.
@@ -89,7 +114,10 @@ fn get_stack_branches(
 }
 
 /// Find a stack by branch name and return the stack ID and branches.
+///
+/// Fail with [`Error::BranchNotFound`] if there is no local branch named `branch_name` in `repo`.
 fn find_stack_by_branch_name(
+    repo: &gix::Repository,
     stacks: &[HeadInfoStack],
     branch_name: &str,
 ) -> anyhow::Result<(StackId, Vec<String>)> {
@@ -102,6 +130,12 @@ fn find_stack_by_branch_name(
         }
     }
 
+    let is_local_branch = repo
+        .try_find_reference(format!("refs/heads/{branch_name}").as_str())
+        .is_ok_and(|reference| reference.is_some());
+    if !is_local_branch {
+        return Err(Error::BranchNotFound(branch_name.to_owned()).into_anyhow());
+    }
     bail!("Branch '{branch_name}' not found in any applied stack");
 }
 
@@ -109,18 +143,52 @@ fn unapply_stack(
     ctx: &mut but_ctx::Context,
     sid: StackId,
     branches: &[String],
+    assigned_changes: AssignedChanges,
     out: &mut OutputChannel,
     perm: &mut but_core::sync::RepoExclusive,
 ) -> anyhow::Result<()> {
+    let t = theme::get();
     let branches_display = branches.join(", ");
 
-    but_api::legacy::virtual_branches::unapply_stack_with_perm(ctx, sid, perm)?;
+    let outcome = but_api::legacy::virtual_branches::unapply_stack_with_assigned_changes_with_perm(
+        ctx,
+        sid,
+        assigned_changes,
+        perm,
+    )?;
+    let uncommitted_changes = if outcome.assigned_paths.is_empty() {
+        "none"
+    } else if outcome.stash_commit.is_some() {
+        "stashed"
+    } else {
+        "kept"
+    };
 
     if let Some(out) = out.for_human() {
         writeln!(
             out,
             "Unapplied stack with branches '{branches_display}' from workspace"
         )?;
+        if !outcome.assigned_paths.is_empty() {
+            let num_files = outcome.assigned_paths.len();
+            let files = if num_files == 1 { "file" } else { "files" };
+            match outcome.stash_commit {
+                Some(stash_commit) => writeln!(
+                    out,
+                    "Stashed uncommitted changes to {num_files} {files} in commit {} on '{}'",
+                    t.commit_id
+                        .paint(stash_commit.to_hex_with_len(7).to_string()),
+                    branches.first().map(String::as_str).unwrap_or_default()
+                )?,
+                None => writeln!(
+                    out,
+                    "Kept uncommitted changes to {num_files} {files} in the worktree, unassigned"
+                )?,
+            }
+            for path in &outcome.assigned_paths {
+                writeln!(out, "  {}", path.to_str_lossy())?;
+            }
+        }
     }
 
     if let Some(out) = out.for_shell() {
@@ -133,7 +201,14 @@ fn unapply_stack(
     if let Some(out) = out.for_json() {
         out.write_value(serde_json::json!({
             "unapplied": true,
-            "branches": branches
+            "branches": branches,
+            "uncommitted_changes": uncommitted_changes,
+            "uncommitted_paths": outcome
+                .assigned_paths
+                .iter()
+                .map(|path| path.to_str_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            "stash_commit_id": outcome.stash_commit.map(|id| id.to_string()),
         }))?;
     }
 
@@ -102,6 +102,21 @@ impl Display for BadInput {
     }
 }
 
+/// If `err` is a typed error of the API layer, turn it into a message with a hint for the user,
+/// along with the exit code that lets scripts tell the failures apart.
+pub(crate) fn api_error_report(err: &anyhow::Error) -> Option<(BadInput, i32)> {
+    use but_api::error::Error;
+    let api_err = err.downcast_ref::<Error>()?;
+    let (hint, exit_code) = match api_err {
+        Error::NoDefaultTarget => ("Run `but setup` to configure a target branch", 3),
+        Error::StackNotFound(_) => ("Run `but status` to see the stacks in the workspace", 4),
+        Error::BranchNotFound(_) => ("Run `but branch list` to see available branches", 4),
+        Error::CommitNotFound(_) => ("Run `but status` to see the commits in the workspace", 4),
+        Error::WorktreeConflict { .. } => ("Commit or stash the changes to these paths first", 5),
+    };
+    Some((bad_input(api_err.to_string()).hint(hint), exit_code))
+}
+
 #[derive(Debug)]
 pub enum CliError {
     /// User provided bad input.
@@ -164,6 +179,31 @@ impl CliError {
         }
     }
 
+    /// Write this error to stdout as `{"error": {"message": …, "kind": …}}`, the shape of all
+    /// errors in JSON mode.
+    pub(crate) fn write_json_to_stdout(&self) -> std::io::Result<()> {
+        let error = match self {
+            Self::BadInput(value) => JsonError::from_bad_input(value),
+            Self::ExternalCommandNotFound(command_name) => JsonError {
+                message: format!(
+                    "Unrecognized subcommand '{}'",
+                    command_name.to_string_lossy()
+                ),
+                kind: "unrecognized_subcommand",
+                hint: None,
+            },
+            Self::Internal(err) => match api_error_report(err) {
+                Some((report, _exit_code)) => JsonError::from_bad_input(&report),
+                None => JsonError {
+                    message: format!("{err:#}"),
+                    kind: "internal",
+                    hint: None,
+                },
+            },
+        };
+        crate::utils::json_pretty_to_stdout(&JsonErrorEnvelope { error })
+    }
+
     pub fn into_internal(self) -> anyhow::Error {
         match self {
             CliError::BadInput(..) | CliError::ExternalCommandNotFound(..) => {
@@ -190,6 +230,31 @@ impl Display for CliError {
     }
 }
 
+#[derive(Debug, serde::Serialize)]
+struct JsonErrorEnvelope {
+    error: JsonError,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonError {
+    /// The message without any styling.
+    message: String,
+    /// One of `bad_input`, `unrecognized_subcommand` or `internal`.
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl JsonError {
+    fn from_bad_input(value: &BadInput) -> Self {
+        JsonError {
+            message: value.message.clone(),
+            kind: "bad_input",
+            hint: value.hint.clone(),
+        }
+    }
+}
+
 pub type CliResult<T> = Result<T, CliError>;
 
 pub trait CliResultExt<T> {
@@ -0,0 +1,71 @@
+//! The uncommitted file and hunk IDs that were last shown to the user.
+//!
+//! File IDs are derived from the path and hunk IDs from the content, so they don't change when
+//! other changes come and go. But as the shortest unambiguous prefix is shown, an ID noted earlier
+//! may match a different change once the change it was shown for is gone. To prevent that, the IDs
+//! shown by `but status` and `but diff` are remembered along with what they were shown for, so
+//! such IDs can be refused as stale instead.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use but_ctx::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::id::ShortId;
+
+/// What an uncommitted file or hunk ID was shown for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DisplayedTarget {
+    /// The full ID derived from the path of the file.
+    pub file: String,
+    /// The full ID derived from the content of the hunk, or `None` if the ID was shown for the
+    /// whole file.
+    pub hunk: Option<String>,
+}
+
+/// The uncommitted file and hunk IDs that were last shown, with what they were shown for.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DisplayedIds {
+    targets: BTreeMap<ShortId, DisplayedTarget>,
+}
+
+impl DisplayedIds {
+    /// Create an instance from the `targets` of each shown ID.
+    pub fn new(targets: BTreeMap<ShortId, DisplayedTarget>) -> Self {
+        DisplayedIds { targets }
+    }
+
+    /// What `id` was shown for, if it was shown exactly like this.
+    pub fn get(&self, id: &str) -> Option<&DisplayedTarget> {
+        self.targets.get(id)
+    }
+
+    /// Read the IDs last shown in the project of `ctx`, or none if they can't be read.
+    pub fn load(ctx: &Context) -> Self {
+        let ids = std::fs::read(path(ctx))
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(serde_json::from_slice(&data)?));
+        match ids {
+            Ok(ids) => ids,
+            Err(err) => {
+                if !err
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+                {
+                    tracing::warn!(?err, "Failed to read the IDs shown last, ignoring them");
+                }
+                DisplayedIds::default()
+            }
+        }
+    }
+
+    /// Remember these IDs as the ones last shown in the project of `ctx`.
+    pub fn save(&self, ctx: &Context) -> anyhow::Result<()> {
+        std::fs::write(path(ctx), serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+}
+
+fn path(ctx: &Context) -> PathBuf {
+    ctx.project_data_dir.join("displayed-ids.json")
+}
@@ -22,12 +22,16 @@ use self_cell::self_cell;
 use uuid::Uuid;
 
 use crate::id::{
-    file_info::FileInfo, id_usage::UintId, stacks_info::StacksInfo,
+    displayed::{DisplayedIds, DisplayedTarget},
+    file_info::FileInfo,
+    id_usage::UintId,
+    stacks_info::StacksInfo,
     uncommitted_info::UncommittedInfo,
 };
 use crate::theme;
-use crate::utils::get_change_id_for_commit;
+use crate::utils::{get_change_id_for_commit, quoted_path::unquote_path};
 
+mod displayed;
 mod file_info;
 mod id_usage;
 pub mod parser;
@@ -55,6 +59,9 @@ pub(crate) const UNCOMMITTED: &str = "zz";
 
 const INDEX_SEPARATOR: char = '#';
 
+/// The shortest hex string that is looked up as a commit hash prefix in the object database.
+const MIN_COMMIT_HASH_PREFIX_LEN: usize = 4;
+
 /// The ID of a hunk, without its namespace (file).
 #[derive(Debug, Clone, Default)]
 struct UnqualifiedHunkId {
@@ -68,6 +75,14 @@ struct UnqualifiedHunkId {
 }
 
 impl UnqualifiedHunkId {
+    /// The whole ID, which only changes with the content of the hunk.
+    fn full_id(&self) -> String {
+        match &self.collision_index {
+            Some(collision_index) => format!("{}{INDEX_SEPARATOR}{collision_index}", self.id),
+            None => self.id.clone(),
+        }
+    }
+
     fn short_id(&self) -> String {
         let prefix = &self.id[..self.min_short_id_chars];
 
@@ -371,7 +386,7 @@ impl<'a> Node<'a> for &'a WorkspaceCommitWithId {
         )?)?;
         for (tree_changes, change_id, short_id) in rhs_indexes {
             let is_match = change_id.starts_with(element.as_bytes())
-                || tree_changes.first().path == BStr::new(element);
+                || tree_changes.first().path.as_bstr() == unquote_path(element).as_ref();
             if is_match {
                 matches.push(Box::new(Leaf {
                     cli_id: CliId::CommittedFile(CommittedFileId {
@@ -528,13 +543,14 @@ impl<'a> Node<'a> for &'a StackWithId {
         if element.ends_with('/') {
             return Ok(id_map.parse_uncommitted_path_prefix(element));
         }
+        let path = unquote_path(element);
         for uncommitted_file in id_map.uncommitted_files.values() {
             let hunk_assignments = uncommitted_file.hunk_assignments();
             let hunk_assignment = hunk_assignments.first();
             // TODO once the set of allowed CLI IDs is determined and the
             // access patterns of `uncommitted_files` are known, change its data
             // structure to be more efficient than the current linear search.
-            if hunk_assignment.path_bytes == element.as_bytes() {
+            if hunk_assignment.path_bytes.as_bstr() == path.as_ref() {
                 return Ok(vec![Box::new(uncommitted_file)]);
             }
         }
@@ -655,6 +671,13 @@ pub struct IdMap {
     pub uncommitted_files: BTreeMap<ChangeId, UncommittedFile>,
     /// Uncommitted hunks.
     pub uncommitted_hunks: HashMap<ShortId, UncommittedHunk>,
+    /// The uncommitted file and hunk IDs shown last, to refuse those that became stale.
+    displayed_ids: DisplayedIds,
+}
+
+/// Whether `element` is a glob pattern rather than a plain path or ID.
+pub(crate) fn is_path_glob(element: &str) -> bool {
+    element.contains(['*', '?', '['])
 }
 
 fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
@@ -777,9 +800,9 @@ impl IdMap {
                 )?;
             }
 
-            for (hunk_id, hunk_assignment) in &uncommitted_file.short_id_hunk_assignments {
+            for (hunk_id, hunk_assignment) in uncommitted_file.hunk_ids_and_assignments() {
                 uncommitted_hunks.insert(
-                    format!("{}:{}", uncommitted_file.short_id, hunk_id.short_id()),
+                    hunk_id,
                     UncommittedHunk {
                         hunk_assignment: hunk_assignment.clone(),
                     },
@@ -809,6 +832,7 @@ impl IdMap {
             },
             uncommitted_files,
             uncommitted_hunks,
+            displayed_ids: DisplayedIds::default(),
         })
     }
 
@@ -967,7 +991,61 @@ impl IdMap {
             })
             .collect();
 
-        Self::new(ws.stacks.clone(), hunk_assignments, commit_id_to_change_id)
+        let mut id_map = Self::new(ws.stacks.clone(), hunk_assignments, commit_id_to_change_id)?;
+        id_map.displayed_ids = DisplayedIds::load(ctx);
+        Ok(id_map)
+    }
+
+    /// Remember the uncommitted file and hunk IDs of this map as shown to the user, so that
+    /// [`IdMap`]s created later refuse them once they refer to changes that are gone.
+    ///
+    /// Failing to do so isn't fatal as it only makes these IDs less safe to use later.
+    pub fn remember_displayed_ids(&self, ctx: &Context) {
+        if let Err(err) = self.displayed_ids().save(ctx) {
+            tracing::warn!(?err, "Failed to remember the IDs shown");
+        }
+    }
+
+    /// The uncommitted file and hunk IDs of this map, with what they are for.
+    fn displayed_ids(&self) -> DisplayedIds {
+        let mut targets = BTreeMap::new();
+        for (reverse_hex, uncommitted_file) in &self.uncommitted_files {
+            let file = reverse_hex.to_string();
+            targets.insert(
+                uncommitted_file.short_id.clone(),
+                DisplayedTarget {
+                    file: file.clone(),
+                    hunk: None,
+                },
+            );
+            for (hunk_id, _) in &uncommitted_file.short_id_hunk_assignments {
+                targets.insert(
+                    format!("{}:{}", uncommitted_file.short_id, hunk_id.short_id()),
+                    DisplayedTarget {
+                        file: file.clone(),
+                        hunk: Some(hunk_id.full_id()),
+                    },
+                );
+            }
+        }
+        DisplayedIds::new(targets)
+    }
+
+    /// Whether the uncommitted file or hunk an ID was shown for still exists.
+    fn has_displayed_target(&self, target: &DisplayedTarget) -> bool {
+        let Some(uncommitted_file) = self
+            .uncommitted_files
+            .get(&ChangeId::from(BString::from(target.file.as_str())))
+        else {
+            return false;
+        };
+        match &target.hunk {
+            None => true,
+            Some(hunk) => uncommitted_file
+                .short_id_hunk_assignments
+                .iter()
+                .any(|(hunk_id, _)| hunk_id.full_id() == *hunk),
+        }
     }
 }
 
@@ -976,13 +1054,15 @@ impl IdMap {
 impl IdMap {
     fn parse_uncommitted_filename<'a>(&'a self, element: &str) -> Vec<Box<dyn Node<'a> + 'a>> {
         let mut matches = Vec::<Box<dyn Node<'a> + 'a>>::new();
+        // Paths are displayed quoted if they have special characters, and have to match that way.
+        let path = unquote_path(element);
         for uncommitted_file in self.uncommitted_files.values() {
             let hunk_assignments = uncommitted_file.hunk_assignments();
             let hunk_assignment = hunk_assignments.first();
             // TODO once the set of allowed CLI IDs is determined and the
             // access patterns of `uncommitted_files` are known, change its data
             // structure to be more efficient than the current linear search.
-            if hunk_assignment.path_bytes == element.as_bytes() {
+            if hunk_assignment.path_bytes.as_bstr() == path.as_ref() {
                 matches.push(Box::new(uncommitted_file));
             }
         }
@@ -990,10 +1070,38 @@ impl IdMap {
     }
 
     fn parse_uncommitted_path_prefix<'a>(&'a self, element: &str) -> Vec<Box<dyn Node<'a> + 'a>> {
+        self.uncommitted_paths_matching(element, |path| path.starts_with(element.as_bytes()))
+    }
+
+    /// All uncommitted hunks under the directory `element`, as if it was given with a trailing `/`.
+    fn parse_uncommitted_directory<'a>(&'a self, element: &str) -> Vec<Box<dyn Node<'a> + 'a>> {
+        let prefix = format!("{element}/");
+        self.uncommitted_paths_matching(element, |path| path.starts_with(prefix.as_bytes()))
+    }
+
+    /// All uncommitted hunks whose path matches the glob `element`, where `*` doesn't match `/`
+    /// but `**` does.
+    fn parse_uncommitted_glob<'a>(&'a self, element: &str) -> Vec<Box<dyn Node<'a> + 'a>> {
+        self.uncommitted_paths_matching(element, |path| {
+            gix::glob::wildmatch(
+                element.into(),
+                path.as_bstr(),
+                gix::glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            )
+        })
+    }
+
+    /// A [`CliId::PathPrefix`] identified by `element` with all uncommitted hunks whose path is
+    /// accepted by `matches`, or nothing if there are none.
+    fn uncommitted_paths_matching<'a>(
+        &'a self,
+        element: &str,
+        matches: impl Fn(&BString) -> bool,
+    ) -> Vec<Box<dyn Node<'a> + 'a>> {
         let mut hunk_assignments = Vec::<(String, WorktreeHunk)>::new();
         for (short_id, uncommitted_hunk) in self.uncommitted_hunks.iter() {
             let hunk_assignment = &uncommitted_hunk.hunk_assignment;
-            if hunk_assignment.path_bytes.starts_with(element.as_bytes()) {
+            if matches(&hunk_assignment.path_bytes) {
                 hunk_assignments.push((short_id.to_owned(), hunk_assignment.to_owned()));
             }
         }
@@ -1090,6 +1198,15 @@ impl IdMap {
             }
         }
         matches.extend(self.parse_uncommitted_filename(element));
+        // Paths given as glob or directory select all changes they contain, unless a changed file
+        // has this very name.
+        if matches.is_empty() {
+            if is_path_glob(element) {
+                matches.extend(self.parse_uncommitted_glob(element));
+            } else {
+                matches.extend(self.parse_uncommitted_directory(element));
+            }
+        }
 
         // The following match only if there have been no matches so far.
         if !matches.is_empty() {
@@ -1215,6 +1332,104 @@ impl IdMap {
     }
 }
 
+/// Methods for parsing ref names and revisions.
+impl IdMap {
+    /// Branches named by a ref name other than their short name: `refs/heads/<name>` is the
+    /// branch `<name>`, while `<remote>/<name>` and `refs/remotes/<remote>/<name>` are the
+    /// branches that have it as their remote-tracking branch.
+    pub(crate) fn parse_ref_name(&self, entity: &str) -> anyhow::Result<Vec<CliId>> {
+        let local_name = entity.strip_prefix("refs/heads/");
+        let mut cli_ids = Vec::new();
+        for segment in self.stacks().iter().flat_map(|stack| &stack.segments) {
+            let is_match = match local_name {
+                Some(local_name) => segment.branch_name().is_some_and(|name| name == local_name),
+                None => segment
+                    .inner
+                    .remote_tracking_ref_name
+                    .as_ref()
+                    .is_some_and(|ref_name| {
+                        ref_name.as_bstr() == entity || ref_name.shorten() == entity
+                    }),
+            };
+            if is_match {
+                cli_ids.extend(Box::new(segment).to_cli_id(entity, self)?);
+            }
+        }
+        Ok(cli_ids)
+    }
+
+    /// Commits named by a small subset of git's revision syntax, for those typing it out of habit:
+    ///
+    /// * `HEAD` or `@` is the tip of each applied stack, so it's ambiguous if there is more
+    ///   than one.
+    /// * `<branch>~N`, `<branch>~` and `<branch>^` are the N-th or first parent of the tip of
+    ///   `<branch>`, given by name or short ID. These suffixes may also follow `HEAD` or `@`,
+    ///   and can be chained like in `HEAD~2^`.
+    ///
+    /// Parents are only followed within the stack, so a revision below its base matches nothing.
+    fn parse_rev(&self, entity: &str) -> anyhow::Result<Vec<CliId>> {
+        let (base, suffix) = entity
+            .find(['~', '^'])
+            .map_or((entity, ""), |pos| entity.split_at(pos));
+        let is_head = base == "HEAD" || base == "@";
+        let Some(generations) = parse_rev_suffix(suffix) else {
+            return Ok(Vec::new());
+        };
+        if !is_head && suffix.is_empty() {
+            // Plain branch names are matched like any other ID.
+            return Ok(Vec::new());
+        }
+
+        let mut cli_ids = Vec::new();
+        for stack in self.stacks() {
+            let start = if is_head {
+                Some(0)
+            } else {
+                stack.segments.iter().position(|segment| {
+                    segment.short_id == base
+                        || segment.branch_name().is_some_and(|name| name == base)
+                })
+            };
+            let Some(commit) = start.and_then(|start| {
+                stack.segments[start..]
+                    .iter()
+                    .flat_map(|segment| &segment.workspace_commits)
+                    .nth(generations)
+            }) else {
+                continue;
+            };
+            cli_ids.extend(Box::new(commit).to_cli_id(&commit.short_id, self)?);
+        }
+        Ok(cli_ids)
+    }
+}
+
+/// The number of first-parent generations to go back for a revision `suffix` like `~2^`, or
+/// `None` if it isn't one of the supported forms `~N`, `~`, `^` or `^1`.
+fn parse_rev_suffix(mut suffix: &str) -> Option<usize> {
+    let mut generations = 0usize;
+    while let Some(op) = suffix.chars().next() {
+        let digits = &suffix[1..];
+        let digits_len = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let (digits, rest) = digits.split_at(digits_len);
+        suffix = rest;
+        let n: usize = if digits.is_empty() {
+            1
+        } else {
+            digits.parse().ok()?
+        };
+        generations = generations.checked_add(match op {
+            '~' => n,
+            // Only first parents are supported, with `^0` being the commit itself.
+            '^' if n <= 1 => n,
+            _ => return None,
+        })?;
+    }
+    Some(generations)
+}
+
 /// The `zz` uncommitted-area sentinel as a parse node: children are unstaged
 /// filenames, and by itself it resolves to [`CliId::Uncommitted`]. Shared by
 /// the full and the uncommitted-scoped element parsers so the sentinel cannot
@@ -1313,6 +1528,15 @@ impl IdMap {
             }
         }
 
+        // Ref names and revisions are only considered if nothing else matched, so an
+        // exactly matching branch like a local `origin/feature` always takes precedence.
+        if cli_ids.is_empty() && scope == SourceScope::Any && !entity.contains(':') {
+            cli_ids = self.parse_ref_name(entity)?;
+            if cli_ids.is_empty() {
+                cli_ids = self.parse_rev(entity)?;
+            }
+        }
+
         let mut deduped = Vec::new();
         'next: for cli_id in cli_ids {
             for existing in &deduped {
@@ -1323,20 +1547,103 @@ impl IdMap {
             deduped.push(cli_id);
         }
 
+        if !deduped.is_empty()
+            && let Some(target) = self.displayed_ids.get(entity)
+            && !self.has_displayed_target(target)
+        {
+            anyhow::bail!(
+                "The ID '{entity}' is stale, the change it was shown for is gone. Re-run `but status` to see the current IDs"
+            );
+        }
         Ok(deduped)
     }
     /// Convenience for [IdMap::parse] if a [gix::Repository] is available.
+    ///
+    /// If nothing in the workspace matches, `entity` is also tried as a commit hash or a prefix
+    /// of one, see [IdMap::parse_commit_hash].
     pub fn parse_using_repo<'a>(
         &'a self,
         entity: &str,
         repo: &'a gix::Repository,
     ) -> anyhow::Result<Vec<CliId>> {
-        self.parse(
+        let cli_ids = self.parse(
             entity,
             Box::new(move |commit_id, parent_id| {
                 but_core::diff::tree_changes(repo, parent_id, commit_id)
             }),
-        )
+        )?;
+        if !cli_ids.is_empty() {
+            return Ok(cli_ids);
+        }
+        self.parse_commit_hash(entity, repo)
+    }
+
+    /// Resolves `entity`, a hex string of 4 up to 40 characters in any case, to all commits in
+    /// the object database of `repo` whose hash starts with it. This finds commits in applied
+    /// stacks as well as those of unapplied branches or anywhere else in the history, like hashes
+    /// copied from `git log`.
+    ///
+    /// Commits that are part of the workspace are returned with the ID they have there.
+    pub fn parse_commit_hash(
+        &self,
+        entity: &str,
+        repo: &gix::Repository,
+    ) -> anyhow::Result<Vec<CliId>> {
+        let hex = entity.to_ascii_lowercase();
+        if !(MIN_COMMIT_HASH_PREFIX_LEN..=repo.object_hash().len_in_hex()).contains(&hex.len())
+            || !hex.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Ok(Vec::new());
+        }
+        let prefix = gix::hash::Prefix::from_hex(&hex)?;
+        let mut candidates = gix::hashtable::HashSet::default();
+        let mut object_ids: Vec<gix::ObjectId> =
+            match repo.objects.lookup_prefix(prefix, Some(&mut candidates))? {
+                None => return Ok(Vec::new()),
+                Some(Ok(object_id)) => Vec::from([object_id]),
+                Some(Err(())) => candidates.into_iter().collect(),
+            };
+        object_ids.sort();
+
+        let mut cli_ids = Vec::new();
+        for object_id in object_ids {
+            let Ok(commit) = repo.find_commit(object_id) else {
+                // Blobs, trees and tags sharing the prefix aren't commits.
+                continue;
+            };
+            let cli_id = match self.workspace_commit_cli_id(object_id)? {
+                Some(cli_id) => cli_id,
+                None => CliId::Commit(CommitId {
+                    commit_id: object_id,
+                    id: commit.id().shorten_or_id().to_string(),
+                    change_id: None,
+                }),
+            };
+            cli_ids.push(cli_id);
+        }
+        Ok(cli_ids)
+    }
+
+    /// The [`CliId::Commit`] of the workspace or remote commit `commit_id` in any of the stacks,
+    /// if it is one.
+    fn workspace_commit_cli_id(&self, commit_id: gix::ObjectId) -> anyhow::Result<Option<CliId>> {
+        for segment in self.stacks().iter().flat_map(|stack| &stack.segments) {
+            if let Some(commit) = segment
+                .workspace_commits
+                .iter()
+                .find(|commit| commit.commit_id() == commit_id)
+            {
+                return Box::new(commit).to_cli_id(&commit.short_id, self);
+            }
+            if let Some(commit) = segment
+                .remote_commits
+                .iter()
+                .find(|commit| commit.commit_id() == commit_id)
+            {
+                return Box::new(commit).to_cli_id(&commit.short_id, self);
+            }
+        }
+        Ok(None)
     }
 
     /// Convenience for [IdMap::parse] if a [Context] is available.
@@ -1387,6 +1694,29 @@ impl IdMap {
         self.indexed_stacks.borrow_owner()
     }
 
+    /// Up to `limit` paths of uncommitted files that are closest to `pattern`, for when it
+    /// matches none of them. Paths sharing the longest prefix with the literal start of the
+    /// pattern come first.
+    pub fn closest_uncommitted_paths(&self, pattern: &str, limit: usize) -> Vec<BString> {
+        let literal = pattern
+            .split(['*', '?', '['])
+            .next()
+            .unwrap_or_default()
+            .as_bytes();
+        let mut paths: Vec<_> = self
+            .uncommitted_files
+            .values()
+            .map(|file| file.path().to_owned())
+            .collect();
+        paths.sort_by(|a, b| {
+            common_prefix_len(b, literal)
+                .cmp(&common_prefix_len(a, literal))
+                .then_with(|| a.cmp(b))
+        });
+        paths.truncate(limit);
+        paths
+    }
+
     /// The change ID behind the primary identifier `but status` displays for
     /// `commit_id`, with its disambiguated short form. Returns `None` when the
     /// sha is the identifier instead, i.e. for commits without a change ID or
@@ -1617,6 +1947,18 @@ impl UncommittedFile {
             .as_ref()
             .map(|(_, hunk_assignment)| hunk_assignment)
     }
+
+    /// Hunk assignments along with the CLI ID of each hunk, i.e. `<file-id>:<hunk-id>`.
+    pub fn hunk_ids_and_assignments(&self) -> impl Iterator<Item = (ShortId, &WorktreeHunk)> {
+        self.short_id_hunk_assignments
+            .iter()
+            .map(|(hunk_id, hunk_assignment)| {
+                (
+                    format!("{}:{}", self.short_id, hunk_id.short_id()),
+                    hunk_assignment,
+                )
+            })
+    }
 }
 
 impl<'a> Node<'a> for &'a UncommittedFile {
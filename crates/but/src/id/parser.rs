@@ -4,7 +4,7 @@ use but_ctx::Context;
 
 use crate::{
     CliId, IdMap,
-    id::{CommitId, CommittedFileId, SourceScope},
+    id::{CommitId, CommittedFileId, SourceScope, is_path_glob},
     utils::OutputChannel,
 };
 
@@ -25,6 +25,24 @@ impl std::fmt::Display for IdResolutionError {
 
 impl std::error::Error for IdResolutionError {}
 
+/// The number of changed paths to suggest when a path pattern matches none of them.
+const CLOSEST_PATHS: usize = 3;
+
+/// An explanation for the path pattern `source` matching no changes, with the changed paths
+/// closest to it, or `None` if `source` doesn't look like a path pattern.
+pub(crate) fn unmatched_path_pattern(id_map: &IdMap, source: &str) -> Option<String> {
+    if !(source.contains('/') || is_path_glob(source)) || source.contains([':', '@']) {
+        return None;
+    }
+    let closest = id_map.closest_uncommitted_paths(source, CLOSEST_PATHS);
+    Some(if closest.is_empty() {
+        format!("'{source}' matches no changed paths, as there are no uncommitted changes.")
+    } else {
+        let closest: String = closest.iter().map(|path| format!("\n  {path}")).collect();
+        format!("'{source}' matches no changed paths. The closest are:{closest}")
+    })
+}
+
 fn parse_scoped(
     ctx: &mut Context,
     id_map: &IdMap,
@@ -32,11 +50,47 @@ fn parse_scoped(
     scope: SourceScope,
 ) -> anyhow::Result<Vec<CliId>> {
     match scope {
-        SourceScope::Any => id_map.parse_using_context(part, ctx),
+        SourceScope::Any => {
+            without_hunks_lacking_diff(part, id_map.parse_using_context(part, ctx)?)
+        }
         SourceScope::UncommittedOnly => resolve_uncommitted_part(ctx, id_map, part),
     }
 }
 
+/// Remove the hunks from `ids` that only stand in for a whole file without a textual diff, like
+/// binary files, as these can't be selected hunk by hunk.
+/// Fail with a hint to use the file ID instead if `part` resolved only to such hunks.
+fn without_hunks_lacking_diff(part: &str, ids: Vec<CliId>) -> anyhow::Result<Vec<CliId>> {
+    let lacks_diff = |id: &CliId| match id {
+        CliId::UncommittedHunkOrFile(hunk) => {
+            !hunk.is_entire_file
+                && hunk
+                    .hunk_assignments
+                    .iter()
+                    .all(|assignment| assignment.hunk_header.is_none())
+        }
+        _ => false,
+    };
+    let Some(CliId::UncommittedHunkOrFile(first_without_diff)) =
+        ids.iter().find(|id| lacks_diff(id)).cloned()
+    else {
+        return Ok(ids);
+    };
+    let with_diff: Vec<CliId> = ids.into_iter().filter(|id| !lacks_diff(id)).collect();
+    if !with_diff.is_empty() {
+        return Ok(with_diff);
+    }
+    let file_id = first_without_diff
+        .id
+        .split_once(':')
+        .map_or(first_without_diff.id.as_str(), |(file_id, _hunk)| file_id);
+    Err(IdResolutionError::new(format!(
+        "'{part}' is no hunk as '{}' has no textual diff, like binary files. Use its file ID '{file_id}' to select all of it",
+        first_without_diff.hunk_assignments.first().path
+    ))
+    .into())
+}
+
 /// Resolve one selector that must name uncommitted changes: the uncommitted
 /// namespace first, then a full-namespace fallback that keeps any uncommitted
 /// interpretations (container selectors the scoped parser does not model) and
@@ -49,11 +103,12 @@ pub(crate) fn resolve_uncommitted_part(
     id_map: &IdMap,
     part: &str,
 ) -> anyhow::Result<Vec<CliId>> {
-    let scoped = id_map.parse_uncommitted_using_context(part, ctx)?;
+    let scoped =
+        without_hunks_lacking_diff(part, id_map.parse_uncommitted_using_context(part, ctx)?)?;
     if !scoped.is_empty() {
         return Ok(scoped);
     }
-    let full = id_map.parse_using_context(part, ctx)?;
+    let full = without_hunks_lacking_diff(part, id_map.parse_using_context(part, ctx)?)?;
     let uncommitted: Vec<CliId> = full
         .iter()
         .filter(|id| matches!(id, CliId::UncommittedHunkOrFile(_)))
@@ -115,9 +170,11 @@ fn parse_sources_scoped(
     let source_result = parse_scoped(ctx, id_map, source, scope)?;
     if source_result.len() != 1 {
         if source_result.is_empty() {
-            return Err(IdResolutionError::new(format!(
-                "Source '{source}' not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
-            ))
+            return Err(IdResolutionError::new(
+                unmatched_path_pattern(id_map, source).unwrap_or_else(|| format!(
+                    "Source '{source}' not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
+                )),
+            )
             .into());
         } else {
             let matches: Vec<String> = source_result
@@ -250,15 +307,18 @@ fn parse_sources_with_disambiguation_scoped(
     // Single source (including strings with dashes that aren't valid ranges)
     let source_result = parse_scoped(ctx, id_map, source, scope)?;
     if source_result.is_empty() {
-        return Err(IdResolutionError::new(format!(
-            "Source '{source}' not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
-        ))
+        return Err(IdResolutionError::new(
+            unmatched_path_pattern(id_map, source).unwrap_or_else(|| format!(
+                "Source '{source}' not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
+            )),
+        )
         .into());
     }
 
     if source_result.len() > 1 {
         // Ambiguous - prompt the user to disambiguate
-        let selected = prompt_for_disambiguation(source, source_result, "the source", out)?;
+        let repo = ctx.repo.get()?;
+        let selected = prompt_for_disambiguation(source, source_result, "the source", &repo, out)?;
         return Ok(vec![selected]);
     }
 
@@ -286,9 +346,11 @@ fn parse_list_with_disambiguation(
 
         let matches = parse_scoped(ctx, id_map, part, scope)?;
         if matches.is_empty() {
-            return Err(IdResolutionError::new(format!(
-                "Item '{part}' in list not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
-            ))
+            return Err(IdResolutionError::new(
+                unmatched_path_pattern(id_map, part).unwrap_or_else(|| format!(
+                    "Item '{part}' in list not found. If you just performed a Git operation (squash, rebase, etc.), try running 'but status' to refresh the current state."
+                )),
+            )
             .into());
         }
 
@@ -296,8 +358,14 @@ fn parse_list_with_disambiguation(
             result.push(matches[0].clone());
         } else {
             // Ambiguous - prompt the user to disambiguate
-            let selected =
-                prompt_for_disambiguation(part, matches, &format!("item '{part}' in list"), out)?;
+            let repo = ctx.repo.get()?;
+            let selected = prompt_for_disambiguation(
+                part,
+                matches,
+                &format!("item '{part}' in list"),
+                &repo,
+                out,
+            )?;
             result.push(selected);
         }
     }
@@ -364,17 +432,20 @@ fn parse_list(
 /// * `entity_str` - The original string the user typed
 /// * `matches` - The possible matches (must not be empty)
 /// * `context` - Description of what we're resolving (e.g., "source", "target")
+/// * `repo` - The repository to look up the subjects of matching commits in
 /// * `out` - Output channel to check if environment is interactive
 ///
 /// # Returns
 /// The selected CliId from the user's choice
 ///
 /// # Errors
-/// Returns an error if the environment is non-interactive or if the user cancels the selection
+/// Returns an error listing all matches if the environment is non-interactive, or an error if
+/// the user cancels the selection
 pub fn prompt_for_disambiguation(
     entity_str: &str,
     matches: Vec<CliId>,
     context: &str,
+    repo: &gix::Repository,
     out: &mut OutputChannel,
 ) -> anyhow::Result<CliId> {
     // Defensive check
@@ -386,69 +457,16 @@ pub fn prompt_for_disambiguation(
 
     let Some(mut input) = out.prepare_for_terminal_input() else {
         // In non-interactive mode, show all options and error
-        let options_str = matches
-            .iter()
-            .enumerate()
-            .map(|(i, id)| {
-                format!(
-                    "  {}. {} ({})",
-                    i + 1,
-                    id.to_short_string(),
-                    id.kind_for_humans()
-                )
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-
         return Err(IdResolutionError::new(format!(
-            "'{entity_str}' is ambiguous for {context}. Cannot prompt in non-interactive mode. Matches:\n{options_str}"
+            "'{entity_str}' is ambiguous for {context}. Cannot prompt in non-interactive mode. Matches:\n{}",
+            list_matches(&matches, repo)
         ))
         .into());
     };
 
-    // Build options with clear descriptions
     let options = matches
         .into_iter()
-        .map(|id| {
-            let short_id = id.to_short_string();
-            let kind = id.kind_for_humans();
-
-            // Add additional context based on the type
-            let label = match &id {
-                CliId::Commit(CommitId { commit_id, .. }) => {
-                    format!(
-                        "{} - {} (commit {})",
-                        short_id,
-                        kind,
-                        &commit_id.to_string()[..7]
-                    )
-                }
-                CliId::Branch(branch) => {
-                    format!("{short_id} - {kind} (branch '{}')", branch.name)
-                }
-                CliId::CommittedFile(CommittedFileId {
-                    path, commit_id, ..
-                }) => {
-                    format!(
-                        "{} - {} (file '{}' in commit {})",
-                        short_id,
-                        kind,
-                        path,
-                        &commit_id.to_string()[..7]
-                    )
-                }
-                CliId::UncommittedHunkOrFile(uncommitted) => {
-                    if uncommitted.is_entire_file {
-                        let first_hunk = uncommitted.hunk_assignments.first();
-                        format!("{} - {} (file '{}')", short_id, kind, first_hunk.path)
-                    } else {
-                        format!("{short_id} - {kind} (hunk)")
-                    }
-                }
-                _ => format!("{short_id} - {kind}"),
-            };
-            (label, id)
-        })
+        .map(|id| (describe_match(&id, repo), id))
         .collect::<Vec<_>>();
     let options = nonempty::NonEmpty::from_vec(options)
         .context("Internal error: prompt_for_disambiguation called with empty matches")?;
@@ -463,3 +481,76 @@ pub fn prompt_for_disambiguation(
         .cloned()
         .ok_or_else(|| anyhow::anyhow!("Selection aborted"))
 }
+
+/// List `matches` of an ambiguous selector as numbered lines, described with [`describe_match()`],
+/// so a longer or different selector can be picked to tell them apart.
+pub(crate) fn list_matches(matches: &[CliId], repo: &gix::Repository) -> String {
+    matches
+        .iter()
+        .enumerate()
+        .map(|(i, id)| format!("  {}. {}", i + 1, describe_match(id, repo)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Describe `id` as one of several matches of a selector: its short ID and kind, along with its
+/// full commit hash or name, and the subject of commits.
+pub(crate) fn describe_match(id: &CliId, repo: &gix::Repository) -> String {
+    let short_id = id.to_short_string();
+    let kind = id.kind_for_humans();
+    match id {
+        CliId::Commit(CommitId { commit_id, .. }) => {
+            format!(
+                "{short_id} - {kind} {commit_id} ({})",
+                commit_subject(repo, *commit_id)
+            )
+        }
+        CliId::Branch(branch) => {
+            format!("{short_id} - {kind} (branch '{}')", branch.name)
+        }
+        CliId::CommittedFile(CommittedFileId {
+            path, commit_id, ..
+        }) => {
+            format!(
+                "{short_id} - {kind} (file '{path}' in commit {} {})",
+                &commit_id.to_string()[..7],
+                commit_subject(repo, *commit_id)
+            )
+        }
+        CliId::UncommittedHunkOrFile(uncommitted) => {
+            let first_hunk = uncommitted.hunk_assignments.first();
+            match first_hunk.hunk_header {
+                Some(header) if !uncommitted.is_entire_file => format!(
+                    "{short_id} - {kind} (hunk @@ -{},{} +{},{} @@ in '{}')",
+                    header.old_start,
+                    header.old_lines,
+                    header.new_start,
+                    header.new_lines,
+                    first_hunk.path
+                ),
+                _ if !uncommitted.is_entire_file => {
+                    format!("{short_id} - {kind} (hunk in '{}')", first_hunk.path)
+                }
+                _ => format!("{short_id} - {kind} (file '{}')", first_hunk.path),
+            }
+        }
+        CliId::PathPrefix {
+            hunk_assignments, ..
+        } => format!("{short_id} - {kind} ({} hunks)", hunk_assignments.len()),
+        CliId::Uncommitted { .. } | CliId::Stack { .. } => format!("{short_id} - {kind}"),
+    }
+}
+
+/// The subject of the commit `id`, or `(no message)` if it has none or can't be read.
+fn commit_subject(repo: &gix::Repository, id: gix::ObjectId) -> String {
+    repo.find_commit(id)
+        .ok()
+        .and_then(|commit| {
+            commit
+                .message()
+                .ok()
+                .map(|message| message.summary().to_string())
+        })
+        .filter(|subject| !subject.is_empty())
+        .unwrap_or_else(|| "(no message)".to_string())
+}
@@ -1,7 +1,7 @@
 use anyhow::bail;
 use bstr::BString;
 use but_core::{ChangeId, ref_metadata::StackId};
-use but_graph::workspace::Stack;
+use but_graph::workspace::{Stack, StackSegment};
 use but_hunk_assignment::HunkAssignment;
 use but_testsupport::{hex_to_id, hunk_header};
 use snapbox::{assert_data_eq, prelude::*};
@@ -1237,6 +1237,62 @@ fn uncommitted_path() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn uncommitted_directory_and_glob() -> anyhow::Result<()> {
+    let stacks = vec![stack([segment("foo", [id(1)], None, [])])];
+    let hunk_assignments = vec![
+        hunk_assignment("prefixx", None),
+        hunk_assignment("prefix/a", None),
+        hunk_assignment("prefix/b.rs", None),
+        hunk_assignment("prefix/deep/c.rs", None),
+    ];
+    let id_map = IdMap::new(stacks, hunk_assignments, gix::hashtable::HashMap::default())?;
+    let paths_of = |entity: &str| -> anyhow::Result<Vec<String>> {
+        let changed_paths_fn = |commit_id: gix::ObjectId,
+                                parent_id: Option<gix::ObjectId>|
+         -> anyhow::Result<Vec<but_core::TreeChange>> {
+            bail!("unexpected IDs {commit_id} {parent_id:?}");
+        };
+        Ok(id_map
+            .parse(entity, Box::new(changed_paths_fn))?
+            .into_iter()
+            .flat_map(|id| match id {
+                CliId::PathPrefix {
+                    hunk_assignments, ..
+                } => hunk_assignments
+                    .into_iter()
+                    .map(|(_, hunk)| hunk.path_bytes.to_string())
+                    .collect(),
+                other => vec![format!("{other:?}")],
+            })
+            .collect())
+    };
+
+    assert_eq!(
+        paths_of("prefix")?,
+        ["prefix/a", "prefix/b.rs", "prefix/deep/c.rs"],
+        "a bare directory selects everything under it, but not its siblings"
+    );
+    assert_eq!(
+        paths_of("prefix/*.rs")?,
+        ["prefix/b.rs"],
+        "`*` doesn't cross directories"
+    );
+    assert_eq!(
+        paths_of("prefix/**/*.rs")?,
+        ["prefix/b.rs", "prefix/deep/c.rs"]
+    );
+    assert_eq!(paths_of("prefix*")?, ["prefixx"]);
+    assert!(paths_of("other/**")?.is_empty());
+
+    assert_eq!(
+        id_map.closest_uncommitted_paths("prefix/deep/*.txt", 2),
+        ["prefix/deep/c.rs", "prefix/a"],
+        "the paths sharing the longest prefix come first"
+    );
+    Ok(())
+}
+
 #[test]
 fn committed_files_are_deduplicated_by_commit_oid_path() -> anyhow::Result<()> {
     let stacks = vec![stack([segment("branch", [id(2)], Some(id(1)), [])])];
@@ -2743,6 +2799,228 @@ branches: [ no ]
     )
 }
 
+#[test]
+fn revisions_relative_to_head_and_branches() -> anyhow::Result<()> {
+    let stacks = vec![
+        stack([
+            segment("feature-x", [id(1), id(2)], None, []),
+            segment("base-x", [id(3)], None, []),
+        ]),
+        stack([segment("other", [id(4), id(5)], None, [])]),
+    ];
+    let id_map = IdMap::new(stacks, Vec::new(), gix::hashtable::HashMap::default())?;
+    let commits = |entity: &str| -> anyhow::Result<Vec<gix::ObjectId>> {
+        let changed_paths_fn = |commit_id: gix::ObjectId,
+                                parent_id: Option<gix::ObjectId>|
+         -> anyhow::Result<Vec<but_core::TreeChange>> {
+            bail!("unexpected IDs {commit_id} {parent_id:?}");
+        };
+        Ok(id_map
+            .parse(entity, Box::new(changed_paths_fn))?
+            .iter()
+            .map(|cli_id| match cli_id {
+                CliId::Commit(CommitId { commit_id, .. }) => *commit_id,
+                other => panic!("expected a commit, got {other:?}"),
+            })
+            .collect())
+    };
+
+    assert_eq!(commits("feature-x~1")?, [id(2)]);
+    assert_eq!(commits("feature-x~")?, [id(2)]);
+    assert_eq!(commits("feature-x^")?, [id(2)]);
+    assert_eq!(commits("feature-x^0")?, [id(1)]);
+    assert_eq!(
+        commits("feature-x~2")?,
+        [id(3)],
+        "parents are followed into the branch below"
+    );
+    assert_eq!(commits("base-x~0")?, [id(3)]);
+    assert_eq!(commits("other^^")?, [id(5)], "suffixes can be chained");
+    assert!(
+        commits("feature-x~3")?.is_empty(),
+        "revisions below the base of the stack aren't resolved"
+    );
+    assert!(
+        commits("feature-x^2")?.is_empty(),
+        "only first parents are supported"
+    );
+    assert!(commits("unknown~1")?.is_empty());
+
+    assert_eq!(
+        commits("HEAD")?,
+        [id(1), id(4)],
+        "with two stacks, HEAD is ambiguous and yields the tip of each"
+    );
+    assert_eq!(commits("@~1")?, [id(2), id(5)]);
+    assert_eq!(commits("HEAD~2")?, [id(3)], "only one stack is deep enough");
+    Ok(())
+}
+
+#[test]
+fn head_is_the_tip_of_the_only_stack() -> anyhow::Result<()> {
+    let stacks = vec![stack([segment("feature-x", [id(1), id(2)], None, [])])];
+    let id_map = IdMap::new(stacks, Vec::new(), gix::hashtable::HashMap::default())?;
+    let changed_paths_fn = |commit_id: gix::ObjectId,
+                            parent_id: Option<gix::ObjectId>|
+     -> anyhow::Result<Vec<but_core::TreeChange>> {
+        bail!("unexpected IDs {commit_id} {parent_id:?}");
+    };
+
+    for (entity, expected) in [
+        ("HEAD", id(1)),
+        ("@", id(1)),
+        ("HEAD^", id(2)),
+        ("@~1", id(2)),
+    ] {
+        assert!(
+            matches!(
+                id_map.parse(entity, Box::new(changed_paths_fn))?.as_slice(),
+                [CliId::Commit(CommitId { commit_id, .. })] if *commit_id == expected
+            ),
+            "{entity} resolves to {expected}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn branches_by_full_and_remote_tracking_ref_name() -> anyhow::Result<()> {
+    let tracking = |name: &str| {
+        Some(gix::refs::FullName::try_from(format!("refs/remotes/{name}")).expect("valid ref name"))
+    };
+    let stacks = vec![
+        stack([StackSegment {
+            remote_tracking_ref_name: tracking("origin/feature-x"),
+            ..segment("feature-x", [id(1)], None, [])
+        }]),
+        stack([StackSegment {
+            remote_tracking_ref_name: tracking("origin/foo"),
+            ..segment("foo", [id(2)], None, [])
+        }]),
+        stack([segment("origin/foo", [id(3)], None, [])]),
+    ];
+    let id_map = IdMap::new(stacks, Vec::new(), gix::hashtable::HashMap::default())?;
+    let branches = |entity: &str| -> anyhow::Result<Vec<String>> {
+        let changed_paths_fn = |commit_id: gix::ObjectId,
+                                parent_id: Option<gix::ObjectId>|
+         -> anyhow::Result<Vec<but_core::TreeChange>> {
+            bail!("unexpected IDs {commit_id} {parent_id:?}");
+        };
+        Ok(id_map
+            .parse(entity, Box::new(changed_paths_fn))?
+            .into_iter()
+            .map(|cli_id| match cli_id {
+                CliId::Branch(BranchId { name, .. }) => name,
+                other => panic!("expected a branch, got {other:?}"),
+            })
+            .collect())
+    };
+
+    assert_eq!(branches("refs/heads/feature-x")?, ["feature-x"]);
+    assert_eq!(
+        branches("origin/feature-x")?,
+        ["feature-x"],
+        "a remote-tracking branch maps to the local branch tracking it"
+    );
+    assert_eq!(branches("refs/remotes/origin/feature-x")?, ["feature-x"]);
+    assert_eq!(
+        branches("origin/foo")?,
+        ["origin/foo"],
+        "a local branch with that exact name is preferred"
+    );
+    assert_eq!(
+        id_map.parse_ref_name("origin/foo")?.len(),
+        1,
+        "the remote-tracking interpretation is still available to mention it"
+    );
+    assert!(branches("upstream/feature-x")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn displayed_hunk_ids_are_refused_once_their_hunk_is_gone() -> anyhow::Result<()> {
+    let id_map_with_hunk = |added_line: &str| {
+        IdMap::new(
+            Vec::new(),
+            vec![HunkAssignment {
+                hunk_header: Some(hunk_header("-1,1", "+1,1")),
+                diff: Some(BString::from(format!(
+                    "@@ -1,1 +1,1 @@\n-old\n+{added_line}\n"
+                ))),
+                ..hunk_assignment("file.txt", None)
+            }],
+            gix::hashtable::HashMap::default(),
+        )
+    };
+    let changed_paths_fn = |commit_id: gix::ObjectId,
+                            parent_id: Option<gix::ObjectId>|
+     -> anyhow::Result<Vec<but_core::TreeChange>> {
+        bail!("unexpected IDs {commit_id} {parent_id:?}");
+    };
+
+    let shown = id_map_with_hunk("shown")?;
+    let shown_hunk_id = shown
+        .uncommitted_hunks
+        .keys()
+        .next()
+        .expect("one hunk")
+        .clone();
+    let shown_file_id = shown
+        .uncommitted_files
+        .values()
+        .next()
+        .expect("one file")
+        .short_id
+        .clone();
+
+    // Change the hunk to one whose short ID happens to be the same, so the ID that was shown
+    // would resolve to a different hunk.
+    let mut changed = (0..1000)
+        .map(|i| id_map_with_hunk(&format!("changed {i}")))
+        .find(|id_map| {
+            id_map
+                .as_ref()
+                .is_ok_and(|id_map| id_map.uncommitted_hunks.contains_key(&shown_hunk_id))
+        })
+        .expect("a hunk with the same short ID")?;
+    assert_eq!(
+        changed
+            .parse(&shown_hunk_id, Box::new(changed_paths_fn))?
+            .len(),
+        1,
+        "without knowing what was shown, the ID silently resolves to the changed hunk"
+    );
+
+    changed.displayed_ids = shown.displayed_ids();
+    let err = changed
+        .parse(&shown_hunk_id, Box::new(changed_paths_fn))
+        .expect_err("the hunk the ID was shown for is gone");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "The ID '{shown_hunk_id}' is stale, the change it was shown for is gone. Re-run `but status` to see the current IDs"
+        )
+    );
+    assert_eq!(
+        changed
+            .parse(&shown_file_id, Box::new(changed_paths_fn))?
+            .len(),
+        1,
+        "the file is still there, so its ID remains valid"
+    );
+
+    let mut unchanged = id_map_with_hunk("shown")?;
+    unchanged.displayed_ids = shown.displayed_ids();
+    assert_eq!(
+        unchanged
+            .parse(&shown_hunk_id, Box::new(changed_paths_fn))?
+            .len(),
+        1,
+        "IDs of hunks that are still there resolve as usual"
+    );
+    Ok(())
+}
+
 mod util {
     use std::{cmp::Ordering, fmt::Formatter};
 
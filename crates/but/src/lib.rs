@@ -30,8 +30,8 @@ use clap::{CommandFactory, FromArgMatches as _, Parser as _};
 
 pub mod args;
 use args::{
-    Args, OutputFormat, Subcommands, actions, agent, alias as alias_args, branch, forge,
-    update as update_args, worktree,
+    Args, OutputFormat, Subcommands, actions, agent, alias as alias_args, branch, claude, forge,
+    stack, update as update_args, worktree,
 };
 use but_settings::AppSettings;
 use gix::date::time::CustomFormat;
@@ -107,10 +107,24 @@ fn parse_args(args: Vec<OsString>, agent_detected: bool) -> Args {
     let matches = command.get_matches_from(args);
     let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
 
+    apply_directories(&mut args);
+    if args.format.json {
+        args.format.format = OutputFormat::Json;
+    }
     args.status_after = agent_detected;
     args
 }
 
+/// Set the `current_dir` of `args` from all `-C` options, each of which is relative to the
+/// previous one like with `git -C`.
+fn apply_directories(args: &mut Args) {
+    let mut directories = args.directories.iter();
+    if let Some(first) = directories.next() {
+        args.current_dir =
+            directories.fold(first.clone(), |current_dir, dir| current_dir.join(dir));
+    }
+}
+
 static APP_SETTINGS: std::sync::OnceLock<AppSettings> = std::sync::OnceLock::new();
 
 /// The application settings, loaded from the default path once per process.
@@ -203,6 +217,7 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
     }
 
     let mut args = parse_args(args, agent_detected);
+    theme::init_color(args.color);
     let _tracing_appender_worker_guard = if args.trace > 0 {
         trace::init(args.trace, args.log_file.as_deref())?
     } else {
@@ -216,6 +231,9 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
     but_secret::secret::set_application_namespace(namespace);
 
     let mut out = OutputChannel::new(args.format.format);
+    if args.no_pager {
+        out.disable_pager();
+    }
     #[cfg(feature = "legacy")]
     if matches!(
         &args.cmd,
@@ -245,6 +263,7 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
     }
     let app_settings = app_settings()?.clone();
 
+    let json_errors = args.format.format.is_json();
     let result = match args.cmd.take() {
         Some(cmd @ Subcommands::External(_)) => {
             let metrics_ctx = cmd.to_metrics_context(&app_settings, &args.current_dir);
@@ -260,6 +279,7 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
             let expanded_alias = alias::expand_alias("default")?;
             default_args.extend_from_slice(&expanded_alias);
             let mut default_alias_args: Args = clap::Parser::parse_from(default_args);
+            apply_directories(&mut default_alias_args);
 
             // Preserve globals from the default alias, while letting explicit user globals
             // take precedence (e.g. `but -C <dir>` without a subcommand).
@@ -284,8 +304,14 @@ pub async fn handle_args(args: impl Iterator<Item = OsString>) -> Result<()> {
         Some(cmd) => match_subcommand(cmd, args, app_settings, out).await,
     };
 
+    if json_errors && let Err(err) = &result {
+        err.write_json_to_stdout().ok();
+    }
     match result {
-        Err(CliError::Internal(err)) => Err(err),
+        Err(CliError::Internal(err)) => match error::api_error_report(&err) {
+            Some((report, exit_code)) => print_and_exit(report, exit_code),
+            None => Err(err),
+        },
         Err(CliError::BadInput(bad_input)) => print_and_exit_non_zero(bad_input),
         Err(CliError::ExternalCommandNotFound(command_name)) => {
             // We reparse without external subcommands allowed, which _should_ result in a proper
@@ -397,8 +423,12 @@ fn print_err_infallible<T: std::fmt::Display>(err: T) {
 }
 
 fn print_and_exit_non_zero<T: std::fmt::Display>(err: T) -> ! {
+    print_and_exit(err, 1)
+}
+
+fn print_and_exit<T: std::fmt::Display>(err: T, exit_code: i32) -> ! {
     print_err_infallible(err);
-    std::process::exit(1)
+    std::process::exit(exit_code)
 }
 
 async fn match_subcommand(
@@ -413,6 +443,14 @@ async fn match_subcommand(
         Subcommands::AgentLog { cmd } => {
             return Ok(run_agentlog_command(&args.current_dir, cmd, out)?);
         }
+        // Hooks run after every Claude Code session, so stay silent and don't even look at the
+        // repository unless asked to.
+        #[cfg(feature = "legacy")]
+        Subcommands::Claude(claude::Platform {
+            cmd: claude::Subcommands::PostSession,
+        }) if !command::legacy::claude::auto_commit_enabled(&app_settings.claude) => {
+            return Ok(());
+        }
         cmd => cmd,
     };
 
@@ -426,6 +464,7 @@ async fn match_subcommand(
                 | Subcommands::Agent(_)
                 | Subcommands::Help { .. }
                 | Subcommands::Completions { .. }
+                | Subcommands::_Complete { .. }
                 | Subcommands::Metrics { .. }
         );
     let agent_skill_notice = show_agent_skill_notice
@@ -445,6 +484,11 @@ async fn match_subcommand(
         metrics_ctx.push_extra_prop("agentSkillHintShown", true);
     }
 
+    #[cfg(feature = "legacy")]
+    if command::legacy::edit::is_refused_in_edit_mode(&cmd) {
+        command::legacy::edit::ensure_not_in_edit_mode(&args.current_dir)?;
+    }
+
     match cmd {
         Subcommands::Metrics {
             command_name,
@@ -487,6 +531,10 @@ async fn match_subcommand(
             )?;
             command::open::open(&ctx, sources, program_id).emit_metrics(metrics_ctx)
         }
+        Subcommands::_Complete { words } => {
+            command::completions::complete(&args.current_dir, &words);
+            Ok(())
+        }
         Subcommands::Completions { shell } => command::completions::generate_completions(shell)
             .emit_metrics(metrics_ctx)
             .map_err(CliError::from),
@@ -542,6 +590,13 @@ async fn match_subcommand(
                         .emit_metrics(metrics_ctx)
                         .map_err(CliError::from)
                 }
+                Some(
+                    settings_cmd @ (args::config::Subcommands::List
+                    | args::config::Subcommands::Get { .. }
+                    | args::config::Subcommands::Set { .. }),
+                ) => command::config::settings_config(out, settings_cmd)
+                    .emit_metrics(metrics_ctx)
+                    .map_err(CliError::from),
                 Some(args::config::Subcommands::Forge { cmd: forge_cmd }) => {
                     command::config::forge_config(out, forge_cmd.clone())
                         .await
@@ -623,13 +678,21 @@ async fn match_subcommand(
                 #[cfg(feature = "legacy")]
                 Some(branch::Subcommands::List {
                     filter,
+                    author,
+                    case_sensitive,
                     local,
                     remote,
                     all,
                     no_ahead,
                     review,
+                    refresh,
                     no_check,
                     empty,
+                    verbose,
+                    limit,
+                    fast,
+                    no_worktree,
+                    hyperlinks,
                 }) => {
                     let mut ctx = setup::init_ctx(
                         &args,
@@ -639,9 +702,29 @@ async fn match_subcommand(
                         },
                         out,
                     )?;
+                    out.request_git_pager(&*ctx.repo.get()?);
+                    let filter = command::legacy::branch::BranchFilter {
+                        name: filter,
+                        author,
+                        case_sensitive,
+                    };
                     command::legacy::branch::list_branches(
-                        &mut ctx, out, filter, local, remote, all, no_ahead, review, no_check,
+                        &mut ctx,
+                        out,
+                        filter,
+                        local,
+                        remote,
+                        all,
+                        no_ahead,
+                        review,
+                        refresh,
+                        no_check,
                         empty,
+                        verbose,
+                        limit,
+                        fast,
+                        no_worktree,
+                        hyperlinks,
                     )
                     .map_err(CliError::from)
                 }
@@ -666,9 +749,34 @@ async fn match_subcommand(
                     )
                 }
                 #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::SetDescription { branch, text }) => {
+                    let mut ctx = setup::init_ctx(
+                        &args,
+                        InitCtxOptions {
+                            background_sync: BackgroundSync::Enabled { silent: false },
+                            ..Default::default()
+                        },
+                        out,
+                    )?;
+                    command::legacy::branch::set_description(&mut ctx, out, branch, text)
+                }
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::Describe { branch }) => {
+                    let mut ctx = setup::init_ctx(
+                        &args,
+                        InitCtxOptions {
+                            background_sync: BackgroundSync::Enabled { silent: false },
+                            ..Default::default()
+                        },
+                        out,
+                    )?;
+                    command::legacy::branch::describe(&mut ctx, out, branch)
+                }
+                #[cfg(feature = "legacy")]
                 Some(branch::Subcommands::New {
                     branch_name,
                     anchor,
+                    at,
                 }) => {
                     let mut ctx = setup::init_ctx(
                         &args,
@@ -678,10 +786,30 @@ async fn match_subcommand(
                         },
                         out,
                     )?;
-                    command::legacy::branch::new(&mut ctx, out, branch_name, anchor)
+                    command::legacy::branch::new(&mut ctx, out, branch_name, anchor, at)
                 }
                 #[cfg(feature = "legacy")]
-                Some(branch::Subcommands::Delete { branch_name }) => {
+                Some(branch::Subcommands::Delete {
+                    branch_name,
+                    remote,
+                    force,
+                }) => {
+                    let mut ctx = setup::init_ctx(
+                        &args,
+                        InitCtxOptions {
+                            background_sync: BackgroundSync::Enabled { silent: false },
+                            ..Default::default()
+                        },
+                        out,
+                    )?;
+                    command::legacy::branch::delete(&mut ctx, out, branch_name, remote, force)
+                }
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::Unapply {
+                    identifier,
+                    keep_changes,
+                    stash_changes: _,
+                }) => {
                     let mut ctx = setup::init_ctx(
                         &args,
                         InitCtxOptions {
@@ -690,12 +818,42 @@ async fn match_subcommand(
                         },
                         out,
                     )?;
-                    command::legacy::branch::delete(&mut ctx, out, branch_name)
+                    command::legacy::unapply::handle(
+                        &mut ctx,
+                        out,
+                        &identifier,
+                        command::legacy::unapply::assigned_changes(keep_changes),
+                    )
+                    .context("Failed to unapply branch.")
+                    .map_err(CliError::from)
                 }
                 #[cfg(not(feature = "legacy"))]
-                Some(branch::Subcommands::Apply { branch_name }) => {
+                Some(branch::Subcommands::Apply {
+                    branch_name,
+                    force_apply,
+                }) => {
                     let ctx = but_ctx::Context::discover(&args.current_dir)?;
-                    command::branch::apply(ctx, &branch_name, out).map_err(CliError::from)
+                    command::branch::apply(ctx, &branch_name, force_apply, out)
+                }
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::Apply {
+                    branch_name,
+                    force_apply,
+                }) => {
+                    let ctx = setup::init_ctx(
+                        &args,
+                        InitCtxOptions {
+                            background_sync: BackgroundSync::Enabled { silent: false },
+                            ..Default::default()
+                        },
+                        out,
+                    )?;
+                    let branch_name = {
+                        let repo = ctx.repo.get()?;
+                        resolve_legacy_top_level_apply_branch_name(&repo, &branch_name)?
+                    };
+                    command::branch::apply(ctx, &branch_name, force_apply, out)
+                        .map_err(|err| err.context("Failed to apply branch."))
                 }
                 Some(branch::Subcommands::Update {
                     branch,
@@ -734,6 +892,21 @@ async fn match_subcommand(
             };
             result.emit_metrics(metrics_ctx)
         }
+        #[cfg(feature = "legacy")]
+        Subcommands::Stack(stack::Platform { cmd }) => {
+            let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+            match cmd {
+                None => command::legacy::stack::list(&mut ctx, out, false),
+                Some(stack::Subcommands::List { all }) => {
+                    command::legacy::stack::list(&mut ctx, out, all)
+                }
+                Some(stack::Subcommands::Merge { a, b, order }) => {
+                    command::legacy::stack::merge(&mut ctx, out, &a, &b, order)
+                }
+            }
+            .emit_metrics(metrics_ctx)
+            .map_err(CliError::from)
+        }
         Subcommands::Switch {
             target,
             workspace,
@@ -770,9 +943,24 @@ async fn match_subcommand(
             }
         },
         #[cfg(feature = "legacy")]
+        Subcommands::Claude(claude::Platform { cmd }) => match cmd {
+            claude::Subcommands::PostSession => {
+                let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+                let notify =
+                    utils::notifications::enabled(&args, ctx.settings.claude.notify_on_completion);
+                command::legacy::claude::post_session(&mut ctx, out, notify)
+            }
+            claude::Subcommands::InstallHooks => {
+                let ctx = but_ctx::Context::discover(&args.current_dir)?;
+                command::legacy::claude::install_hooks(&ctx, out)
+            }
+        },
+        #[cfg(feature = "legacy")]
         Subcommands::Pull { check } => {
             let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
-            command::legacy::pull::handle(&mut ctx, out, check)
+            let notify =
+                utils::notifications::enabled(&args, ctx.settings.cli.notify_on_slow_operations);
+            command::legacy::pull::handle(&mut ctx, out, check, notify)
                 .await
                 .emit_metrics(metrics_ctx)
                 .map_err(CliError::from)
@@ -789,7 +977,9 @@ async fn match_subcommand(
                 )
             )?;
             let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
-            command::legacy::pull::handle(&mut ctx, out, true)
+            let notify =
+                utils::notifications::enabled(&args, ctx.settings.cli.notify_on_slow_operations);
+            command::legacy::pull::handle(&mut ctx, out, true, notify)
                 .await
                 .emit_metrics(metrics_ctx)
                 .map_err(CliError::from)
@@ -814,7 +1004,11 @@ async fn match_subcommand(
                 let mut progress = out.progress_channel();
                 writeln!(progress, "Pulling latest...")?;
                 let mut pull_out = OutputChannel::new(OutputFormat::None);
-                command::legacy::pull::handle(&mut ctx, &mut pull_out, false).await?;
+                let notify = utils::notifications::enabled(
+                    &args,
+                    ctx.settings.cli.notify_on_slow_operations,
+                );
+                command::legacy::pull::handle(&mut ctx, &mut pull_out, false, notify).await?;
                 writeln!(progress, "Pull complete.")?;
             }
             out.begin_status_after(status_after);
@@ -839,13 +1033,27 @@ async fn match_subcommand(
         }
         #[cfg(feature = "legacy")]
         Subcommands::Status {
+            stack,
+            branch,
+            show_unassigned,
             show_files,
             verbose,
             refresh_prs: sync_prs,
             upstream,
             no_hint,
+            no_stat,
+            collapse_untracked,
+            no_collapse,
+            group_by_dir,
+            watch,
+            interval,
+            porcelain,
+            ids,
+            ids_only,
+            nul_terminated,
             short: _,
         } => {
+            use crate::command::legacy::status::CollapseUntracked;
             use crate::command::legacy::status::FilesStatusFlag;
             use crate::command::legacy::status::StatusFlags;
 
@@ -857,26 +1065,80 @@ async fn match_subcommand(
                 },
                 out,
             )?;
+            if porcelain || nul_terminated {
+                let mut porcelain_out = OutputChannel::new(OutputFormat::Shell);
+                return command::legacy::status::porcelain::worktree(
+                    &mut ctx,
+                    &mut porcelain_out,
+                    nul_terminated,
+                )
+                .emit_metrics(metrics_ctx)
+                .map_err(CliError::from);
+            }
+            if ids_only {
+                let mut ids_out = OutputChannel::new(OutputFormat::Shell);
+                return command::legacy::status::ids_only::worktree(&mut ctx, &mut ids_out)
+                    .emit_metrics(metrics_ctx)
+                    .map_err(CliError::from);
+            }
             let show_files = if show_files {
                 FilesStatusFlag::All
             } else {
                 FilesStatusFlag::None
             };
+            let offline = utils::envs::is_offline();
+            if sync_prs && offline {
+                use std::fmt::Write;
+                writeln!(
+                    out.progress_channel(),
+                    "{}",
+                    theme::get().attention.paint(format!(
+                        "{} is set, showing cached pull requests instead of refreshing them",
+                        utils::envs::BUT_OFFLINE
+                    ))
+                )?;
+            }
             let flags = StatusFlags {
                 show_files,
                 verbose,
-                refresh_prs: sync_prs,
+                refresh_prs: sync_prs && !offline,
                 show_upstream: upstream,
                 hint: !no_hint,
+                diff_stat: !no_stat,
+                collapse_untracked: if no_collapse {
+                    CollapseUntracked::Never
+                } else if collapse_untracked {
+                    CollapseUntracked::Always
+                } else {
+                    CollapseUntracked::PastThreshold
+                },
+                group_by_dir,
+                ids,
             };
+            let filter = stack
+                .or(branch)
+                .map(|target| command::legacy::status::StackFilter {
+                    target,
+                    show_unassigned,
+                });
+            if watch {
+                return command::legacy::status::watch::watch(
+                    &mut ctx,
+                    out,
+                    flags,
+                    filter.as_ref(),
+                    std::time::Duration::from_secs(interval),
+                )
+                .emit_metrics(metrics_ctx);
+            }
             command::legacy::status::worktree(
                 &mut ctx,
                 out,
                 flags,
                 command::legacy::status::StatusRenderMode::Oneshot,
+                filter.as_ref(),
             )
             .emit_metrics(metrics_ctx)
-            .map_err(CliError::from)
         }
         #[cfg(feature = "legacy")]
         Subcommands::Tui {
@@ -931,9 +1193,9 @@ async fn match_subcommand(
                 out,
                 StatusFlags::for_tui(),
                 StatusRenderMode::Tui(_options),
+                None,
             )
             .emit_metrics(metrics_ctx)
-            .map_err(CliError::from)
         }
         #[cfg(feature = "legacy")]
         Subcommands::Rub { source, target } => {
@@ -962,6 +1224,96 @@ async fn match_subcommand(
             result.show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
+        Subcommands::Assign {
+            sources,
+            branch,
+            from,
+            force,
+        } => {
+            let status_after = args.status_after;
+            let mut ctx = setup::init_ctx(
+                &args,
+                InitCtxOptions {
+                    background_sync: BackgroundSync::Enabled { silent: false },
+                    ..Default::default()
+                },
+                out,
+            )?;
+            out.begin_status_after(status_after);
+            let result = command::legacy::assign::handle(
+                &mut ctx,
+                out,
+                &sources,
+                &branch,
+                from.as_deref(),
+                force,
+            )
+            .context("Failed to assign changes.")
+            .emit_metrics(metrics_ctx);
+            run_status_after_if_ok(status_after, &result, &mut ctx, out);
+            result.show_root_cause_error_then_exit_without_destructors(output)
+        }
+        #[cfg(feature = "legacy")]
+        Subcommands::Rule(args::rule::Platform { cmd }) => match cmd {
+            None | Some(args::rule::Subcommands::List) => {
+                let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+                command::legacy::rule::handle_list(&mut ctx, out)
+                    .emit_metrics(metrics_ctx)
+                    .map_err(CliError::from)
+            }
+            Some(args::rule::Subcommands::Add { path, branch }) => {
+                let status_after = args.status_after;
+                let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+                out.begin_status_after(status_after);
+                let result = command::legacy::rule::handle_add(&mut ctx, out, &path, &branch)
+                    .context("Failed to add the rule.")
+                    .emit_metrics(metrics_ctx);
+                run_status_after_if_ok(status_after, &result, &mut ctx, out);
+                result.show_root_cause_error_then_exit_without_destructors(output)
+            }
+            Some(args::rule::Subcommands::Remove { ids, all, prune }) => {
+                let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+                let selection = if all {
+                    command::legacy::rule::Selection::All
+                } else if prune {
+                    command::legacy::rule::Selection::Pruned
+                } else {
+                    command::legacy::rule::Selection::Ids(ids)
+                };
+                command::legacy::rule::handle_remove(&mut ctx, out, selection)
+                    .context("Failed to remove rules.")
+                    .emit_metrics(metrics_ctx)
+                    .map_err(CliError::from)
+            }
+        },
+        #[cfg(feature = "legacy")]
+        Subcommands::Unassign {
+            sources,
+            all: _,
+            branch,
+        } => {
+            let status_after = args.status_after;
+            let mut ctx = setup::init_ctx(
+                &args,
+                InitCtxOptions {
+                    background_sync: BackgroundSync::Enabled { silent: false },
+                    ..Default::default()
+                },
+                out,
+            )?;
+            out.begin_status_after(status_after);
+            let result = command::legacy::assign::handle_unassign(
+                &mut ctx,
+                out,
+                &sources,
+                branch.as_deref(),
+            )
+            .context("Failed to unassign changes.")
+            .emit_metrics(metrics_ctx);
+            run_status_after_if_ok(status_after, &result, &mut ctx, out);
+            result.show_root_cause_error_then_exit_without_destructors(output)
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::Diff {
             target,
             tui,
@@ -1003,14 +1355,44 @@ async fn match_subcommand(
                     .show_root_cause_error_then_exit_without_destructors(output)
             }
         }
-        Subcommands::Edit { file } => {
+        Subcommands::_EditFile { file } => {
             let path = args.current_dir.join(&file);
             tui::editor::edit_file(&path)
                 .emit_metrics(metrics_ctx)
                 .show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Show { commit, verbose } => {
+        Subcommands::Edit {
+            commit,
+            r#continue,
+            abort,
+        } => {
+            let mut ctx = setup::init_ctx(
+                &args,
+                InitCtxOptions {
+                    background_sync: BackgroundSync::Enabled { silent: false },
+                    ..Default::default()
+                },
+                out,
+            )?;
+            match commit {
+                Some(commit) => command::legacy::edit::handle(&mut ctx, out, &commit)
+                    .context("Failed to enter edit mode."),
+                None if r#continue => command::legacy::edit::continue_edit(&mut ctx, out)
+                    .context("Failed to apply edits."),
+                None if abort => command::legacy::edit::abort_edit(&mut ctx, out)
+                    .context("Failed to abort edit."),
+                None => unreachable!("clap requires a commit without --continue or --abort"),
+            }
+            .emit_metrics(metrics_ctx)
+            .show_root_cause_error_then_exit_without_destructors(output)
+        }
+        #[cfg(feature = "legacy")]
+        Subcommands::Show {
+            commit,
+            verbose,
+            path,
+        } => {
             let mut ctx = setup::init_ctx(
                 &args,
                 InitCtxOptions {
@@ -1019,21 +1401,13 @@ async fn match_subcommand(
                 },
                 out,
             )?;
-            command::legacy::show::show_commit(&mut ctx, out, &commit, verbose)
+            out.request_git_pager(&*ctx.repo.get()?);
+            command::legacy::show::show_commit(&mut ctx, out, &commit, verbose, path.as_deref())
                 .emit_metrics(metrics_ctx)
                 .show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
         Subcommands::Commit(commit_args) => {
-            if let Ok(repo) = gix::discover(&args.current_dir)
-                && repo.worktree().is_some_and(|worktree| !worktree.is_main())
-            {
-                return Err(anyhow::anyhow!(
-                    "`but commit` is not supported from linked worktrees. Use Git directly for this worktree."
-                )
-                .into());
-            }
-
             let status_after = args.status_after;
             let mut ctx = setup::init_ctx(
                 &args,
@@ -1086,6 +1460,12 @@ async fn match_subcommand(
                             bad_input("--no-hooks cannot be used with 'commit empty'.").into()
                         );
                     }
+                    if commit_args.allow_large_files {
+                        return Err(bad_input(
+                            "--allow-large-files cannot be used with 'commit empty'.",
+                        )
+                        .into());
+                    }
                     if commit_args.ai.is_some() {
                         return Err(bad_input("--ai cannot be used with 'commit empty'.").into());
                     }
@@ -1144,8 +1524,11 @@ async fn match_subcommand(
                         &commit_args.changes,
                         commit_args.all,
                         commit_args.create,
+                        commit_args.anchor.clone(),
                         commit_args.no_hooks,
+                        commit_args.allow_large_files,
                         commit_args.ai.clone(),
+                        commit_args.no_edit,
                         ShowDiffInEditor::from_args(commit_args.diff, commit_args.no_diff)
                             .unwrap_or(ShowDiffInEditor::Unspecified),
                     )
@@ -1250,7 +1633,9 @@ async fn match_subcommand(
         #[cfg(feature = "legacy")]
         Subcommands::Push(push_args) => {
             let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
-            command::legacy::push::handle(push_args, &mut ctx, out)
+            let notify =
+                utils::notifications::enabled(&args, ctx.settings.cli.notify_on_slow_operations);
+            command::legacy::push::handle(push_args, &mut ctx, out, notify)
                 .await
                 .emit_metrics(metrics_ctx)
                 .map_err(CliError::from)
@@ -1286,6 +1671,9 @@ async fn match_subcommand(
         #[cfg(feature = "legacy")]
         Subcommands::Oplog(args::oplog::Platform { cmd }) => {
             let mut ctx = setup::init_ctx(&args, InitCtxOptions::default(), out)?;
+            if matches!(cmd, None | Some(args::oplog::Subcommands::List { .. })) {
+                out.request_git_pager(&*ctx.repo.get()?);
+            }
             match cmd {
                 Some(args::oplog::Subcommands::List { since, snapshot }) => {
                     let filter = if snapshot {
@@ -1361,7 +1749,14 @@ async fn match_subcommand(
                 .map_err(CliError::from)
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Setup { init } => {
+        Subcommands::Setup {
+            init,
+            target,
+            fetch,
+            guess: _,
+            no_guess,
+            no_adopt,
+        } => {
             let repo = match but_api::legacy::projects::add_project_best_effort(
                 args.current_dir.clone(),
             )? {
@@ -1380,10 +1775,21 @@ async fn match_subcommand(
             };
             let mut ctx = but_ctx::Context::from_repo_with_settings(repo, app_settings.clone())?;
             let mut guard = ctx.exclusive_worktree_access();
-            command::legacy::setup::repo(&mut ctx, &args.current_dir, out, guard.write_permission())
-                .context("Failed to set up GitButler project.")
-                .emit_metrics(metrics_ctx)
-                .map_err(CliError::from)
+            command::legacy::setup::repo(
+                &mut ctx,
+                &args.current_dir,
+                command::legacy::setup::TargetOptions {
+                    branch: target.as_deref(),
+                    fetch,
+                    no_guess,
+                    no_adopt,
+                },
+                out,
+                guard.write_permission(),
+            )
+            .context("Failed to set up GitButler project.")
+            .emit_metrics(metrics_ctx)
+            .map_err(CliError::from)
         }
         #[cfg(feature = "legacy")]
         Subcommands::Teardown { checkout_to } => {
@@ -1401,6 +1807,25 @@ async fn match_subcommand(
                 .emit_metrics(metrics_ctx)
         }
         #[cfg(feature = "legacy")]
+        Subcommands::Deinit {
+            checkout,
+            keep_data: _,
+            purge,
+        } => {
+            let mut ctx = setup::init_ctx(
+                &args,
+                InitCtxOptions {
+                    workspace_check: setup::WorkspaceCheck::Disabled,
+                    target_requirement: TargetRequirement::Optional,
+                    ..Default::default()
+                },
+                out,
+            )?;
+            command::legacy::deinit::deinit(&mut ctx, checkout, purge, out)
+                .map_err(|err| err.context("Failed to stop managing the repository."))
+                .emit_metrics(metrics_ctx)
+        }
+        #[cfg(feature = "legacy")]
         Subcommands::Pr(forge::pr::Platform {
             cmd,
             draft: top_level_draft,
@@ -1418,6 +1843,8 @@ async fn match_subcommand(
                     branch,
                     message,
                     file,
+                    title,
+                    body_file,
                     skip_force_push_protection,
                     with_force,
                     no_hooks,
@@ -1425,6 +1852,21 @@ async fn match_subcommand(
                     draft,
                 }) => {
                     let draft = top_level_draft || draft;
+                    let body = match &body_file {
+                        Some(path) => Some(std::fs::read_to_string(path).with_context(|| {
+                            format!(
+                                "Failed to read forge review description from file: {}",
+                                path.display()
+                            )
+                        })?),
+                        None => None,
+                    };
+                    let overrides = command::legacy::forge::review::ReviewMessageOverrides {
+                        title,
+                        body: body.map(|body| body.trim().to_owned()),
+                    };
+                    // Given parts of the message make the rest default, instead of prompting for it.
+                    let default = default || !overrides.is_empty();
                     // Read message content from file or inline
                     let message_content = match &file {
                         Some(path) => Some(std::fs::read_to_string(path).with_context(|| {
@@ -1452,7 +1894,7 @@ async fn match_subcommand(
                         }
                         if review_message.is_none() && !default {
                             return Err(bad_input(
-                                "Non-interactive environment detected. Provide one of: --message (-m), --file (-F), or --default (-t)."
+                                "Non-interactive environment detected. Provide one of: --message (-m), --file (-F), --title, --body-file, or --default (-t)."
                             ).into());
                         }
                     }
@@ -1465,6 +1907,7 @@ async fn match_subcommand(
                         default,
                         draft,
                         review_message,
+                        overrides,
                         out,
                     )
                     .await
@@ -1482,6 +1925,12 @@ async fn match_subcommand(
                     .emit_metrics(metrics_ctx)
                     .map_err(CliError::from)
                 }
+                Some(forge::pr::Subcommands::Open { branch, print }) => {
+                    command::legacy::forge::review::open_review(&mut ctx, branch, print, out)
+                        .context("Failed to open the forge review.")
+                        .emit_metrics(metrics_ctx)
+                        .map_err(CliError::from)
+                }
                 Some(forge::pr::Subcommands::AutoMerge { selector, off }) => {
                     command::legacy::forge::review::enable_auto_merge(&mut ctx, selector, off, out)
                         .await
@@ -1514,6 +1963,7 @@ async fn match_subcommand(
                         false,
                         top_level_draft,
                         None,
+                        Default::default(),
                         out,
                     )
                     .await
@@ -1536,7 +1986,12 @@ async fn match_subcommand(
                 .map_err(CliError::from)
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Resolve { cmd, commit, ai } => {
+        Subcommands::Resolve {
+            cmd,
+            commit,
+            ai,
+            tool,
+        } => {
             let mut ctx = setup::init_ctx(
                 &args,
                 InitCtxOptions {
@@ -1545,7 +2000,7 @@ async fn match_subcommand(
                 },
                 out,
             )?;
-            command::legacy::resolve::handle(&mut ctx, out, cmd, commit, ai)
+            command::legacy::resolve::handle(&mut ctx, out, cmd, commit, ai, tool)
                 .context("Failed to handle conflict resolution.")
                 .emit_metrics(metrics_ctx)
                 .show_root_cause_error_then_exit_without_destructors(output)
@@ -1745,7 +2200,11 @@ async fn match_subcommand(
                 .show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Unapply { identifier } => {
+        Subcommands::Unapply {
+            identifier,
+            keep_changes,
+            stash_changes: _,
+        } => {
             let mut ctx = setup::init_ctx(
                 &args,
                 InitCtxOptions {
@@ -1754,13 +2213,21 @@ async fn match_subcommand(
                 },
                 out,
             )?;
-            command::legacy::unapply::handle(&mut ctx, out, &identifier)
-                .context("Failed to unapply branch.")
-                .emit_metrics(metrics_ctx)
-                .show_root_cause_error_then_exit_without_destructors(output)
+            command::legacy::unapply::handle(
+                &mut ctx,
+                out,
+                &identifier,
+                command::legacy::unapply::assigned_changes(keep_changes),
+            )
+            .context("Failed to unapply branch.")
+            .emit_metrics(metrics_ctx)
+            .show_root_cause_error_then_exit_without_destructors(output)
         }
         #[cfg(feature = "legacy")]
-        Subcommands::Apply { branch_name } => {
+        Subcommands::Apply {
+            branch_name,
+            force_apply,
+        } => {
             let ctx = setup::init_ctx(
                 &args,
                 InitCtxOptions {
@@ -1773,8 +2240,8 @@ async fn match_subcommand(
                 let repo = ctx.repo.get()?;
                 resolve_legacy_top_level_apply_branch_name(&repo, &branch_name)?
             };
-            command::branch::apply(ctx, &branch_name, out)
-                .context("Failed to apply branch.")
+            command::branch::apply(ctx, &branch_name, force_apply, out)
+                .map_err(|err| err.context("Failed to apply branch."))
                 .emit_metrics(metrics_ctx)
                 .show_root_cause_error_then_exit_without_destructors(output)
         }
@@ -1947,6 +2414,7 @@ fn run_status_after(
             out,
             StatusFlags::all_false(),
             command::legacy::status::StatusRenderMode::Oneshot,
+            None,
         );
         let status_json = out.take_json_buffer().unwrap_or(serde_json::Value::Null);
 
@@ -1990,6 +2458,7 @@ fn run_status_after(
                 ..StatusFlags::all_false()
             },
             command::legacy::status::StatusRenderMode::Oneshot,
+            None,
         ) {
             eprintln!(
                 "warning: status after mutation failed: {err:#}. Run 'but status' separately to check workspace state."
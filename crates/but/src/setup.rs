@@ -126,10 +126,39 @@ pub fn init_ctx(
         };
         #[cfg(feature = "legacy")]
         {
-            use but_ctx::LegacyProject;
+            use anyhow::Context as _;
+            use but_ctx::{LegacyProject, RepoOpenMode};
 
             use crate::command::legacy::setup::check_project_setup;
 
+            // A linked worktree belongs to the project of the main worktree, but keeps its own
+            // `HEAD` and index.
+            let linked_worktree_git_dir = repo
+                .worktree()
+                .is_some_and(|worktree| !worktree.is_main())
+                .then(|| repo.git_dir().to_owned());
+            let main_workdir = match linked_worktree_git_dir {
+                Some(_) => Some(
+                    repo.main_repo()?
+                        .workdir()
+                        .context("The main worktree of a linked worktree must not be bare")?
+                        .to_owned(),
+                ),
+                None => None,
+            };
+            let workdir = main_workdir.as_deref().unwrap_or(workdir);
+            let new_ctx = |project: &LegacyProject| match &linked_worktree_git_dir {
+                Some(git_dir) => Context::new_from_legacy_project_in_worktree(
+                    project,
+                    app_settings.clone(),
+                    git_dir.clone(),
+                    RepoOpenMode::Standard,
+                ),
+                None => {
+                    Context::new_from_legacy_project_and_settings(project, app_settings.clone())
+                }
+            };
+
             if app_settings.feature_flags.single_branch {
                 let project = match LegacyProject::find_by_worktree_dir(workdir) {
                     Ok(project) => project,
@@ -139,8 +168,7 @@ pub fn init_ctx(
                         outcome => outcome.try_project()?,
                     },
                 };
-                let mut ctx =
-                    Context::new_from_legacy_project_and_settings(&project, app_settings.clone())?;
+                let mut ctx = new_ctx(&project)?;
                 if matches!(options.target_requirement, TargetRequirement::Required)
                     && ctx.project_meta()?.target_ref.is_none()
                 {
@@ -206,8 +234,7 @@ pub fn init_ctx(
                 };
 
                 // Check project setup, prompt for setup if needed
-                let mut ctx =
-                    Context::new_from_legacy_project_and_settings(&project, app_settings.clone())?;
+                let mut ctx = new_ctx(&project)?;
                 {
                     let mut guard = ctx.exclusive_worktree_access();
                     if let Err(e) = check_project_setup(&ctx, guard.read_permission()) {
@@ -270,8 +297,9 @@ pub fn init_ctx(
             return Ok(ctx);
         }
         BackgroundSync::Enabled { silent } => {
-            // Check if background tasks are disabled via environment variable
-            if std::env::var("NO_BG_TASKS").is_ok() {
+            // Check if background tasks are disabled via environment variable, or if we are
+            // offline as they would all access the network.
+            if std::env::var("NO_BG_TASKS").is_ok() || crate::utils::envs::is_offline() {
                 return Ok(ctx);
             }
 
@@ -495,15 +523,7 @@ fn prompt_for_setup(out: &mut OutputChannel, message: &str) -> SetupPromptResult
     }
 
     // The declined/non-interactive cases end in the caller's "Setup required" error,
-    // which carries the `but setup` remediation for every format; JSON additionally
-    // gets it as a structured value.
-    if let Some(json_out) = out.for_json() {
-        _ = json_out.write_value(serde_json::json!({
-            "error": "setup_required",
-            "message": message.to_string(),
-            "hint": "run `but setup` to configure the project"
-        }));
-    }
+    // which carries the `but setup` remediation for every format, JSON included.
     SetupPromptResult::Declined
 }
 
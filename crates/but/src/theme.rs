@@ -27,7 +27,9 @@
 //! Missing fields in a user-supplied file fall back to the built-in defaults thanks to
 //! `#[serde(default)]`.
 
-use std::{fmt::Display, path::Path, str::FromStr, sync::OnceLock};
+use std::{
+    ffi::OsString, fmt::Display, io::IsTerminal as _, path::Path, str::FromStr, sync::OnceLock,
+};
 
 use bstr::ByteSlice as _;
 use but_core::ChangeId;
@@ -41,6 +43,8 @@ use ratatui::{
 use serde::{Deserialize, Serialize};
 use syntect::highlighting::{self, ThemeSet};
 
+use crate::args::ColorMode;
+
 const CATPPUCCIN_THEME_DARK: &[u8] =
     include_bytes!("../assets/syntax-highlighting-themes/Catppuccin Mocha.tmTheme");
 
@@ -80,6 +84,49 @@ pub fn get() -> &'static Theme {
     }
 }
 
+/// Decide whether styled output is colored, according to `mode` and the environment.
+///
+/// Must be called before any output is produced. All styled output, like [`Paint::paint()`],
+/// follows this decision, and [`should_colorize()`] returns it.
+pub fn init_color(mode: ColorMode) {
+    let colorize = resolve_color(
+        mode,
+        |name| std::env::var_os(name),
+        std::io::stdout().is_terminal(),
+    );
+    colored::control::set_override(colorize);
+}
+
+/// Return `true` if styled output should contain color codes.
+pub fn should_colorize() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Decide whether to use colors for `mode`, with `CLICOLOR_FORCE` taking precedence over
+/// `NO_COLOR`, and both of them over `CLICOLOR` and whether the output `is_terminal`.
+fn resolve_color(
+    mode: ColorMode,
+    var: impl Fn(&str) -> Option<OsString>,
+    is_terminal: bool,
+) -> bool {
+    let is_set = |name: &str| var(name).is_some_and(|value| !value.is_empty() && value != "0");
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            if is_set("CLICOLOR_FORCE") {
+                true
+            } else if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                false
+            } else if var("CLICOLOR").is_some_and(|value| value == "0") {
+                false
+            } else {
+                is_terminal
+            }
+        }
+    }
+}
+
 /// Load a theme from a JSON file.
 ///
 /// Fields that are absent in the file keep their [`Theme::default`] values.
@@ -114,7 +161,7 @@ impl Paint for Style {
     fn paint<S: AsRef<str>>(&self, text: S) -> ColoredString {
         // This is technically unnecessary as `colored` performs this check internally, it's just
         // here for clarity of intent
-        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        if !should_colorize() {
             return text.as_ref().into();
         }
 
@@ -252,6 +299,14 @@ pub struct Theme {
     pub cli_id: Style,
     /// PR / review number decorations
     pub pr_number: Style,
+    /// PR / review numbers of open reviews, where their state matters
+    pub pr_open: Style,
+    /// PR / review numbers of draft reviews, where their state matters
+    pub pr_draft: Style,
+    /// PR / review numbers of merged reviews, where their state matters
+    pub pr_merged: Style,
+    /// PR / review numbers of reviews closed without merging, where their state matters
+    pub pr_closed: Style,
     /// Hyperlinks (PR URLs, review links).
     pub link: Style,
     /// Configuration value (user name, email, provider, alias value, etc.).
@@ -397,6 +452,10 @@ impl Theme {
             change_id: style_fg(Color::Magenta),
             cli_id: style_fg_bold(Color::Blue),
             pr_number: style_fg(Color::Blue),
+            pr_open: style_fg(Color::Green),
+            pr_draft: style_fg(Color::DarkGray),
+            pr_merged: style_fg(Color::Magenta),
+            pr_closed: style_fg(Color::Red),
             link: Style::new()
                 .fg(Color::Blue)
                 .add_modifier(Modifier::UNDERLINED),
@@ -715,6 +774,59 @@ mod tests {
         assert_eq!(theme.addition, Theme::default().addition);
     }
 
+    #[test]
+    fn color_follows_mode_then_environment_then_terminal() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+        assert!(resolve_color(ColorMode::Auto, env(&[]), true));
+        assert!(!resolve_color(ColorMode::Auto, env(&[]), false));
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            env(&[("NO_COLOR", "1")]),
+            true
+        ));
+        assert!(
+            resolve_color(ColorMode::Auto, env(&[("NO_COLOR", "")]), true),
+            "an empty NO_COLOR is ignored"
+        );
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            env(&[("CLICOLOR", "0")]),
+            true
+        ));
+        assert!(resolve_color(
+            ColorMode::Auto,
+            env(&[("CLICOLOR_FORCE", "1")]),
+            false
+        ));
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            env(&[("CLICOLOR_FORCE", "0")]),
+            false
+        ));
+        assert!(resolve_color(
+            ColorMode::Auto,
+            env(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]),
+            false
+        ));
+
+        assert!(resolve_color(
+            ColorMode::Always,
+            env(&[("NO_COLOR", "1")]),
+            false
+        ));
+        assert!(!resolve_color(
+            ColorMode::Never,
+            env(&[("CLICOLOR_FORCE", "1")]),
+            true
+        ));
+    }
+
     #[test]
     fn empty_json_produces_default_theme() {
         let theme: Theme = serde_json::from_str("{}").unwrap();
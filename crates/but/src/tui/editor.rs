@@ -1,7 +1,7 @@
 //! A built-in TUI text editor inspired by Microsoft's `edit`.
 //!
 //! Used as the fallback when no external editor (`GIT_EDITOR`, `core.editor`, `EDITOR`) is configured,
-//! and also available directly via `but edit <file>`.
+//! and also available directly via `but _edit-file <file>`.
 
 use std::time::Duration;
 
@@ -51,7 +51,7 @@ fn filter_content_from_editor(content: &BStr) -> Vec<&BStr> {
 /// identified by a `filename_safe_intent` to help the user understand what's wanted of them.
 /// Note that this string must be valid in filenames.
 ///
-/// If the user has an external editor configured (via `GIT_EDITOR`, `core.editor`, or `EDITOR`),
+/// If the user has an external editor configured (via the `cli.editor` setting, `GIT_EDITOR`, `core.editor`, or `EDITOR`),
 /// that editor is used. Otherwise, the built-in TUI editor is launched.
 ///
 /// Returns the edited text (*without known encoding*) verbatim.
@@ -161,29 +161,41 @@ fn from_builtin_editor(
 
 /// Get the user's preferred editor command, if one is configured.
 ///
-/// Runs `git var GIT_EDITOR`, which lets git do its resolution of the editor command.
-/// This typically uses the git config value for `core.editor`, and env vars like `GIT_EDITOR` or `EDITOR`.
+/// The `cli.editor` application setting takes precedence, so users can pick an editor for `but`
+/// without changing the one Git uses. Otherwise, the editor is resolved like Git does,
+/// typically using the git config value for `core.editor`, and env vars like `GIT_EDITOR` or `EDITOR`.
 ///
 /// Returns `None` when no editor is configured, signalling that the built-in editor should be used.
 ///
 /// Note: Because git config parsing is used, the current directory matters for potential local git config overrides.
 pub fn get_editor_command() -> Option<String> {
-    get_editor_command_impl(std::env::vars_os())
+    let settings_editor =
+        but_settings::AppSettings::load_from_default_path_creating_without_customization()
+            .ok()
+            .map(|settings| settings.cli.editor);
+    get_editor_command_impl(settings_editor.as_deref(), std::env::vars_os())
 }
 
-/// Internal implementation that can be tested with the controlled environment `env`.
+/// Internal implementation that can be tested with the controlled `settings_editor` and environment `env`.
 ///
-/// Checks the standard Git editor sources in precedence order:
-/// 1. `GIT_EDITOR` env var
-/// 2. `core.editor` git config
-/// 3. `VISUAL` env var
-/// 4. `EDITOR` env var
+/// Checks the editor sources in precedence order:
+/// 1. `settings_editor`, i.e. the `cli.editor` application setting
+/// 2. `GIT_EDITOR` env var
+/// 3. `core.editor` git config
+/// 4. `VISUAL` env var
+/// 5. `EDITOR` env var
 ///
 /// Unlike `git var GIT_EDITOR`, this does NOT fall back to `vi` when nothing
 /// is configured — it returns `None` so the caller can use the built-in editor.
 fn get_editor_command_impl<AsOsStr: AsRef<OsStr>>(
+    settings_editor: Option<&str>,
     env: impl IntoIterator<Item = (AsOsStr, AsOsStr)>,
 ) -> Option<String> {
+    // 1. The editor configured in the application settings
+    if let Some(editor) = settings_editor.map(str::trim).filter(|e| !e.is_empty()) {
+        return Some(editor.to_owned());
+    }
+
     let env: Vec<(String, String)> = env
         .into_iter()
         .filter_map(|(k, v)| {
@@ -201,12 +213,12 @@ fn get_editor_command_impl<AsOsStr: AsRef<OsStr>>(
             .filter(|v| !v.is_empty())
     };
 
-    // 1. GIT_EDITOR environment variable
+    // 2. GIT_EDITOR environment variable
     if let Some(editor) = lookup_env("GIT_EDITOR") {
         return Some(editor);
     }
 
-    // 2. core.editor from git config
+    // 3. core.editor from git config
     {
         let mut cmd = std::process::Command::new(gix::path::env::exe_invocation());
         let res = cmd
@@ -224,12 +236,12 @@ fn get_editor_command_impl<AsOsStr: AsRef<OsStr>>(
         }
     }
 
-    // 3. VISUAL environment variable
+    // 4. VISUAL environment variable
     if let Some(editor) = lookup_env("VISUAL") {
         return Some(editor);
     }
 
-    // 4. EDITOR environment variable
+    // 5. EDITOR environment variable
     if let Some(editor) = lookup_env("EDITOR") {
         return Some(editor);
     }
@@ -277,28 +289,42 @@ mod tests {
             ("VISUAL", "from-VISUAL"),
             ("EDITOR", "from-EDITOR"),
         ];
-        let actual = get_editor_command_impl(env);
+        let actual = get_editor_command_impl(None, env);
         assert_eq!(actual.as_deref(), Some("from-GIT_EDITOR"));
     }
 
+    #[test]
+    fn settings_editor_takes_precedence_over_everything() {
+        let env = vec![("GIT_EDITOR", "from-GIT_EDITOR"), ("EDITOR", "from-EDITOR")];
+        let actual = get_editor_command_impl(Some("code --wait"), env.clone());
+        assert_eq!(actual.as_deref(), Some("code --wait"));
+
+        let actual = get_editor_command_impl(Some("  "), env);
+        assert_eq!(
+            actual.as_deref(),
+            Some("from-GIT_EDITOR"),
+            "an empty setting falls back to the usual resolution"
+        );
+    }
+
     #[test]
     fn visual_used_when_no_git_editor() {
         let env = vec![("VISUAL", "from-VISUAL"), ("EDITOR", "from-EDITOR")];
-        let actual = get_editor_command_impl(env);
+        let actual = get_editor_command_impl(None, env);
         assert_eq!(actual.as_deref(), Some("from-VISUAL"));
     }
 
     #[test]
     fn editor_used_as_last_env_fallback() {
         let env = vec![("EDITOR", "from-EDITOR")];
-        let actual = get_editor_command_impl(env);
+        let actual = get_editor_command_impl(None, env);
         assert_eq!(actual.as_deref(), Some("from-EDITOR"));
     }
 
     #[test]
     fn falls_back_to_builtin_when_nothing_set() {
         let no_env = None::<(String, String)>;
-        let actual = get_editor_command_impl(no_env);
+        let actual = get_editor_command_impl(None, no_env);
         assert!(
             actual.is_none(),
             "Should return None when no editor is configured, got: {actual:?}"
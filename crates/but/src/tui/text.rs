@@ -18,6 +18,49 @@ pub fn terminal_width() -> usize {
     }
 }
 
+/// Returns `true` if the environment indicates a terminal known to support OSC 8 hyperlinks.
+///
+/// `FORCE_HYPERLINK` overrides the detection, with `0` disabling hyperlinks.
+pub fn terminal_supports_hyperlinks() -> bool {
+    supports_hyperlinks_with(|key| std::env::var(key).ok())
+}
+
+/// [`terminal_supports_hyperlinks`] with `var` to look up environment variables.
+fn supports_hyperlinks_with(var: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if var("CI").is_some() {
+        return false;
+    }
+    if ["WT_SESSION", "KONSOLE_VERSION", "DOMTERM"]
+        .iter()
+        .any(|key| var(key).is_some())
+    {
+        return true;
+    }
+    if var("VTE_VERSION")
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| version >= 5000)
+    {
+        return true;
+    }
+    if var("TERM_PROGRAM").is_some_and(|program| {
+        matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+        )
+    }) {
+        return true;
+    }
+    var("TERM").is_some_and(|term| {
+        matches!(
+            term.as_str(),
+            "xterm-kitty" | "xterm-ghostty" | "alacritty" | "foot" | "wezterm"
+        )
+    })
+}
+
 /// Truncate `text` to fit within `max_width` display columns.
 ///
 /// Uses [`unicode_width`] so that CJK / emoji characters (which occupy
@@ -47,73 +90,129 @@ pub fn truncate_text<'a>(text: impl Into<Cow<'a, str>>, max_width: usize) -> Cow
     let target_width = max_width.saturating_sub(1);
     let mut width = 0;
     let mut out = String::new();
-    let mut in_ansi = false;
-    let mut ansi_buffer = String::new();
+    let mut in_hyperlink = false;
+    let mut rest = text.as_ref();
 
-    for ch in text.chars() {
-        // Start of an ANSI escape sequence — buffer it.
+    while let Some(ch) = rest.chars().next() {
+        // Pass escape sequences through as a whole without counting toward display width.
         if ch == '\x1b' {
-            in_ansi = true;
-            ansi_buffer.push(ch);
-            continue;
-        }
-
-        // Inside an escape sequence — keep buffering until the terminating 'm'.
-        if in_ansi {
-            ansi_buffer.push(ch);
-            if ch == 'm' {
-                // Flush the whole escape sequence into the result
-                // without counting toward display width.
-                out.extend(ansi_buffer.drain(..));
-                in_ansi = false;
+            let (sequence, tail) = rest.split_at(ansi_escape_len(rest));
+            if sequence.starts_with(HYPERLINK_START) {
+                in_hyperlink = !matches!(sequence, HYPERLINK_END | "\x1b]8;;\x07");
             }
+            out.push_str(sequence);
+            rest = tail;
             continue;
         }
 
         let ch_width = ch.width().unwrap_or(0);
         if width + ch_width > target_width {
             out.push('…');
+            if in_hyperlink {
+                out.push_str(HYPERLINK_END);
+            }
             return out.into();
         }
         out.push(ch);
         width += ch_width;
+        rest = &rest[ch.len_utf8()..];
     }
 
     out.into()
 }
 
+/// Truncate plain `text` to fit within `max_width` display columns by replacing
+/// its middle with `…`, keeping the start and the end visible.
+///
+/// This is useful for names whose suffix carries meaning, like ticket numbers in
+/// branch names. Unlike [`truncate_text`], `text` must not contain ANSI escape sequences.
+pub fn truncate_text_middle(text: &str, max_width: usize) -> Cow<'_, str> {
+    if text.width() <= max_width {
+        return Cow::Borrowed(text);
+    }
+    if max_width <= 1 {
+        return truncate_text(text, max_width);
+    }
+
+    // Reserve one display column for the ellipsis, and favor the end if the rest is odd.
+    let target_width = max_width - 1;
+    let tail_budget = target_width.div_ceil(2);
+    let head_budget = target_width - tail_budget;
+
+    let mut tail = Vec::new();
+    let mut tail_width = 0;
+    for ch in text.chars().rev() {
+        let ch_width = ch.width().unwrap_or(0);
+        if tail_width + ch_width > tail_budget {
+            break;
+        }
+        tail.push(ch);
+        tail_width += ch_width;
+    }
+
+    let mut out = String::new();
+    let mut head_width = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if head_width + ch_width > head_budget {
+            break;
+        }
+        out.push(ch);
+        head_width += ch_width;
+    }
+    out.push('…');
+    out.extend(tail.into_iter().rev());
+    out.into()
+}
+
 /// Remove all ANSI escape sequences from `s`, returning plaintext.
 ///
 /// Useful when you need to measure the *display* width of a string
 /// that may contain color / style codes.
 pub fn strip_ansi_codes(s: &str) -> Cow<'_, str> {
-    let mut out = Cow::Borrowed(s);
-    let mut needs_mutation = false;
-    let mut in_escape = false;
+    if !s.contains('\x1b') {
+        return Cow::Borrowed(s);
+    }
 
-    for (idx, ch) in s.char_indices() {
-        if ch == '\x1b' {
-            if !needs_mutation {
-                out.to_mut().truncate(idx);
-                needs_mutation = true;
-            }
-            in_escape = true;
-            continue;
-        }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('\x1b') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        rest = &rest[ansi_escape_len(rest)..];
+    }
+    out.push_str(rest);
+    out.into()
+}
 
-        if in_escape {
-            if ch == 'm' {
-                in_escape = false;
-            }
-            continue;
-        }
+/// The start of an OSC 8 hyperlink, followed by the parameters, `;`, the URL and the string terminator.
+const HYPERLINK_START: &str = "\x1b]8;";
+/// The OSC 8 sequence that ends a hyperlink.
+const HYPERLINK_END: &str = "\x1b]8;;\x1b\\";
 
-        if needs_mutation {
-            out.to_mut().push(ch);
-        }
-    }
+/// Make `text` a terminal hyperlink to `url` with an OSC 8 escape sequence.
+///
+/// Terminals without support for it show `text` as is.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("{HYPERLINK_START};{url}\x1b\\{text}{HYPERLINK_END}")
+}
 
-    out
+/// Return the length in bytes of the escape sequence `s` starts with, which must be `ESC`.
+///
+/// OSC sequences like hyperlinks end with `BEL` or `ESC \`, all others are assumed to be
+/// SGR sequences like color codes ending with `m`. Unterminated sequences extend to the end of `s`.
+fn ansi_escape_len(s: &str) -> usize {
+    debug_assert!(s.starts_with('\x1b'));
+    if let Some(osc) = s.strip_prefix("\x1b]") {
+        let len = match osc.find(['\x07', '\x1b']) {
+            Some(pos) if osc[pos..].starts_with("\x1b\\") => pos + 2,
+            Some(pos) => pos + 1,
+            None => osc.len(),
+        };
+        "\x1b]".len() + len
+    } else {
+        s.find('m').map_or(s.len(), |pos| pos + 1)
+    }
 }
 
 #[cfg(test)]
@@ -259,6 +358,56 @@ mod truncate_text_tests {
                 This also means we will remove relevant ansi codes."
             );
     }
+
+    #[test]
+    fn hyperlinks_are_truncated_without_counting_the_url() {
+        let link = super::hyperlink("#1234", "https://example.com/pull/1234");
+        assert_eq!(
+            truncate_text(link.as_str(), 5),
+            link,
+            "only the text counts"
+        );
+        assert_eq!(
+            truncate_text(format!("see {link}"), 6),
+            "see \x1b]8;;https://example.com/pull/1234\x1b\\#…\x1b]8;;\x1b\\",
+            "a hyperlink cut short is still ended"
+        );
+    }
+}
+
+#[cfg(test)]
+mod truncate_text_middle_tests {
+    use unicode_width::UnicodeWidthStr;
+
+    use super::truncate_text_middle;
+
+    #[test]
+    fn text_within_limit_is_not_truncated() {
+        assert_eq!(truncate_text_middle("feat/ABC-123", 12), "feat/ABC-123");
+    }
+
+    #[test]
+    fn text_exceeding_limit_keeps_start_and_end() {
+        assert_eq!(
+            truncate_text_middle("feature/some-long-description-ABC-123", 16),
+            "feature…-ABC-123",
+            "the suffix with the ticket number stays visible"
+        );
+    }
+
+    #[test]
+    fn small_limits_degrade_gracefully() {
+        assert_eq!(truncate_text_middle("hello", 0), "");
+        assert_eq!(truncate_text_middle("hello", 1), "…");
+        assert_eq!(truncate_text_middle("hello", 2), "…o");
+    }
+
+    #[test]
+    fn cjk_does_not_exceed_max_width() {
+        let result = truncate_text_middle("你好世界你好", 6);
+        assert_eq!(result, "你…好", "你(2) + …(1) + 好(2) = 5 cols ≤ 6");
+        assert!(result.width() <= 6);
+    }
 }
 
 #[cfg(test)]
@@ -286,4 +435,62 @@ mod strip_ansi_codes_tests {
         let colored = "\x1b[31mhello\x1b[0m world";
         assert_eq!(strip_ansi_codes(colored), "hello world");
     }
+
+    #[test]
+    fn strip_ansi_removes_hyperlinks_but_keeps_their_text() {
+        let link = super::hyperlink("#1234", "https://example.com/pull/1234");
+        assert_eq!(
+            strip_ansi_codes(&format!("(\x1b[34m{link}\x1b[0m)")),
+            "(#1234)"
+        );
+        assert_eq!(
+            strip_ansi_codes("\x1b]8;;https://example.com\x07text\x1b]8;;\x07"),
+            "text",
+            "BEL terminates OSC sequences as well"
+        );
+    }
+}
+
+#[cfg(test)]
+mod supports_hyperlinks_tests {
+    use super::supports_hyperlinks_with;
+
+    fn supports_hyperlinks(vars: &[(&str, &str)]) -> bool {
+        supports_hyperlinks_with(|key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn unknown_terminals_are_assumed_to_not_support_hyperlinks() {
+        assert!(!supports_hyperlinks(&[]));
+        assert!(!supports_hyperlinks(&[("TERM", "xterm-256color")]));
+        assert!(!supports_hyperlinks(&[("VTE_VERSION", "4800")]));
+    }
+
+    #[test]
+    fn known_terminals_support_hyperlinks() {
+        assert!(supports_hyperlinks(&[("TERM_PROGRAM", "iTerm.app")]));
+        assert!(supports_hyperlinks(&[("TERM", "xterm-kitty")]));
+        assert!(supports_hyperlinks(&[("VTE_VERSION", "6003")]));
+        assert!(supports_hyperlinks(&[("WT_SESSION", "id")]));
+    }
+
+    #[test]
+    fn ci_and_force_override_detection() {
+        assert!(!supports_hyperlinks(&[
+            ("CI", "true"),
+            ("WT_SESSION", "id")
+        ]));
+        assert!(supports_hyperlinks(&[
+            ("CI", "true"),
+            ("FORCE_HYPERLINK", "1")
+        ]));
+        assert!(!supports_hyperlinks(&[
+            ("FORCE_HYPERLINK", "0"),
+            ("TERM", "xterm-kitty")
+        ]));
+    }
 }
@@ -0,0 +1,128 @@
+//! Binary files can't be shown as text, so they are described by how their size changed instead,
+//! and text that is shown is kept from affecting the terminal it's printed to.
+
+use std::borrow::Cow;
+
+use bstr::ByteSlice as _;
+use but_core::{ChangeState, TreeChange, TreeStatus, UnifiedPatch};
+
+/// Describe `change` like `binary file changed (12.4 KiB -> 13.1 KiB)` if its `patch` is binary, or
+/// return `None` if it has a textual diff.
+///
+/// Files are binary if Git would consider them binary, i.e. if they contain NUL bytes or
+/// are marked as such with the `binary` or `-diff` attributes.
+pub(crate) fn describe_binary_change(
+    repo: &gix::Repository,
+    change: &TreeChange,
+    patch: Option<&UnifiedPatch>,
+) -> anyhow::Result<Option<String>> {
+    if !matches!(patch, Some(UnifiedPatch::Binary)) {
+        return Ok(None);
+    }
+    let size = |state: &ChangeState| state_size(repo, change, state);
+    Ok(Some(match &change.status {
+        TreeStatus::Addition { state, .. } => {
+            format!("binary file added ({})", format_size(size(state)?))
+        }
+        TreeStatus::Deletion { previous_state } => {
+            format!(
+                "binary file deleted ({})",
+                format_size(size(previous_state)?)
+            )
+        }
+        TreeStatus::Modification {
+            previous_state,
+            state,
+            ..
+        }
+        | TreeStatus::Rename {
+            previous_state,
+            state,
+            ..
+        } => format!(
+            "binary file changed ({} -> {})",
+            format_size(size(previous_state)?),
+            format_size(size(state)?)
+        ),
+    }))
+}
+
+/// The size of the file of `change` in `state`, which is read from the worktree if it wasn't
+/// hashed yet.
+pub(crate) fn state_size(
+    repo: &gix::Repository,
+    change: &TreeChange,
+    state: &ChangeState,
+) -> anyhow::Result<u64> {
+    if !state.id.is_null() {
+        return Ok(repo.find_header(state.id)?.size());
+    }
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("Worktree changes need a worktree"))?;
+    let path = workdir.join(gix::path::from_bstr(change.path.as_bstr()));
+    Ok(std::fs::symlink_metadata(path)?.len())
+}
+
+/// Format `bytes` for humans, like `512 B` or `12.4 KiB`.
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// Replace control characters in `text` other than tabs with their caret notation, like `^[`
+/// for escape, so text converted from binary files or files that only look like text can't
+/// change the state of the terminal.
+pub(crate) fn sanitize_for_terminal(text: &str) -> Cow<'_, str> {
+    let is_unsafe = |c: char| c.is_control() && c != '\t';
+    if !text.contains(is_unsafe) {
+        return Cow::Borrowed(text);
+    }
+    let mut sanitized = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\u{7f}' => sanitized.push_str("^?"),
+            c if is_unsafe(c) && (c as u32) < 0x20 => {
+                sanitized.push('^');
+                sanitized.push(char::from(c as u8 ^ 0x40));
+            }
+            c if is_unsafe(c) => sanitized.push(char::REPLACEMENT_CHARACTER),
+            c => sanitized.push(c),
+        }
+    }
+    Cow::Owned(sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, sanitize_for_terminal};
+
+    #[test]
+    fn sizes_are_formatted_with_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(12_698), "12.4 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn control_characters_are_made_visible() {
+        assert_eq!(sanitize_for_terminal("plain\ttext"), "plain\ttext");
+        assert_eq!(sanitize_for_terminal("\u{1b}[31mred"), "^[[31mred");
+        assert_eq!(sanitize_for_terminal("a\rb\u{7f}"), "a^Mb^?");
+        assert_eq!(sanitize_for_terminal("c\u{9b}d"), "c\u{fffd}d");
+    }
+}
@@ -6,11 +6,28 @@ pub const BUT_OUTPUT_FORMAT: &str = "BUT_OUTPUT_FORMAT";
 pub const BUT_PAGER: &str = "BUT_PAGER";
 pub const BUT_PAGER_DESCRIPTION: &str = "Sets the pager for large outputs. [default: less]";
 
+/// The pager git uses if `core.pager` isn't set, which is respected as well.
+pub const PAGER: &str = "PAGER";
+
+/// The pager git uses over `core.pager` and [`PAGER`], which is respected as well.
+pub const GIT_PAGER: &str = "GIT_PAGER";
+
 pub const BUT_THEME: &str = "BUT_THEME";
 pub const BUT_THEME_DESCRIPTION: &str =
     "Sets the theme for but. Options: dark, light. [default: dark]";
 
-pub const ALL_ENVS: [(&str, &str); 2] = [
+pub const BUT_OFFLINE: &str = "BUT_OFFLINE";
+pub const BUT_OFFLINE_DESCRIPTION: &str =
+    "Set to 1 to not access the network, using cached forge data.";
+
+pub const ALL_ENVS: [(&str, &str); 3] = [
     (BUT_PAGER, BUT_PAGER_DESCRIPTION),
     (BUT_THEME, BUT_THEME_DESCRIPTION),
+    (BUT_OFFLINE, BUT_OFFLINE_DESCRIPTION),
 ];
+
+/// Return `true` if [`BUT_OFFLINE`] is set to anything but `0` or the empty string, so no
+/// background sync should be started and forge data should be read from the cache.
+pub fn is_offline() -> bool {
+    std::env::var_os(BUT_OFFLINE).is_some_and(|value| !value.is_empty() && value != "0")
+}
@@ -0,0 +1,74 @@
+//! Large files are easy to commit by accident and hard to remove once pushed, so they are found
+//! by their size before committing, without reading their content.
+
+use bstr::BString;
+use but_core::{TreeChange, TreeStatus};
+use gix::object::tree::EntryKind;
+
+use crate::utils::binary_files::state_size;
+
+/// The git config key for the size in bytes above which files are large, or `0` to allow files
+/// of any size.
+pub(crate) const LARGE_FILE_THRESHOLD_KEY: &str = "but.commit.largeFileThreshold";
+const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 50 * 1024 * 1024;
+
+/// A file about to be committed that is larger than the threshold.
+#[derive(Debug)]
+pub(crate) struct LargeFile {
+    pub path: BString,
+    pub size: u64,
+}
+
+/// Return the size in bytes above which files are large, or `None` if files of any size are fine.
+/// Sizes may use suffixes like `k`, `m` or `g`, as other integers in the git config.
+pub(crate) fn large_file_threshold(repo: &gix::Repository) -> Option<u64> {
+    let threshold = repo
+        .config_snapshot()
+        .integer(LARGE_FILE_THRESHOLD_KEY)
+        .and_then(|threshold| u64::try_from(threshold).ok())
+        .unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD);
+    (threshold != 0).then_some(threshold)
+}
+
+/// Return the files in `changes` whose new version is larger than `threshold`, sorted by path.
+/// Deleted files are never large.
+pub(crate) fn large_files(
+    repo: &gix::Repository,
+    changes: &[TreeChange],
+    threshold: u64,
+) -> anyhow::Result<Vec<LargeFile>> {
+    let mut large = Vec::new();
+    for change in changes {
+        let state = match &change.status {
+            TreeStatus::Deletion { .. } => continue,
+            TreeStatus::Addition { state, .. }
+            | TreeStatus::Modification { state, .. }
+            | TreeStatus::Rename { state, .. } => state,
+        };
+        if matches!(state.kind, EntryKind::Commit | EntryKind::Tree) {
+            continue;
+        }
+        let size = state_size(repo, change, state)?;
+        if size > threshold {
+            large.push(LargeFile {
+                path: change.path.clone(),
+                size,
+            });
+        }
+    }
+    large.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(large)
+}
+
+/// Return `true` if the `.gitattributes` file at the root of the worktree of `repo` configures
+/// Git LFS for some paths.
+pub(crate) fn uses_lfs(repo: &gix::Repository) -> bool {
+    repo.workdir()
+        .and_then(|workdir| std::fs::read_to_string(workdir.join(".gitattributes")).ok())
+        .is_some_and(|attributes| {
+            attributes
+                .lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .any(|line| line.split_whitespace().any(|attr| attr == "filter=lfs"))
+        })
+}
@@ -113,6 +113,16 @@ impl Subcommands {
             #[cfg(feature = "legacy")]
             Subcommands::Rub { .. } => Rub,
             #[cfg(feature = "legacy")]
+            Subcommands::Assign { .. } => Assign,
+            #[cfg(feature = "legacy")]
+            Subcommands::Unassign { .. } => Unassign,
+            #[cfg(feature = "legacy")]
+            Subcommands::Rule(crate::args::rule::Platform { cmd }) => match cmd {
+                None | Some(crate::args::rule::Subcommands::List) => RuleList,
+                Some(crate::args::rule::Subcommands::Add { .. }) => RuleAdd,
+                Some(crate::args::rule::Subcommands::Remove { .. }) => RuleRemove,
+            },
+            #[cfg(feature = "legacy")]
             Subcommands::Diff { .. } => Diff,
             #[cfg(feature = "legacy")]
             Subcommands::_Diff2(..) => Diff2,
@@ -122,6 +132,11 @@ impl Subcommands {
             Subcommands::Pull { .. } => Pull,
             #[cfg(feature = "legacy")]
             Subcommands::Fetch => Pull,
+            #[cfg(feature = "legacy")]
+            Subcommands::Stack(crate::args::stack::Platform { cmd }) => match cmd {
+                None | Some(crate::args::stack::Subcommands::List { .. }) => StackList,
+                Some(crate::args::stack::Subcommands::Merge { .. }) => StackMerge,
+            },
             Subcommands::Branch(branch::Platform { cmd }) => match cmd {
                 None => BranchList,
                 #[cfg(feature = "legacy")]
@@ -132,10 +147,15 @@ impl Subcommands {
                 Some(branch::Subcommands::Delete { .. }) => BranchDelete,
                 #[cfg(feature = "legacy")]
                 Some(branch::Subcommands::Show { .. }) => BranchShow,
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::SetDescription { .. }) => BranchSetDescription,
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::Describe { .. }) => BranchDescribe,
                 Some(branch::Subcommands::Update { .. }) => BranchUpdate,
                 Some(branch::Subcommands::Move { .. }) => BranchMove,
-                #[cfg(not(feature = "legacy"))]
                 Some(branch::Subcommands::Apply { .. }) => BranchApply,
+                #[cfg(feature = "legacy")]
+                Some(branch::Subcommands::Unapply { .. }) => BranchUnapply,
             },
             #[cfg(feature = "legacy")]
             Subcommands::Unapply { .. } => BranchUnapply,
@@ -176,6 +196,7 @@ impl Subcommands {
             Subcommands::Pr(forge::pr::Platform { cmd, .. }) => match cmd {
                 None | Some(forge::pr::Subcommands::New { .. }) => PrNew,
                 Some(forge::pr::Subcommands::Template { .. }) => PrTemplate,
+                Some(forge::pr::Subcommands::Open { .. }) => PrOpen,
                 Some(forge::pr::Subcommands::AutoMerge { off, .. }) => {
                     if *off {
                         DisableAutoMerge
@@ -188,9 +209,11 @@ impl Subcommands {
             },
             #[cfg(feature = "legacy")]
             Subcommands::Actions(_)
+            | Subcommands::Claude(_)
             | Subcommands::Mcp
             | Subcommands::Setup { .. }
-            | Subcommands::Teardown { .. } => Unknown,
+            | Subcommands::Teardown { .. }
+            | Subcommands::Deinit { .. } => Unknown,
             Subcommands::Config(config::Platform { cmd }) => match cmd {
                 Some(config::Subcommands::Forge {
                     cmd: Some(config::ForgeSubcommand::Auth),
@@ -205,7 +228,7 @@ impl Subcommands {
             },
             Subcommands::Completions { .. } => Completions,
             Subcommands::Help { .. } => Unknown,
-            Subcommands::_Expand { .. } => Unknown,
+            Subcommands::_Expand { .. } | Subcommands::_Complete { .. } => Unknown,
             Subcommands::Alias(alias_args::Platform { cmd }) => match cmd {
                 None | Some(alias_args::Subcommands::List) => AliasCheck,
                 Some(alias_args::Subcommands::Add { .. }) => AliasAdd,
@@ -245,7 +268,9 @@ impl Subcommands {
             Subcommands::Agent(agent::Platform { cmd }) => match cmd {
                 None | Some(agent::Subcommands::Setup { .. }) => AgentSetup,
             },
-            Subcommands::Edit { .. } => Edit,
+            Subcommands::_EditFile { .. } => Edit,
+            #[cfg(feature = "legacy")]
+            Subcommands::Edit { .. } => EditCommit,
             #[cfg(feature = "legacy")]
             Subcommands::Clean { .. } => Clean,
             Subcommands::Onboarding => Unknown,
@@ -505,7 +530,7 @@ pub fn add_workspace_shape(event: &mut Event, current_dir: &Path) {
     };
     // Only inspect repositories that already carry GitButler project state; capturing an
     // event must never be what initializes a project.
-    if !but_db::DbHandle::db_file_path(ctx.project_data_dir()).exists() {
+    if !but_db::DbHandle::db_file_path(ctx.shared_project_data_dir()).exists() {
         return;
     }
     let _guard = ctx.shared_worktree_access();
@@ -545,8 +570,8 @@ pub fn add_workspace_shape(event: &mut Event, current_dir: &Path) {
 fn read_only_workspace(ctx: &but_ctx::Context) -> Option<but_graph::Workspace> {
     let repo = ctx.repo.get().ok()?;
     let meta = but_meta::BranchOrderMetadata::from_paths_read_only(
-        ctx.project_data_dir().join("virtual_branches.toml"),
-        ctx.project_data_dir(),
+        ctx.shared_project_data_dir().join("virtual_branches.toml"),
+        ctx.shared_project_data_dir(),
     )
     .ok()?;
     let graph = but_graph::Graph::from_head(
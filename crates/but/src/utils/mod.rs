@@ -4,7 +4,7 @@ mod output_channel;
 pub(crate) use output_channel::PromptLine;
 pub use output_channel::{
     CliOutput, CliOutputHuman, Confirm, ConfirmDefault, ConfirmOrEmpty, InputOutputChannel,
-    IntermediateChannel, OutputChannel, WriteWithUtils,
+    IntermediateChannel, OutputChannel, ProgressChannel, WriteWithUtils,
 };
 
 mod object_id;
@@ -25,8 +25,17 @@ pub use metrics::types::OneshotMetricsContext;
 pub mod detect_agent;
 pub mod time;
 
+#[cfg(feature = "legacy")]
+pub(crate) mod binary_files;
 pub(crate) mod binary_path;
 pub(crate) mod diff_specs;
+#[cfg(feature = "legacy")]
+pub(crate) mod large_files;
+#[cfg(feature = "legacy")]
+pub(crate) mod notifications;
+pub(crate) mod quoted_path;
+#[cfg(feature = "legacy")]
+pub(crate) mod submodules;
 pub(crate) mod targeting;
 
 pub mod diff_rendering;
@@ -40,15 +49,36 @@ pub mod envs;
 
 impl ResultErrorExt for anyhow::Result<()> {
     fn show_root_cause_error_then_exit_without_destructors(self, out: OutputChannel) -> ! {
+        self.map_err(crate::CliError::Internal)
+            .show_root_cause_error_then_exit_without_destructors(out)
+    }
+}
+
+impl ResultErrorExt for crate::CliResult<()> {
+    fn show_root_cause_error_then_exit_without_destructors(self, out: OutputChannel) -> ! {
+        let json_errors = out.is_json();
         // Trigger the pager to be flushed before exiting early, or destructors aren't called.
         drop(out);
-        let code = if let Err(e) = &self {
-            writeln!(std::io::stderr(), "{} {}", e, e.root_cause()).ok();
-            1
-        } else {
-            0
+        let Err(err) = self else {
+            std::process::exit(0);
         };
-        std::process::exit(code);
+        // Like in the main error path, JSON consumers get the error on stdout, while it's still
+        // shown on stderr as well.
+        if json_errors {
+            err.write_json_to_stdout().ok();
+        }
+        if let crate::CliError::Internal(e) = &err
+            && let Some((report, exit_code)) = crate::error::api_error_report(e)
+        {
+            write!(std::io::stderr(), "{report}").ok();
+            std::process::exit(exit_code);
+        }
+        match &err {
+            crate::CliError::Internal(e) => writeln!(std::io::stderr(), "{} {}", e, e.root_cause()),
+            err => write!(std::io::stderr(), "{err}"),
+        }
+        .ok();
+        std::process::exit(1);
     }
 }
 
@@ -62,7 +92,7 @@ pub trait ResultMetricsExt<T, E> {
     fn emit_metrics(self, ctx: Option<OneshotMetricsContext>) -> Result<T, E>;
 }
 
-fn json_pretty_to_stdout(value: &impl serde::Serialize) -> std::io::Result<()> {
+pub(crate) fn json_pretty_to_stdout(value: &impl serde::Serialize) -> std::io::Result<()> {
     let stdout = std::io::stdout();
     let mut stdout = stdout.lock();
     let value = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
@@ -0,0 +1,202 @@
+//! Desktop notifications about operations that took long enough for users to turn to something
+//! else in the meantime.
+//!
+//! They are shown if `cli.notify_on_slow_operations` is enabled, unless overridden with `--notify`
+//! or `--no-notify`, and never where there is no desktop to show them on, like in CI.
+
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::args::Args;
+
+/// Operations finishing sooner than this don't notify, as users are likely still watching them.
+pub(crate) const SLOW_OPERATION_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Something that can show notifications.
+pub(crate) trait Notifier {
+    /// Show a notification titled `summary` with `body` as details.
+    fn notify(&self, summary: &str, body: &str) -> anyhow::Result<()>;
+}
+
+/// Shows notifications on the desktop, or does nothing if there is none.
+pub(crate) struct Desktop;
+
+impl Notifier for Desktop {
+    fn notify(&self, summary: &str, body: &str) -> anyhow::Result<()> {
+        if !is_graphical_environment() {
+            return Ok(());
+        }
+        notify_rust::Notification::new()
+            .appname("GitButler")
+            .summary(summary)
+            .body(body)
+            .show()?;
+        Ok(())
+    }
+}
+
+/// Return `true` if there is a desktop to show notifications on, which isn't the case in CI or
+/// on Unix systems without a display server.
+fn is_graphical_environment() -> bool {
+    if std::env::var_os("CI").is_some() {
+        return false;
+    }
+    if cfg!(any(target_os = "macos", windows)) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .into_iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// Show `summary` and `body` on the desktop, only logging if that isn't possible.
+pub(crate) fn show(summary: &str, body: &str) {
+    notify_or_log(&Desktop, summary, body);
+}
+
+fn notify_or_log(notifier: &dyn Notifier, summary: &str, body: &str) {
+    if let Err(err) = notifier.notify(summary, body) {
+        tracing::warn!(%summary, error = %err, "Failed to show desktop notification");
+    }
+}
+
+/// Return `true` if an operation should notify once it finishes, as forced with `--notify` or
+/// `--no-notify` in `args`, or if it's `configured` to otherwise.
+pub(crate) fn enabled(args: &Args, configured: bool) -> bool {
+    if args.no_notify {
+        false
+    } else {
+        args.notify || configured
+    }
+}
+
+/// An operation that notifies how it ended if it took longer than [`SLOW_OPERATION_THRESHOLD`].
+#[must_use = "call `finish()` once the operation is done"]
+pub(crate) struct SlowOperation {
+    name: &'static str,
+    started: Instant,
+    enabled: bool,
+}
+
+impl SlowOperation {
+    /// Start timing the operation called `name`, like `Push`, which only notifies if `enabled`.
+    pub(crate) fn start(name: &'static str, enabled: bool) -> Self {
+        SlowOperation {
+            name,
+            started: Instant::now(),
+            enabled,
+        }
+    }
+
+    /// Notify on the desktop that the operation on the subject produced by `subject`, like a branch
+    /// and its commit, ended with `result`, if it was slow.
+    /// `subject` is only called if there is a notification to show.
+    pub(crate) fn finish<T, E: Display>(
+        self,
+        subject: impl FnOnce() -> String,
+        result: &Result<T, E>,
+    ) {
+        self.finish_with(&Desktop, SLOW_OPERATION_THRESHOLD, subject, result);
+    }
+
+    fn finish_with<T, E: Display>(
+        self,
+        notifier: &dyn Notifier,
+        threshold: Duration,
+        subject: impl FnOnce() -> String,
+        result: &Result<T, E>,
+    ) {
+        let elapsed = self.started.elapsed();
+        if !self.enabled || elapsed < threshold {
+            return;
+        }
+        let subject = subject();
+        let (summary, body) = match result {
+            Ok(_) => (
+                format!("{} finished", self.name),
+                format!("{subject} after {}s", elapsed.as_secs()),
+            ),
+            Err(err) => (
+                format!("{} failed", self.name),
+                format!("{subject}: {err:#}"),
+            ),
+        };
+        notify_or_log(notifier, &summary, &body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        time::{Duration, Instant},
+    };
+
+    use super::{Notifier, SlowOperation};
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<(String, String)>>);
+
+    impl Notifier for Recorder {
+        fn notify(&self, summary: &str, body: &str) -> anyhow::Result<()> {
+            self.0
+                .borrow_mut()
+                .push((summary.to_owned(), body.to_owned()));
+            Ok(())
+        }
+    }
+
+    fn operation_that_took(duration: Duration, enabled: bool) -> SlowOperation {
+        SlowOperation {
+            name: "Push",
+            started: Instant::now() - duration,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn slow_operations_notify_how_they_ended() {
+        let threshold = Duration::from_secs(10);
+        let notifier = Recorder::default();
+        operation_that_took(Duration::from_secs(12), true).finish_with(
+            &notifier,
+            threshold,
+            || "branch 'A' at 1234567".to_owned(),
+            &Ok::<_, anyhow::Error>(()),
+        );
+        operation_that_took(Duration::from_secs(30), true).finish_with(
+            &notifier,
+            threshold,
+            || "branch 'A'".to_owned(),
+            &Err::<(), _>(anyhow::anyhow!("rejected").context("push failed")),
+        );
+        assert_eq!(
+            notifier.0.into_inner(),
+            [
+                (
+                    "Push finished".to_owned(),
+                    "branch 'A' at 1234567 after 12s".to_owned()
+                ),
+                (
+                    "Push failed".to_owned(),
+                    "branch 'A': push failed: rejected".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fast_or_disabled_operations_do_not_notify() {
+        let threshold = Duration::from_secs(10);
+        let notifier = Recorder::default();
+        let ok = Ok::<_, anyhow::Error>(());
+        let subject = || -> String { unreachable!("the subject is only needed to notify") };
+        operation_that_took(Duration::from_secs(1), true)
+            .finish_with(&notifier, threshold, subject, &ok);
+        operation_that_took(Duration::from_secs(60), false)
+            .finish_with(&notifier, threshold, subject, &ok);
+        assert!(notifier.0.into_inner().is_empty());
+    }
+}
@@ -47,6 +47,8 @@ pub struct OutputChannel {
     /// When `Some`, JSON values written via `write_value` are captured here instead of going to stdout.
     /// Used to buffer mutation JSON before combining with status JSON.
     json_buffer: Option<serde_json::Value>,
+    /// If `true`, requests for a pager are ignored.
+    pager_disabled: bool,
 }
 
 /// A channel that implements [`std::io::Write`], to make unbuffered writes to [`std::io::stderr`]
@@ -657,20 +659,41 @@ impl OutputChannel {
             stdout: std::io::stdout(),
             pager: None,
             json_buffer: None,
+            pager_disabled: false,
         }
     }
 
+    /// Ignore all future requests for a pager, as with `--no-pager`.
+    pub fn disable_pager(&mut self) {
+        self.pager_disabled = true;
+    }
+
     /// Request paging for large output. The pager is only started when human UI is allowed,
-    /// stdout is a terminal, and paging is not disabled by the environment.
+    /// stdout is a terminal, and paging is not disabled with `--no-pager` or by the environment.
     pub fn request_pager(&mut self) {
+        self.request_pager_with_core_pager(None);
+    }
+
+    /// Like [`Self::request_pager()`], but use the pager configured for git in `repo`
+    /// unless `BUT_PAGER` is set.
+    pub fn request_git_pager(&mut self, repo: &gix::Repository) {
+        let core_pager = repo
+            .config_snapshot()
+            .string("core.pager")
+            .map(|pager| pager.to_string());
+        self.request_pager_with_core_pager(core_pager.as_deref());
+    }
+
+    fn request_pager_with_core_pager(&mut self, core_pager: Option<&str>) {
         if self.pager.is_some()
+            || self.pager_disabled
             || !self.format.allows_human_ui()
             || std::env::var_os("NOPAGER").is_some()
             || !self.stdout.is_terminal()
         {
             return;
         }
-        self.pager = pager::try_init_pager();
+        self.pager = pager::try_init_pager(core_pager);
     }
 
     /// Get the output format setting.
@@ -775,6 +798,25 @@ impl OutputChannel {
         }
     }
 
+    /// Write `bytes` verbatim if the format is text-based, for output that isn't necessarily
+    /// valid UTF-8 like paths in machine-readable formats.
+    ///
+    /// The builtin pager can only display text, so it receives them lossily decoded.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+        if !self.format.is_text() {
+            return Ok(());
+        }
+        match self.pager.as_mut() {
+            Some(Pager::Builtin(pager)) => {
+                std::fmt::Write::write_str(pager, &String::from_utf8_lossy(bytes))
+                    .map_err(std::io::Error::other)
+            }
+            Some(Pager::External(_, stdin)) => stdin.write_all(bytes).or_else(ignore_broken_pipe),
+            None => self.stdout.write_all(bytes).or_else(ignore_broken_pipe),
+        }
+    }
+
     /// Start buffering JSON output instead of writing to stdout.
     pub fn start_json_buffering(&mut self) {
         self.json_buffer = Some(serde_json::Value::Null);
@@ -31,8 +31,13 @@ const LESS_ANSI_END_CHARS_ENV_VAR: &str = "LESSANSIENDCHARS";
 /// case for initializing a pager more than once per CLI invocation, but if that need arises
 /// this implementation needs to be extended to account for the fact that there may already be
 /// a pager running that needs to be dropped before a new one is initialized.
-pub(crate) fn try_init_pager() -> Option<Pager> {
-    if let Some((child, stdin)) = try_spawn_external_pager() {
+///
+/// `core_pager` is the value of `core.pager` in the git configuration, if known. Like git, it's used
+/// over `PAGER` but not over `GIT_PAGER`, and paging is disabled if the configured pager is empty
+/// or `cat`.
+pub(crate) fn try_init_pager(core_pager: Option<&str>) -> Option<Pager> {
+    let git_pager = git_pager(core_pager, |name| std::env::var(name).ok())?;
+    if let Some((child, stdin)) = try_spawn_external_pager(git_pager.as_deref()) {
         Some(Pager::External(child, stdin))
     } else {
         match try_spawn_builtin_pager() {
@@ -48,12 +53,36 @@ pub(crate) fn try_init_pager() -> Option<Pager> {
     }
 }
 
-fn try_spawn_external_pager() -> Option<(std::process::Child, std::process::ChildStdin)> {
+/// Return the pager command configured for git, using `var` to read environment variables, or `None`
+/// if paging is disabled. `Some(None)` means no pager is configured.
+fn git_pager(
+    core_pager: Option<&str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Option<Option<String>> {
+    let git_pager = var(envs::GIT_PAGER)
+        .or_else(|| core_pager.map(ToOwned::to_owned))
+        .or_else(|| var(envs::PAGER));
+    if git_pager
+        .as_deref()
+        .is_some_and(|pager| matches!(pager.trim(), "" | "cat"))
+    {
+        return None;
+    }
+    Some(git_pager)
+}
+
+fn try_spawn_external_pager(
+    git_pager: Option<&str>,
+) -> Option<(std::process::Child, std::process::ChildStdin)> {
     use std::process::{Command, Stdio};
 
     let pager_override = std::env::var(envs::BUT_PAGER).ok();
-    let (program, is_default) = if let Some(cmd) = &pager_override {
-        (cmd.as_ref(), false)
+    // The git pager is a shell command, possibly with arguments.
+    let git_pager = git_pager.and_then(|cmd| shell_words::split(cmd).ok());
+    let (program, args, is_default) = if let Some(cmd) = &pager_override {
+        (cmd.as_ref(), &[][..], false)
+    } else if let Some((program, args)) = git_pager.as_deref().and_then(|cmd| cmd.split_first()) {
+        (program.as_str(), args, false)
     } else if Command::new(DEFAULT_PAGER)
         .arg("--version")
         .stdout(Stdio::null())
@@ -61,13 +90,13 @@ fn try_spawn_external_pager() -> Option<(std::process::Child, std::process::Chil
         .status()
         .is_ok()
     {
-        (DEFAULT_PAGER, true)
+        (DEFAULT_PAGER, &[][..], true)
     } else {
         return None;
     };
 
     let mut cmd = Command::new(program);
-    cmd.stdin(Stdio::piped());
+    cmd.args(args).stdin(Stdio::piped());
 
     if is_default {
         // If we use the default pager, we're going to forcefully set our intended default
@@ -106,3 +135,50 @@ fn try_spawn_builtin_pager() -> Result<minus::Pager, minus::MinusError> {
     pager.set_prompt("GitButler")?;
     Ok(pager)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::git_pager;
+    use crate::utils::envs;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn git_pager_takes_precedence_over_core_pager_and_pager() {
+        let vars = [(envs::GIT_PAGER, "git-pager"), (envs::PAGER, "pager")];
+        assert_eq!(
+            git_pager(Some("core-pager"), env(&vars)),
+            Some(Some("git-pager".into()))
+        );
+        assert_eq!(
+            git_pager(Some("core-pager"), env(&vars[1..])),
+            Some(Some("core-pager".into())),
+            "core.pager is used over PAGER"
+        );
+        assert_eq!(git_pager(None, env(&vars[1..])), Some(Some("pager".into())));
+        assert_eq!(git_pager(None, env(&[])), Some(None), "nothing configured");
+    }
+
+    #[test]
+    fn empty_or_cat_pager_disables_paging() {
+        for disabled in ["", " ", "cat"] {
+            assert_eq!(
+                git_pager(Some("core-pager"), env(&[(envs::GIT_PAGER, disabled)])),
+                None,
+                "GIT_PAGER={disabled:?}"
+            );
+            assert_eq!(git_pager(Some(disabled), env(&[])), None);
+        }
+        assert_eq!(
+            git_pager(Some("cat"), env(&[(envs::GIT_PAGER, "git-pager")])),
+            Some(Some("git-pager".into())),
+            "only the pager that is used counts"
+        );
+    }
+}
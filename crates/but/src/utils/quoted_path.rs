@@ -0,0 +1,166 @@
+//! Paths are bytes which don't have to be valid UTF-8, so they are shown quoted like Git does
+//! to be unambiguous and to round-trip when they are passed back to `but`.
+
+use std::borrow::Cow;
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// Return `true` if paths with bytes outside of ASCII should be quoted, as configured with
+/// `core.quotePath` in `repo`, which is the default.
+pub(crate) fn quote_path_enabled(repo: &gix::Repository) -> bool {
+    repo.config_snapshot()
+        .boolean("core.quotePath")
+        .unwrap_or(true)
+}
+
+/// Quote `path` the way Git does if it contains double quotes, backslashes, control characters
+/// or, if `quote_non_ascii` is set, bytes outside of ASCII. Otherwise return it unchanged.
+pub(crate) fn quote_path(path: &BStr, quote_non_ascii: bool) -> BString {
+    let needs_quoting = |byte: u8| {
+        byte == b'"'
+            || byte == b'\\'
+            || byte < 0x20
+            || byte == 0x7f
+            || (quote_non_ascii && byte > 0x7f)
+    };
+    if !path.iter().copied().any(needs_quoting) {
+        return path.to_owned();
+    }
+    let mut quoted = BString::from("\"");
+    for &byte in path.iter() {
+        match byte {
+            b'"' => quoted.extend_from_slice(b"\\\""),
+            b'\\' => quoted.extend_from_slice(b"\\\\"),
+            b'\x07' => quoted.extend_from_slice(b"\\a"),
+            b'\x08' => quoted.extend_from_slice(b"\\b"),
+            b'\t' => quoted.extend_from_slice(b"\\t"),
+            b'\n' => quoted.extend_from_slice(b"\\n"),
+            b'\x0b' => quoted.extend_from_slice(b"\\v"),
+            b'\x0c' => quoted.extend_from_slice(b"\\f"),
+            b'\r' => quoted.extend_from_slice(b"\\r"),
+            byte if needs_quoting(byte) => {
+                quoted.extend_from_slice(format!("\\{byte:03o}").as_bytes())
+            }
+            byte => quoted.push(byte),
+        }
+    }
+    quoted.push(b'"');
+    quoted
+}
+
+/// Like [`quote_path()`], but for display as string, so paths that aren't valid UTF-8 are
+/// always quoted to not lose any of their bytes.
+pub(crate) fn display_path(path: &BStr, quote_non_ascii: bool) -> String {
+    let quote_non_ascii = quote_non_ascii || path.to_str().is_err();
+    quote_path(path, quote_non_ascii)
+        .to_str()
+        .expect("quoting leaves only valid UTF-8")
+        .to_owned()
+}
+
+/// Turn `path` as given on the command line back into the bytes of the path it names, undoing
+/// the quoting of [`quote_path()`]. Paths that aren't quoted are returned as is.
+pub(crate) fn unquote_path(path: &str) -> Cow<'_, BStr> {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .filter(|inner| !inner.is_empty())
+    else {
+        return Cow::Borrowed(path.as_bytes().as_bstr());
+    };
+    let mut unquoted = BString::default();
+    let mut bytes = inner.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            unquoted.push(byte);
+            continue;
+        }
+        let unescaped = match bytes.next() {
+            Some(b'a') => b'\x07',
+            Some(b'b') => b'\x08',
+            Some(b't') => b'\t',
+            Some(b'n') => b'\n',
+            Some(b'v') => b'\x0b',
+            Some(b'f') => b'\x0c',
+            Some(b'r') => b'\r',
+            Some(digit @ b'0'..=b'3') => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    match bytes.next() {
+                        Some(digit @ b'0'..=b'7') => value = value * 8 + (digit - b'0'),
+                        _ => return Cow::Borrowed(path.as_bytes().as_bstr()),
+                    }
+                }
+                value
+            }
+            Some(byte @ (b'"' | b'\\')) => byte,
+            _ => return Cow::Borrowed(path.as_bytes().as_bstr()),
+        };
+        unquoted.push(unescaped);
+    }
+    Cow::Owned(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::ByteSlice;
+
+    use super::{display_path, quote_path, unquote_path};
+
+    #[test]
+    fn quote_path_leaves_plain_paths_alone() {
+        assert_eq!(
+            quote_path(b"dir/file name.txt".as_bstr(), true),
+            "dir/file name.txt"
+        );
+    }
+
+    #[test]
+    fn quote_path_escapes_like_git() {
+        assert_eq!(
+            quote_path(b"caf\xe9.txt".as_bstr(), true),
+            r#""caf\351.txt""#
+        );
+        assert_eq!(
+            quote_path("café".as_bytes().as_bstr(), true),
+            r#""caf\303\251""#
+        );
+        assert_eq!(quote_path(b"a\"b\\c\td".as_bstr(), true), r#""a\"b\\c\td""#);
+    }
+
+    #[test]
+    fn quote_path_keeps_non_ascii_bytes_if_disabled() {
+        assert_eq!(quote_path("café".as_bytes().as_bstr(), false), "café");
+        assert_eq!(
+            quote_path(b"caf\xe9\t".as_bstr(), false),
+            b"\"caf\xe9\\t\"".as_bstr()
+        );
+    }
+
+    #[test]
+    fn display_path_always_quotes_invalid_utf8() {
+        assert_eq!(display_path("café".as_bytes().as_bstr(), false), "café");
+        assert_eq!(
+            display_path(b"caf\xe9.txt".as_bstr(), false),
+            r#""caf\351.txt""#
+        );
+    }
+
+    #[test]
+    fn unquote_path_round_trips() {
+        for path in [
+            b"plain.txt".as_bstr(),
+            b"caf\xe9.txt".as_bstr(),
+            "café".as_bytes().as_bstr(),
+            b"a\"b\\c\td\x7f".as_bstr(),
+        ] {
+            let quoted = display_path(path, true);
+            assert_eq!(unquote_path(&quoted).as_ref(), path, "{quoted}");
+        }
+        assert_eq!(
+            unquote_path(r#""not\9octal""#).as_ref(),
+            r#""not\9octal""#,
+            "invalid escapes are taken literally"
+        );
+    }
+}
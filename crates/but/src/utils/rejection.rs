@@ -104,7 +104,7 @@ pub fn explain_rejections(
         .map(|(reason, spec)| {
             let dependencies = match &dependencies {
                 Some(deps) if is_dependency_reason(*reason) => {
-                    dependencies_for_spec(ws, deps, spec)
+                    dependencies_for_spec(ws, deps, spec, |_| true)
                 }
                 _ => Vec::new(),
             };
@@ -292,7 +292,8 @@ fn sole_dependency_branch(rejected: &[RejectedChange]) -> Option<&str> {
     branch
 }
 
-/// Find the hunks of `dependencies` that belong to `spec`.
+/// Find the hunks of `dependencies` that belong to `spec`, along with the commits they depend on
+/// whose lock target passes `filter`.
 ///
 /// Matching is by path, then by hunk overlap: a rejected spec covering specific
 /// hunks only reports the dependent hunks that overlap them, while a whole-file
@@ -303,6 +304,7 @@ fn dependencies_for_spec(
     ws: &Workspace,
     dependencies: &HunkDependencies,
     spec: &DiffSpec,
+    filter: impl Fn(HunkLockTarget) -> bool,
 ) -> Vec<HunkDependency> {
     let spec_path = spec.path.as_bstr();
     let mut result = Vec::new();
@@ -312,7 +314,7 @@ fn dependencies_for_spec(
         if dep_path.as_bytes().as_bstr() != spec_path {
             continue;
         }
-        if locks.is_empty() {
+        if !locks.iter().any(|lock| filter(lock.target)) {
             continue;
         }
         let hunk = HunkHeader::from(dep_hunk);
@@ -326,6 +328,7 @@ fn dependencies_for_spec(
         }
         let commits = locks
             .iter()
+            .filter(|lock| filter(lock.target))
             .map(|lock| DependencyCommit {
                 commit_id: lock.commit_id,
                 branch: branch_of_commit(ws, lock.commit_id, stack_of(lock.target)),
@@ -400,6 +403,57 @@ fn branches_touching_path(
     branches
 }
 
+/// The `specs` about to be committed to the stack `target_stack` that have hunks locked to
+/// commits in other stacks, i.e. that depend on them and thus can't be committed on their own.
+///
+/// Failure to compute the dependencies isn't fatal, as the commit then still reports such changes
+/// as rejected.
+pub fn locked_to_other_stacks(
+    repo: &gix::Repository,
+    ws: &Workspace,
+    specs: &[DiffSpec],
+    target_stack: StackId,
+) -> Vec<RejectedChange> {
+    let dependencies = match hunk_dependencies_for_workspace_changes_by_worktree_dir(repo, ws, None)
+    {
+        Ok(dependencies) => dependencies,
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                "Failed to compute hunk dependencies to check for locks"
+            );
+            return Vec::new();
+        }
+    };
+    specs
+        .iter()
+        .filter_map(|spec| {
+            let dependencies = dependencies_for_spec(
+                ws,
+                &dependencies,
+                spec,
+                |target| matches!(target, HunkLockTarget::Stack(id) if id != target_stack),
+            );
+            (!dependencies.is_empty()).then(|| RejectedChange {
+                path: spec.path.clone(),
+                reason: RejectionReason::WorkspaceMergeConflict,
+                dependencies,
+                suspected_branches: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// The error for committing the `locked` changes, as returned by [`locked_to_other_stacks()`],
+/// to `target_branch`, naming the commits they are locked to.
+pub fn locked_changes_error(locked: &[RejectedChange], target_branch: &str) -> anyhow::Error {
+    let mut message = format!(
+        "Cannot commit to '{target_branch}': the selected changes are locked to commits on another branch.\n"
+    );
+    write_rejection_body(&mut message, locked, Some(target_branch)).ok();
+    anyhow::anyhow!(message.trim_end().to_string())
+}
+
 /// A targeted error for a commit that failed outright (not just rejected
 /// specs): when the attempted changes conflict with commits on another
 /// workspace branch, name that branch and the stacking recovery instead of
@@ -437,7 +491,7 @@ pub fn commit_failure_error(
 ///
 /// Zero-length ranges (pure deletions) are treated as covering a single line so
 /// that they still match an overlapping hunk.
-fn hunks_overlap(a: &HunkHeader, b: &HunkHeader) -> bool {
+pub(crate) fn hunks_overlap(a: &HunkHeader, b: &HunkHeader) -> bool {
     let end_a = a.new_start.saturating_add(a.new_lines.max(1));
     let end_b = b.new_start.saturating_add(b.new_lines.max(1));
     a.new_start < end_b && b.new_start < end_a
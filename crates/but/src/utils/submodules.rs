@@ -0,0 +1,80 @@
+//! Submodules are committed as the commit they have checked out, so changes within them can't be
+//! committed from the repository that contains them.
+
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString};
+
+/// Whether a submodule has uncommitted changes in its own worktree or index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SubmoduleState {
+    Clean,
+    Dirty,
+    /// The submodule couldn't be checked, for instance because it isn't checked out.
+    Unknown,
+}
+
+/// Return the state of the submodules of `repo` at `changed_paths`, which are expected to be the
+/// paths of submodules that point to new commits.
+/// Only these are checked as looking for changes inside a submodule is as expensive as running
+/// `git status` in it. Submodules that can't be checked are [`SubmoduleState::Unknown`].
+pub(crate) fn changed_submodule_states<'a>(
+    repo: &gix::Repository,
+    changed_paths: impl IntoIterator<Item = &'a BStr>,
+) -> BTreeMap<BString, SubmoduleState> {
+    let mut changed_paths = changed_paths.into_iter().peekable();
+    if changed_paths.peek().is_none() {
+        return BTreeMap::new();
+    }
+    let submodules: Vec<_> = match repo.submodules() {
+        Ok(submodules) => submodules.into_iter().flatten().collect(),
+        Err(err) => {
+            tracing::warn!(?err, "could not read the submodules of the repository");
+            Vec::new()
+        }
+    };
+    changed_paths
+        .map(|path| {
+            let state = submodules
+                .iter()
+                .find(|submodule| {
+                    submodule
+                        .path()
+                        .is_ok_and(|sm_path| sm_path.as_ref() == path)
+                })
+                .map_or(SubmoduleState::Unknown, submodule_state);
+            (path.to_owned(), state)
+        })
+        .collect()
+}
+
+fn submodule_state(submodule: &gix::Submodule<'_>) -> SubmoduleState {
+    let is_dirty = || -> anyhow::Result<Option<bool>> {
+        let Some(submodule_repo) = submodule.open()? else {
+            return Ok(None);
+        };
+        Ok(Some(submodule_repo.is_dirty()?))
+    };
+    match is_dirty() {
+        Ok(Some(true)) => SubmoduleState::Dirty,
+        Ok(Some(false)) => SubmoduleState::Clean,
+        Ok(None) => SubmoduleState::Unknown,
+        Err(err) => {
+            tracing::warn!(?err, "could not check the submodule for changes");
+            SubmoduleState::Unknown
+        }
+    }
+}
+
+/// Return `true` if `status` records a change of the commit a submodule points to.
+pub(crate) fn is_submodule_change(status: &but_core::ui::TreeStatus) -> bool {
+    use but_core::ui::TreeStatus;
+    use gix::object::tree::EntryKind;
+    match status {
+        TreeStatus::Addition { state, .. } => state.kind == EntryKind::Commit,
+        TreeStatus::Deletion { previous_state } => previous_state.kind == EntryKind::Commit,
+        TreeStatus::Modification { state, .. } | TreeStatus::Rename { state, .. } => {
+            state.kind == EntryKind::Commit
+        }
+    }
+}
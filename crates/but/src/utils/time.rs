@@ -58,9 +58,30 @@ pub fn format_relative_time_verbose(now: std::time::SystemTime, timestamp_ms: u1
             hours,
             if hours == 1 { "hour" } else { "hours" }
         )
-    } else {
+    } else if elapsed_secs < 604800 {
         let days = elapsed_secs / 86400;
         format!("{} {} ago", days, if days == 1 { "day" } else { "days" })
+    } else if elapsed_secs < 2592000 {
+        let weeks = elapsed_secs / 604800;
+        format!(
+            "{} {} ago",
+            weeks,
+            if weeks == 1 { "week" } else { "weeks" }
+        )
+    } else if elapsed_secs < 31536000 {
+        let months = elapsed_secs / 2592000;
+        format!(
+            "{} {} ago",
+            months,
+            if months == 1 { "month" } else { "months" }
+        )
+    } else {
+        let years = elapsed_secs / 31536000;
+        format!(
+            "{} {} ago",
+            years,
+            if years == 1 { "year" } else { "years" }
+        )
     }
 }
 
@@ -153,5 +174,17 @@ mod tests {
             format_relative_time_verbose(now, now_ms - 172_800_000),
             "2 days ago"
         );
+        assert_eq!(
+            format_relative_time_verbose(now, now_ms - 21 * 86_400_000),
+            "3 weeks ago"
+        );
+        assert_eq!(
+            format_relative_time_verbose(now, now_ms - 60 * 86_400_000),
+            "2 months ago"
+        );
+        assert_eq!(
+            format_relative_time_verbose(now, now_ms - 400 * 86_400_000),
+            "1 year ago"
+        );
     }
 }
@@ -71,11 +71,20 @@ fn uncommitted_file() -> anyhow::Result<()> {
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 2,
+        "deletions": 2,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
 ...
@@ -125,6 +134,7 @@ lasta
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
 ...
 
@@ -207,11 +217,20 @@ last
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 1,
+        "deletions": 1,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
 ...
@@ -348,8 +367,8 @@ nk:5 a.txt│
         .success()
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   nk M a.txt
+╭┄ zz [uncommitted] (1 file, +2 -2)
+┊   nk M a.txt  +2 -2
 ┊
 ┊╭┄ g0 [A]
 ┊●   1#0 partial change to a.txt 3
@@ -567,11 +586,20 @@ last
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 2,
+        "deletions": 2,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
 ...
@@ -0,0 +1,292 @@
+use snapbox::str;
+
+use crate::{
+    command::util::{commit_file_with_worktree_changes_as_two_hunks, status_json},
+    utils::{CommandExt as _, Sandbox},
+};
+
+fn uncommitted_cli_id(env: &Sandbox, path: &str) -> anyhow::Result<String> {
+    let status = status_json(env)?;
+    status["uncommittedChanges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|change| change["filePath"] == path)
+        .and_then(|change| change["cliId"].as_str())
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| anyhow::anyhow!("'{path}' should be an uncommitted change"))
+}
+
+/// The sorted paths of the changes assigned to the stack of `branch` in the JSON `status`.
+fn assigned_paths(status: &serde_json::Value, branch: &str) -> Vec<String> {
+    let mut paths: Vec<String> = status["stacks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|stack| {
+            stack["branches"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|candidate| candidate["name"] == branch)
+        })
+        .and_then(|stack| stack["assignedChanges"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|change| change["filePath"].as_str().map(ToOwned::to_owned))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn assign_to_branch_then_move_everything_to_another() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("new.txt", "content\n");
+    let id = uncommitted_cli_id(&env, "new.txt")?;
+
+    env.but(format!("assign {id} A"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Assigned to A:
+  [..] new.txt (1 hunk)
+
+"#]]);
+
+    env.but(format!("assign {id} A"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Nothing to assign, the changes are already assigned to 'A'.
+
+"#]]);
+
+    env.but("assign --from A B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Assigned to B:
+  [..] new.txt (1 hunk)
+
+"#]]);
+
+    env.but("assign --from A B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Nothing to assign, no such changes are assigned to 'A'.
+
+"#]]);
+
+    let output = env
+        .but(format!("--format json assign {id} A"))
+        .allow_json()
+        .output()?;
+    assert!(output.status.success());
+    let outcome: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(outcome["branch"], "A");
+    assert_eq!(outcome["assigned"][0]["path"], "new.txt");
+    assert_eq!(outcome["assigned"][0]["cliId"], id.as_str());
+    assert_eq!(outcome["assigned"][0]["hunks"], 1);
+    Ok(())
+}
+
+#[test]
+fn assigning_hunks_locked_to_another_stack_requires_force() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    commit_file_with_worktree_changes_as_two_hunks(&env, "A", "a.txt");
+    let id = uncommitted_cli_id(&env, "a.txt")?;
+
+    env.but(format!("assign {id} B"))
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+Use --force to assign them anyway.
+...
+"#]]);
+
+    env.but(format!("assign {id} B --force"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Warning: assigning changes that are locked to commits in other stacks:
+...
+Assigned to B:
+  [..] a.txt (2 hunks)
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn unassign_skips_unassigned_targets_and_fails_only_if_nothing_was_done() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("a.txt", "a\n");
+    env.file("b.txt", "b\n");
+    env.file("c.txt", "c\n");
+    let (a, b, c) = (
+        uncommitted_cli_id(&env, "a.txt")?,
+        uncommitted_cli_id(&env, "b.txt")?,
+        uncommitted_cli_id(&env, "c.txt")?,
+    );
+    env.but(format!("assign {a} {b} A")).assert().success();
+
+    env.but(format!("unassign {a} {c}"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] a.txt (1 hunk from A)
+Skipped:
+  [..]: already unassigned
+
+"#]]);
+
+    env.but(format!("unassign {c}"))
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+  [..]: already unassigned
+...
+"#]]);
+
+    env.but("unassign --all --branch A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] b.txt (1 hunk from A)
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn assign_and_unassign_by_glob_and_directory() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("src/auth/login.rs", "login\n");
+    env.file("src/auth/token.rs", "token\n");
+    env.file("src/main.rs", "main\n");
+
+    env.but("assign 'src/auth/**' A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+'src/auth/**' matches 2 files:
+  src/auth/login.rs
+  src/auth/token.rs
+Assigned to A:
+  [..] src/auth/login.rs (1 hunk)
+  [..] src/auth/token.rs (1 hunk)
+
+"#]]);
+
+    env.but("unassign src/auth")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+'src/auth' matches 2 files:
+  src/auth/login.rs
+  src/auth/token.rs
+Unassigned:
+  [..] src/auth/login.rs (1 hunk from A)
+  [..] src/auth/token.rs (1 hunk from A)
+
+"#]]);
+
+    env.but("assign 'src/auht/**' A")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+'src/auht/**' matches no changed paths. The closest are:
+  src/auth/login.rs
+  src/auth/token.rs
+  src/main.rs
+...
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn assignments_survive_renames() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+
+    let numbered_lines =
+        |prefix: &str| -> String { (1..=20).map(|n| format!("{prefix} line {n}\n")).collect() };
+    let edited = |prefix: &str| numbered_lines(prefix).replace(" line 10\n", " line ten\n");
+    for name in ["pure", "edited", "recreated"] {
+        env.file(format!("{name}.txt"), numbered_lines(name));
+    }
+    env.but("commit A -m base").assert().success();
+    for name in ["pure", "edited", "recreated"] {
+        env.file(format!("{name}.txt"), edited(name));
+        let id = uncommitted_cli_id(&env, &format!("{name}.txt"))?;
+        env.but(format!("assign {id} A")).assert().success();
+    }
+
+    // A pure rename, a rename followed by another edit of the same hunk, and a rename after
+    // which the previous path is created again as a new file.
+    env.invoke_git("mv pure.txt pure-renamed.txt");
+    env.invoke_git("mv edited.txt edited-renamed.txt");
+    env.file(
+        "edited-renamed.txt",
+        edited("edited").replace(" line 11\n", " line eleven\n"),
+    );
+    env.invoke_git("mv recreated.txt moved.txt");
+    env.file("recreated.txt", "new\n");
+
+    let status = status_json(&env)?;
+    assert_eq!(
+        assigned_paths(&status, "A"),
+        ["edited-renamed.txt", "moved.txt", "pure-renamed.txt"],
+        "renamed files stay assigned to A, also after another edit"
+    );
+    assert!(
+        status["uncommittedChanges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|change| change["filePath"] == "recreated.txt"),
+        "the file created at the previous path of a rename is unassigned"
+    );
+
+    // Once followed, renames don't take the assignments of files created at their previous path.
+    let id = uncommitted_cli_id(&env, "recreated.txt")?;
+    env.but(format!("assign {id} B"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Assigned to B:
+  [..] recreated.txt (1 hunk)
+
+"#]]);
+
+    env.but("unassign --all --branch A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] edited-renamed.txt (1 hunk from A)
+  [..] moved.txt (1 hunk from A)
+  [..] pure-renamed.txt (1 hunk from A)
+
+"#]]);
+    env.but("unassign --all --branch B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] recreated.txt (1 hunk from B)
+
+"#]]);
+    Ok(())
+}
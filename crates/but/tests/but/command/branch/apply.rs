@@ -28,6 +28,8 @@ fn single_branch() -> anyhow::Result<()> {
         .stdout_eq(str![[r#"
 Applied branch 'main' to workspace
 Applied branch 'A' to workspace
+...
+Workspace now contains: [..]
 
 "#]]);
 
@@ -64,6 +66,8 @@ Applied branch 'A' to workspace
         .success()
         .stdout_eq(str![[r#"
 Applied remote branch 'origin/B' to workspace
+New stack [..] for 'B'
+Workspace now contains: [..]
 
 "#]])
         .stderr_eq(str![""]);
@@ -131,6 +135,8 @@ fn local_branch() {
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
 Applied branch 'feature-branch' to workspace
+New stack [..] for 'feature-branch'
+Workspace now contains: [..]
 
 "#]]);
     env.but("apply")
@@ -280,6 +286,8 @@ requested_branch=refs/heads/feature-branch
 workspace_changed=true
 workspace_ref_created=false
 applied_branch=refs/heads/feature-branch
+applied_stack_id=[..]
+applied_stack=refs/heads/feature-branch
 
 "#]])
         .stderr_eq(str![]);
@@ -317,6 +325,8 @@ fn remote_branch_creates_local_tracking_branch_automatically() {
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
 Applied remote branch 'origin/remote-feature' to workspace
+New stack [..] for 'remote-feature'
+Workspace now contains: [..]
 
 "#]]);
 
@@ -368,6 +378,8 @@ fn remote_branch_short_name_resolves_to_unique_remote_tracking_branch() {
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
 Applied remote branch 'origin/remote-feature' to workspace
+New stack [..] for 'remote-feature'
+Workspace now contains: [..]
 
 "#]]);
 
@@ -429,6 +441,8 @@ Failed to apply branch. The reference 'remote-feature' did not exist
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
 Applied remote branch 'origin/remote-feature' to workspace
+New stack [..] for 'remote-feature'
+Workspace now contains: [..]
 
 "#]]);
 
@@ -538,6 +552,8 @@ fn multiple_branches_sequentially() {
         .success()
         .stdout_eq(str![[r#"
 Applied branch 'feature-1' to workspace
+New stack [..] for 'feature-1'
+Workspace now contains: [..]
 
 "#]])
         .stderr_eq(str![]);
@@ -548,6 +564,8 @@ Applied branch 'feature-1' to workspace
         .success()
         .stdout_eq(str![[r#"
 Applied branch 'feature-2' to workspace
+New stack [..] for 'feature-2'
+Workspace now contains: [..]
 
 "#]])
         .stderr_eq(str![]);
@@ -595,12 +613,14 @@ fn apply_branch_conflicting_with_workspace_reports_error() {
     );
 
     // It's notable that this behaviour is different from what the GUI does, which
-    // unapplies all conflicting instead.
+    // unapplies all conflicting instead. That's what `--force-apply` is for.
     env.but("apply conflicting-branch")
         .assert()
         .failure()
         .stderr_eq(str![[r#"
-Failed to apply branch. 'conflicting-branch' conflicts with existing stack in the workspace: A
+Error: 'conflicting-branch' conflicts with existing stack in the workspace: A
+
+Hint: Use `--force-apply` to apply it anyway, which unapplies the conflicting stacks
 
 "#]])
         .stdout_eq(str![""]);
@@ -610,7 +630,9 @@ Failed to apply branch. 'conflicting-branch' conflicts with existing stack in th
         .assert()
         .failure()
         .stderr_eq(str![[r#"
-Failed to apply branch. 'conflicting-branch' conflicts with existing stack in the workspace: A
+Error: 'conflicting-branch' conflicts with existing stack in the workspace: A
+
+Hint: Use `--force-apply` to apply it anyway, which unapplies the conflicting stacks
 
 "#]])
         .stdout_eq(str![[r#"
@@ -620,6 +642,38 @@ workspace_changed=true
 workspace_ref_created=false
 conflicting_stack=refs/heads/A
 
+"#]]);
+
+    env.but("branch apply conflicting-branch --force-apply")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Applied branch 'conflicting-branch' to workspace
+New stack [..] for 'conflicting-branch'
+Unapplied conflicting stacks: A
+Workspace now contains: [..]
+
+"#]]);
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn branch_apply_subcommand_applies_local_branch() {
+    let env = Sandbox::open_or_init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    create_local_branch_with_commit(&env, "feature-branch");
+
+    env.but("branch apply feature-branch")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Applied branch 'feature-branch' to workspace
+New stack [..] for 'feature-branch'
+Workspace now contains: [..]
+
 "#]]);
 }
 
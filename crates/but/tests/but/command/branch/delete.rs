@@ -23,7 +23,7 @@ fn can_delete_branch_with_commits() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
     env.setup_metadata(&["A"]);
 
-    env.but("branch delete A")
+    env.but("branch delete --force A")
         .assert()
         .success()
         .stderr_eq(str![[""]])
@@ -56,7 +56,7 @@ fn can_delete_branch_with_commits_in_the_bottom_of_a_stack() {
     );
     env.setup_metadata(&["A", "B", "C"]);
 
-    env.but("branch delete A")
+    env.but("branch delete --force A")
         .assert()
         .success()
         .stderr_eq(str![[""]])
@@ -92,7 +92,7 @@ fn can_delete_branch_with_commits_in_the_middle_of_a_stack() {
     );
     env.setup_metadata(&["A", "B", "C"]);
 
-    env.but("branch delete B")
+    env.but("branch delete --force B")
         .assert()
         .success()
         .stderr_eq(str![[""]])
@@ -128,7 +128,7 @@ fn can_delete_branch_with_commits_in_the_top_of_a_stack() {
     );
     env.setup_metadata(&["A", "B", "C"]);
 
-    env.but("branch delete C")
+    env.but("branch delete --force C")
         .assert()
         .success()
         .stderr_eq(str![[""]])
@@ -164,7 +164,7 @@ fn can_delete_branches_via_short_code() {
     );
     env.setup_metadata(&["A", "B", "C"]);
 
-    env.but("branch delete g0")
+    env.but("branch delete --force g0")
         .assert()
         .success()
         .stderr_eq(str![[""]])
@@ -192,3 +192,76 @@ Hint: run `but help` for all commands
 
 "#]]);
 }
+
+#[test]
+fn refuses_to_delete_branch_with_unpushed_commits_without_force() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A"]);
+
+    env.but("branch delete A")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Branch 'A' has 1 unpushed commit
+
+Hint: use `--force` to delete it anyway
+
+"#]])
+        .stdout_eq(str![[]]);
+}
+
+#[test]
+fn deletes_local_branch_outside_the_workspace() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch other main");
+
+    env.but("--format json branch delete other")
+        .allow_json()
+        .assert()
+        .success()
+        .stderr_eq(str![[""]])
+        .stdout_eq(str![[r#"
+{
+  "deletedRefs": [
+    "refs/heads/other"
+  ]
+}
+
+"#]]);
+
+    assert!(
+        env.open_repo()
+            .try_find_reference("refs/heads/other")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn keeps_local_branch_if_deleting_the_remote_branch_fails() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch other main");
+    env.invoke_git("update-ref refs/remotes/origin/other main");
+    env.invoke_git("config branch.other.remote origin");
+    env.invoke_git("config branch.other.merge refs/heads/other");
+
+    // The remote of the fixture doesn't exist, so pushing the deletion fails.
+    env.but("branch delete --remote other")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Failed to delete remote branch 'origin/other': [..]
+...
+"#]])
+        .stdout_eq(str![[]]);
+
+    assert!(
+        env.open_repo()
+            .try_find_reference("refs/heads/other")
+            .unwrap()
+            .is_some(),
+        "the local branch stays if the remote one couldn't be deleted"
+    );
+}
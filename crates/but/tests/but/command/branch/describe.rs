@@ -0,0 +1,135 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt, Sandbox};
+
+#[test]
+fn set_description_and_describe_roundtrip() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("branch describe A")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Branch A has no description. Set one with `but branch set-description A`
+
+"#]]);
+
+    env.but("branch set-description A 'Adds A\n\nWith more details.'")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Set description of branch A
+
+"#]]);
+
+    env.but("branch describe A")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Adds A
+
+With more details.
+
+"#]]);
+
+    let output = env
+        .but("--format json branch describe A")
+        .allow_json()
+        .output()?;
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        json,
+        serde_json::json!({"branch": "A", "description": "Adds A\n\nWith more details."})
+    );
+
+    env.but("branch set-description A ''")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Removed description of branch A
+
+"#]]);
+    env.but("--format shell branch describe A")
+        .assert()
+        .success()
+        .stdout_eq(str![]);
+    Ok(())
+}
+
+#[test]
+fn set_description_with_editor_on_unapplied_branch() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch feature A");
+
+    env.file(
+        ".git/editor.sh",
+        "printf 'Described in the editor\\n# ignored\\n' > \"$1\"\n",
+    );
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but("branch set-description feature")
+        .env("GIT_EDITOR", format!("sh {}", editor_path.display()))
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Set description of branch feature
+
+"#]]);
+
+    env.but("branch describe feature")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Described in the editor
+
+"#]]);
+}
+
+#[test]
+fn list_verbose_shows_first_line_of_description() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.but("branch set-description A 'Adds A\n\nWith more details.'")
+        .assert()
+        .success();
+
+    env.but("branch list --verbose")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Applied branches
+active  ✓ *A Adds A[..]9477ae7 [..]years ago author
+
+"#]]);
+
+    env.but("branch list")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Applied branches
+active  ✓ *A          26y ago    author
+
+"#]]);
+}
+
+#[test]
+fn set_description_rejects_unknown_branch() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("branch set-description does-not-exist text")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: [..]does-not-exist[..]
+...
+"#]]);
+}
@@ -46,6 +46,43 @@ fn list_hides_empty_applied_branches_by_default() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `--json` is a shorthand for `--format json` and emits a versioned schema with tips and heads.
+#[test]
+fn list_json_flag_emits_versioned_schema() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch unapplied A");
+
+    let result = env.but("branch list --json").allow_json().output()?;
+    assert!(result.status.success());
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(stdout.trim())?;
+
+    assert_eq!(json["schemaVersion"], serde_json::json!(1));
+
+    let stack = &json["appliedStacks"][0];
+    assert_eq!(stack["applied"], serde_json::json!(true));
+    assert_eq!(stack["heads"][0]["name"], serde_json::json!("A"));
+    assert_eq!(
+        stack["heads"][0]["tip"],
+        serde_json::json!(env.invoke_git("rev-parse A"))
+    );
+
+    let unapplied = json["branches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|branch| branch["name"] == "unapplied")
+        .expect("the unapplied branch is listed");
+    assert_eq!(unapplied["hasLocal"], serde_json::json!(true));
+    assert_eq!(
+        unapplied["head"],
+        serde_json::json!(env.invoke_git("rev-parse A"))
+    );
+    assert!(unapplied["remotes"].as_array().unwrap().is_empty());
+    Ok(())
+}
+
 #[test]
 fn list_truncates_results_if_they_exceed_default_limit() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
@@ -120,8 +157,364 @@ local   ✓ branch-6  ↑1     26y ago    author
 local   ✓ branch-7  ↑1     26y ago    author
 local   ✓ branch-8  ↑1     26y ago    author
 
-... result truncated to 20 matching branches (use --all to show all that match filters)
+… and up to 1 more (use --limit 0 for all)
 
 "#]])
         .stderr_eq(snapbox::str![[]]);
 }
+
+/// Name patterns and `--author` compose to filter unapplied branches, while applied stacks remain.
+#[test]
+fn list_filters_by_name_pattern_and_author() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch feat/one A");
+    env.invoke_git("branch feat/two A");
+    env.invoke_git("branch fix/three A");
+
+    let listed_names = |args: &str| -> anyhow::Result<(Vec<String>, Vec<String>)> {
+        let result = env
+            .but(format!("--format json branch list {args}"))
+            .allow_json()
+            .output()?;
+        assert!(result.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+        let names = |value: &serde_json::Value| {
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|branch| branch["name"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+        let applied = json["appliedStacks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|stack| names(&stack["heads"]))
+            .collect();
+        Ok((applied, names(&json["branches"])))
+    };
+
+    let (applied, unapplied) = listed_names("'FEAT/*'")?;
+    assert_eq!(applied, ["A"], "applied stacks are never hidden");
+    assert_eq!(unapplied, ["feat/one", "feat/two"]);
+
+    let (_, unapplied) = listed_names("--case-sensitive 'FEAT/*'")?;
+    assert!(
+        unapplied.is_empty(),
+        "case-sensitive matching finds nothing"
+    );
+
+    let (_, unapplied) = listed_names("thr")?;
+    assert_eq!(unapplied, ["fix/three"], "substrings match as well");
+
+    let (_, unapplied) = listed_names("--author AUTHOR 'feat/*'")?;
+    assert_eq!(unapplied, ["feat/one", "feat/two"]);
+
+    let (applied, unapplied) = listed_names("--author someone-else")?;
+    assert_eq!(applied, ["A"]);
+    assert!(
+        unapplied.is_empty(),
+        "no branch is authored by someone else"
+    );
+    Ok(())
+}
+
+/// `--verbose` adds the tip commit and its age, and shortens long names in the middle.
+#[test]
+fn list_verbose_shows_tip_and_shortens_long_names() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let long_name = "feature/very-long-description-of-the-work-ABC-123";
+    env.invoke_git(&format!("branch {long_name} A"));
+
+    env.but("branch list --verbose")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A[..]9477ae7 [..]years ago author
+
+Unapplied Branches
+local   ✓ feature/very…work-ABC-123 9477ae7 ↑1     [..]years ago author
+
+"#]]);
+
+    let result = env
+        .but("--format json branch list --verbose")
+        .allow_json()
+        .output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    assert_eq!(
+        json["branches"][0]["name"],
+        serde_json::json!(long_name),
+        "names are never shortened in JSON"
+    );
+    Ok(())
+}
+
+/// `--limit` restricts unapplied branches only, and `--limit 0` shows them all.
+#[test]
+fn list_limit_truncates_unapplied_branches_only() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    for i in 0..3 {
+        env.invoke_git(&format!("branch branch-{i} A"));
+    }
+
+    env.but("branch list --limit 1 --empty")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A          26y ago    author
+
+Unapplied Branches
+local   ✓ branch-0  ↑1     26y ago    author
+
+… and [..] more (use --limit 0 for all)
+
+"#]]);
+
+    env.but("branch list --limit 0")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A          26y ago    author
+
+Unapplied Branches
+local   ✓ branch-0  ↑1     26y ago    author
+local   ✓ branch-1  ↑1     26y ago    author
+local   ✓ branch-2  ↑1     26y ago    author
+
+"#]]);
+}
+
+/// Branches of unapplied stacks are grouped and drawn as a tree, both for humans and in JSON.
+#[test]
+fn list_groups_unapplied_stacks() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.but("branch new --anchor A B").assert().success();
+    env.but("unapply A").assert().success();
+
+    env.but("branch list")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Unapplied Branches
+local   ✓ *-B (unapplied) ↑1 [..]
+local   ✓ └─A [..]↑1 [..]
+
+"#]]);
+
+    let result = env.but("branch list --json").allow_json().output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    let branches = json["branches"].as_array().unwrap();
+    let names: Vec<_> = branches
+        .iter()
+        .map(|branch| branch["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["B", "A"], "stack members are listed top to bottom");
+    for branch in branches {
+        assert_eq!(
+            branch["stack"]["branches"],
+            serde_json::json!(["B", "A"]),
+            "each member knows its stack"
+        );
+    }
+    Ok(())
+}
+
+/// Applied branches with conflicted commits are marked, for humans and in JSON.
+#[test]
+fn list_marks_conflicted_applied_branches() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    crate::command::util::create_conflicted_commit(&env)?;
+
+    env.but("branch list --no-check")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+...
+active  *branchB [conflicted] [..]
+...
+"#]]);
+
+    let result = env.but("branch list --json").allow_json().output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    let conflicted: std::collections::BTreeMap<_, _> = json["appliedStacks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|stack| stack["heads"].as_array().unwrap())
+        .map(|head| (head["name"].to_string(), head["conflicted"].clone()))
+        .collect();
+    assert_eq!(
+        conflicted,
+        [
+            (r#""A""#.to_owned(), serde_json::json!(false)),
+            (r#""branchB""#.to_owned(), serde_json::json!(true)),
+        ]
+        .into(),
+        "only branches with conflicted commits are marked"
+    );
+    Ok(())
+}
+
+/// Unapplied branches whose changes landed in the target, here by squash-merge, are marked
+/// as integrated, unless `--fast` skips the check.
+#[test]
+fn list_marks_integrated_unapplied_branches() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("branch feature A");
+    let squashed = env.invoke_git("commit-tree A^{tree} -p main -m 'add A (squashed)'");
+    env.invoke_git(&format!("update-ref refs/remotes/origin/main {squashed}"));
+
+    env.but("branch list --no-check")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+...
+Unapplied Branches
+local   feature [integrated] [..]
+
+"#]]);
+
+    let result = env.but("branch list --json").allow_json().output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    assert_eq!(json["branches"][0]["name"], "feature");
+    assert_eq!(json["branches"][0]["integrated"], true);
+
+    let result = env.but("branch list --json --fast").allow_json().output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    assert_eq!(
+        json["branches"][0].get("integrated"),
+        None,
+        "the integration check was skipped"
+    );
+    Ok(())
+}
+
+/// Without a forge to ask, refreshing reviews leaves branches without them instead of failing.
+#[test]
+fn list_refresh_without_forge_still_lists_branches() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("branch list --refresh --no-check")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  *A [..]
+
+"#]]);
+}
+
+#[test]
+fn color_flag_overrides_terminal_detection_and_environment() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let output = env.but("branch list --color=always").output()?;
+    assert!(output.status.success());
+    assert!(
+        output.stdout.contains(&b'\x1b'),
+        "colors are used even though the output isn't a terminal"
+    );
+
+    env.but("branch list --color=never")
+        .with_color_for_svg()
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A          26y ago    author
+
+"#]]);
+    let output = env
+        .but("branch list --color=never")
+        .with_color_for_svg()
+        .output()?;
+    assert!(
+        !output.stdout.contains(&b'\x1b'),
+        "`--color=never` wins over CLICOLOR_FORCE"
+    );
+    Ok(())
+}
+
+/// Applied stacks show how many uncommitted files are assigned to them, and the unassigned ones
+/// are counted at the bottom, unless `--no-worktree` skips looking at the worktree.
+#[test]
+fn list_counts_assigned_and_unassigned_files() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("assigned.txt", "content\n");
+    let status = crate::command::util::status_json(&env)?;
+    let file_id = status["uncommittedChanges"][0]["cliId"]
+        .as_str()
+        .expect("the new file is uncommitted")
+        .to_owned();
+    env.but(format!("assign {file_id} A")).assert().success();
+    env.file("unassigned-1.txt", "content\n");
+    env.file("unassigned-2.txt", "content\n");
+
+    env.but("branch list")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![[]])
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A ●1 [..]
+
+2 unassigned changed files
+
+"#]]);
+
+    let result = env.but("branch list --json").allow_json().output()?;
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    assert_eq!(
+        json["appliedStacks"][0]["assignedFiles"],
+        serde_json::json!(1)
+    );
+    assert_eq!(json["unassignedFiles"], serde_json::json!(2));
+
+    env.but("branch list --no-worktree")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+Applied branches
+active  ✓ *A [..]
+
+"#]]);
+
+    let result = env
+        .but("branch list --json --no-worktree")
+        .allow_json()
+        .output()?;
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout)?;
+    assert!(json["appliedStacks"][0].get("assignedFiles").is_none());
+    assert!(json.get("unassignedFiles").is_none());
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod apply;
 mod delete;
+mod describe;
 mod list;
 mod new;
 mod show;
@@ -32,6 +32,9 @@ fn outputs_branch_name() {
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
 ✓ Created branch my-anchored-feature stacked on [..]
+Stack (top to bottom):
+  my-anchored-feature (new)
+  A
 
 "#]]);
 }
@@ -46,13 +49,54 @@ fn rejects_anchor_outside_workspace() {
         .assert()
         .failure()
         .stderr_eq(snapbox::str![[r#"
-Error: Could not find anchor: 'A'
+Error: Cannot stack on branch 'A' as it isn't applied to the workspace
+
+Hint: apply it first with `but apply A`
+
+"#]]);
+
+    env.but("branch new --anchor does-not-exist new-branch")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+Error: Could not find anchor: 'does-not-exist'
 
 Hint: Run `but status` for applicable targets.
 
 "#]]);
 }
 
+#[test]
+fn anchor_onto_branch_stacks_new_branch_on_top() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("branch new --anchor A B")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Created branch B stacked on A
+Stack (top to bottom):
+  B (new)
+  A
+
+"#]]);
+
+    env.but("branch new --anchor A C")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Created branch C stacked on A
+Stack (top to bottom):
+  B
+  C (new)
+  A
+
+"#]]);
+}
+
 #[test]
 fn rejects_head() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
@@ -280,3 +324,67 @@ Hint: run `but help` for all commands
 
 "#]]);
 }
+
+#[test]
+fn at_commit_splits_stack() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("second.txt", "content\n");
+    env.but("commit A -m second").assert().success();
+
+    env.but("branch new part-one --at 9477ae7")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+✓ Created branch part-one at 9477ae7
+Stack (top to bottom):
+  A
+  part-one (new)
+
+"#]]);
+
+    let output = env.but("branch list --format json").allow_json().output()?;
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let heads: Vec<_> = json["appliedStacks"][0]["heads"]
+        .as_array()
+        .expect("heads should be an array")
+        .iter()
+        .map(|head| head["name"].clone())
+        .collect();
+    assert_eq!(
+        heads,
+        [serde_json::json!("A"), serde_json::json!("part-one")],
+        "the stack was split in two"
+    );
+    Ok(())
+}
+
+#[test]
+fn at_commit_outside_applied_stack_says_where_it_is() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.but("unapply A").assert().success();
+
+    env.but("branch new part-one --at 9477ae7")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Commit 9477ae7 isn't part of an applied stack, it was found in [..]
+
+Hint: apply the branch first with `but apply [..]`
+
+"#]]);
+
+    env.but("branch new part-one --at does-not-exist")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Could not find commit: 'does-not-exist'
+
+Hint: Run `but status` for applicable targets.
+
+"#]]);
+}
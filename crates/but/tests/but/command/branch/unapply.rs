@@ -164,15 +164,26 @@ feature-branch
 fn unapply_nonexistent_branch() {
     let env = Sandbox::open_or_init_scenario_with_target_and_default_settings("one-stack");
 
-    // Try to unapply a branch that doesn't exist - should fail with the new command
+    // Unknown branches are reported with a hint and their own exit code.
     env.but("unapply nonexistent-branch")
         .assert()
-        .failure()
+        .code(4)
         .stderr_eq(str![[r#"
-Failed to unapply branch. Branch 'nonexistent-branch' not found in any applied stack
+Error: Branch 'nonexistent-branch' not found
+
+Hint: Run `but branch list` to see available branches
 
 "#]])
         .stdout_eq(str![]);
+    env.but("branch unapply nonexistent-branch")
+        .assert()
+        .code(4)
+        .stderr_eq(str![[r#"
+Error: Branch 'nonexistent-branch' not found
+
+Hint: Run `but branch list` to see available branches
+
+"#]]);
 }
 
 #[test]
@@ -183,7 +194,17 @@ fn unapply_nonexistent_branch_with_json() {
     env.but("--format json unapply nonexistent-branch")
         .allow_json()
         .assert()
-        .failure();
+        .code(4)
+        .stdout_eq(str![[r#"
+{
+  "error": {
+    "message": "Branch 'nonexistent-branch' not found",
+    "kind": "bad_input",
+    "hint": "Run `but branch list` to see available branches"
+  }
+}
+
+"#]]);
 }
 
 #[test]
@@ -456,6 +477,81 @@ fn unapply_json_output_validation() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn unapply_stashes_assigned_changes_onto_branch_by_default() -> anyhow::Result<()> {
+    let env = Sandbox::open_or_init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let branch_name = "feature-branch";
+    create_local_branch_with_commit(&env, branch_name);
+    env.but("apply").arg(branch_name).assert().success();
+
+    env.file("assigned.txt", "content\n");
+    env.but("rub assigned.txt feature-branch")
+        .assert()
+        .success();
+
+    env.but("branch unapply feature-branch")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Unapplied stack with branches 'feature-branch' from workspace
+Stashed uncommitted changes to 1 file in commit [..] on 'feature-branch'
+  assigned.txt
+
+"#]]);
+
+    assert!(
+        !env.projects_root().join("assigned.txt").exists(),
+        "the change travels with the branch"
+    );
+    let message = env.invoke_git("log -1 --format=%s feature-branch");
+    assert_eq!(message, "WIP Assignments");
+
+    // Applying the branch again brings the change back.
+    env.but("branch apply feature-branch").assert().success();
+    assert!(env.projects_root().join("assigned.txt").exists());
+    Ok(())
+}
+
+#[test]
+fn unapply_keep_changes_leaves_assigned_changes_in_worktree() -> anyhow::Result<()> {
+    let env = Sandbox::open_or_init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let branch_name = "feature-branch";
+    create_local_branch_with_commit(&env, branch_name);
+    env.but("apply").arg(branch_name).assert().success();
+
+    env.file("assigned.txt", "content\n");
+    env.but("rub assigned.txt feature-branch")
+        .assert()
+        .success();
+
+    let output = env
+        .but("--format json branch unapply feature-branch --keep-changes")
+        .allow_json()
+        .output()?;
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["uncommitted_changes"], serde_json::json!("kept"));
+    assert_eq!(
+        json["uncommitted_paths"],
+        serde_json::json!(["assigned.txt"])
+    );
+    assert_eq!(json["stash_commit_id"], serde_json::Value::Null);
+
+    assert!(env.projects_root().join("assigned.txt").exists());
+    assert!(
+        !env.invoke_git("rev-parse --verify --quiet refs/heads/feature-branch")
+            .is_empty(),
+        "the branch remains so it can be applied again"
+    );
+    Ok(())
+}
+
 mod utils {
     use crate::utils::Sandbox;
 
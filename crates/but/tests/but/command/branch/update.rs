@@ -45,6 +45,7 @@ fn integrate_pull_rebase_applies_and_snapshots_before_and_after() -> anyhow::Res
         pretty_status(&env)?,
         snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
   "stacks": [],
   "mergeBase": {
@@ -104,6 +105,7 @@ Updated branch A.
         pretty_status(&env)?,
         snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
   "stacks": [],
   "mergeBase": {
@@ -239,6 +241,7 @@ o 0dc3733
         &before_status,
         snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
   "stacks": [],
   "mergeBase": {
@@ -530,6 +533,7 @@ fn integrate_errors_cleanly_without_tracking_branch() -> anyhow::Result<()> {
         pretty_status(&env)?,
         snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
   "stacks": [],
   "mergeBase": {
@@ -0,0 +1,157 @@
+use snapbox::str;
+
+use crate::utils::Sandbox;
+
+#[test]
+fn post_session_does_nothing_unless_enabled() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content\n");
+    let head_before = env.invoke_git("rev-parse A");
+
+    env.but("claude post-session")
+        .stdin(r#"{"session_id":"session-1"}"#)
+        .assert()
+        .success()
+        .stdout_eq(str![""])
+        .stderr_eq(str![""]);
+
+    // The executable is needed as well.
+    env.but("config set claude.auto_commit_after_completion true")
+        .assert()
+        .success();
+    env.but("claude post-session")
+        .stdin(r#"{"session_id":"session-1"}"#)
+        .assert()
+        .success()
+        .stdout_eq(str![""]);
+
+    assert_eq!(env.invoke_git("rev-parse A"), head_before);
+    assert_eq!(env.invoke_git("status --porcelain"), "?? new-file.txt");
+}
+
+#[cfg(unix)]
+#[test]
+fn post_session_commits_the_files_of_the_session_to_its_branch() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    super::util::fake_claude(&env, "Add new file")?;
+    env.but("config set claude.auto_commit_after_completion true")
+        .assert()
+        .success();
+    let write_transcript = |files: &[&str]| {
+        let mut transcript = String::from(
+            r#"{"type":"user","message":{"role":"user","content":"Please add a new file"}}"#,
+        );
+        for file in files {
+            let tool_use = serde_json::json!({
+                "type": "assistant",
+                "message": {
+                    "role": "assistant",
+                    "content": [{
+                        "type": "tool_use",
+                        "name": "Write",
+                        "input": { "file_path": env.projects_root().join(file) },
+                    }],
+                },
+            });
+            transcript.push('\n');
+            transcript.push_str(&tool_use.to_string());
+        }
+        transcript.push('\n');
+        env.file(".git/transcript.jsonl", transcript);
+    };
+    let transcript = env.projects_root().join(".git/transcript.jsonl");
+    let hook_input = serde_json::json!({
+        "session_id": "session-1",
+        "transcript_path": transcript,
+        "hook_event_name": "SessionEnd",
+    })
+    .to_string();
+
+    write_transcript(&["new-file.txt"]);
+    env.file("new-file.txt", "test content\n");
+    env.file("unrelated-file.txt", "not from the session\n");
+    env.but("claude post-session")
+        .stdin(hook_input.clone())
+        .assert()
+        .success();
+    let branches: serde_json::Value =
+        serde_json::from_str(&env.read_file(".git/gitbutler/claude-session-branches.json")?)?;
+    let branch = branches["session-1"]
+        .as_str()
+        .expect("the branch of the session is remembered")
+        .to_owned();
+    assert_ne!(branch, "A", "sessions get a branch of their own");
+    assert_eq!(
+        env.invoke_git(&format!("log -1 --format=%B {branch}")),
+        "Add new file"
+    );
+    assert_eq!(
+        env.invoke_git(&format!("show --format= --name-only {branch}")),
+        "new-file.txt",
+        "only the files the session edited are committed"
+    );
+    assert!(
+        !env.read_file(".git/config")?.contains("claudeSession"),
+        "nothing is stored in the Git config"
+    );
+    let prompt = env.read_file(".git/claude-prompt")?;
+    assert!(
+        prompt.contains("Please add a new file") && prompt.contains("+test content"),
+        "the prompts of the session and its diff are part of the prompt:\n{prompt}"
+    );
+
+    write_transcript(&["new-file.txt", "another-file.txt"]);
+    env.file("another-file.txt", "more content\n");
+    env.but("claude post-session")
+        .stdin(hook_input)
+        .assert()
+        .success();
+    assert_eq!(
+        env.invoke_git(&format!(
+            "log -2 --format=%s {branch} -- another-file.txt new-file.txt"
+        )),
+        "Add new file\nAdd new file",
+        "later changes of a resumed session go to the same branch"
+    );
+    assert_eq!(
+        env.invoke_git("status --porcelain"),
+        "?? unrelated-file.txt",
+        "changes the session didn't make stay uncommitted"
+    );
+    Ok(())
+}
+
+#[test]
+fn install_hooks_adds_the_session_end_hook_once() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.file(
+        ".claude/settings.json",
+        r#"{
+  "model": "opus",
+  "hooks": {
+    "Stop": [{ "hooks": [{ "type": "command", "command": "but claude post-session" }] }]
+  }
+}"#,
+    );
+
+    env.but("claude install-hooks").assert().success();
+    env.but("claude install-hooks").assert().success();
+
+    let settings: serde_json::Value =
+        serde_json::from_str(&env.read_file(".claude/settings.json")?)?;
+    assert_eq!(
+        settings,
+        serde_json::json!({
+            "model": "opus",
+            "hooks": {
+                "SessionEnd": [{
+                    "hooks": [{ "type": "command", "command": "but claude post-session" }]
+                }]
+            }
+        }),
+        "the hook that used to run after each response is replaced"
+    );
+    Ok(())
+}
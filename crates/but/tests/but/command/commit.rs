@@ -2,27 +2,342 @@ use snapbox::IntoData;
 use snapbox::str;
 
 use super::util;
-use crate::utils::Sandbox;
+use crate::utils::{CommandExt, Sandbox};
 
 #[test]
-fn commit_rejects_linked_worktree_with_specific_error() -> anyhow::Result<()> {
+fn commit_in_linked_worktree_commits_to_its_branch() -> anyhow::Result<()> {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
     let linked_root = tempfile::tempdir()?;
     let linked_worktree_dir = linked_root.path().join("linked");
     env.invoke_git(&format!(
-        "worktree add -b linked-mutation {}",
+        "worktree add -b linked-mutation {} main",
         linked_worktree_dir.display()
     ));
+    std::fs::write(linked_worktree_dir.join("linked-file"), "linked content\n")?;
+    env.file("main-file", "main content\n");
 
-    env.but("commit linked-mutation -m rejected")
+    env.but("--format json status")
         .current_dir(&linked_worktree_dir)
+        .allow_json()
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+{
+  "schemaVersion": 1,
+  "uncommittedChanges": [
+    {
+      "cliId": "[..]",
+      "filePath": "linked-file",
+      "changeType": "added",
+      "stat": {
+        "insertions": 1,
+        "deletions": 0,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
+    }
+  ],
+  "stacks": [
+...
+"#]]);
+    env.but("commit linked-mutation -m 'linked change'")
+        .current_dir(&linked_worktree_dir)
+        .assert()
+        .success();
+
+    let linked_git = format!("-C {}", linked_worktree_dir.display());
+    assert_eq!(
+        env.invoke_git(&format!("{linked_git} log -1 --format=%s linked-mutation")),
+        "linked change"
+    );
+    assert_eq!(
+        env.invoke_git(&format!("{linked_git} status --porcelain")),
+        "",
+        "the change in the linked worktree was committed"
+    );
+    assert_eq!(
+        env.invoke_git("rev-parse --abbrev-ref HEAD"),
+        "gitbutler/workspace",
+        "the main worktree stays in the workspace"
+    );
+    assert!(
+        !env.projects_root().join("linked-file").exists(),
+        "the main worktree isn't affected"
+    );
+    Ok(())
+}
+
+#[test]
+fn binary_file_is_described_and_committed_as_a_whole() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("binary.bin", b"\0\x01\x02binary\0");
+    env.but("commit A -m 'add binary'").assert().success();
+    env.file("binary.bin", b"\0\x1b[31mbinary\0\0");
+
+    let output = env.but("status -v").output()?;
+    let status = String::from_utf8(output.stdout)?;
+    assert!(
+        status.contains("binary file changed (9 B -> 14 B)"),
+        "binary files are described by their size:\n{status}"
+    );
+
+    let output = env.but("--format json status").output()?;
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let file_id = find_uncommitted_cli_id(&status, "binary.bin").expect("binary file is listed");
+    let hunk_id = status["uncommittedChanges"][0]["hunks"][0]["cliId"]
+        .as_str()
+        .expect("the whole-file change is listed as hunk")
+        .to_owned();
+    env.but(format!("commit A -m 'change binary' --changes {hunk_id}"))
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...'[..]' is no hunk as 'binary.bin' has no textual diff, like binary files. Use its file ID '[..]' to select all of it
+...
+"#]]);
+
+    env.but(format!("commit A -m 'change binary' --changes {file_id}"))
+        .assert()
+        .success();
+    assert_eq!(env.invoke_git("status --porcelain"), "");
+    assert_eq!(
+        env.invoke_git("cat-file -s A:binary.bin"),
+        "14",
+        "the new binary content was committed"
+    );
+    Ok(())
+}
+
+#[test]
+fn large_files_need_to_be_allowed_explicitly() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.invoke_git("config but.commit.largeFileThreshold 1k");
+    env.file("large.bin", "x".repeat(2048));
+    env.file("small.txt", "small\n");
+
+    env.but("commit A -m 'add large file'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: These files are larger than 1.0 KiB:
+  large.bin (2.0 KiB)
+
+Hint: Pass --allow-large-files to commit them anyway, or change the size limit with `git config but.commit.largeFileThreshold <bytes>`
+
+"#]]);
+
+    env.file(
+        ".gitattributes",
+        "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+    );
+    env.but("commit A -m 'add large file'")
         .assert()
         .failure()
-        .stdout_eq(snapbox::str![])
-        .stderr_eq(snapbox::str![[r#"
-Error: `but commit` is not supported from linked worktrees. Use Git directly for this worktree.
+        .stderr_eq(str![[r#"
+...
+Hint: This repository uses Git LFS, consider tracking them with `git lfs track`. Pass --allow-large-files to commit them anyway, or change the size limit with `git config but.commit.largeFileThreshold <bytes>`
+
+"#]]);
+
+    env.but("commit A -m 'add large file' --allow-large-files")
+        .assert()
+        .success();
+    assert_eq!(env.invoke_git("status --porcelain"), "");
+    assert_eq!(env.invoke_git("cat-file -s A:large.bin"), "2048");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn executable_bit_flip_is_shown_and_committed() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let path = env.projects_root().join("M");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+
+    let output = env.but("status").output()?;
+    let status = String::from_utf8(output.stdout)?;
+    assert!(
+        status.contains("mode change: M"),
+        "mode-only changes are listed:\n{status}"
+    );
+
+    env.but("commit A -m 'make M executable'")
+        .assert()
+        .success();
+    assert!(
+        env.invoke_git("ls-tree A M").starts_with("100755 blob"),
+        "the executable bit was committed"
+    );
+    assert_eq!(env.invoke_git("status --porcelain"), "");
+
+    let output = env.but("show A --verbose").output()?;
+    let show = String::from_utf8(output.stdout)?;
+    assert!(
+        show.contains("1 file changed, 0 insertions, 0 deletions"),
+        "the mode change counts as changed file without lines:\n{show}"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_retarget_is_committed() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let link = env.projects_root().join("link");
+    std::os::unix::fs::symlink("A", &link)?;
+    env.but("commit A -m 'add link'").assert().success();
+
+    std::fs::remove_file(&link)?;
+    std::os::unix::fs::symlink("M", &link)?;
+    env.but("commit A -m 'retarget link'").assert().success();
+    assert!(
+        env.invoke_git("ls-tree A link").starts_with("120000 blob"),
+        "it's still a link"
+    );
+    assert_eq!(env.invoke_git("cat-file -p A:link"), "M");
+    assert_eq!(env.invoke_git("status --porcelain"), "");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn file_replaced_by_symlink_is_shown_as_typechange_and_committed() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let path = env.projects_root().join("M");
+    std::fs::remove_file(&path)?;
+    std::os::unix::fs::symlink("A", &path)?;
+
+    let output = env.but("status").output()?;
+    let status = String::from_utf8(output.stdout)?;
+    assert!(
+        status.contains("typechange: M"),
+        "type changes are listed:\n{status}"
+    );
+
+    let template_path = env.projects_root().join(".git/commit-template");
+    env.file(
+        ".git/editor.sh",
+        format!(
+            "cp \"$1\" '{}'\nprintf 'turn M into a link\\n' > \"$1\"\n",
+            template_path.display()
+        ),
+    );
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but("commit A")
+        .env("GIT_EDITOR", format!("sh {}", editor_path.display()))
+        .assert()
+        .success();
+    let template = std::fs::read_to_string(&template_path)?;
+    assert!(
+        template.contains("#\ttypechange:  M"),
+        "the template lists the type change:\n{template}"
+    );
+    assert!(
+        template.contains("diff --git a/M b/M\ndeleted file mode 100644\n")
+            && template.contains("diff --git a/M b/M\nnew file mode 120000\n")
+            && template.contains("+A"),
+        "like Git, the type change is shown as deletion and addition:\n{template}"
+    );
+    assert!(
+        env.invoke_git("ls-tree A M").starts_with("120000 blob"),
+        "the file was committed as link"
+    );
+    assert_eq!(env.invoke_git("cat-file -p A:M"), "A");
+    assert_eq!(env.invoke_git("status --porcelain"), "");
+    Ok(())
+}
+
+#[test]
+fn submodule_pointing_to_new_commit_is_committed_as_gitlink() -> anyhow::Result<()> {
+    let env =
+        Sandbox::init_scenario_with_target_and_default_settings("one-stack-with-moved-submodule");
+    env.setup_metadata(&["A"]);
+    let new_submodule_head = env.invoke_git("-C submodule rev-parse HEAD");
+
+    let output = env.but("status").output()?;
+    let status = String::from_utf8(output.stdout)?;
+    assert!(
+        status.contains("submodule: submodule (new commits)"),
+        "submodules are listed as such:\n{status}"
+    );
+
+    std::fs::write(
+        env.projects_root().join("submodule").join("sub-new"),
+        "changed\n",
+    )?;
+    let output = env.but("status").output()?;
+    let status = String::from_utf8(output.stdout)?;
+    assert!(
+        status.contains("submodule: submodule (new commits, dirty)"),
+        "changes inside of submodules are shown as well:\n{status}"
+    );
+
+    env.but("commit A -m 'move submodule'")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Warning: Only the new commit of submodule 'submodule' is recorded, not the uncommitted changes inside of it
+✓ Created commit [..] on branch A
+
+"#]]);
+    assert_eq!(
+        env.invoke_git("ls-tree A submodule"),
+        format!("160000 commit {new_submodule_head}\tsubmodule"),
+        "the commit records the commit the submodule has checked out"
+    );
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn non_utf8_path_is_quoted_and_can_be_assigned_and_committed() -> anyhow::Result<()> {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    use crate::utils::CommandExt as _;
+
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let latin1_name = b"caf\xe9.txt";
+    std::fs::write(
+        env.projects_root()
+            .join(std::ffi::OsStr::from_bytes(latin1_name)),
+        "latin-1\n",
+    )?;
+
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+?? - "caf\351.txt"
 
 "#]]);
+    let output = env.but("--format json status").allow_json().output()?;
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        status["uncommittedChanges"][0]["filePathBytes"],
+        serde_json::json!(latin1_name),
+        "JSON carries the path losslessly"
+    );
+
+    env.but(r#"assign '"caf\351.txt"' A"#).assert().success();
+    env.but("commit A -m 'add latin-1 file'").assert().success();
+
+    assert_eq!(env.invoke_git("log -1 --format=%s A"), "add latin-1 file");
+    assert_eq!(
+        env.invoke_git("-c core.quotePath=true show --name-only --format= A"),
+        r#""caf\351.txt""#,
+        "the file is committed under its original name"
+    );
     Ok(())
 }
 
@@ -92,6 +407,170 @@ fn commit_with_message_from_file() {
     assert!(log.contains("Add new file from message file"));
 }
 
+#[test]
+fn commit_uses_editor_from_settings_before_git_editor() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content");
+
+    env.file(
+        ".git/editor.sh",
+        "printf 'Message from the settings editor\\n' > \"$1\"\n",
+    );
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but(format!(
+        "config set cli.editor 'sh {}'",
+        editor_path.display()
+    ))
+    .assert()
+    .success();
+
+    env.but("commit")
+        .env("GIT_EDITOR", "false")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+✓ Created commit [..] on branch A
+
+"#]]);
+
+    let log = env.git_log();
+    assert!(
+        log.contains("Message from the settings editor"),
+        "the editor from settings takes precedence over GIT_EDITOR:\n{log}"
+    );
+}
+
+#[test]
+fn commit_warns_about_message_conventions_from_settings() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content");
+    env.but("config set commit_message.subject_max_length 20")
+        .assert()
+        .success();
+    env.but("config set commit_message.require_blank_second_line true")
+        .assert()
+        .success();
+
+    env.but("commit -m 'A subject that is too long\nwithout a blank line'")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Warning: The subject has 26 characters, more than 20
+Warning: The subject should be followed by an empty line
+✓ Created commit [..] on branch A
+
+"#]]);
+}
+
+#[test]
+fn commit_in_strict_mode_reopens_editor_until_message_conforms() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content");
+    env.but("config set commit_message.subject_max_length 20")
+        .assert()
+        .success();
+    env.but("config set commit_message.strict true")
+        .assert()
+        .success();
+
+    env.file(
+        ".git/editor.sh",
+        "if grep -q 'more than 20' \"$1\"; then\n  printf 'Short subject\\n' > \"$1\"\nelse\n  printf 'A subject that is too long\\n' > \"$1\"\nfi\n",
+    );
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but("commit")
+        .env("GIT_EDITOR", format!("sh {}", editor_path.display()))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+✓ Created commit [..] on branch A
+
+"#]]);
+
+    let log = env.git_log();
+    assert!(
+        log.contains("Short subject"),
+        "the revised message is used:\n{log}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn commit_ai_no_edit_commits_message_from_claude_code() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content\n");
+    util::fake_claude(&env, "Add new file\\n\\nIt is needed.")?;
+
+    env.but("commit A --ai --no-edit").assert().success();
+    assert_eq!(
+        env.invoke_git("log -1 --format=%B A"),
+        "Add new file\n\nIt is needed."
+    );
+    assert_eq!(
+        env.read_file(".git/claude-args")?.trim(),
+        "--print --model haiku"
+    );
+    let prompt = env.read_file(".git/claude-prompt")?;
+    assert!(
+        prompt.contains("diff --git a/new-file.txt b/new-file.txt")
+            && prompt.contains("+test content"),
+        "the diff of the committed files is part of the prompt:\n{prompt}"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn commit_ai_prefills_editor_and_uses_configured_model() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content\n");
+    util::fake_claude(&env, "Add new file")?;
+    env.but("config set claude.use_configured_model true")
+        .assert()
+        .success();
+
+    env.file(
+        ".git/editor.sh",
+        "sed -i.bak 's/^Add new file$/Add the new file/' \"$1\"\n",
+    );
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but("commit A --ai")
+        .env("GIT_EDITOR", format!("sh {}", editor_path.display()))
+        .assert()
+        .success();
+    assert_eq!(env.invoke_git("log -1 --format=%B A"), "Add the new file");
+    assert_eq!(env.read_file(".git/claude-args")?.trim(), "--print");
+    Ok(())
+}
+
+#[test]
+fn commit_ai_falls_back_to_editor_if_claude_code_fails() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new-file.txt", "test content\n");
+    env.but("config set claude.executable does-not-exist-claude")
+        .assert()
+        .success();
+
+    env.file(".git/editor.sh", "printf 'Written by hand\\n' > \"$1\"\n");
+    let editor_path = env.projects_root().join(".git/editor.sh");
+    env.but("commit A --ai")
+        .env("GIT_EDITOR", format!("sh {}", editor_path.display()))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Warning: Couldn't generate a commit message: Failed to run Claude Code at 'does-not-exist-claude': [..]
+✓ Created commit [..] on branch A
+
+"#]]);
+    assert_eq!(env.invoke_git("log -1 --format=%B A"), "Written by hand");
+}
+
 #[test]
 fn commit_reports_dependency_changes() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("zero-stacks");
@@ -103,26 +582,31 @@ fn commit_reports_dependency_changes() {
 
     // Change the same file, then try to commit it onto a new, independent
     // branch. The change depends on `foo`'s commit, so it cannot land here; the
-    // CLI should name the branch/commit it depends on and suggest stacking.
+    // CLI should refuse, name the branch/commit it depends on and suggest stacking.
     env.file("first", "changes");
     env.but("commit -m 'change first elsewhere' -c bar")
         .assert()
-        .success()
-        .stdout_eq(str![[r#"
-Created new independent branch 'bar'
-✓ Created commit [..] on branch bar
-Note: 1 change could not be applied:
+        .failure()
+        .stderr_eq(str![[r#"
+...
+Cannot commit to 'bar': the selected changes are locked to commits on another branch.
   first
     line 1 depends on foo ([..])
 
 Hint: to apply these changes, stack bar on top of foo and commit them again — commits already on the branch move with it:
   but move bar foo
-
+...
 "#]]);
+
+    let log = env.git_log();
+    assert!(
+        !log.contains("change first elsewhere"),
+        "nothing is committed if changes are locked elsewhere"
+    );
 }
 
 #[test]
-fn commit_reports_dependency_changes_json() -> anyhow::Result<()> {
+fn commit_reports_dependency_changes_json() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("zero-stacks");
     env.setup_metadata(&[]);
 
@@ -130,29 +614,46 @@ fn commit_reports_dependency_changes_json() -> anyhow::Result<()> {
     env.but("commit -m 'add first' -c foo").assert().success();
 
     // The change to `first` depends on foo, so committing it onto bar is
-    // rejected; assert the `--json` shape of the reported dependency.
+    // refused in JSON mode as well, with the dependency named in the error.
     env.file("first", "changes");
-    let output = env
-        .but("commit -m 'change first elsewhere' -c bar --format json")
+    env.but("commit -m 'change first elsewhere' -c bar --format json")
         .assert()
-        .success();
-    let stdout = std::str::from_utf8(&output.get_output().stdout)?;
-    let json: serde_json::Value = serde_json::from_str(stdout)?;
+        .failure()
+        .stderr_eq(str![[r#"
+...
+Cannot commit to 'bar': the selected changes are locked to commits on another branch.
+  first
+    line 1 depends on foo ([..])
+...
+"#]]);
+}
 
-    let rejected = json["rejected"].as_array().expect("rejected array");
-    assert_eq!(rejected.len(), 1, "exactly one change should be rejected");
-    let change = &rejected[0];
-    assert_eq!(change["path"], "first");
-    assert!(change["reason"].is_string(), "reason should be a string");
-    let commit = &change["dependencies"][0]["commits"][0];
-    assert_eq!(commit["branch"], "foo");
-    assert!(
-        commit["commitId"].as_str().is_some_and(|id| id.len() == 40),
-        "commitId should serialize as a full hex object id, got {:?}",
-        commit["commitId"]
-    );
+#[test]
+fn commit_refuses_hunks_locked_to_another_stack() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    util::commit_file_with_worktree_changes_as_two_hunks(&env, "A", "a.txt");
 
-    Ok(())
+    // Both hunks edit lines introduced by the commit on A.
+    env.but("commit B -m 'edit a elsewhere'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+Cannot commit to 'B': the selected changes are locked to commits on another branch.
+  a.txt
+    line 1 depends on A ([..])
+...
+"#]]);
+
+    // Committing them to the branch they are locked to works.
+    env.but("commit A -m 'edit a'")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+✓ Created commit [..] on branch A
+
+"#]]);
 }
 
 #[test]
@@ -263,6 +764,22 @@ fn commit_with_branch_hint() {
     assert!(log.contains("Change for B"));
 }
 
+#[test]
+fn commit_with_fully_qualified_branch_hint() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+
+    env.file("file-for-b.txt", "content for B");
+
+    env.but("commit -m 'Change for B' refs/heads/B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+✓ Created commit [..] on branch B
+
+"#]]);
+}
+
 #[test]
 fn commit_with_nonexistent_branch_fails() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
@@ -433,6 +950,34 @@ fn commit_empty_with_message() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn commit_empty_json_reports_rewritten_commits() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let output = env
+        .but("commit empty --before 9477ae7 --format json")
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout)?;
+
+    let status = util::status_json(&env)?;
+    let commits = &status["stacks"][0]["branches"][0]["commits"];
+    assert_eq!(
+        json["commit_id"], commits[1]["commitId"],
+        "the blank commit is placed below the commit it was inserted before"
+    );
+    let (_, rewritten_a) = json["rewritten_commits"]
+        .as_object()
+        .expect("rewritten commits are an object")
+        .iter()
+        .find(|(old, _)| old.starts_with("9477ae7"))
+        .expect("the commit above the blank commit was rewritten");
+    assert_eq!(*rewritten_a, commits[0]["commitId"]);
+
+    Ok(())
+}
+
 #[test]
 fn commit_empty_rejects_empty_message() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
@@ -1225,6 +1770,49 @@ fn chained_commits_reuse_uncommitted_ids_from_one_status() -> anyhow::Result<()>
     Ok(())
 }
 
+#[test]
+fn commit_refuses_hunk_id_whose_hunk_changed_since_status() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("file.txt", "shown\n");
+    let status = util::status_json(&env)?;
+    let hunk_id = status["uncommittedChanges"][0]["hunks"][0]["cliId"]
+        .as_str()
+        .expect("the hunk of file.txt should have a CLI ID")
+        .to_owned();
+    let (_, hunk_part) = hunk_id.split_once(':').expect("a hunk ID");
+
+    // Change the file so that the ID of its new hunk starts like the one shown before, which is
+    // derived from the hash of the diff lines.
+    let content = (0..1000)
+        .map(|i| format!("changed {i}\n"))
+        .find(|content| {
+            let mut hasher = gix::hash::hasher(gix::hash::Kind::Sha1);
+            hasher.update(format!("+{content}").as_bytes());
+            hasher
+                .try_finalize()
+                .is_ok_and(|hash| hash.to_string().starts_with(hunk_part))
+        })
+        .expect("content with a colliding hunk ID");
+    env.file("file.txt", content);
+
+    env.but(format!("commit A -m 'Stale' --changes {hunk_id}"))
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+The ID '[..]' is stale, the change it was shown for is gone. Re-run `but status` to see the current IDs
+...
+"#]]);
+
+    util::status_json(&env)?;
+    env.but(format!("commit A -m 'Fresh' --changes {hunk_id}"))
+        .assert()
+        .success();
+    Ok(())
+}
+
 #[test]
 fn commit_with_short_changes_flag() -> anyhow::Result<()> {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
@@ -1394,6 +1982,44 @@ fn commit_with_filename_shadowed_by_branch_of_same_name_succeeds() -> anyhow::Re
     Ok(())
 }
 
+#[test]
+fn commit_with_directory_and_glob_changes() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("src/auth/login.rs", "login\n");
+    env.file("src/auth/token.rs", "token\n");
+    env.file("src/main.rs", "main\n");
+    env.file("README.md", "readme\n");
+
+    env.but("commit A -m 'auth' --changes src/auth")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+'src/auth' matches 2 files:
+  src/auth/login.rs
+  src/auth/token.rs
+...
+"#]]);
+    assert_eq!(uncommitted_file_count(&env), 2);
+
+    env.but("commit A -m 'rust' --changes 'src/*.rs'")
+        .assert()
+        .success();
+    assert_eq!(uncommitted_file_count(&env), 1, "only README.md is left");
+    assert_eq!(branch_commit_messages(&env, "A")[..2], ["rust", "auth"]);
+
+    env.but("commit A -m 'nothing' --changes 'lib/**'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Invalid file ID(s):
+  'lib/**' matches no changed paths. The closest are:
+  README.md
+
+"#]]);
+    Ok(())
+}
+
 #[test]
 fn commit_with_empty_file_list_uses_all_files() -> anyhow::Result<()> {
     let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
@@ -1982,3 +2608,82 @@ mod concurrent_commits {
         Ok(())
     }
 }
+
+#[test]
+fn commit_create_with_anchor_stacks_the_new_branch() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("stacked.txt", "content\n");
+    env.but("commit -c stacked --anchor A -m 'Add stacked'")
+        .assert()
+        .success()
+        .stderr_eq(str![])
+        .stdout_eq(str![[r#"
+Created new branch 'stacked' stacked on A
+Stack (top to bottom):
+  stacked (new)
+  A
+...
+"#]]);
+
+    let message = env.invoke_git("log -1 --format=%s stacked");
+    assert_eq!(message, "Add stacked");
+    let parent_of_stacked = env.invoke_git("rev-parse stacked~1");
+    let tip_of_a = env.invoke_git("rev-parse A");
+    assert_eq!(parent_of_stacked, tip_of_a, "the commit is stacked on A");
+}
+
+#[test]
+fn commit_create_with_anchor_in_different_stack_fails() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.but("branch new B").assert().success();
+
+    env.file("stacked.txt", "content\n");
+    env.but("commit -c B --anchor A -m 'Add stacked'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Branch 'B' already exists, but isn't stacked right on anchor 'A'
+
+"#]]);
+}
+
+#[test]
+fn commit_create_with_anchor_requires_existing_branch_right_above_it() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings(
+        "one-stack-three-dependent-branches",
+    );
+    env.setup_metadata(&["A", "B", "C"]);
+
+    env.file("stacked.txt", "content\n");
+    env.but("commit -c C --anchor A -m 'Add stacked'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Branch 'C' already exists, but isn't stacked right on anchor 'A'
+
+"#]]);
+
+    env.but("commit -c B --anchor A -m 'Add stacked'")
+        .assert()
+        .success();
+    let message = env.invoke_git("log -1 --format=%s B");
+    assert_eq!(message, "Add stacked", "B is right above A, so it was used");
+}
+
+#[test]
+fn commit_anchor_requires_create() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("commit --anchor A -m 'Add stacked'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: the following required arguments were not provided:
+  --create
+...
+"#]]);
+}
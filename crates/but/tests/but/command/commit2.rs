@@ -501,8 +501,8 @@ fn empty_flag_to_force_empty_commit_when_changes_exist() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   vq A changes
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   vq A changes  +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 empty commit despite changes in worktree (no changes)
@@ -616,8 +616,8 @@ fn commit_above_commit() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv A file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   uv A file.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   ywx add second
@@ -664,8 +664,8 @@ fn commit_above_branch() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv A file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   uv A file.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -712,8 +712,8 @@ fn commit_below_commit() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv A file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   uv A file.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   ywx add second
@@ -760,8 +760,8 @@ fn commit_below_branch() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv A file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   uv A file.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -808,8 +808,8 @@ fn commit_below_branch_with_multiple_commits_treats_branch_as_bucket() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv A file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   uv A file.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   ywx add second
@@ -1052,9 +1052,9 @@ fn committing_specific_cli_ids() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   kl   A one
-┊   twop A two
+╭┄ zz [uncommitted] (2 files, +2 -0)
+┊   kl   A one  +1 -0
+┊   twop A two  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -1072,8 +1072,8 @@ Hint: run `but diff` to see uncommitted changes and `but commit <branch> -m "mes
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   twop A two
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   twop A two  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1 (no commit message)
@@ -1292,10 +1292,10 @@ fn can_commit_with_path_prefix() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   om A path/other/to/third.txt
-┊   ms A path/to/first.txt
-┊   rr A path/to/second.txt
+╭┄ zz [uncommitted] (3 files, +3 -0)
+┊   om A path/other/to/third.txt  +1 -0
+┊   ms A path/to/first.txt        +1 -0
+┊   rr A path/to/second.txt       +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -1313,8 +1313,8 @@ Hint: run `but diff` to see uncommitted changes and `but commit <branch> -m "mes
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   om A path/other/to/third.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   om A path/other/to/third.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1 (no commit message)
@@ -1353,10 +1353,10 @@ fn path_prefix_with_mix_of_modifications() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   lm D dir/to_delete.txt
-┊   no M dir/to_empty.txt
-┊   xv M dir/to_modify.txt
+╭┄ zz [uncommitted] (3 files, +2 -3)
+┊   lm D dir/to_delete.txt  +0 -1
+┊   no M dir/to_empty.txt   +0 -1
+┊   xv M dir/to_modify.txt  +2 -1
 ┊
 ┊╭┄ g0 [A]
 ┊●   1 (no commit message)
@@ -1559,9 +1559,9 @@ fn committing_below_an_empty_branch() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   kl   A one
-┊   twop A two
+╭┄ zz [uncommitted] (2 files, +2 -0)
+┊   kl   A one  +1 -0
+┊   twop A two  +1 -0
 ┊
 ┊╭┄ to [top] (no commits)
 ├╯
@@ -1580,8 +1580,8 @@ Hint: run `but diff` to see uncommitted changes and `but commit <branch> -m "mes
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   twop A two
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   twop A two  +1 -0
 ┊
 ┊╭┄ to [top] (no commits)
 ┊│
@@ -1805,7 +1805,9 @@ qs:78 file│
         .assert()
         .failure()
         .stderr_eq(snapbox::str![[r#"
-Error: Ambiguous uncommitted change 'qs:7', matches multiple items
+Error: Ambiguous uncommitted change 'qs:7', matches multiple items:
+  1. qs:7[..] - an uncommitted file or hunk (hunk @@ -[..] @@ in 'file')
+  2. qs:7[..] - an uncommitted file or hunk (hunk @@ -[..] @@ in 'file')
 
 Hint: Use a longer ID to disambiguate
 
@@ -1875,9 +1877,9 @@ Hint: run `but help` for all commands
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   qs M file
-┊   kw D file-2
+╭┄ zz [uncommitted] (2 files, +1 -2)
+┊   qs M file    +1 -1
+┊   kw D file-2  +0 -1
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 add files
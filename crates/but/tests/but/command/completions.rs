@@ -0,0 +1,100 @@
+use crate::utils::Sandbox;
+
+#[test]
+fn completions_work_without_a_repository() -> anyhow::Result<()> {
+    let env = Sandbox::empty();
+
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = env.but(format!("completion {shell}")).output()?;
+        assert!(
+            output.status.success(),
+            "{shell}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let hint = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(
+            hint.contains("only completes commands and options"),
+            shell == "powershell",
+            "{shell}: only shells without dynamic completions get a hint: {hint}"
+        );
+        let script = String::from_utf8(output.stdout)?;
+        for subcommand in ["branch", "completions"] {
+            assert!(
+                script.contains(subcommand),
+                "{shell} completions mention '{subcommand}'"
+            );
+        }
+        #[cfg(feature = "legacy")]
+        for subcommand in ["commit", "status"] {
+            assert!(
+                script.contains(subcommand),
+                "{shell} completions mention '{subcommand}'"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn dynamic_completions_offer_branches_and_changed_paths() {
+    use snapbox::str;
+
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("new-file.txt", "content");
+
+    env.but("__complete unapply ''")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+A
+B
+"#]]);
+    env.but("__complete commit -b B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+B
+"#]]);
+    env.but("__complete diff new")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+new-file.txt
+"#]]);
+    env.but("__complete commit -m ''")
+        .assert()
+        .success()
+        .stdout_eq(str![""]);
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn dynamic_completions_use_the_directory_given_with_c() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    let repo = env.projects_root().display().to_string();
+
+    env.but(format!(
+        "__complete -C {} unapply ''",
+        shell_words::quote(&repo)
+    ))
+    .current_dir(env.app_data_dir())
+    .assert()
+    .success()
+    .stdout_eq(snapbox::str![[r#"
+A
+B
+"#]]);
+}
+
+#[test]
+fn dynamic_completions_are_empty_outside_a_repository() {
+    let env = Sandbox::empty();
+
+    env.but("__complete unapply ''")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![""]);
+}
@@ -108,6 +108,110 @@ fn feature_config_json_output_uses_stable_key() {
 "#]]);
 }
 
+#[test]
+fn settings_set_and_get_round_trip_through_settings_file() -> anyhow::Result<()> {
+    let env = Sandbox::empty();
+
+    env.but("config set feature_flags.single_branch false")
+        .assert()
+        .success();
+    env.but("config set fetch.auto_fetch_interval_minutes -1")
+        .assert()
+        .success();
+
+    let get_json = |key: &str| -> anyhow::Result<serde_json::Value> {
+        let output = env
+            .but(format!("--format json config get {key}"))
+            .allow_json()
+            .output()?;
+        assert!(output.status.success(), "getting '{key}' should succeed");
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+    let single_branch = get_json("feature_flags.single_branch")?;
+    assert_eq!(single_branch["value"], false);
+    assert_eq!(single_branch["source"], "default");
+    let interval = get_json("fetch.auto_fetch_interval_minutes")?;
+    assert_eq!(interval["value"], -1);
+    assert_eq!(interval["source"], "user");
+
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        env.app_data_dir().join("gitbutler/settings.json"),
+    )?)?;
+    assert_eq!(saved["featureFlags"]["singleBranch"], false);
+    assert_eq!(saved["fetch"]["autoFetchIntervalMinutes"], -1);
+    assert_eq!(
+        saved["irc"]["server"]["host"], "irc.example.com",
+        "unrelated customizations are preserved"
+    );
+    Ok(())
+}
+
+#[test]
+fn settings_passwords_are_never_printed() -> anyhow::Result<()> {
+    let env = Sandbox::empty();
+
+    env.but("config set irc.connection.sasl_password hunter2")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+✓ Set irc.connection.sasl_password to <redacted>
+
+"#]]);
+    env.but("config get irc.connection.sasl_password")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+<redacted> (user)
+
+"#]]);
+    env.but("config get irc.connection.server_password")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+<unset> (default)
+
+"#]]);
+
+    env.but("config set irc.connection.sasl_password null")
+        .assert()
+        .success();
+    env.but("config get irc.connection.sasl_password")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+<unset> (default)
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn settings_reject_unknown_keys_and_mistyped_values() -> anyhow::Result<()> {
+    let env = Sandbox::empty();
+
+    let output = env.but("config get fetch.auto_fetch_interval").output()?;
+    assert!(!output.status.success(), "unknown keys are rejected");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("did you mean 'fetch.auto_fetch_interval_minutes'?"),
+        "unexpected stderr: {stderr}"
+    );
+
+    let output = env
+        .but("config set feature_flags.single_branch maybe")
+        .output()?;
+    assert!(
+        !output.status.success(),
+        "values must match the setting type"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expects a boolean"),
+        "unexpected stderr: {stderr}"
+    );
+    Ok(())
+}
+
 #[test]
 fn ai_openai_defaults_to_global_config() {
     let env = Sandbox::empty();
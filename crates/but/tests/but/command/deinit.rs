@@ -0,0 +1,57 @@
+use crate::utils::{CommandExt as _, Sandbox};
+
+#[test]
+fn single_stack_is_checked_out_and_uncommitted_changes_are_kept() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("wip.txt", "work in progress\n");
+
+    let output = env.but("--format json deinit").allow_json().output()?;
+    assert!(
+        output.status.success(),
+        "deinit failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["checkedOutBranch"], "A");
+    assert_eq!(json["purged"], false);
+
+    assert_eq!(env.invoke_git("rev-parse --abbrev-ref HEAD"), "A");
+    env.invoke_git_fails(
+        "rev-parse --verify refs/heads/gitbutler/workspace",
+        "the workspace branch is deleted",
+    );
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("wip.txt"))?,
+        "work in progress\n",
+        "uncommitted changes survive"
+    );
+    Ok(())
+}
+
+#[test]
+fn multiple_stacks_require_a_branch_to_check_out() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+
+    let output = env.but("deinit").output()?;
+    assert!(!output.status.success(), "it's unclear which branch wins");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("2 stacks are applied, so it's unclear which branch to check out"),
+        "unexpected stderr: {stderr}"
+    );
+    assert_eq!(
+        env.invoke_git("rev-parse --abbrev-ref HEAD"),
+        "gitbutler/workspace",
+        "nothing changed"
+    );
+
+    env.but("deinit --checkout B --purge").assert().success();
+    assert_eq!(env.invoke_git("rev-parse --abbrev-ref HEAD"), "B");
+    assert!(
+        !env.projects_root().join(".git/gitbutler").exists(),
+        "the GitButler data is deleted"
+    );
+    Ok(())
+}
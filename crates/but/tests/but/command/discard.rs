@@ -53,10 +53,10 @@ fn discard_by_path_prefix_removes_only_matching_files() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   up A path/other/third.txt
-┊   ms A path/to/first.txt
-┊   rr A path/to/second.txt
+╭┄ zz [uncommitted] (3 files, +3 -0)
+┊   up A path/other/third.txt  +1 -0
+┊   ms A path/to/first.txt     +1 -0
+┊   rr A path/to/second.txt    +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -74,8 +74,8 @@ Hint: run `but diff` to see uncommitted changes and `but commit <branch> -m "mes
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   up A path/other/third.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   up A path/other/third.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
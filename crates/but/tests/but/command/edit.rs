@@ -0,0 +1,112 @@
+use anyhow::Context as _;
+use snapbox::str;
+
+use crate::utils::Sandbox;
+
+fn current_branch_name(env: &Sandbox) -> anyhow::Result<String> {
+    let repo = env.open_repo();
+    repo.head_name()?
+        .map(|name| name.as_ref().shorten().to_string())
+        .context("HEAD should point to a branch")
+}
+
+#[test]
+fn edit_checks_out_commit_and_refuses_mutating_commands() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("edit tpm")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+Editing commit [..]
+...
+"#]]);
+    assert_eq!(current_branch_name(&env)?, "gitbutler/edit");
+
+    env.but("status").assert().success().stdout_eq(str![[r#"
+You are currently editing commit [..]
+...
+"#]]);
+
+    env.file("new-file.txt", "content\n");
+    env.but("commit -m 'not allowed'")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: This command can't run while a commit is being edited.
+...
+"#]]);
+    assert_eq!(current_branch_name(&env)?, "gitbutler/edit");
+    Ok(())
+}
+
+#[test]
+fn edit_continue_amends_commit_and_is_idempotent() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("edit tpm").assert().success();
+    env.file("A", "edited during edit mode\n");
+
+    env.but("edit --continue")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+Updated commit [..] → [..]
+Workspace restored with your uncommitted changes.
+
+"#]]);
+    assert_eq!(current_branch_name(&env)?, "gitbutler/workspace");
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("A"))?,
+        "edited during edit mode\n"
+    );
+
+    env.but("edit --continue")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+No commit is being edited, nothing to do.
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn edit_abort_restores_workspace() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    let log_before = env.git_log();
+
+    env.file("uncommitted.txt", "keep me\n");
+    env.but("edit tpm").assert().success();
+    env.file("A", "edited during edit mode\n");
+
+    env.but("edit --abort")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+Stopped editing commit [..], it is unchanged.
+Workspace restored with your uncommitted changes.
+
+"#]]);
+    assert_eq!(current_branch_name(&env)?, "gitbutler/workspace");
+    assert_eq!(env.git_log(), log_before);
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("uncommitted.txt"))?,
+        "keep me\n"
+    );
+
+    env.but("edit --abort")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+No commit is being edited, nothing to do.
+
+"#]]);
+    Ok(())
+}
@@ -138,7 +138,7 @@ fn changing_pushed_commit_does_not_cause_change_id_ambiguity() {
 ┊
 ┊╭┄ br [a-branch-1]
 ┊┊
-┊╭┄┄ (upstream: on origin/a-branch-1)
+┊╭┄┄ ↓ 1 incoming commit on origin/a-branch-1 (run `but branch update a-branch-1`)
 ┊●   a5caff1 second
 ┊-
 ┊◐   123 rewritten
@@ -45,6 +45,32 @@ Please run 'but setup' to initialize the project.
     }
 }
 
+#[test]
+#[cfg(feature = "legacy")]
+fn json_flag_reports_errors_as_json_on_stdout() {
+    use crate::utils::CommandExt;
+    let env = Sandbox::empty();
+
+    env.but("status --json")
+        .allow_json()
+        .assert()
+        .failure()
+        .stdout_eq(snapbox::str![[r#"
+{
+  "error": {
+    "message": "No git repository found at .\nPlease run 'but setup' to initialize the project.",
+    "kind": "internal"
+  }
+}
+
+"#]])
+        .stderr_eq(snapbox::str![[r#"
+Error: No git repository found at .
+Please run 'but setup' to initialize the project.
+
+"#]]);
+}
+
 #[test]
 #[cfg(feature = "legacy")]
 fn default_command_respects_c_flag_for_setup_checks() -> anyhow::Result<()> {
@@ -9,21 +9,30 @@ mod alias;
 #[cfg(feature = "legacy")]
 mod amend;
 #[cfg(feature = "legacy")]
+mod assign;
+#[cfg(feature = "legacy")]
 mod branch;
 #[cfg(feature = "legacy")]
+mod claude;
+#[cfg(feature = "legacy")]
 mod clean;
 #[cfg(feature = "legacy")]
 mod commit;
 #[cfg(feature = "legacy")]
 mod commit2;
+mod completions;
 mod config;
 #[cfg(feature = "legacy")]
+mod deinit;
+#[cfg(feature = "legacy")]
 mod diff;
 #[cfg(feature = "legacy")]
 mod diff2;
 #[cfg(feature = "legacy")]
 mod discard;
 #[cfg(feature = "legacy")]
+mod edit;
+#[cfg(feature = "legacy")]
 mod expand;
 #[cfg(unix)]
 mod external;
@@ -39,9 +48,13 @@ mod move2;
 mod onboarding;
 #[cfg(feature = "legacy")]
 mod open;
+#[cfg(all(feature = "legacy", target_os = "linux"))]
+mod paging;
 #[cfg(feature = "legacy")]
 mod pick;
 #[cfg(feature = "legacy")]
+mod pr;
+#[cfg(feature = "legacy")]
 mod pull;
 #[cfg(feature = "legacy")]
 mod push;
@@ -52,6 +65,8 @@ mod reword;
 #[cfg(feature = "legacy")]
 mod rub;
 #[cfg(feature = "legacy")]
+mod rule;
+#[cfg(feature = "legacy")]
 mod setup;
 mod skill;
 #[cfg(feature = "legacy")]
@@ -59,6 +74,8 @@ mod squash;
 #[cfg(feature = "legacy")]
 mod squash2;
 #[cfg(feature = "legacy")]
+mod stack;
+#[cfg(feature = "legacy")]
 mod status;
 mod r#switch;
 #[cfg(feature = "legacy")]
@@ -127,6 +144,29 @@ mod util {
         );
     }
 
+    /// Configure a fake Claude Code executable that records its arguments and prompt, and prints
+    /// `message`.
+    #[cfg(unix)]
+    pub fn fake_claude(env: &Sandbox, message: &str) -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        env.file(
+            ".git/claude.sh",
+            format!(
+                "#!/bin/sh\necho \"$@\" > \"$(dirname \"$0\")/claude-args\"\ncat > \"$(dirname \"$0\")/claude-prompt\"\nprintf '{message}\\n'\n"
+            ),
+        );
+        let executable = env.projects_root().join(".git/claude.sh");
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755))?;
+        env.but(format!(
+            "config set claude.executable {}",
+            executable.display()
+        ))
+        .assert()
+        .success();
+        Ok(())
+    }
+
     /// Return `but status` JSON output as a parsed value.
     pub fn status_json(env: &Sandbox) -> anyhow::Result<serde_json::Value> {
         let output = env.but("--format json status").allow_json().output()?;
@@ -300,8 +340,8 @@ mod util {
             .context("expected branch in status output")
     }
 
-    /// Create a conflicted edit-mode session by reordering commits and entering `resolve`.
-    pub fn enter_edit_mode_with_conflicted_commit(env: &Sandbox) -> anyhow::Result<()> {
+    /// Create a conflicted commit on `branchB` by reordering commits, returning its CLI ID.
+    pub fn create_conflicted_commit(env: &Sandbox) -> anyhow::Result<String> {
         env.but("branch new branchB").assert().success();
 
         env.file("test-file.txt", "line 1\nline 2\nline 3\n");
@@ -337,7 +377,12 @@ mod util {
             .find(|commit| commit["conflicted"].as_bool() == Some(true))
             .and_then(|commit| commit["cliId"].as_str())
             .context("should find conflicted commit cli id")?;
+        Ok(conflicted_commit_cli_id.to_owned())
+    }
 
+    /// Create a conflicted edit-mode session by reordering commits and entering `resolve`.
+    pub fn enter_edit_mode_with_conflicted_commit(env: &Sandbox) -> anyhow::Result<()> {
+        let conflicted_commit_cli_id = create_conflicted_commit(env)?;
         env.but(format!("resolve {conflicted_commit_cli_id}"))
             .assert()
             .success();
@@ -2472,8 +2472,8 @@ fn cannot_move_from_uncommitted() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   qs A file
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   qs A file  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   tpm add A
@@ -59,8 +59,8 @@ fn open_uncommitted_file_with() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   xk A new-file.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   xk A new-file.txt  +1 -0
 ┊
 ┴ 0dc3733 (common base) 2000-01-02 add M
 
@@ -223,8 +223,8 @@ fn open_uncommitted_hunk_in_file_that_contains_spaces_and_shell_metacharacters()
     );
 
     env.but("status").assert().success().stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   pv M file with some $meta; cat A > new-file.txt; spaces in it.txt
+╭┄ zz [uncommitted] (1 file, +2 -0)
+┊   pv M file with some $meta; cat A > new-file.txt; spaces in it.txt  +2 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 Add file
@@ -359,8 +359,8 @@ fn user_defined_program_path_executable_handles_shell_metacharacters() {
     .unwrap();
 
     env.but("status -f").assert().success().stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   pv M file with some $meta; cat A > new-file.txt; spaces in it.txt
+╭┄ zz [uncommitted] (1 file, +2 -0)
+┊   pv M file with some $meta; cat A > new-file.txt; spaces in it.txt  +2 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 Add file
@@ -461,8 +461,8 @@ fn user_defined_program_defaults_to_default_open_args() {
     .unwrap();
 
     env.but("status -f").assert().success().stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   uv M file.txt
+╭┄ zz [uncommitted] (1 file, +2 -0)
+┊   uv M file.txt  +2 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 Add file
@@ -721,10 +721,10 @@ filepath='/[..]/file.txt'
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   tt A Dockerfile
-┊   zn A file.md
-┊   uv A file.txt
+╭┄ zz [uncommitted] (3 files, +3 -0)
+┊   tt A Dockerfile  +1 -0
+┊   zn A file.md     +1 -0
+┊   uv A file.txt    +1 -0
 ┊
 ┴ 0dc3733 (common base) 2000-01-02 add M
 
@@ -742,11 +742,11 @@ Hint: run `but branch new` to create a new branch to work on
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   tt A Dockerfile
-┊   zn A file.md
-┊   ul A file.md.touch
-┊   uv A file.txt
+╭┄ zz [uncommitted] (4 files, +3 -0)
+┊   tt A Dockerfile     +1 -0
+┊   zn A file.md        +1 -0
+┊   ul A file.md.touch  +0 -0
+┊   uv A file.txt       +1 -0
 ┊
 ┴ 0dc3733 (common base) 2000-01-02 add M
 
@@ -764,12 +764,12 @@ Hint: run `but branch new` to create a new branch to work on
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   tt A Dockerfile
-┊   mu A Dockerfile.touch
-┊   zn A file.md
-┊   ul A file.md.touch
-┊   uv A file.txt
+╭┄ zz [uncommitted] (5 files, +3 -0)
+┊   tt A Dockerfile        +1 -0
+┊   mu A Dockerfile.touch  +0 -0
+┊   zn A file.md           +1 -0
+┊   ul A file.md.touch     +0 -0
+┊   uv A file.txt          +1 -0
 ┊
 ┴ 0dc3733 (common base) 2000-01-02 add M
 
@@ -0,0 +1,73 @@
+//! Paging only happens if stdout is a terminal, so `but` runs in a pseudo-terminal here.
+
+use crate::utils::Sandbox;
+
+/// Return a sandbox whose `core.pager` prints `paged by core-pager` before the output,
+/// along with a `GIT_PAGER` value which prints `paged by git-pager` instead.
+fn sandbox_with_pagers() -> (Sandbox, String) {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file(".git/pager.sh", "printf 'paged by %s\\n' \"$1\"\ncat\n");
+    let pager = env.projects_root().join(".git/pager.sh");
+    let pager = shell_words::quote(&pager.display().to_string()).into_owned();
+    env.invoke_git(&format!(
+        "config core.pager {}",
+        shell_words::quote(&format!("sh {pager} core-pager"))
+    ));
+    (env, format!("sh {pager} git-pager"))
+}
+
+fn stdout_of(cmd: snapbox::cmd::Command) -> String {
+    let output = cmd.output().expect("script can run");
+    assert!(
+        output.status.success(),
+        "command failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn show_is_paged_with_git_pager_over_core_pager() {
+    let (env, git_pager) = sandbox_with_pagers();
+    let commit = env.invoke_git("rev-parse A");
+
+    let stdout = stdout_of(env.but_in_terminal(format!("show {commit}")));
+    assert!(
+        stdout.contains("paged by core-pager"),
+        "core.pager is used: {stdout}"
+    );
+
+    let stdout = stdout_of(
+        env.but_in_terminal(format!("show {commit}"))
+            .env("GIT_PAGER", &git_pager),
+    );
+    assert!(
+        stdout.contains("paged by git-pager") && !stdout.contains("core-pager"),
+        "GIT_PAGER is used over core.pager: {stdout}"
+    );
+}
+
+#[test]
+fn oplog_is_paged() {
+    let (env, _) = sandbox_with_pagers();
+
+    let stdout = stdout_of(env.but_in_terminal("oplog"));
+    assert!(stdout.contains("paged by core-pager"), "{stdout}");
+}
+
+#[test]
+fn no_pager_is_a_global_flag() {
+    let (env, git_pager) = sandbox_with_pagers();
+    let commit = env.invoke_git("rev-parse A");
+
+    for args in [
+        format!("--no-pager show {commit}"),
+        format!("show {commit} --no-pager"),
+    ] {
+        let stdout = stdout_of(env.but_in_terminal(&args).env("GIT_PAGER", &git_pager));
+        assert!(!stdout.contains("paged by"), "{args}: {stdout}");
+    }
+    let stdout = stdout_of(env.but_in_terminal("oplog --no-pager"));
+    assert!(!stdout.contains("paged by"), "{stdout}");
+}
@@ -64,6 +64,39 @@ fn pick_by_short_sha() {
     ));
 }
 
+#[test]
+fn pick_by_uppercase_sha_prefix() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("pick-from-unapplied");
+    env.setup_metadata(&["applied-branch"]);
+
+    let prefix = get_commit_sha(&env, "refs/gitbutler/pickable-first")[..8].to_uppercase();
+    env.but(format!("pick {prefix} applied-branch"))
+        .assert()
+        .success();
+
+    assert!(branch_has_commit_message(
+        &env,
+        "applied-branch",
+        "first pickable commit"
+    ));
+}
+
+#[test]
+fn show_unapplied_commit_by_sha_prefix() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("pick-from-unapplied");
+    env.setup_metadata(&["applied-branch"]);
+
+    let sha = get_commit_sha(&env, "refs/gitbutler/pickable-first");
+    env.but(format!("show {}", &sha[..8]))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+...
+[..]first pickable commit[..]
+...
+"#]]);
+}
+
 #[test]
 fn pick_by_branch_name() {
     let env = Sandbox::init_scenario_with_target_and_default_settings("pick-from-unapplied");
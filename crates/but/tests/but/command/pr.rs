@@ -0,0 +1,35 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt, Sandbox};
+
+#[test]
+fn open_suggests_creating_a_review_if_there_is_none() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("pr open A --print")
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+Error: Failed to open the forge review.
+
+Caused by:
+    Branch 'A' has no review yet.
+    Run 'but pr create A' to create one.
+...
+"#]]);
+
+    env.but("pr open --print")
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+Error: Failed to open the forge review.
+
+Caused by:
+    No branch in the workspace has a review yet.
+    Run 'but pr create <branch>' to create one.
+...
+"#]]);
+}
@@ -289,6 +289,10 @@ Hint: origin/main moved ahead; run `but pull` to update the workspace
     env.but("pull").assert().success();
 
     env.but("status").assert().success().stdout_eq(str![[r#"
+⚠ Conflicted commits:
+  A nyo A-change (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
 ╭┄ zz [uncommitted] (no changes)
 ┊
 ┊╭┄ g0 [A]
@@ -401,8 +405,8 @@ fn pull_does_not_report_branch_rebase_conflicts_as_worktree_conflicts() -> anyho
     env.file("shared.txt", "local\nextra local work\n");
 
     env.but("status").assert().success().stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   ot M shared.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   ot M shared.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   vun local change
@@ -438,8 +442,12 @@ Hint: run `but diff` to see uncommitted changes and `but commit <branch> -m "mes
     );
 
     env.but("status").assert().success().stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   ot M shared.txt
+⚠ Conflicted commits:
+  A vun local change (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
+╭┄ zz [uncommitted] (1 file, +2 -1)
+┊   ot M shared.txt  +2 -1
 ┊
 ┊╭┄ g0 [A]
 ┊◐   vun local change (no changes) {conflicted}
@@ -503,6 +511,10 @@ Hint: origin/main moved ahead; run `but pull` to update the workspace
     );
 
     env.but("status").assert().success().stdout_eq(str![[r#"
+⚠ Conflicted commits:
+  A vun local change (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
 ╭┄ zz [uncommitted] (no changes)
 ┊
 ┊╭┄ g0 [A]
@@ -568,6 +580,10 @@ To undo this operation:
 "#]]);
 
     env.but("status").assert().success().stdout_eq(str![[r#"
+⚠ Conflicted commits:
+  B rou bottom change (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
 ╭┄ zz [uncommitted] (no changes)
 ┊
 ┊╭┄ g0 [A]
@@ -638,6 +654,11 @@ To undo this operation:
 "#]]);
 
     env.but("status").assert().success().stdout_eq(str![[r#"
+⚠ Conflicted commits:
+  A wmr top change (1 conflicted file)
+  B trk bottom change (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
 ╭┄ zz [uncommitted] (no changes)
 ┊
 ┊╭┄ g0 [A]
@@ -1,7 +1,7 @@
 use anyhow::Context as _;
 use snapbox::str;
 
-use super::util::enter_edit_mode_with_conflicted_commit;
+use super::util::{create_conflicted_commit, enter_edit_mode_with_conflicted_commit};
 use crate::utils::Sandbox;
 
 fn current_branch_name(env: &Sandbox) -> anyhow::Result<String> {
@@ -110,3 +110,48 @@ If you want to keep the changes you have made, consider finishing the resolution
     assert_eq!(current_branch_name(&env)?, "gitbutler/workspace");
     Ok(())
 }
+
+#[test]
+fn resolve_without_arguments_lists_conflicted_files() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    create_conflicted_commit(&env)?;
+
+    env.but("resolve")
+        .assert()
+        .success()
+        .stderr_eq(str![""])
+        .stdout_eq(str![[r#"
+Found conflicted commits:
+
+Branch: branchB
+  [..]second commit
+      test-file.txt
+
+Run `but resolve <commit-id>` to start resolving a commit.
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn resolve_with_tool_runs_configured_merge_tool() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    let conflicted_commit_cli_id = create_conflicted_commit(&env)?;
+    env.invoke_git("config merge.tool take-remote");
+    env.invoke_git(r#"config mergetool.take-remote.cmd 'cp "$REMOTE" "$MERGED"'"#);
+
+    env.but(format!("resolve {conflicted_commit_cli_id} --tool"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+...
+✓ test-file.txt
+...
+"#]]);
+    assert_eq!(current_branch_name(&env)?, "gitbutler/edit");
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("test-file.txt"))?,
+        "line 1\nline 2\nline 3\nline 4\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use snapbox::str;
+
+use crate::utils::{CommandExt as _, Sandbox};
+
+#[test]
+fn rules_assign_unassigned_and_new_changes_but_not_unassigned_ones() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("docs/guide.md", "guide\n");
+    env.file("src/main.rs", "main\n");
+
+    env.but("rule add --path docs/ --branch A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Added rule: docs/ → A
+Assigned 1 unassigned hunk that matches it.
+
+"#]]);
+
+    env.file("docs/faq.md", "faq\n");
+    env.but("status").assert().success().stdout_eq(str![[r#"
+...
+[..]docs/faq.md (auto: A)
+[..]docs/guide.md (auto: A)
+[..]src/main.rs
+...
+"#]]);
+
+    env.but("unassign --all --branch A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] docs/faq.md (1 hunk from A)
+  [..] docs/guide.md (1 hunk from A)
+
+"#]]);
+
+    env.file("docs/faq.md", "faq\nmore\n");
+    env.but("unassign --all --branch A")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+...
+  A: nothing is assigned to 'A'
+...
+"#]]);
+
+    let output = env
+        .but("--format json rule add --path 'src/*.rs' --branch B")
+        .allow_json()
+        .output()?;
+    assert!(output.status.success());
+    let outcome: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(outcome["path"], "src/*.rs");
+    assert_eq!(outcome["branch"], "B");
+    assert_eq!(outcome["assignedHunks"], 1);
+    Ok(())
+}
+
+#[test]
+fn list_rules_and_remove_them_by_id_or_when_their_branch_is_gone() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("docs/guide.md", "guide\n");
+
+    env.but("rule list").assert().success().stdout_eq(str![[r#"
+No rules. Add one with `but rule add --path <glob> --branch <branch>`.
+
+"#]]);
+
+    env.but("rule add --path docs/ --branch A")
+        .assert()
+        .success();
+    env.but("rule add --path 'src/*.rs' --branch B")
+        .assert()
+        .success();
+    env.invoke_git("branch -D B");
+
+    env.but("rule").assert().success().stdout_eq(str![[r#"
+[..] docs/    → A
+[..] src/*.rs → B (branch doesn't exist)
+
+"#]]);
+
+    env.but("rule remove --prune")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Removed:
+  [..] src/*.rs → B
+Changes that were assigned by these rules stay assigned.
+
+"#]]);
+
+    env.but("rule remove --prune")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+All rules point to branches that exist.
+
+"#]]);
+
+    let output = env.but("--format json rule list").allow_json().output()?;
+    assert!(output.status.success());
+    let rules: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(rules.as_array().map(Vec::len), Some(1));
+    assert_eq!(rules[0]["path"], "docs/");
+    assert_eq!(rules[0]["branch"], "A");
+    assert_eq!(rules[0]["branchExists"], true);
+    let id = rules[0]["shortId"].as_str().expect("short ID");
+
+    env.but(format!("rule remove {id}"))
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Removed:
+  [..] docs/ → A
+Changes that were assigned by these rules stay assigned.
+
+"#]]);
+
+    env.but("unassign --all --branch A")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Unassigned:
+  [..] docs/guide.md (1 hunk from A)
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn failing_to_add_a_rule_reports_the_error_as_json() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+
+    let output = env
+        .but("--json rule add --path docs/ --branch does-not-exist")
+        .allow_json()
+        .output()?;
+    assert_eq!(output.status.code(), Some(1), "the exit code is unchanged");
+    let outcome: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(outcome["error"]["kind"], "internal");
+    let message = outcome["error"]["message"]
+        .as_str()
+        .expect("the message is a string");
+    assert!(
+        message.starts_with("Failed to add the rule.")
+            && message.contains("Could not find the branch or stack 'does-not-exist'"),
+        "unexpected message: {message}"
+    );
+    Ok(())
+}
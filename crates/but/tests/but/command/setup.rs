@@ -457,7 +457,15 @@ fn json_output_not_a_git_repo() {
 Error: No git repository found - run `but setup --init` to initialize a new repository.
 
 "#]])
-        .stdout_eq(snapbox::str![]);
+        .stdout_eq(snapbox::str![[r#"
+{
+  "error": {
+    "message": "No git repository found - run `but setup --init` to initialize a new repository.",
+    "kind": "internal"
+  }
+}
+
+"#]]);
 }
 
 #[test]
@@ -731,3 +739,193 @@ Learn more at https://docs.gitbutler.com/cli-overview
 
     Ok(())
 }
+
+#[test]
+fn target_flag_chooses_the_target_branch() -> anyhow::Result<()> {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-and-head");
+    env.invoke_git("update-ref refs/remotes/origin/develop refs/remotes/origin/main");
+
+    env.but("--format json setup --target origin/develop")
+        .allow_json()
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+{
+  "repositoryPath": "[..]",
+  "projectStatus": "alreadyexists",
+  "target": {
+    "branchName": "origin/develop",
+    "remoteName": "origin",
+    "newlySet": true
+  }
+}
+
+"#]]);
+
+    let output = env
+        .but("--format json config target")
+        .allow_json()
+        .output()?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["branch"], "origin/develop");
+    Ok(())
+}
+
+#[test]
+fn target_flag_rejects_missing_branches() {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-and-head");
+
+    env.but("setup --target origin/develop")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+...
+    The remote-tracking branch 'origin/develop' doesn't exist - pass `--fetch` to fetch the remote first.
+...
+"#]]);
+
+    env.but("setup --target nowhere/main")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+...
+    'nowhere/main' doesn't start with the name of a remote - pass the target as <remote>/<branch>, like `origin/main`.
+...
+"#]]);
+}
+
+#[test]
+fn no_remote_head_falls_back_to_upstream_of_current_branch() -> anyhow::Result<()> {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-no-head");
+    env.invoke_bash(
+        "git update-ref refs/remotes/origin/release refs/remotes/origin/main && \
+         git update-ref -d refs/remotes/origin/main && \
+         git config branch.main.remote origin && \
+         git config branch.main.merge refs/heads/release",
+    );
+
+    let output = env.but("--format json setup").allow_json().output()?;
+    assert!(
+        output.status.success(),
+        "setup failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["target"]["branchName"], "origin/release");
+    Ok(())
+}
+
+#[test]
+fn no_guess_requires_remote_head() {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-no-head");
+
+    env.but("setup --no-guess")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+...
+    No HEAD reference found for remote origin - looked for refs/remotes/origin/HEAD. Pass `--target <remote>/<branch>` to choose the target.
+...
+"#]]);
+}
+
+#[test]
+fn checked_out_branch_with_commits_is_adopted() -> anyhow::Result<()> {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-and-head");
+    env.invoke_git("checkout -b feature-x");
+    env.file("feature.txt", "feature\n");
+    env.invoke_git("add feature.txt");
+    env.invoke_git("commit -m 'add feature'");
+    env.file("wip.txt", "work in progress\n");
+
+    let output = env.but("--format json setup").allow_json().output()?;
+    assert!(
+        output.status.success(),
+        "setup failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["target"]["adoptedBranch"], "feature-x");
+
+    assert_eq!(
+        env.invoke_git("log -1 --format=%s feature-x"),
+        "add feature"
+    );
+    assert!(
+        env.invoke_git("log --format=%s gitbutler/workspace")
+            .lines()
+            .any(|subject| subject == "add feature"),
+        "the adopted commits are part of the workspace"
+    );
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("wip.txt"))?,
+        "work in progress\n",
+        "uncommitted changes survive"
+    );
+    Ok(())
+}
+
+#[test]
+fn no_adopt_leaves_checked_out_branch_unapplied() -> anyhow::Result<()> {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-and-head");
+    env.invoke_git("checkout -b feature-x");
+    env.file("feature.txt", "feature\n");
+    env.invoke_git("add feature.txt");
+    env.invoke_git("commit -m 'add feature'");
+
+    let output = env
+        .but("--format json setup --no-adopt")
+        .allow_json()
+        .output()?;
+    assert!(
+        output.status.success(),
+        "setup failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["target"]["adoptedBranch"], serde_json::Value::Null);
+    assert!(
+        !env.invoke_git("log --format=%s gitbutler/workspace")
+            .lines()
+            .any(|subject| subject == "add feature"),
+        "the branch isn't applied"
+    );
+    Ok(())
+}
+
+#[test]
+fn no_adopt_keeps_uncommitted_changes() -> anyhow::Result<()> {
+    let env = Sandbox::open_with_default_settings("repo-with-remote-and-head");
+    env.invoke_git("checkout -b feature-x");
+    env.file("feature.txt", "feature\n");
+    env.invoke_git("add feature.txt");
+    env.invoke_git("commit -m 'add feature'");
+    env.file("wip.txt", "work in progress\n");
+
+    let output = env.but("setup --no-adopt").output()?;
+    assert!(
+        output.status.success(),
+        "setup failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        std::fs::read_to_string(env.projects_root().join("wip.txt"))?,
+        "work in progress\n",
+        "uncommitted changes survive"
+    );
+    let status = env.but("--format json status").allow_json().output()?;
+    assert!(status.status.success(), "status failed");
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        status.contains("wip.txt"),
+        "the uncommitted change is still listed: {status}"
+    );
+    assert!(
+        !env.invoke_git("log --format=%s gitbutler/workspace")
+            .lines()
+            .any(|subject| subject == "add feature"),
+        "the branch isn't applied"
+    );
+    Ok(())
+}
@@ -66,7 +66,15 @@ fn skill_install_json_outside_repo_requires_path_instead_of_repo_context() {
         .allow_json()
         .assert()
         .failure()
-        .stdout_eq(str![[]])
+        .stdout_eq(str![[r#"
+{
+  "error": {
+    "message": "No supported agent was detected. In non-interactive mode, specify --path or --detect. Use --path <path> to choose an installation directory, or --detect to update an existing installation.",
+    "kind": "internal"
+  }
+}
+
+"#]])
         .stderr_eq(str![[r#"
 Error: No supported agent was detected. In non-interactive mode, specify --path or --detect. Use --path <path> to choose an installation directory, or --detect to update an existing installation.
 
@@ -930,10 +930,10 @@ fn amend_uncommitted_files_into_commit() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   kl   A one
-┊   or   A three
-┊   twop A two
+╭┄ zz [uncommitted] (3 files, +3 -0)
+┊   kl   A one    +1 -0
+┊   or   A three  +1 -0
+┊   twop A two    +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 (no commit message) (no changes)
@@ -957,8 +957,8 @@ Amended 7adb8e6 to create d2f176a
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   or A three
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   or A three  +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 (no commit message)
@@ -981,10 +981,10 @@ fn amend_all_uncommitted_changes_into_commit() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   kl   A one
-┊   or   A three
-┊   twop A two
+╭┄ zz [uncommitted] (3 files, +3 -0)
+┊   kl   A one    +1 -0
+┊   or   A three  +1 -0
+┊   twop A two    +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 (no commit message) (no changes)
@@ -1308,8 +1308,8 @@ Error: --target cannot be an empty branch
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   qs A file
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   qs A file  +1 -0
 ┊
 ┊╭┄ mi [middle] (no commits)
 ┊│
@@ -1366,8 +1366,8 @@ Uncommitted f55169f
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   or A three
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   or A three  +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1#0 add two
@@ -1427,8 +1427,8 @@ Uncommitted from f55169f
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   or A three
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   or A three  +1 -0
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1#0 add three (no changes)
@@ -1549,6 +1549,7 @@ fn committed_file_to_uncommitted_area() -> anyhow::Result<()> {
         .stdout_eq(snapbox::str![[r#"
 ...
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [],
   "stacks": [
     {
@@ -1613,11 +1614,20 @@ fn committed_file_to_uncommitted_area() -> anyhow::Result<()> {
         .stderr_eq(snapbox::str![""])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "pn",
       "filePath": "b.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 1,
+        "deletions": 1,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
   "stacks": [
@@ -1714,11 +1724,20 @@ fn uncommitted_hunk_to_commit() -> anyhow::Result<()> {
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 1,
+        "deletions": 1,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
 ...
@@ -2210,9 +2229,9 @@ Hint: run `but help` for all commands
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   qs M file
-┊   kw D file-2
+╭┄ zz [uncommitted] (2 files, +1 -2)
+┊   qs M file    +1 -1
+┊   kw D file-2  +0 -1
 ┊
 ┊╭┄ br [a-branch-1]
 ┊●   1 add files
@@ -2418,8 +2437,8 @@ fn squashing_into_branch_that_sits_below_empty_branch() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   qs A file
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   qs A file  +1 -0
 ┊
 ┊╭┄ br [a-branch-1] (no commits)
 ┊│
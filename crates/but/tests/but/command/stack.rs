@@ -0,0 +1,212 @@
+use snapbox::str;
+
+use crate::{
+    command::util::status_json,
+    utils::{CommandExt as _, Sandbox},
+};
+
+#[test]
+fn list_shows_applied_stacks_with_their_branches() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("stack list").assert().success().stdout_eq(str![[r#"
+j0 [..] (1 commit, 0 assigned files)
+  A 9477ae7 (1 commit)
+
+"#]]);
+
+    // Listing is what `but stack` does without a subcommand.
+    env.but("stack").assert().success().stdout_eq(str![[r#"
+j0 [..] (1 commit, 0 assigned files)
+  A 9477ae7 (1 commit)
+
+"#]]);
+}
+
+#[test]
+fn list_as_json_counts_assigned_files() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("new.txt", "content\n");
+    let status = status_json(&env)?;
+    let file_id = status["uncommittedChanges"][0]["cliId"]
+        .as_str()
+        .expect("the new file is uncommitted");
+    env.but(format!("assign {file_id} A")).assert().success();
+
+    let output = env.but("--format json stack list").allow_json().output()?;
+    let stacks: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stack_id = stacks[0]["id"].as_str().expect("stacks have an ID");
+    assert_eq!(
+        stacks,
+        serde_json::json!([{
+            "id": stack_id,
+            "cliId": "j0",
+            "applied": true,
+            "branches": [{
+                "name": "A",
+                "tip": "9477ae721ab521d9d0174f70e804ce3ff9f6fb56",
+                "commits": 1,
+            }],
+            "commits": 1,
+            "assignedFiles": 1,
+        }])
+    );
+    Ok(())
+}
+
+#[test]
+fn list_all_includes_unapplied_stacks() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.but("unapply B").assert().success();
+
+    env.but("stack list").assert().success().stdout_eq(str![[r#"
+[..] [..] (1 commit, 0 assigned files)
+  A [..] (1 commit)
+
+"#]]);
+
+    env.but("stack list --all")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[..] [..] (1 commit, 0 assigned files)
+  A [..] (1 commit)
+unapplied [..] (1 commit, 0 assigned files)
+  B [..] (1 commit)
+
+"#]]);
+}
+
+#[test]
+fn merge_stacks_b_on_top_of_a() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("new.txt", "content\n");
+    let status = status_json(&env)?;
+    let file_id = status["uncommittedChanges"][0]["cliId"]
+        .as_str()
+        .expect("the new file is uncommitted");
+    env.but(format!("assign {file_id} B")).assert().success();
+
+    env.but("stack merge A B")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[..] Stacked B on top of A:
+  B
+  A
+Assigned 1 hunk of B to the merged stack
+
+"#]]);
+
+    env.invoke_git("merge-base --is-ancestor A B");
+    let output = env.but("--format json stack list").allow_json().output()?;
+    let stacks: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stacks = stacks.as_array().expect("stacks are listed");
+    assert_eq!(stacks.len(), 1, "both stacks were merged into one");
+    assert_eq!(stacks[0]["branches"][0]["name"], "B");
+    assert_eq!(stacks[0]["branches"][1]["name"], "A");
+    assert_eq!(
+        stacks[0]["assignedFiles"], 1,
+        "the changes assigned to B stay with the merged stack"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_stacks_a_on_top_of_b() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+
+    env.but("stack merge A B --order b-first")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[..] Stacked A on top of B:
+  A
+  B
+
+"#]]);
+
+    env.invoke_git("merge-base --is-ancestor B A");
+}
+
+#[test]
+fn merge_keeps_the_branches_of_multi_branch_stacks() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings(
+        "two-stacks-one-single-and-ready-to-mingle-one-double",
+    );
+    env.setup_metadata(&["A", "B"]);
+
+    env.but("stack merge A C")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[..] Stacked C on top of A:
+  C
+  B
+  A
+
+"#]]);
+
+    let output = env.but("--format json stack list").allow_json().output()?;
+    let stacks: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stacks = stacks.as_array().expect("stacks are listed");
+    assert_eq!(stacks.len(), 1, "both stacks were merged into one");
+    assert_eq!(
+        env.invoke_git("log --format=%s A..B"),
+        "add B",
+        "B is stacked directly on A"
+    );
+    assert_eq!(
+        env.invoke_git("log --format=%s B..C"),
+        "add C",
+        "C stays on top of B with only its own commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_restores_both_stacks_if_stacking_conflicts() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings(
+        "two-stacks-conflicting-when-stacked",
+    );
+    env.setup_metadata(&["A", "B"]);
+    let tips_before = env.invoke_git("rev-parse A B");
+
+    env.but("stack merge A B")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: Merging the stacks would cause conflicts, so both were left untouched:
+...
+"#]]);
+
+    assert_eq!(
+        env.invoke_git("rev-parse A B"),
+        tips_before,
+        "the snapshot was restored, so both branches point to their original commits"
+    );
+    let output = env.but("--format json stack list").allow_json().output()?;
+    let stacks: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stacks = stacks.as_array().expect("stacks are listed");
+    assert_eq!(stacks.len(), 2, "both stacks are still separate");
+    Ok(())
+}
+
+#[test]
+fn merge_rejects_branches_of_the_same_stack() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("stack merge A A")
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+Error: 'A' and 'A' are already in the same stack
+
+"#]]);
+}
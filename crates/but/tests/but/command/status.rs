@@ -1,4 +1,4 @@
-use super::util::{enter_edit_mode_with_conflicted_commit, status_json};
+use super::util::{create_conflicted_commit, enter_edit_mode_with_conflicted_commit, status_json};
 use crate::utils::{CommandExt as _, Sandbox};
 use snapbox::IntoData;
 
@@ -168,11 +168,20 @@ fn json_shows_paths_as_strings() {
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "qu",
       "filePath": "test-file.txt",
-      "changeType": "added"
+      "changeType": "added",
+      "stat": {
+        "insertions": 1,
+        "deletions": 0,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
   "stacks": [
@@ -291,16 +300,33 @@ fn uncommitted_and_committed_file_cli_ids() -> anyhow::Result<()> {
         .stderr_eq(snapbox::str![])
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 2,
+        "deletions": 2,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     },
     {
       "cliId": "pn",
       "filePath": "b.txt",
-      "changeType": "modified"
+      "changeType": "modified",
+      "stat": {
+        "insertions": 1,
+        "deletions": 1,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
   "stacks": [
@@ -1365,17 +1391,17 @@ fn file_ids_are_nicely_aligned() {
         .assert()
         .success()
         .stdout_eq(snapbox::str![[r#"
-╭┄ zz [uncommitted]
-┊   rr A file-0.txt
-┊   kr A file-1.txt
-┊   tp A file-2.txt
-┊   vk A file-3.txt
-┊   wx A file-4.txt
-┊   wv A file-5.txt
-┊   wk A file-6.txt
-┊   xx A file-7.txt
-┊   mv A file-8.txt
-┊   zx A file-9.txt
+╭┄ zz [uncommitted] (10 files, +10 -0)
+┊   rr A file-0.txt  +1 -0
+┊   kr A file-1.txt  +1 -0
+┊   tp A file-2.txt  +1 -0
+┊   vk A file-3.txt  +1 -0
+┊   wx A file-4.txt  +1 -0
+┊   wv A file-5.txt  +1 -0
+┊   wk A file-6.txt  +1 -0
+┊   xx A file-7.txt  +1 -0
+┊   mv A file-8.txt  +1 -0
+┊   zx A file-9.txt  +1 -0
 ┊
 ┴ 0dc3733 (common base) 2000-01-02 add M
 
@@ -1439,3 +1465,628 @@ Hint: run `but help` for all commands
 
 "#]]);
 }
+
+#[test]
+fn refresh_prs_uses_cache_when_offline() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let cached = env.but("status").output()?;
+    assert!(cached.status.success());
+    let offline = env
+        .but("status --refresh-prs")
+        .env("BUT_OFFLINE", "1")
+        .output()?;
+    assert!(offline.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&offline.stdout),
+        String::from_utf8_lossy(&cached.stdout),
+        "the forge isn't contacted, so the status is the same as the cached one"
+    );
+    Ok(())
+}
+
+#[test]
+fn json_flag_lists_hunks_that_can_be_committed_individually() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", format!("first\n{}last\n", "line\n".repeat(100)));
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", format!("firsta\n{}lasta\n", "line\n".repeat(100)));
+
+    let status = env.but("status --json").allow_json().output()?;
+    assert!(status.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&status.stdout)?;
+    assert_eq!(status["schemaVersion"], 1);
+    let change = &status["uncommittedChanges"][0];
+    assert_eq!(change["filePath"], "a.txt");
+    assert!(
+        change.get("filePathBytes").is_none(),
+        "raw bytes are only needed for paths that aren't UTF-8"
+    );
+    let hunks = change["hunks"].as_array().expect("hunks are listed");
+    assert_eq!(hunks.len(), 2, "the first and the last line changed");
+    let first_hunk_id = hunks[0]["cliId"].as_str().expect("hunks have an ID");
+    assert!(first_hunk_id.starts_with(&format!("{}:", change["cliId"].as_str().unwrap())));
+    assert_eq!(hunks[0]["hunkHeader"]["oldStart"], 1);
+
+    env.but(format!("commit A -m first-line --changes {first_hunk_id}"))
+        .assert()
+        .success();
+    let hunks_after = status_json(&env)?["uncommittedChanges"][0]["hunks"].clone();
+    assert_eq!(
+        hunks_after.as_array().map(Vec::len),
+        Some(1),
+        "only the hunk that wasn't committed is left"
+    );
+    assert_eq!(hunks_after[0]["cliId"], hunks[1]["cliId"]);
+    Ok(())
+}
+
+#[test]
+fn porcelain_lists_uncommitted_changes_one_per_line() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("modified.txt", "before\n");
+    env.file("deleted.txt", "delete me\n");
+    env.file("renamed-before.txt", "rename me\n");
+    env.but("commit A -m base").assert().success();
+
+    env.file("added.txt", "added\n");
+    env.file("modified.txt", "after\n");
+    std::fs::remove_file(env.projects_root().join("deleted.txt"))?;
+    env.rename_file("renamed-before.txt", "renamed-after.txt");
+
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+?? - added.txt
+ D - deleted.txt
+ M - modified.txt
+R  - renamed-before.txt -> renamed-after.txt
+
+"#]]);
+
+    let output = env.but("status -z").output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout.as_slice(),
+        b"?? - added.txt\0 D - deleted.txt\0 M - modified.txt\0R  - renamed-after.txt\0renamed-before.txt\0",
+        "-z implies --porcelain and writes renames as new, then old path"
+    );
+
+    env.but("--format json status --porcelain")
+        .allow_json()
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+?? - added.txt
+ D - deleted.txt
+ M - modified.txt
+R  - renamed-before.txt -> renamed-after.txt
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn porcelain_shows_the_branch_changes_are_assigned_to() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    env.file("assigned.txt", "assigned\n");
+    env.file("unassigned.txt", "unassigned\n");
+    let status = status_json(&env)?;
+    let assigned_id = status["uncommittedChanges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|change| change["filePath"] == "assigned.txt")
+        .and_then(|change| change["cliId"].as_str())
+        .expect("the new file is uncommitted");
+    env.but(format!("assign {assigned_id} A"))
+        .assert()
+        .success();
+
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+?? A assigned.txt
+?? - unassigned.txt
+
+"#]]);
+
+    let output = env.but("status -z").output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout.as_slice(),
+        b"?? A assigned.txt\0?? - unassigned.txt\0"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn porcelain_quotes_non_utf8_paths_unless_nul_terminated() -> anyhow::Result<()> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt as _};
+
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+    std::fs::write(
+        env.projects_root().join(OsStr::from_bytes(b"caf\xe9.txt")),
+        "content\n",
+    )?;
+
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+?? - "caf/351.txt"
+
+"#]]);
+
+    let output = env.but("status -z").output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout.as_slice(), b"?? - caf\xe9.txt\0");
+    Ok(())
+}
+
+#[test]
+fn verbose_lists_hunks_of_uncommitted_files() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", format!("first\n{}last\n", "line\n".repeat(100)));
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", format!("firsta\n{}lasta\n", "line\n".repeat(100)));
+    env.file("binary.bin", b"\0\x01\x02binary\0");
+
+    env.but("status -v")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +2 -2)
+┊   nk M a.txt       +2 -2
+┊      nk:[..] @@ -1,[..] +1,[..] @@ first (locked to [..] on A)
+┊      nk:[..] @@ -[..] @@ last (locked to [..] on A)
+┊   [..] A binary.bin    bin
+┊      binary file added (9 B)
+┊
+...
+"#]]);
+
+    env.invoke_git("config but.status.maxHunksPerFile 1");
+    env.but("status -v")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +2 -2)
+┊   nk M a.txt       +2 -2
+┊      nk:[..] @@ -1,[..] +1,[..] @@ first (locked to [..] on A)
+┊      … 1 more
+┊   [..] A binary.bin    bin
+┊      binary file added (9 B)
+┊
+...
+"#]]);
+
+    env.but("status")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +2 -2)
+┊   nk M a.txt       +2 -2
+┊   [..] A binary.bin    bin
+┊
+...
+"#]]);
+}
+
+#[test]
+fn ids_flag_lists_hunks_without_verbose() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", format!("first\n{}last\n", "line\n".repeat(100)));
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", format!("firsta\n{}lasta\n", "line\n".repeat(100)));
+
+    env.but("status --ids")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (1 file, +2 -2)
+┊   nk M a.txt  +2 -2
+┊      nk:[..] @@ -1,[..] +1,[..] @@ first (locked to [..] on A)
+┊      nk:[..] @@ -[..] @@ last (locked to [..] on A)
+┊
+...
+"#]]);
+}
+
+#[test]
+fn verbose_marks_hunks_locked_to_commits_of_another_stack() {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    super::util::commit_file_with_worktree_changes_as_two_hunks(&env, "A", "a.txt");
+    env.file("new.txt", "new\n");
+
+    env.but("status -v")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +3 -2)
+┊   [..] M a.txt[..]
+┊      [..] @@ -1,[..] +1,[..] @@ first (locked to [..] on A)
+┊      [..] @@ -[..] @@ last (locked to [..] on A)
+┊   [..] A new.txt[..]
+┊      [..] @@ -1,0 +1,1 @@ new
+┊
+...
+"#]]);
+}
+
+#[test]
+fn ids_only_prints_tab_separated_file_and_hunk_ids() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", format!("first\n{}last\n", "line\n".repeat(100)));
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", format!("firsta\n{}lasta\n", "line\n".repeat(100)));
+    env.file("b.txt", "new\n");
+
+    let output = env.but("status --ids-only").output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .collect();
+    assert_eq!(
+        lines.len(),
+        5,
+        "two files, the first with two hunks: {stdout}"
+    );
+    assert_eq!(lines[0], ["nk", "a.txt"]);
+    assert_eq!(lines[1][1], "a.txt");
+    assert!(lines[1][0].starts_with("nk:"));
+    assert!(lines[1][2].starts_with("@@ -1,"));
+    assert_eq!(lines[3][1], "b.txt");
+    assert_eq!(lines[4][1], "b.txt");
+    assert!(lines[4][0].starts_with(&format!("{}:", lines[3][0])));
+
+    let status = status_json(&env)?;
+    assert_eq!(
+        status["uncommittedChanges"][0]["hunks"][0]["cliId"], lines[1][0],
+        "the IDs are the same as everywhere else"
+    );
+
+    env.but(format!("commit A -m first-line --changes {}", lines[1][0]))
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn branch_or_stack_argument_only_shows_its_stack() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("two-stacks");
+    env.setup_metadata(&["A", "B"]);
+    env.file("test-file.txt", "test content");
+
+    env.but("status A")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+┊╭┄ g0 [A]
+┊●   tpm add A
+├╯
+┊
+┴ 0dc3733 (common base) 2000-01-02 add M
+
+Hint: run `but help` for all commands
+
+"#]]);
+
+    let stack_b = status_json(&env)?["stacks"][1]["cliId"]
+        .as_str()
+        .expect("stacks have an ID")
+        .to_owned();
+    env.but(format!("status {stack_b} --show-unassigned"))
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   qu A test-file.txt  +1 -0
+┊
+┊╭┄ h0 [B]
+┊●   lrm add B
+├╯
+...
+"#]]);
+
+    let status = env.but("status --branch B --json").allow_json().output()?;
+    assert!(status.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&status.stdout)?;
+    assert_eq!(status["uncommittedChanges"], serde_json::json!([]));
+    let stacks = status["stacks"].as_array().expect("stacks are listed");
+    assert_eq!(stacks.len(), 1);
+    assert_eq!(stacks[0]["cliId"], stack_b);
+
+    env.but("status does-not-exist")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+Error: Could not find branch: 'does-not-exist'
+...
+"#]]);
+    env.but("status qu")
+        .assert()
+        .failure()
+        .stderr_eq(snapbox::str![[r#"
+Error: Cannot show the status of an uncommitted file or hunk, expected a branch or a stack
+...
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn renamed_files_are_shown_with_their_previous_path() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let numbered_lines =
+        |prefix: &str| -> String { (1..=20).map(|n| format!("{prefix} line {n}\n")).collect() };
+    env.file("pure-before.txt", numbered_lines("pure"));
+    env.file("edited-before.txt", numbered_lines("edited"));
+    env.but("commit A -m base").assert().success();
+
+    env.rename_file("pure-before.txt", "pure-after.txt");
+    env.rename_file("edited-before.txt", "edited-after.txt");
+    env.file(
+        "edited-after.txt",
+        numbered_lines("edited").replace("edited line 10\n", "changed line 10\n"),
+    );
+
+    env.but("status")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +1 -1)
+┊   [..] R edited-before.txt -> edited-after.txt  +1 -1
+┊   [..] R pure-before.txt -> pure-after.txt      +0 -0
+┊
+...
+"#]]);
+
+    let status = status_json(&env)?;
+    let changes = status["uncommittedChanges"]
+        .as_array()
+        .expect("changes are listed");
+    assert_eq!(changes.len(), 2, "each rename is a single change");
+    assert_eq!(changes[0]["changeType"], "renamed");
+    assert_eq!(changes[0]["filePath"], "edited-after.txt");
+    assert_eq!(changes[0]["previousFilePath"], "edited-before.txt");
+    assert_eq!(changes[1]["previousFilePath"], "pure-before.txt");
+
+    env.but(format!(
+        "commit A -m rename --changes {}",
+        changes[0]["cliId"].as_str().expect("changes have an ID")
+    ))
+    .assert()
+    .success();
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+R  - pure-before.txt -> pure-after.txt
+
+"#]]);
+
+    env.invoke_git("config diff.renames false");
+    env.but("status --porcelain")
+        .assert()
+        .success()
+        .stdout_eq(snapbox::str![[r#"
+?? - pure-after.txt
+ D - pure-before.txt
+
+"#]]);
+    Ok(())
+}
+
+#[test]
+fn conflicted_commits_are_listed_at_the_top() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    let status = status_json(&env)?;
+    assert!(
+        status.get("conflicts").is_none(),
+        "a clean workspace has no conflicts section"
+    );
+
+    let conflicted_commit_cli_id = create_conflicted_commit(&env)?;
+
+    env.but("status")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+⚠ Conflicted commits:
+  branchB [..] second commit (1 conflicted file)
+Hint: run `but resolve <commit>` to resolve a conflicted commit
+
+...
+"#]]);
+
+    let status = status_json(&env)?;
+    let conflicts = status["conflicts"]
+        .as_array()
+        .expect("conflicts are listed in JSON");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0]["branch"], "branchB");
+    assert_eq!(conflicts[0]["cliId"], conflicted_commit_cli_id.as_str());
+    assert_eq!(conflicts[0]["subject"], "second commit");
+    assert_eq!(conflicts[0]["conflictedFileCount"], 1);
+    Ok(())
+}
+
+#[test]
+fn color_flag_overrides_terminal_detection_and_environment() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.but("status --color=always")
+        .env("COLORTERM", "truecolor")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::file![
+            "snapshots/status/hints/status-hint-clean-workspace.stdout.term.svg"
+        ]);
+
+    let output = env
+        .but("status --color=never")
+        .with_color_for_svg()
+        .output()?;
+    assert!(output.status.success());
+    assert!(
+        !output.stdout.contains(&b'\x1b'),
+        "`--color=never` wins over CLICOLOR_FORCE"
+    );
+    Ok(())
+}
+
+#[test]
+fn uncommitted_files_show_their_diff_stat() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", "one\ntwo\nthree\n");
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", "one\n2\nthree\nfour\n");
+    env.file("binary.bin", b"\0\x01\x02binary\0");
+
+    env.but("status")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted] (2 files, +2 -1)
+┊   [..] M a.txt       +2 -1
+┊   [..] A binary.bin    bin
+┊
+...
+"#]]);
+
+    env.but("status --no-stat")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted]
+┊   [..] M a.txt
+┊   [..] A binary.bin
+┊
+...
+"#]]);
+
+    let status = status_json(&env)?;
+    let changes = status["uncommittedChanges"]
+        .as_array()
+        .expect("changes are listed");
+    assert_eq!(
+        changes[0]["stat"],
+        serde_json::json!({"insertions": 2, "deletions": 1, "binary": false})
+    );
+    assert_eq!(changes[1]["stat"]["binary"], true);
+
+    let output = env
+        .but("--format json status --no-stat")
+        .allow_json()
+        .output()?;
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert!(
+        status["uncommittedChanges"][0].get("stat").is_none(),
+        "`--no-stat` omits the stat from JSON as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_directories_can_be_collapsed_and_files_grouped_by_directory() -> anyhow::Result<()> {
+    let env = Sandbox::init_scenario_with_target_and_default_settings("one-stack");
+    env.setup_metadata(&["A"]);
+
+    env.file("a.txt", "one\n");
+    env.but("commit A -m create-a").assert().success();
+    env.file("a.txt", "one\ntwo\n");
+    env.file("gen/a.txt", "a\n");
+    env.file("gen/b.txt", "b\n");
+    env.file("gen/sub/c.txt", "c\n");
+
+    env.but("status --no-stat")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted]
+┊   [..] M a.txt
+┊   [..] A gen/a.txt
+┊   [..] A gen/b.txt
+┊   [..] A gen/sub/c.txt
+┊
+...
+"#]]);
+
+    env.but("status --no-stat --collapse-untracked")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted]
+┊   [..] M a.txt
+┊   [..]A gen/ (3 files)
+┊
+...
+"#]]);
+
+    env.but("status --no-stat --collapse-untracked --no-collapse")
+        .assert()
+        .failure();
+
+    env.but("status --no-stat --group-by-dir")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![])
+        .stdout_eq(snapbox::str![[r#"
+╭┄ zz [uncommitted]
+┊   [..] M a.txt
+┊   gen/ (3 files)
+┊     [..] A gen/a.txt
+┊     [..] A gen/b.txt
+┊     [..] A gen/sub/c.txt
+┊
+...
+"#]]);
+
+    // The collapsed directory is the ID of all of its files.
+    env.but("diff gen/")
+        .assert()
+        .success()
+        .stderr_eq(snapbox::str![]);
+    Ok(())
+}
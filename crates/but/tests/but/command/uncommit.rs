@@ -182,9 +182,9 @@ Uncommitted changes
         .success()
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   ls A c1.txt
-┊   wy A c2.txt
+╭┄ zz [uncommitted] (2 files, +2 -0)
+┊   ls A c1.txt  +1 -0
+┊   wy A c2.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1#0 add c2 (no changes)
@@ -285,9 +285,9 @@ Uncommitted changes
         .success()
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   ls A c1.txt
-┊   wy A c2.txt
+╭┄ zz [uncommitted] (2 files, +2 -0)
+┊   ls A c1.txt  +1 -0
+┊   wy A c2.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1 add c1 and c2 (no changes)
@@ -396,8 +396,8 @@ Uncommitted changes
         .success()
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   sp A f.txt
+╭┄ zz [uncommitted] (1 file, +1 -0)
+┊   sp A f.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1#0 write v3 (no changes)
@@ -497,9 +497,9 @@ Uncommitted changes
         .success()
         .stderr_eq(str![])
         .stdout_eq(str![[r#"
-╭┄ zz [uncommitted]
-┊   sk A fa.txt
-┊   qq A fb.txt
+╭┄ zz [uncommitted] (2 files, +2 -0)
+┊   sk A fa.txt  +1 -0
+┊   qq A fb.txt  +1 -0
 ┊
 ┊╭┄ g0 [A]
 ┊●   1#0 add fa (no changes)
@@ -559,16 +559,33 @@ fn uncommit_command_on_commit() -> anyhow::Result<()> {
         .success()
         .stdout_eq(snapbox::str![[r#"
 {
+  "schemaVersion": 1,
   "uncommittedChanges": [
     {
       "cliId": "nk",
       "filePath": "a.txt",
-      "changeType": "added"
+      "changeType": "added",
+      "stat": {
+        "insertions": 9,
+        "deletions": 0,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     },
     {
       "cliId": "pn",
       "filePath": "b.txt",
-      "changeType": "added"
+      "changeType": "added",
+      "stat": {
+        "insertions": 9,
+        "deletions": 0,
+        "binary": false
+      },
+      "hunks": [
+...
+      ]
     }
   ],
   "stacks": [
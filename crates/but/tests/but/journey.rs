@@ -38,7 +38,7 @@ Error: No git repository found - run `but setup --init` to initialize a new repo
 
 "#]]);
 
-    // TODO: this should work, but we still have requirements and can't deal with any repo.
+    // Without any remote, a local-only target is set up that is good enough for everything but pushing.
     env.but("setup --init")
         .assert()
         .success()
@@ -94,6 +94,14 @@ Learn more at https://docs.gitbutler.com/cli-overview
 
 "#]]);
 
+    let output = env.but("push").output().unwrap();
+    assert!(!output.status.success(), "there is nothing to push to");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("This project has no remote to push to - configure a remote first"),
+        "unexpected stderr: {stderr}"
+    );
+
     // Forcefully add fake remote
     {
         env.append_file(
@@ -154,6 +162,38 @@ Hint: run `but branch new` to create a new branch to work on
         .stderr_eq(str![""]);
 }
 
+#[cfg(feature = "legacy")]
+#[test]
+fn from_sibling_directory() {
+    let env = Sandbox::empty();
+    env.invoke_bash("mkdir scripts repo");
+
+    // Like with Git, each `-C` is relative to the previous one.
+    env.but("-C scripts -C ../repo setup --init")
+        .assert()
+        .success();
+    assert!(
+        env.projects_root().join("repo/.git").is_dir(),
+        "the repository is initialized in the composed directory"
+    );
+    assert!(
+        !env.projects_root().join("scripts/.git").exists(),
+        "intermediate directories are left alone"
+    );
+
+    env.but("-C scripts --directory ../repo status")
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+╭┄ zz [uncommitted] (no changes)
+┊
+┴ 6f66116 (common base) 2000-01-02 Initial empty commit
+
+Hint: run `but branch new` to create a new branch to work on
+
+"#]]);
+}
+
 #[cfg(feature = "legacy")]
 #[test]
 fn from_workspace() {
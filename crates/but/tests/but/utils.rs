@@ -131,6 +131,27 @@ impl Sandbox {
             .env("NOPAGER", "1")
     }
 
+    /// Like [`Self::but()`], but run `but` with a pseudo-terminal as stdout using `script` from util-linux,
+    /// so it may start a pager. Pagers configured in the environment are ignored.
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    pub fn but_in_terminal(&self, args: impl AsRef<str>) -> snapbox::cmd::Command {
+        let but = snapbox::cmd::cargo_bin!("but").display().to_string();
+        let command = format!("{} {}", shell_words::quote(&but), args.as_ref());
+        let cmd = snapbox::cmd::Command::new("script").args([
+            "--quiet",
+            "--return",
+            "--command",
+            &command,
+            "/dev/null",
+        ]);
+        self.with_updated_env(cmd)
+            .env("GITBUTLER_CHANGE_ID", "42")
+            .env_remove("BUT_PAGER")
+            .env_remove("GIT_PAGER")
+            .env_remove("PAGER")
+    }
+
     fn with_updated_env(&self, cmd: snapbox::cmd::Command) -> snapbox::cmd::Command {
         isolate_snapbox_cmd_with_additional_removals(cmd, but::AGENT_ENVIRONMENT_VARIABLES)
             .env("E2E_TEST_APP_DATA_DIR", self.app_data_dir())
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+source "${BASH_SOURCE[0]%/*}/shared.sh"
+
+### General Description
+
+# Like `one-stack`, but `main` also has a submodule at `submodule` whose checked-out HEAD moved
+# to a new commit, so the superproject sees a change of its recorded commit.
+# The repository the submodule was cloned from is removed, the submodule is self-contained.
+git init submodule-origin
+(cd submodule-origin
+  commit-file sub-initial
+)
+
+git-init-frozen
+commit-file M
+git -c protocol.file.allow=always submodule add ./submodule-origin submodule
+git commit -m "add submodule"
+rm -rf submodule-origin
+setup_target_to_match_main
+
+git checkout -b A
+  commit-file A
+create_workspace_commit_once A
+
+(cd submodule
+  commit-file sub-new
+)
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+
+set -eu -o pipefail
+
+source "${BASH_SOURCE[0]%/*}/shared.sh"
+
+### General Description
+
+# A ws-ref points to a workspace commit, with two stacks inside that merge cleanly.
+# A changes `shared.txt`, while B changes it in its first commit and reverts it in its second,
+# so B can't be rebased onto A without conflicting.
+git-init-frozen
+echo base >shared.txt
+git add shared.txt
+git commit -m "base"
+setup_target_to_match_main
+
+git branch B
+git checkout -b A
+  echo A >shared.txt
+  commit "A changes shared"
+git checkout B
+  echo B >shared.txt
+  commit "B changes shared"
+  echo base >shared.txt
+  commit "B reverts shared"
+create_workspace_commit_once A B
@@ -14,7 +14,17 @@ pub fn set_base_branch(
     perm: &mut RepoExclusive,
 ) -> Result<BaseBranch> {
     let _ = ctx.create_snapshot(SnapshotDetails::new(OperationKind::SetBaseBranch), perm);
-    base::set_base_branch(ctx, perm.read_permission(), target_branch)
+    base::set_base_branch(ctx, perm.read_permission(), target_branch, true)
+}
+
+/// Like [`set_base_branch()`], but don't apply the branch `HEAD` points to as stack.
+pub fn set_base_branch_without_adopting_head(
+    ctx: &Context,
+    target_branch: &RemoteRefname,
+    perm: &mut RepoExclusive,
+) -> Result<BaseBranch> {
+    let _ = ctx.create_snapshot(SnapshotDetails::new(OperationKind::SetBaseBranch), perm);
+    base::set_base_branch(ctx, perm.read_permission(), target_branch, false)
 }
 
 pub fn set_target_push_remote(ctx: &mut Context, push_remote: &str) -> Result<()> {
@@ -147,10 +147,14 @@ fn go_back_to_integration(ctx: &Context, perm: &RepoShared) -> Result<BaseBranch
     get_base_branch_data(ctx, perm)
 }
 
+/// Set the target to `target_branch_ref`. If `adopt_head` is set and `HEAD` points to a branch
+/// with commits on top of the target or there are uncommitted changes, it's applied as stack.
+/// Otherwise, uncommitted changes are kept in a new empty stack.
 pub(crate) fn set_base_branch(
     ctx: &Context,
     perm: &RepoShared,
     target_branch_ref: &RemoteRefname,
+    adopt_head: bool,
 ) -> Result<BaseBranch> {
     let repo = ctx.repo.get()?;
 
@@ -223,7 +227,16 @@ pub(crate) fn set_base_branch(
         // put them into a virtual branch
 
         let changes = but_core::diff::worktree_changes(&*ctx.repo.get()?)?.changes;
-        if !changes.is_empty() || current_head_commit != target_commit_oid {
+        if !adopt_head {
+            // The commits of the head branch stay out of the workspace, but uncommitted changes
+            // still need a virtual branch to be kept in.
+            if !changes.is_empty() {
+                let branch_name = but_core::branch::canned_refname(&*ctx.repo.get()?)?
+                    .shorten()
+                    .to_string();
+                vb_state.set_stack(Stack::new_empty(ctx, branch_name, target_commit_oid, 0)?)?;
+            }
+        } else if !changes.is_empty() || current_head_commit != target_commit_oid {
             let (upstream, branch_matches_target) = if let Refname::Local(head_name) = &head_name {
                 let upstream_name = target_branch_ref.with_branch(head_name.branch());
                 if upstream_name.eq(target_branch_ref) {
@@ -299,7 +299,7 @@ fn verify_head_is_clean(ctx: &Context, perm: &mut RepoExclusive) -> Result<()> {
         .reset(workspace_commit.as_object(), git2::ResetType::Soft, None)
         .context("failed to reset to workspace commit")?;
 
-    let mut vb_handle = VirtualBranchesHandle::new(ctx.project_data_dir());
+    let mut vb_handle = VirtualBranchesHandle::new(ctx.shared_project_data_dir());
     let branch_manager = ctx.branch_manager();
     let mut new_branch = branch_manager
         .create_virtual_branch(
@@ -6,7 +6,7 @@
 
 mod actions;
 // This is our API
-pub use actions::{set_base_branch, set_target_push_remote};
+pub use actions::{set_base_branch, set_base_branch_without_adopting_head, set_target_push_remote};
 
 mod branch_manager;
 pub use branch_manager::BranchManagerExt;
@@ -31,7 +31,7 @@ trait VirtualBranchesExt {
 
 impl VirtualBranchesExt for but_ctx::Context {
     fn virtual_branches(&self) -> VirtualBranchesHandle {
-        VirtualBranchesHandle::new(self.project_data_dir())
+        VirtualBranchesHandle::new(self.shared_project_data_dir())
     }
 }
 
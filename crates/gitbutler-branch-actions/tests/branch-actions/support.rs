@@ -1,6 +1,6 @@
 #![expect(
     deprecated,
-    reason = "calls but_workspace::legacy::stacks_v3 and but_workspace::legacy::stack_details_v3"
+    reason = "calls but_workspace::legacy::all_stack_details_v3"
 )]
 
 use anyhow::Result;
@@ -43,37 +43,14 @@ pub fn hook_case() -> Result<HookCase> {
 
 pub fn stack_details(ctx: &Context) -> Vec<(StackId, StackDetails)> {
     let repo = ctx.clone_repo_for_merging_non_persisting().unwrap();
-    let stacks = {
-        let meta = ctx.legacy_meta().unwrap();
-        but_workspace::legacy::stacks_v3(
-            &repo,
-            &meta,
-            &ctx.project_meta().unwrap(),
-            ctx.graph_options(but_graph::init::Options::limited())
-                .unwrap(),
-            StacksFilter::default(),
-            None,
-        )
-    }
-    .unwrap();
-
-    stacks
-        .into_iter()
-        .filter_map(|stack| {
-            let stack_id = stack.id?;
-            let details = {
-                let meta = ctx.legacy_meta().unwrap();
-                but_workspace::legacy::stack_details_v3(
-                    stack_id.into(),
-                    &repo,
-                    &meta,
-                    &ctx.project_meta().unwrap(),
-                    ctx.graph_options(but_graph::init::Options::limited())
-                        .unwrap(),
-                )
-            }
-            .unwrap();
-            Some((stack_id, details))
-        })
-        .collect()
+    let meta = ctx.legacy_meta().unwrap();
+    but_workspace::legacy::all_stack_details_v3(
+        &repo,
+        &meta,
+        &ctx.project_meta().unwrap(),
+        ctx.graph_options(but_graph::init::Options::limited())
+            .unwrap(),
+        StacksFilter::default(),
+    )
+    .unwrap()
 }
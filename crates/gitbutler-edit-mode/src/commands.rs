@@ -38,7 +38,10 @@ pub fn enter_edit_mode(
     Ok(edit_mode_metadata)
 }
 
-pub fn save_and_return_to_workspace(ctx: &mut Context, perm: &mut RepoExclusive) -> Result<()> {
+pub fn save_and_return_to_workspace(
+    ctx: &mut Context,
+    perm: &mut RepoExclusive,
+) -> Result<gix::ObjectId> {
     ensure_edit_mode(ctx, perm.read_permission())
         .context("Edit mode may only be left while in edit mode")?;
 
@@ -56,6 +59,15 @@ pub fn abort_and_return_to_workspace(
     crate::abort_and_return_to_workspace(ctx, force, perm)
 }
 
+/// Remove edit-mode state left behind by an interrupted attempt to leave edit mode,
+/// keeping the stashed uncommitted changes. See [`crate::LeftoverEditModeState`].
+pub fn clear_leftover_edit_mode_state(
+    ctx: &Context,
+    perm: &mut RepoExclusive,
+) -> Result<crate::LeftoverEditModeState> {
+    crate::clear_leftover_edit_mode_state(ctx, perm)
+}
+
 pub fn starting_index_state(
     ctx: &Context,
     perm: &RepoShared,
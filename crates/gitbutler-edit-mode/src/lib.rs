@@ -19,7 +19,8 @@ use gitbutler_cherry_pick::{ConflictedTreeKey, GixRepositoryExt as _};
 use gitbutler_commit::commit_ext::{CommitExt, CommitMessageBstr};
 use gitbutler_operating_modes::{
     EDIT_BRANCH_REF, EditModeMetadata, INTEGRATION_BRANCH_REF, OperatingMode, WORKSPACE_BRANCH_REF,
-    delete_edit_mode_metadata, operating_mode, read_edit_mode_metadata, write_edit_mode_metadata,
+    delete_edit_mode_metadata, has_edit_mode_metadata, operating_mode, read_edit_mode_metadata,
+    write_edit_mode_metadata,
 };
 use gitbutler_workspace::branch_trees::{WorkspaceState, update_uncommitted_changes_with_tree};
 use gix::prelude::ObjectIdExt as _;
@@ -27,7 +28,8 @@ use serde::Serialize;
 
 pub mod commands;
 
-const UNCOMMITTED_CHANGES_REF: &str = "refs/gitbutler/edit-uncommitted-changes";
+/// The reference to the tree of uncommitted changes that were stashed when entering edit mode.
+pub const UNCOMMITTED_CHANGES_REF: &str = "refs/gitbutler/edit-uncommitted-changes";
 
 fn commit_title_to_merge_conflict_label(commit: &gix::Commit<'_>) -> String {
     gix::objs::commit::MessageRef::from_bytes(
@@ -246,6 +248,13 @@ pub(crate) fn enter_edit_mode(
 
     ensure_stack_in_workspace(ctx, stack_id)?;
 
+    if repo.try_find_reference(UNCOMMITTED_CHANGES_REF)?.is_some() {
+        bail!(
+            "Uncommitted changes of an interrupted edit are still stashed in {UNCOMMITTED_CHANGES_REF}.\n\
+             Restore them with `git restore --source={UNCOMMITTED_CHANGES_REF} --worktree -- .`, \
+             then remove the stash with `git update-ref -d {UNCOMMITTED_CHANGES_REF}`."
+        );
+    }
     commit_uncommited_changes(repo)?;
     write_edit_mode_metadata(ctx, &edit_mode_metadata).context("Failed to persist metadata")?;
     checkout_edit_branch(ctx, commit_oid).context("Failed to checkout edit branch")?;
@@ -270,6 +279,56 @@ fn cleanup_edit_mode(ctx: &Context, repo: &gix::Repository) -> Result<()> {
     Ok(())
 }
 
+/// What [`clear_leftover_edit_mode_state`] found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LeftoverEditModeState {
+    /// `true` if leftover state was found and removed.
+    pub cleaned_up: bool,
+    /// The tree with the uncommitted changes that were stashed when edit mode was entered, if
+    /// it is still referenced by [`UNCOMMITTED_CHANGES_REF`].
+    ///
+    /// It is never deleted here, as the interrupted attempt may not have restored these changes
+    /// into the worktree yet.
+    pub stashed_changes: Option<gix::ObjectId>,
+}
+
+/// Remove edit-mode state that an interrupted attempt to leave edit mode left behind.
+///
+/// Nothing is done while `HEAD` still points at [`EDIT_BRANCH_REF`], as edit mode is then
+/// still active. The uncommitted changes stashed under [`UNCOMMITTED_CHANGES_REF`] are kept
+/// and returned so they can be recovered.
+pub(crate) fn clear_leftover_edit_mode_state(
+    ctx: &Context,
+    _perm: &mut RepoExclusive,
+) -> Result<LeftoverEditModeState> {
+    let repo = &*ctx.repo.get()?;
+    if repo
+        .head_name()?
+        .is_some_and(|head_name| head_name.as_bstr() == EDIT_BRANCH_REF)
+    {
+        return Ok(LeftoverEditModeState::default());
+    }
+
+    let stashed_changes = repo
+        .try_find_reference(UNCOMMITTED_CHANGES_REF)?
+        .map(|mut reference| reference.peel_to_id().map(|id| id.detach()))
+        .transpose()?;
+    let edit_branch = repo.try_find_reference(EDIT_BRANCH_REF)?;
+    let cleaned_up = has_edit_mode_metadata(ctx) || edit_branch.is_some();
+    if let Some(reference) = edit_branch {
+        reference
+            .delete()
+            .with_context(|| format!("Failed to delete reference {EDIT_BRANCH_REF}"))?;
+    }
+    if cleaned_up {
+        delete_edit_mode_metadata(ctx)?;
+    }
+    Ok(LeftoverEditModeState {
+        cleaned_up,
+        stashed_changes,
+    })
+}
+
 pub(crate) fn abort_and_return_to_workspace(
     ctx: &Context,
     force: bool,
@@ -301,7 +360,13 @@ pub(crate) fn abort_and_return_to_workspace(
     Ok(())
 }
 
-pub(crate) fn save_and_return_to_workspace(ctx: &Context, perm: &mut RepoExclusive) -> Result<()> {
+/// Amend the worktree into the commit being edited, rebase its descendants and return to the workspace.
+///
+/// Returns the id of the rewritten commit.
+pub(crate) fn save_and_return_to_workspace(
+    ctx: &Context,
+    perm: &mut RepoExclusive,
+) -> Result<gix::ObjectId> {
     let edit_mode_metadata = read_edit_mode_metadata(ctx).context("Failed to read metadata")?;
     #[expect(deprecated, reason = "checkout/index materialization boundary")]
     let git2_repo = &*ctx.git2_repo.get()?;
@@ -409,7 +474,7 @@ pub(crate) fn save_and_return_to_workspace(ctx: &Context, perm: &mut RepoExclusi
 
     cleanup_edit_mode(ctx, repo)?;
 
-    Ok(())
+    Ok(new_commit_oid)
 }
 
 #[derive(Serialize, Debug, Clone)]
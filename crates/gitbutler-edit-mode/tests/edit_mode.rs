@@ -10,7 +10,8 @@ use but_ctx::Context;
 use but_meta::VirtualBranchesTomlMetadata;
 use but_testsupport::{gix_testtools, open_repo, visualize_commit_graph};
 use gitbutler_edit_mode::commands::{
-    abort_and_return_to_workspace, enter_edit_mode, save_and_return_to_workspace,
+    abort_and_return_to_workspace, clear_leftover_edit_mode_state, enter_edit_mode,
+    save_and_return_to_workspace,
 };
 use gitbutler_operating_modes::{
     EditModeMetadata, INTEGRATION_BRANCH_REF, read_edit_mode_metadata, write_edit_mode_metadata,
@@ -454,6 +455,70 @@ Some(
     Ok(())
 }
 
+#[test]
+fn clearing_leftovers_of_interrupted_continue_keeps_stashed_changes() -> Result<()> {
+    let (mut ctx, _tempdir) = command_ctx("conficted_entries_get_written_when_leaving_edit_mode")?;
+    let repo = ctx.repo.get()?;
+    let foobar = repo.head_commit()?.decode()?.parents().next().unwrap();
+    let worktree_dir = repo.workdir().unwrap().to_owned();
+    drop(repo);
+
+    std::fs::write(worktree_dir.join("uncommitted"), "stashed before editing\n")?;
+    let mut guard = ctx.exclusive_worktree_access();
+    let stack_id = {
+        let (_repo, ws, _db) = ctx.workspace_and_db_with_perm(guard.read_permission())?;
+        stack_id(&ws)?
+    };
+    enter_edit_mode(&mut ctx, foobar, stack_id, guard.write_permission())?;
+    assert!(
+        !worktree_dir.join("uncommitted").exists(),
+        "entering edit mode stashes the uncommitted changes"
+    );
+
+    // Simulate `--continue` being interrupted right after switching back to the workspace,
+    // before the stashed changes were restored into the worktree.
+    {
+        let repo = ctx.repo.get()?;
+        repo.edit_reference(gix::refs::transaction::RefEdit {
+            change: gix::refs::transaction::Change::Update {
+                log: Default::default(),
+                expected: gix::refs::transaction::PreviousValue::Any,
+                new: gix::refs::Target::Symbolic("refs/heads/gitbutler/workspace".try_into()?),
+            },
+            name: "HEAD".try_into()?,
+            deref: false,
+        })?;
+    }
+
+    let leftovers = clear_leftover_edit_mode_state(&ctx, guard.write_permission())?;
+    assert!(leftovers.cleaned_up);
+    let stashed_changes = leftovers
+        .stashed_changes
+        .context("the stashed changes are reported")?;
+
+    let repo = ctx.repo.get()?;
+    assert!(
+        repo.try_find_reference("refs/heads/gitbutler/edit")?
+            .is_none(),
+        "the edit branch is removed"
+    );
+    assert!(
+        read_edit_mode_metadata(&ctx).is_err(),
+        "edit mode metadata is removed"
+    );
+    let mut stash_ref = repo
+        .try_find_reference("refs/gitbutler/edit-uncommitted-changes")?
+        .context("the stashed changes must survive the cleanup")?;
+    assert_eq!(stash_ref.peel_to_id()?.detach(), stashed_changes);
+    let stashed_tree = repo.find_tree(stashed_changes)?;
+    let blob = stashed_tree
+        .find_entry("uncommitted")
+        .context("the stash holds the uncommitted file")?
+        .object()?;
+    assert_eq!(blob.data.as_slice(), b"stashed before editing\n");
+    Ok(())
+}
+
 #[test]
 fn enter_edit_mode_checks_out_conflicted_commit() -> Result<()> {
     let (mut ctx, _tempdir) = command_ctx("enter_edit_mode_with_conflicted_commit")?;
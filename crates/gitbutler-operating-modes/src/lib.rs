@@ -59,6 +59,11 @@ pub fn write_edit_mode_metadata(
     Ok(())
 }
 
+#[doc(hidden)]
+pub fn has_edit_mode_metadata(ctx: &Context) -> bool {
+    edit_mode_metadata_path(ctx).exists()
+}
+
 #[doc(hidden)]
 pub fn delete_edit_mode_metadata(ctx: &Context) -> Result<()> {
     match fs::remove_file(edit_mode_metadata_path(ctx).as_path()) {
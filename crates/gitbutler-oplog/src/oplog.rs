@@ -282,7 +282,7 @@ impl OplogExt for Context {
         let next_commit_id = match oplog_commit_id {
             Some(id) => Some(id),
             None => {
-                let oplog_state = OplogHandle::new(&self.project_data_dir());
+                let oplog_state = OplogHandle::new(&self.shared_project_data_dir());
                 oplog_state.oplog_head()?
             }
         };
@@ -349,7 +349,7 @@ impl OplogExt for Context {
 
     /// Gets the sha of the last snapshot commit if present.
     fn oplog_head(&self) -> Result<Option<gix::ObjectId>> {
-        let oplog_state = OplogHandle::new(&self.project_data_dir());
+        let oplog_state = OplogHandle::new(&self.shared_project_data_dir());
         oplog_state.oplog_head()
     }
 }
@@ -590,7 +590,7 @@ mod legacy_virtual_branches {
     }
 
     fn toml_path(ctx: &Context) -> PathBuf {
-        ctx.project_data_dir().join("virtual_branches.toml")
+        ctx.shared_project_data_dir().join("virtual_branches.toml")
     }
 
     pub(super) fn in_workspace_stacks(
@@ -807,7 +807,7 @@ fn commit_snapshot(
 ) -> Result<gix::ObjectId> {
     repo.find_tree(snapshot_tree_id)?;
 
-    let project_data_dir = ctx.project_data_dir();
+    let project_data_dir = ctx.shared_project_data_dir();
     let oplog_state = OplogHandle::new(&project_data_dir);
     let oplog_head_commit = oplog_state
         .oplog_head()?
@@ -1192,7 +1192,7 @@ fn find_oplog_child(
     ctx: &Context,
     target_id: gix::ObjectId,
 ) -> Result<Option<gix::ObjectId>> {
-    let oplog_state = OplogHandle::new(&ctx.project_data_dir());
+    let oplog_state = OplogHandle::new(&ctx.shared_project_data_dir());
     let Some(head_id) = oplog_state.oplog_head()? else {
         return Ok(None);
     };
@@ -22,7 +22,7 @@ impl ReflogCommits {
     /// Collect the current state of all relevant commits that we want to protect in the reflog to prevent them from being GC'd.
     pub fn new(ctx: &Context, target: gix::ObjectId) -> Result<Self> {
         let last_pushed_base = ctx.legacy_meta()?.data().last_pushed_base;
-        let project_data_dir = ctx.project_data_dir();
+        let project_data_dir = ctx.shared_project_data_dir();
         let oplog_state = OplogHandle::new(&project_data_dir);
         let oplog = oplog_state.oplog_head()?;
 
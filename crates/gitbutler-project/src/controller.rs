@@ -331,6 +331,14 @@ impl Controller {
         self.projects_storage.list()
     }
 
+    /// Remove the project with `id` from the list of projects, but keep its data and references.
+    pub(crate) fn unregister(&self, id: ProjectHandleOrLegacyProjectId) -> Result<()> {
+        let Some(project) = self.projects_storage.try_get(id)? else {
+            return Ok(());
+        };
+        self.projects_storage.purge(project.id)
+    }
+
     pub(crate) fn delete(&self, id: ProjectHandleOrLegacyProjectId) -> Result<()> {
         let Some(project) = self.projects_storage.try_get(id.clone())? else {
             return Ok(());
@@ -110,6 +110,13 @@ pub fn delete(id: ProjectHandleOrLegacyProjectId) -> anyhow::Result<()> {
     controller.delete(id)
 }
 
+/// Remove the project with `id` from the list of projects, like [`delete()`], but keep
+/// its data in the repository and its `gitbutler/*` references.
+pub fn unregister(id: ProjectHandleOrLegacyProjectId) -> anyhow::Result<()> {
+    let controller = Controller::from_path(but_path::app_data_dir()?);
+    controller.unregister(id)
+}
+
 /// Testing purpose only.
 pub fn delete_with_path<P: AsRef<Path>>(
     app_data_dir: P,
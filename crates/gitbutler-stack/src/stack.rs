@@ -382,7 +382,7 @@ impl Stack {
             new_heads.insert(spec.reference.to_string(), spec.commit_id);
         }
 
-        self.set_all_heads(&*ctx.repo.get()?, &ctx.project_data_dir(), new_heads)
+        self.set_all_heads(&*ctx.repo.get()?, &ctx.shared_project_data_dir(), new_heads)
     }
 }
 
@@ -432,7 +432,7 @@ fn branch_state_from_project_data_dir(project_data_dir: &Path) -> VirtualBranche
     reason = "VirtualBranchesHandle should be replaced with ctx.workspace_* helpers"
 )]
 fn branch_state(ctx: &Context) -> VirtualBranchesHandle {
-    branch_state_from_project_data_dir(&ctx.project_data_dir())
+    branch_state_from_project_data_dir(&ctx.shared_project_data_dir())
 }
 
 #[expect(
@@ -37,6 +37,8 @@ pub struct StackBranch {
     /// backwards-compatible snapshot and storage handling.
     #[deprecated(note = "review identifiers are no longer persisted or populated")]
     pub review_id: Option<String>,
+    /// A free-form description of the branch, kept so it survives legacy round-trips.
+    pub description: Option<String>,
 }
 
 #[expect(
@@ -51,6 +53,7 @@ impl From<virtual_branches_legacy_types::StackBranch> for StackBranch {
             pr_number,
             archived,
             review_id,
+            description,
         }: virtual_branches_legacy_types::StackBranch,
     ) -> Self {
         StackBranch {
@@ -59,6 +62,7 @@ impl From<virtual_branches_legacy_types::StackBranch> for StackBranch {
             pr_number,
             archived,
             review_id,
+            description,
         }
     }
 }
@@ -75,6 +79,7 @@ impl From<StackBranch> for virtual_branches_legacy_types::StackBranch {
             pr_number,
             archived,
             review_id,
+            description,
         }: StackBranch,
     ) -> Self {
         virtual_branches_legacy_types::StackBranch {
@@ -83,6 +88,7 @@ impl From<StackBranch> for virtual_branches_legacy_types::StackBranch {
             pr_number,
             archived,
             review_id,
+            description,
         }
     }
 }
@@ -99,6 +105,7 @@ impl StackBranch {
             pr_number: None,
             archived: false,
             review_id: None,
+            description: None,
         };
         branch.set_real_reference(repo, branch.head)?;
         Ok(branch)
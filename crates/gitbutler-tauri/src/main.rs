@@ -289,6 +289,7 @@ fn main() -> anyhow::Result<()> {
                 bitbucket::tauri_clear_all_bitbucket_tokens::clear_all_bitbucket_tokens,
                 bitbucket::tauri_check_bitbucket_credentials::check_bitbucket_credentials,
                 diff::tauri_commit_details::commit_details,
+                diff::tauri_blob_at::blob_at,
                 diff::tauri_commit_details_with_line_stats::commit_details_with_line_stats,
                 workspace::tauri_get_workspace::get_workspace,
                 workspace::tauri_set_target_ref_and_init_project::set_target_ref_and_init_project,
@@ -395,6 +396,7 @@ fn main() -> anyhow::Result<()> {
                 legacy::cli::tauri_cli_path::cli_path,
                 legacy::workspace::tauri_head_info::head_info,
                 legacy::workspace::tauri_branch_details::branch_details,
+                legacy::workspace::tauri_stack_details_by_branch::stack_details_by_branch,
                 legacy::workspace::tauri_discard_worktree_changes::discard_worktree_changes,
                 legacy::workspace::tauri_stash_into_branch::stash_into_branch,
                 legacy::workspace::tauri_canned_branch_name::canned_branch_name,
@@ -502,6 +504,7 @@ fn main() -> anyhow::Result<()> {
                 workspace::tauri_workspace_integrate_upstream::workspace_integrate_upstream,
                 workspace::tauri_workspace_fetch_from_remotes::workspace_fetch_from_remotes,
                 workspace::tauri_workspace_fetch_status::workspace_fetch_status,
+                workspace::tauri_stack_containing_commit::stack_containing_commit,
                 land::tauri_branch_land::branch_land,
                 resolve::tauri_resolve_commit_conflicts_ai::resolve_commit_conflicts_ai,
                 platform::tauri_build_type::build_type,
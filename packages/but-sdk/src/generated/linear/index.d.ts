@@ -838,6 +838,10 @@ export type AppSettings = {
   reviews: Reviews;
   /** UI settings. */
   ui: UiSettings;
+  /** Settings that only affect the `but` CLI. */
+  cli: CliSettings;
+  /** Conventions that commit messages are checked against. */
+  commitMessage: CommitMessageSettings;
   /**
    * The duration between application update checks in seconds. If `0`, no update checks will be performed.
    * This setting controls background update checks for both the CLI and GUI.
@@ -948,6 +952,12 @@ export type Branch = {
   refInfo: MetadataRefInfo;
   /** Information about possibly ongoing reviews in various forges. */
   review: Review;
+  /**
+   * A free-form description of what the branch is about, as written by the user.
+   *
+   * It's used as starting point for the body of reviews created for the branch.
+   */
+  description: string | null;
 };
 
 /** Represents a "commit author" or "signature", based on the data from the git history */
@@ -1250,6 +1260,16 @@ export type Claude = {
   useConfiguredModel: boolean;
 };
 
+export type CliSettings = {
+  /**
+   * The editor command the `but` CLI uses to edit messages, e.g. `code --wait`.
+   * If empty, the editor is determined like Git does, i.e. by `GIT_EDITOR`, `core.editor`, `VISUAL` and `EDITOR`.
+   */
+  editor: string;
+  /** Whether to show a desktop notification when slow operations like `but push` or `but pull` finish. */
+  notifyOnSlowOperations: boolean;
+};
+
 /**
  * A unique code that consumers of the API may rely on to identify errors.
  *
@@ -1357,6 +1377,17 @@ export type CommitInsertBlankResult = {
   workspace: WorkspaceState;
 };
 
+export type CommitMessageSettings = {
+  /** Warn if the subject line has more characters than this. If `0`, the subject length isn't checked. */
+  subjectMaxLength: number;
+  /** Warn if a line of the body has more characters than this. If `0`, body lines aren't checked. */
+  bodyMaxLineLength: number;
+  /** Warn if the subject isn't followed by an empty line. */
+  requireBlankSecondLine: boolean;
+  /** Instead of only warning, re-open the editor with the problems listed if the message was written in an editor. */
+  strict: boolean;
+};
+
 /** JSON transport type for moving a commit. */
 export type CommitMoveResult = {
   /** Workspace state after moving the commit. */